@@ -0,0 +1,20 @@
+//! Build script
+//!
+//! With the `grpc` feature, generates the `citescrape.v1.CrawlControl`
+//! messages and server from `proto/citescrape.proto`. protox parses the proto
+//! file, so the build needs no `protoc`.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/citescrape.proto");
+        let descriptors = protox::compile(["proto/citescrape.proto"], ["proto"])?;
+        tonic_prost_build::configure()
+            .build_client(false)
+            .compile_fds(descriptors)?;
+    }
+
+    Ok(())
+}
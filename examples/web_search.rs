@@ -75,15 +75,15 @@ async fn main() -> anyhow::Result<()> {
             // Display all 10 results
             for result in &results.results {
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)).set_bold(true))?;
-                writeln!(&mut stdout, "  {}. {}", result.rank, result.title)?;
+                writeln!(&mut stdout, "  {}. {}", result.rank(), result.title())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::Blue)))?;
-                writeln!(&mut stdout, "     🔗 {}", result.url)?;
+                writeln!(&mut stdout, "     🔗 {}", result.url())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)))?;
-                writeln!(&mut stdout, "     📄 {}", result.snippet)?;
+                writeln!(&mut stdout, "     📄 {}", result.snippet())?;
                 stdout.reset()?;
                 writeln!(&mut stdout)?;
             }
@@ -129,15 +129,15 @@ async fn main() -> anyhow::Result<()> {
             // Display all 10 results
             for result in &results.results {
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)).set_bold(true))?;
-                writeln!(&mut stdout, "  {}. {}", result.rank, result.title)?;
+                writeln!(&mut stdout, "  {}. {}", result.rank(), result.title())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::Blue)))?;
-                writeln!(&mut stdout, "     🔗 {}", result.url)?;
+                writeln!(&mut stdout, "     🔗 {}", result.url())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)))?;
-                writeln!(&mut stdout, "     📄 {}", result.snippet)?;
+                writeln!(&mut stdout, "     📄 {}", result.snippet())?;
                 stdout.reset()?;
                 writeln!(&mut stdout)?;
             }
@@ -186,15 +186,15 @@ async fn main() -> anyhow::Result<()> {
             // Display all 10 results
             for result in &results.results {
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)).set_bold(true))?;
-                writeln!(&mut stdout, "  {}. {}", result.rank, result.title)?;
+                writeln!(&mut stdout, "  {}. {}", result.rank(), result.title())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::Blue)))?;
-                writeln!(&mut stdout, "     🔗 {}", result.url)?;
+                writeln!(&mut stdout, "     🔗 {}", result.url())?;
                 stdout.reset()?;
 
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White)))?;
-                writeln!(&mut stdout, "     📄 {}", result.snippet)?;
+                writeln!(&mut stdout, "     📄 {}", result.snippet())?;
                 stdout.reset()?;
                 writeln!(&mut stdout)?;
             }
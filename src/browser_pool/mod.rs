@@ -2,19 +2,24 @@
 //!
 //! Provides instant browser access by maintaining a pool of pre-warmed Chrome instances.
 //! Pool size dynamically scales based on demand: target = max(in_use + 2, min_pool_size).
+//! At capacity, `acquire` calls are served in arrival order: each checked-out
+//! browser holds a permit of a FIFO semaphore, and a released permit goes to
+//! the longest-waiting caller.
 
 use anyhow::{Context, Result};
 use chromiumoxide::browser::Browser;
-use std::collections::VecDeque;
+use crossbeam_queue::SegQueue;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tokio::sync::{Mutex, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::sync::{AcquireError, Mutex, Notify, OwnedSemaphorePermit, Semaphore, TryAcquireError};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+use crate::crawl_engine::CrawlError;
+
 // =============================================================================
 // Cleanup Channel Types
 // =============================================================================
@@ -85,7 +90,24 @@ pub struct PooledBrowserWrapper {
     user_data_dir: Option<PathBuf>,
     cleanup_tx: mpsc::UnboundedSender<CleanupMessage>,
     /// Capacity permit - auto-released when browser is destroyed
-    _permit: OwnedSemaphorePermit,
+    _permit: CapacityPermit,
+}
+
+/// A browser's share of the pool capacity
+///
+/// Dropping it frees the capacity and wakes `acquire` calls waiting to
+/// launch a browser.
+#[derive(Debug)]
+pub(crate) struct CapacityPermit {
+    permit: Option<PoolPermit>,
+    freed: Arc<Notify>,
+}
+
+impl Drop for CapacityPermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        self.freed.notify_waiters();
+    }
 }
 
 impl PooledBrowserWrapper {
@@ -94,7 +116,7 @@ impl PooledBrowserWrapper {
         handler: JoinHandle<()>,
         user_data_dir: PathBuf,
         cleanup_tx: mpsc::UnboundedSender<CleanupMessage>,
-        permit: OwnedSemaphorePermit,
+        permit: CapacityPermit,
     ) -> Self {
         Self {
             browser: Arc::new(browser),
@@ -212,10 +234,21 @@ impl PooledBrowser {
 #[derive(Debug)]
 pub struct BrowserPool {
     config: BrowserPoolConfig,
-    /// Available (ready) browsers
-    available: Arc<Mutex<VecDeque<PooledBrowser>>>,
+    /// Available (ready) browsers, oldest first; lock-free so acquire and
+    /// release never contend on a lock
+    available: SegQueue<PooledBrowser>,
     /// Enforces max_pool_size atomically - each browser holds one permit
-    capacity_semaphore: Arc<Semaphore>,
+    capacity_semaphore: ResizableSemaphore,
+    /// One permit per browser callers may hold at once; tokio semaphores
+    /// queue waiters in FIFO order, so `acquire` calls are served in order
+    checkout_semaphore: ResizableSemaphore,
+    /// `acquire` calls waiting for a checkout permit (monitoring only)
+    waiting_count: AtomicUsize,
+    /// Notified when a browser becomes available or capacity is freed
+    available_notify: Arc<Notify>,
+    /// Current pool bounds; start from `config` and change with [`BrowserPool::resize`]
+    min_pool_size: AtomicUsize,
+    max_pool_size: AtomicUsize,
     /// Count of browsers currently checked out (monitoring only, not for gating)
     in_use_count: AtomicUsize,
     /// Counter for unique browser IDs
@@ -242,8 +275,22 @@ pub struct BrowserPool {
     release_rx: Mutex<Option<mpsc::UnboundedReceiver<PooledBrowser>>>,
     /// Handle to the release task for shutdown coordination
     release_task_handle: Mutex<Option<JoinHandle<()>>>,
-    /// Notification sent when a browser is released back to pool (for acquire waiters)
-    available_notify: Arc<Notify>,
+}
+
+/// Counts an `acquire` call as waiting until dropped
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaitingGuard<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::AcqRel);
+        Self(count)
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 impl BrowserPool {
@@ -253,9 +300,14 @@ impl BrowserPool {
         let (cleanup_tx, cleanup_rx) = mpsc::unbounded_channel();
 
         Arc::new(Self {
-            capacity_semaphore: Arc::new(Semaphore::new(config.max_pool_size)),
+            capacity_semaphore: ResizableSemaphore::new(config.max_pool_size),
+            checkout_semaphore: ResizableSemaphore::new(config.max_pool_size),
+            waiting_count: AtomicUsize::new(0),
+            available_notify: Arc::new(Notify::new()),
+            min_pool_size: AtomicUsize::new(config.min_pool_size),
+            max_pool_size: AtomicUsize::new(config.max_pool_size),
             config,
-            available: Arc::new(Mutex::new(VecDeque::new())),
+            available: SegQueue::new(),
             in_use_count: AtomicUsize::new(0),
             next_id: AtomicU64::new(0),
             scaler_handle: Mutex::new(None),
@@ -269,10 +321,60 @@ impl BrowserPool {
             release_tx: std::sync::Mutex::new(Some(release_tx)),
             release_rx: Mutex::new(Some(release_rx)),
             release_task_handle: Mutex::new(None),
-            available_notify: Arc::new(Notify::new()),
         })
     }
 
+    /// Minimum browsers kept warm
+    pub fn min_pool_size(&self) -> usize {
+        self.min_pool_size.load(Ordering::Acquire)
+    }
+
+    /// Maximum browsers the pool may hold
+    pub fn max_pool_size(&self) -> usize {
+        self.max_pool_size.load(Ordering::Acquire)
+    }
+
+    /// Browsers currently checked out
+    pub fn in_use(&self) -> usize {
+        self.in_use_count.load(Ordering::Acquire)
+    }
+
+    /// Warm browsers waiting to be acquired
+    pub fn available(&self) -> usize {
+        self.available.len()
+    }
+
+    /// `acquire` calls waiting for a browser at max capacity
+    pub fn waiting(&self) -> usize {
+        self.waiting_count.load(Ordering::Acquire)
+    }
+
+    /// Return `browser` to the available queue and wake `acquire` calls
+    /// waiting for one
+    fn make_available(&self, browser: PooledBrowser) {
+        self.available.push(browser);
+        self.available_notify.notify_waiters();
+    }
+
+    /// Change the pool bounds while the pool is running
+    ///
+    /// A larger maximum takes effect immediately. A smaller one takes effect as
+    /// checked-out browsers are released; browsers in use are never closed.
+    pub fn resize(&self, min_pool_size: usize, max_pool_size: usize) -> Result<()> {
+        if max_pool_size == 0 || min_pool_size > max_pool_size {
+            anyhow::bail!(
+                "Invalid pool bounds: min {min_pool_size}, max {max_pool_size} (need 1 <= max and min <= max)"
+            );
+        }
+        self.min_pool_size.store(min_pool_size, Ordering::Release);
+        let old_max = self.max_pool_size.swap(max_pool_size, Ordering::AcqRel);
+
+        self.capacity_semaphore.resize(old_max, max_pool_size);
+        self.checkout_semaphore.resize(old_max, max_pool_size);
+        info!("Browser pool resized to min {min_pool_size}, max {max_pool_size}");
+        Ok(())
+    }
+
     /// Start the pool and background tasks
     ///
     /// Pre-warms the pool to min_pool_size and starts scaler/keepalive tasks.
@@ -318,7 +420,7 @@ impl BrowserPool {
 
         info!(
             "Browser pool started with {} pre-warmed browsers",
-            self.available.lock().await.len()
+            self.available.len()
         );
         Ok(())
     }
@@ -326,7 +428,8 @@ impl BrowserPool {
     /// Acquire a browser from the pool with timeout
     ///
     /// Returns a guard that automatically releases the browser when dropped.
-    /// If no browsers available, waits with exponential backoff up to timeout.
+    /// If no browser is available and the pool is at max capacity, waits in
+    /// line: released browsers go to waiting callers in arrival order.
     ///
     /// # Arguments
     /// * `timeout` - Maximum time to wait for a browser
@@ -343,89 +446,52 @@ impl BrowserPool {
         }
 
         let deadline = Instant::now() + timeout;
-        let mut backoff = Duration::from_millis(10);
-        let max_backoff = Duration::from_secs(1);
-        let mut wait_logged = false;
-        let health_timeout = self.config.health_check_timeout;
+        let exhausted = || -> anyhow::Error {
+            CrawlError::PoolExhausted {
+                message: format!("Timeout after {timeout:?} waiting for browser from pool"),
+            }
+            .into()
+        };
 
-        loop {
-            // Check timeout first
-            let now = Instant::now();
-            if now >= deadline {
-                return Err(anyhow::anyhow!(
-                    "Timeout after {:?} waiting for browser from pool",
-                    timeout
-                ));
+        // Phase 1: Wait in line for the right to hold a browser
+        let mut waited = false;
+        let checkout = match self.checkout_semaphore.try_acquire() {
+            Ok(permit) => permit,
+            Err(TryAcquireError::Closed) => {
+                return Err(anyhow::anyhow!("Browser pool is shutting down"));
             }
+            Err(TryAcquireError::NoPermits) => {
+                warn!(
+                    "Browser pool at max capacity ({}), waiting (timeout: {:?})",
+                    self.max_pool_size(), timeout
+                );
+                waited = true;
+                let _waiting = WaitingGuard::new(&self.waiting_count);
+                let acquire = self.checkout_semaphore.acquire();
+                match tokio::time::timeout_at(deadline.into(), acquire).await {
+                    Ok(Ok(permit)) => permit,
+                    Ok(Err(_)) => return Err(anyhow::anyhow!("Browser pool is shutting down")),
+                    Err(_) => return Err(exhausted()),
+                }
+            }
+        };
 
-            // Also check shutdown inside loop in case it started while waiting
+        // Phase 2: Take a warm browser or launch one. With a checkout permit
+        // held, one is available or launchable unless browsers are in transit
+        // (being released, health-checked or closed); wait for those.
+        let health_timeout = self.config.health_check_timeout;
+        loop {
             if self.shutdown.load(Ordering::Acquire) {
                 return Err(anyhow::anyhow!("Browser pool is shutting down"));
             }
 
-            // Phase 1: Pop browser from pool (brief lock)
-            let browser = {
-                let mut available = self.available.lock().await;
-                available.pop_front()
-            };
-            // Lock released immediately
-
-            if let Some(mut browser) = browser {
-                // Phase 2: Health check WITHOUT holding lock
-                let health_result = tokio::time::timeout(
-                    health_timeout,
-                    browser.wrapper.browser().version()
-                ).await;
-
-                match health_result {
-                    Ok(Ok(_)) => {
-                        // Browser is healthy - return it
-                        browser.last_used = Instant::now();
-                        browser.last_health_check = Instant::now();
-                        self.in_use_count.fetch_add(1, Ordering::AcqRel);
-
-                        if wait_logged {
-                            debug!("Acquired browser {} after waiting", browser.id);
-                        } else {
-                            debug!("Acquired browser {} from pool", browser.id);
-                        }
+            let notified = self.available_notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
 
-                        return Ok(PooledBrowserGuard {
-                            browser: Some(browser),
-                            pool: Arc::clone(self),
-                        });
-                    }
-                    Ok(Err(e)) => {
-                        warn!("Browser {} failed health check during acquire: {}", browser.id, e);
-                        // Spawn async cleanup and try next browser
-                        let PooledBrowser { id, mut wrapper, .. } = browser;
-                        if let Some(path) = wrapper.user_data_dir.take() {
-                            tokio::spawn(async move {
-                                debug!("Async cleanup for failed browser {}", id);
-                                let _ = tokio::fs::remove_dir_all(&path).await;
-                            });
-                        }
-                        continue;
-                    }
-                    Err(_) => {
-                        warn!("Browser {} health check timed out during acquire", browser.id);
-                        // Spawn async cleanup and try next browser
-                        let PooledBrowser { id, mut wrapper, .. } = browser;
-                        if let Some(path) = wrapper.user_data_dir.take() {
-                            tokio::spawn(async move {
-                                debug!("Async cleanup for timed out browser {}", id);
-                                let _ = tokio::fs::remove_dir_all(&path).await;
-                            });
-                        }
-                        continue;
-                    }
-                }
-            }
-
-            // No browser available - try to launch new one
-            // Semaphore in launch_browser_internal() atomically gates capacity (no TOCTOU race!)
-            match self.launch_browser_internal().await {
-                Ok(browser) => {
+            let Some(mut browser) = self.available.pop() else {
+                if let Ok(permit) = self.capacity_semaphore.try_acquire() {
+                    let browser = self.launch_with_permit(permit).await?;
                     self.in_use_count.fetch_add(1, Ordering::AcqRel);
                     debug!(
                         "Launched new browser {} for acquire (pool was empty)",
@@ -435,50 +501,61 @@ impl BrowserPool {
                     return Ok(PooledBrowserGuard {
                         browser: Some(browser),
                         pool: Arc::clone(self),
+                        _checkout: checkout,
                     });
                 }
-                Err(e) => {
-                    // Check if this is a capacity error (semaphore exhausted)
-                    let err_msg = e.to_string();
-                    if err_msg.contains("capacity") || err_msg.contains("semaphore") {
-                        // At max capacity - fall through to wait with backoff
-                    } else {
-                        // Real launch error - propagate it
-                        return Err(e);
-                    }
+                if tokio::time::timeout_at(deadline.into(), notified).await.is_err() {
+                    return Err(exhausted());
                 }
-            }
+                continue;
+            };
 
-            // At max capacity - wait with backoff
-            if !wait_logged {
-                warn!(
-                    "Browser pool at max capacity ({}), waiting (timeout: {:?})",
-                    self.config.max_pool_size, timeout
-                );
-                wait_logged = true;
-            }
+            // Health check
+            let health_result = tokio::time::timeout(
+                health_timeout,
+                browser.wrapper.browser().version()
+            ).await;
 
-            // Calculate remaining time
-            let remaining = deadline.saturating_duration_since(Instant::now());
-            if remaining.is_zero() {
-                return Err(anyhow::anyhow!(
-                    "Timeout after {:?} waiting for browser from pool",
-                    timeout
-                ));
-            }
+            match health_result {
+                Ok(Ok(_)) => {
+                    // Browser is healthy - return it
+                    browser.last_used = Instant::now();
+                    browser.last_health_check = Instant::now();
+                    self.in_use_count.fetch_add(1, Ordering::AcqRel);
 
-            // Wait for notification OR backoff timeout (whichever is shorter)
-            let wait_time = backoff.min(remaining);
-            tokio::select! {
-                biased;
+                    if waited {
+                        debug!("Acquired browser {} after waiting", browser.id);
+                    } else {
+                        debug!("Acquired browser {} from pool", browser.id);
+                    }
 
-                _ = self.available_notify.notified() => {
-                    // Browser released, reset backoff and retry immediately
-                    backoff = Duration::from_millis(10);
+                    return Ok(PooledBrowserGuard {
+                        browser: Some(browser),
+                        pool: Arc::clone(self),
+                        _checkout: checkout,
+                    });
                 }
-                _ = tokio::time::sleep(wait_time) => {
-                    // Timeout, increase backoff for next iteration
-                    backoff = (backoff * 2).min(max_backoff);
+                Ok(Err(e)) => {
+                    warn!("Browser {} failed health check during acquire: {}", browser.id, e);
+                    // Spawn async cleanup and try next browser
+                    let PooledBrowser { id, mut wrapper, .. } = browser;
+                    if let Some(path) = wrapper.user_data_dir.take() {
+                        tokio::spawn(async move {
+                            debug!("Async cleanup for failed browser {}", id);
+                            let _ = tokio::fs::remove_dir_all(&path).await;
+                        });
+                    }
+                }
+                Err(_) => {
+                    warn!("Browser {} health check timed out during acquire", browser.id);
+                    // Spawn async cleanup and try next browser
+                    let PooledBrowser { id, mut wrapper, .. } = browser;
+                    if let Some(path) = wrapper.user_data_dir.take() {
+                        tokio::spawn(async move {
+                            debug!("Async cleanup for timed out browser {}", id);
+                            let _ = tokio::fs::remove_dir_all(&path).await;
+                        });
+                    }
                 }
             }
         }
@@ -554,6 +631,10 @@ impl BrowserPool {
         // and prevent new browser launches
         self.capacity_semaphore.close();
 
+        // Fail callers waiting in line or for a browser with "shutting down"
+        self.checkout_semaphore.close();
+        self.available_notify.notify_waiters();
+
         // Abort background scaling/keepalive tasks first
         if let Some(handle) = self.scaler_handle.lock().await.take() {
             handle.abort();
@@ -621,10 +702,10 @@ impl BrowserPool {
         }
 
         // Drain and close all available browsers
-        let mut available = self.available.lock().await;
-        let browser_count = available.len();
+        let mut browser_count = 0;
 
-        while let Some(mut browser) = available.pop_front() {
+        while let Some(mut browser) = self.available.pop() {
+            browser_count += 1;
             // Try to get mutable access - only works if no other Arc refs exist
             if let Some(b) = browser.wrapper.browser_mut() {
                 if let Err(e) = b.close().await {
@@ -637,7 +718,6 @@ impl BrowserPool {
             // Use synchronous cleanup during shutdown for guaranteed cleanup
             browser.wrapper.cleanup_temp_dir();
         }
-        drop(available);
 
         // Signal cleanup task to shutdown and wait for completion
         let _ = self.cleanup_tx.send(CleanupMessage::Shutdown);
@@ -674,8 +754,8 @@ impl BrowserPool {
 
         // Base target: in_use + 2 buffer, clamped to [min, max]
         let base_target = (in_use + 2)
-            .max(self.config.min_pool_size)
-            .min(self.config.max_pool_size);
+            .max(self.min_pool_size())
+            .min(self.max_pool_size());
 
         // Apply hysteresis band
         if current_total < base_target.saturating_sub(1) {
@@ -686,13 +766,13 @@ impl BrowserPool {
             base_target
         } else {
             // Within hysteresis band - maintain current
-            current_total.min(self.config.max_pool_size)
+            current_total.min(self.max_pool_size())
         }
     }
 
     /// Scale pool to target size (uses hysteresis)
     async fn scale_to_target(&self) -> Result<()> {
-        let current = self.available.lock().await.len();
+        let current = self.available.len();
         let target = self.target_pool_size_with_hysteresis(current);
 
         if current >= target {
@@ -711,11 +791,10 @@ impl BrowserPool {
 
         let results = futures::future::join_all(futs).await;
 
-        let mut available = self.available.lock().await;
         for result in results {
             match result {
                 Ok(browser) => {
-                    available.push_back(browser);
+                    self.make_available(browser);
                 }
                 Err(e) => {
                     warn!("Failed to launch browser for pool: {}", e);
@@ -728,46 +807,38 @@ impl BrowserPool {
 
     /// Remove idle browsers with non-blocking cleanup
     ///
-    /// Identifies idle browsers under brief lock, then spawns async cleanup
-    /// tasks for each removed browser's temp directory.
+    /// Checks each available browser once, keeping recently used ones and
+    /// at least `min_pool_size`, then spawns async cleanup tasks for each removed browser's
+    /// temp directory.
     async fn remove_idle_browsers(&self) {
         let now = Instant::now();
-        let min_size = self.config.min_pool_size;
+        let min_size = self.min_pool_size();
         let idle_timeout = self.config.idle_timeout;
 
-        // Collect browsers to remove with paths (brief lock)
+        // Rotate through the queue once; browsers acquired meanwhile are simply skipped
         let mut to_cleanup: Vec<PathBuf> = Vec::new();
-        {
-            let mut available = self.available.lock().await;
-
-            // Remove from front (oldest first) while above min_size
-            while available.len() > min_size {
-                if let Some(browser) = available.front() {
-                    if now.duration_since(browser.last_used) > idle_timeout {
-                        if let Some(mut removed) = available.pop_front() {
-                            debug!(
-                                "Removing idle browser {} (idle {:?})",
-                                removed.id,
-                                now.duration_since(removed.last_used)
-                            );
-                            // Extract path BEFORE drop to prevent blocking cleanup
-                            if let Some(path) = removed.wrapper.take_user_data_dir() {
-                                to_cleanup.push(path);
-                            }
-                            // Browser dropped here - but no blocking I/O since path was taken
-                        }
-                    } else {
-                        // Front browser is not idle, none behind it will be either
-                        // (VecDeque maintains insertion order, oldest at front)
-                        break;
-                    }
-                } else {
-                    break;
+        for _ in 0..self.available.len() {
+            let Some(mut browser) = self.available.pop() else {
+                break;
+            };
+            // The popped browser is no longer counted in `len()`
+            if self.available.len() >= min_size && now.duration_since(browser.last_used) > idle_timeout {
+                debug!(
+                    "Removing idle browser {} (idle {:?})",
+                    browser.id,
+                    now.duration_since(browser.last_used)
+                );
+                // Extract path BEFORE drop to prevent blocking cleanup
+                if let Some(path) = browser.wrapper.take_user_data_dir() {
+                    to_cleanup.push(path);
                 }
+                // Browser dropped here - but no blocking I/O since path was taken
+            } else {
+                self.make_available(browser);
             }
-        } // Lock released here
+        }
 
-        // Spawn async cleanup tasks outside the lock
+        // Spawn async cleanup tasks
         for path in to_cleanup {
             tokio::spawn(async move {
                 info!("Async cleanup of browser temp directory: {}", path.display());
@@ -788,10 +859,14 @@ impl BrowserPool {
         // This prevents TOCTOU race - the semaphore is the single source of truth
         let permit = self
             .capacity_semaphore
-            .clone()
-            .try_acquire_owned()
+            .try_acquire()
             .map_err(|_| anyhow::anyhow!("Browser pool at max capacity (semaphore exhausted)"))?;
 
+        self.launch_with_permit(permit).await
+    }
+
+    /// Launch a new browser that holds `permit` until it is destroyed
+    async fn launch_with_permit(&self, permit: PoolPermit) -> Result<PooledBrowser> {
         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
 
         // Create unique temp directory for this pooled browser using UUID
@@ -806,6 +881,10 @@ impl BrowserPool {
                 .context("Failed to launch browser for pool")?;
 
         // Pass the permit to the wrapper - it will be auto-released on Drop
+        let permit = CapacityPermit {
+            permit: Some(permit),
+            freed: Arc::clone(&self.available_notify),
+        };
         let wrapper = PooledBrowserWrapper::new(
             browser,
             handler,
@@ -817,6 +896,87 @@ impl BrowserPool {
     }
 }
 
+/// FIFO semaphore whose size can shrink while its permits are held
+///
+/// Shrinking forgets the free permits at once and records the rest as debt,
+/// paid off by forgetting permits as they are released; growing pays off
+/// the debt before adding permits. Nothing waits on the semaphore for the
+/// debt, so callers in line are not held up behind it.
+#[derive(Debug)]
+struct ResizableSemaphore {
+    semaphore: Arc<Semaphore>,
+    /// Permits to forget when they are next released
+    debt: Arc<AtomicUsize>,
+}
+
+impl ResizableSemaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(permits)),
+            debt: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn try_acquire(&self) -> Result<PoolPermit, TryAcquireError> {
+        let permit = Arc::clone(&self.semaphore).try_acquire_owned()?;
+        Ok(self.wrap(permit))
+    }
+
+    async fn acquire(&self) -> Result<PoolPermit, AcquireError> {
+        let permit = Arc::clone(&self.semaphore).acquire_owned().await?;
+        Ok(self.wrap(permit))
+    }
+
+    fn wrap(&self, permit: OwnedSemaphorePermit) -> PoolPermit {
+        PoolPermit {
+            permit: Some(permit),
+            debt: Arc::clone(&self.debt),
+        }
+    }
+
+    /// Move from `old` to `new` total permits
+    fn resize(&self, old: usize, new: usize) {
+        if new > old {
+            let grow = new - old;
+            let paid = self
+                .debt
+                .try_update(Ordering::AcqRel, Ordering::Acquire, |debt| Some(debt.saturating_sub(grow)))
+                .map_or(0, |debt| debt.min(grow));
+            self.semaphore.add_permits(grow - paid);
+        } else if new < old {
+            let excess = old - new;
+            let forgotten = self.semaphore.forget_permits(excess);
+            self.debt.fetch_add(excess - forgotten, Ordering::AcqRel);
+        }
+    }
+
+    fn close(&self) {
+        self.semaphore.close();
+    }
+}
+
+/// Permit of a [`ResizableSemaphore`], forgotten on release while the
+/// semaphore owes permits from a shrink
+#[derive(Debug)]
+pub(crate) struct PoolPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    debt: Arc<AtomicUsize>,
+}
+
+impl Drop for PoolPermit {
+    fn drop(&mut self) {
+        let retired = self
+            .debt
+            .try_update(Ordering::AcqRel, Ordering::Acquire, |debt| debt.checked_sub(1))
+            .is_ok();
+        if let Some(permit) = self.permit.take()
+            && retired
+        {
+            permit.forget();
+        }
+    }
+}
+
 // =============================================================================
 // RAII Guard
 // =============================================================================
@@ -825,6 +985,8 @@ impl BrowserPool {
 pub struct PooledBrowserGuard {
     browser: Option<PooledBrowser>,
     pool: Arc<BrowserPool>,
+    /// Released on drop, letting the next waiting `acquire` call in
+    _checkout: PoolPermit,
 }
 
 impl PooledBrowserGuard {
@@ -932,17 +1094,16 @@ async fn release_loop(
             
             debug!("Browser {} closed during shutdown", id);
         } else {
-            // Normal operation: return browser to available queue
-            pool.available.lock().await.push_back(browser);
+            // Normal operation: hand the browser to the longest-waiting
+            // acquire call, or return it to the available queue
+            pool.make_available(browser);
 
             // Decrement in_use_count AFTER browser is safely back
-            // Using Release ordering to ensure the push_back is visible
+            // Using Release ordering to ensure the push is visible
             pool.in_use_count.fetch_sub(1, Ordering::Release);
             
             // Notify in case shutdown is waiting
             pool.release_notify.notify_one();
-            // Wake one waiter immediately after browser is available
-            pool.available_notify.notify_one();
 
             debug!("Released browser {} back to pool", id);
         }
@@ -953,10 +1114,10 @@ async fn release_loop(
 
 /// Background task: Keepalive ping every 30 seconds using `browser.version()` CDP command
 ///
-/// Uses parallel health checks with timeout to minimize lock contention:
-/// 1. Brief lock to drain all browsers from pool
-/// 2. Parallel health checks with configurable timeout (no lock held)
-/// 3. Brief lock to return healthy browsers to pool
+/// Uses parallel health checks with timeout:
+/// 1. Drain all browsers from pool
+/// 2. Parallel health checks with configurable timeout
+/// 3. Return healthy browsers to pool
 /// 4. Async cleanup spawned for failed browsers (non-blocking)
 async fn keepalive_loop(pool: Arc<BrowserPool>) {
     let mut interval = tokio::time::interval(pool.config.keepalive_interval);
@@ -965,12 +1126,8 @@ async fn keepalive_loop(pool: Arc<BrowserPool>) {
     while !pool.shutdown.load(Ordering::Acquire) {
         interval.tick().await;
 
-        // Phase 1: Drain all browsers from pool (brief lock)
-        let browsers: Vec<PooledBrowser> = {
-            let mut available = pool.available.lock().await;
-            std::mem::take(&mut *available).into_iter().collect()
-        };
-        // Lock released immediately
+        // Phase 1: Drain all browsers from pool
+        let browsers: Vec<PooledBrowser> = std::iter::from_fn(|| pool.available.pop()).collect();
 
         if browsers.is_empty() {
             continue;
@@ -978,7 +1135,7 @@ async fn keepalive_loop(pool: Arc<BrowserPool>) {
 
         let browser_count = browsers.len();
 
-        // Phase 2: Parallel health checks with timeout (NO LOCK HELD)
+        // Phase 2: Parallel health checks with timeout
         let health_check_futures = browsers.into_iter().map(|mut browser| {
             let timeout = health_timeout;
             async move {
@@ -1006,12 +1163,12 @@ async fn keepalive_loop(pool: Arc<BrowserPool>) {
         let results = futures::future::join_all(health_check_futures).await;
 
         // Separate healthy from unhealthy browsers
-        let mut healthy_browsers = VecDeque::with_capacity(browser_count);
+        let mut healthy_browsers = Vec::with_capacity(browser_count);
         let mut unhealthy_browsers = Vec::new();
 
         for result in results {
             match result {
-                Ok(browser) => healthy_browsers.push_back(browser),
+                Ok(browser) => healthy_browsers.push(browser),
                 Err(browser) => unhealthy_browsers.push(browser),
             }
         }
@@ -1019,12 +1176,10 @@ async fn keepalive_loop(pool: Arc<BrowserPool>) {
         let healthy_count = healthy_browsers.len();
         let unhealthy_count = unhealthy_browsers.len();
 
-        // Phase 3: Return healthy browsers to pool (brief lock)
-        {
-            let mut available = pool.available.lock().await;
-            available.append(&mut healthy_browsers);
+        // Phase 3: Return healthy browsers to pool (or to waiting callers)
+        for browser in healthy_browsers {
+            pool.make_available(browser);
         }
-        // Lock released immediately
 
         // Phase 4: Spawn async cleanup for unhealthy browsers (non-blocking)
         for browser in unhealthy_browsers {
@@ -1075,3 +1230,57 @@ async fn cleanup_loop(mut rx: mpsc::UnboundedReceiver<CleanupMessage>) {
 
     debug!("Cleanup loop exiting");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_retires_permits_in_use() {
+        let permits = ResizableSemaphore::new(3);
+        let held = [permits.try_acquire().unwrap(), permits.try_acquire().unwrap()];
+
+        permits.resize(3, 5);
+        assert_eq!(permits.semaphore.available_permits(), 3);
+
+        // Three free permits are forgotten now, one more once `held` is back
+        permits.resize(5, 1);
+        assert_eq!(permits.semaphore.available_permits(), 0);
+        drop(held);
+        assert_eq!(permits.semaphore.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_grow_after_shrink_while_permits_held() {
+        let permits = ResizableSemaphore::new(3);
+        let held = [permits.try_acquire().unwrap(), permits.try_acquire().unwrap()];
+
+        // One free permit is forgotten and one is owed by the held permits
+        permits.resize(3, 1);
+        assert!(permits.try_acquire().is_err());
+
+        // Growing back pays off the debt and frees the one permit not in use
+        permits.resize(1, 3);
+        let third = permits.try_acquire().expect("grown permit available at once");
+        drop(held);
+        drop(third);
+        assert_eq!(permits.semaphore.available_permits(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_released_capacity_wakes_waiters() {
+        let semaphore = ResizableSemaphore::new(1);
+        let freed = Arc::new(Notify::new());
+        let permit = CapacityPermit {
+            permit: Some(semaphore.try_acquire().unwrap()),
+            freed: Arc::clone(&freed),
+        };
+
+        let notified = freed.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        drop(permit);
+        tokio::time::timeout(Duration::from_secs(1), notified).await.unwrap();
+        assert_eq!(semaphore.semaphore.available_permits(), 1);
+    }
+}
@@ -218,6 +218,18 @@ pub async fn download_managed_browser() -> Result<PathBuf> {
 pub async fn launch_browser(
     headless: bool,
     chrome_data_dir: Option<PathBuf>,
+) -> Result<(Browser, JoinHandle<()>, PathBuf)> {
+    launch_browser_with_proxy(headless, chrome_data_dir, None).await
+}
+
+/// Like [`launch_browser`], routing all page traffic through `proxy`
+///
+/// `proxy` is passed to Chrome as `--proxy-server`, so any value Chrome
+/// accepts works (`http://host:3128`, `socks5://host:1080`).
+pub async fn launch_browser_with_proxy(
+    headless: bool,
+    chrome_data_dir: Option<PathBuf>,
+    proxy: Option<&str>,
 ) -> Result<(Browser, JoinHandle<()>, PathBuf)> {
     // First try to find the browser
     let chrome_path = match find_browser_executable().await {
@@ -292,6 +304,10 @@ pub async fn launch_browser(
         .arg("--hide-scrollbars")
         .arg("--mute-audio");
 
+    if let Some(proxy) = proxy {
+        config_builder = config_builder.arg(format!("--proxy-server={proxy}"));
+    }
+
     let browser_config = config_builder
         .build()
         .map_err(|e| anyhow::anyhow!("Failed to build browser config: {e}"))?;
@@ -6,31 +6,18 @@
 use crate::utils::{
     DEFAULT_CRAWL_RATE_RPS, DEFAULT_MAX_DEPTH, SCREENSHOT_QUALITY, SEARCH_BATCH_SIZE,
 };
+use crate::imurl::UrlMatcher;
+use crate::page_extractor::structured::PageSchema;
 use anyhow::{anyhow, Context, Result};
-use regex::Regex;
+use regex::RegexSet;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
-use super::types::CrawlConfig;
-
-/// Compile a glob pattern into a regex
-///
-/// Converts glob patterns (where * matches any sequence) into proper regex patterns.
-/// This is done once at config creation time to avoid repeated compilation in hot paths.
-///
-/// # Errors
-///
-/// Returns an error if the resulting regex pattern is invalid.
-fn compile_glob_pattern(pattern: &str) -> Result<Regex> {
-    // Convert glob pattern to regex: * becomes .*
-    let regex_pattern = pattern.replace('*', ".*");
-
-    // Anchor pattern to match full string
-    let anchored = format!("^{regex_pattern}$");
-
-    // Compile the regex
-    Regex::new(&anchored).map_err(|e| anyhow!("Invalid glob pattern '{pattern}': {e}"))
-}
+use super::cookies::{Cookie, load_cookie_file};
+use super::secret::Secret;
+use super::types::{CrawlConfig, CrawlScope};
+use crate::content_saver::markdown_converter::{ChromeFilterLevel, DEFAULT_MIN_QUALITY_SCORE, ExtractionBackend, Typography};
 
 // Type states for the builder
 pub struct WithStorageDir;
@@ -74,10 +61,39 @@ pub struct CrawlConfigBuilder<State = ()> {
     pub(crate) circuit_breaker_enabled: bool,
     pub(crate) circuit_breaker_failure_threshold: u32,
     pub(crate) circuit_breaker_retry_delay_secs: u64,
+    pub(crate) retry_pass_enabled: bool,
+    pub(crate) retry_pass_timeout_multiplier: f64,
+    pub(crate) retry_pass_headless: Option<bool>,
+    pub(crate) retry_pass_proxy: Option<String>,
     pub(crate) max_concurrent_pages: Option<usize>,
     pub(crate) max_concurrent_per_domain: Option<usize>,
     pub(crate) compression_threshold_bytes: Option<usize>,
     pub(crate) max_page_retries: Option<u8>,
+    pub(crate) wait_for_selector: Option<String>,
+    pub(crate) wait_for_network_idle_ms: Option<u64>,
+    pub(crate) wait_for_function: Option<String>,
+    pub(crate) dismiss_overlays: bool,
+    pub(crate) reject_consent: bool,
+    pub(crate) mirror_assets: bool,
+    pub(crate) event_journal: bool,
+    pub(crate) sitemap: bool,
+    pub(crate) sitemap_base_url: Option<String>,
+    pub(crate) output_url: Option<String>,
+    pub(crate) extraction_backend: ExtractionBackend,
+    pub(crate) min_extraction_quality: f64,
+    pub(crate) keep_comment_patterns: Vec<String>,
+    pub(crate) chrome_filter_level: ChromeFilterLevel,
+    pub(crate) typography: Typography,
+    pub(crate) keep_chrome_patterns: Vec<String>,
+    pub(crate) drop_chrome_patterns: Vec<String>,
+    pub(crate) extraction_schemas: Vec<PageSchema>,
+    pub(crate) link_index_url: Option<String>,
+    pub(crate) link_rewrite_window_ms: u64,
+    pub(crate) seed_urls: Vec<String>,
+    pub(crate) scope: CrawlScope,
+    pub(crate) headers: HashMap<String, Secret>,
+    pub(crate) cookies: Vec<Cookie>,
+    pub(crate) cookie_file: Option<PathBuf>,
     pub(crate) _phantom: PhantomData<State>,
 }
 
@@ -120,10 +136,39 @@ impl Default for CrawlConfigBuilder<()> {
             circuit_breaker_enabled: true,
             circuit_breaker_failure_threshold: 5,
             circuit_breaker_retry_delay_secs: 300,
+            retry_pass_enabled: true,
+            retry_pass_timeout_multiplier: 2.0,
+            retry_pass_headless: None,
+            retry_pass_proxy: None,
             max_concurrent_pages: Some(10),
             max_concurrent_per_domain: Some(2),
             compression_threshold_bytes: Some(1_048_576), // 1MB default
             max_page_retries: Some(3),  // Default: 3 retry attempts
+            wait_for_selector: None,
+            wait_for_network_idle_ms: None,
+            wait_for_function: None,
+            dismiss_overlays: true,
+            reject_consent: false,
+            mirror_assets: false,
+            event_journal: true,
+            sitemap: true,
+            sitemap_base_url: None,
+            output_url: None,
+            extraction_backend: ExtractionBackend::default(),
+            min_extraction_quality: DEFAULT_MIN_QUALITY_SCORE,
+            keep_comment_patterns: Vec::new(),
+            chrome_filter_level: ChromeFilterLevel::default(),
+            typography: Typography::default(),
+            keep_chrome_patterns: Vec::new(),
+            drop_chrome_patterns: Vec::new(),
+            extraction_schemas: Vec::new(),
+            link_index_url: None,
+            link_rewrite_window_ms: 500,
+            seed_urls: Vec::new(),
+            scope: CrawlScope::default(),
+            headers: HashMap::new(),
+            cookies: Vec::new(),
+            cookie_file: None,
             _phantom: PhantomData,
         }
     }
@@ -176,10 +221,39 @@ impl CrawlConfigBuilder<()> {
             circuit_breaker_enabled: self.circuit_breaker_enabled,
             circuit_breaker_failure_threshold: self.circuit_breaker_failure_threshold,
             circuit_breaker_retry_delay_secs: self.circuit_breaker_retry_delay_secs,
+            retry_pass_enabled: self.retry_pass_enabled,
+            retry_pass_timeout_multiplier: self.retry_pass_timeout_multiplier,
+            retry_pass_headless: self.retry_pass_headless,
+            retry_pass_proxy: self.retry_pass_proxy,
             max_concurrent_pages: self.max_concurrent_pages,
             max_concurrent_per_domain: self.max_concurrent_per_domain,
             compression_threshold_bytes: self.compression_threshold_bytes,
             max_page_retries: self.max_page_retries,
+            wait_for_selector: self.wait_for_selector,
+            wait_for_network_idle_ms: self.wait_for_network_idle_ms,
+            wait_for_function: self.wait_for_function,
+            dismiss_overlays: self.dismiss_overlays,
+            reject_consent: self.reject_consent,
+            mirror_assets: self.mirror_assets,
+            event_journal: self.event_journal,
+            sitemap: self.sitemap,
+            sitemap_base_url: self.sitemap_base_url,
+            output_url: self.output_url,
+            extraction_backend: self.extraction_backend,
+            min_extraction_quality: self.min_extraction_quality,
+            keep_comment_patterns: self.keep_comment_patterns,
+            chrome_filter_level: self.chrome_filter_level,
+            typography: self.typography,
+            keep_chrome_patterns: self.keep_chrome_patterns,
+            drop_chrome_patterns: self.drop_chrome_patterns,
+            extraction_schemas: self.extraction_schemas,
+            link_index_url: self.link_index_url,
+            link_rewrite_window_ms: self.link_rewrite_window_ms,
+            seed_urls: self.seed_urls,
+            scope: self.scope,
+            headers: self.headers,
+            cookies: self.cookies,
+            cookie_file: self.cookie_file,
             _phantom: PhantomData,
         }
     }
@@ -234,10 +308,39 @@ impl CrawlConfigBuilder<WithStorageDir> {
             circuit_breaker_enabled: self.circuit_breaker_enabled,
             circuit_breaker_failure_threshold: self.circuit_breaker_failure_threshold,
             circuit_breaker_retry_delay_secs: self.circuit_breaker_retry_delay_secs,
+            retry_pass_enabled: self.retry_pass_enabled,
+            retry_pass_timeout_multiplier: self.retry_pass_timeout_multiplier,
+            retry_pass_headless: self.retry_pass_headless,
+            retry_pass_proxy: self.retry_pass_proxy,
             max_concurrent_pages: self.max_concurrent_pages,
             max_concurrent_per_domain: self.max_concurrent_per_domain,
             compression_threshold_bytes: self.compression_threshold_bytes,
             max_page_retries: self.max_page_retries,
+            wait_for_selector: self.wait_for_selector,
+            wait_for_network_idle_ms: self.wait_for_network_idle_ms,
+            wait_for_function: self.wait_for_function,
+            dismiss_overlays: self.dismiss_overlays,
+            reject_consent: self.reject_consent,
+            mirror_assets: self.mirror_assets,
+            event_journal: self.event_journal,
+            sitemap: self.sitemap,
+            sitemap_base_url: self.sitemap_base_url,
+            output_url: self.output_url,
+            extraction_backend: self.extraction_backend,
+            min_extraction_quality: self.min_extraction_quality,
+            keep_comment_patterns: self.keep_comment_patterns,
+            chrome_filter_level: self.chrome_filter_level,
+            typography: self.typography,
+            keep_chrome_patterns: self.keep_chrome_patterns,
+            drop_chrome_patterns: self.drop_chrome_patterns,
+            extraction_schemas: self.extraction_schemas,
+            link_index_url: self.link_index_url,
+            link_rewrite_window_ms: self.link_rewrite_window_ms,
+            seed_urls: self.seed_urls,
+            scope: self.scope,
+            headers: self.headers,
+            cookies: self.cookies,
+            cookie_file: self.cookie_file,
             _phantom: PhantomData,
         }
     }
@@ -250,12 +353,66 @@ impl CrawlConfigBuilder<WithStartUrl> {
         let excluded_patterns_compiled = if let Some(ref patterns) = self.excluded_patterns {
             patterns
                 .iter()
-                .map(|p| compile_glob_pattern(p))
+                .map(|p| {
+                    if let Some(legacy) = UrlMatcher::legacy_equivalent(p) {
+                        tracing::warn!(
+                            "Excluded pattern '{p}' is matched as a glob; \
+                            write '{legacy}' to keep its regex meaning"
+                        );
+                    }
+                    UrlMatcher::parse(p)
+                })
                 .collect::<Result<Vec<_>>>()?
         } else {
             Vec::new()
         };
 
+        let keep_comments_compiled = if self.keep_comment_patterns.is_empty() {
+            None
+        } else {
+            Some(RegexSet::new(&self.keep_comment_patterns).context("Invalid keep_comment_patterns")?)
+        };
+
+        let keep_chrome_compiled = if self.keep_chrome_patterns.is_empty() {
+            None
+        } else {
+            Some(RegexSet::new(&self.keep_chrome_patterns).context("Invalid keep_chrome_patterns")?)
+        };
+
+        let drop_chrome_compiled = if self.drop_chrome_patterns.is_empty() {
+            None
+        } else {
+            Some(RegexSet::new(&self.drop_chrome_patterns).context("Invalid drop_chrome_patterns")?)
+        };
+
+        let extraction_schemas_compiled = self
+            .extraction_schemas
+            .iter()
+            .map(|schema| {
+                let context = || format!("Invalid extraction schema for '{}'", schema.url_pattern);
+                let matcher = UrlMatcher::parse(&schema.url_pattern).with_context(context)?;
+                Ok((matcher, schema.spec.compile().with_context(context)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for name in self.headers.keys() {
+            if !is_valid_header_name(name) {
+                return Err(anyhow!("Invalid request header name '{name}'"));
+            }
+        }
+
+        let multiplier = self.retry_pass_timeout_multiplier;
+        if !(multiplier.is_finite() && multiplier >= 1.0) {
+            return Err(anyhow!("retry_pass_timeout_multiplier must be at least 1, got {multiplier}"));
+        }
+
+        // Cookies from the cookie file come first so explicit cookies override them
+        let mut cookies = match &self.cookie_file {
+            Some(path) => load_cookie_file(path)?,
+            None => Vec::new(),
+        };
+        cookies.extend(self.cookies);
+
         // Enforce headless mode in release builds for production safety
         #[cfg(not(debug_assertions))]
         let headless = if !self.headless {
@@ -272,6 +429,13 @@ impl CrawlConfigBuilder<WithStartUrl> {
         #[cfg(debug_assertions)]
         let headless = self.headless;
 
+        // The retry pass browser is subject to the same release-build restriction
+        let retry_pass_headless = if cfg!(debug_assertions) {
+            self.retry_pass_headless
+        } else {
+            self.retry_pass_headless.map(|_| true)
+        };
+
         // Normalize storage_dir to absolute path for consistent path operations
         // This ensures LinkIndex, LinkRewriter, and all other consumers get absolute paths
         let storage_dir = self
@@ -326,15 +490,48 @@ impl CrawlConfigBuilder<WithStartUrl> {
             circuit_breaker_enabled: self.circuit_breaker_enabled,
             circuit_breaker_failure_threshold: self.circuit_breaker_failure_threshold,
             circuit_breaker_retry_delay_secs: self.circuit_breaker_retry_delay_secs,
+            retry_pass_enabled: self.retry_pass_enabled,
+            retry_pass_timeout_multiplier: self.retry_pass_timeout_multiplier,
+            retry_pass_headless,
+            retry_pass_proxy: self.retry_pass_proxy,
             event_bus: None,
             indexing_sender: None,
             max_concurrent_pages: self.max_concurrent_pages,
             max_concurrent_per_domain: self.max_concurrent_per_domain,
             chrome_data_dir: None,
             browser_pool: None,
+            crawl_control: None,
             compress_output: false, // Default to uncompressed
             compression_threshold_bytes: self.compression_threshold_bytes,
             max_page_retries: self.max_page_retries,
+            wait_for_selector: self.wait_for_selector,
+            wait_for_network_idle_ms: self.wait_for_network_idle_ms,
+            wait_for_function: self.wait_for_function,
+            dismiss_overlays: self.dismiss_overlays,
+            reject_consent: self.reject_consent,
+            mirror_assets: self.mirror_assets,
+            event_journal: self.event_journal,
+            sitemap: self.sitemap,
+            sitemap_base_url: self.sitemap_base_url,
+            output_url: self.output_url,
+            extraction_backend: self.extraction_backend,
+            min_extraction_quality: self.min_extraction_quality,
+            keep_comment_patterns: self.keep_comment_patterns,
+            chrome_filter_level: self.chrome_filter_level,
+            typography: self.typography,
+            keep_chrome_patterns: self.keep_chrome_patterns,
+            drop_chrome_patterns: self.drop_chrome_patterns,
+            keep_comments_compiled,
+            keep_chrome_compiled,
+            drop_chrome_compiled,
+            extraction_schemas: self.extraction_schemas,
+            extraction_schemas_compiled,
+            link_index_url: self.link_index_url,
+            link_rewrite_window_ms: self.link_rewrite_window_ms,
+            seed_urls: self.seed_urls,
+            scope: self.scope,
+            headers: self.headers,
+            cookies,
         })
     }
 }
@@ -395,3 +592,8 @@ impl<State> CrawlConfigBuilder<State> {
         self
     }
 }
+
+/// Whether `name` can be sent as a request header name
+pub(crate) fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c == ':' || c.is_whitespace() || c.is_control())
+}
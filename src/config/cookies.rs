@@ -0,0 +1,103 @@
+//! Cookies sent with every crawled page
+//!
+//! Cookies are set on each page through CDP before navigation, so sites that
+//! gate content behind a session or consent cookie can be crawled. They come
+//! from the config directly or from a Netscape-format `cookies.txt` file as
+//! exported by browsers and `curl -c`. Values may reference a secret store
+//! instead of holding the credential (see [`super::secret`]).
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::secret::Secret;
+
+/// A cookie to set before navigation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Cookie {
+    pub name: String,
+    /// Inline value or a secret reference (`env:`, `file:`, `keychain:`)
+    pub value: Secret,
+    /// Domain the cookie applies to; defaults to the host of the page being crawled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// Expiry in seconds since the Unix epoch; `None` for a session cookie
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<f64>,
+}
+
+impl Cookie {
+    /// Session cookie for the crawled host
+    #[must_use]
+    pub fn new(name: impl Into<String>, value: impl Into<Secret>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            expires: None,
+        }
+    }
+}
+
+/// Parse a Netscape `cookies.txt` file
+///
+/// Each line holds seven tab-separated fields: domain, include-subdomains
+/// flag, path, secure flag, expiry, name and value. `#` lines are comments,
+/// except the `#HttpOnly_` prefix curl uses to mark HTTP-only cookies.
+pub fn parse_netscape_cookies(text: &str) -> Result<Vec<Cookie>> {
+    let mut cookies = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [domain, _subdomains, path, secure, expires, name, value] = fields[..] else {
+            bail!(
+                "line {}: expected 7 tab-separated fields, found {}",
+                index + 1,
+                fields.len()
+            );
+        };
+        let expires: f64 = expires
+            .trim()
+            .parse()
+            .with_context(|| format!("line {}: invalid expiry '{expires}'", index + 1))?;
+
+        cookies.push(Cookie {
+            name: name.to_string(),
+            value: Secret::Literal(value.to_string()),
+            domain: Some(domain.to_string()),
+            path: Some(path.to_string()).filter(|p| !p.is_empty()),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+            // 0 marks a session cookie
+            expires: (expires > 0.0).then_some(expires),
+        });
+    }
+    Ok(cookies)
+}
+
+/// Read and parse a Netscape `cookies.txt` file
+pub fn load_cookie_file(path: impl AsRef<Path>) -> Result<Vec<Cookie>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cookie file '{}'", path.display()))?;
+    parse_netscape_cookies(&text).with_context(|| format!("Invalid cookie file '{}'", path.display()))
+}
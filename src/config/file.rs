@@ -0,0 +1,558 @@
+//! Loading `CrawlConfig` from TOML or YAML files
+//!
+//! Crawl definitions can live in version control next to the project they
+//! document. The file mirrors the builder: `start_url` and `storage_dir` are
+//! required, everything else is optional and grouped into sections:
+//!
+//! ```toml
+//! start_url = "https://docs.rs/tokio"
+//! storage_dir = "docs/tokio"   # relative to the file
+//! profile = "stealth"          # fast | thorough | stealth, applied first
+//! stealth_mode = true
+//!
+//! [filters]
+//! allowed_domains = ["docs.rs"]
+//! excluded_patterns = ["*/src/*"]
+//! scope = "same_registrable_domain"   # or same_host (default)
+//!
+//! [budgets]
+//! max_depth = 4
+//! limit = 500
+//! crawl_rate_rps = 1.5
+//!
+//! [output]
+//! save_screenshots = false
+//! compress_output = true
+//! url = "s3://docs-bucket/tokio"  # also persist to object storage
+//! sitemap_base_url = "https://mirror.example.org/"  # sitemap lists the re-hosted mirror
+//! extraction_backend = "readability"  # heuristic (default) | readability | compare
+//! min_extraction_quality = 0.3  # retry low-scoring pages unfiltered; 0 disables
+//! keep_comment_patterns = ["^docs-build:", "TODO"]  # HTML comments kept in markdown
+//! chrome_filter_level = "lenient"  # lenient | standard (default) | strict
+//! keep_chrome_patterns = ["^related-api$"]  # class/id always converted
+//! drop_chrome_patterns = ["announcement-bar"]  # class/id always dropped
+//! normalize_punctuation = true  # curly quotes, dashes, ellipses to ASCII (prose only)
+//! decode_numeric_entities = true  # &#8217; to its character, except < > &
+//! strip_emoji = true
+//!
+//! [wait]
+//! selector = "main article"
+//! dismiss_overlays = true  # remove cookie banners and paywalls (default)
+//! reject_consent = true    # click "reject" before removing them (opt-in)
+//!
+//! [retry_pass]  # final attempt at pages lost to transient failures
+//! timeout_multiplier = 3.0
+//! headless = false
+//! proxy = "http://proxy.internal:3128"
+//!
+//! [request]
+//! headers = { Authorization = "env:DOCS_AUTH_HEADER" }  # or file:, keychain:, a value
+//! cookie_file = "cookies.txt"  # Netscape format, relative to the file
+//!
+//! [[extraction_schemas]]  # matching pages get a data.json next to index.md
+//! url_pattern = "*/releases/*"
+//! fields.version = { selector = "h1", regex = "v([0-9.]+)" }
+//! fields.date = { selector = "time", attribute = "datetime" }
+//! ```
+//!
+//! Unknown keys are rejected, and type and validation errors name the
+//! offending key (`budgets.max_depth: invalid type: ...`).
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::builder::is_valid_header_name;
+use super::cookies::Cookie;
+use super::secret::Secret;
+use super::profile::CrawlProfile;
+use super::types::{CrawlConfig, CrawlScope};
+use crate::content_saver::markdown_converter::{ChromeFilterLevel, ExtractionBackend};
+use crate::imurl::UrlMatcher;
+use crate::page_extractor::structured::PageSchema;
+
+/// Format of a crawl configuration file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Format implied by `path`'s extension (`.toml`, `.yaml`, `.yml`)
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            _ => bail!(
+                "Unsupported config file '{}': expected a .toml, .yaml or .yml extension",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Which pages the crawl may visit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FilterSettings {
+    pub allowed_domains: Option<Vec<String>>,
+    /// Glob patterns (`*` matches anything) or `re:` regexes of URLs to skip;
+    /// patterns from before globs need `re:^…$` to keep their regex meaning
+    pub excluded_patterns: Option<Vec<String>>,
+    pub allow_subdomains: Option<bool>,
+    pub allow_external_domains: Option<bool>,
+    pub content_selector: Option<String>,
+    pub only_html: Option<bool>,
+    pub seed_urls: Option<Vec<String>>,
+    /// `same_host` or `same_registrable_domain`
+    pub scope: Option<CrawlScope>,
+}
+
+/// How much the crawl may do and how fast
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetSettings {
+    pub max_depth: Option<u8>,
+    /// Most pages to crawl
+    pub limit: Option<usize>,
+    /// Requests per second; 0 disables rate limiting
+    pub crawl_rate_rps: Option<f64>,
+    pub max_concurrent_pages: Option<usize>,
+    pub max_concurrent_per_domain: Option<usize>,
+    pub max_page_retries: Option<u8>,
+    pub max_deferred_queue_size: Option<usize>,
+    pub page_load_timeout_secs: Option<u64>,
+    pub navigation_timeout_secs: Option<u64>,
+    pub event_timeout_secs: Option<u64>,
+}
+
+/// What gets written for each page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputSettings {
+    pub save_markdown: Option<bool>,
+    pub save_json: Option<bool>,
+    pub save_raw_html: Option<bool>,
+    pub save_screenshots: Option<bool>,
+    pub screenshot_quality: Option<u8>,
+    pub compress_output: Option<bool>,
+    pub compression_threshold_bytes: Option<usize>,
+    pub mirror_assets: Option<bool>,
+    pub event_journal: Option<bool>,
+    pub sitemap: Option<bool>,
+    /// Absolute URL the mirror is re-hosted at, used in `sitemap.xml`
+    pub sitemap_base_url: Option<String>,
+    pub full_resources: Option<bool>,
+    pub max_inline_image_size_bytes: Option<usize>,
+    pub generate_components: Option<bool>,
+    pub progressive: Option<bool>,
+    pub presentation_style: Option<String>,
+    pub search_index_dir: Option<PathBuf>,
+    /// `s3://bucket/prefix` or `gs://bucket/prefix` to persist the output to
+    pub url: Option<String>,
+    /// `heuristic` (default), `readability` or `compare`
+    pub extraction_backend: Option<ExtractionBackend>,
+    /// Quality score (0 to 1) below which pages are converted again unfiltered
+    pub min_extraction_quality: Option<f64>,
+    /// Regexes of HTML comments kept in the markdown
+    pub keep_comment_patterns: Option<Vec<String>>,
+    /// `lenient`, `standard` (default) or `strict` filtering of page chrome
+    pub chrome_filter_level: Option<ChromeFilterLevel>,
+    /// Regexes of `class`/`id` values whose elements are always converted
+    pub keep_chrome_patterns: Option<Vec<String>>,
+    /// Regexes of `class`/`id` values whose elements are always dropped
+    pub drop_chrome_patterns: Option<Vec<String>>,
+    /// Replace typographic quotes, dashes and ellipses in prose with ASCII
+    pub normalize_punctuation: Option<bool>,
+    /// Decode numeric character references left in prose
+    pub decode_numeric_entities: Option<bool>,
+    /// Remove emoji from prose
+    pub strip_emoji: Option<bool>,
+}
+
+/// When a page counts as loaded and how it is prepared for extraction
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaitSettings {
+    pub selector: Option<String>,
+    pub network_idle_ms: Option<u64>,
+    pub function: Option<String>,
+    pub dismiss_overlays: Option<bool>,
+    pub reject_consent: Option<bool>,
+}
+
+/// Incremental crawl cache settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    pub enable_validation: Option<bool>,
+    pub ignore: Option<bool>,
+    pub validation_timeout_secs: Option<u64>,
+}
+
+/// Per-domain failure handling
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    pub enabled: Option<bool>,
+    pub failure_threshold: Option<u32>,
+    pub retry_delay_secs: Option<u64>,
+}
+
+/// End-of-crawl retry pass for pages that failed transiently
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPassSettings {
+    pub enabled: Option<bool>,
+    /// Factor applied to page load and navigation timeouts, at least 1
+    pub timeout_multiplier: Option<f64>,
+    pub headless: Option<bool>,
+    /// Chrome `--proxy-server` value, e.g. `http://host:3128`
+    pub proxy: Option<String>,
+}
+
+/// Headers and cookies sent with every page request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RequestSettings {
+    pub headers: Option<HashMap<String, Secret>>,
+    pub cookies: Option<Vec<Cookie>>,
+    /// Netscape `cookies.txt`; relative paths are resolved against the file's directory
+    pub cookie_file: Option<PathBuf>,
+}
+
+/// Contents of a crawl configuration file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CrawlConfigFile {
+    pub start_url: String,
+    /// Relative paths are resolved against the file's directory
+    pub storage_dir: PathBuf,
+    /// Named profile applied before the other keys
+    #[serde(default)]
+    pub profile: Option<CrawlProfile>,
+    #[serde(default)]
+    pub headless: Option<bool>,
+    #[serde(default)]
+    pub stealth_mode: Option<bool>,
+    #[serde(default)]
+    pub link_index_url: Option<String>,
+    #[serde(default)]
+    pub link_rewrite_window_ms: Option<u64>,
+    #[serde(default)]
+    pub filters: FilterSettings,
+    #[serde(default)]
+    pub budgets: BudgetSettings,
+    #[serde(default)]
+    pub output: OutputSettings,
+    #[serde(default)]
+    pub wait: WaitSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerSettings,
+    #[serde(default)]
+    pub retry_pass: RetryPassSettings,
+    #[serde(default)]
+    pub request: RequestSettings,
+    /// Structured extraction per URL pattern (`[[extraction_schemas]]`)
+    #[serde(default)]
+    pub extraction_schemas: Vec<PageSchema>,
+}
+
+impl CrawlConfigFile {
+    /// Parse `text` as `format`; errors name the offending key
+    pub fn parse(text: &str, format: ConfigFormat) -> Result<Self> {
+        deserialize_keyed(text, format)
+    }
+
+    /// Check values the types alone do not constrain
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |key: &str, message: String| Err(anyhow!("{key}: {message}"));
+
+        let start_url = if self.start_url.contains("://") {
+            self.start_url.clone()
+        } else {
+            format!("https://{}", self.start_url)
+        };
+        if let Err(e) = url::Url::parse(&start_url) {
+            return invalid("start_url", format!("invalid URL '{}': {e}", self.start_url));
+        }
+        if self.storage_dir.as_os_str().is_empty() {
+            return invalid("storage_dir", "must not be empty".to_string());
+        }
+        for (index, url) in self.filters.seed_urls.iter().flatten().enumerate() {
+            if url::Url::parse(url).is_err() {
+                return invalid(&format!("filters.seed_urls[{index}]"), format!("invalid URL '{url}'"));
+            }
+        }
+        for (index, pattern) in self.filters.excluded_patterns.iter().flatten().enumerate() {
+            if let Err(e) = UrlMatcher::parse(pattern) {
+                return invalid(
+                    &format!("filters.excluded_patterns[{index}]"),
+                    e.to_string(),
+                );
+            }
+        }
+        if let Some(selector) = &self.filters.content_selector
+            && scraper::Selector::parse(selector).is_err()
+        {
+            return invalid("filters.content_selector", format!("invalid CSS selector '{selector}'"));
+        }
+        if let Some(rate) = self.budgets.crawl_rate_rps
+            && !(rate.is_finite() && rate >= 0.0)
+        {
+            return invalid("budgets.crawl_rate_rps", format!("must be 0 or a positive number, got {rate}"));
+        }
+        if let Some(pages) = self.budgets.max_concurrent_pages
+            && !(1..=100).contains(&pages)
+        {
+            return invalid("budgets.max_concurrent_pages", format!("must be 1-100, got {pages}"));
+        }
+        if let Some(per_domain) = self.budgets.max_concurrent_per_domain
+            && !(1..=10).contains(&per_domain)
+        {
+            return invalid("budgets.max_concurrent_per_domain", format!("must be 1-10, got {per_domain}"));
+        }
+        if let Some(quality) = self.output.screenshot_quality
+            && !(1..=100).contains(&quality)
+        {
+            return invalid("output.screenshot_quality", format!("must be 1-100, got {quality}"));
+        }
+        if let Some(score) = self.output.min_extraction_quality
+            && !(0.0..=1.0).contains(&score)
+        {
+            return invalid("output.min_extraction_quality", format!("must be 0-1, got {score}"));
+        }
+        if let Some(base) = &self.output.sitemap_base_url
+            && !url::Url::parse(base).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            return invalid("output.sitemap_base_url", format!("must be an absolute http(s) URL, got '{base}'"));
+        }
+        let pattern_lists = [
+            ("keep_comment_patterns", &self.output.keep_comment_patterns),
+            ("keep_chrome_patterns", &self.output.keep_chrome_patterns),
+            ("drop_chrome_patterns", &self.output.drop_chrome_patterns),
+        ];
+        for (key, patterns) in pattern_lists {
+            for (index, pattern) in patterns.iter().flatten().enumerate() {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    return invalid(&format!("output.{key}[{index}]"), format!("invalid regex '{pattern}': {e}"));
+                }
+            }
+        }
+        for (index, schema) in self.extraction_schemas.iter().enumerate() {
+            if let Err(e) = schema.validate() {
+                return invalid(&format!("extraction_schemas[{index}]"), format!("{e:#}"));
+            }
+        }
+        if let Some(multiplier) = self.retry_pass.timeout_multiplier
+            && !(multiplier.is_finite() && multiplier >= 1.0)
+        {
+            return invalid("retry_pass.timeout_multiplier", format!("must be at least 1, got {multiplier}"));
+        }
+        if let Some(proxy) = &self.retry_pass.proxy
+            && proxy.trim().is_empty()
+        {
+            return invalid("retry_pass.proxy", "must not be empty".to_string());
+        }
+        if let Some(selector) = &self.wait.selector
+            && scraper::Selector::parse(selector).is_err()
+        {
+            return invalid("wait.selector", format!("invalid CSS selector '{selector}'"));
+        }
+        for name in self.request.headers.iter().flat_map(HashMap::keys) {
+            if !is_valid_header_name(name) {
+                return invalid(&format!("request.headers.{name}"), "invalid header name".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the `CrawlConfig`, resolving relative paths against `base_dir`
+    pub fn into_config(self, base_dir: &Path) -> Result<CrawlConfig> {
+        self.validate()?;
+        let resolve = |path: PathBuf| if path.is_absolute() { path } else { base_dir.join(path) };
+
+        let mut builder = CrawlConfig::builder()
+            .storage_dir(resolve(self.storage_dir))
+            .start_url(self.start_url);
+        if let Some(profile) = self.profile {
+            builder = builder.profile(profile);
+        }
+
+        macro_rules! set {
+            ($value:expr => Some $field:ident) => {
+                if let Some(value) = $value {
+                    builder.$field = Some(value);
+                }
+            };
+            ($value:expr => $($field:ident).+) => {
+                if let Some(value) = $value {
+                    builder.$($field).+ = value;
+                }
+            };
+        }
+
+        set!(self.headless => headless);
+        set!(self.stealth_mode => stealth_mode);
+        set!(self.link_index_url => Some link_index_url);
+        set!(self.link_rewrite_window_ms => link_rewrite_window_ms);
+
+        let filters = self.filters;
+        set!(filters.allowed_domains => Some allowed_domains);
+        set!(filters.excluded_patterns => Some excluded_patterns);
+        set!(filters.allow_subdomains => allow_subdomains);
+        set!(filters.allow_external_domains => allow_external_domains);
+        set!(filters.content_selector => Some content_selector);
+        set!(filters.only_html => only_html);
+        set!(filters.seed_urls => seed_urls);
+        set!(filters.scope => scope);
+
+        let budgets = self.budgets;
+        set!(budgets.max_depth => max_depth);
+        set!(budgets.limit => Some limit);
+        if let Some(rate) = budgets.crawl_rate_rps {
+            builder.crawl_rate_rps = (rate > 0.0).then_some(rate);
+        }
+        set!(budgets.max_concurrent_pages => Some max_concurrent_pages);
+        set!(budgets.max_concurrent_per_domain => Some max_concurrent_per_domain);
+        set!(budgets.max_page_retries => Some max_page_retries);
+        set!(budgets.max_deferred_queue_size => Some max_deferred_queue_size);
+        set!(budgets.page_load_timeout_secs => Some page_load_timeout_secs);
+        set!(budgets.navigation_timeout_secs => Some navigation_timeout_secs);
+        set!(budgets.event_timeout_secs => Some event_timeout_secs);
+
+        let output = self.output;
+        set!(output.save_markdown => save_markdown);
+        set!(output.save_json => save_json);
+        set!(output.save_raw_html => save_raw_html);
+        set!(output.save_screenshots => save_screenshots);
+        set!(output.screenshot_quality => screenshot_quality);
+        set!(output.compression_threshold_bytes => Some compression_threshold_bytes);
+        set!(output.mirror_assets => mirror_assets);
+        set!(output.event_journal => event_journal);
+        set!(output.sitemap => sitemap);
+        set!(output.sitemap_base_url => Some sitemap_base_url);
+        set!(output.url => Some output_url);
+        set!(output.extraction_backend => extraction_backend);
+        set!(output.min_extraction_quality => min_extraction_quality);
+        set!(output.keep_comment_patterns => keep_comment_patterns);
+        set!(output.chrome_filter_level => chrome_filter_level);
+        set!(output.keep_chrome_patterns => keep_chrome_patterns);
+        set!(output.drop_chrome_patterns => drop_chrome_patterns);
+        set!(output.normalize_punctuation => typography.normalize_punctuation);
+        set!(output.decode_numeric_entities => typography.decode_numeric_entities);
+        set!(output.strip_emoji => typography.strip_emoji);
+        set!(output.full_resources => full_resources);
+        set!(output.max_inline_image_size_bytes => Some max_inline_image_size_bytes);
+        set!(output.generate_components => generate_components);
+        set!(output.progressive => progressive);
+        set!(output.presentation_style => presentation_style);
+        set!(output.search_index_dir.clone().map(resolve) => Some search_index_dir);
+
+        set!(self.wait.selector => Some wait_for_selector);
+        set!(self.wait.network_idle_ms => Some wait_for_network_idle_ms);
+        set!(self.wait.function => Some wait_for_function);
+        set!(self.wait.dismiss_overlays => dismiss_overlays);
+        set!(self.wait.reject_consent => reject_consent);
+        if !self.extraction_schemas.is_empty() {
+            builder.extraction_schemas = self.extraction_schemas;
+        }
+
+        set!(self.cache.enable_validation => enable_cache_validation);
+        set!(self.cache.ignore => ignore_cache);
+        set!(self.cache.validation_timeout_secs => Some cache_validation_timeout_secs);
+
+        set!(self.circuit_breaker.enabled => circuit_breaker_enabled);
+        set!(self.circuit_breaker.failure_threshold => circuit_breaker_failure_threshold);
+        set!(self.circuit_breaker.retry_delay_secs => circuit_breaker_retry_delay_secs);
+
+        set!(self.retry_pass.enabled => retry_pass_enabled);
+        set!(self.retry_pass.timeout_multiplier => retry_pass_timeout_multiplier);
+        set!(self.retry_pass.headless => Some retry_pass_headless);
+        set!(self.retry_pass.proxy => Some retry_pass_proxy);
+
+        // Relative `file:` secrets are relative to the config file too
+        let resolve_secret = |secret: Secret| match secret {
+            Secret::File(path) => Secret::File(resolve(path)),
+            other => other,
+        };
+        set!(self.request.headers.map(|headers| {
+            headers.into_iter().map(|(name, value)| (name, resolve_secret(value))).collect()
+        }) => headers);
+        set!(self.request.cookies.map(|cookies| {
+            cookies
+                .into_iter()
+                .map(|cookie| Cookie { value: resolve_secret(cookie.value), ..cookie })
+                .collect()
+        }) => cookies);
+        set!(self.request.cookie_file.map(resolve) => Some cookie_file);
+
+        let mut config = builder.build()?;
+        // Not exposed on the builder
+        if let Some(compress) = output.compress_output {
+            config.compress_output = compress;
+        }
+        Ok(config)
+    }
+}
+
+/// Deserialize `text` as `format`; errors name the offending key
+pub(super) fn deserialize_keyed<T: serde::de::DeserializeOwned>(text: &str, format: ConfigFormat) -> Result<T> {
+    match format {
+        ConfigFormat::Toml => {
+            let deserializer = toml::Deserializer::parse(text).map_err(|e| anyhow!("{e}"))?;
+            serde_path_to_error::deserialize(deserializer).map_err(|e| keyed_error(e.path(), e.inner()))
+        }
+        ConfigFormat::Yaml => {
+            let deserializer = serde_norway::Deserializer::from_str(text);
+            serde_path_to_error::deserialize(deserializer).map_err(|e| keyed_error(e.path(), e.inner()))
+        }
+    }
+}
+
+/// Error prefixed with the dotted path of the key it concerns
+fn keyed_error(path: &serde_path_to_error::Path, error: &impl std::fmt::Display) -> anyhow::Error {
+    let path = path.to_string();
+    if path.is_empty() || path == "." {
+        anyhow!("{error}")
+    } else {
+        anyhow!("{path}: {error}")
+    }
+}
+
+impl CrawlConfig {
+    /// Load a crawl definition from a TOML (`.toml`) or YAML (`.yaml`, `.yml`) file
+    ///
+    /// See the [module docs](crate::config::file) for the layout. Relative
+    /// `storage_dir` and `output.search_index_dir` paths are resolved against
+    /// the file's directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the file cannot be read, has an unsupported extension, does
+    /// not parse, has unknown keys or holds invalid values; the message names
+    /// the file and the offending key.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+        let base_dir = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+
+        CrawlConfigFile::parse(&text, format)
+            .and_then(|file| file.into_config(&base_dir))
+            .with_context(|| format!("Invalid config file '{}'", path.display()))
+    }
+}
@@ -3,9 +3,15 @@
 //! This module provides all the accessor methods for retrieving configuration
 //! values from a `CrawlConfig` instance.
 
+use regex::RegexSet;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use super::types::CrawlConfig;
+use super::cookies::Cookie;
+use super::secret::Secret;
+use super::types::{CrawlConfig, CrawlScope};
+use crate::content_saver::markdown_converter::{ChromeFilter, ChromeFilterLevel, ConversionOptions, ExtractionBackend, Typography};
+use crate::page_extractor::structured::{CompiledExtraction, PageSchema};
 
 impl CrawlConfig {
     #[must_use]
@@ -233,6 +239,36 @@ impl CrawlConfig {
         self.circuit_breaker_retry_delay_secs
     }
 
+    /// Check if the end-of-crawl retry pass is enabled
+    ///
+    /// Default is true.
+    #[must_use]
+    pub fn retry_pass_enabled(&self) -> bool {
+        self.retry_pass_enabled
+    }
+
+    /// Get the factor applied to timeouts in the retry pass
+    ///
+    /// Default is 2.0.
+    #[must_use]
+    pub fn retry_pass_timeout_multiplier(&self) -> f64 {
+        self.retry_pass_timeout_multiplier
+    }
+
+    /// Get the headless setting for the retry pass browser
+    ///
+    /// Falls back to the main crawl's headless setting.
+    #[must_use]
+    pub fn retry_pass_headless(&self) -> bool {
+        self.retry_pass_headless.unwrap_or(self.headless)
+    }
+
+    /// Get the proxy server for the retry pass browser, if any
+    #[must_use]
+    pub fn retry_pass_proxy(&self) -> Option<&str> {
+        self.retry_pass_proxy.as_deref()
+    }
+
     /// Get the maximum number of pages to crawl concurrently
     ///
     /// Returns the configured concurrency limit.
@@ -259,6 +295,199 @@ impl CrawlConfig {
     pub fn max_page_retries(&self) -> u8 {
         self.max_page_retries.unwrap_or(3)
     }
+
+    /// Get the CSS selector that must match before the page is ready
+    #[must_use]
+    pub fn wait_for_selector(&self) -> Option<&str> {
+        self.wait_for_selector.as_deref()
+    }
+
+    /// Get the network idle quiet period in milliseconds
+    #[must_use]
+    pub fn wait_for_network_idle_ms(&self) -> Option<u64> {
+        self.wait_for_network_idle_ms
+    }
+
+    /// Get the JavaScript readiness predicate
+    #[must_use]
+    pub fn wait_for_function(&self) -> Option<&str> {
+        self.wait_for_function.as_deref()
+    }
+
+    /// Check if consent banners and overlays are dismissed before extraction
+    #[must_use]
+    pub fn dismiss_overlays(&self) -> bool {
+        self.dismiss_overlays
+    }
+
+    /// Check if consent dialogs are rejected before they are removed
+    #[must_use]
+    pub fn reject_consent(&self) -> bool {
+        self.reject_consent
+    }
+
+    /// Collect the configured page readiness conditions
+    #[must_use]
+    pub fn page_ready_conditions(&self) -> crate::page_extractor::PageReadyConditions {
+        crate::page_extractor::PageReadyConditions {
+            selector: self.wait_for_selector.clone(),
+            network_idle_ms: self.wait_for_network_idle_ms,
+            function: self.wait_for_function.clone(),
+        }
+    }
+
+    /// Check if page assets are mirrored alongside saved HTML
+    #[must_use]
+    pub fn mirror_assets(&self) -> bool {
+        self.mirror_assets
+    }
+
+    /// Check if crawl events are journaled to the output directory
+    #[must_use]
+    pub fn event_journal(&self) -> bool {
+        self.event_journal
+    }
+
+    /// Check if `sitemap.xml` is written when the crawl finishes
+    #[must_use]
+    pub fn sitemap(&self) -> bool {
+        self.sitemap
+    }
+
+    /// Get the base URL sitemap entries are re-hosted onto, if configured
+    #[must_use]
+    pub fn sitemap_base_url(&self) -> Option<&str> {
+        self.sitemap_base_url.as_deref()
+    }
+
+    /// Get the object storage URL the output is persisted to, if configured
+    #[must_use]
+    pub fn output_url(&self) -> Option<&str> {
+        self.output_url.as_deref()
+    }
+
+    /// Get the main-content extractor used for markdown conversion
+    #[must_use]
+    pub fn extraction_backend(&self) -> ExtractionBackend {
+        self.extraction_backend
+    }
+
+    /// Get the extraction quality score below which pages are retried unfiltered
+    #[must_use]
+    pub fn min_extraction_quality(&self) -> f64 {
+        self.min_extraction_quality
+    }
+
+    /// Get the regex patterns of HTML comments kept in the markdown
+    #[must_use]
+    pub fn keep_comment_patterns(&self) -> &[String] {
+        &self.keep_comment_patterns
+    }
+
+    /// Get the compiled patterns of HTML comments kept in the markdown
+    #[must_use]
+    pub fn keep_comments(&self) -> Option<&RegexSet> {
+        self.keep_comments_compiled.as_ref()
+    }
+
+    /// Get how aggressively navigation, headers, footers and asides are filtered
+    #[must_use]
+    pub fn chrome_filter_level(&self) -> ChromeFilterLevel {
+        self.chrome_filter_level
+    }
+
+    /// Get the character normalizations applied to the prose of saved markdown
+    #[must_use]
+    pub fn typography(&self) -> Typography {
+        self.typography
+    }
+
+    /// Get the regex patterns of `class`/`id` values always converted
+    #[must_use]
+    pub fn keep_chrome_patterns(&self) -> &[String] {
+        &self.keep_chrome_patterns
+    }
+
+    /// Get the regex patterns of `class`/`id` values always dropped
+    #[must_use]
+    pub fn drop_chrome_patterns(&self) -> &[String] {
+        &self.drop_chrome_patterns
+    }
+
+    /// Get the chrome filter applied during markdown conversion
+    #[must_use]
+    pub fn chrome_filter(&self) -> ChromeFilter {
+        ChromeFilter {
+            level: self.chrome_filter_level,
+            keep: self.keep_chrome_compiled.clone(),
+            drop: self.drop_chrome_compiled.clone(),
+        }
+    }
+
+    /// Get the markdown conversion options for a page at `base_url`
+    #[must_use]
+    pub fn conversion_options(&self, base_url: &str) -> ConversionOptions {
+        ConversionOptions {
+            base_url: Some(base_url.to_string()),
+            extraction_backend: self.extraction_backend,
+            min_quality_score: self.min_extraction_quality,
+            keep_comments: self.keep_comments_compiled.clone(),
+            chrome_filter: self.chrome_filter(),
+            typography: self.typography,
+            ..ConversionOptions::default()
+        }
+    }
+
+    /// Get the structured extraction schemas
+    #[must_use]
+    pub fn extraction_schemas(&self) -> &[PageSchema] {
+        &self.extraction_schemas
+    }
+
+    /// Extraction spec of the first schema whose pattern matches `url`
+    #[must_use]
+    pub fn extraction_schema_for(&self, url: &str) -> Option<&CompiledExtraction> {
+        self.extraction_schemas_compiled
+            .iter()
+            .find(|(matcher, _)| matcher.matches_str(url))
+            .map(|(_, extraction)| extraction)
+    }
+
+    /// Get the shared link index database URL, if configured
+    #[must_use]
+    pub fn link_index_url(&self) -> Option<&str> {
+        self.link_index_url.as_deref()
+    }
+
+    /// Get the retroactive link rewrite batching window
+    #[must_use]
+    pub fn link_rewrite_window(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.link_rewrite_window_ms)
+    }
+
+    /// Get the extra URLs queued alongside the start URL
+    #[must_use]
+    pub fn seed_urls(&self) -> &[String] {
+        &self.seed_urls
+    }
+
+    /// Get which hosts links are followed to
+    #[must_use]
+    pub fn scope(&self) -> CrawlScope {
+        self.scope
+    }
+
+    /// Get the extra HTTP headers sent with every page request
+    #[must_use]
+    pub fn headers(&self) -> &HashMap<String, Secret> {
+        &self.headers
+    }
+
+    /// Get the cookies set on every page before navigation
+    #[must_use]
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
 }
 
 fn get_available_memory() -> usize {
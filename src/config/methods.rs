@@ -3,7 +3,15 @@
 //! This module contains methods that can be called on the builder
 //! regardless of its current type state.
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+
 use super::builder::CrawlConfigBuilder;
+use super::cookies::Cookie;
+use super::secret::Secret;
+use super::types::CrawlScope;
+use crate::content_saver::markdown_converter::{ChromeFilterLevel, ExtractionBackend, Typography};
+use crate::page_extractor::structured::PageSchema;
 
 // Methods available for all states after required fields are set
 impl<State> CrawlConfigBuilder<State> {
@@ -140,6 +148,10 @@ impl<State> CrawlConfigBuilder<State> {
         self
     }
 
+    /// URLs to skip: globs where only `*` is special, or `re:<regex>`
+    ///
+    /// Patterns written for the older regex semantics need the `re:^…$` form;
+    /// see [`UrlMatcher::legacy_equivalent`](crate::imurl::UrlMatcher::legacy_equivalent).
     #[must_use]
     pub fn excluded_patterns(mut self, patterns: Option<Vec<String>>) -> Self {
         self.excluded_patterns = patterns;
@@ -394,4 +406,289 @@ impl<State> CrawlConfigBuilder<State> {
         self.circuit_breaker_retry_delay_secs = delay_secs;
         self
     }
+
+    /// Enable or disable the end-of-crawl retry pass
+    ///
+    /// When enabled, pages that exhausted their retries on transient failures
+    /// (timeouts, network errors, 5xx responses) are re-attempted once more
+    /// after the main crawl finishes. A `RetryPassCompleted` event reports
+    /// which of them recovered.
+    ///
+    /// Default: true
+    ///
+    /// # Examples
+    /// ```
+    /// # use kodegen_tools_citescrape::config::CrawlConfig;
+    /// let config = CrawlConfig::builder()
+    ///     .storage_dir("./output")
+    ///     .start_url("https://example.com")
+    ///     .retry_pass_enabled(true)
+    ///     .retry_pass_timeout_multiplier(3.0)
+    ///     .retry_pass_proxy("http://proxy.internal:3128")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn retry_pass_enabled(mut self, enabled: bool) -> Self {
+        self.retry_pass_enabled = enabled;
+        self
+    }
+
+    /// Set the factor applied to page load and navigation timeouts in the retry pass
+    ///
+    /// Must be at least 1; `build()` rejects smaller or non-finite values.
+    ///
+    /// Default: 2.0
+    #[must_use]
+    pub fn retry_pass_timeout_multiplier(mut self, multiplier: f64) -> Self {
+        self.retry_pass_timeout_multiplier = multiplier;
+        self
+    }
+
+    /// Run the retry pass in a headless (`true`) or headed (`false`) browser
+    ///
+    /// By default the retry pass uses the main crawl's headless setting.
+    /// Release builds always run headless.
+    #[must_use]
+    pub fn retry_pass_headless(mut self, headless: bool) -> Self {
+        self.retry_pass_headless = Some(headless);
+        self
+    }
+
+    /// Route the retry pass through a proxy server
+    ///
+    /// Accepts any Chrome `--proxy-server` value, e.g. `http://host:3128`
+    /// or `socks5://host:1080`.
+    #[must_use]
+    pub fn retry_pass_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.retry_pass_proxy = Some(proxy.into());
+        self
+    }
+
+    /// Wait for a CSS selector to match before extracting page content
+    ///
+    /// Use this for single-page apps whose content is rendered client-side
+    /// after the `load` event. If the selector does not appear within the
+    /// navigation timeout, extraction proceeds with a warning.
+    ///
+    /// # Examples
+    /// ```
+    /// # use kodegen_tools_citescrape::config::CrawlConfig;
+    /// let config = CrawlConfig::builder()
+    ///     .storage_dir("./output")
+    ///     .start_url("https://example.com")
+    ///     .wait_for_selector(Some("main article".to_string()))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn wait_for_selector(mut self, selector: Option<String>) -> Self {
+        self.wait_for_selector = selector;
+        self
+    }
+
+    /// Wait until the page has made no new network requests for `idle_ms` milliseconds
+    ///
+    /// Default: None (no network idle wait)
+    #[must_use]
+    pub fn wait_for_network_idle_ms(mut self, idle_ms: Option<u64>) -> Self {
+        self.wait_for_network_idle_ms = idle_ms;
+        self
+    }
+
+    /// Wait until a JavaScript function or expression returns a truthy value
+    ///
+    /// Accepts an expression (`window.appReady`) or a function
+    /// (`() => document.querySelectorAll('.item').length > 0`). Promises are awaited.
+    #[must_use]
+    pub fn wait_for_function(mut self, js: Option<String>) -> Self {
+        self.wait_for_function = js;
+        self
+    }
+
+    /// Remove cookie consent banners and paywall overlays before extraction
+    #[must_use]
+    pub fn dismiss_overlays(mut self, dismiss: bool) -> Self {
+        self.dismiss_overlays = dismiss;
+        self
+    }
+
+    /// Click "reject" on consent dialogs before removing them
+    ///
+    /// Only takes effect together with `dismiss_overlays(true)`.
+    #[must_use]
+    pub fn reject_consent(mut self, reject: bool) -> Self {
+        self.reject_consent = reject;
+        self
+    }
+
+    /// Mirror page assets (images, scripts, stylesheets) next to saved HTML
+    ///
+    /// Only takes effect together with `save_raw_html(true)`.
+    #[must_use]
+    pub fn mirror_assets(mut self, mirror: bool) -> Self {
+        self.mirror_assets = mirror;
+        self
+    }
+
+    /// Append crawl events to `.citescrape/events.jsonl` (on by default)
+    #[must_use]
+    pub fn event_journal(mut self, enabled: bool) -> Self {
+        self.event_journal = enabled;
+        self
+    }
+
+    /// Write `sitemap.xml` for the saved pages at the end of the crawl (on by default)
+    #[must_use]
+    pub fn sitemap(mut self, enabled: bool) -> Self {
+        self.sitemap = enabled;
+        self
+    }
+
+    /// List sitemap pages under the URL the mirror is re-hosted at
+    ///
+    /// Must be an absolute `http`/`https` URL; see `CrawlConfig::sitemap_base_url`.
+    #[must_use]
+    pub fn sitemap_base_url(mut self, base: Option<String>) -> Self {
+        self.sitemap_base_url = base;
+        self
+    }
+
+    /// Persist the crawl output to object storage as well as the local disk
+    ///
+    /// Accepts `s3://bucket/prefix` and `gs://bucket/prefix` URLs when built
+    /// with the `object-store` feature; see [`crate::content_saver::sink`].
+    #[must_use]
+    pub fn output_url(mut self, url: Option<String>) -> Self {
+        self.output_url = url;
+        self
+    }
+
+    /// Choose the main-content extractor run before markdown conversion
+    #[must_use]
+    pub fn extraction_backend(mut self, backend: ExtractionBackend) -> Self {
+        self.extraction_backend = backend;
+        self
+    }
+
+    /// Set the extraction quality score (0 to 1) below which pages are
+    /// converted again unfiltered; 0 disables scoring
+    #[must_use]
+    pub fn min_extraction_quality(mut self, score: f64) -> Self {
+        self.min_extraction_quality = score.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Keep HTML comments matching any of these regexes as markdown comments
+    #[must_use]
+    pub fn keep_comment_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.keep_comment_patterns = patterns;
+        self
+    }
+
+    /// Set how aggressively navigation, headers, footers and asides are filtered
+    #[must_use]
+    pub fn chrome_filter_level(mut self, level: ChromeFilterLevel) -> Self {
+        self.chrome_filter_level = level;
+        self
+    }
+
+    /// Set the character normalizations applied to the prose of saved markdown
+    #[must_use]
+    pub fn typography(mut self, typography: Typography) -> Self {
+        self.typography = typography;
+        self
+    }
+
+    /// Always convert elements whose `class` or `id` matches any of these regexes
+    #[must_use]
+    pub fn keep_chrome_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.keep_chrome_patterns = patterns;
+        self
+    }
+
+    /// Always drop elements whose `class` or `id` matches any of these regexes
+    #[must_use]
+    pub fn drop_chrome_patterns(mut self, patterns: Vec<String>) -> Self {
+        self.drop_chrome_patterns = patterns;
+        self
+    }
+
+    /// Attach structured extraction schemas to URL patterns; matching pages
+    /// get a `data.json` next to their markdown
+    #[must_use]
+    pub fn extraction_schemas(mut self, schemas: Vec<PageSchema>) -> Self {
+        self.extraction_schemas = schemas;
+        self
+    }
+
+    /// Use a shared link index database instead of the local SQLite file
+    ///
+    /// Accepts `postgres://` / `postgresql://` URLs when built with the `postgres` feature.
+    #[must_use]
+    pub fn link_index_url(mut self, url: Option<String>) -> Self {
+        self.link_index_url = url;
+        self
+    }
+
+    /// Set the window for batching retroactive link rewrites (0 = immediate)
+    #[must_use]
+    pub fn link_rewrite_window_ms(mut self, window_ms: u64) -> Self {
+        self.link_rewrite_window_ms = window_ms;
+        self
+    }
+
+    /// Queue extra URLs at depth 0 alongside the start URL
+    #[must_use]
+    pub fn seed_urls(mut self, urls: Vec<String>) -> Self {
+        self.seed_urls = urls;
+        self
+    }
+
+    /// Follow links to the start host only, or to its whole registrable domain
+    #[must_use]
+    pub fn scope(mut self, scope: CrawlScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Send extra HTTP headers with every page request (replaces earlier headers)
+    #[must_use]
+    pub fn headers(mut self, headers: HashMap<String, Secret>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Send one extra HTTP header with every page request
+    ///
+    /// The value may be a secret reference such as `Secret::Env("API_TOKEN".into())`.
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<Secret>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Set cookies on every page before navigation (replaces earlier cookies)
+    #[must_use]
+    pub fn cookies(mut self, cookies: Vec<Cookie>) -> Self {
+        self.cookies = cookies;
+        self
+    }
+
+    /// Set one cookie on every page before navigation
+    #[must_use]
+    pub fn cookie(mut self, cookie: Cookie) -> Self {
+        self.cookies.push(cookie);
+        self
+    }
+
+    /// Load cookies from a Netscape `cookies.txt` file when the config is built
+    ///
+    /// Cookies set with [`cookie`](Self::cookie) or [`cookies`](Self::cookies)
+    /// are applied after file cookies, so they win when name, domain and path match.
+    #[must_use]
+    pub fn cookie_file(mut self, path: Option<PathBuf>) -> Self {
+        self.cookie_file = path;
+        self
+    }
 }
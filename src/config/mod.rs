@@ -1,14 +1,28 @@
 //! Configuration module for web crawling
 //!
 //! This module provides the `CrawlConfig` struct and its type-safe builder
-//! for configuring web crawling operations with validation and sensible defaults.
+//! for configuring web crawling operations with validation and sensible defaults,
+//! plus named profiles, loading crawl definitions from TOML or YAML files and
+//! the layered, hot-reloadable settings of the server binary.
 
 // Sub-modules
 pub mod builder;
+pub mod cookies;
+pub mod file;
 pub mod getters;
 pub mod methods;
+pub mod profile;
+pub mod reload;
+pub mod secret;
+pub mod server;
 pub mod types;
 
 // Re-exports for public API
 pub use builder::{Complete, CrawlConfigBuilder, WithStartUrl, WithStorageDir};
-pub use types::CrawlConfig;
+pub use cookies::{Cookie, load_cookie_file, parse_netscape_cookies};
+pub use file::{ConfigFormat, CrawlConfigFile};
+pub use profile::{CrawlProfile, ProfileSettings};
+pub use reload::{ConfigReloader, ReloadTargets, SettingChange};
+pub use secret::{Secret, SecretValue};
+pub use server::{ApiSettings, LogFormat, LoggingSettings, MetricsSettings, ServerConfig, TelemetrySettings};
+pub use types::{CrawlConfig, CrawlScope};
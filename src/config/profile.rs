@@ -0,0 +1,180 @@
+//! Named crawl profiles
+//!
+//! A profile bundles concurrency, waits, evasion and output options that work
+//! well together, so callers pick `fast`, `thorough` or `stealth` instead of
+//! tuning a dozen settings. Options set after the profile still override it.
+
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::builder::CrawlConfigBuilder;
+use super::types::CrawlConfig;
+
+/// Built-in crawl preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CrawlProfile {
+    /// High concurrency, short timeouts, markdown only
+    Fast,
+    /// Waits for the network to settle, retries more and saves every format
+    Thorough,
+    /// Stealth evasions, one page per domain at a slow, polite rate
+    Stealth,
+}
+
+/// Settings a profile controls
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileSettings {
+    pub max_concurrent_pages: usize,
+    pub max_concurrent_per_domain: usize,
+    pub crawl_rate_rps: f64,
+    pub stealth_mode: bool,
+    pub wait_for_network_idle_ms: Option<u64>,
+    pub page_load_timeout_secs: u64,
+    pub navigation_timeout_secs: u64,
+    pub max_page_retries: u8,
+    pub save_screenshots: bool,
+    pub save_json: bool,
+    pub save_raw_html: bool,
+    pub full_resources: bool,
+    pub mirror_assets: bool,
+}
+
+impl CrawlProfile {
+    pub const ALL: [Self; 3] = [Self::Fast, Self::Thorough, Self::Stealth];
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Fast => "fast",
+            Self::Thorough => "thorough",
+            Self::Stealth => "stealth",
+        }
+    }
+
+    /// The values this profile sets
+    #[must_use]
+    pub fn settings(self) -> ProfileSettings {
+        match self {
+            Self::Fast => ProfileSettings {
+                max_concurrent_pages: 20,
+                max_concurrent_per_domain: 4,
+                crawl_rate_rps: 8.0,
+                stealth_mode: false,
+                wait_for_network_idle_ms: None,
+                page_load_timeout_secs: 15,
+                navigation_timeout_secs: 15,
+                max_page_retries: 1,
+                save_screenshots: false,
+                save_json: false,
+                save_raw_html: false,
+                full_resources: false,
+                mirror_assets: false,
+            },
+            Self::Thorough => ProfileSettings {
+                max_concurrent_pages: 8,
+                max_concurrent_per_domain: 2,
+                crawl_rate_rps: 2.0,
+                stealth_mode: false,
+                wait_for_network_idle_ms: Some(1000),
+                page_load_timeout_secs: 60,
+                navigation_timeout_secs: 60,
+                max_page_retries: 5,
+                save_screenshots: true,
+                save_json: true,
+                save_raw_html: true,
+                full_resources: true,
+                mirror_assets: true,
+            },
+            Self::Stealth => ProfileSettings {
+                max_concurrent_pages: 2,
+                max_concurrent_per_domain: 1,
+                crawl_rate_rps: 0.5,
+                stealth_mode: true,
+                wait_for_network_idle_ms: Some(1500),
+                page_load_timeout_secs: 45,
+                navigation_timeout_secs: 45,
+                max_page_retries: 3,
+                save_screenshots: false,
+                save_json: true,
+                save_raw_html: false,
+                full_resources: true,
+                mirror_assets: false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for CrawlProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CrawlProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fast" => Ok(Self::Fast),
+            "thorough" => Ok(Self::Thorough),
+            "stealth" => Ok(Self::Stealth),
+            other => bail!("Unknown crawl profile '{other}': expected fast, thorough or stealth"),
+        }
+    }
+}
+
+/// Copy `settings` onto a builder or config (both have the same field names)
+macro_rules! apply_settings {
+    ($target:expr, $settings:expr) => {{
+        let s = $settings;
+        $target.max_concurrent_pages = Some(s.max_concurrent_pages);
+        $target.max_concurrent_per_domain = Some(s.max_concurrent_per_domain);
+        $target.crawl_rate_rps = Some(s.crawl_rate_rps);
+        $target.stealth_mode = s.stealth_mode;
+        $target.wait_for_network_idle_ms = s.wait_for_network_idle_ms;
+        $target.page_load_timeout_secs = Some(s.page_load_timeout_secs);
+        $target.navigation_timeout_secs = Some(s.navigation_timeout_secs);
+        $target.max_page_retries = Some(s.max_page_retries);
+        $target.save_screenshots = s.save_screenshots;
+        $target.save_json = s.save_json;
+        $target.save_raw_html = s.save_raw_html;
+        $target.full_resources = s.full_resources;
+        $target.mirror_assets = s.mirror_assets;
+    }};
+}
+
+impl<State> CrawlConfigBuilder<State> {
+    /// Apply a named profile; options set afterwards override it
+    ///
+    /// # Example
+    /// ```rust
+    /// # use kodegen_tools_citescrape::config::{CrawlConfig, CrawlProfile};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let config = CrawlConfig::builder()
+    ///     .storage_dir("./output")
+    ///     .start_url("https://example.com")
+    ///     .profile(CrawlProfile::Stealth)
+    ///     .save_screenshots(true)
+    ///     .build()?;
+    /// assert!(config.stealth_mode());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn profile(mut self, profile: CrawlProfile) -> Self {
+        apply_settings!(self, profile.settings());
+        self
+    }
+}
+
+impl CrawlConfig {
+    /// Apply a named profile to an existing config, replacing the settings it controls
+    pub fn apply_profile(&mut self, profile: CrawlProfile) {
+        apply_settings!(self, profile.settings());
+    }
+}
+
@@ -0,0 +1,220 @@
+//! Hot reload of server settings
+//!
+//! The server polls its config file and applies the settings that are safe to
+//! change at runtime: browser pool bounds, crawl limits and web search pacing.
+//! Each applied change is logged under the [`AUDIT_TARGET`] log target.
+//! Changes to the listener, TLS, headless mode, idle timeout, output root or
+//! tracking parameters are logged as needing a restart and otherwise left alone.
+//!
+//! A reload re-runs the full layering (file, environment, flag overrides), so
+//! environment variables and flags keep winning over the edited file. A file
+//! that fails to parse or validate is reported and the running settings stay.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+use super::server::ServerConfig;
+use crate::browser_pool::BrowserPool;
+use crate::mcp::CrawlRegistry;
+use crate::web_search::EnginePacer;
+
+/// Log target of the entries recording applied changes
+pub const AUDIT_TARGET: &str = "citescrape::audit";
+
+/// How often the config file's modification time is checked
+pub const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// One setting that differs between two configurations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingChange {
+    /// Dotted key, as in the config file
+    pub key: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.key, self.from, self.to)
+    }
+}
+
+/// Settings that differ from `old` to `new`
+///
+/// Returns the changes that can be applied at runtime and those that need a
+/// restart, in that order.
+#[must_use]
+pub fn diff_settings(old: &ServerConfig, new: &ServerConfig) -> (Vec<SettingChange>, Vec<SettingChange>) {
+    fn show(value: &impl Serialize) -> String {
+        serde_json::to_string(value).unwrap_or_default()
+    }
+
+    let mut runtime = Vec::new();
+    let mut restart = Vec::new();
+    macro_rules! compare {
+        ($list:ident, $key:literal, $($field:ident).+) => {
+            if old.$($field).+ != new.$($field).+ {
+                $list.push(SettingChange {
+                    key: $key,
+                    from: show(&old.$($field).+),
+                    to: show(&new.$($field).+),
+                });
+            }
+        };
+    }
+
+    compare!(runtime, "pool.min_size", pool.min_size);
+    compare!(runtime, "pool.max_size", pool.max_size);
+    compare!(runtime, "crawl.max_crawls_per_connection", crawl.max_crawls_per_connection);
+    compare!(runtime, "crawl.max_pages_per_crawl", crawl.max_pages_per_crawl);
+    compare!(runtime, "crawl.allowed_domains", crawl.allowed_domains);
+    compare!(runtime, "crawl.block_private_hosts", crawl.block_private_hosts);
+    compare!(runtime, "search.min_interval_ms", search.min_interval_ms);
+    compare!(runtime, "search.jitter_ms", search.jitter_ms);
+    compare!(runtime, "search.max_concurrent", search.max_concurrent);
+
+    compare!(restart, "server.http", server.http);
+    compare!(restart, "server.tls_cert", server.tls_cert);
+    compare!(restart, "server.tls_key", server.tls_key);
+    compare!(restart, "server.shutdown_timeout_secs", server.shutdown_timeout_secs);
+    compare!(restart, "pool.headless", pool.headless);
+    compare!(restart, "pool.idle_timeout_secs", pool.idle_timeout_secs);
+    compare!(restart, "output.root", output.root);
+    compare!(restart, "output.tracking_params", output.tracking_params);
+    compare!(restart, "logging.format", logging.format);
+    compare!(restart, "metrics.http", metrics.http);
+    compare!(restart, "api.http", api.http);
+    compare!(restart, "api.token", api.token);
+    compare!(restart, "api.grpc", api.grpc);
+    compare!(restart, "telemetry.otlp_endpoint", telemetry.otlp_endpoint);
+    compare!(restart, "telemetry.service_name", telemetry.service_name);
+
+    (runtime, restart)
+}
+
+/// Running components that reloaded settings are applied to
+#[derive(Clone)]
+pub struct ReloadTargets {
+    pub browser_pool: Arc<BrowserPool>,
+    pub crawl_registry: Arc<CrawlRegistry>,
+    pub search_pacer: Arc<EnginePacer>,
+}
+
+impl ReloadTargets {
+    /// Apply the runtime-changeable settings of `config`
+    pub fn apply(&self, config: &ServerConfig) -> Result<()> {
+        self.browser_pool.resize(config.pool.min_size, config.pool.max_size)?;
+        self.crawl_registry.set_quota(config.crawl_quota());
+        self.search_pacer.reconfigure(config.pacing_config());
+        Ok(())
+    }
+}
+
+type Overrides = Arc<dyn Fn(&mut ServerConfig) + Send + Sync>;
+
+/// Watches a server config file and applies safe changes
+pub struct ConfigReloader {
+    path: PathBuf,
+    current: ServerConfig,
+    targets: ReloadTargets,
+    overrides: Overrides,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigReloader {
+    /// Reloader for `path`, starting from the settings the server runs with
+    pub fn new(path: impl Into<PathBuf>, current: ServerConfig, targets: ReloadTargets) -> Self {
+        let path = path.into();
+        let modified = modified_time(&path);
+        Self {
+            path,
+            current,
+            targets,
+            overrides: Arc::new(|_| {}),
+            modified,
+        }
+    }
+
+    /// Re-apply command-line flags on top of every reloaded file
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: impl Fn(&mut ServerConfig) + Send + Sync + 'static) -> Self {
+        self.overrides = Arc::new(overrides);
+        self
+    }
+
+    /// Settings currently in effect
+    #[must_use]
+    pub fn current(&self) -> &ServerConfig {
+        &self.current
+    }
+
+    /// Re-read the file and apply what changed; returns the applied changes
+    ///
+    /// # Errors
+    ///
+    /// Fails when the file cannot be loaded, is invalid or a change is
+    /// rejected; the running settings are left as they were.
+    pub fn reload(&mut self) -> Result<Vec<SettingChange>> {
+        let mut next = ServerConfig::load(Some(&self.path))?;
+        (self.overrides)(&mut next);
+        next.validate()?;
+
+        let (runtime, restart) = diff_settings(&self.current, &next);
+        for change in &restart {
+            log::warn!(
+                target: AUDIT_TARGET,
+                "Config reload: {change} needs a restart, keeping {}",
+                change.from
+            );
+        }
+        if runtime.is_empty() {
+            return Ok(runtime);
+        }
+
+        // Settings that need a restart keep their running values
+        next.server = self.current.server.clone();
+        next.output = self.current.output.clone();
+        next.pool.headless = self.current.pool.headless;
+        next.pool.idle_timeout_secs = self.current.pool.idle_timeout_secs;
+
+        self.targets.apply(&next)?;
+        for change in &runtime {
+            log::info!(target: AUDIT_TARGET, "Config reload applied {change} (from {})", self.path.display());
+        }
+        self.current = next;
+        Ok(runtime)
+    }
+
+    /// Reload whenever the file's modification time changes
+    ///
+    /// Polls every [`RELOAD_POLL_INTERVAL`] until the returned task is aborted.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_POLL_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let modified = modified_time(&self.path);
+                if modified.is_none() || modified == self.modified {
+                    continue;
+                }
+                self.modified = modified;
+                if let Err(e) = self.reload() {
+                    log::warn!(
+                        "Ignoring changed config file '{}': {e:#}",
+                        self.path.display()
+                    );
+                }
+            }
+        })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,191 @@
+//! Credentials referenced from crawl configuration
+//!
+//! Header values and cookie values are [`Secret`]s. In config files and tool
+//! arguments a secret is a string: `env:NAME` reads an environment variable,
+//! `file:PATH` reads a file (trailing newline removed) and
+//! `keychain:SERVICE/ACCOUNT` reads the OS keychain (macOS Keychain via
+//! `security`, the Secret Service on Linux via `secret-tool`). Any other string
+//! is the value itself.
+//!
+//! Secrets are resolved when a crawl starts. Inline values serialize and print
+//! as `<redacted>`; references serialize as the reference, never the value, so
+//! credentials do not end up in manifests, session records or logs.
+
+use anyhow::{Context, Result, anyhow, bail};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+use zeroize::Zeroizing;
+
+/// Printed and serialized in place of inline secret values
+pub const REDACTED: &str = "<redacted>";
+
+/// A credential, given inline or as a reference to where it is stored
+#[derive(Clone, PartialEq, Eq)]
+pub enum Secret {
+    /// Value given directly; never serialized or printed
+    Literal(String),
+    /// Environment variable holding the value
+    Env(String),
+    /// File holding the value
+    File(PathBuf),
+    /// OS keychain entry holding the value
+    Keychain { service: String, account: String },
+}
+
+/// A resolved secret; prints as `<redacted>` and is zeroed on drop
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretValue(Zeroizing<String>);
+
+impl SecretValue {
+    /// The credential itself; keep it out of logs and errors
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Secret {
+    /// Parse the string form (`env:`, `file:`, `keychain:` or an inline value)
+    pub fn parse(value: &str) -> Result<Self> {
+        if let Some(name) = value.strip_prefix("env:") {
+            if name.is_empty() {
+                bail!("env: secret reference needs a variable name");
+            }
+            Ok(Self::Env(name.to_string()))
+        } else if let Some(path) = value.strip_prefix("file:") {
+            if path.is_empty() {
+                bail!("file: secret reference needs a path");
+            }
+            Ok(Self::File(PathBuf::from(path)))
+        } else if let Some(entry) = value.strip_prefix("keychain:") {
+            match entry.split_once('/') {
+                Some((service, account)) if !service.is_empty() && !account.is_empty() => Ok(Self::Keychain {
+                    service: service.to_string(),
+                    account: account.to_string(),
+                }),
+                _ => bail!("keychain: secret reference must be 'keychain:SERVICE/ACCOUNT'"),
+            }
+        } else {
+            Ok(Self::Literal(value.to_string()))
+        }
+    }
+
+    /// Whether the value is stored outside the configuration
+    #[must_use]
+    pub fn is_reference(&self) -> bool {
+        !matches!(self, Self::Literal(_))
+    }
+
+    /// Look up the value
+    ///
+    /// # Errors
+    ///
+    /// Fails when the variable is unset, the file cannot be read or the
+    /// keychain has no such entry. Errors name the reference, never a value.
+    pub fn resolve(&self) -> Result<SecretValue> {
+        let value = match self {
+            Self::Literal(value) => value.clone(),
+            Self::Env(name) => std::env::var(name).map_err(|_| anyhow!("Secret variable {name} is not set"))?,
+            Self::File(path) => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read secret file '{}'", path.display()))?;
+                text.trim_end_matches(['\r', '\n']).to_string()
+            }
+            Self::Keychain { service, account } => read_keychain(service, account)?,
+        };
+        Ok(SecretValue(Zeroizing::new(value)))
+    }
+}
+
+/// Read a generic password from the platform keychain
+fn read_keychain(service: &str, account: &str) -> Result<String> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("security");
+        command.args(["find-generic-password", "-s", service, "-a", account, "-w"]);
+        command
+    } else if cfg!(target_os = "linux") {
+        let mut command = Command::new("secret-tool");
+        command.args(["lookup", "service", service, "account", account]);
+        command
+    } else {
+        bail!("Keychain secrets are not supported on this platform");
+    };
+
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to query keychain for {service}/{account}"))?;
+    if !output.status.success() {
+        bail!("Keychain has no entry for {service}/{account}");
+    }
+    let value = Zeroizing::new(output.stdout);
+    let value = std::str::from_utf8(&value)
+        .map_err(|_| anyhow!("Keychain entry {service}/{account} is not UTF-8"))?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::Literal(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self::Literal(value.to_string())
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(_) => f.write_str(REDACTED),
+            Self::Env(name) => write!(f, "env:{name}"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Keychain { service, account } => write!(f, "keychain:{service}/{account}"),
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({self})")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for Secret {
+    fn schema_name() -> Cow<'static, str> {
+        "Secret".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = String::json_schema(generator);
+        schema.insert(
+            "description".into(),
+            "Value, or a reference: env:NAME, file:PATH or keychain:SERVICE/ACCOUNT".into(),
+        );
+        schema
+    }
+}
@@ -0,0 +1,552 @@
+//! Layered configuration for the citescrape server binary
+//!
+//! Settings are resolved in four layers, later layers winning:
+//!
+//! 1. Built-in defaults
+//! 2. A TOML or YAML file (`--config` or `CITESCRAPE_CONFIG`)
+//! 3. `CITESCRAPE_*` environment variables
+//! 4. Command-line flags (applied by the binary)
+//!
+//! ```toml
+//! [server]
+//! http = "127.0.0.1:30439"
+//! tls_cert = "certs/server.pem"   # relative to the file
+//! tls_key = "certs/server.key"
+//! shutdown_timeout_secs = 30
+//!
+//! [pool]
+//! min_size = 2
+//! max_size = 10
+//! headless = true
+//! idle_timeout_secs = 300
+//!
+//! [output]
+//! root = "/var/lib/citescrape"
+//! tracking_params = ["utm_*", "gclid", "ref"]
+//!
+//! [crawl]
+//! max_crawls_per_connection = 4
+//! max_pages_per_crawl = 2000
+//! allowed_domains = ["docs.rs"]
+//! block_private_hosts = true
+//!
+//! [search]
+//! min_interval_ms = 1500
+//! jitter_ms = 1000
+//! max_concurrent = 2
+//!
+//! [logging]
+//! format = "json"                  # "text" (default) or one JSON object per line
+//!
+//! [metrics]
+//! http = "127.0.0.1:9464"          # Prometheus scrape target, off by default
+//!
+//! [api]
+//! http = "127.0.0.1:30440"         # REST endpoints for non-MCP clients, off by default
+//! token = "env:CITESCRAPE_TOKEN"   # `Authorization: Bearer ...`; needed off loopback
+//! grpc = "127.0.0.1:30441"         # gRPC control service, needs the `grpc` feature
+//!
+//! [telemetry]                      # needs the `otel` feature
+//! otlp_endpoint = "http://localhost:4318"
+//! service_name = "citescrape"
+//! ```
+//!
+//! Environment variables: `CITESCRAPE_HTTP`, `CITESCRAPE_TLS_CERT`,
+//! `CITESCRAPE_TLS_KEY`, `CITESCRAPE_SHUTDOWN_TIMEOUT_SECS`,
+//! `CITESCRAPE_POOL_MIN_SIZE`, `CITESCRAPE_POOL_MAX_SIZE`, `CITESCRAPE_HEADLESS`,
+//! `CITESCRAPE_POOL_IDLE_TIMEOUT_SECS`, `CITESCRAPE_OUTPUT_ROOT`,
+//! `CITESCRAPE_TRACKING_PARAMS`, `CITESCRAPE_LOG_FORMAT`, `CITESCRAPE_METRICS_HTTP`,
+//! `CITESCRAPE_API_HTTP`, `CITESCRAPE_API_TOKEN`, `CITESCRAPE_API_GRPC`,
+//! `CITESCRAPE_OTLP_ENDPOINT`, `CITESCRAPE_OTLP_SERVICE_NAME`, the crawl
+//! limits documented in [`crate::mcp::quota`] and the search pacing variables
+//! ([`crate::web_search::PacingConfig`]).
+//!
+//! Pool bounds, crawl limits and search pacing can change while the server
+//! runs (see [`super::reload`]).
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::file::{ConfigFormat, deserialize_keyed};
+use super::secret::Secret;
+use crate::browser_pool::BrowserPoolConfig;
+use crate::mcp::quota::{CrawlQuota, is_truthy, parse_domain_list};
+use crate::web_search::{JITTER_ENV, MAX_CONCURRENT_ENV, MIN_INTERVAL_ENV, PacingConfig};
+
+/// Environment variable naming the server config file
+pub const CONFIG_ENV: &str = "CITESCRAPE_CONFIG";
+
+/// Listener and shutdown settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ListenSettings {
+    /// Address to serve MCP over HTTP(S) on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<SocketAddr>,
+    /// TLS certificate; HTTPS is enabled when set together with `tls_key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+    /// Time allowed for connections and crawls to wind down on shutdown
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for ListenSettings {
+    fn default() -> Self {
+        Self {
+            http: Some(SocketAddr::from((Ipv4Addr::LOCALHOST, kodegen_config::PORT_CITESCRAPE))),
+            tls_cert: None,
+            tls_key: None,
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+/// Browser pool sizing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolSettings {
+    pub min_size: usize,
+    pub max_size: usize,
+    pub headless: bool,
+    /// Idle browsers above `min_size` are closed after this long
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        let pool = BrowserPoolConfig::default();
+        Self {
+            min_size: pool.min_pool_size,
+            max_size: pool.max_pool_size,
+            headless: pool.headless,
+            idle_timeout_secs: pool.idle_timeout.as_secs(),
+        }
+    }
+}
+
+/// Where crawl output goes when a request names no directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageSettings {
+    /// Replaces the per-project `.kodegen/citescrape` default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// Query parameters stripped from URLs before they are stored or matched;
+    /// defaults to
+    /// [`DEFAULT_TRACKING_PARAMS`](crate::link_index::DEFAULT_TRACKING_PARAMS), an empty list keeps them all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_params: Option<Vec<String>>,
+}
+
+/// Limits applied to every crawl (see [`CrawlQuota`])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CrawlLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_crawls_per_connection: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pages_per_crawl: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_domains: Option<Vec<String>>,
+    pub block_private_hosts: bool,
+}
+
+/// Per-engine web search pacing (see [`PacingConfig`])
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchSettings {
+    pub min_interval_ms: u64,
+    pub jitter_ms: u64,
+    pub max_concurrent: usize,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        let pacing = PacingConfig::default();
+        Self {
+            min_interval_ms: u64::try_from(pacing.min_interval.as_millis()).unwrap_or(u64::MAX),
+            jitter_ms: u64::try_from(pacing.jitter.as_millis()).unwrap_or(u64::MAX),
+            max_concurrent: pacing.max_concurrent,
+        }
+    }
+}
+
+/// How log records are written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines (`env_logger`)
+    #[default]
+    Text,
+    /// One JSON object per line, with crawl context fields (see [`crate::logging`])
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => bail!("expected 'text' or 'json', got '{other}'"),
+        }
+    }
+}
+
+/// Log output settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    pub format: LogFormat,
+}
+
+/// Prometheus endpoint (see [`crate::mcp::metrics`])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsSettings {
+    /// Address serving `GET /metrics`; unset disables the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<SocketAddr>,
+}
+
+/// REST and gRPC endpoints next to the MCP server (see [`crate::mcp::rest_api`])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiSettings {
+    /// Address serving `/crawls` and `/search`, shared with `/metrics` when
+    /// `metrics.http` is the same address; unset disables the API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http: Option<SocketAddr>,
+    /// Bearer token clients must send; unset accepts every request, which is
+    /// only allowed on a loopback address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<Secret>,
+    /// Address serving the `citescrape.v1.CrawlControl` gRPC service
+    /// (`proto/citescrape.proto`) under the same token; unset disables it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<SocketAddr>,
+}
+
+/// Export of crawl pipeline traces over OTLP/HTTP (see [`crate::telemetry`])
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    /// Collector base URL (traces go to `/v1/traces` under it); unset disables export
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` reported with every span
+    pub service_name: String,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "citescrape".to_string(),
+        }
+    }
+}
+
+/// Effective settings of the citescrape server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub server: ListenSettings,
+    pub pool: PoolSettings,
+    pub output: StorageSettings,
+    pub crawl: CrawlLimits,
+    pub search: SearchSettings,
+    pub logging: LoggingSettings,
+    pub metrics: MetricsSettings,
+    pub api: ApiSettings,
+    pub telemetry: TelemetrySettings,
+}
+
+impl ServerConfig {
+    /// The config file in use: `path` if given, otherwise `CITESCRAPE_CONFIG`
+    #[must_use]
+    pub fn file_path(path: Option<&Path>) -> Option<PathBuf> {
+        path.map(Path::to_path_buf)
+            .or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from))
+    }
+
+    /// Defaults, then the config file (`path` or `CITESCRAPE_CONFIG`), then the environment
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut config = match Self::file_path(path) {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.apply_env(|name| std::env::var(name).ok())?;
+        Ok(config)
+    }
+
+    /// Parse `text` as `format`; errors name the offending key
+    pub fn parse(text: &str, format: ConfigFormat) -> Result<Self> {
+        deserialize_keyed(text, format)
+    }
+
+    /// Load a TOML or YAML file, resolving relative paths against its directory
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+        let mut config = ConfigFormat::from_path(path)
+            .and_then(|format| Self::parse(&text, format))
+            .with_context(|| format!("Invalid config file '{}'", path.display()))?;
+
+        if let Some(dir) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            for relative in [
+                &mut config.server.tls_cert,
+                &mut config.server.tls_key,
+                &mut config.output.root,
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|p| p.is_relative())
+            {
+                *relative = dir.join(&*relative);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Override settings from `CITESCRAPE_*` variables returned by `lookup`
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        fn parsed<T: FromStr>(name: &str, value: &str) -> Result<T>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value
+                .trim()
+                .parse()
+                .map_err(|e| anyhow!("{name}: invalid value '{value}': {e}"))
+        }
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+
+        if let Some(v) = var("CITESCRAPE_HTTP") {
+            self.server.http = Some(parsed("CITESCRAPE_HTTP", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_TLS_CERT") {
+            self.server.tls_cert = Some(PathBuf::from(v));
+        }
+        if let Some(v) = var("CITESCRAPE_TLS_KEY") {
+            self.server.tls_key = Some(PathBuf::from(v));
+        }
+        if let Some(v) = var("CITESCRAPE_SHUTDOWN_TIMEOUT_SECS") {
+            self.server.shutdown_timeout_secs = parsed("CITESCRAPE_SHUTDOWN_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_POOL_MIN_SIZE") {
+            self.pool.min_size = parsed("CITESCRAPE_POOL_MIN_SIZE", &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_POOL_MAX_SIZE") {
+            self.pool.max_size = parsed("CITESCRAPE_POOL_MAX_SIZE", &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_HEADLESS") {
+            self.pool.headless = is_truthy(&v);
+        }
+        if let Some(v) = var("CITESCRAPE_POOL_IDLE_TIMEOUT_SECS") {
+            self.pool.idle_timeout_secs = parsed("CITESCRAPE_POOL_IDLE_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_OUTPUT_ROOT") {
+            self.output.root = Some(PathBuf::from(v));
+        }
+        if let Some(v) = var("CITESCRAPE_TRACKING_PARAMS") {
+            self.output.tracking_params = Some(
+                v.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect(),
+            );
+        }
+        if let Some(v) = var("CITESCRAPE_MAX_CRAWLS_PER_CONNECTION") {
+            self.crawl.max_crawls_per_connection = Some(parsed("CITESCRAPE_MAX_CRAWLS_PER_CONNECTION", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_MAX_PAGES_PER_CRAWL") {
+            self.crawl.max_pages_per_crawl = Some(parsed("CITESCRAPE_MAX_PAGES_PER_CRAWL", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_ALLOWED_DOMAINS") {
+            self.crawl.allowed_domains = Some(parse_domain_list(&v));
+        }
+        if let Some(v) = var("CITESCRAPE_BLOCK_PRIVATE_HOSTS") {
+            self.crawl.block_private_hosts = is_truthy(&v);
+        }
+        if let Some(v) = var(MIN_INTERVAL_ENV) {
+            self.search.min_interval_ms = parsed(MIN_INTERVAL_ENV, &v)?;
+        }
+        if let Some(v) = var(JITTER_ENV) {
+            self.search.jitter_ms = parsed(JITTER_ENV, &v)?;
+        }
+        if let Some(v) = var(MAX_CONCURRENT_ENV) {
+            self.search.max_concurrent = parsed(MAX_CONCURRENT_ENV, &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_LOG_FORMAT") {
+            self.logging.format = parsed("CITESCRAPE_LOG_FORMAT", &v)?;
+        }
+        if let Some(v) = var("CITESCRAPE_METRICS_HTTP") {
+            self.metrics.http = Some(parsed("CITESCRAPE_METRICS_HTTP", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_API_HTTP") {
+            self.api.http = Some(parsed("CITESCRAPE_API_HTTP", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_API_TOKEN") {
+            self.api.token = Some(Secret::parse(v.trim()).context("CITESCRAPE_API_TOKEN")?);
+        }
+        if let Some(v) = var("CITESCRAPE_API_GRPC") {
+            self.api.grpc = Some(parsed("CITESCRAPE_API_GRPC", &v)?);
+        }
+        if let Some(v) = var("CITESCRAPE_OTLP_ENDPOINT") {
+            self.telemetry.otlp_endpoint = Some(v.trim().to_string());
+        }
+        if let Some(v) = var("CITESCRAPE_OTLP_SERVICE_NAME") {
+            self.telemetry.service_name = v.trim().to_string();
+        }
+        Ok(())
+    }
+
+    /// Check values the types alone do not constrain
+    pub fn validate(&self) -> Result<()> {
+        match (&self.server.tls_cert, &self.server.tls_key) {
+            (Some(_), None) => bail!("server.tls_key: required when server.tls_cert is set"),
+            (None, Some(_)) => bail!("server.tls_cert: required when server.tls_key is set"),
+            _ => {}
+        }
+        if self.server.http.is_some_and(|addr| addr.port() == 0) {
+            bail!("server.http: an explicit port is required (MCP clients cannot follow port 0)");
+        }
+        if self.pool.max_size == 0 {
+            bail!("pool.max_size: must be at least 1");
+        }
+        if self.pool.min_size > self.pool.max_size {
+            bail!(
+                "pool.min_size: {} exceeds pool.max_size {}",
+                self.pool.min_size,
+                self.pool.max_size
+            );
+        }
+        if self.crawl.max_crawls_per_connection == Some(0) {
+            bail!("crawl.max_crawls_per_connection: must be at least 1");
+        }
+        if self.crawl.max_pages_per_crawl == Some(0) {
+            bail!("crawl.max_pages_per_crawl: must be at least 1");
+        }
+        if self.search.max_concurrent == 0 {
+            bail!("search.max_concurrent: must be at least 1");
+        }
+        if let (Some(metrics), Some(http)) = (self.metrics.http, self.server.http)
+            && same_listener(metrics, http)
+        {
+            bail!("metrics.http: {metrics} is already used by server.http");
+        }
+        if let Some(api) = self.api.http {
+            if self.server.http.is_some_and(|http| same_listener(api, http)) {
+                bail!("api.http: {api} is already used by server.http");
+            }
+            // The same address serves both; overlapping ones cannot be bound twice
+            if self
+                .metrics
+                .http
+                .is_some_and(|metrics| metrics != api && same_listener(api, metrics))
+            {
+                bail!("api.http: {api} is already used by metrics.http");
+            }
+            if self.api.token.is_none() && !api.ip().is_loopback() {
+                bail!("api.token: required when api.http ({api}) is not a loopback address");
+            }
+        }
+        if let Some(grpc) = self.api.grpc {
+            let taken = [
+                ("server.http", self.server.http),
+                ("metrics.http", self.metrics.http),
+                ("api.http", self.api.http),
+            ];
+            if let Some((name, _)) = taken
+                .iter()
+                .find(|(_, addr)| addr.is_some_and(|addr| same_listener(grpc, addr)))
+            {
+                bail!("api.grpc: {grpc} is already used by {name}");
+            }
+            if self.api.token.is_none() && !grpc.ip().is_loopback() {
+                bail!("api.token: required when api.grpc ({grpc}) is not a loopback address");
+            }
+            if !cfg!(feature = "grpc") {
+                bail!("api.grpc: this build has no gRPC support (enable the `grpc` feature)");
+            }
+        }
+        if let Some(endpoint) = &self.telemetry.otlp_endpoint {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                bail!("telemetry.otlp_endpoint: expected an http(s) URL, got '{endpoint}'");
+            }
+            if !cfg!(feature = "otel") {
+                bail!("telemetry.otlp_endpoint: this build has no OTLP support (enable the `otel` feature)");
+            }
+        }
+        Ok(())
+    }
+
+    /// The effective configuration as TOML, for `--print-config`
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Failed to serialize server config")
+    }
+
+    /// Address to listen on
+    pub fn http_address(&self) -> Result<SocketAddr> {
+        self.server
+            .http
+            .ok_or_else(|| anyhow!("No listen address: set --http, CITESCRAPE_HTTP or server.http"))
+    }
+
+    /// Certificate and key paths when HTTPS is enabled
+    #[must_use]
+    pub fn tls_paths(&self) -> Option<(PathBuf, PathBuf)> {
+        Some((self.server.tls_cert.clone()?, self.server.tls_key.clone()?))
+    }
+
+    #[must_use]
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.server.shutdown_timeout_secs)
+    }
+
+    /// Browser pool settings, keeping the pool defaults for values not exposed here
+    #[must_use]
+    pub fn pool_config(&self) -> BrowserPoolConfig {
+        BrowserPoolConfig {
+            min_pool_size: self.pool.min_size,
+            max_pool_size: self.pool.max_size,
+            headless: self.pool.headless,
+            idle_timeout: Duration::from_secs(self.pool.idle_timeout_secs),
+            ..BrowserPoolConfig::default()
+        }
+    }
+
+    #[must_use]
+    pub fn crawl_quota(&self) -> CrawlQuota {
+        CrawlQuota {
+            max_concurrent_crawls: self.crawl.max_crawls_per_connection,
+            max_pages_per_crawl: self.crawl.max_pages_per_crawl,
+            allowed_domains: self
+                .crawl
+                .allowed_domains
+                .as_ref()
+                .map(|domains| parse_domain_list(&domains.join(","))),
+            block_private_hosts: self.crawl.block_private_hosts,
+        }
+    }
+
+    #[must_use]
+    pub fn pacing_config(&self) -> PacingConfig {
+        PacingConfig {
+            min_interval: Duration::from_millis(self.search.min_interval_ms),
+            jitter: Duration::from_millis(self.search.jitter_ms),
+            max_concurrent: self.search.max_concurrent,
+        }
+    }
+}
+
+/// Whether listeners on `a` and `b` would collide
+fn same_listener(a: SocketAddr, b: SocketAddr) -> bool {
+    a.port() == b.port() && (a.ip() == b.ip() || a.ip().is_unspecified() || b.ip().is_unspecified())
+}
@@ -3,10 +3,29 @@
 //! This module contains the main `CrawlConfig` struct and its associated types
 //! that define the configuration parameters for web crawling operations.
 
+use regex::RegexSet;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::secret::Secret;
+use crate::content_saver::markdown_converter::{ChromeFilterLevel, DEFAULT_MIN_QUALITY_SCORE, ExtractionBackend, Typography};
+use crate::imurl::UrlMatcher;
+use crate::page_extractor::structured::{CompiledExtraction, PageSchema};
+
+/// Which hosts a crawl follows links to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlScope {
+    /// Only the start URL's host
+    #[default]
+    SameHost,
+    /// Any host sharing the start URL's registrable domain (eTLD+1), so a crawl
+    /// of `www.example.co.uk` also follows `docs.example.co.uk`
+    SameRegistrableDomain,
+}
+
 /// Main configuration struct for web crawling operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrawlConfig {
@@ -33,10 +52,10 @@ pub struct CrawlConfig {
     pub(crate) allowed_domains: Option<Vec<String>>,
     pub(crate) excluded_patterns: Option<Vec<String>>,
 
-    /// Compiled regex patterns from `excluded_patterns`
+    /// Matchers compiled from `excluded_patterns`
     /// Pre-compiled at config creation to avoid hot-path regex compilation
     #[serde(skip)]
-    pub(crate) excluded_patterns_compiled: Vec<regex::Regex>,
+    pub(crate) excluded_patterns_compiled: Vec<UrlMatcher>,
 
     pub(crate) generate_components: bool,
     pub(crate) progressive: bool,
@@ -118,6 +137,31 @@ pub struct CrawlConfig {
     /// Default: 300 seconds (5 minutes)
     pub(crate) circuit_breaker_retry_delay_secs: u64,
 
+    /// Re-attempt pages that failed with retryable errors in a final pass
+    ///
+    /// Pages whose per-page retries are exhausted by transient failures
+    /// (timeouts, network errors, 5xx responses) are collected and tried once
+    /// more after the main crawl, using the `retry_pass_*` profile.
+    ///
+    /// Default: true
+    pub(crate) retry_pass_enabled: bool,
+
+    /// Factor applied to page load and navigation timeouts in the retry pass
+    ///
+    /// Default: 2.0
+    pub(crate) retry_pass_timeout_multiplier: f64,
+
+    /// Headless mode for the retry pass browser
+    ///
+    /// `Some(false)` retries in a headed browser, which some bot checks treat
+    /// more leniently. None keeps the main crawl's setting.
+    pub(crate) retry_pass_headless: Option<bool>,
+
+    /// Proxy server (`--proxy-server` value) for the retry pass browser
+    ///
+    /// None retries without a proxy.
+    pub(crate) retry_pass_proxy: Option<String>,
+
     /// Optional event bus for publishing crawl events
     ///
     /// When set, the crawler will publish `CrawlEvent` updates to this bus.
@@ -151,6 +195,10 @@ pub struct CrawlConfig {
     #[serde(skip)]
     pub(crate) browser_pool: Option<Arc<crate::browser_pool::BrowserPool>>,
 
+    /// Optional cancellation/pause handle shared with the crawl's owner
+    #[serde(skip)]
+    pub(crate) crawl_control: Option<crate::crawl_engine::CrawlControl>,
+
     /// Enable gzip compression for saved files (markdown, html, json, screenshots)
     /// When true, files are saved with .gz extension and compressed
     /// When false (default), files are saved uncompressed for easier inspection
@@ -191,6 +239,248 @@ pub struct CrawlConfig {
     ///
     /// Default: 3
     pub(crate) max_page_retries: Option<u8>,
+
+    /// CSS selector that must match an element before the page is considered ready
+    ///
+    /// Useful for SPAs that render their main content after `load` fires.
+    /// When the selector never appears, extraction proceeds after
+    /// `navigation_timeout_secs` with a warning.
+    ///
+    /// Default: None
+    pub(crate) wait_for_selector: Option<String>,
+
+    /// Quiet period in milliseconds with no new network requests before the page is ready
+    ///
+    /// Measured via the Resource Timing API: the page is idle once no new
+    /// resource entries have been recorded for this many milliseconds.
+    ///
+    /// Default: None (no network idle wait)
+    pub(crate) wait_for_network_idle_ms: Option<u64>,
+
+    /// JavaScript function or expression that must return a truthy value before the page is ready
+    ///
+    /// Accepts either an expression (`window.appReady === true`) or a function
+    /// (`() => document.querySelectorAll('.item').length > 10`). Promises are awaited.
+    ///
+    /// Default: None
+    pub(crate) wait_for_function: Option<String>,
+
+    /// Remove cookie consent banners and paywall overlays before extraction
+    ///
+    /// Removes consent containers, fixed modals and backdrops from the DOM so
+    /// they do not end up in saved pages. Nothing on the page is clicked.
+    ///
+    /// Default: true
+    #[serde(default = "default_dismiss_overlays")]
+    pub(crate) dismiss_overlays: bool,
+
+    /// Click "reject" on consent dialogs before removing them
+    ///
+    /// Only reject buttons of common consent managers such as OneTrust and
+    /// Cookiebot, or buttons labelled "reject"/"decline" inside a consent
+    /// dialog, are clicked; consent is never accepted and links are never
+    /// followed. Only applies when `dismiss_overlays` is enabled.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub(crate) reject_consent: bool,
+
+    /// Download page assets into the mirror and point saved HTML at the local copies
+    ///
+    /// Covers `img[src]`, `source[src]`, `script[src]`, `link[href]` (stylesheets,
+    /// icons, preloads) and every `srcset` candidate on `<img>` and
+    /// `<picture><source>` so saved pages render offline. Resources already inlined as
+    /// data URIs are left alone. Only applies when `save_raw_html` is enabled.
+    ///
+    /// Default: false
+    pub(crate) mirror_assets: bool,
+
+    /// Append every crawl event to `.citescrape/events.jsonl` in the output directory
+    ///
+    /// The journal is rotated by size and kept across crawls, so failed or
+    /// interrupted crawls can be examined afterwards (see
+    /// [`crate::crawl_events::journal`]).
+    ///
+    /// Default: true
+    pub(crate) event_journal: bool,
+
+    /// Write `sitemap.xml` for the saved pages when the crawl finishes
+    ///
+    /// Default: true
+    pub(crate) sitemap: bool,
+
+    /// Base URL the mirror is re-hosted at, used for sitemap `<loc>` entries
+    ///
+    /// With a base, every page is listed at the URL its mirror directory is
+    /// served from (`{base}/{host}/{path}/`) and sitemap shards are referenced
+    /// by absolute URL. Without one, pages keep their crawled URLs and crawls
+    /// above 50,000 pages get no sitemap, since a sitemap index can only
+    /// reference its shards by absolute URL.
+    ///
+    /// Default: None
+    pub(crate) sitemap_base_url: Option<String>,
+
+    /// Object storage location the crawl output is persisted to
+    ///
+    /// `None` keeps the output on the local filesystem only. An `s3://` or
+    /// `gs://` URL (requires the `object-store` feature) uploads every saved
+    /// file to the bucket under the same relative path, so crawls on
+    /// ephemeral workers outlive the worker's disk.
+    ///
+    /// Default: None
+    pub(crate) output_url: Option<String>,
+
+    /// Main-content extractor used when converting pages to markdown
+    ///
+    /// `Heuristic` converts the whole page and filters boilerplate by tag and
+    /// class name; `Readability` converts the article picked by Readability
+    /// scoring; `Compare` runs both and keeps the one with more text. The
+    /// extractor used is recorded in each page's JSON metadata.
+    ///
+    /// Default: `ExtractionBackend::Heuristic`
+    #[serde(default)]
+    pub(crate) extraction_backend: ExtractionBackend,
+
+    /// Extraction quality score below which a page is converted again
+    /// without filtering page chrome
+    ///
+    /// Scores run from 0 to 1 (text kept relative to the page, link density,
+    /// boilerplate). Low-scoring pages are flagged in their JSON metadata
+    /// whether or not the unfiltered retry was kept. 0 disables scoring.
+    ///
+    /// Default: `DEFAULT_MIN_QUALITY_SCORE` (0.3)
+    #[serde(default = "default_min_extraction_quality")]
+    pub(crate) min_extraction_quality: f64,
+
+    /// Regex patterns of HTML comments kept in the markdown
+    ///
+    /// Comments are dropped by default; those whose trimmed text matches any
+    /// pattern (e.g. `^docs-build:` markers or `TODO` annotations) are kept
+    /// as `<!-- ... -->` markdown comments.
+    ///
+    /// Default: empty
+    #[serde(default)]
+    pub(crate) keep_comment_patterns: Vec<String>,
+
+    /// `keep_comment_patterns` compiled at config creation
+    #[serde(skip)]
+    pub(crate) keep_comments_compiled: Option<RegexSet>,
+
+    /// How aggressively navigation, headers, footers and asides are filtered
+    ///
+    /// `lenient` converts asides, headers and footers like any block (for
+    /// themes that keep real content there); `strict` also drops navigation,
+    /// sticky bars and landmark-role chrome.
+    ///
+    /// Default: `ChromeFilterLevel::Standard`
+    #[serde(default)]
+    pub(crate) chrome_filter_level: ChromeFilterLevel,
+
+    /// Character normalizations applied to the prose of saved markdown
+    /// (punctuation to ASCII, numeric entity decoding, emoji removal)
+    ///
+    /// Default: none
+    #[serde(default)]
+    pub(crate) typography: Typography,
+
+    /// Regex patterns of `class`/`id` values whose elements are always
+    /// converted, whatever the filter level
+    ///
+    /// Default: empty
+    #[serde(default)]
+    pub(crate) keep_chrome_patterns: Vec<String>,
+
+    /// Regex patterns of `class`/`id` values whose elements are always
+    /// dropped
+    ///
+    /// Default: empty
+    #[serde(default)]
+    pub(crate) drop_chrome_patterns: Vec<String>,
+
+    /// `keep_chrome_patterns` compiled at config creation
+    #[serde(skip)]
+    pub(crate) keep_chrome_compiled: Option<RegexSet>,
+
+    /// `drop_chrome_patterns` compiled at config creation
+    #[serde(skip)]
+    pub(crate) drop_chrome_compiled: Option<RegexSet>,
+
+    /// Structured extraction schemas attached to URL patterns
+    ///
+    /// Pages matching a schema's `url_pattern` (the first matching schema
+    /// wins) get the extracted record, or records with `item_selector`,
+    /// written to `data.json` next to `index.md`.
+    ///
+    /// Default: empty
+    #[serde(default)]
+    pub(crate) extraction_schemas: Vec<PageSchema>,
+
+    /// `extraction_schemas` compiled once for every page they apply to:
+    /// the `url_pattern` matcher and the prepared extraction
+    #[serde(skip)]
+    pub(crate) extraction_schemas_compiled: Vec<(UrlMatcher, CompiledExtraction)>,
+
+    /// Database URL of a shared link index backend
+    ///
+    /// `None` keeps the per-output-directory SQLite index. A `postgres://` URL
+    /// stores the link graph in Postgres (requires the `postgres` feature) so
+    /// crawlers on several machines share one graph.
+    ///
+    /// Default: None
+    pub(crate) link_index_url: Option<String>,
+
+    /// Window for batching retroactive link rewrites, in milliseconds
+    ///
+    /// Pages linking to newly saved pages are rewritten at most once per window,
+    /// so index and navigation pages are not rewritten for every new page.
+    /// 0 rewrites them immediately.
+    ///
+    /// Default: 500
+    pub(crate) link_rewrite_window_ms: u64,
+
+    /// Extra URLs queued at depth 0 alongside `start_url`
+    ///
+    /// Lets a crawl start from a known set of pages (feed entries, sitemap
+    /// URLs) instead of discovering them by following links.
+    ///
+    /// Default: empty
+    #[serde(default)]
+    pub(crate) seed_urls: Vec<String>,
+
+    /// Which hosts links are followed to
+    ///
+    /// The start URL's path scope applies to the start host only; other hosts
+    /// in the registrable domain are followed from their root.
+    ///
+    /// Default: `CrawlScope::SameHost`
+    #[serde(default)]
+    pub(crate) scope: CrawlScope,
+
+    /// Extra HTTP headers sent with every page request
+    ///
+    /// Set through CDP before navigation. Not serialized, since headers often
+    /// carry API tokens.
+    ///
+    /// Default: empty
+    #[serde(default, skip_serializing)]
+    pub(crate) headers: HashMap<String, Secret>,
+
+    /// Cookies set on every page before navigation
+    ///
+    /// Includes cookies loaded from the builder's cookie file. Not serialized,
+    /// since cookies often carry session credentials.
+    ///
+    /// Default: empty
+    #[serde(default, skip_serializing)]
+    pub(crate) cookies: Vec<super::cookies::Cookie>,
+}
+
+fn default_dismiss_overlays() -> bool {
+    true
+}
+
+fn default_min_extraction_quality() -> f64 {
+    DEFAULT_MIN_QUALITY_SCORE
 }
 
 impl Default for CrawlConfig {
@@ -233,15 +523,48 @@ impl Default for CrawlConfig {
             circuit_breaker_enabled: true,
             circuit_breaker_failure_threshold: 5,
             circuit_breaker_retry_delay_secs: 300,
+            retry_pass_enabled: true,
+            retry_pass_timeout_multiplier: 2.0,
+            retry_pass_headless: None,
+            retry_pass_proxy: None,
             event_bus: None,
             indexing_sender: None,
             max_concurrent_pages: Some(10),
             max_concurrent_per_domain: Some(2),
             chrome_data_dir: None,
             browser_pool: None,
+            crawl_control: None,
             compress_output: false, // Default to uncompressed for easier inspection
             compression_threshold_bytes: Some(1_048_576), // 1MB default
             max_page_retries: Some(3),
+            wait_for_selector: None,
+            wait_for_network_idle_ms: None,
+            wait_for_function: None,
+            dismiss_overlays: true,
+            reject_consent: false,
+            mirror_assets: false,
+            event_journal: true,
+            sitemap: true,
+            sitemap_base_url: None,
+            output_url: None,
+            extraction_backend: ExtractionBackend::default(),
+            min_extraction_quality: DEFAULT_MIN_QUALITY_SCORE,
+            keep_comment_patterns: Vec::new(),
+            keep_comments_compiled: None,
+            chrome_filter_level: ChromeFilterLevel::default(),
+            typography: Typography::default(),
+            keep_chrome_patterns: Vec::new(),
+            drop_chrome_patterns: Vec::new(),
+            keep_chrome_compiled: None,
+            drop_chrome_compiled: None,
+            extraction_schemas: Vec::new(),
+            extraction_schemas_compiled: Vec::new(),
+            link_index_url: None,
+            link_rewrite_window_ms: 500,
+            seed_urls: Vec::new(),
+            scope: CrawlScope::default(),
+            headers: HashMap::new(),
+            cookies: Vec::new(),
         }
     }
 }
@@ -339,7 +662,7 @@ impl CrawlConfig {
     /// These patterns are compiled once at config creation time
     /// to avoid repeated regex compilation in the hot path.
     #[must_use]
-    pub fn excluded_patterns_compiled(&self) -> &[regex::Regex] {
+    pub fn excluded_patterns_compiled(&self) -> &[UrlMatcher] {
         &self.excluded_patterns_compiled
     }
 
@@ -382,4 +705,21 @@ impl CrawlConfig {
     pub fn browser_pool(&self) -> Option<&Arc<crate::browser_pool::BrowserPool>> {
         self.browser_pool.as_ref()
     }
+
+    /// Attach a control handle for cancelling or pausing the crawl
+    ///
+    /// Keep a clone of the handle: `cancel()` aborts in-flight pages and ends
+    /// the crawl after flushing partial state, `pause()` / `resume()` stop and
+    /// restart scheduling of new pages.
+    #[must_use]
+    pub fn with_crawl_control(mut self, control: crate::crawl_engine::CrawlControl) -> Self {
+        self.crawl_control = Some(control);
+        self
+    }
+
+    /// Get the crawl control handle if configured
+    #[must_use]
+    pub fn crawl_control(&self) -> Option<&crate::crawl_engine::CrawlControl> {
+        self.crawl_control.as_ref()
+    }
 }
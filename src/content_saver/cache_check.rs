@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use chromiumoxide::cdp::browser_protocol::network::{
     EventResponseReceived, Headers, ResourceType,
 };
@@ -11,7 +12,7 @@ use std::time::Duration;
 use tokio::time::timeout;
 use url::Url;
 
-use crate::content_saver::CacheMetadata;
+use crate::content_saver::{CacheMetadata, ResponseValidators};
 
 /// Timeout for blocking I/O operations (file open, gzip decompression, JSON parsing)
 /// 
@@ -24,17 +25,7 @@ const BLOCKING_DECOMPRESSION_TIMEOUT: Duration = Duration::from_secs(15);
 /// Get the mirror path for a URL synchronously (internal helper for cache checking)
 pub fn get_mirror_path_sync(url: &str, output_dir: &Path, filename: &str) -> Result<PathBuf> {
     let url = Url::parse(url).context("Failed to parse URL")?;
-    let domain = url
-        .host_str()
-        .ok_or_else(|| anyhow::anyhow!("Invalid URL: no host"))?;
-    let path = if url.path() == "/" {
-        PathBuf::new()
-    } else {
-        PathBuf::from(url.path().trim_start_matches('/'))
-    };
-
-    let mirror_path = output_dir.join(domain).join(path).join(filename);
-    Ok(mirror_path)
+    Ok(output_dir.join(crate::utils::url_utils::mirror_relative_path(&url)?).join(filename))
 }
 
 /// Read the cached etag from a gzip file's header comment
@@ -42,6 +33,11 @@ pub fn get_mirror_path_sync(url: &str, output_dir: &Path, filename: &str) -> Res
 /// Looks for the expected cache file path based on URL and reads
 /// the etag stored in the gzip header comment metadata.
 pub async fn read_cached_etag(url: &str, output_dir: &Path) -> Result<Option<String>> {
+    Ok(read_cached_metadata(url, output_dir).await?.map(|metadata| metadata.etag))
+}
+
+/// Read the cache metadata stored in a gzip file's header comment
+pub async fn read_cached_metadata(url: &str, output_dir: &Path) -> Result<Option<CacheMetadata>> {
     // Get expected cache path (this is pure computation, can stay sync)
     let cache_path = get_mirror_path_sync(url, output_dir, "index.md")?;
     let gz_path = cache_path.with_extension("md.gz");
@@ -51,7 +47,7 @@ pub async fn read_cached_etag(url: &str, output_dir: &Path) -> Result<Option<Str
 
     // Spawn blocking for file I/O + decompression + parsing
     // Moves work to dedicated blocking thread pool (max 512 threads)
-    let blocking_task = tokio::task::spawn_blocking(move || -> Result<Option<String>> {
+    let blocking_task = tokio::task::spawn_blocking(move || -> Result<Option<CacheMetadata>> {
         // Attempt to open file - handle NotFound gracefully
         let file = match File::open(&gz_path) {
             Ok(f) => f,
@@ -80,7 +76,7 @@ pub async fn read_cached_etag(url: &str, output_dir: &Path) -> Result<Option<Str
         let metadata: CacheMetadata =
             serde_json::from_str(comment).context("Failed to parse cache metadata JSON")?;
 
-        Ok(Some(metadata.etag))
+        Ok(Some(metadata))
     });
 
     match tokio::time::timeout(BLOCKING_DECOMPRESSION_TIMEOUT, blocking_task).await {
@@ -114,6 +110,45 @@ pub fn extract_etag_from_headers(headers: &Headers) -> Option<String> {
     })
 }
 
+/// Find a header value by name, case-insensitively (HTTP/1.1 responses
+/// keep the server's casing)
+fn header_value<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+    headers
+        .inner()
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))?
+        .1
+        .as_str()
+}
+
+/// Parse an HTTP date header, `None` if missing or not a valid HTTP date
+fn header_date(headers: &Headers, name: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(header_value(headers, name)?.trim())
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Extract the `ETag`, `Last-Modified` and `Date` headers from Network Response headers
+#[must_use]
+pub fn extract_validators_from_headers(headers: &Headers) -> ResponseValidators {
+    ResponseValidators {
+        etag: header_value(headers, "etag")
+            .map(|etag| etag.strip_prefix("W/").unwrap_or(etag).to_string()),
+        last_modified: header_date(headers, "last-modified"),
+        date: header_date(headers, "date"),
+    }
+}
+
+/// Outcome of checking a navigation response against the cached copy
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheCheck {
+    /// The cached copy can be reused
+    pub hit: bool,
+    /// Validators of the matched response
+    pub response: ResponseValidators,
+}
+
 /// Normalize a URL for cache matching comparison.
 ///
 /// Handles common URL variations that represent the same resource:
@@ -136,11 +171,12 @@ pub(crate) fn normalize_url_for_cache_matching(url_str: &str) -> Option<String>
     Some(format!("{}://{}{}", scheme, host, normalized_path))
 }
 
-/// Async helper: Check if received etag matches expected etag
+/// Async helper: Check the received response against the cached copy
 ///
 /// Listens to responseReceived events and matches the Document response for the
-/// specified URL (not the first Document encountered). Returns true if etag matches
-/// (cache hit), false otherwise.
+/// specified URL (not the first Document encountered). It is a cache hit only if
+/// the response carries a validator matching the cached copy, and the copy is
+/// still fresh (see [`CacheMetadata::validated_by`]).
 ///
 /// Handles multiple Document resources correctly (iframes, embedded frames) by matching
 /// the response URL against the target URL after normalization.
@@ -148,24 +184,24 @@ pub(crate) fn normalize_url_for_cache_matching(url_str: &str) -> Option<String>
 /// # Arguments
 /// * `events` - Event stream of network responses from CDP
 /// * `url` - The URL to match against response events (used for matching, not ignored)
-/// * `expected_etag` - The etag from cached file to compare
+/// * `cached` - Metadata of the cached file to compare
 /// * `timeout_duration` - How long to wait for response (configurable per crawl)
 ///
 /// # Returns
-/// * `true` - ETag matches (cache hit)
-/// * `false` - ETag mismatch, no matching document found, or timeout
+/// * `hit: true` - ETag or Last-Modified matches and the cached copy is fresh
+/// * `hit: false` - No matching validator, stale copy, no matching document found, or timeout
 pub async fn check_etag_from_events(
     events: &mut EventStream<EventResponseReceived>,
     url: &str,  // Now actively used!
-    expected_etag: &str,
+    cached: &CacheMetadata,
     timeout_duration: Duration,
-) -> bool {
+) -> CacheCheck {
     // Normalize target URL once (efficient)
     let target_url_normalized = match normalize_url_for_cache_matching(url) {
         Some(normalized) => normalized,
         None => {
             log::warn!("Failed to normalize target URL for cache check: {}", url);
-            return false;
+            return CacheCheck::default();
         }
     };
     
@@ -196,22 +232,16 @@ pub async fn check_etag_from_events(
             
             // Match by normalized URL (not by "first Document")
             if response_url_normalized == target_url_normalized {
-                if let Some(received_etag) = extract_etag_from_headers(&event.response.headers) {
-                    log::debug!(
-                        "Cache check: URL match found (document #{}) - etag comparison: {} vs {}",
-                        document_count,
-                        received_etag,
-                        expected_etag
-                    );
-                    return received_etag == expected_etag;
-                }
-                
-                // Matched URL but no etag header
+                let response = extract_validators_from_headers(&event.response.headers);
+                let hit = cached.validated_by(&response, Utc::now());
                 log::debug!(
-                    "Cache check: URL match found but no etag header for {}",
-                    response_url
+                    "Cache check: URL match found (document #{}) - etag {:?}, last-modified {:?} (hit: {})",
+                    document_count,
+                    response.etag,
+                    response.last_modified,
+                    hit
                 );
-                return false;
+                return CacheCheck { hit, response };
             }
         }
         
@@ -225,11 +255,11 @@ pub async fn check_etag_from_events(
             );
         }
         
-        false
+        CacheCheck::default()
     })
     .await;
 
-    result.unwrap_or(false)
+    result.unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -299,6 +329,22 @@ mod tests {
         assert_eq!(result, Some("https://example.com/".to_string()));
     }
 
+    #[test]
+    fn extract_validators_matches_headers_case_insensitively() {
+        let headers = Headers::new(serde_json::json!({
+            "Date": "Sat, 01 Mar 2025 12:00:00 GMT",
+            "ETag": "W/\"abc\"",
+            "Last-Modified": "Fri, 28 Feb 2025 12:00:00 GMT",
+        }));
+        let validators = extract_validators_from_headers(&headers);
+        assert_eq!(validators.date.map(|d| d.timestamp()), Some(1_740_830_400));
+        assert_eq!(validators.last_modified.map(|d| d.timestamp()), Some(1_740_744_000));
+        assert_eq!(validators.etag.as_deref(), Some("\"abc\""));
+
+        let invalid = Headers::new(serde_json::json!({"date": "yesterday"}));
+        assert_eq!(extract_validators_from_headers(&invalid), ResponseValidators::default());
+    }
+
     #[test]
     fn normalize_url_complex_case() {
         // Complex URL with everything
@@ -5,9 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::Path;
 use std::time::Duration as StdDuration;
-use tempfile::NamedTempFile;
 use tokio::time::timeout;
 
+use super::sink::output_sink_for;
+
 /// Timeout for blocking compression operations
 /// Large files (>1MB) are compressed on blocking thread pool
 const BLOCKING_COMPRESSION_TIMEOUT: StdDuration = StdDuration::from_secs(30);
@@ -24,6 +25,27 @@ const MAX_CONTENT_TYPE_LEN: usize = 512;
 /// Gzip comment field maximum size per RFC 1952
 const MAX_METADATA_JSON_LEN: usize = 60_000;
 
+/// How long saved content stays fresh: 7 days
+const DEFAULT_MAX_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+fn default_max_age_secs() -> u64 {
+    DEFAULT_MAX_AGE_SECS
+}
+
+/// Clock skew tolerated when measuring the age of cached content
+const CLOCK_SKEW_TOLERANCE: StdDuration = StdDuration::from_secs(5 * 60);
+
+/// Validator headers of a page's HTTP response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseValidators {
+    /// `ETag` header, without the weak `W/` prefix
+    pub etag: Option<String>,
+    /// `Last-Modified` header
+    pub last_modified: Option<DateTime<Utc>>,
+    /// `Date` header
+    pub date: Option<DateTime<Utc>>,
+}
+
 /// Sanitize content_type header to prevent oversized metadata
 ///
 /// Extracts the essential parts of a Content-Type header:
@@ -68,14 +90,78 @@ fn sanitize_content_type(raw: &str) -> String {
 
 /// All compression + file I/O uses `spawn_blocking` to prevent blocking the async runtime
 /// Metadata stored in compressed files for caching
+///
+/// Besides the content hash, the validators (`ETag`, `Last-Modified`) and
+/// `Date` of the response the content came from are recorded. A cached copy
+/// is only reused when a new response carries a matching validator, see
+/// [`Self::validated_by`]; freshness never produces a hit on its own.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheMetadata {
+    /// Hash of the saved content
     pub etag: String,
+    /// Local save time plus `max_age_secs`; informational only, see [`Self::is_fresh`]
     #[serde(with = "chrono::serde::ts_seconds")]
     pub expires: DateTime<Utc>,
+    /// Local wall-clock time the content was saved
     #[serde(with = "chrono::serde::ts_seconds")]
     pub last_modified: DateTime<Utc>,
     pub content_type: String,
+    /// How long the content stays fresh after it was fetched
+    #[serde(default = "default_max_age_secs")]
+    pub max_age_secs: u64,
+    /// `Date` header of the response the content came from, if it had one
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub server_date: Option<DateTime<Utc>>,
+    /// `ETag` header of the response the content came from, if it had one
+    #[serde(default)]
+    pub server_etag: Option<String>,
+    /// `Last-Modified` header of the response the content came from, if it had one
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub server_last_modified: Option<DateTime<Utc>>,
+}
+
+impl CacheMetadata {
+    /// Age of the content at local time `now`
+    ///
+    /// With `response_date` (the `Date` of a new response from the same
+    /// server) and a recorded `server_date`, both ends of the interval come
+    /// from the server's clock. Otherwise the local clock is used, and a
+    /// clock set back past the save time counts as age zero rather than
+    /// negative.
+    #[must_use]
+    pub fn age(&self, now: DateTime<Utc>, response_date: Option<DateTime<Utc>>) -> StdDuration {
+        let (saved, current) = match (self.server_date, response_date) {
+            (Some(saved), Some(current)) => (saved, current),
+            _ => (self.last_modified, now),
+        };
+        (current - saved).to_std().unwrap_or_default()
+    }
+
+    /// Whether the content is within its freshness lifetime (see [`Self::age`]),
+    /// allowing a few minutes of clock skew
+    #[must_use]
+    pub fn is_fresh(&self, now: DateTime<Utc>, response_date: Option<DateTime<Utc>>) -> bool {
+        let max_age = StdDuration::from_secs(self.max_age_secs).saturating_add(CLOCK_SKEW_TOLERANCE);
+        self.age(now, response_date) <= max_age
+    }
+
+    /// Whether a new response shows the cached copy can be reused
+    ///
+    /// The response must match a recorded validator: its `ETag`, or when it
+    /// has none, a `Last-Modified` no later than the recorded one. A response
+    /// without validators never matches. The copy must also still be fresh.
+    #[must_use]
+    pub fn validated_by(&self, response: &ResponseValidators, now: DateTime<Utc>) -> bool {
+        let matched = if let Some(etag) = response.etag.as_deref() {
+            etag == self.server_etag.as_deref().unwrap_or(&self.etag)
+        } else {
+            matches!(
+                (response.last_modified, self.server_last_modified),
+                (Some(current), Some(saved)) if current <= saved
+            )
+        };
+        matched && self.is_fresh(now, response.date)
+    }
 }
 
 /// Save content as a file with optional compression and cache metadata
@@ -89,6 +175,7 @@ pub struct CacheMetadata {
 /// * `content` - Raw content bytes to save
 /// * `path` - Target file path (extension will be modified if compressing)
 /// * `content_type` - MIME type for cache metadata
+/// * `response` - Validators and `Date` of the response the content came from
 /// * `compress` - Whether to gzip compress the content
 /// * `compression_threshold` - Size threshold in bytes for using spawn_blocking (default: 1MB)
 ///
@@ -101,6 +188,7 @@ pub async fn save_compressed_file(
     content: Vec<u8>,
     path: &Path,
     content_type: &str,
+    response: ResponseValidators,
     compress: bool,
     _compression_threshold: usize,
 ) -> Result<(std::path::PathBuf, CacheMetadata)> {
@@ -114,15 +202,20 @@ pub async fn save_compressed_file(
     let hash = xxhash_rust::xxh3::xxh3_64(&content);
     let etag = format!("\"{hash:x}\"");
 
-    // Set cache control headers (unchanged)
+    // Set cache control headers
     let now = Utc::now();
-    let expires = now + Duration::seconds(7 * 24 * 60 * 60);
+    let max_age_secs = DEFAULT_MAX_AGE_SECS;
+    let expires = now + Duration::seconds(max_age_secs as i64);
 
     let metadata = CacheMetadata {
         etag,
         expires,
         last_modified: now,
         content_type,
+        max_age_secs,
+        server_date: response.date,
+        server_etag: response.etag,
+        server_last_modified: response.last_modified,
     };
 
     if compress {
@@ -132,11 +225,6 @@ pub async fn save_compressed_file(
             path.extension().unwrap_or_default().to_str().unwrap_or("")
         ));
 
-        // Get parent directory for atomic temp file creation
-        let parent_dir = gz_path
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("Path has no parent directory"))?;
-
         let metadata_json = serde_json::to_string(&metadata)?;
 
         // SAFETY: This should never fail now that content_type is sanitized
@@ -155,62 +243,43 @@ pub async fn save_compressed_file(
             .ok_or_else(|| anyhow::anyhow!("Invalid filename encoding"))?
             .to_string();
 
-        let gz_path_clone = gz_path.clone();
-            let parent_dir_clone = parent_dir.to_path_buf();
-            let filename_clone = filename_str.clone();
-            let metadata_json_clone = metadata_json.clone();
-            
-            // Save values for error logging (needed after closure consumes originals)
-            let gz_path_for_log = gz_path_clone.clone();
-            let content_len = content.len();
-
-            let blocking_task = tokio::task::spawn_blocking(move || -> Result<()> {
-                // Create temp file in same directory as target (ATOMIC)
-                let temp_file = NamedTempFile::new_in(&parent_dir_clone)?;
-                
-                // Write compressed data to temp file
-                let mut gz = GzBuilder::new()
-                    .filename(filename_clone)
-                    .comment(metadata_json_clone)
-                    .write(temp_file, Compression::new(3));
-                gz.write_all(&content)?;
-                let temp_file = gz.finish()?;
-                
-                // Atomically rename temp file to final path
-                // This is atomic at OS level - prevents race conditions
-                temp_file.persist(&gz_path_clone)?;
-                Ok(())
-            });
-
-            match timeout(BLOCKING_COMPRESSION_TIMEOUT, blocking_task).await {
-                Ok(Ok(result)) => result?,
-                Ok(Err(e)) => return Err(anyhow::anyhow!("Blocking compression task panicked: {}", e)),
-                Err(_) => {
-                    log::warn!(
-                        "Blocking compression timeout for file: {:?} (size: {} bytes, timeout: {:?})",
-                        gz_path_for_log,
-                        content_len,
-                        BLOCKING_COMPRESSION_TIMEOUT
-                    );
-                    return Err(anyhow::anyhow!(
-                        "Compression timed out after {:?} - possible filesystem hang or extremely slow disk",
-                        BLOCKING_COMPRESSION_TIMEOUT
-                    ));
-                }
+        // Save values for error logging (needed after closure consumes originals)
+        let gz_path_for_log = gz_path.clone();
+        let content_len = content.len();
+
+        let blocking_task = tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            let mut gz = GzBuilder::new()
+                .filename(filename_str)
+                .comment(metadata_json)
+                .write(Vec::new(), Compression::new(3));
+            gz.write_all(&content)?;
+            Ok(gz.finish()?)
+        });
+
+        let compressed = match timeout(BLOCKING_COMPRESSION_TIMEOUT, blocking_task).await {
+            Ok(Ok(result)) => result?,
+            Ok(Err(e)) => return Err(anyhow::anyhow!("Blocking compression task panicked: {}", e)),
+            Err(_) => {
+                log::warn!(
+                    "Blocking compression timeout for file: {:?} (size: {} bytes, timeout: {:?})",
+                    gz_path_for_log,
+                    content_len,
+                    BLOCKING_COMPRESSION_TIMEOUT
+                );
+                return Err(anyhow::anyhow!(
+                    "Compression timed out after {:?} - possible filesystem hang or extremely slow disk",
+                    BLOCKING_COMPRESSION_TIMEOUT
+                ));
             }
+        };
+
+        // Atomic write through the crawl's output sink
+        output_sink_for(&gz_path).write(&gz_path, compressed).await?;
 
         Ok((gz_path, metadata))
     } else {
-        // Uncompressed file: atomic write pattern
-        let parent_dir = path
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("Path has no parent directory"))?;
-
-        let mut temp_file = NamedTempFile::new_in(parent_dir)?;
-        temp_file.write_all(&content)?;
-        
-        // Atomic rename to final path
-        temp_file.persist(&path)?;
+        // Uncompressed file: atomic write through the crawl's output sink
+        output_sink_for(&path).write(&path, content).await?;
 
         Ok((path.clone(), metadata))
     }
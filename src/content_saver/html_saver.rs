@@ -6,7 +6,7 @@ use crate::inline_css::domain_queue::{CachedResponse, DomainDownloadQueue};
 use crate::page_extractor::schema::ResourceInfo;
 use crate::utils::{ensure_domain_gitignore, get_mirror_path};
 
-use super::compression::save_compressed_file;
+use super::compression::{ResponseValidators, save_compressed_file};
 
 /// Save HTML content after inlining all resources
 pub async fn save_html_content(
@@ -63,6 +63,7 @@ pub async fn save_html_content(
         inlined_html.into_bytes(),
         &path,
         "text/html",
+        ResponseValidators::default(),
         false,
         compression_threshold,
     )
@@ -135,6 +136,7 @@ pub async fn save_html_content_with_resources(
         inlined_html.into_bytes(),
         &path,
         "text/html",
+        ResponseValidators::default(),
         false,
         compression_threshold,
     )
@@ -4,7 +4,7 @@ use tokio::time::timeout;
 
 use crate::utils::{ensure_domain_gitignore, get_mirror_path};
 
-use super::compression::save_compressed_file;
+use super::compression::{ResponseValidators, save_compressed_file};
 
 /// Timeout for blocking JSON serialization
 /// Prevents hangs on pathological data structures
@@ -17,10 +17,32 @@ pub async fn save_json_data(
     output_dir: std::path::PathBuf,
     compression_threshold: usize,
 ) -> Result<()> {
-    let path = get_mirror_path(&url, &output_dir, "index.json").await?;
+    save_json_file(data, &url, &output_dir, "index.json", compression_threshold).await
+}
+
+/// Save records extracted by a crawl extraction schema as `data.json`
+pub async fn save_structured_data(
+    data: serde_json::Value,
+    url: String,
+    output_dir: std::path::PathBuf,
+    compression_threshold: usize,
+) -> Result<()> {
+    let document = serde_json::json!({ "url": url, "data": data });
+    save_json_file(document, &url, &output_dir, "data.json", compression_threshold).await
+}
+
+/// Serialize `data` to `filename` in the mirror directory of `url`
+async fn save_json_file(
+    data: serde_json::Value,
+    url: &str,
+    output_dir: &std::path::Path,
+    filename: &str,
+    compression_threshold: usize,
+) -> Result<()> {
+    let path = get_mirror_path(url, output_dir, filename).await?;
 
     // Ensure .gitignore exists in domain directory
-    ensure_domain_gitignore(&path, &output_dir).await?;
+    ensure_domain_gitignore(&path, output_dir).await?;
 
     // JSON serialization (keep spawn_blocking - CPU intensive)
     let blocking_task = tokio::task::spawn_blocking(move || serde_json::to_string_pretty(&data));
@@ -49,6 +71,7 @@ pub async fn save_json_data(
         json_str.into_bytes(),
         &path,
         "application/json",
+        ResponseValidators::default(),
         false,
         compression_threshold,
     )
@@ -100,6 +123,7 @@ pub async fn save_page_data(
         json_content.into_bytes(),
         &path,
         "application/json",
+        ResponseValidators::default(),
         false,
         compression_threshold,
     )
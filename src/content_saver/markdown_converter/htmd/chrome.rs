@@ -0,0 +1,169 @@
+//! How much page chrome the element handlers filter
+//!
+//! The default handlers reduce `<nav>` to its links, `<header>` to its `<h1>`,
+//! drop `<footer>` and drop asides and blocks that look like widgets. Some
+//! themes put real content in those elements (an `<aside>` of related API
+//! notes), others leave sticky bars and skip links the defaults miss. A
+//! [`ChromeFilter`] tunes this per conversion with a [`ChromeFilterLevel`]
+//! and class/id patterns that always keep or always drop an element. The
+//! filter is part of the [`ConversionContext`](super::context::ConversionContext)
+//! of the conversion in progress, so the cached element handlers stay shared.
+
+use std::rc::Rc;
+
+use html5ever::Attribute;
+use markup5ever_rcdom::{Node, NodeData};
+use regex::RegexSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tags the filter is consulted for
+pub(crate) const FILTERED_TAGS: &[&str] = &["a", "div", "section", "aside", "nav", "header", "footer", "span"];
+
+/// Link texts that mark a skip link (WCAG 2.4.1 bypass blocks)
+const SKIP_LINK_TEXTS: &[&str] = &["skip to", "skip navigation", "skip nav", "jump to content", "jump to main"];
+
+/// Class tokens of bars pinned to the viewport
+const STICKY_CLASSES: &[&str] = &[
+    "sticky",
+    "is-sticky",
+    "is-fixed",
+    "affix",
+    "fixed-top",
+    "fixed-bottom",
+    "sticky-top",
+    "sticky-bottom",
+    "navbar-fixed-top",
+    "navbar-fixed-bottom",
+];
+
+/// Landmark roles of site-wide chrome
+const CHROME_ROLES: &[&str] = &["banner", "navigation", "contentinfo", "complementary"];
+
+/// How aggressively page chrome is filtered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChromeFilterLevel {
+    /// Convert `<aside>`, `<header>` and `<footer>` like any block
+    Lenient,
+    /// Reduce navigation to its links and headers to their title, drop
+    /// footers and widget-like asides
+    #[default]
+    Standard,
+    /// Also drop navigation entirely, sticky or fixed bars, and elements with
+    /// a `banner`, `navigation`, `contentinfo` or `complementary` role
+    Strict,
+}
+
+impl ChromeFilterLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lenient => "lenient",
+            Self::Standard => "standard",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl std::fmt::Display for ChromeFilterLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Chrome filtering for one conversion
+///
+/// `keep` and `drop` are matched against an element's `class` and `id`
+/// attributes; an element matching `keep` is converted even when `drop` or
+/// the level would filter it. Skip links are dropped at every level.
+#[derive(Debug, Clone, Default)]
+pub struct ChromeFilter {
+    pub level: ChromeFilterLevel,
+    pub keep: Option<RegexSet>,
+    pub drop: Option<RegexSet>,
+}
+
+/// What the filter decided for an element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    /// Convert the element's content even if it looks like chrome
+    Keep,
+    /// Discard the element and its content
+    Drop,
+    /// Leave it to the element's handler
+    Default,
+}
+
+/// Decide how the `tag` element with `attrs` is filtered
+pub(crate) fn verdict(tag: &str, attrs: &[Attribute], node: &Rc<Node>) -> Verdict {
+    super::context::with(|context| {
+        let filter = &context.chrome_filter;
+        let class = attr(attrs, "class");
+        let id = attr(attrs, "id");
+        let matches = |patterns: &Option<RegexSet>| {
+            patterns
+                .as_ref()
+                .is_some_and(|set| class.is_some_and(|c| set.is_match(c)) || id.is_some_and(|i| set.is_match(i)))
+        };
+
+        if matches(&filter.keep) {
+            return Verdict::Keep;
+        }
+        if matches(&filter.drop) || (tag == "a" && is_skip_link(attrs, node)) {
+            return Verdict::Drop;
+        }
+        match filter.level {
+            ChromeFilterLevel::Lenient if matches!(tag, "aside" | "header" | "footer") => Verdict::Keep,
+            ChromeFilterLevel::Strict if tag == "nav" || is_pinned(attrs, class) || has_chrome_role(attrs) => {
+                Verdict::Drop
+            }
+            _ => Verdict::Default,
+        }
+    })
+}
+
+fn attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|attr| &*attr.name.local == name)
+        .map(|attr| &*attr.value)
+}
+
+/// In-page link whose text offers to skip past the navigation
+fn is_skip_link(attrs: &[Attribute], node: &Rc<Node>) -> bool {
+    if !attr(attrs, "href").is_some_and(|href| href.trim_start().starts_with('#')) {
+        return false;
+    }
+    let mut text = String::new();
+    collect_text(node, &mut text);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    SKIP_LINK_TEXTS.iter().any(|prefix| text.starts_with(prefix))
+}
+
+fn collect_text(node: &Rc<Node>, text: &mut String) {
+    for child in node.children.borrow().iter() {
+        match &child.data {
+            NodeData::Text { contents } => text.push_str(&contents.borrow()),
+            NodeData::Element { .. } => collect_text(child, text),
+            _ => {}
+        }
+    }
+}
+
+/// Sticky or fixed-position bar, by class or inline style
+fn is_pinned(attrs: &[Attribute], class: Option<&str>) -> bool {
+    let by_class = class.is_some_and(|class| {
+        class
+            .split_whitespace()
+            .any(|token| STICKY_CLASSES.contains(&token.to_ascii_lowercase().as_str()))
+    });
+    by_class
+        || attr(attrs, "style").is_some_and(|style| {
+            let style = style.to_ascii_lowercase().replace(' ', "");
+            style.contains("position:sticky") || style.contains("position:fixed")
+        })
+}
+
+fn has_chrome_role(attrs: &[Attribute]) -> bool {
+    attr(attrs, "role").is_some_and(|role| CHROME_ROLES.contains(&role.trim().to_ascii_lowercase().as_str()))
+}
@@ -0,0 +1,37 @@
+//! HTML comments kept in the markdown
+//!
+//! Comments are dropped by default. A conversion can keep the ones whose text
+//! matches configured patterns (docs build markers, TODO annotations); they
+//! are emitted as `<!-- ... -->`, which markdown renderers hide. The patterns
+//! are part of the [`ConversionContext`](super::context::ConversionContext)
+//! of the conversion in progress.
+
+use regex::RegexSet;
+
+/// Whether `patterns` keep the comment with text `contents`
+pub(crate) fn matches(patterns: Option<&RegexSet>, contents: &str) -> bool {
+    patterns.is_some_and(|patterns| patterns.is_match(contents.trim()))
+}
+
+/// Whether the conversion in progress keeps the comment with text `contents`
+pub(crate) fn keeps(contents: &str) -> bool {
+    super::context::with(|context| matches(context.keep_comments.as_ref(), contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content_saver::markdown_converter::htmd::context::{ContextGuard, ConversionContext};
+
+    #[test]
+    fn test_keeps_installed_patterns() {
+        assert!(!keeps("docs-build: 42"));
+        let _context = ContextGuard::install(ConversionContext {
+            keep_comments: Some(RegexSet::new(["^docs-build:", "(?i)todo"]).unwrap()),
+            ..ConversionContext::default()
+        });
+        assert!(keeps(" docs-build: 42 "));
+        assert!(keeps("TODO: split this page"));
+        assert!(!keeps("google_ad_section_start"));
+    }
+}
@@ -0,0 +1,131 @@
+//! Settings and state of the conversion in progress
+//!
+//! The DOM walker recurses through handler callbacks and the element handlers
+//! are cached per thread, so per-conversion settings cannot be passed down as
+//! arguments. They are collected in one [`ConversionContext`] (resource
+//! limits, chrome filter, kept comments) that [`ContextGuard`] installs on the
+//! current thread for the length of a conversion, together with the node and
+//! time budget the walk draws from.
+
+use std::cell::RefCell;
+
+use regex::RegexSet;
+
+use super::chrome::{ChromeFilter, ChromeFilterLevel};
+use super::limits::{Budget, ConversionLimits, Truncation};
+
+/// Per-conversion settings consulted by the DOM walker and element handlers
+#[derive(Debug, Clone)]
+pub struct ConversionContext {
+    /// Caps on input size, DOM nodes and time
+    pub limits: ConversionLimits,
+    /// How navigation, headers, footers and asides are filtered
+    pub chrome_filter: ChromeFilter,
+    /// HTML comments whose text matches are kept
+    pub keep_comments: Option<RegexSet>,
+}
+
+impl ConversionContext {
+    /// No limits, standard chrome filtering, no comments kept
+    pub const DEFAULT: Self = Self {
+        limits: ConversionLimits::UNLIMITED,
+        chrome_filter: ChromeFilter {
+            level: ChromeFilterLevel::Standard,
+            keep: None,
+            drop: None,
+        },
+        keep_comments: None,
+    };
+}
+
+impl Default for ConversionContext {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The installed context and the budget of its conversion
+struct Active {
+    context: ConversionContext,
+    budget: Budget,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Active>> = const { RefCell::new(None) };
+}
+
+/// Applies a [`ConversionContext`] on the current thread until dropped
+///
+/// The previously installed context (if any) is restored on drop, so nested
+/// conversions each see their own settings.
+pub(crate) struct ContextGuard {
+    previous: Option<Active>,
+}
+
+impl ContextGuard {
+    pub(crate) fn install(context: ConversionContext) -> Self {
+        let active = Active {
+            budget: Budget::new(context.limits),
+            context,
+        };
+        let previous = ACTIVE.with(|cell| cell.borrow_mut().replace(active));
+        Self { previous }
+    }
+
+    /// Why the walk stopped early, if it did
+    pub(crate) fn truncation(&self) -> Option<Truncation> {
+        ACTIVE.with(|cell| cell.borrow().as_ref().and_then(|active| active.budget.exhausted()))
+    }
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        ACTIVE.with(|cell| *cell.borrow_mut() = previous);
+    }
+}
+
+/// Run `f` with the installed context, or [`ConversionContext::DEFAULT`]
+/// outside a conversion
+pub(crate) fn with<R>(f: impl FnOnce(&ConversionContext) -> R) -> R {
+    ACTIVE.with(|cell| match cell.borrow().as_ref() {
+        Some(active) => f(&active.context),
+        None => f(&ConversionContext::DEFAULT),
+    })
+}
+
+/// Run `f` with the budget of the installed context, `None` outside a conversion
+pub(crate) fn with_budget<R>(f: impl FnOnce(&mut Budget) -> R) -> Option<R> {
+    ACTIVE.with(|cell| cell.borrow_mut().as_mut().map(|active| f(&mut active.budget)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_scopes_context() {
+        let keeps = || with(|context| context.keep_comments.as_ref().is_some_and(|set| set.is_match("docs-build: 42")));
+        assert!(!keeps());
+        {
+            let _outer = ContextGuard::install(ConversionContext {
+                keep_comments: Some(RegexSet::new(["^docs-build:"]).unwrap()),
+                ..ConversionContext::default()
+            });
+            assert!(keeps());
+            {
+                let inner = ContextGuard::install(ConversionContext {
+                    limits: ConversionLimits { max_dom_nodes: 0, ..ConversionLimits::UNLIMITED },
+                    ..ConversionContext::default()
+                });
+                assert!(!keeps());
+                assert_eq!(with_budget(Budget::charge), Some(false));
+                assert_eq!(inner.truncation(), Some(Truncation::DomNodes { limit: 0 }));
+            }
+            assert!(keeps());
+            assert_eq!(with_budget(Budget::charge), Some(true));
+        }
+        assert!(!keeps());
+        assert_eq!(with_budget(Budget::charge), None);
+    }
+}
@@ -4,7 +4,9 @@ use markup5ever_rcdom::{Node, NodeData};
 use phf::phf_set;
 use std::{borrow::Cow, rc::Rc};
 
+use super::comments;
 use super::element_handler::ElementHandlers;
+use super::limits;
 
 use super::{
     node_util::get_node_tag_name,
@@ -23,6 +25,10 @@ pub(crate) fn walk_node(
     trim_leading_spaces: bool,
     is_pre: bool,
 ) -> bool {
+    if !limits::charge_node() {
+        // Budget spent: skip the rest of the document
+        return true;
+    }
     let mut markdown_translated = true;
     match node.data {
         NodeData::Document => {
@@ -95,7 +101,7 @@ pub(crate) fn walk_node(
         }
 
         NodeData::Comment { ref contents } => {
-            if handlers.options.translation_mode == TranslationMode::Faithful {
+            if handlers.options.translation_mode == TranslationMode::Faithful || comments::keeps(contents) {
                 buffer.push_str(&format!("<!--{}-->", contents));
             }
         }
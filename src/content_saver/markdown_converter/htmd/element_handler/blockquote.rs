@@ -1,21 +1,121 @@
+//! Handler for <blockquote> elements
+//!
+//! Every line of the quote's markdown gets a `> ` prefix, so quotes nested
+//! inside it keep their level (`> > ...`). A trailing attribution element
+//! (`<footer>`, `<cite>`, or one classed like `attribution`) and the `cite`
+//! URL become a final `— source` line inside the quote; left to the default
+//! handlers the footer would be dropped as page chrome.
+
+use std::rc::Rc;
+
+use html5ever::Attribute;
+use markup5ever_rcdom::{Node, NodeData};
+
 use super::super::{
     Element,
+    node_util::get_node_tag_name,
     text_util::{JoinOnStringIterator, TrimDocumentWhitespace, concat_strings},
 };
+use super::element_util::get_attr;
 use super::{HandlerResult, Handlers};
 use crate::serialize_if_faithful;
 
+/// Classes marking the element that names a quote's source
+const ATTRIBUTION_CLASSES: &[&str] = &[
+    "attribution",
+    "author",
+    "blockquote-footer",
+    "quote-author",
+    "quote-source",
+    "source",
+];
+
 pub(super) fn blockquote_handler(
     handlers: &dyn Handlers,
     element: Element,
 ) -> Option<HandlerResult> {
     serialize_if_faithful!(handlers, element, 0);
-    let content = handlers.walk_children(element.node, element.is_pre).content;
-    let content = content.trim_start_matches('\n');
-    let content = content
-        .trim_end_document_whitespace()
-        .lines()
-        .map(|line| concat_strings!("> ", line))
-        .join("\n");
-    Some(concat_strings!("\n\n", content, "\n\n").into())
+    Some(format_quote(handlers, element.node, element.attrs, element.is_pre, None))
+}
+
+/// Convert a blockquote, using `caption` (a figure's `<figcaption>`) as the
+/// attribution when the quote has none of its own
+pub(super) fn format_quote(
+    handlers: &dyn Handlers,
+    node: &Rc<Node>,
+    attrs: &[Attribute],
+    is_pre: bool,
+    caption: Option<String>,
+) -> HandlerResult {
+    let (content, attribution) = match take_trailing_attribution(node) {
+        Some((index, child)) => {
+            let content = handlers.walk_children(node, is_pre).content;
+            node.children.borrow_mut().insert(index, Rc::clone(&child));
+            (content, Some(attribution_text(handlers, &child)))
+        }
+        None => (handlers.walk_children(node, is_pre).content, None),
+    };
+    let attribution = attribution.filter(|text| !text.is_empty()).or(caption);
+    let cite = get_attr(attrs, "cite")
+        .map(|url| url.trim().to_string())
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"));
+    let source = match (attribution, cite) {
+        (Some(text), Some(url)) if !text.contains("](") => Some(concat_strings!("[", text, "](", url, ")")),
+        (Some(text), _) => Some(text),
+        (None, Some(url)) => Some(concat_strings!("<", url, ">")),
+        (None, None) => None,
+    };
+
+    let mut content = content.trim_start_matches('\n').trim_end_document_whitespace().to_string();
+    if let Some(source) = source {
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str("— ");
+        content.push_str(&source);
+    }
+    let content = content.lines().map(|line| concat_strings!("> ", line)).join("\n");
+    concat_strings!("\n\n", content, "\n\n").into()
+}
+
+/// Text of a figure caption or attribution element, on one line and without
+/// its leading dash
+pub(super) fn attribution_text(handlers: &dyn Handlers, node: &Rc<Node>) -> String {
+    let text = if get_node_tag_name(node) == Some("cite") {
+        handlers.handle(node).map(|result| result.content).unwrap_or_default()
+    } else {
+        handlers.walk_children(node, false).content
+    };
+    text.split_whitespace()
+        .join(" ")
+        .trim_start_matches(|c: char| matches!(c, '—' | '–' | '―' | '-' | '~') || c.is_whitespace())
+        .to_string()
+}
+
+/// Detach the quote's last child when it is an attribution element
+///
+/// Returns the child and its index so the caller can put it back.
+fn take_trailing_attribution(node: &Rc<Node>) -> Option<(usize, Rc<Node>)> {
+    let mut children = node.children.borrow_mut();
+    let index = children.iter().rposition(|child| match &child.data {
+        NodeData::Element { .. } => true,
+        NodeData::Text { contents } => !contents.borrow().trim().is_empty(),
+        _ => false,
+    })?;
+    if !is_attribution(&children[index]) {
+        return None;
+    }
+    Some((index, children.remove(index)))
+}
+
+fn is_attribution(node: &Rc<Node>) -> bool {
+    let NodeData::Element { name, attrs, .. } = &node.data else {
+        return false;
+    };
+    matches!(&*name.local, "footer" | "cite")
+        || get_attr(&attrs.borrow(), "class").is_some_and(|class| {
+            class
+                .split_whitespace()
+                .any(|class| ATTRIBUTION_CLASSES.contains(&class.to_ascii_lowercase().as_str()))
+        })
 }
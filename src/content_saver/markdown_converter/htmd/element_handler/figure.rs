@@ -1,9 +1,17 @@
 //! Handler for figures: <figure>, <figcaption>
 //!
 //! Converts figure elements to image + italicized caption.
-//! The <img> inside figure is handled by the existing img_handler.
+//! The <img> inside figure is handled by the existing img_handler. A figure
+//! holding only a blockquote and its caption becomes the quote with the
+//! caption as its `— source` line.
+
+use std::rc::Rc;
+
+use markup5ever_rcdom::{Node, NodeData};
 
 use super::super::Element;
+use super::super::node_util::get_node_tag_name;
+use super::blockquote::{attribution_text, format_quote};
 use super::{HandlerResult, Handlers};
 use crate::serialize_if_faithful;
 
@@ -17,6 +25,11 @@ pub(super) fn figure_handler(
     element: Element,
 ) -> Option<HandlerResult> {
     serialize_if_faithful!(handlers, element, 0);
+
+    // A quote with its caption: the caption names the quote's source
+    if let Some(quote) = quote_with_caption(handlers, element.node, element.is_pre) {
+        return Some(quote);
+    }
     
     // Process all children (img and figcaption handlers will format appropriately)
     let content = handlers.walk_children(element.node, element.is_pre).content;
@@ -46,3 +59,34 @@ pub(super) fn figcaption_handler(
     // Italicize caption text
     Some(format!("\n*{}*\n", content).into())
 }
+
+/// Convert a figure whose element children are a blockquote and, optionally,
+/// a figcaption; `None` for any other figure
+fn quote_with_caption(handlers: &dyn Handlers, node: &Rc<Node>, is_pre: bool) -> Option<HandlerResult> {
+    let children: Vec<Rc<Node>> = node
+        .children
+        .borrow()
+        .iter()
+        .filter(|child| matches!(child.data, NodeData::Element { .. }))
+        .cloned()
+        .collect();
+    let quote = children.iter().find(|child| get_node_tag_name(child) == Some("blockquote"))?;
+    if children
+        .iter()
+        .any(|child| !matches!(get_node_tag_name(child), Some("blockquote" | "figcaption")))
+        || children.len() > 2
+    {
+        return None;
+    }
+    let caption = children
+        .iter()
+        .find(|child| get_node_tag_name(child) == Some("figcaption"))
+        .map(|caption| attribution_text(handlers, caption))
+        .filter(|caption| !caption.is_empty());
+
+    let NodeData::Element { attrs, .. } = &quote.data else {
+        return None;
+    };
+    let attrs = attrs.borrow();
+    Some(format_quote(handlers, quote, &attrs, is_pre, caption))
+}
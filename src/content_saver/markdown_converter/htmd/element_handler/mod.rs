@@ -46,6 +46,7 @@ pub mod language_patterns;
 pub mod list_processing;
 
 use super::{
+    chrome::{self, Verdict},
     dom_walker::walk_node,
     options::{Options, TranslationMode},
     text_util::concat_strings,
@@ -389,6 +390,10 @@ impl ElementHandlers {
         handlers.add_handler(vec!["rt"], rt_handler);
         handlers.add_handler(vec!["rp"], rp_handler);
 
+        // Chrome filter level and keep/drop patterns, consulted before the
+        // handlers above
+        handlers.add_handler(chrome::FILTERED_TAGS.to_vec(), chrome_filter_handler);
+
         handlers
     }
 
@@ -541,6 +546,41 @@ fn picture_handler(handlers: &dyn Handlers, element: Element) -> Option<HandlerR
     Some("".into())
 }
 
+/// Tags whose content the default handlers drop as page chrome or widgets
+pub(crate) const CHROME_TAGS: &[&str] = &["div", "section", "aside", "nav", "header", "footer", "span"];
+
+/// Keep the content of page chrome that the default handlers drop
+///
+/// Navigation, headers and footers are converted like any block; other
+/// elements go through their default handler first, and only when that
+/// discards them (widget filtering) are their children converted anyway.
+pub(crate) fn unfiltered_handler(handlers: &dyn Handlers, element: Element) -> Option<HandlerResult> {
+    let (node, tag, is_pre) = (element.node, element.tag, element.is_pre);
+    if !matches!(tag, "nav" | "header" | "footer")
+        && let Some(converted) = handlers.fallback(element)
+        && !converted.content.trim().is_empty()
+    {
+        return Some(converted);
+    }
+
+    let content = handlers.walk_children(node, is_pre).content;
+    if tag == "span" {
+        return Some(content.into());
+    }
+    let content = content.trim_matches('\n');
+    Some(concat_strings!("\n\n", content, "\n\n").into())
+}
+
+/// Apply the conversion's [`ChromeFilter`](chrome::ChromeFilter) before the
+/// element's default handler
+fn chrome_filter_handler(handlers: &dyn Handlers, element: Element) -> Option<HandlerResult> {
+    match chrome::verdict(element.tag, element.attrs, element.node) {
+        Verdict::Drop => Some("".into()),
+        Verdict::Keep if element.tag != "a" => unfiltered_handler(handlers, element),
+        Verdict::Keep | Verdict::Default => handlers.fallback(element),
+    }
+}
+
 fn block_handler(handlers: &dyn Handlers, element: Element) -> Option<HandlerResult> {
     if handlers.options().translation_mode == TranslationMode::Pure {
         let content = handlers.walk_children(element.node, element.is_pre).content;
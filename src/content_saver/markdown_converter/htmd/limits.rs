@@ -0,0 +1,124 @@
+//! Resource limits for a single conversion
+//!
+//! The budget for the conversion in progress is installed with its
+//! [`ConversionContext`](super::context::ConversionContext) rather than being
+//! threaded through every handler. `walk_node` charges one node per visit;
+//! once the node or time budget is spent the remaining nodes are skipped and
+//! the conversion ends with what has been emitted so far.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How often (in visited nodes) the deadline is checked
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// Hard caps applied to one HTML→Markdown conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionLimits {
+    /// HTML beyond this many bytes is dropped before parsing
+    pub max_html_bytes: usize,
+    /// Nodes beyond this many are not converted
+    pub max_dom_nodes: usize,
+    /// Conversion stops once this much time has elapsed
+    pub max_duration: Duration,
+}
+
+impl ConversionLimits {
+    /// No limits.
+    pub const UNLIMITED: Self = Self {
+        max_html_bytes: usize::MAX,
+        max_dom_nodes: usize::MAX,
+        max_duration: Duration::MAX,
+    };
+}
+
+impl Default for ConversionLimits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+/// Why a conversion produced partial output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation {
+    /// The HTML was longer than `max_html_bytes`
+    HtmlBytes { limit: usize, actual: usize },
+    /// The document had more than `max_dom_nodes` nodes
+    DomNodes { limit: usize },
+    /// Conversion ran longer than `max_duration`
+    Time { limit: Duration },
+}
+
+impl fmt::Display for Truncation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HtmlBytes { limit, actual } => {
+                write!(f, "page HTML is {actual} bytes, only the first {limit} were converted")
+            }
+            Self::DomNodes { limit } => {
+                write!(f, "page has more than {limit} DOM nodes")
+            }
+            Self::Time { limit } => {
+                write!(f, "conversion exceeded {}s", limit.as_secs_f64())
+            }
+        }
+    }
+}
+
+/// Nodes and time left for one conversion
+pub(crate) struct Budget {
+    remaining_nodes: usize,
+    visited: usize,
+    deadline: Option<Instant>,
+    limits: ConversionLimits,
+    exhausted: Option<Truncation>,
+}
+
+impl Budget {
+    pub(crate) fn new(limits: ConversionLimits) -> Self {
+        Self {
+            remaining_nodes: limits.max_dom_nodes,
+            visited: 0,
+            deadline: Instant::now().checked_add(limits.max_duration),
+            limits,
+            exhausted: None,
+        }
+    }
+
+    /// Why the walk stopped early, if it did.
+    pub(crate) fn exhausted(&self) -> Option<Truncation> {
+        self.exhausted
+    }
+
+    /// Charge one node visit, returning `false` once the budget is spent.
+    pub(crate) fn charge(&mut self) -> bool {
+        if self.exhausted.is_some() {
+            return false;
+        }
+        if self.remaining_nodes == 0 {
+            self.exhausted = Some(Truncation::DomNodes {
+                limit: self.limits.max_dom_nodes,
+            });
+            return false;
+        }
+        self.remaining_nodes -= 1;
+        self.visited += 1;
+        if self.visited.is_multiple_of(DEADLINE_CHECK_INTERVAL)
+            && self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.exhausted = Some(Truncation::Time {
+                limit: self.limits.max_duration,
+            });
+            return false;
+        }
+        true
+    }
+}
+
+/// Charge one node visit to the conversion in progress, returning `false`
+/// once its budget is spent.
+///
+/// Always succeeds outside a conversion.
+pub(crate) fn charge_node() -> bool {
+    super::context::with_budget(Budget::charge).unwrap_or(true)
+}
@@ -1,6 +1,10 @@
+pub mod chrome;
+pub(crate) mod comments;
+pub mod context;
 mod dom_walker;
 pub mod element_handler;
 mod html_escape;
+pub mod limits;
 pub(crate) mod node_util;
 pub mod options;
 pub(crate) mod text_util;
@@ -9,6 +13,8 @@ use std::rc::Rc;
 
 use dom_walker::walk_node;
 use element_handler::{ElementHandler, ElementHandlers};
+use context::{ContextGuard, ConversionContext};
+use limits::{ConversionLimits, Truncation};
 use html5ever::tendril::TendrilSink;
 use html5ever::tree_builder::TreeBuilderOpts;
 use html5ever::{Attribute, ParseOpts, parse_document};
@@ -32,6 +38,17 @@ pub fn convert(html: &str) -> Result<String, std::io::Error> {
     HtmlToMarkdown::new().convert(html)
 }
 
+/// Markdown of one conversion and how it went
+#[derive(Debug, Clone)]
+pub struct Converted {
+    pub markdown: String,
+    /// Why the conversion stopped early, if it did
+    pub truncation: Option<Truncation>,
+    /// Letters and digits of the page's visible text, counted on the parsed
+    /// DOM (see [`visible_text_chars`](super::quality::visible_text_chars))
+    pub page_text_chars: usize,
+}
+
 /// The DOM element.
 pub struct Element<'a> {
     /// The html5ever node of the element.
@@ -94,6 +111,18 @@ impl HtmlToMarkdown {
         }
     }
 
+    /// Create a converter that keeps navigation, headers, footers and widgets.
+    ///
+    /// Used to retry pages whose filtered conversion lost their content.
+    pub fn unfiltered() -> Self {
+        let mut handlers = ElementHandlers::new(Options::default());
+        handlers.add_handler(element_handler::CHROME_TAGS.to_vec(), element_handler::unfiltered_handler);
+        Self {
+            handlers,
+            scripting_enabled: true,
+        }
+    }
+
     pub(crate) fn from_params(handlers: ElementHandlers, scripting_enabled: bool) -> Self {
         Self {
             handlers,
@@ -108,6 +137,43 @@ impl HtmlToMarkdown {
 
     /// Convert HTML to Markdown.
     pub fn convert(&self, html: &str) -> std::io::Result<String> {
+        self.convert_with_limits(html, ConversionLimits::UNLIMITED)
+            .map(|(markdown, _)| markdown)
+    }
+
+    /// Convert HTML to Markdown within `limits`.
+    ///
+    /// Input past `max_html_bytes` is dropped before parsing, and the DOM
+    /// walk stops once `max_dom_nodes` or `max_duration` is reached. The
+    /// markdown produced up to that point is returned along with the reason
+    /// it was cut short.
+    pub fn convert_with_limits(
+        &self,
+        html: &str,
+        limits: ConversionLimits,
+    ) -> std::io::Result<(String, Option<Truncation>)> {
+        self.convert_in_context(html, ConversionContext { limits, ..ConversionContext::default() })
+            .map(|converted| (converted.markdown, converted.truncation))
+    }
+
+    /// Convert HTML to Markdown with the settings of `context`.
+    ///
+    /// Limits apply as in [`Self::convert_with_limits`]; the chrome filter and
+    /// kept comments are consulted by the element handlers.
+    pub fn convert_in_context(&self, html: &str, context: ConversionContext) -> std::io::Result<Converted> {
+        let limits = context.limits;
+        let mut truncation = None;
+        let html = if html.len() > limits.max_html_bytes {
+            truncation = Some(Truncation::HtmlBytes {
+                limit: limits.max_html_bytes,
+                actual: html.len(),
+            });
+            &html[..html.floor_char_boundary(limits.max_html_bytes)]
+        } else {
+            html
+        };
+
+        let context = ContextGuard::install(context);
         let dom = parse_document(
             RcDom::default(),
             ParseOpts {
@@ -120,6 +186,7 @@ impl HtmlToMarkdown {
         )
         .from_utf8()
         .read_from(&mut html.as_bytes())?;
+        let page_text_chars = super::quality::dom_text_chars(&dom.document);
 
         // Pre-allocate based on HTML size (markdown is typically 75% of HTML)
         let estimated_capacity = html.len() * 3 / 4;
@@ -133,6 +200,8 @@ impl HtmlToMarkdown {
             true,
             false,
         );
+        let truncation = truncation.or(context.truncation());
+        drop(context);
 
         // Trim leading newlines in-place
         let start = buffer.find(|c: char| c != '\n').unwrap_or(0);
@@ -163,7 +232,11 @@ impl HtmlToMarkdown {
 
         content.push_str(append.trim_end_matches('\n'));
 
-        Ok(content)
+        Ok(Converted {
+            markdown: content,
+            truncation,
+            page_text_chars,
+        })
     }
 }
 
@@ -17,13 +17,17 @@
 //! without complex lookahead/lookbehind patterns that can corrupt inline formatting.
 
 use anyhow::Result;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use smallvec::SmallVec;
 use std::fmt::Write;
 use std::sync::{Arc, LazyLock};
 use url::Url;
 
 use super::htmd::HtmlToMarkdown;
+use super::htmd::chrome::ChromeFilter;
+use super::htmd::comments;
+use super::htmd::context::ConversionContext;
+use super::htmd::limits::ConversionLimits;
 // Note: Link card transformation removed - it was site-specific (assumed "card" in class names)
 
 // =============================================================================
@@ -49,6 +53,14 @@ static IMAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
         .expect("IMAGE_RE: hardcoded regex is valid")
 });
 
+thread_local! {
+    // The element handler table is the same for every page, so it is built
+    // once per thread. Per-thread rather than shared because handlers (e.g.
+    // referenced-style anchors) buffer state during a single conversion.
+    static CONVERTER: HtmlToMarkdown = HtmlToMarkdown::new();
+    static UNFILTERED_CONVERTER: HtmlToMarkdown = HtmlToMarkdown::unfiltered();
+}
+
 
 // =============================================================================
 // LINE TYPE CLASSIFICATION
@@ -197,19 +209,22 @@ impl LineType {
 /// - Code fence passthrough (no processing inside fences)
 /// - Block element spacing (blank lines before headings, etc.)
 /// - Heading normalization (`##Text` → `## Text`)
-struct MarkdownNormalizer {
+struct MarkdownNormalizer<'a> {
     output: String,
+    keep_comments: Option<&'a RegexSet>,
     prev_type: LineType,
     consecutive_blanks: u8,
     in_code_fence: bool,
     prev_was_heading: bool,  // Track if previous non-blank line was a heading
 }
 
-impl MarkdownNormalizer {
-    /// Normalize markdown in a single pass with pre-allocated buffer.
-    fn normalize(input: &str) -> String {
+impl<'a> MarkdownNormalizer<'a> {
+    /// Normalize markdown in a single pass with pre-allocated buffer,
+    /// keeping HTML comments matched by `keep_comments`.
+    fn normalize(input: &str, keep_comments: Option<&'a RegexSet>) -> String {
         let mut this = Self {
             output: String::with_capacity(input.len()),
+            keep_comments,
             prev_type: LineType::Blank,
             consecutive_blanks: 0,
             in_code_fence: false,
@@ -241,8 +256,11 @@ impl MarkdownNormalizer {
             self.in_code_fence = true;
         }
 
-        // Skip HTML comments and empty list markers
-        if matches!(line_type, LineType::HtmlComment | LineType::EmptyListMarkers) {
+        // Skip HTML comments (unless configured to be kept) and empty list markers
+        if line_type == LineType::HtmlComment && !self.is_kept_comment(line) {
+            return;
+        }
+        if line_type == LineType::EmptyListMarkers {
             return;
         }
 
@@ -302,6 +320,13 @@ impl MarkdownNormalizer {
         self.output.push_str(line);
     }
 
+    /// Check if a comment line holds a comment the conversion keeps.
+    fn is_kept_comment(&self, line: &str) -> bool {
+        let contents = line.trim().trim_start_matches("<!--");
+        let contents = contents.find("-->").map_or(contents, |end| &contents[..end]);
+        comments::matches(self.keep_comments, contents)
+    }
+
     /// Ensure space after # in headings: `##Text` → `## Text`
     fn normalize_heading(line: &str) -> String {
         let trimmed = line.trim_start();
@@ -506,6 +531,9 @@ pub struct MarkdownConverter {
     preserve_links: bool,
     preserve_images: bool,
     code_highlighting: bool,
+    unfiltered: bool,
+    /// Limits, chrome filter and kept comments of each conversion
+    context: ConversionContext,
 }
 
 impl Default for MarkdownConverter {
@@ -515,6 +543,8 @@ impl Default for MarkdownConverter {
             preserve_links: true,
             preserve_images: true,
             code_highlighting: true,
+            unfiltered: false,
+            context: ConversionContext::default(),
         }
     }
 }
@@ -549,6 +579,36 @@ impl MarkdownConverter {
         self
     }
 
+    /// Keep navigation, headers, footers and widgets instead of filtering them.
+    #[must_use]
+    pub fn with_unfiltered(mut self, unfiltered: bool) -> Self {
+        self.unfiltered = unfiltered;
+        self
+    }
+
+    /// Keep HTML comments whose text matches one of `patterns`.
+    #[must_use]
+    pub fn with_keep_comments(mut self, patterns: Option<RegexSet>) -> Self {
+        self.context.keep_comments = patterns;
+        self
+    }
+
+    /// Filter navigation, headers, footers and asides according to `filter`.
+    #[must_use]
+    pub fn with_chrome_filter(mut self, filter: ChromeFilter) -> Self {
+        self.context.chrome_filter = filter;
+        self
+    }
+
+    /// Cap input size, DOM nodes and time spent converting.
+    ///
+    /// Output cut short by a limit ends with a truncation marker line.
+    #[must_use]
+    pub fn with_limits(mut self, limits: ConversionLimits) -> Self {
+        self.context.limits = limits;
+        self
+    }
+
     /// Convert HTML to Markdown synchronously.
     ///
     /// Pipeline:
@@ -558,20 +618,28 @@ impl MarkdownConverter {
     /// 3. Table formatting (optional)
     /// 4. HTML img tag fallback conversion
     /// 5. Link/image removal (optional)
+    /// 6. Truncation marker, if a limit was hit
     pub fn convert_sync(&self, html: &str) -> Result<String> {
+        self.convert_counting_sync(html).map(|(markdown, _)| markdown)
+    }
+
+    /// [`Self::convert_sync`], also returning the letters and digits of the
+    /// page's visible text, counted on the DOM parsed for the conversion
+    pub fn convert_counting_sync(&self, html: &str) -> Result<(String, usize)> {
         // Stage 0: Preprocessing (site-specific transformations removed)
         // Note: Callout transformation removed - it was site-specific and only worked with hard-coded class names
         // Note: Link card transformation removed - it was site-specific (assumed "card" in class names)
         // Note: Tab transformation removed - site-specific patterns conflict with generic crawler mission
         
         // Stage 1: htmd conversion
-        let converter = HtmlToMarkdown::new();
-        let raw_markdown = converter.convert(html)?;
+        let converter = if self.unfiltered { &UNFILTERED_CONVERTER } else { &CONVERTER };
+        let converted = converter.with(|converter| converter.convert_in_context(html, self.context.clone()))?;
+        let (raw_markdown, truncation) = (converted.markdown, converted.truncation);
 
         // Stage 2: Streaming normalization (single pass)
         // Handles: blank line collapsing, heading spacing, code fence passthrough,
         // HTML comment removal, empty list marker removal
-        let mut markdown = MarkdownNormalizer::normalize(&raw_markdown);
+        let mut markdown = MarkdownNormalizer::normalize(&raw_markdown, self.context.keep_comments.as_ref());
 
         // Stage 3: Table formatting (line-based, already efficient)
         if self.preserve_tables {
@@ -586,7 +654,17 @@ impl MarkdownConverter {
             markdown = Self::remove_images_static(&markdown);
         }
 
-        Ok(markdown.trim().to_string())
+        // Stage 6: Say where the page was cut off
+        let mut markdown = markdown.trim().to_string();
+        if let Some(truncation) = truncation {
+            log::warn!("Markdown conversion truncated: {truncation}");
+            if !markdown.is_empty() {
+                markdown.push_str("\n\n");
+            }
+            let _ = write!(markdown, "*[Content truncated: {truncation}]*");
+        }
+
+        Ok((markdown, converted.page_text_chars))
     }
 
     /// Convert HTML to Markdown asynchronously
@@ -609,6 +687,8 @@ impl MarkdownConverter {
         let preserve_links = self.preserve_links;
         let preserve_images = self.preserve_images;
         let code_highlighting = self.code_highlighting;
+        let unfiltered = self.unfiltered;
+        let context = self.context.clone();
         
         tokio::task::spawn_blocking(move || {
             let converter = MarkdownConverter {
@@ -616,6 +696,8 @@ impl MarkdownConverter {
                 preserve_links,
                 preserve_images,
                 code_highlighting,
+                unfiltered,
+                context,
             };
             converter.convert_sync(&html)
         })
@@ -3,9 +3,13 @@
 //! This module provides the complete pipeline for converting HTML to clean, well-formatted markdown:
 //! 1. Convert to Markdown using htmd with DOM-based element handlers (filtering happens here)
 //! 2. Process markdown links (optional, resolve relative URLs)
+//! 3. Normalize punctuation, entities and emoji (optional, see [`ConversionOptions`])
 //!
 //! Note: HTML filtering (widget removal, script/style removal, nav/header/footer removal)
 //! is handled by htmd element handlers during DOM traversal. See htmd/element_handler/ for details.
+//! With [`ExtractionBackend::Readability`] the main article is first picked out by
+//! Readability scoring (see [`readability`]), and only that is converted.
+//! [`compare_backends_sync`] converts a page with both to show how they differ.
 //!
 //! # Usage
 //!
@@ -42,20 +46,39 @@
 //!     process_headings: true,
 //!     normalize_whitespace: true,
 //!     base_url: None,
+//!     ..ConversionOptions::default()
 //! };
 //! let markdown = convert_html_to_markdown_sync(html, &options)?;
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 
 use anyhow::Result;
+use regex::RegexSet;
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::markdown_diff::{MarkdownDiff, diff_markdown};
 
 // Declare sub-modules
 pub mod htmd;
 pub mod html_to_markdown;
+pub mod quality;
+pub mod readability;
+mod typography;
 
 // Re-export sub-modules for advanced usage
 pub use html_to_markdown::MarkdownConverter;
+pub use htmd::chrome::{ChromeFilter, ChromeFilterLevel};
+pub use htmd::limits::{ConversionLimits, Truncation};
+pub use quality::{DEFAULT_MIN_QUALITY_SCORE, ExtractionQuality};
+pub use readability::{ExtractionBackend, ExtractionReport};
+pub use typography::Typography;
+
+/// Default cap on DOM nodes converted per page
+pub const DEFAULT_MAX_DOM_NODES: usize = 500_000;
+
+/// Default cap on time spent converting one page
+pub const DEFAULT_MAX_CONVERSION_TIME: Duration = Duration::from_secs(30);
 
 
 /// Configuration options for HTML to Markdown conversion
@@ -107,6 +130,49 @@ pub struct ConversionOptions {
     /// - "#section" → "#section" (preserved as-is)
     /// - "https://other.com" → "https://other.com" (preserved as-is)
     pub base_url: Option<String>,
+
+    /// HTML bytes converted (default: `MAX_PAGE_HTML_BYTES`)
+    ///
+    /// Longer input is cut at this size before parsing.
+    pub max_html_bytes: usize,
+
+    /// DOM nodes converted (default: `DEFAULT_MAX_DOM_NODES`)
+    pub max_dom_nodes: usize,
+
+    /// Time allowed for the conversion (default: `DEFAULT_MAX_CONVERSION_TIME`)
+    ///
+    /// When any limit is hit, the markdown produced so far is returned with a
+    /// `*[Content truncated: ...]*` line at the end.
+    pub max_conversion_time: Duration,
+
+    /// Main-content extractor run before conversion (default: `Heuristic`)
+    ///
+    /// `Compare` runs both extractors; [`convert_page_sync`] reports which
+    /// output was kept.
+    pub extraction_backend: ExtractionBackend,
+
+    /// Quality score below which the page is converted again unfiltered
+    /// (default: 0, no scoring)
+    ///
+    /// Scores run from 0 to 1; see [`quality::score_extraction`]. The
+    /// unfiltered conversion keeps navigation, headers, footers and widgets,
+    /// and replaces the extraction only when it scores better. Crawls use
+    /// [`DEFAULT_MIN_QUALITY_SCORE`].
+    pub min_quality_score: f64,
+
+    /// HTML comments to keep as `<!-- ... -->` (default: None, all dropped)
+    ///
+    /// A comment is kept when its trimmed text matches any pattern, e.g.
+    /// build markers or TODO annotations teams embed in their docs.
+    pub keep_comments: Option<RegexSet>,
+
+    /// How navigation, headers, footers and asides are filtered (default:
+    /// [`ChromeFilterLevel::Standard`], no patterns)
+    pub chrome_filter: ChromeFilter,
+
+    /// Character normalizations applied to prose; code is never touched
+    /// (default: none)
+    pub typography: Typography,
 }
 
 impl Default for ConversionOptions {
@@ -120,6 +186,14 @@ impl Default for ConversionOptions {
             process_headings: true,
             normalize_whitespace: true,
             base_url: None,
+            max_html_bytes: crate::utils::constants::MAX_PAGE_HTML_BYTES,
+            max_dom_nodes: DEFAULT_MAX_DOM_NODES,
+            max_conversion_time: DEFAULT_MAX_CONVERSION_TIME,
+            extraction_backend: ExtractionBackend::default(),
+            min_quality_score: 0.0,
+            keep_comments: None,
+            chrome_filter: ChromeFilter::default(),
+            typography: Typography::default(),
         }
     }
 }
@@ -146,6 +220,7 @@ impl ConversionOptions {
             process_headings: false,
             normalize_whitespace: false,
             base_url: None,
+            ..Self::default()
         }
     }
 
@@ -161,6 +236,7 @@ impl ConversionOptions {
             process_headings: true,
             normalize_whitespace: true,
             base_url: None,
+            ..Self::default()
         }
     }
 }
@@ -212,14 +288,119 @@ impl ConversionOptions {
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn convert_html_to_markdown_sync(html: &str, options: &ConversionOptions) -> Result<String> {
+    convert_page_sync(html, options).map(|conversion| conversion.markdown)
+}
+
+/// Markdown of a page together with the extractor that produced it
+#[derive(Debug, Clone)]
+pub struct Conversion {
+    pub markdown: String,
+    pub extraction: ExtractionReport,
+}
+
+/// Convert HTML to Markdown with the configured extraction backend
+///
+/// Like [`convert_html_to_markdown_sync`], but also reports which extractor's
+/// output was kept. `Readability` falls back to the heuristic output when no
+/// article is found; `Compare` keeps the Readability output only when it holds
+/// more text, i.e. when the heuristic filtering dropped content.
+pub fn convert_page_sync(html: &str, options: &ConversionOptions) -> Result<Conversion> {
+    let requested = options.extraction_backend;
+    let domain = options
+        .base_url
+        .as_deref()
+        .and_then(|base| url::Url::parse(base).ok())
+        .and_then(|base| base.host_str().map(str::to_string));
+    let article = || {
+        // Readability parses the document on its own, so apply the size cap first
+        let html = &html[..html.floor_char_boundary(options.max_html_bytes)];
+        readability::extract_article_for(html, domain.as_deref())
+    };
+
+    // The page's visible text is counted on whichever parse of the page ran
+    let (mut markdown, used, heuristic_chars, readability_chars, page_chars) = match requested {
+        ExtractionBackend::Heuristic => {
+            let (markdown, page_chars) = convert_document_with(html, options, false)?;
+            (markdown, ExtractionBackend::Heuristic, None, None, page_chars)
+        }
+        ExtractionBackend::Readability => match article() {
+            Some(article) => {
+                let markdown = convert_document(&article.html, options)?;
+                let chars = readability::text_chars(&markdown);
+                (markdown, ExtractionBackend::Readability, None, Some(chars), article.page_text_chars)
+            }
+            None => {
+                let (markdown, page_chars) = convert_document_with(html, options, false)?;
+                (markdown, ExtractionBackend::Heuristic, None, None, page_chars)
+            }
+        },
+        ExtractionBackend::Compare => {
+            let (heuristic, page_chars) = convert_document_with(html, options, false)?;
+            let heuristic_chars = readability::text_chars(&heuristic);
+            let readability = article().map(|article| convert_document(&article.html, options)).transpose()?;
+            let readability_chars = readability.as_deref().map(readability::text_chars);
+            match readability {
+                Some(markdown) if readability_chars > Some(heuristic_chars) => {
+                    (markdown, ExtractionBackend::Readability, Some(heuristic_chars), readability_chars, page_chars)
+                }
+                _ => (heuristic, ExtractionBackend::Heuristic, Some(heuristic_chars), readability_chars, page_chars),
+            }
+        }
+    };
+
+    let mut extraction = ExtractionReport {
+        requested,
+        used,
+        heuristic_chars,
+        readability_chars,
+        quality: None,
+        low_quality: false,
+        unfiltered_fallback: false,
+    };
+
+    // Retry pages whose extraction lost the content (or kept only chrome) unfiltered
+    if options.min_quality_score > 0.0 {
+        let score = quality::score_against(page_chars, &markdown);
+        extraction.quality = Some(score);
+        if score.is_low(options.min_quality_score) {
+            extraction.low_quality = true;
+            let (unfiltered, _) = convert_document_with(html, options, true)?;
+            let retry = quality::score_against(page_chars, &unfiltered);
+            if retry.score > score.score {
+                markdown = unfiltered;
+                extraction.quality = Some(retry);
+                extraction.unfiltered_fallback = true;
+            }
+        }
+    }
+
+    Ok(Conversion { markdown, extraction })
+}
+
+/// Convert a whole document: htmd conversion, then link resolution
+fn convert_document(html: &str, options: &ConversionOptions) -> Result<String> {
+    convert_document_with(html, options, false).map(|(markdown, _)| markdown)
+}
+
+/// [`convert_document`], optionally keeping page chrome the handlers filter,
+/// along with the letters and digits of the page's visible text
+fn convert_document_with(html: &str, options: &ConversionOptions, unfiltered: bool) -> Result<(String, usize)> {
     // HTML goes directly to htmd converter - element handlers filter non-content during DOM traversal
     let converter = MarkdownConverter::new()
         .with_preserve_tables(options.preserve_tables)
         .with_preserve_links(options.preserve_links)
         .with_preserve_images(options.preserve_images)
-        .with_code_highlighting(options.code_highlighting);
-
-    let markdown = converter.convert_sync(html)?;
+        .with_code_highlighting(options.code_highlighting)
+        .with_unfiltered(unfiltered)
+        .with_keep_comments(options.keep_comments.clone())
+        .with_chrome_filter(options.chrome_filter.clone())
+        .with_limits(ConversionLimits {
+            max_html_bytes: options.max_html_bytes,
+            max_dom_nodes: options.max_dom_nodes,
+            max_duration: options.max_conversion_time,
+        });
+
+    let (markdown, page_chars) = converter.convert_counting_sync(html)?;
 
     // Stage 2: Process markdown links (convert relative URLs to absolute)
     // URL resolution for scraped content - all other normalization is handled by htmd handlers
@@ -229,7 +410,10 @@ pub fn convert_html_to_markdown_sync(html: &str, options: &ConversionOptions) ->
         markdown
     };
 
-    Ok(markdown.trim().to_string())
+    // Stage 3: Opt-in character normalization of prose (code is left alone)
+    let markdown = typography::normalize(markdown, options.typography);
+
+    Ok((markdown.trim().to_string(), page_chars))
 }
 
 /// Convert HTML to Markdown asynchronously
@@ -269,6 +453,42 @@ pub async fn convert_html_to_markdown(html: &str, options: &ConversionOptions) -
         .map_err(|e| anyhow::anyhow!("HTML-to-Markdown conversion task panicked: {}", e))?
 }
 
+/// Heuristic and Readability conversions of one page, and how they differ
+#[derive(Debug, Clone)]
+pub struct BackendComparison {
+    pub heuristic: Conversion,
+    /// Falls back to the heuristic output when Readability finds no article;
+    /// see [`ExtractionReport::used`]
+    pub readability: Conversion,
+    /// Changes going from the heuristic to the Readability markdown
+    pub diff: MarkdownDiff,
+}
+
+/// Convert `html` with each extraction backend and diff the outputs
+///
+/// Shows what switching [`ConversionOptions::extraction_backend`] would change
+/// for a page before the default is switched; `tests/golden_corpus_test.rs`
+/// runs it over a fixture corpus. The backend set in `options` is ignored.
+pub fn compare_backends_sync(html: &str, options: &ConversionOptions) -> Result<BackendComparison> {
+    let with_backend = |extraction_backend| {
+        convert_page_sync(html, &ConversionOptions { extraction_backend, ..options.clone() })
+    };
+    let heuristic = with_backend(ExtractionBackend::Heuristic)?;
+    let readability = with_backend(ExtractionBackend::Readability)?;
+    let diff = diff_markdown(&heuristic.markdown, &readability.markdown, 3);
+    Ok(BackendComparison { heuristic, readability, diff })
+}
+
+/// Async [`convert_page_sync`]
+pub async fn convert_page(html: &str, options: &ConversionOptions) -> Result<Conversion> {
+    let html = Arc::<str>::from(html);
+    let options = options.clone();
+
+    tokio::task::spawn_blocking(move || convert_page_sync(&html, &options))
+        .await
+        .map_err(|e| anyhow::anyhow!("HTML-to-Markdown conversion task panicked: {}", e))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,12 +646,222 @@ mod tests {
             process_headings: false,
             normalize_whitespace: false,
             base_url: None,
+            ..ConversionOptions::default()
         };
 
         let html = "<html><body><h1>Test</h1><a href='#'>Link</a></body></html>";
         let result = convert_html_to_markdown_sync(html, &options);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_limits_truncate_with_marker() -> Result<()> {
+        let paragraphs: String = (0..1000).map(|i| format!("<p>Paragraph {i}</p>")).collect();
+        let html = format!("<html><body>{paragraphs}</body></html>");
+
+        let markdown = convert_html_to_markdown_sync(&html, &ConversionOptions::default())?;
+        assert!(markdown.contains("Paragraph 999"));
+        assert!(!markdown.contains("Content truncated"));
+
+        let by_nodes = ConversionOptions {
+            max_dom_nodes: 100,
+            ..ConversionOptions::default()
+        };
+        let markdown = convert_html_to_markdown_sync(&html, &by_nodes)?;
+        assert!(markdown.contains("Paragraph 0"));
+        assert!(!markdown.contains("Paragraph 999"));
+        assert!(markdown.ends_with("*[Content truncated: page has more than 100 DOM nodes]*"));
+
+        let by_bytes = ConversionOptions {
+            max_html_bytes: 200,
+            ..ConversionOptions::default()
+        };
+        let markdown = convert_html_to_markdown_sync(&html, &by_bytes)?;
+        assert!(markdown.contains("Paragraph 0"));
+        assert!(!markdown.contains("Paragraph 999"));
+        assert!(markdown.ends_with("only the first 200 were converted]*"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_low_quality_extraction_falls_back_to_unfiltered() -> Result<()> {
+        // The article sits in a container the widget filter drops
+        let article: String = (0..6)
+            .map(|i| format!("<p>Paragraph {i} carries the actual story that readers came to this page for.</p>"))
+            .collect();
+        let html = format!(
+            "<html><body><h1>Story</h1><div class=\"social-share-wrapper\">{article}</div></body></html>"
+        );
+
+        let filtered = convert_page_sync(&html, &ConversionOptions::default())?;
+        assert!(!filtered.markdown.contains("Paragraph 5"));
+        assert_eq!(filtered.extraction.quality, None);
+
+        let scored = ConversionOptions {
+            min_quality_score: DEFAULT_MIN_QUALITY_SCORE,
+            ..ConversionOptions::default()
+        };
+        let conversion = convert_page_sync(&html, &scored)?;
+        assert!(conversion.extraction.low_quality);
+        assert!(conversion.extraction.unfiltered_fallback);
+        assert!(conversion.markdown.contains("Paragraph 5"));
+
+        // A good extraction is scored but left alone
+        let conversion = convert_page_sync(&format!("<html><body><h1>Story</h1>{article}</body></html>"), &scored)?;
+        assert!(!conversion.extraction.low_quality);
+        assert!(conversion.extraction.quality.is_some_and(|quality| quality.score > DEFAULT_MIN_QUALITY_SCORE));
+        Ok(())
+    }
+
+    #[test]
+    fn test_blockquote_attribution_and_nesting() -> Result<()> {
+        let convert = |body: &str| convert_html_to_markdown_sync(&format!("<html><body>{body}</body></html>"), &ConversionOptions::default());
+
+        let markdown = convert(
+            "<blockquote cite=\"https://example.com/speech\"><p>To be, or not to be.</p>\
+             <footer>— <cite>Hamlet</cite></footer></blockquote>",
+        )?;
+        assert_eq!(markdown, "> To be, or not to be.\n> \n> — [*Hamlet*](https://example.com/speech)");
+
+        let markdown = convert(
+            "<figure><blockquote><p>Simplicity is prerequisite for reliability.</p></blockquote>\
+             <figcaption>Edsger Dijkstra</figcaption></figure>",
+        )?;
+        assert!(markdown.ends_with("> — Edsger Dijkstra"), "{markdown}");
+
+        let markdown = convert(
+            "<blockquote><p>Outer.</p><blockquote><p>Inner.</p><blockquote><p>Third.</p></blockquote>\
+             <footer>Inner source</footer></blockquote></blockquote>",
+        )?;
+        assert!(markdown.contains("> > > Third."), "{markdown}");
+        assert!(markdown.contains("> > — Inner source"), "{markdown}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_typography_options() -> Result<()> {
+        let html = "<html><body><p>It\u{2019}s \u{201C}done\u{201D} \u{2705}</p>\
+                    <pre><code>let s = \"\u{201C}kept\u{201D}\";</code></pre></body></html>";
+
+        let markdown = convert_html_to_markdown_sync(html, &ConversionOptions::default())?;
+        assert!(markdown.contains("It\u{2019}s \u{201C}done\u{201D} \u{2705}"), "{markdown}");
+
+        let options = ConversionOptions {
+            typography: Typography {
+                normalize_punctuation: true,
+                strip_emoji: true,
+                ..Typography::default()
+            },
+            ..ConversionOptions::default()
+        };
+        let markdown = convert_html_to_markdown_sync(html, &options)?;
+        assert!(markdown.starts_with("It's \"done\"\n"), "{markdown}");
+        assert!(markdown.contains("\u{201C}kept\u{201D}"), "{markdown}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_matching_comments() -> Result<()> {
+        let html = "<html><body><h1>Guide</h1><!-- docs-build: 2024.1 -->\
+                    <p>First paragraph of the guide.</p><!-- google_ad_section_start -->\
+                    <p>Second paragraph <!-- TODO: link the API --> of the guide.</p></body></html>";
+
+        let markdown = convert_html_to_markdown_sync(html, &ConversionOptions::default())?;
+        assert!(!markdown.contains("<!--"), "{markdown}");
+
+        let options = ConversionOptions {
+            keep_comments: Some(RegexSet::new(["^docs-build:", "^TODO"])?),
+            ..ConversionOptions::default()
+        };
+        let markdown = convert_html_to_markdown_sync(html, &options)?;
+        assert!(markdown.contains("<!-- docs-build: 2024.1 -->"), "{markdown}");
+        assert!(markdown.contains("<!-- TODO: link the API -->"), "{markdown}");
+        assert!(!markdown.contains("google_ad"), "{markdown}");
+        assert!(markdown.contains("First paragraph of the guide."), "{markdown}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_chrome_filter_levels_and_patterns() -> Result<()> {
+        let html = "<html><body><a href=\"#main\">Skip to main content</a>\
+                    <div class=\"sticky-top\"><a href=\"/\">Home</a> Sale ends soon</div>\
+                    <nav><a href=\"/a\">Alpha</a><a href=\"/b\">Beta</a></nav>\
+                    <main id=\"main\"><h1>Guide</h1><p>Body text of the guide.</p>\
+                    <aside class=\"share-links\"><p>Related: the scheduler notes.</p></aside>\
+                    <div class=\"announcement\"><p>New release out.</p></div></main>\
+                    <footer><p>Maintained by the docs team.</p></footer></body></html>";
+        let convert = |filter: ChromeFilter| {
+            let options = ConversionOptions { chrome_filter: filter, ..ConversionOptions::default() };
+            convert_html_to_markdown_sync(html, &options)
+        };
+
+        let standard = convert(ChromeFilter::default())?;
+        assert!(!standard.contains("Skip to"), "{standard}");
+        assert!(standard.contains("Sale ends soon"), "{standard}");
+        assert!(standard.contains("[Alpha](/a)"), "{standard}");
+        assert!(!standard.contains("scheduler notes"), "{standard}");
+        assert!(!standard.contains("docs team"), "{standard}");
+
+        let lenient = convert(ChromeFilter { level: ChromeFilterLevel::Lenient, ..ChromeFilter::default() })?;
+        assert!(lenient.contains("Related: the scheduler notes."), "{lenient}");
+        assert!(lenient.contains("Maintained by the docs team."), "{lenient}");
+        assert!(!lenient.contains("Skip to"), "{lenient}");
+
+        let strict = convert(ChromeFilter {
+            level: ChromeFilterLevel::Strict,
+            keep: Some(RegexSet::new(["^share-links$"])?),
+            drop: Some(RegexSet::new(["announcement"])?),
+        })?;
+        assert!(!strict.contains("Sale ends soon"), "{strict}");
+        assert!(!strict.contains("Alpha"), "{strict}");
+        assert!(strict.contains("Related: the scheduler notes."), "{strict}");
+        assert!(!strict.contains("New release out."), "{strict}");
+        assert!(strict.contains("Body text of the guide."), "{strict}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_extraction_backends_report_choice() -> Result<()> {
+        let paragraphs: String = (0..8)
+            .map(|i| format!("<p>Paragraph {i} of the story, with enough words, commas, and detail to score.</p>"))
+            .collect();
+        let html = format!(
+            "<html><body><div class=\"menu\"><a href=\"/\">Home</a></div><div>{paragraphs}</div></body></html>"
+        );
+
+        let heuristic = convert_page_sync(&html, &ConversionOptions::default())?;
+        assert_eq!(heuristic.extraction.used, ExtractionBackend::Heuristic);
+        assert_eq!(heuristic.extraction.readability_chars, None);
+
+        let readability = ConversionOptions {
+            extraction_backend: ExtractionBackend::Readability,
+            ..ConversionOptions::default()
+        };
+        let conversion = convert_page_sync(&html, &readability)?;
+        assert_eq!(conversion.extraction.used, ExtractionBackend::Readability);
+        assert!(conversion.markdown.contains("Paragraph 7"));
+        assert!(!conversion.markdown.contains("Home"));
+
+        // Too little text for an article: falls back to the whole page
+        let conversion = convert_page_sync("<html><body><p>Short.</p></body></html>", &readability)?;
+        assert_eq!(conversion.extraction.used, ExtractionBackend::Heuristic);
+
+        let compare = ConversionOptions {
+            extraction_backend: ExtractionBackend::Compare,
+            ..ConversionOptions::default()
+        };
+        let report = convert_page_sync(&html, &compare)?.extraction;
+        let (Some(heuristic_chars), Some(readability_chars)) = (report.heuristic_chars, report.readability_chars) else {
+            panic!("compare mode measures both backends: {report:?}");
+        };
+        let expected = if readability_chars > heuristic_chars {
+            ExtractionBackend::Readability
+        } else {
+            ExtractionBackend::Heuristic
+        };
+        assert_eq!(report.used, expected);
+        Ok(())
+    }
 }
 #[test]
 fn test_basic_link_full_pipeline() {
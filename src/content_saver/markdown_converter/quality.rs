@@ -0,0 +1,196 @@
+//! Quality scoring of extracted page content
+//!
+//! Extraction can go wrong in two directions: the filtering drops the body
+//! of the page (a news article inside a `div class="related-wrapper"`), or
+//! it keeps menus and link lists instead of prose. [`score_extraction`]
+//! measures both from the converted markdown and the page HTML, so pages
+//! that came out badly can be retried unfiltered and flagged in their
+//! metadata.
+
+use std::sync::LazyLock;
+
+use ego_tree::iter::Edge;
+use markup5ever_rcdom::{Node as DomNode, NodeData};
+use regex::Regex;
+use scraper::{Html, Node};
+use serde::{Deserialize, Serialize};
+
+/// Scores below this trigger the unfiltered retry (0 disables it)
+pub const DEFAULT_MIN_QUALITY_SCORE: f64 = 0.3;
+
+/// Share of the page's text an extraction needs for full coverage
+///
+/// Articles on busy pages are often a third of the visible text, so keeping
+/// a quarter of it is not penalized.
+const FULL_COVERAGE_RATIO: f64 = 0.25;
+
+/// Lines with fewer words than this count as boilerplate ("Share", "Menu")
+const MIN_CONTENT_LINE_WORDS: usize = 4;
+
+/// Elements whose text is never shown
+const INVISIBLE_TAGS: &[&str] = &["head", "script", "style", "noscript", "template", "svg"];
+
+static MARKDOWN_IMAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!\[[^\]]{0,200}\]\([^)]{0,2000}\)").expect("hardcoded regex is valid"));
+
+static MARKDOWN_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]{0,500})\]\([^)]{0,2000}\)").expect("hardcoded regex is valid"));
+
+static BOILERPLATE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)cookie|all rights reserved|©|copyright|subscribe|newsletter|sign (in|up)|log ?in|follow us|share (this|on)|skip to|privacy policy|terms of (use|service)",
+    )
+    .expect("hardcoded regex is valid")
+});
+
+/// How well an extraction kept the page's content
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionQuality {
+    /// Overall score from 0 (nothing useful) to 1
+    pub score: f64,
+    /// Text kept, relative to the visible text of the whole page
+    pub text_ratio: f64,
+    /// Share of the kept text that is link text
+    pub link_density: f64,
+    /// Share of lines that look like page chrome (short fragments, cookie
+    /// and copyright notices)
+    pub boilerplate_ratio: f64,
+}
+
+impl ExtractionQuality {
+    pub fn is_low(&self, min_score: f64) -> bool {
+        self.score < min_score
+    }
+}
+
+/// Score the markdown extracted from `html`
+///
+/// The score is the page coverage (text kept relative to
+/// [`FULL_COVERAGE_RATIO`] of the page text, capped at 1), scaled down by the
+/// link density and the boilerplate ratio.
+pub fn score_extraction(html: &str, markdown: &str) -> ExtractionQuality {
+    let page_chars = visible_text_chars(html);
+    score_against(page_chars, markdown)
+}
+
+/// [`score_extraction`] with the page's visible text already counted
+pub fn score_against(page_chars: usize, markdown: &str) -> ExtractionQuality {
+    let without_images = MARKDOWN_IMAGE.replace_all(markdown, "");
+    let text = MARKDOWN_LINK.replace_all(&without_images, "$1");
+    let text_chars = count_chars(&text);
+    let link_chars: usize = MARKDOWN_LINK
+        .captures_iter(&without_images)
+        .map(|caps| count_chars(&caps[1]))
+        .sum();
+
+    let text_ratio = if page_chars == 0 { 1.0 } else { (text_chars as f64 / page_chars as f64).min(1.0) };
+    let link_density = if text_chars == 0 { 0.0 } else { link_chars as f64 / text_chars as f64 };
+    let boilerplate_ratio = boilerplate_ratio(&text);
+
+    let coverage = if text_chars == 0 { 0.0 } else { (text_ratio / FULL_COVERAGE_RATIO).min(1.0) };
+    ExtractionQuality {
+        score: coverage * (1.0 - link_density) * (1.0 - boilerplate_ratio),
+        text_ratio,
+        link_density,
+        boilerplate_ratio,
+    }
+}
+
+/// Letters and digits of the text a browser would show for `html`
+pub fn visible_text_chars(html: &str) -> usize {
+    document_text_chars(&Html::parse_document(html))
+}
+
+/// [`visible_text_chars`] of an already parsed document
+pub(crate) fn document_text_chars(document: &Html) -> usize {
+    let mut hidden_depth = 0usize;
+    let mut chars = 0;
+    for edge in document.tree.root().traverse() {
+        match edge {
+            Edge::Open(node) => match node.value() {
+                Node::Element(element) if hidden_depth > 0 || INVISIBLE_TAGS.contains(&element.name()) => {
+                    hidden_depth += 1;
+                }
+                Node::Text(text) if hidden_depth == 0 => chars += count_chars(text),
+                _ => {}
+            },
+            Edge::Close(node) => {
+                if hidden_depth > 0 && node.value().is_element() {
+                    hidden_depth -= 1;
+                }
+            }
+        }
+    }
+    chars
+}
+
+/// [`visible_text_chars`] of a document parsed for the markdown conversion
+pub(crate) fn dom_text_chars(node: &DomNode) -> usize {
+    match &node.data {
+        NodeData::Text { contents } => count_chars(&contents.borrow()),
+        NodeData::Element { name, .. } if INVISIBLE_TAGS.contains(&&*name.local) => 0,
+        _ => node.children.borrow().iter().map(|child| dom_text_chars(child)).sum(),
+    }
+}
+
+fn count_chars(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// Share of prose lines (outside code blocks, tables and headings) that are
+/// fragments or boilerplate notices
+fn boilerplate_ratio(markdown: &str) -> f64 {
+    let mut in_code = false;
+    let mut lines = 0usize;
+    let mut boilerplate = 0usize;
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.is_empty() || line.starts_with('#') || line.starts_with('|') {
+            continue;
+        }
+        lines += 1;
+        let words = line.split_whitespace().filter(|word| word.chars().any(char::is_alphanumeric)).count();
+        if words < MIN_CONTENT_LINE_WORDS || BOILERPLATE_LINE.is_match(line) {
+            boilerplate += 1;
+        }
+    }
+    if lines == 0 { 0.0 } else { boilerplate as f64 / lines as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html5ever::tendril::TendrilSink;
+
+    #[test]
+    fn test_visible_text_skips_scripts_and_head() {
+        let html = "<html><head><title>Title</title></head><body><p>abc</p>\
+                    <script>var x = 1;</script><style>p{}</style><p>de f</p></body></html>";
+        assert_eq!(visible_text_chars(html), 6);
+
+        let dom = html5ever::parse_document(markup5ever_rcdom::RcDom::default(), Default::default()).one(html);
+        assert_eq!(dom_text_chars(&dom.document), 6);
+    }
+
+    #[test]
+    fn test_scores() {
+        let prose = "This paragraph explains the feature in plenty of words.\n\n\
+                     A second paragraph adds more explanation for the reader.";
+        let good = score_against(count_chars(prose), prose);
+        assert!(good.score > 0.9, "{good:?}");
+        assert_eq!(good.link_density, 0.0);
+
+        // A fraction of the page with nothing but links
+        let menu = "[Home](/)\n[Docs](/docs)\n[Blog](/blog)\n\nAll rights reserved © 2024";
+        let bad = score_against(2000, menu);
+        assert!(bad.score < DEFAULT_MIN_QUALITY_SCORE, "{bad:?}");
+        assert!(bad.link_density > 0.3);
+        assert_eq!(bad.boilerplate_ratio, 1.0);
+
+        assert_eq!(score_against(500, "").score, 0.0);
+    }
+}
@@ -0,0 +1,530 @@
+//! Readability-style main content extraction
+//!
+//! The default pipeline converts the whole page and relies on the htmd
+//! element handlers to drop navigation, footers and widgets by tag and class
+//! name. That misses the article body on news-style layouts, where content
+//! sits in generic `div`s next to equally generic boilerplate. This module
+//! implements the scoring of Mozilla's Readability instead: paragraphs award
+//! points to their ancestors, the best-scoring container (scaled down by its
+//! link density) wins, and related siblings are pulled in with it.
+//!
+//! [`ExtractionBackend`] selects between the two, or runs both and records
+//! which one was kept.
+//!
+//! Pages of one site share a template, so the container that won on one page
+//! is remembered per domain and scored first on the next (see
+//! [`extract_article_for`]); the whole body is only scored when that
+//! container is missing or no longer holds an article.
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use ego_tree::NodeId;
+use regex::Regex;
+use scraper::{ElementRef, Html};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::quality::{self, ExtractionQuality};
+
+/// Articles with less text than this are treated as not found
+const MIN_ARTICLE_CHARS: usize = 500;
+
+/// Paragraphs shorter than this don't contribute to scores
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Ancestor levels a paragraph's score propagates to
+const SCORE_ANCESTOR_LEVELS: usize = 5;
+
+/// Domains whose main-content container is remembered
+const MAIN_CONTENT_DOMAINS: NonZeroUsize = NonZeroUsize::new(1024).expect("nonzero");
+
+/// Container each domain's last article was found in
+static MAIN_CONTENT: LazyLock<Mutex<lru::LruCache<String, Container>>> =
+    LazyLock::new(|| Mutex::new(lru::LruCache::new(MAIN_CONTENT_DOMAINS)));
+
+static UNLIKELY_CANDIDATES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)-ad-|ai2html|banner|breadcrumbs|combx|comment|community|cover-wrap|disqus|extra|footer|gdpr|header|legends|menu|related|remark|replies|rss|shoutbox|sidebar|skyscraper|social|sponsor|supplemental|ad-break|agegate|pagination|pager|popup|yom-remote",
+    )
+    .expect("hardcoded regex is valid")
+});
+
+static MAYBE_CANDIDATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)and|article|body|column|content|main|mathjax|shadow").expect("hardcoded regex is valid")
+});
+
+static POSITIVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|text|blog|story")
+        .expect("hardcoded regex is valid")
+});
+
+static NEGATIVE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|com-|contact|footer|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|widget",
+    )
+    .expect("hardcoded regex is valid")
+});
+
+/// Elements that never hold article content
+const REMOVED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "nav", "aside", "footer", "form", "button", "object",
+    "embed", "dialog",
+];
+
+/// ARIA roles of page chrome
+const UNLIKELY_ROLES: &[&str] = &["menu", "menubar", "complementary", "navigation", "alert", "alertdialog", "dialog"];
+
+/// Children that keep a `div` from being scored like a paragraph
+const BLOCK_TAGS: &[&str] = &["blockquote", "dl", "div", "img", "ol", "p", "pre", "table", "ul"];
+
+/// Which main-content extractor feeds the markdown converter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractionBackend {
+    /// Convert the whole page and let the element handlers filter boilerplate
+    #[default]
+    Heuristic,
+    /// Convert the article found by Readability scoring, or the whole page
+    /// when none is found
+    Readability,
+    /// Run both and keep whichever output holds more text
+    Compare,
+}
+
+impl ExtractionBackend {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Heuristic => "heuristic",
+            Self::Readability => "readability",
+            Self::Compare => "compare",
+        }
+    }
+}
+
+impl std::fmt::Display for ExtractionBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which extractor produced a page's markdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionReport {
+    /// Backend that was configured
+    pub requested: ExtractionBackend,
+    /// Backend whose output was kept (`heuristic` or `readability`)
+    pub used: ExtractionBackend,
+    /// Letters and digits in the heuristic output, when it was produced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heuristic_chars: Option<usize>,
+    /// Letters and digits in the Readability output, when an article was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readability_chars: Option<usize>,
+    /// Quality of the saved markdown, when scoring is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<ExtractionQuality>,
+    /// The extraction scored below the quality threshold
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub low_quality: bool,
+    /// The saved markdown is the unfiltered whole-page conversion, kept
+    /// because it scored better than the low-quality extraction
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unfiltered_fallback: bool,
+}
+
+/// Amount of text in markdown, ignoring markup and whitespace
+pub fn text_chars(markdown: &str) -> usize {
+    markdown.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// HTML of the page's main article, or `None` when no container stands out
+///
+/// The result is a standalone document holding the winning container and
+/// its related siblings, preceded by the page's `h1` (or `<title>`) when the
+/// article doesn't include one.
+pub fn extract_article(html: &str) -> Option<String> {
+    extract_article_for(html, None).map(|article| article.html)
+}
+
+/// Article extracted from a page
+#[derive(Debug, Clone)]
+pub struct Article {
+    /// The article as a standalone HTML document
+    pub html: String,
+    /// Letters and digits of the whole page's visible text (see
+    /// [`visible_text_chars`](super::quality::visible_text_chars))
+    pub page_text_chars: usize,
+}
+
+/// [`extract_article`] for a page of `domain`, starting from the container
+/// the domain's previous article was found in
+pub fn extract_article_for(html: &str, domain: Option<&str>) -> Option<Article> {
+    let mut document = Html::parse_document(html);
+    let page_text_chars = quality::document_text_chars(&document);
+    let title = document_title(&document);
+    remove_unlikely_elements(&mut document);
+
+    let body = document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .find(|el| el.value().name() == "body")
+        .unwrap_or_else(|| document.root_element());
+    let known = domain
+        .and_then(|domain| main_content().get(domain).cloned())
+        .and_then(|container| container.find_in(body));
+    let (top, parts) = known
+        .and_then(|root| select_article(&document, root))
+        .or_else(|| select_article(&document, body))?;
+    if let Some(domain) = domain {
+        main_content().put(domain.to_string(), Container::of(top));
+    }
+
+    let mut article = String::from("<html><body><article>");
+    let has_h1 = parts
+        .iter()
+        .flat_map(|part| part.descendants().filter_map(ElementRef::wrap))
+        .any(|el| el.value().name() == "h1");
+    if let Some(title) = title.filter(|_| !has_h1) {
+        article.push_str("<h1>");
+        article.push_str(&escape_text(&title));
+        article.push_str("</h1>");
+    }
+    for part in parts {
+        article.push_str(&part.html());
+    }
+    article.push_str("</article></body></html>");
+    Some(Article {
+        html: article,
+        page_text_chars,
+    })
+}
+
+fn main_content() -> std::sync::MutexGuard<'static, lru::LruCache<String, Container>> {
+    MAIN_CONTENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Tag, `id` and `class` identifying a container across pages of one template
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Container {
+    tag: String,
+    id: Option<String>,
+    class: Option<String>,
+}
+
+impl Container {
+    fn of(el: ElementRef<'_>) -> Self {
+        let element = el.value();
+        Self {
+            tag: element.name().to_string(),
+            id: element.attr("id").map(str::to_string),
+            class: element.attr("class").map(str::to_string),
+        }
+    }
+
+    /// The one element under `root` matching this container, if exactly one does
+    fn find_in<'a>(&self, root: ElementRef<'a>) -> Option<ElementRef<'a>> {
+        if self.id.is_none() && self.class.is_none() {
+            return None;
+        }
+        let mut matches = root
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|el| Self::of(*el) == *self);
+        let found = matches.next()?;
+        matches.next().is_none().then_some(found)
+    }
+}
+
+/// Best container among the paragraphs under `root` and the siblings that
+/// belong with it, or `None` when they hold too little text
+fn select_article<'a>(document: &'a Html, root: ElementRef<'a>) -> Option<(ElementRef<'a>, Vec<ElementRef<'a>>)> {
+    let scores = score_candidates(root);
+    let (top_id, top_score) = scores
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(id, score)| (*id, *score))?;
+    let top = ElementRef::wrap(document.tree.get(top_id)?)?;
+
+    let parts = article_parts(top, top_score, &scores);
+    let text: usize = parts.iter().map(|part| normalized_text(*part).chars().count()).sum();
+    (text >= MIN_ARTICLE_CHARS).then_some((top, parts))
+}
+
+/// Text of the first `h1`, else of `<title>`
+fn document_title(document: &Html) -> Option<String> {
+    let elements = || document.root_element().descendants().filter_map(ElementRef::wrap);
+    elements()
+        .find(|el| el.value().name() == "h1")
+        .or_else(|| elements().find(|el| el.value().name() == "title"))
+        .map(normalized_text)
+        .filter(|title| !title.is_empty())
+}
+
+/// Detach scripts, page chrome and hidden elements before scoring
+fn remove_unlikely_elements(document: &mut Html) {
+    let doomed: Vec<NodeId> = document
+        .root_element()
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|el| is_unlikely(*el))
+        .map(|el| el.id())
+        .collect();
+    for id in doomed {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            node.detach();
+        }
+    }
+}
+
+fn is_unlikely(el: ElementRef<'_>) -> bool {
+    let element = el.value();
+    let tag = element.name();
+    if REMOVED_TAGS.contains(&tag) {
+        return true;
+    }
+    if element.attr("hidden").is_some() || element.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    if let Some(style) = element.attr("style") {
+        let style = style.replace(' ', "").to_ascii_lowercase();
+        if style.contains("display:none") || style.contains("visibility:hidden") {
+            return true;
+        }
+    }
+    if element.attr("role").is_some_and(|role| UNLIKELY_ROLES.contains(&role)) {
+        return true;
+    }
+    if matches!(tag, "html" | "body" | "a") || has_ancestor(el, &["table", "code"]) {
+        return false;
+    }
+    let match_string = format!("{} {}", element.attr("class").unwrap_or(""), element.attr("id").unwrap_or(""));
+    UNLIKELY_CANDIDATES.is_match(&match_string) && !MAYBE_CANDIDATE.is_match(&match_string)
+}
+
+fn has_ancestor(el: ElementRef<'_>, tags: &[&str]) -> bool {
+    el.ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| tags.contains(&ancestor.value().name()))
+}
+
+/// Readability scores of every container that holds scored paragraphs
+fn score_candidates(body: ElementRef<'_>) -> HashMap<NodeId, f64> {
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    let mut candidates = HashMap::new();
+    for el in body.descendants().filter_map(ElementRef::wrap) {
+        let tag = el.value().name();
+        let scorable = matches!(tag, "p" | "pre" | "td" | "section" | "h2" | "h3" | "h4" | "h5" | "h6")
+            || (tag == "div" && !has_block_children(el));
+        if !scorable {
+            continue;
+        }
+        let text = normalized_text(el);
+        let length = text.chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+
+        // One point for the paragraph, one per comma, one per 100 characters (up to 3)
+        let score = 1.0 + text.matches(',').count() as f64 + (length / 100).min(3) as f64;
+        let ancestors = el
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .filter(|ancestor| ancestor.value().name() != "html")
+            .take(SCORE_ANCESTOR_LEVELS);
+        for (level, ancestor) in ancestors.enumerate() {
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                level => level as f64 * 3.0,
+            };
+            *scores.entry(ancestor.id()).or_insert_with(|| initial_score(ancestor)) += score / divider;
+            candidates.insert(ancestor.id(), ancestor);
+        }
+    }
+
+    // Containers made of links (menus, tag clouds) lose most of their score
+    for (id, score) in &mut scores {
+        *score *= 1.0 - link_density(candidates[id]);
+    }
+    scores
+}
+
+fn has_block_children(el: ElementRef<'_>) -> bool {
+    el.descendants()
+        .skip(1)
+        .filter_map(ElementRef::wrap)
+        .any(|child| BLOCK_TAGS.contains(&child.value().name()))
+}
+
+/// Starting score of a container from its tag and class/id names
+fn initial_score(el: ElementRef<'_>) -> f64 {
+    let tag_score = match el.value().name() {
+        "div" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag_score + class_weight(el)
+}
+
+fn class_weight(el: ElementRef<'_>) -> f64 {
+    [el.value().attr("class"), el.value().attr("id")]
+        .into_iter()
+        .flatten()
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let mut weight = 0.0;
+            if NEGATIVE.is_match(name) {
+                weight -= 25.0;
+            }
+            if POSITIVE.is_match(name) {
+                weight += 25.0;
+            }
+            weight
+        })
+        .sum()
+}
+
+/// Share of an element's text that sits inside links
+fn link_density(el: ElementRef<'_>) -> f64 {
+    let total = normalized_text(el).chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let linked: usize = el
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| child.value().name() == "a")
+        .map(|link| normalized_text(link).chars().count())
+        .sum();
+    linked as f64 / total as f64
+}
+
+/// The top candidate plus siblings that belong to the same article
+fn article_parts<'a>(top: ElementRef<'a>, top_score: f64, scores: &HashMap<NodeId, f64>) -> Vec<ElementRef<'a>> {
+    let Some(parent) = top.parent() else {
+        return vec![top];
+    };
+    let threshold = (top_score * 0.2).max(10.0);
+    let top_class = top.value().attr("class").filter(|class| !class.is_empty());
+
+    parent
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|sibling| {
+            if sibling.id() == top.id() {
+                return true;
+            }
+            let bonus = if top_class.is_some() && sibling.value().attr("class") == top_class {
+                top_score * 0.2
+            } else {
+                0.0
+            };
+            if scores.get(&sibling.id()).is_some_and(|score| score + bonus >= threshold) {
+                return true;
+            }
+            if sibling.value().name() != "p" {
+                return false;
+            }
+            let text = normalized_text(*sibling);
+            let length = text.chars().count();
+            let density = link_density(*sibling);
+            (length > 80 && density < 0.25)
+                || (length > 0 && length <= 80 && density == 0.0 && (text.contains(". ") || text.ends_with('.')))
+        })
+        .collect()
+}
+
+fn normalized_text(el: ElementRef<'_>) -> String {
+    el.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" ")
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paragraph(topic: &str) -> String {
+        format!(
+            "<p>The {topic} story continues here, with details, quotes, and context that a reader \
+             came for, written in full sentences so it scores like article text.</p>"
+        )
+    }
+
+    #[test]
+    fn test_extract_article_picks_content_over_chrome() {
+        let body: String = ["council", "budget", "school", "transit", "housing"]
+            .iter()
+            .map(|topic| paragraph(topic))
+            .collect();
+        let html = format!(
+            r#"<html><head><title>Local news</title></head><body>
+                <div class="site-header"><a href="/">Home</a> <a href="/world">World</a></div>
+                <div class="promo-rail"><a href="/a">Read this</a><a href="/b">And this</a></div>
+                <div class="x7f"><h1>Council passes budget</h1><div class="y2">{body}</div></div>
+                <div class="share-tools">Share on social networks and tell your friends about it today</div>
+            </body></html>"#
+        );
+
+        let article = extract_article(&html).expect("article found");
+        assert!(article.contains("Council passes budget"));
+        assert!(article.contains("housing story"));
+        assert!(!article.contains("Read this"));
+        assert!(!article.contains("Share on social"));
+    }
+
+    #[test]
+    fn test_main_content_container_remembered_per_domain() {
+        let body: String = ["council", "budget", "school", "transit", "housing"]
+            .iter()
+            .map(|topic| paragraph(topic))
+            .collect();
+        let page = |container: &str, sidebar: &str| {
+            format!(
+                r#"<html><body><div class="layout">{container}<div class="column">{sidebar}</div></div></body></html>"#
+            )
+        };
+        let domain = Some("news.template.test");
+        let cached = || main_content().get("news.template.test").cloned();
+
+        let first = page(&format!(r#"<div id="story">{body}</div>"#), "");
+        assert!(extract_article_for(&first, domain).is_some());
+        let container = cached().expect("container remembered");
+        assert_eq!((container.tag.as_str(), container.id.as_deref()), ("div", Some("story")));
+
+        // The remembered container wins over a longer sidebar of article-like text
+        let sidebar: String = ["sports", "weather", "arts", "science", "travel", "food"]
+            .iter()
+            .map(|topic| paragraph(topic))
+            .collect();
+        let second = page(&format!(r#"<div id="story">{body}</div>"#), &sidebar);
+        let article = extract_article_for(&second, domain).expect("article found").html;
+        assert!(article.contains("council story") && !article.contains("sports story"));
+        assert!(extract_article(&second).is_some_and(|article| article.contains("sports story")));
+
+        // Without the container the whole body is scored again
+        let redesigned = page(&format!(r#"<div class="post-body">{body}</div>"#), "");
+        assert!(extract_article_for(&redesigned, domain).is_some());
+        assert_eq!(cached().and_then(|container| container.class).as_deref(), Some("post-body"));
+    }
+
+    #[test]
+    fn test_extract_article_gives_up_on_short_pages() {
+        let html = "<html><body><nav><a href='/'>Home</a></nav><p>Just a short note.</p></body></html>";
+        assert_eq!(extract_article(html), None);
+    }
+
+    #[test]
+    fn test_backend_names() {
+        let backend: ExtractionBackend = serde_json::from_str("\"readability\"").unwrap();
+        assert_eq!(backend, ExtractionBackend::Readability);
+        assert_eq!(ExtractionBackend::Compare.to_string(), "compare");
+    }
+}
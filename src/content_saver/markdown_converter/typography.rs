@@ -0,0 +1,395 @@
+//! Character-level normalization of converted markdown
+//!
+//! Runs after conversion, on prose only: fenced and indented code blocks and
+//! inline code spans pass through untouched so code samples keep their exact
+//! characters. Code blocks are recognized by the CommonMark rules, including
+//! inside blockquotes and list items. Every step is opt-in through
+//! [`Typography`]:
+//!
+//! - numeric character references left in the text (`&#8217;`, `&#x2014;`)
+//!   are decoded, except those that would produce markup (`<`, `>`, `&`)
+//! - typographic quotes, dashes, ellipses and non-breaking spaces become
+//!   their ASCII equivalents
+//! - emoji (with their modifiers and joiners) are removed
+
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+static NUMERIC_ENTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"&#(?:([0-9]{1,7})|[xX]([0-9a-fA-F]{1,6}));").expect("hardcoded regex is valid")
+});
+
+/// Character normalizations applied to the prose of converted markdown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Typography {
+    /// Decode numeric character references left in the text, such as
+    /// `&#8217;` (default: false, kept as written)
+    ///
+    /// References to `<`, `>` and `&` stay encoded so they cannot form markup.
+    pub decode_numeric_entities: bool,
+    /// Replace typographic quotes, dashes, ellipses and non-breaking spaces
+    /// with ASCII (default: false)
+    pub normalize_punctuation: bool,
+    /// Remove emoji and their modifiers (default: false)
+    pub strip_emoji: bool,
+}
+
+impl Typography {
+    /// Whether no normalization is enabled
+    #[must_use]
+    pub fn is_noop(&self) -> bool {
+        !(self.decode_numeric_entities || self.normalize_punctuation || self.strip_emoji)
+    }
+}
+
+/// Apply `typography` to the prose of `markdown`
+pub(crate) fn normalize(markdown: String, typography: Typography) -> String {
+    if typography.is_noop() {
+        return markdown;
+    }
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut blocks = CodeBlocks::default();
+    for line in markdown.split_inclusive('\n') {
+        if blocks.is_code(line) {
+            output.push_str(line);
+        } else {
+            normalize_line(line, typography, &mut output);
+        }
+    }
+    output
+}
+
+/// An open fenced code block: fence character and length of the opening run
+#[derive(Debug, Clone, Copy)]
+struct Fence {
+    ch: char,
+    len: usize,
+}
+
+/// Line-by-line tracker of CommonMark code blocks
+///
+/// Blockquote markers are stripped before a line is classified, and the
+/// content column of the list item being continued is subtracted from its
+/// indentation, so fences and indented code nested in those containers are
+/// found too.
+#[derive(Debug)]
+struct CodeBlocks {
+    fence: Option<Fence>,
+    /// Content column of the list item the current lines belong to
+    list_column: usize,
+    /// Inside an indented code block
+    indented: bool,
+    /// The previous line was blank (or there was none), so an indented
+    /// line starts a code block rather than continuing a paragraph
+    after_blank: bool,
+}
+
+impl Default for CodeBlocks {
+    fn default() -> Self {
+        Self {
+            fence: None,
+            list_column: 0,
+            indented: false,
+            after_blank: true,
+        }
+    }
+}
+
+impl CodeBlocks {
+    /// Whether `line` belongs to a code block (fences included)
+    fn is_code(&mut self, line: &str) -> bool {
+        let content = strip_blockquote(line.trim_end_matches(['\n', '\r']));
+        let (indent, rest) = indentation(content);
+
+        if let Some(fence) = self.fence {
+            let relative = indent.saturating_sub(self.list_column);
+            if relative <= 3 && closes_fence(rest, fence) {
+                self.fence = None;
+            }
+            return true;
+        }
+
+        if rest.is_empty() {
+            self.after_blank = true;
+            return self.indented;
+        }
+        if indent < self.list_column {
+            if !self.after_blank && opens_fence(rest).is_none() && list_marker(rest).is_none() {
+                // Lazy continuation of the item's paragraph
+                return false;
+            }
+            self.list_column = 0;
+        }
+        let relative = indent - self.list_column;
+
+        if relative >= 4 && (self.indented || self.after_blank) {
+            self.indented = true;
+            self.after_blank = false;
+            return true;
+        }
+        self.indented = false;
+        self.after_blank = false;
+
+        if relative <= 3 {
+            if let Some(fence) = opens_fence(rest) {
+                self.fence = Some(fence);
+                return true;
+            }
+            if let Some((marker, spacing)) = list_marker(rest) {
+                self.list_column = indent + marker + spacing;
+                // The item may start with a fence: "- ```rust"
+                let item = rest[marker..].trim_start_matches([' ', '\t']);
+                if let Some(fence) = opens_fence(item) {
+                    self.fence = Some(fence);
+                    return true;
+                }
+                self.after_blank = item.is_empty();
+            }
+        }
+        false
+    }
+}
+
+/// `line` without its blockquote markers (`>` plus one optional space)
+fn strip_blockquote(mut line: &str) -> &str {
+    loop {
+        let (indent, rest) = indentation(line);
+        match rest.strip_prefix('>') {
+            Some(quoted) if indent <= 3 => line = quoted.strip_prefix(' ').unwrap_or(quoted),
+            _ => return line,
+        }
+    }
+}
+
+/// Indentation width of `line` (tabs to the next multiple of 4) and the rest
+fn indentation(line: &str) -> (usize, &str) {
+    let mut width = 0;
+    for (i, c) in line.char_indices() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += 4 - width % 4,
+            _ => return (width, &line[i..]),
+        }
+    }
+    (width, "")
+}
+
+/// Fence opened by `rest`: three or more backticks or tildes; a backtick
+/// fence's info string may not contain backticks
+fn opens_fence(rest: &str) -> Option<Fence> {
+    let ch = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = rest.chars().take_while(|c| *c == ch).count();
+    if len < 3 || (ch == '`' && rest[len..].contains('`')) {
+        return None;
+    }
+    Some(Fence { ch, len })
+}
+
+/// Whether `rest` closes `fence`: at least as many of the same character,
+/// followed by nothing but whitespace
+fn closes_fence(rest: &str, fence: Fence) -> bool {
+    let len = rest.chars().take_while(|c| *c == fence.ch).count();
+    len >= fence.len && rest[len..].trim().is_empty()
+}
+
+/// Width of the list marker starting `rest` (`-`, `*`, `+`, `1.`, `1)`) and
+/// of the spacing after it, counted as CommonMark does for the item's
+/// content column
+fn list_marker(rest: &str) -> Option<(usize, usize)> {
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    let marker = match rest.as_bytes().get(digits)? {
+        b'-' | b'*' | b'+' if digits == 0 => 1,
+        b'.' | b')' if (1..=9).contains(&digits) => digits + 1,
+        _ => return None,
+    };
+    let after = &rest[marker..];
+    if after.is_empty() {
+        return Some((marker, 1));
+    }
+    let (spacing, item) = indentation(after);
+    match spacing {
+        0 => None,
+        // Five or more spaces start indented code inside the item
+        1..=4 if !item.is_empty() => Some((marker, spacing)),
+        _ => Some((marker, 1)),
+    }
+}
+
+/// Normalize one prose line, leaving inline code spans as they are
+fn normalize_line(line: &str, typography: Typography, output: &mut String) {
+    let mut rest = line;
+    while let Some(start) = rest.find('`') {
+        let run = rest[start..].chars().take_while(|c| *c == '`').count();
+        let delimiter = &rest[start..start + run];
+        let Some(close) = rest[start + run..].find(delimiter) else {
+            break;
+        };
+        let end = start + run + close + run;
+        output.push_str(&normalize_text(&rest[..start], typography));
+        output.push_str(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    output.push_str(&normalize_text(rest, typography));
+}
+
+fn normalize_text(text: &str, typography: Typography) -> Cow<'_, str> {
+    let mut text = Cow::Borrowed(text);
+    if typography.decode_numeric_entities && text.contains("&#") {
+        text = Cow::Owned(NUMERIC_ENTITY.replace_all(&text, decode_entity).into_owned());
+    }
+    if typography.normalize_punctuation && !text.is_ascii() {
+        text = Cow::Owned(ascii_punctuation(&text));
+    }
+    if typography.strip_emoji && !text.is_ascii() {
+        text = Cow::Owned(strip_emoji(&text));
+    }
+    text
+}
+
+fn decode_entity(caps: &Captures) -> String {
+    let code = match (caps.get(1), caps.get(2)) {
+        (Some(decimal), _) => decimal.as_str().parse().ok(),
+        (_, Some(hex)) => u32::from_str_radix(hex.as_str(), 16).ok(),
+        _ => None,
+    };
+    match code.and_then(char::from_u32) {
+        Some(c) if !matches!(c, '<' | '>' | '&') && (!c.is_control() || c == '\t') => c.to_string(),
+        _ => caps[0].to_string(),
+    }
+}
+
+fn ascii_punctuation(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => output.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{00AB}' | '\u{00BB}' => output.push('"'),
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2212}' => output.push('-'),
+            '\u{2014}' | '\u{2015}' => output.push_str("--"),
+            '\u{2026}' => output.push_str("..."),
+            '\u{00A0}' | '\u{2007}' | '\u{2009}' | '\u{200A}' | '\u{202F}' => output.push(' '),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Pictographic code points (emoticons, symbols, flags, dingbats)
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x3030 | 0x303D | 0x3297 | 0x3299
+    )
+}
+
+/// Code points that only modify a preceding emoji
+fn is_emoji_modifier(c: char) -> bool {
+    matches!(c as u32, 0x200D | 0xFE0E | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F)
+}
+
+fn strip_emoji(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut after_emoji = false;
+    for c in text.chars() {
+        if is_emoji(c) || (after_emoji && is_emoji_modifier(c)) {
+            after_emoji = true;
+            continue;
+        }
+        // Drop the space the emoji was separated by ("🚀 Launch" -> "Launch",
+        // "Done ✅" -> "Done")
+        if after_emoji && c == ' ' && (output.is_empty() || output.ends_with(' ')) {
+            after_emoji = false;
+            continue;
+        }
+        if after_emoji && c == '\n' {
+            output.truncate(output.trim_end_matches(' ').len());
+        }
+        after_emoji = false;
+        output.push(c);
+    }
+    if after_emoji {
+        output.truncate(output.trim_end_matches(' ').len());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: Typography = Typography {
+        decode_numeric_entities: true,
+        normalize_punctuation: true,
+        strip_emoji: true,
+    };
+
+    #[test]
+    fn test_prose_is_normalized() {
+        let markdown = "## 🚀 Getting started\n\nIt\u{2019}s \u{201C}fast\u{201D} \u{2014} really\u{2026} \
+                        &#8220;quoted&#x201D; &#60;tag&#62; 👍🏽 done\n";
+        assert_eq!(
+            normalize(markdown.to_string(), ALL),
+            "## Getting started\n\nIt's \"fast\" -- really... \"quoted\" &#60;tag&#62; done\n"
+        );
+
+        let only_entities = Typography { decode_numeric_entities: true, ..Typography::default() };
+        assert_eq!(normalize("Caf&#233; &#x2014; ok".to_string(), only_entities), "Café — ok");
+        assert_eq!(normalize("Caf&#233; 🚀".to_string(), Typography::default()), "Caf&#233; 🚀");
+    }
+
+    #[test]
+    fn test_code_is_untouched() {
+        let markdown = "Use `\u{201C}raw\u{201D} &#38; 🚀` here \u{2014} and ``a ` \u{2019}b``.\n\n\
+                        ```js\nconst s = \u{201C}smart\u{201D}; // 🚀 &#169;\n```\n\n\
+                        > ~~~\n> \u{2018}quoted code\u{2019}\n> ~~~\n\nAfter \u{2019}fence\u{2019}\n";
+        assert_eq!(
+            normalize(markdown.to_string(), ALL),
+            "Use `\u{201C}raw\u{201D} &#38; 🚀` here -- and ``a ` \u{2019}b``.\n\n\
+             ```js\nconst s = \u{201C}smart\u{201D}; // 🚀 &#169;\n```\n\n\
+             > ~~~\n> \u{2018}quoted code\u{2019}\n> ~~~\n\nAfter 'fence'\n"
+        );
+    }
+
+    #[test]
+    fn test_indented_code_is_untouched() {
+        let markdown = "Intro \u{2014} text\n\n    let s = \u{201C}x\u{201D};\n\tlet t = \u{2019};\n\n\
+                        After \u{2014} code\n    continued \u{2014} paragraph\n";
+        assert_eq!(
+            normalize(markdown.to_string(), ALL),
+            "Intro -- text\n\n    let s = \u{201C}x\u{201D};\n\tlet t = \u{2019};\n\n\
+             After -- code\n    continued -- paragraph\n"
+        );
+
+        // Code indented inside a list item, and list text that is not code
+        let markdown = "1. Step \u{2014} one\n\n       \u{201C}code\u{201D}\n\n   More \u{2014} text\n";
+        assert_eq!(
+            normalize(markdown.to_string(), ALL),
+            "1. Step -- one\n\n       \u{201C}code\u{201D}\n\n   More -- text\n"
+        );
+    }
+
+    #[test]
+    fn test_fences_follow_commonmark() {
+        // A fence line with an info string does not close the block, and a
+        // shorter run does not either
+        let markdown = "````\n```rust\n\u{2014}\n```\n\u{2014}\n````\n\u{2014}\n";
+        assert_eq!(normalize(markdown.to_string(), ALL), "````\n```rust\n\u{2014}\n```\n\u{2014}\n````\n--\n");
+
+        // Backticks indented four spaces after a paragraph, or with a
+        // backtick in the info string, are not a fence
+        let markdown = "Text\n    ```\n\u{2014}\n``` a`b\n\u{2014}\n";
+        assert_eq!(normalize(markdown.to_string(), ALL), "Text\n    ```\n--\n``` a`b\n--\n");
+
+        // Fences opened on a list marker or nested in the item
+        let markdown = "- ```\n  \u{2014}\n  ```\n- Item \u{2014}\n\n  ~~~\n  \u{2014}\n  ~~~\n\u{2014}\n";
+        assert_eq!(
+            normalize(markdown.to_string(), ALL),
+            "- ```\n  \u{2014}\n  ```\n- Item --\n\n  ~~~\n  \u{2014}\n  ~~~\n--\n"
+        );
+    }
+}
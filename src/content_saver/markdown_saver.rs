@@ -5,7 +5,7 @@ use crate::search::IndexingSender;
 use crate::search::MessagePriority;
 use crate::utils::{ensure_domain_gitignore, get_mirror_path};
 
-use super::compression::save_compressed_file;
+use super::compression::{ResponseValidators, save_compressed_file};
 
 /// Save markdown content to disk with optional search indexing
 ///
@@ -16,10 +16,12 @@ use super::compression::save_compressed_file;
 /// * `output_dir` - Base directory for mirrored content
 /// * `priority` - Indexing priority for search
 /// * `indexing_sender` - Optional channel for triggering search indexing
+/// * `response` - Validators and `Date` of the page's response, recorded in the cache metadata
 ///
 /// # Returns
 ///
 /// * `Result<()>` - Result of the save operation
+#[allow(clippy::too_many_arguments)]
 pub async fn save_markdown_content(
     markdown_content: String,
     url: String,
@@ -28,6 +30,7 @@ pub async fn save_markdown_content(
     indexing_sender: Option<Arc<IndexingSender>>,
     compress: bool,
     compression_threshold: usize,
+    response: ResponseValidators,
 ) -> Result<()> {
     let path = get_mirror_path(&url, &output_dir, "index.md").await?;
 
@@ -46,6 +49,7 @@ pub async fn save_markdown_content(
         markdown_content.into_bytes(),
         &path,
         "text/markdown",
+        response,
         compress,
         compression_threshold,
     )
@@ -8,14 +8,16 @@ mod indexing;
 mod json_saver;
 pub mod markdown_converter;
 mod markdown_saver;
+pub mod sink;
 
 // Re-export public API from cache_check module
 pub use cache_check::{
-    check_etag_from_events, extract_etag_from_headers, get_mirror_path_sync, read_cached_etag,
+    CacheCheck, check_etag_from_events, extract_etag_from_headers, extract_validators_from_headers,
+    get_mirror_path_sync, read_cached_etag, read_cached_metadata,
 };
 
 // Re-export public API from compression module
-pub use compression::{CacheMetadata, save_compressed_file};
+pub use compression::{CacheMetadata, ResponseValidators, save_compressed_file};
 
 // Re-export public API from html_saver module
 pub use html_saver::{save_html_content, save_html_content_with_resources};
@@ -24,7 +26,7 @@ pub use html_saver::{save_html_content, save_html_content_with_resources};
 pub use indexing::optimize_search_index;
 
 // Re-export public API from json_saver module
-pub use json_saver::{save_json_data, save_page_data};
+pub use json_saver::{save_json_data, save_page_data, save_structured_data};
 
 // Re-export public API from markdown_saver module
 pub use markdown_saver::save_markdown_content;
@@ -0,0 +1,209 @@
+//! Where saved crawl output is written
+//!
+//! Savers hand finished files to an [`OutputSink`] instead of writing them
+//! directly. The [`FilesystemSink`] is the default and writes atomically into
+//! the crawl's output directory. With the `object-store` feature an
+//! `s3://` or `gs://` output URL adds an [`ObjectStoreSink`], which keeps the
+//! local copy (link rewriting, cache checks and search indexing read it) and
+//! uploads every saved file to the bucket under the same relative path.
+//!
+//! A crawl registers its sink for its output directory
+//! ([`register_output_sink`]); [`output_sink_for`] picks the sink of the
+//! directory a path lives in, so savers need no extra arguments. Code that
+//! changes output files without a saver (link rewrites, assets, sitemaps)
+//! reports them with [`note_output_changed`], and [`OutputSink::flush`] waits
+//! until every change is persisted.
+
+#[cfg(feature = "object-store")]
+mod object_store;
+
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, LazyLock};
+
+use anyhow::{Context, Result, bail};
+use parking_lot::RwLock;
+use tempfile::NamedTempFile;
+
+#[cfg(feature = "object-store")]
+pub use self::object_store::{ObjectStoreLocation, ObjectStoreService, ObjectStoreSink};
+
+/// Boxed future returned by `OutputSink` methods.
+pub type SinkFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Destination of saved output files.
+pub trait OutputSink: Send + Sync {
+    /// Store `bytes` as the file at `path`, replacing it atomically.
+    fn write<'a>(&'a self, path: &'a Path, bytes: Vec<u8>) -> SinkFuture<'a, ()>;
+
+    /// Record that the file or directory at `path` was changed without going
+    /// through [`Self::write`].
+    fn changed(&self, _path: &Path) {}
+
+    /// Wait until every written or changed file is persisted.
+    fn flush(&self) -> SinkFuture<'_, ()> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Writes files into the local output directory.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FilesystemSink;
+
+impl FilesystemSink {
+    /// Write `bytes` to a temporary file next to `path` and rename it into place.
+    pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+        let parent = path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Path has no parent directory"))?;
+        let mut temp_file = NamedTempFile::new_in(parent)?;
+        temp_file.write_all(bytes)?;
+        temp_file.persist(path)?;
+        Ok(())
+    }
+}
+
+impl OutputSink for FilesystemSink {
+    fn write<'a>(&'a self, path: &'a Path, bytes: Vec<u8>) -> SinkFuture<'a, ()> {
+        let path = path.to_path_buf();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || Self::write_atomic(&path, &bytes))
+                .await
+                .context("File write task panicked")?
+        })
+    }
+}
+
+/// Open the sink for a crawl writing to `output_dir`.
+///
+/// Without `output_url` this is the [`FilesystemSink`]. `s3://` and `gs://`
+/// URLs upload to object storage and require the `object-store` feature.
+pub fn open_output_sink(output_dir: &Path, output_url: Option<&str>) -> Result<Arc<dyn OutputSink>> {
+    match output_url {
+        None => Ok(Arc::new(FilesystemSink)),
+        Some(url) if url.starts_with("s3://") || url.starts_with("gs://") => {
+            #[cfg(feature = "object-store")]
+            {
+                let location = ObjectStoreLocation::parse(url)?;
+                Ok(Arc::new(ObjectStoreSink::new(location, output_dir.to_path_buf())?))
+            }
+            #[cfg(not(feature = "object-store"))]
+            {
+                let _ = output_dir;
+                bail!("Object storage output requires building with the `object-store` feature")
+            }
+        }
+        Some(url) => {
+            let scheme = url.split(':').next().unwrap_or(url);
+            bail!("Unsupported output URL scheme: {scheme}")
+        }
+    }
+}
+
+/// Output directory of a running crawl and its sink
+type RegisteredSink = (PathBuf, Arc<dyn OutputSink>);
+
+/// Sinks of running crawls
+static SINKS: LazyLock<RwLock<Vec<RegisteredSink>>> = LazyLock::new(RwLock::default);
+
+/// Keeps a sink registered for an output directory until dropped.
+#[must_use = "the sink is unregistered when the registration is dropped"]
+pub struct SinkRegistration {
+    root: PathBuf,
+    sink: Arc<dyn OutputSink>,
+}
+
+impl Drop for SinkRegistration {
+    fn drop(&mut self) {
+        let mut sinks = SINKS.write();
+        if let Some(pos) = sinks
+            .iter()
+            .position(|(root, sink)| *root == self.root && Arc::ptr_eq(sink, &self.sink))
+        {
+            sinks.remove(pos);
+        }
+    }
+}
+
+/// Route files saved under `root` to `sink` while the registration lives.
+pub fn register_output_sink(root: &Path, sink: Arc<dyn OutputSink>) -> SinkRegistration {
+    SINKS.write().push((root.to_path_buf(), sink.clone()));
+    SinkRegistration {
+        root: root.to_path_buf(),
+        sink,
+    }
+}
+
+/// The sink for a file at `path`: that of the innermost registered directory
+/// containing it, or the [`FilesystemSink`].
+pub fn output_sink_for(path: &Path) -> Arc<dyn OutputSink> {
+    SINKS
+        .read()
+        .iter()
+        .filter(|(root, _)| path.starts_with(root))
+        .max_by_key(|(root, _)| root.components().count())
+        .map_or_else(|| Arc::new(FilesystemSink) as Arc<dyn OutputSink>, |(_, sink)| sink.clone())
+}
+
+/// Report a file or directory changed outside the savers to the sink of the
+/// crawl it belongs to.
+pub fn note_output_changed(path: &Path) {
+    output_sink_for(path).changed(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct CountingSink(AtomicUsize);
+
+    impl OutputSink for CountingSink {
+        fn write<'a>(&'a self, _path: &'a Path, _bytes: Vec<u8>) -> SinkFuture<'a, ()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_routing_by_directory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let site = temp_dir.path().join("site");
+        let docs = site.join("docs");
+
+        let outer = Arc::new(CountingSink::default());
+        let inner = Arc::new(CountingSink::default());
+        let outer_registration = register_output_sink(&site, outer.clone());
+        let inner_registration = register_output_sink(&docs, inner.clone());
+
+        output_sink_for(&site.join("index.md")).write(&site.join("index.md"), Vec::new()).await?;
+        output_sink_for(&docs.join("a.md")).write(&docs.join("a.md"), Vec::new()).await?;
+        assert_eq!(outer.0.load(Ordering::Relaxed), 1);
+        assert_eq!(inner.0.load(Ordering::Relaxed), 1);
+
+        // Once unregistered, files fall back to the local filesystem
+        drop(inner_registration);
+        drop(outer_registration);
+        std::fs::create_dir_all(&docs)?;
+        output_sink_for(&docs.join("b.md")).write(&docs.join("b.md"), b"# b".to_vec()).await?;
+        assert_eq!(inner.0.load(Ordering::Relaxed), 1);
+        assert_eq!(std::fs::read(docs.join("b.md"))?, b"# b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_output_sink_schemes() {
+        let dir = Path::new("/tmp/out");
+        assert!(open_output_sink(dir, None).is_ok());
+        let err = open_output_sink(dir, Some("ftp://bucket/prefix")).err().unwrap();
+        assert_eq!(err.to_string(), "Unsupported output URL scheme: ftp");
+        if !cfg!(feature = "object-store") {
+            let err = open_output_sink(dir, Some("s3://bucket/prefix")).err().unwrap();
+            assert!(err.to_string().contains("`object-store` feature"), "{err}");
+        }
+    }
+}
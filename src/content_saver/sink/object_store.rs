@@ -0,0 +1,418 @@
+//! Object storage sink
+//!
+//! Uploads go through the [`object_store`] crate, which signs requests for
+//! AWS S3, S3-compatible services (MinIO, R2, ...) and Google Cloud Storage,
+//! retries transient failures and switches to multipart uploads for large
+//! files. Objects are keyed `<prefix>/<path relative to the output
+//! directory>`, mirroring the local layout.
+//!
+//! Output URLs look like `s3://bucket/prefix?region=eu-west-1` or
+//! `gs://bucket/prefix`. `s3://` reads its configuration from the `AWS_*`
+//! environment variables (`AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+//! `AWS_SESSION_TOKEN`, `AWS_REGION`, `AWS_ENDPOINT_URL`, ...); `?endpoint=`
+//! selects an S3-compatible service and switches to path-style addressing.
+//! `gs://` only reads the `GOOGLE_*` variables
+//! (`GOOGLE_SERVICE_ACCOUNT`, `GOOGLE_APPLICATION_CREDENTIALS`, ...).
+//!
+//! Saving never waits for the network: files are written locally and queued
+//! for a background uploader, and [`OutputSink::flush`] waits for the queue
+//! to drain.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{Context, Result, anyhow, bail};
+use futures::StreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::buffered::BufWriter;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{Attribute, Attributes, ObjectStore};
+use parking_lot::Mutex;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{Notify, mpsc};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use url::Url;
+
+use super::{FilesystemSink, OutputSink, SinkFuture};
+
+/// Buffer size of an upload; larger files go up as a multipart upload
+pub const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Files uploaded concurrently by the background uploader
+const UPLOAD_CONCURRENCY: usize = 4;
+
+/// Storage service an output URL points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectStoreService {
+    /// AWS S3 or an S3-compatible service (`s3://`)
+    S3,
+    /// Google Cloud Storage (`gs://`)
+    Gcs,
+}
+
+impl ObjectStoreService {
+    fn scheme(self) -> &'static str {
+        match self {
+            Self::S3 => "s3",
+            Self::Gcs => "gs",
+        }
+    }
+}
+
+/// Bucket, key prefix and service endpoint parsed from an output URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectStoreLocation {
+    pub service: ObjectStoreService,
+    pub bucket: String,
+    /// Key prefix without leading or trailing slashes; may be empty
+    pub prefix: String,
+    /// S3 region from `?region=`; otherwise taken from the environment
+    pub region: Option<String>,
+    /// Custom S3 service endpoint from `?endpoint=`, addressed path-style
+    pub endpoint: Option<Url>,
+}
+
+impl ObjectStoreLocation {
+    /// Parse an `s3://` or `gs://` output URL.
+    pub fn parse(output_url: &str) -> Result<Self> {
+        let url = Url::parse(output_url).with_context(|| format!("Invalid output URL: {output_url}"))?;
+        let bucket = url
+            .host_str()
+            .filter(|bucket| !bucket.is_empty())
+            .ok_or_else(|| anyhow!("Output URL has no bucket: {output_url}"))?
+            .to_string();
+        let prefix = url.path().trim_matches('/').to_string();
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        let (service, region, endpoint) = match url.scheme() {
+            "s3" => {
+                let endpoint = query
+                    .get("endpoint")
+                    .map(|endpoint| {
+                        Url::parse(endpoint).with_context(|| format!("Invalid object storage endpoint: {endpoint}"))
+                    })
+                    .transpose()?;
+                (ObjectStoreService::S3, query.get("region").cloned(), endpoint)
+            }
+            "gs" => (ObjectStoreService::Gcs, None, None),
+            scheme => bail!("Unsupported output URL scheme: {scheme}"),
+        };
+
+        Ok(Self {
+            service,
+            bucket,
+            prefix,
+            region,
+            endpoint,
+        })
+    }
+
+    /// Object key for a path relative to the output directory
+    fn key_for(&self, relative: &Path) -> String {
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if self.prefix.is_empty() {
+            relative
+        } else {
+            format!("{}/{relative}", self.prefix)
+        }
+    }
+
+    /// Client for the bucket, configured from the service's own environment
+    fn open(&self) -> Result<Arc<dyn ObjectStore>> {
+        let store: Arc<dyn ObjectStore> = match self.service {
+            ObjectStoreService::S3 => {
+                let mut builder = AmazonS3Builder::from_env().with_bucket_name(&self.bucket);
+                if let Some(region) = &self.region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(endpoint) = &self.endpoint {
+                    builder = builder
+                        .with_endpoint(endpoint.as_str().trim_end_matches('/'))
+                        .with_allow_http(endpoint.scheme() == "http");
+                }
+                Arc::new(builder.build()?)
+            }
+            ObjectStoreService::Gcs => Arc::new(
+                GoogleCloudStorageBuilder::from_env()
+                    .with_bucket_name(&self.bucket)
+                    .build()?,
+            ),
+        };
+        Ok(store)
+    }
+}
+
+impl std::fmt::Display for ObjectStoreLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}/{}", self.service.scheme(), self.bucket, self.prefix)
+    }
+}
+
+/// Progress of the background uploader
+#[derive(Default)]
+struct UploadState {
+    /// Paths queued but not yet picked up, so repeated changes upload once
+    queued: Mutex<HashSet<PathBuf>>,
+    /// Paths queued or uploading
+    outstanding: AtomicUsize,
+    /// Signalled when `outstanding` drops to zero
+    idle: Notify,
+    /// Uploads that failed since the last flush
+    failures: Mutex<Vec<anyhow::Error>>,
+}
+
+/// Keeps output on local disk and mirrors it into an object storage bucket.
+pub struct ObjectStoreSink {
+    location: ObjectStoreLocation,
+    root: PathBuf,
+    queue: mpsc::UnboundedSender<PathBuf>,
+    state: Arc<UploadState>,
+}
+
+impl ObjectStoreSink {
+    /// Mirror files saved under `root` into `location`.
+    ///
+    /// Starts the background uploader on the current Tokio runtime; it stops
+    /// when the sink is dropped.
+    pub fn new(location: ObjectStoreLocation, root: PathBuf) -> Result<Self> {
+        let store = location
+            .open()
+            .with_context(|| format!("Failed to configure object storage for {location}"))?;
+        let runtime = tokio::runtime::Handle::try_current().context("Object storage uploads need a Tokio runtime")?;
+
+        let (queue, receiver) = mpsc::unbounded_channel();
+        let state = Arc::new(UploadState::default());
+        let uploader = Uploader {
+            store,
+            location: location.clone(),
+            root: root.clone(),
+        };
+        let worker_state = Arc::clone(&state);
+        runtime.spawn(async move {
+            UnboundedReceiverStream::new(receiver)
+                .for_each_concurrent(UPLOAD_CONCURRENCY, |path: PathBuf| {
+                    let uploader = &uploader;
+                    let state = &worker_state;
+                    async move {
+                        state.queued.lock().remove(&path);
+                        if let Err(e) = uploader.upload_path(&path).await {
+                            log::warn!("{e:#}");
+                            state.failures.lock().push(e);
+                        }
+                        if state.outstanding.fetch_sub(1, Ordering::AcqRel) == 1 {
+                            state.idle.notify_waiters();
+                        }
+                    }
+                })
+                .await;
+        });
+
+        Ok(Self {
+            location,
+            root,
+            queue,
+            state,
+        })
+    }
+}
+
+impl OutputSink for ObjectStoreSink {
+    fn write<'a>(&'a self, path: &'a Path, bytes: Vec<u8>) -> SinkFuture<'a, ()> {
+        Box::pin(async move {
+            FilesystemSink.write(path, bytes).await?;
+            self.changed(path);
+            Ok(())
+        })
+    }
+
+    fn changed(&self, path: &Path) {
+        if !path.starts_with(&self.root) || !self.state.queued.lock().insert(path.to_path_buf()) {
+            return;
+        }
+        self.state.outstanding.fetch_add(1, Ordering::AcqRel);
+        if self.queue.send(path.to_path_buf()).is_err() {
+            // The uploader only stops once the sink is gone
+            self.state.queued.lock().remove(path);
+            self.state.outstanding.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    fn flush(&self) -> SinkFuture<'_, ()> {
+        Box::pin(async move {
+            loop {
+                let idle = self.state.idle.notified();
+                tokio::pin!(idle);
+                idle.as_mut().enable();
+                if self.state.outstanding.load(Ordering::Acquire) == 0 {
+                    break;
+                }
+                idle.await;
+            }
+
+            let failures = std::mem::take(&mut *self.state.failures.lock());
+            match failures.first() {
+                None => Ok(()),
+                Some(first) => bail!(
+                    "{} files failed to upload to {}: {first:#}",
+                    failures.len(),
+                    self.location
+                ),
+            }
+        })
+    }
+}
+
+/// Uploads local files to their mirrored keys
+struct Uploader {
+    store: Arc<dyn ObjectStore>,
+    location: ObjectStoreLocation,
+    root: PathBuf,
+}
+
+impl Uploader {
+    /// Upload the file at `path`, or every file under it if it is a directory.
+    async fn upload_path(&self, path: &Path) -> Result<()> {
+        let metadata = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata,
+            // Removed again before the upload started
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        if !metadata.is_dir() {
+            return self.upload_file(path).await;
+        }
+
+        let dir = path.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || files_under(&dir))
+            .await
+            .context("Output directory scan panicked")?;
+        for file in files {
+            self.upload_file(&file).await?;
+        }
+        Ok(())
+    }
+
+    /// Stream the local file at `path` to its mirrored key.
+    async fn upload_file(&self, path: &Path) -> Result<()> {
+        let relative = path
+            .strip_prefix(&self.root)
+            .with_context(|| format!("{} is outside the output directory", path.display()))?;
+        let key = ObjectPath::from(self.location.key_for(relative));
+        let mut file = match tokio::fs::File::open(path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+        };
+
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type(path).into());
+        let mut writer =
+            BufWriter::with_capacity(Arc::clone(&self.store), key, PART_SIZE).with_attributes(attributes);
+        if let Err(e) = tokio::io::copy(&mut file, &mut writer).await {
+            // Abort so the bucket is not billed for orphaned parts
+            if let Err(abort) = writer.abort().await {
+                log::warn!("Failed to abort upload of {}: {abort}", path.display());
+            }
+            return Err(e).with_context(|| format!("Failed to upload {}", path.display()));
+        }
+        writer
+            .shutdown()
+            .await
+            .with_context(|| format!("Failed to upload {}", path.display()))
+    }
+}
+
+/// Files under `dir`, skipping temporary files of in-flight atomic writes
+fn files_under(dir: &Path) -> Vec<PathBuf> {
+    jwalk::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with(".tmp"))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Content type stored with an uploaded object
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("json") => "application/json",
+        Some("gz") => "application/gzip",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_location_keys_mirror_layout() -> Result<()> {
+        let location = ObjectStoreLocation::parse("s3://docs/crawls/tokio?region=eu-west-1")?;
+        assert_eq!(location.service, ObjectStoreService::S3);
+        assert_eq!(location.bucket, "docs");
+        assert_eq!(location.prefix, "crawls/tokio");
+        assert_eq!(location.region.as_deref(), Some("eu-west-1"));
+
+        let key = location.key_for(Path::new("docs.rs/tokio/index.md.gz"));
+        assert_eq!(key, "crawls/tokio/docs.rs/tokio/index.md.gz");
+
+        let location = ObjectStoreLocation::parse("s3://docs?endpoint=http://localhost:9000")?;
+        assert_eq!(location.endpoint.as_ref().map(Url::as_str), Some("http://localhost:9000/"));
+        assert_eq!(location.region, None);
+
+        let location = ObjectStoreLocation::parse("gs://docs/site/?region=eu")?;
+        assert_eq!(location.service, ObjectStoreService::Gcs);
+        assert_eq!(location.prefix, "site");
+        assert_eq!((&location.region, &location.endpoint), (&None, &None));
+        assert_eq!(location.to_string(), "gs://docs/site");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_uploads_files_and_directories() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path().to_path_buf();
+        std::fs::create_dir_all(root.join(".citescrape"))?;
+        std::fs::write(root.join("index.md"), "# Home")?;
+        std::fs::write(root.join(".citescrape/events.jsonl"), "{}\n")?;
+        std::fs::write(root.join(".citescrape/.tmpAbc"), "partial")?;
+
+        let store = Arc::new(InMemory::new());
+        let uploader = Uploader {
+            store: store.clone(),
+            location: ObjectStoreLocation::parse("s3://docs/site")?,
+            root: root.clone(),
+        };
+        uploader.upload_path(&root.join("index.md")).await?;
+        uploader.upload_path(&root.join(".citescrape")).await?;
+        uploader.upload_path(&root.join("deleted.md")).await?;
+
+        let index = store.get(&ObjectPath::from("site/index.md")).await?;
+        assert_eq!(
+            index.attributes.get(&Attribute::ContentType).map(|value| value.as_ref()),
+            Some("text/markdown; charset=utf-8")
+        );
+        assert_eq!(index.bytes().await?.as_ref(), b"# Home");
+        assert!(store.head(&ObjectPath::from("site/.citescrape/events.jsonl")).await.is_ok());
+        assert!(store.head(&ObjectPath::from("site/.citescrape/.tmpAbc")).await.is_err());
+        Ok(())
+    }
+}
@@ -6,6 +6,19 @@
 
 use log::{debug, warn};
 
+/// Markers of bot challenge and captcha interstitials
+const CHALLENGE_MARKERS: &[&str] = &[
+    "cf-chl",
+    "challenge-platform",
+    "_incapsula_resource",
+    "px-captcha",
+    "captcha-delivery.com",
+    "g-recaptcha",
+    "h-captcha",
+    "<title>just a moment...</title>",
+    "<title>attention required!",
+];
+
 /// Result of content validation
 #[derive(Debug, Clone)]
 pub struct ContentValidationResult {
@@ -77,6 +90,16 @@ pub fn validate_page_content(
     ContentValidationResult::valid()
 }
 
+/// Whether a page rejected with an error status is a bot challenge
+///
+/// Only meaningful for pages that already failed validation: plenty of
+/// legitimate pages embed a captcha widget.
+#[must_use]
+pub fn is_challenge_page(html: &str) -> bool {
+    let html = html.to_lowercase();
+    CHALLENGE_MARKERS.iter().any(|marker| html.contains(marker))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +118,14 @@ mod tests {
         assert!(result.reason.unwrap().contains("HTTP error: 500"));
     }
 
+    #[test]
+    fn test_challenge_page_detection() {
+        let cloudflare = "<html><head><title>Just a moment...</title></head>\
+                          <body><script src=\"/cdn-cgi/challenge-platform/h/b/orchestrate\"></script></body></html>";
+        assert!(is_challenge_page(cloudflare));
+        assert!(!is_challenge_page("<html><head><title>Not Found</title></head><body>No such page</body></html>"));
+    }
+
     #[test]
     fn test_http_200_accepts() {
         let result = validate_page_content("", "", "https://example.com", Some(200));
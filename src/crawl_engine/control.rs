@@ -0,0 +1,160 @@
+//! Cancellation and pause control for a running crawl
+//!
+//! `CrawlControl` is cloned into the crawl config and shared with whoever
+//! started the crawl. Cancelling aborts in-flight page loads and ends the
+//! crawl loop; the orchestrator still flushes batched link rewrites and
+//! writes its reports for the pages saved so far. Pausing stops new pages
+//! from being scheduled until the crawl is resumed.
+//!
+//! Aborting is a hard cancel for when nobody will read the result (its owner's
+//! connection is gone): link rewriting stops mid-batch and the final flush and
+//! reports are skipped. A control created with [`CrawlControl::with_parent`]
+//! is aborted when the parent token is cancelled, so one token can stop every
+//! crawl a connection owns.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+/// Shared handle for cancelling or pausing a crawl
+#[derive(Debug, Clone)]
+pub struct CrawlControl {
+    token: CancellationToken,
+    abort: CancellationToken,
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CrawlControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrawlControl {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_abort_token(CancellationToken::new())
+    }
+
+    /// Control that is aborted when `parent` is cancelled
+    #[must_use]
+    pub fn with_parent(parent: &CancellationToken) -> Self {
+        Self::from_abort_token(parent.child_token())
+    }
+
+    fn from_abort_token(abort: CancellationToken) -> Self {
+        Self {
+            token: abort.child_token(),
+            abort,
+            paused: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Cancel the crawl (also releases a pause)
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once the crawl is cancelled
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// Cancel the crawl and drop the work that only finishes its output
+    pub fn abort(&self) {
+        self.abort.cancel();
+    }
+
+    #[must_use]
+    pub fn is_aborted(&self) -> bool {
+        self.abort.is_cancelled()
+    }
+
+    /// Token cancelled when the crawl is aborted, for work the crawl spawns
+    #[must_use]
+    pub fn abort_token(&self) -> &CancellationToken {
+        &self.abort
+    }
+
+    /// Stop scheduling new pages; in-flight pages still complete
+    ///
+    /// Returns `false` if the crawl was already paused.
+    pub fn pause(&self) -> bool {
+        !self.paused.send_replace(true)
+    }
+
+    /// Resume scheduling after `pause()`
+    ///
+    /// Returns `false` if the crawl was not paused.
+    pub fn resume(&self) -> bool {
+        self.paused.send_replace(false)
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Wait until the crawl is resumed or cancelled
+    pub async fn wait_while_paused(&self) {
+        let mut paused = self.paused.subscribe();
+        tokio::select! {
+            _ = self.token.cancelled() => {}
+            // The sender lives in `self`, so the channel cannot close here
+            _ = paused.wait_for(|paused| !paused) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pause_resume_and_cancel() {
+        let control = CrawlControl::new();
+        assert!(control.pause());
+        assert!(!control.pause());
+        assert!(control.is_paused());
+
+        let waiter = {
+            let control = control.clone();
+            tokio::spawn(async move { control.wait_while_paused().await })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+        assert!(control.resume());
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+
+        // Cancelling releases a paused crawl
+        control.pause();
+        control.cancel();
+        tokio::time::timeout(Duration::from_secs(1), control.wait_while_paused())
+            .await
+            .unwrap();
+        assert!(control.is_cancelled());
+        assert!(!control.is_aborted());
+    }
+
+    #[test]
+    fn test_parent_token_aborts() {
+        let parent = CancellationToken::new();
+        let first = CrawlControl::with_parent(&parent);
+        let second = CrawlControl::with_parent(&parent);
+
+        first.cancel();
+        assert!(!first.is_aborted());
+        assert!(!second.is_cancelled());
+
+        parent.cancel();
+        assert!(first.is_aborted());
+        assert!(second.is_cancelled() && second.is_aborted());
+    }
+}
@@ -4,41 +4,186 @@
 //! error types, progress indicators, and the main Crawler trait.
 
 use anyhow::Result;
-use std::fmt;
+use regex::Regex;
+use schemars::JsonSchema;
+use std::sync::LazyLock;
 
-/// Custom error type for crawl operations
-#[derive(Debug, Clone)]
+/// Status code in messages like "HTTP error: 404" or "HTTP 503"
+static HTTP_STATUS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bhttp(?: error)?:? ?([1-5]\d\d)\b").expect("valid regex"));
+
+/// Why a crawl or a page failed
+///
+/// Serialized with a `kind` tag (`{"kind": "http", "status": 404, ...}`) so
+/// clients of events, manifests and tool errors can branch on the failure
+/// without parsing messages. Errors raised where the kind is known carry a
+/// `CrawlError` through `anyhow`; [`CrawlError::classify`] recovers it, or
+/// infers the kind from the message for errors from elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum CrawlError {
-    /// Configuration error
-    ConfigError(String),
-    /// Browser error
-    BrowserError(String),
-    /// Network error
-    NetworkError(String),
+    /// Invalid crawl configuration
+    #[error("{message}")]
+    Config { message: String },
+    /// Browser launch, CDP or page failure
+    #[error("{message}")]
+    Browser { message: String },
+    /// No browser became available in the pool in time
+    #[error("{message}")]
+    PoolExhausted { message: String },
+    /// The host name did not resolve
+    #[error("{message}")]
+    DnsFailure { message: String },
+    /// Navigation or page load did not finish in time
+    #[error("{message}")]
+    NavTimeout { message: String },
+    /// Other connection-level failure
+    #[error("{message}")]
+    Network { message: String },
+    /// The server answered with an error status
+    #[error("{message}")]
+    Http { status: u16, message: String },
+    /// The server answered with a bot challenge or captcha page
+    #[error("{message}")]
+    ChallengeDetected { message: String },
+    /// The server's URL policy refused the page or one of its redirects
+    #[error("{message}")]
+    Blocked { message: String },
+    /// The page loaded but its content could not be extracted or converted
+    #[error("{message}")]
+    ExtractionFailed { message: String },
+    /// Output files could not be written
+    #[error("{message}")]
+    SaveFailed { message: String },
     /// Operation cancelled
+    #[error("Crawl operation was cancelled")]
     Cancelled,
-    /// Other errors
-    Other(String),
+    /// Unclassified failure
+    #[error("{message}")]
+    Other { message: String },
 }
 
-impl fmt::Display for CrawlError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl CrawlError {
+    /// Snake-case name of the failure kind, as serialized in `kind`
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
         match self {
-            Self::ConfigError(msg) => write!(f, "Configuration error: {msg}"),
-            Self::BrowserError(msg) => write!(f, "Browser error: {msg}"),
-            Self::NetworkError(msg) => write!(f, "Network error: {msg}"),
-            Self::Cancelled => write!(f, "Crawl operation was cancelled"),
-            Self::Other(msg) => write!(f, "Crawl error: {msg}"),
+            Self::Config { .. } => "config",
+            Self::Browser { .. } => "browser",
+            Self::PoolExhausted { .. } => "pool_exhausted",
+            Self::DnsFailure { .. } => "dns_failure",
+            Self::NavTimeout { .. } => "nav_timeout",
+            Self::Network { .. } => "network",
+            Self::Http { .. } => "http",
+            Self::ChallengeDetected { .. } => "challenge_detected",
+            Self::Blocked { .. } => "blocked",
+            Self::ExtractionFailed { .. } => "extraction_failed",
+            Self::SaveFailed { .. } => "save_failed",
+            Self::Cancelled => "cancelled",
+            Self::Other { .. } => "other",
+        }
+    }
+
+    /// Whether another attempt later, or with a different browser profile,
+    /// may succeed
+    ///
+    /// Timeouts, network and browser failures, bot challenges and 408, 429
+    /// and 5xx responses are transient; configuration, URL policy, extraction
+    /// and save failures and other HTTP errors are not.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Browser { .. }
+            | Self::PoolExhausted { .. }
+            | Self::DnsFailure { .. }
+            | Self::NavTimeout { .. }
+            | Self::Network { .. }
+            | Self::ChallengeDetected { .. }
+            | Self::Other { .. } => true,
+            Self::Http { status, .. } => matches!(status, 408 | 429 | 500..),
+            Self::Config { .. }
+            | Self::Blocked { .. }
+            | Self::ExtractionFailed { .. }
+            | Self::SaveFailed { .. }
+            | Self::Cancelled => false,
+        }
+    }
+
+    /// Factor applied to the page retry backoff
+    ///
+    /// Rate limits and bot challenges back off longest, browser failures
+    /// somewhat longer than network ones.
+    #[must_use]
+    pub fn backoff_multiplier(&self) -> f64 {
+        match self {
+            Self::Http { status: 429, .. } | Self::ChallengeDetected { .. } => 3.0,
+            Self::Browser { .. } | Self::PoolExhausted { .. } => 1.5,
+            _ => 1.0,
         }
     }
-}
 
-impl std::error::Error for CrawlError {}
+    /// The `CrawlError` in `error`'s chain, or one inferred from its message
+    ///
+    /// The message of an inferred error is the full chain.
+    #[must_use]
+    pub fn classify(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Self>())
+            .cloned()
+            .unwrap_or_else(|| Self::from_message(format!("{error:#}")))
+    }
+
+    /// Infer the failure kind from an error message
+    #[must_use]
+    pub fn from_message(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let msg = message.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| msg.contains(needle));
+
+        if has(&["captcha", "challenge", "cf-chl", "bot detection"]) {
+            Self::ChallengeDetected { message }
+        } else if has(&[
+            "err_name_not_resolved",
+            "name not resolved",
+            "dns",
+            "failed to lookup address",
+            "name or service not known",
+            "no such host",
+        ]) {
+            Self::DnsFailure { message }
+        } else if has(&["too many requests", "rate limit"]) {
+            Self::Http { status: 429, message }
+        } else if let Some(status) = HTTP_STATUS
+            .captures(&message)
+            .and_then(|caps| caps[1].parse().ok())
+        {
+            Self::Http { status, message }
+        } else if has(&["waiting for browser from pool", "pool at max capacity", "pool exhausted"]) {
+            Self::PoolExhausted { message }
+        } else if has(&["timeout", "timed out"]) {
+            if has(&["navigation", "page load", "err_timed_out"]) {
+                Self::NavTimeout { message }
+            } else {
+                Self::Network { message }
+            }
+        } else if has(&["failed to save", "failed to write", "failed to persist"]) {
+            Self::SaveFailed { message }
+        } else if has(&["extract", "validation", "parse", "convert"]) {
+            Self::ExtractionFailed { message }
+        } else if has(&["net::err_", "connection refused", "connection reset", "network", "unreachable"]) {
+            Self::Network { message }
+        } else if has(&["browser", "chrome", "cdp", "websocket"]) {
+            Self::Browser { message }
+        } else {
+            Self::Other { message }
+        }
+    }
+}
 
 impl From<anyhow::Error> for CrawlError {
     fn from(err: anyhow::Error) -> Self {
-        // Use {:#} to preserve full error chain with context
-        Self::Other(format!("{err:#}"))
+        Self::classify(&err)
     }
 }
 
@@ -56,6 +201,15 @@ pub enum CrawlProgress {
     NavigationStarted(String),
     /// Page loaded successfully
     PageLoaded(String),
+    /// Page saved and its links queued
+    PageCrawled {
+        url: String,
+        local_path: std::path::PathBuf,
+        /// Crawl queue length after this page's links were queued
+        queue_size: usize,
+    },
+    /// Page failed and will not be retried; the crawl goes on
+    PageFailed { url: String, error: String },
     /// Extracting page data
     ExtractingData,
     /// Saving assets
@@ -72,6 +226,7 @@ pub enum CrawlProgress {
 
 use crate::CrawlRequest;
 use serde::{Deserialize, Serialize};
+use imstr::ImString;
 
 /// A trait defining the interface for web crawlers.
 pub trait Crawler {
@@ -86,90 +241,75 @@ pub trait Crawler {
 /// Represents an item in the crawl queue with URL and depth tracking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrawlQueue {
-    pub url: String,
+    /// Normalized URL, shared with the crawl's `UrlInterner`
+    #[serde(with = "crate::utils::intern::serde_imstring")]
+    pub url: ImString,
     pub depth: u8,
     /// Number of retry attempts for this URL (0 = first attempt)
     #[serde(default)]
     pub retry_count: u8,
 }
 
-/// Categorizes page failures for intelligent retry decisions
-///
-/// Different failure types have different retry characteristics:
-/// - Network errors are usually transient → high retry value
-/// - Browser errors may recover with backoff → medium retry value  
-/// - Content errors are usually permanent → low/no retry value
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FailureKind {
-    /// Network-level failure (timeout, DNS, connection refused)
-    /// High retry value - usually transient
-    Network,
-    /// Browser/page failure (crash, resource exhaustion, CDP error)
-    /// Medium retry value - may recover with backoff
-    Browser,
-    /// Content extraction failure (invalid HTML, missing elements)
-    /// Low retry value - unlikely to recover
-    ContentExtraction,
-    /// Rate limiting detected (HTTP 429)
-    /// Special handling - use longer backoff
-    RateLimited,
-    /// Unknown/unclassified error
-    Unknown,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl FailureKind {
-    /// Classify an error into a failure kind based on error message patterns
-    #[must_use]
-    pub fn classify(error: &anyhow::Error) -> Self {
-        let msg = error.to_string().to_lowercase();
-        
-        // Rate limiting (highest priority check)
-        if msg.contains("429") || msg.contains("too many requests") || msg.contains("rate limit") {
-            return Self::RateLimited;
-        }
-        
-        // Network errors (high retry value)
-        if msg.contains("timeout") || msg.contains("timed out") ||
-           msg.contains("connection refused") || msg.contains("connection reset") ||
-           msg.contains("dns") || msg.contains("network") ||
-           msg.contains("unreachable") || msg.contains("eof") {
-            return Self::Network;
-        }
-        
-        // Browser/CDP errors (medium retry value)
-        if msg.contains("browser") || msg.contains("page") || 
-           msg.contains("chrome") || msg.contains("cdp") ||
-           msg.contains("target") || msg.contains("session") {
-            return Self::Browser;
-        }
-        
-        // Content errors (low retry value)
-        if msg.contains("extract") || msg.contains("validation") ||
-           msg.contains("content") || msg.contains("html") ||
-           msg.contains("parse") || msg.contains("selector") {
-            return Self::ContentExtraction;
-        }
-        
-        Self::Unknown
+    #[test]
+    fn test_classify_prefers_typed_error_in_chain() {
+        let error = anyhow::Error::new(CrawlError::Http {
+            status: 503,
+            message: "Content validation failed after 3 retries: HTTP error: 503".to_string(),
+        })
+        .context("Failed to crawl https://example.com/");
+        assert!(matches!(CrawlError::classify(&error), CrawlError::Http { status: 503, .. }));
     }
-    
-    /// Whether this failure kind should be retried by default
-    #[must_use]
-    pub const fn is_retryable(&self) -> bool {
-        match self {
-            Self::Network | Self::Browser | Self::RateLimited | Self::Unknown => true,
-            Self::ContentExtraction => false,  // Usually permanent
-        }
+
+    #[test]
+    fn test_classify_from_message() {
+        let kind = |message: &str| CrawlError::from_message(message).kind();
+        assert_eq!(kind("net::ERR_NAME_NOT_RESOLVED"), "dns_failure");
+        assert_eq!(kind("Page navigation timeout after 30 seconds"), "nav_timeout");
+        assert_eq!(kind("Content validation failed after 3 retries: HTTP error: 404"), "http");
+        assert_eq!(kind("HTTP 429 Too Many Requests"), "http");
+        assert_eq!(kind("Timeout after 30s waiting for browser from pool"), "pool_exhausted");
+        assert_eq!(kind("Failed to extract valid page data after 3 retries"), "extraction_failed");
+        assert_eq!(kind("Failed to write index.md"), "save_failed");
+        assert_eq!(kind("net::ERR_CONNECTION_REFUSED"), "network");
+        assert_eq!(kind("Chrome crashed"), "browser");
+        assert_eq!(kind("something odd"), "other");
+        assert_eq!(
+            CrawlError::from_message("HTTP error: 404"),
+            CrawlError::Http { status: 404, message: "HTTP error: 404".to_string() }
+        );
     }
-    
-    /// Base delay multiplier for this failure kind
-    #[must_use]
-    pub const fn delay_multiplier(&self) -> f64 {
-        match self {
-            Self::Network => 1.0,
-            Self::Browser => 1.5,
-            Self::RateLimited => 3.0,  // Longer backoff for rate limits
-            Self::ContentExtraction | Self::Unknown => 1.0,
-        }
+
+    #[test]
+    fn test_serialized_with_kind_tag() {
+        let error = CrawlError::Http { status: 404, message: "HTTP error: 404".to_string() };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json, serde_json::json!({"kind": "http", "status": 404, "message": "HTTP error: 404"}));
+        assert_eq!(serde_json::from_value::<CrawlError>(json).unwrap(), error);
+        assert_eq!(serde_json::to_value(CrawlError::Cancelled).unwrap(), serde_json::json!({"kind": "cancelled"}));
+        assert_eq!(error.to_string(), "HTTP error: 404");
+    }
+
+    #[test]
+    fn test_is_transient() {
+        let transient = |message: &str| CrawlError::from_message(message).is_transient();
+        assert!(transient("Page navigation timeout after 30 seconds"));
+        assert!(transient("net::ERR_CONNECTION_RESET"));
+        assert!(transient("HTTP error: 503"));
+        assert!(transient("HTTP 429 Too Many Requests"));
+        assert!(!transient("HTTP error: 404"));
+        assert!(!transient("Failed to extract valid page data after 3 retries"));
+        assert!(!CrawlError::Cancelled.is_transient());
+    }
+
+    #[test]
+    fn test_backoff_multiplier() {
+        let multiplier = |message: &str| CrawlError::from_message(message).backoff_multiplier();
+        assert_eq!(multiplier("HTTP 429 Too Many Requests"), 3.0);
+        assert_eq!(multiplier("Chrome crashed"), 1.5);
+        assert_eq!(multiplier("net::ERR_CONNECTION_RESET"), 1.0);
     }
 }
@@ -1,13 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
+use tokio_util::sync::CancellationToken;
 
-use super::crawl_types::{CrawlError, Crawler};
-use crate::config::CrawlConfig;
+use super::control::CrawlControl;
+use super::crawl_types::{CrawlError, CrawlProgress, CrawlResult, Crawler};
+use super::progress::{CallbackProgress, NoOpProgress, ProgressReporter};
+use crate::config::{CrawlConfig, CrawlScope};
 use crate::content_saver::{self};
+use crate::content_saver::sink::{note_output_changed, open_output_sink, register_output_sink};
+use crate::crawl_events::{CrawlEvent, CrawlEventBus};
 use crate::imurl::ImUrl;
-use crate::link_index::LinkIndex;
+use crate::link_index::open_link_store;
 use crate::link_rewriter::LinkRewriter;
 use crate::runtime::CrawlRequest;
 
@@ -21,6 +26,9 @@ pub use content_saver::CacheMetadata;
 // save_markdown_content is now in content_saver module
 // save_screenshot is now in PageProcessor module
 
+/// Events buffered per receiver on a bus created by [`ChromiumoxideCrawler::subscribe`]
+const EVENT_BUS_CAPACITY: usize = 1000;
+
 pub struct ChromiumoxideCrawler {
     config: CrawlConfig,
     /// Chrome data directory path. Cleanup is handled by orchestrator::crawl_pages()
@@ -38,25 +46,121 @@ impl ChromiumoxideCrawler {
         }
     }
 
+    /// Subscribe to this crawler's events
+    ///
+    /// Attaches an event bus to the config if none was set, so call this
+    /// before [`Crawler::crawl`]; every subscriber shares the same bus.
+    pub fn subscribe(&mut self) -> broadcast::Receiver<CrawlEvent> {
+        if let Some(bus) = self.config.event_bus() {
+            return bus.subscribe();
+        }
+        let bus = Arc::new(CrawlEventBus::new(EVENT_BUS_CAPACITY));
+        let receiver = bus.subscribe();
+        self.config.event_bus = Some(bus);
+        receiver
+    }
+
+    /// Abort the crawl when `token` is cancelled
+    ///
+    /// Replaces any control set on the config. Use
+    /// [`CrawlConfig::with_crawl_control`] instead to also pause or cancel
+    /// the crawl gracefully.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.config.crawl_control = Some(CrawlControl::with_parent(token));
+        self
+    }
+
+    /// Crawl, passing progress updates to `on_progress` as they happen
+    ///
+    /// Updates cover the crawl lifecycle (browser launch, cleanup, completion)
+    /// and every page that loads, is saved or fails for good. A crawl that
+    /// fails ends with [`CrawlProgress::Error`].
+    pub async fn crawl_with_progress(
+        mut self,
+        on_progress: impl Fn(CrawlProgress) + Send + Sync + 'static,
+    ) -> CrawlResult<()> {
+        let progress = CallbackProgress::new(on_progress);
+        let mut events = self.subscribe();
+
+        let crawl = self.crawl_impl_with(progress.clone());
+        tokio::pin!(crawl);
+        let result = loop {
+            tokio::select! {
+                result = &mut crawl => break result,
+                event = events.recv() => match event {
+                    Ok(event) => progress.report_event(&event),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::debug!("Progress callback missed {skipped} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break (&mut crawl).await,
+                },
+            }
+        };
+        while let Ok(event) = events.try_recv() {
+            progress.report_event(&event);
+        }
+
+        result.map_err(|e| {
+            progress.report_error(&format!("{e:#}"));
+            CrawlError::from(e)
+        })
+    }
+
     async fn crawl_impl(&mut self) -> Result<()> {
-        let config = self.config.clone();
+        self.crawl_impl_with(NoOpProgress).await
+    }
+
+    async fn crawl_impl_with<P: ProgressReporter>(&mut self, progress: P) -> Result<()> {
+        // The orchestrator and the link rewriter must share one control
+        let control = self.config.crawl_control().cloned().unwrap_or_default();
+        let config = self.config.clone().with_crawl_control(control.clone());
         let chrome_data_dir = self.chrome_data_dir.clone();
 
-        // Initialize LinkIndex (opens or creates SQLite database)
-        let link_index = Arc::new(
-            LinkIndex::open(config.storage_dir())
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to open link index: {}", e))?
-        );
+        // Refuse older mirror layouts so this crawl doesn't mix path schemes
+        crate::output_layout::ensure_current_layout(config.storage_dir())
+            .await
+            .context("Failed to prepare output directory layout")?;
+
+        // Initialize link store (local SQLite database unless a shared
+        // database is configured via link_index_url)
+        let link_index = open_link_store(config.storage_dir(), config.link_index_url())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to open link index: {}", e))?;
+
+        // Route saved files to the configured output backend
+        let sink = open_output_sink(config.storage_dir(), config.output_url())?;
+        let _sink_registration = register_output_sink(config.storage_dir(), Arc::clone(&sink));
+
+        // Stores that change continuously are persisted once the crawl ends
+        let persisted_dirs = [config.storage_dir().join(".citescrape"), config.search_index_dir()];
 
         // Create LinkRewriter with the index
         // storage_dir is guaranteed absolute by CrawlConfigBuilder
-        let link_rewriter = LinkRewriter::new(link_index, config.storage_dir().to_path_buf());
-
-        let chrome_data_dir_path =
-            super::crawl_impl(config, link_rewriter, chrome_data_dir).await?;
+        let link_rewriter = LinkRewriter::new(Arc::clone(&link_index), config.storage_dir().to_path_buf())
+            .with_rewrite_window(config.link_rewrite_window())
+            .with_asset_rate_limit(config.crawl_rate_rps())
+            .with_cancellation(control.abort_token().clone());
+
+        let result =
+            super::crawl_impl_with_progress(config, link_rewriter.clone(), chrome_data_dir, progress).await;
+
+        // Close the pool so pending writes are flushed before the crawl reports
+        // done, once no batched rewrite is still using it
+        link_rewriter.wait_for_flushes().await;
+        link_index.close().await;
+
+        // Persist the final link database, journal and search index, then wait
+        // for every queued upload, including those of a crawl that failed part way
+        for dir in &persisted_dirs {
+            note_output_changed(dir);
+        }
+        let flushed = sink.flush().await;
 
-        self.chrome_data_dir = chrome_data_dir_path;
+        self.chrome_data_dir = result?;
+        flushed.map_err(|e| CrawlError::SaveFailed {
+            message: format!("Failed to persist crawl output: {e:#}"),
+        })?;
         Ok(())
     }
 }
@@ -173,12 +277,26 @@ pub fn should_visit_url(url: &str, config: &CrawlConfig) -> bool {
         return false;
     }
 
-    // Host must match exactly (no subdomains, no external domains in real usage)
+    // Host must match exactly, or share the registrable domain when the scope allows it
     let url_host = parsed_url.host().unwrap_or_default();
     let start_host = start_url.host().unwrap_or_default();
+    let same_host = url_host == start_host;
 
-    if url_host != start_host {
-        return false;  // Reject different hosts immediately
+    let in_scope = match config.scope() {
+        CrawlScope::SameHost => same_host,
+        CrawlScope::SameRegistrableDomain => {
+            same_host || parsed_url.registrable_domain() == start_url.registrable_domain()
+        }
+    };
+    if !in_scope {
+        return false;  // Reject out-of-scope hosts immediately
+    }
+
+    // Server-wide URL policy (allowed domains, private hosts)
+    if let Ok(url) = url::Url::parse(url)
+        && crate::url_policy::url_policy().check_navigation(&url).is_err()
+    {
+        return false;
     }
 
     // Check allowed_domains list if configured (rare, but keep for compatibility)
@@ -202,8 +320,8 @@ pub fn should_visit_url(url: &str, config: &CrawlConfig) -> bool {
     let norm_url_path = url_path.trim_end_matches('/');
     let norm_start_path = start_path.trim_end_matches('/');
 
-    // Root path allows all paths on this domain
-    if norm_start_path.is_empty() || norm_start_path == "/" {
+    // Root path allows all paths on this domain; other hosts in scope are not path-limited
+    if !same_host || norm_start_path.is_empty() || norm_start_path == "/" {
         // Continue to excluded patterns check below
     } else {
         // URL must be exact match or child of start path
@@ -216,10 +334,12 @@ pub fn should_visit_url(url: &str, config: &CrawlConfig) -> bool {
     }
 
     // Check excluded patterns
-    for regex in config.excluded_patterns_compiled() {
-        if regex.is_match(url) {
-            return false;
-        }
+    if config
+        .excluded_patterns_compiled()
+        .iter()
+        .any(|matcher| matcher.matches(&parsed_url))
+    {
+        return false;
     }
 
     if let Some(excluded_patterns) = config.excluded_patterns() {
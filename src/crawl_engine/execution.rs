@@ -6,12 +6,19 @@
 
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::oneshot;
 
 use crate::config::CrawlConfig;
+use crate::crawl_events::types::ShutdownReason;
+use crate::crawl_events::{CrawlEvent, CrawlEventBus, EventJournal};
 use crate::link_rewriter::LinkRewriter;
 
 use super::orchestrator::crawl_pages;
-use super::progress::NoOpProgress;
+use super::progress::{NoOpProgress, ProgressReporter};
+
+/// Event bus capacity when one is created only for the journal
+const JOURNAL_BUS_CAPACITY: usize = 1000;
 
 /// Core crawling implementation that handles browser setup, page processing, and cleanup
 ///
@@ -24,6 +31,8 @@ use super::progress::NoOpProgress;
 ///
 /// This is a thin wrapper around `crawl_pages` that uses `NoOpProgress`
 /// for zero-overhead execution (all progress calls are inlined away).
+/// When the event journal is enabled, events are also appended to the
+/// output directory's journal.
 ///
 /// # Arguments
 /// * `config` - Crawl configuration
@@ -38,8 +47,48 @@ pub async fn crawl_impl(
     chrome_data_dir: Option<PathBuf>,
 ) -> Result<Option<PathBuf>> {
     // Use NoOpProgress - event publishing handled directly by crawl_pages
-    let progress = NoOpProgress;
-    let event_bus = config.event_bus().cloned();
+    crawl_impl_with_progress(config, link_rewriter, chrome_data_dir, NoOpProgress).await
+}
+
+/// [`crawl_impl`] reporting lifecycle progress to `progress`
+pub async fn crawl_impl_with_progress<P: ProgressReporter>(
+    config: CrawlConfig,
+    link_rewriter: LinkRewriter,
+    chrome_data_dir: Option<PathBuf>,
+    progress: P,
+) -> Result<Option<PathBuf>> {
+    let mut event_bus = config.event_bus().cloned();
+
+    // Journal writer subscribes before the first event is published
+    let journal = if config.event_journal() {
+        match EventJournal::open(config.storage_dir()).await {
+            Ok(journal) => {
+                let bus = event_bus
+                    .get_or_insert_with(|| Arc::new(CrawlEventBus::new(JOURNAL_BUS_CAPACITY)));
+                let (stop_tx, stop_rx) = oneshot::channel();
+                Some((journal.spawn(bus.subscribe_lossless(), stop_rx), stop_tx))
+            }
+            Err(e) => {
+                log::warn!("Event journal disabled for this crawl: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let result = crawl_pages(config, link_rewriter, chrome_data_dir, progress, event_bus.clone()).await;
+
+    if let Some((writer, stop)) = journal {
+        // A crawl that failed early never published its Shutdown event
+        if let (Err(e), Some(bus)) = (&result, &event_bus) {
+            let _ = bus
+                .publish(CrawlEvent::shutdown(ShutdownReason::Error(format!("{e:#}"))))
+                .await;
+        }
+        let _ = stop.send(());
+        let _ = writer.await;
+    }
 
-    crawl_pages(config, link_rewriter, chrome_data_dir, progress, event_bus).await
+    result
 }
@@ -0,0 +1,329 @@
+//! Request interception for crawl and tool pages
+//!
+//! Chrome delivers a page's paused requests to a single Fetch-domain handler,
+//! so everything that needs to vet or change requests goes through one
+//! [`RequestInterceptor`] per page:
+//!
+//! - the process-wide [`UrlPolicy`]: documents, including redirect hops and
+//!   frames, must be on an allowed domain, and no request may reach a private
+//!   host, checked against the addresses its name resolves to;
+//! - [`OriginOverrides`]: headers sent only to the target's origin, never to
+//!   third parties, and the method and body of the target document request.
+//!
+//! Pages with neither never enable the Fetch domain.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use chromiumoxide::Page;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams, EventRequestPaused, FailRequestParams, HeaderEntry, RequestPattern,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, Headers, ResourceType};
+use futures::{Stream, StreamExt};
+use url::Url;
+
+use crate::url_policy::{UrlPolicy, url_policy};
+
+/// Request changes scoped to one origin
+#[derive(Debug, Clone)]
+pub struct OriginOverrides {
+    /// Document whose request takes `method` and `body`; its origin scopes `headers`
+    pub target: Url,
+    /// Headers added to requests to the target's origin, replacing any of the same name
+    pub headers: Vec<(String, String)>,
+    /// HTTP method for the target document request (e.g. "POST")
+    pub method: Option<String>,
+    /// Body of the target document request
+    pub body: Option<String>,
+}
+
+impl OriginOverrides {
+    /// Headers only, for every request to `target`'s origin
+    #[must_use]
+    pub fn headers(target: Url, headers: Vec<(String, String)>) -> Self {
+        Self {
+            target,
+            headers,
+            method: None,
+            body: None,
+        }
+    }
+
+    /// Request headers with the overrides applied; a body without an explicit
+    /// content type is sent as JSON if it parses as JSON, as a form otherwise
+    fn merge_headers(&self, original: &Headers, with_body: bool) -> Vec<HeaderEntry> {
+        let overridden = |name: &str| self.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case(name));
+        let mut entries: Vec<HeaderEntry> = original
+            .inner()
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(name, _)| !overridden(name))
+            .filter_map(|(name, value)| value.as_str().map(|v| HeaderEntry::new(name.clone(), v)))
+            .collect();
+        entries.extend(self.headers.iter().map(|(name, value)| HeaderEntry::new(name.clone(), value.clone())));
+
+        if with_body
+            && let Some(body) = &self.body
+            && !entries.iter().any(|h| h.name.eq_ignore_ascii_case("content-type"))
+        {
+            let content_type = if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+                "application/json"
+            } else {
+                "application/x-www-form-urlencoded"
+            };
+            entries.push(HeaderEntry::new("Content-Type", content_type));
+        }
+        entries
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    overrides: Option<OriginOverrides>,
+    /// Whether the target document request has taken the method and body
+    document_overridden: bool,
+    /// Why the first blocked document request was blocked
+    blocked: Option<String>,
+}
+
+/// The Fetch-domain handler of one page; stops intercepting when dropped
+pub struct RequestInterceptor {
+    page: Page,
+    policy: Arc<UrlPolicy>,
+    state: Arc<Mutex<State>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl RequestInterceptor {
+    /// Start vetting `page`'s requests against the current URL policy
+    ///
+    /// Install before the page's first navigation.
+    pub async fn install(page: &Page) -> Result<Self> {
+        let policy = url_policy();
+        let state = Arc::new(Mutex::new(State::default()));
+        let events = page
+            .event_listener::<EventRequestPaused>()
+            .await
+            .context("Failed to listen for intercepted requests")?;
+        let task = tokio::spawn(handle_requests(page.clone(), policy.clone(), state.clone(), events));
+        let interceptor = Self {
+            page: page.clone(),
+            policy,
+            state,
+            task,
+        };
+        interceptor.enable().await?;
+        Ok(interceptor)
+    }
+
+    /// Apply `overrides` to requests from now on, replacing earlier ones
+    pub async fn set_overrides(&self, overrides: OriginOverrides) -> Result<()> {
+        {
+            let mut state = lock(&self.state);
+            state.overrides = Some(overrides);
+            state.document_overridden = false;
+        }
+        self.enable().await
+    }
+
+    /// Why a document request of the page was blocked, if one was
+    #[must_use]
+    pub fn blocked(&self) -> Option<String> {
+        lock(&self.state).blocked.clone()
+    }
+
+    async fn enable(&self) -> Result<()> {
+        let origin = lock(&self.state)
+            .overrides
+            .as_ref()
+            .map(|overrides| overrides.target.origin().ascii_serialization());
+        let patterns = patterns(&self.policy, origin);
+        if patterns.is_empty() {
+            return Ok(());
+        }
+        self.page
+            .execute(EnableParams::builder().patterns(patterns).build())
+            .await
+            .context("Failed to enable request interception")?;
+        Ok(())
+    }
+}
+
+impl Drop for RequestInterceptor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Requests the page has to pause for
+fn patterns(policy: &UrlPolicy, override_origin: Option<String>) -> Vec<RequestPattern> {
+    let mut patterns = Vec::new();
+    if policy.blocks_private_hosts() {
+        patterns.push(RequestPattern::builder().url_pattern("*").build());
+    } else if !policy.is_unrestricted() {
+        patterns.push(
+            RequestPattern::builder()
+                .url_pattern("*")
+                .resource_type(ResourceType::Document)
+                .build(),
+        );
+    }
+    if let Some(origin) = override_origin {
+        patterns.push(RequestPattern::builder().url_pattern(format!("{origin}/*")).build());
+    }
+    patterns
+}
+
+async fn handle_requests(
+    page: Page,
+    policy: Arc<UrlPolicy>,
+    state: Arc<Mutex<State>>,
+    mut events: impl Stream<Item = Arc<EventRequestPaused>> + Unpin,
+) {
+    // Host → outcome of its DNS check, so each host is resolved once per page
+    let mut resolved = HashMap::new();
+    while let Some(event) = events.next().await {
+        let result = match check_request(&policy, &event, &mut resolved).await {
+            Err(reason) => {
+                if event.resource_type == ResourceType::Document {
+                    log::warn!("Blocked {}: {reason}", event.request.url);
+                    lock(&state).blocked.get_or_insert(reason);
+                } else {
+                    log::debug!("Blocked {}: {reason}", event.request.url);
+                }
+                page.execute(FailRequestParams::new(event.request_id.clone(), ErrorReason::BlockedByClient))
+                    .await
+                    .map(|_| ())
+            }
+            Ok(()) => match continue_params(&state, &event) {
+                Ok(params) => page.execute(params).await.map(|_| ()),
+                Err(e) => {
+                    log::debug!("Invalid continue request params: {e}");
+                    continue;
+                }
+            },
+        };
+        if let Err(e) = result {
+            log::debug!("Failed to resume intercepted request: {e}");
+        }
+    }
+}
+
+/// Check a paused request against the URL policy
+async fn check_request(
+    policy: &UrlPolicy,
+    event: &EventRequestPaused,
+    resolved: &mut HashMap<String, Result<(), String>>,
+) -> Result<(), String> {
+    if policy.is_unrestricted() {
+        return Ok(());
+    }
+    let url = Url::parse(&event.request.url).map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https" | "ws" | "wss") {
+        return Ok(());
+    }
+    let checked = if event.resource_type == ResourceType::Document {
+        policy.check_navigation(&url)
+    } else {
+        policy.check_request(&url)
+    };
+    checked.map_err(|e| e.to_string())?;
+
+    let host = url.host_str().unwrap_or_default().to_string();
+    if let Some(outcome) = resolved.get(&host) {
+        return outcome.clone();
+    }
+    let outcome = policy.check_resolved(&url).await.map_err(|e| e.to_string());
+    resolved.insert(host, outcome.clone());
+    outcome
+}
+
+/// Continue a paused request, with the overrides applied if it goes to their origin
+fn continue_params(state: &Mutex<State>, event: &EventRequestPaused) -> Result<ContinueRequestParams, String> {
+    let mut params = ContinueRequestParams::builder().request_id(event.request_id.clone());
+    let mut state = lock(state);
+    let State {
+        overrides,
+        document_overridden,
+        ..
+    } = &mut *state;
+    if let Some(overrides) = overrides
+        && let Ok(url) = Url::parse(&event.request.url)
+        && url.origin() == overrides.target.origin()
+    {
+        let is_target = !*document_overridden
+            && event.resource_type == ResourceType::Document
+            && same_resource(&url, &overrides.target);
+        *document_overridden |= is_target;
+
+        params = params.headers(overrides.merge_headers(&event.request.headers, is_target));
+        if is_target {
+            if let Some(method) = &overrides.method {
+                params = params.method(method.to_ascii_uppercase());
+            }
+            if let Some(body) = &overrides.body {
+                params = params.post_data(base64::engine::general_purpose::STANDARD.encode(body));
+            }
+        }
+    }
+    params.build()
+}
+
+/// Whether two URLs name the same resource (fragments ignored)
+fn same_resource(a: &Url, b: &Url) -> bool {
+    let mut a = a.clone();
+    let mut b = b.clone();
+    a.set_fragment(None);
+    b.set_fragment(None);
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_follow_policy_and_overrides() {
+        assert!(patterns(&UrlPolicy::default(), None).is_empty());
+
+        let overrides = patterns(&UrlPolicy::default(), Some("https://example.com".to_string()));
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].url_pattern.as_deref(), Some("https://example.com/*"));
+
+        let domains = patterns(&UrlPolicy::new(Some(vec!["docs.rs".to_string()]), false), None);
+        assert_eq!(domains[0].resource_type, Some(ResourceType::Document));
+
+        let private = patterns(&UrlPolicy::new(None, true), None);
+        assert_eq!(private[0].resource_type, None);
+    }
+
+    #[test]
+    fn test_merge_headers_replaces_and_infers_content_type() {
+        let overrides = OriginOverrides {
+            target: Url::parse("https://example.com/api").unwrap(),
+            headers: vec![("Authorization".to_string(), "Bearer t".to_string())],
+            method: Some("post".to_string()),
+            body: Some(r#"{"q": 1}"#.to_string()),
+        };
+        let original = Headers::new(serde_json::json!({"authorization": "old", "Accept": "*/*"}));
+        let merged = overrides.merge_headers(&original, true);
+        let value = |name: &str| {
+            merged
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case(name))
+                .map(|h| h.value.as_str())
+        };
+        assert_eq!(value("authorization"), Some("Bearer t"));
+        assert_eq!(value("accept"), Some("*/*"));
+        assert_eq!(value("content-type"), Some("application/json"));
+        assert_eq!(merged.len(), 3);
+    }
+}
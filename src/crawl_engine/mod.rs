@@ -8,24 +8,29 @@
 pub mod circuit_breaker;
 pub mod cleanup;
 pub mod content_validator;
+pub mod control;
 pub mod crawl_types;
 pub mod crawler;
 pub mod domain_limiter;
 pub mod execution;
+pub mod interception;
 pub mod orchestrator;
 pub mod page_enhancer;
 pub mod page_processor;
 pub mod page_timeout;
 pub mod progress;
 pub mod rate_limiter;
+pub mod retry_pass;
 pub mod retry_queue;
 
 // Re-exports for public API
-pub use execution::crawl_impl;
+pub use execution::{crawl_impl, crawl_impl_with_progress};
+
+pub use control::CrawlControl;
 
 // Re-export orchestration and progress types for advanced usage
 pub use orchestrator::crawl_pages;
-pub use progress::{NoOpProgress, ProgressReporter};
+pub use progress::{CallbackProgress, NoOpProgress, ProgressReporter};
 
 // Re-export rate limiter types
 pub use rate_limiter::{CrawlRateLimiter, RateLimitDecision, check_crawl_rate_limit, check_http_rate_limit};
@@ -36,7 +41,8 @@ pub use crawler::{ChromiumoxideCrawler, extract_valid_urls, should_visit_url};
 // Re-export circuit breaker types
 pub use circuit_breaker::{CircuitBreaker, CircuitState, DomainHealth, extract_domain};
 
-// Re-export retry queue
+// Re-export retry queue and end-of-crawl retry pass
+pub use retry_pass::RetryPass;
 pub use retry_queue::RetryQueue;
 
 // Re-export content validator types
@@ -46,7 +52,7 @@ pub use content_validator::{validate_page_content, ContentValidationResult};
 pub use domain_limiter::DomainLimiter;
 
 // Re-export crawl types
-pub use crawl_types::{CrawlError, CrawlProgress, CrawlQueue, CrawlResult, Crawler, FailureKind};
+pub use crawl_types::{CrawlError, CrawlProgress, CrawlQueue, CrawlResult, Crawler};
 
 // Re-export page enhancer
 pub use page_enhancer::*;
@@ -11,6 +11,7 @@ use anyhow::{Context, Result};
 use chromiumoxide::Browser;
 use dashmap::{DashMap, DashSet};
 use futures::StreamExt;
+use imstr::ImString;
 use futures::stream::FuturesUnordered;
 use log::{debug, error, info, warn};
 use std::collections::VecDeque;
@@ -20,25 +21,29 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 
-use super::crawl_types::{CrawlQueue, FailureKind};
+use super::crawl_types::{CrawlError, CrawlQueue};
 use rand::Rng;
 use super::{CircuitBreaker, DomainLimiter, extract_domain};
-use super::page_processor::{PageProcessorContext, PageResult, process_single_page};
+use super::page_processor::{PageProcessorContext, PageResult, frontier_url, process_single_page, publish_event};
+use super::retry_pass::{DeferredFailure, RetryPass, needs_own_browser, retry_pass_config};
 use super::retry_queue::RetryQueue;
 use super::progress::ProgressReporter;
-use crate::browser_setup::launch_browser;
+use crate::browser_setup::{launch_browser, launch_browser_with_proxy};
 use crate::config::CrawlConfig;
 use crate::inline_css::domain_queue::CachedResponse;
 use crate::crawl_events::{
     CrawlEventBus,
     types::CrawlEvent,
 };
+use crate::imurl::ImUrl;
 use crate::link_rewriter::LinkRewriter;
+use crate::utils::UrlInterner;
+use tracing::Instrument;
 
 /// Calculate exponential backoff delay with jitter for page retries
 ///
 /// Formula: base_delay * 2^(attempt-1) * failure_multiplier * (1 ± jitter)
-fn calculate_retry_backoff(retry_count: u8, failure_kind: FailureKind) -> std::time::Duration {
+fn calculate_retry_backoff(retry_count: u8, failure: &CrawlError) -> std::time::Duration {
     const BASE_DELAY_MS: u64 = 1000;  // 1 second
     const MAX_DELAY_MS: u64 = 30_000; // 30 seconds cap
     const JITTER_PERCENT: f64 = 0.2;  // ±20%
@@ -47,7 +52,7 @@ fn calculate_retry_backoff(retry_count: u8, failure_kind: FailureKind) -> std::t
     let exp_delay = BASE_DELAY_MS.saturating_mul(1 << retry_count.min(5));
     
     // Apply failure kind multiplier
-    let adjusted_delay = (exp_delay as f64 * failure_kind.delay_multiplier()) as u64;
+    let adjusted_delay = (exp_delay as f64 * failure.backoff_multiplier()) as u64;
     
     // Apply jitter to prevent thundering herd (using rand 0.9+ API)
     let jitter = rand::rng().random_range(-JITTER_PERCENT..=JITTER_PERCENT);
@@ -57,6 +62,42 @@ fn calculate_retry_backoff(retry_count: u8, failure_kind: FailureKind) -> std::t
     std::time::Duration::from_millis(jittered_delay.min(MAX_DELAY_MS))
 }
 
+/// Record a page that failed for good and publish its `Error` event
+///
+/// `attempts` is set when the page ran out of retries, which also publishes
+/// `RetryExhausted`.
+async fn report_page_failure(
+    url: &ImString,
+    error: &anyhow::Error,
+    attempts: Option<u8>,
+    link_rewriter: &LinkRewriter,
+    event_bus: Option<&Arc<CrawlEventBus>>,
+) {
+    // Record terminal failure for broken link reporting
+    if let Err(e) = link_rewriter
+        .index()
+        .record_fetch_result(url, None, Some(&error.to_string()))
+        .await
+    {
+        debug!("Failed to record fetch result for {url}: {e}");
+    }
+
+    let kind = CrawlError::classify(error);
+    publish_event(
+        event_bus,
+        CrawlEvent::error(url.to_string(), error.to_string()).with_error(kind.clone()),
+    )
+    .await;
+    if let Some(attempts) = attempts
+        && let Some(bus) = event_bus
+    {
+        let event = CrawlEvent::retry_exhausted(url.to_string(), attempts, error.to_string()).with_error(kind);
+        if let Err(e) = bus.publish(event).await {
+            warn!("Failed to publish RetryExhausted event: {e}");
+        }
+    }
+}
+
 /// Main crawl orchestration with event bus integration
 ///
 /// This function implements the complete multi-page crawling logic:
@@ -73,6 +114,13 @@ fn calculate_retry_backoff(retry_count: u8, failure_kind: FailureKind) -> std::t
 /// * `chrome_data_dir` - Optional Chrome data directory
 /// * `progress` - Progress reporter (`NoOpProgress`)
 /// * `event_bus` - Optional event bus for crawl events
+///
+/// # Tracing
+/// The crawl runs in a `crawl` span. Each page gets its own trace rooted at a
+/// `crawl.page` span that follows from it, with child spans for acquire,
+/// navigate, extract, convert, rewrite, save and index, so one slow page can
+/// be inspected without loading the whole crawl.
+#[tracing::instrument(name = "crawl", skip_all, fields(start_url = %config.start_url()))]
 pub async fn crawl_pages<P: ProgressReporter>(
     config: CrawlConfig,
     link_rewriter: LinkRewriter,
@@ -87,20 +135,38 @@ pub async fn crawl_pages<P: ProgressReporter>(
 
     progress.report_initializing();
 
-    // Initialize thread-safe crawl queue
+    // One shared copy of each URL for the queue, visited set and retry queue
+    let urls = Arc::new(UrlInterner::new());
+
+    // Initialize thread-safe crawl queue; seeds are queued without their
+    // fragment so `page#intro` and `page` are one entry
     let queue = Arc::new(tokio::sync::Mutex::new({
-        let mut q = VecDeque::new();
-        q.push_back(CrawlQueue {
-            url: config.start_url.clone(),
-            depth: 0,
-            retry_count: 0,
-        });
+        let mut q: VecDeque<CrawlQueue> = VecDeque::new();
+        for url in std::iter::once(&config.start_url).chain(config.seed_urls()) {
+            let seed = ImUrl::parse(url)
+                .and_then(|parsed| parsed.without_fragment())
+                .map_or_else(|_| url.clone(), |parsed| parsed.as_str().to_string());
+            if !q.iter().any(|item| item.url == *seed) {
+                q.push_back(CrawlQueue {
+                    url: urls.intern(&seed),
+                    depth: 0,
+                    retry_count: 0,
+                });
+            }
+        }
         q
     }));
 
+    // Resolve header/cookie secrets once, before any browser work, so a missing
+    // credential fails the crawl immediately
+    let request_overrides = Arc::new(
+        super::page_enhancer::RequestOverrides::resolve(&config)
+            .context("Failed to resolve request headers/cookies")?,
+    );
+
     // Lock-free visited set (replaces Bloom filter for thread-safety)
     // DashSet provides concurrent access without locks, ideal for multi-task crawling
-    let visited: Arc<DashSet<String>> = Arc::new(DashSet::new());
+    let visited: Arc<DashSet<ImString>> = Arc::new(DashSet::new());
 
     // Thread-safe circuit breaker for domain-level failure detection
     let circuit_breaker = if config.circuit_breaker_enabled() {
@@ -131,6 +197,9 @@ pub async fn crawl_pages<P: ProgressReporter>(
         bus.publish(event)
             .await
             .context("Failed to publish CrawlStarted event - event bus may be shutdown or full")?;
+        for item in queue.lock().await.iter() {
+            publish_event(Some(bus), CrawlEvent::page_queued(item.url.to_string(), u32::from(item.depth))).await;
+        }
     }
 
     // Setup browser - either from pool (instant) or launch fresh (2-5 second cold start)
@@ -213,10 +282,35 @@ pub async fn crawl_pages<P: ProgressReporter>(
     let semaphore = Arc::new(Semaphore::new(concurrency));
     let domain_limiter = Arc::new(DomainLimiter::new(config.max_concurrent_per_domain()));
 
+    // Cancellation/pause handle (a private one if the caller did not attach one)
+    let control = config.crawl_control().cloned().unwrap_or_default();
+
+    // Pages that ran out of retries on a transient failure, tried once more
+    // when the queue drains
+    let mut retry_pass = config.retry_pass_enabled().then(RetryPass::new);
+
+    // Browser and config pages are processed with; switched to the retry
+    // pass profile when that pass starts
+    let mut page_browser = Arc::clone(&browser);
+    let mut page_config = config.clone();
+    let mut retry_browser: Option<(tokio::task::JoinHandle<()>, PathBuf)> = None;
+
     // Main concurrent crawl loop
     let mut active_tasks = FuturesUnordered::new();
 
     loop {
+        // Paused: stop scheduling until resumed (in-flight tasks keep running)
+        if control.is_paused() {
+            info!("Crawl paused with {} pages in flight", active_tasks.len());
+            control.wait_while_paused().await;
+            if !control.is_cancelled() {
+                info!("Crawl resumed");
+            }
+        }
+        if control.is_cancelled() {
+            break;
+        }
+
         // Check retry queue for items ready to re-process
         if let Some(ref rq) = retry_queue {
             let ready_items = rq.drain_ready();
@@ -255,9 +349,25 @@ pub async fn crawl_pages<P: ProgressReporter>(
             if !visited.insert(item.url.clone()) {
                 continue; // Already visited
             }
+            // Discovered links are queued under their frontier key; a seed
+            // spelled differently (e.g. with a trailing slash) is marked
+            // under that key too so links back to it are not crawled again
+            if let Some(key) = frontier_url(&item.url)
+                && *key != *item.url
+            {
+                visited.insert(urls.intern(&key));
+            }
+
+            let page_span = tracing::info_span!(parent: None, "crawl.page", url = %item.url, depth = item.depth);
+            page_span.follows_from(tracing::Span::current());
 
             // Acquire global semaphore permit (limits total concurrency)
-            let permit = if let Ok(p) = semaphore.clone().acquire_owned().await {
+            let permit = if let Ok(p) = semaphore
+                .clone()
+                .acquire_owned()
+                .instrument(tracing::info_span!(parent: &page_span, "crawl.acquire", resource = "crawl_slot"))
+                .await
+            {
                 p
             } else {
                 error!("Semaphore closed unexpectedly");
@@ -272,11 +382,14 @@ pub async fn crawl_pages<P: ProgressReporter>(
                     continue;
                 }
             };
-            let domain_permit = domain_limiter.acquire(domain).await;
+            let domain_permit = domain_limiter
+                .acquire(domain)
+                .instrument(tracing::info_span!(parent: &page_span, "crawl.acquire", resource = "domain_slot"))
+                .await;
 
             // Clone all shared state for the task
-            let browser = Arc::clone(&browser);
-            let config = config.clone();
+            let browser = Arc::clone(&page_browser);
+            let config = page_config.clone();
             let link_rewriter = link_rewriter.clone();
             let event_bus = event_bus.clone();
             let circuit_breaker = circuit_breaker.clone();
@@ -286,9 +399,11 @@ pub async fn crawl_pages<P: ProgressReporter>(
             let queue = Arc::clone(&queue);
             let indexing_sender = indexing_sender.clone();
             let visited = Arc::clone(&visited);
+            let urls = Arc::clone(&urls);
             let user_agent = user_agent.clone();
             let http_error_cache = Arc::clone(&http_error_cache);
             let domain_queues = Arc::clone(&domain_queues);
+            let request_overrides = Arc::clone(&request_overrides);
 
             // Spawn concurrent task
             let task = tokio::spawn(async move {
@@ -307,103 +422,168 @@ pub async fn crawl_pages<P: ProgressReporter>(
                     queue,
                     indexing_sender,
                     visited,
+                    urls,
                     user_agent,
                     http_error_cache,
                     domain_queues,
+                    request_overrides,
                 };
 
                 process_single_page(browser, item, ctx).await
-            });
+            }.instrument(page_span));
 
             active_tasks.push(task);
         }
 
-        // Wait for at least one task to complete
-        match active_tasks.next().await {
-            Some(Ok(result)) => match result {
-                PageResult::Success(url) => {
-                    debug!("Completed crawling: {url}");
-                }
-                
-                PageResult::NeedsRetry(item) => {
-                    // Circuit breaker rejected - queue for later retry
-                    if let Some(ref rq) = retry_queue {
-                        debug!("Circuit breaker: queueing for retry: {}", item.url);
-                        rq.add(item);
-                    } else {
-                        warn!("No retry queue available, discarding: {}", item.url);
+        // Wait for at least one task to complete (or cancellation)
+        let next = tokio::select! {
+            biased;
+            () = control.cancelled() => break,
+            next = active_tasks.next() => next,
+        };
+        let idle = match next {
+            Some(Ok(result)) => {
+                match result {
+                    PageResult::Success(url) => {
+                        debug!("Completed crawling: {url}");
+                        if let Some(pass) = retry_pass.as_mut() {
+                            pass.record_success(&url);
+                        }
                     }
-                }
-                
-                PageResult::FailedRetryable { mut item, error, failure_kind } => {
-                    let max_retries = config.max_page_retries();
-                    
-                    if failure_kind.is_retryable() && item.retry_count < max_retries {
-                        item.retry_count += 1;
-                        
-                        // Calculate backoff delay
-                        let delay = calculate_retry_backoff(item.retry_count, failure_kind);
-                        
-                        warn!(
-                            "Page failed (attempt {}/{}): {} [{:?}] - retrying in {:?}",
-                            item.retry_count, max_retries, item.url, failure_kind, delay
-                        );
-                        
-                        // Remove from visited to allow re-processing
-                        visited.remove(&item.url);
-                        
-                        // Apply backoff delay before requeueing
-                        tokio::time::sleep(delay).await;
-                        
-                        // Re-add to main queue
-                        queue.lock().await.push_back(item);
-                    } else {
-                        warn!(
-                            "Page failed after {} attempts: {} [{:?}] - giving up: {}",
-                            item.retry_count, item.url, failure_kind, error
-                        );
-                        
-                        // Record failure in circuit breaker
+
+                    PageResult::NeedsRetry(item) => {
+                        // Circuit breaker rejected - queue for later retry
+                        if let Some(ref rq) = retry_queue {
+                            debug!("Circuit breaker: queueing for retry: {}", item.url);
+                            rq.add(item);
+                        } else {
+                            warn!("No retry queue available, discarding: {}", item.url);
+                        }
+                    }
+
+                    PageResult::FailedRetryable { mut item, error, failure } => {
+                        let max_retries = config.max_page_retries();
+
+                        if failure.is_transient() && item.retry_count < max_retries {
+                            item.retry_count += 1;
+
+                            // Calculate backoff delay
+                            let delay = calculate_retry_backoff(item.retry_count, &failure);
+
+                            warn!(
+                                "Page failed (attempt {}/{}): {} [{}] - retrying in {:?}",
+                                item.retry_count, max_retries, item.url, failure.kind(), delay
+                            );
+
+                            // Remove from visited to allow re-processing
+                            visited.remove(&item.url);
+
+                            // Apply backoff delay before requeueing
+                            tokio::select! {
+                                () = control.cancelled() => break,
+                                () = tokio::time::sleep(delay) => {}
+                            }
+
+                            // Re-add to main queue
+                            queue.lock().await.push_back(item);
+                        } else {
+                            warn!(
+                                "Page failed after {} attempts: {} [{}] - giving up: {}",
+                                item.retry_count, item.url, failure.kind(), error
+                            );
+
+                            // Record failure in circuit breaker
+                            if let Some(ref cb) = circuit_breaker
+                                && let Ok(domain) = extract_domain(&item.url)
+                            {
+                                cb.record_failure(&domain, &error.to_string());
+                            }
+
+                            if let Some(pass) = retry_pass.as_mut()
+                                && pass.accepts(&error)
+                            {
+                                debug!("Deferring {} to the end-of-crawl retry pass", item.url);
+                                let attempts = Some(item.retry_count);
+                                pass.defer(DeferredFailure { item, error, attempts });
+                            } else {
+                                if let Some(pass) = retry_pass.as_mut() {
+                                    pass.record_failure(&item.url);
+                                }
+                                report_page_failure(&item.url, &error, Some(item.retry_count), &link_rewriter, event_bus.as_ref())
+                                    .await;
+                            }
+                        }
+                    }
+
+                    PageResult::FailedPermanent { item, error } => {
+                        warn!("Permanent failure for {}: {}", item.url, error);
+                        // No retry, record failure in circuit breaker
                         if let Some(ref cb) = circuit_breaker
                             && let Ok(domain) = extract_domain(&item.url)
                         {
                             cb.record_failure(&domain, &error.to_string());
                         }
-                        
-                        // Publish RetryExhausted event
-                        if let Some(bus) = &event_bus {
-                            let event = CrawlEvent::retry_exhausted(
-                                item.url,
-                                item.retry_count,
-                                error.to_string(),
-                            );
-                            if let Err(e) = bus.publish(event).await {
-                                warn!("Failed to publish RetryExhausted event: {e}");
+
+                        // Transient kinds (5xx, bot challenges) get one more
+                        // attempt in the retry pass
+                        if let Some(pass) = retry_pass.as_mut()
+                            && pass.accepts(&error)
+                        {
+                            debug!("Deferring {} to the end-of-crawl retry pass", item.url);
+                            pass.defer(DeferredFailure { item, error, attempts: None });
+                        } else {
+                            if let Some(pass) = retry_pass.as_mut() {
+                                pass.record_failure(&item.url);
                             }
+                            report_page_failure(&item.url, &error, None, &link_rewriter, event_bus.as_ref()).await;
                         }
                     }
                 }
-                
-                PageResult::FailedPermanent { url, error } => {
-                    warn!("Permanent failure for {}: {}", url, error);
-                    // No retry, record failure in circuit breaker
-                    if let Some(ref cb) = circuit_breaker
-                        && let Ok(domain) = extract_domain(&url)
-                    {
-                        cb.record_failure(&domain, &error.to_string());
-                    }
-                }
-            },
+                false
+            }
             Some(Err(e)) => {
                 error!("Task panicked: {e}");
+                false
             }
-            None => break, // All tasks completed
-        }
+            None => true, // All tasks completed
+        };
 
         // Check if done
         let remaining = queue.lock().await.len();
         let retry_remaining = retry_queue.as_ref().map_or(0, |rq| rq.len());
-        if remaining == 0 && retry_remaining == 0 && active_tasks.is_empty() {
+        if idle || (remaining == 0 && retry_remaining == 0 && active_tasks.is_empty()) {
+            // Give pages that failed transiently one more attempt, unless the
+            // page limit would stop them from being crawled
+            let limit_reached = config
+                .limit
+                .is_some_and(|limit| total_pages.load(Ordering::Relaxed) >= limit);
+            if let Some(pass) = retry_pass.as_mut()
+                && pass.is_pending()
+                && !limit_reached
+            {
+                let items = pass.start();
+                info!("Retry pass: re-attempting {} pages that failed transiently", items.len());
+                page_config = retry_pass_config(&config);
+                if needs_own_browser(&config) {
+                    match launch_browser_with_proxy(config.retry_pass_headless(), None, config.retry_pass_proxy()).await {
+                        Ok((retry, handler, dir)) => {
+                            page_browser = Arc::new(retry);
+                            retry_browser = Some((handler, dir));
+                        }
+                        Err(e) => warn!("Failed to launch retry pass browser, reusing the crawl's browser: {e:#}"),
+                    }
+                }
+                for item in &items {
+                    publish_event(event_bus.as_ref(), CrawlEvent::page_queued(item.url.to_string(), u32::from(item.depth)))
+                        .await;
+                }
+                let mut q = queue.lock().await;
+                for item in items {
+                    visited.remove(&item.url);
+                    q.push_back(item);
+                }
+                continue;
+            }
             break;
         }
 
@@ -413,14 +593,117 @@ pub async fn crawl_pages<P: ProgressReporter>(
                 "Main queue empty, {} items in retry queue waiting for circuit recovery",
                 retry_remaining
             );
-            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            tokio::select! {
+                () = control.cancelled() => break,
+                () = tokio::time::sleep(std::time::Duration::from_secs(5)) => {}
+            }
+        }
+    }
+
+    // Cancelled: abort in-flight page loads and wait for them to release the browser
+    let cancelled = control.is_cancelled();
+    if cancelled {
+        info!("Crawl cancelled, aborting {} in-flight pages", active_tasks.len());
+        for task in active_tasks.iter() {
+            task.abort();
+        }
+        while active_tasks.next().await.is_some() {}
+    }
+
+    // Pages the retry pass never got to, or never finished, keep their
+    // original failure
+    if let Some(mut pass) = retry_pass {
+        for failure in pass.take_unfinished() {
+            report_page_failure(&failure.item.url, &failure.error, failure.attempts, &link_rewriter, event_bus.as_ref())
+                .await;
+        }
+        if !cancelled && let Some((recovered, failed)) = pass.finish() {
+            info!(
+                "Retry pass recovered {} of {} pages",
+                recovered.len(),
+                recovered.len() + failed.len()
+            );
+            publish_event(event_bus.as_ref(), CrawlEvent::retry_pass_completed(recovered, failed)).await;
+        }
+    }
+
+    // Close the retry pass browser and drop our extra handle on the crawl's
+    // browser so it can be cleaned up below
+    if let Some((handler, dir)) = retry_browser {
+        match Arc::try_unwrap(page_browser) {
+            Ok(retry) => {
+                if let Err(e) = super::cleanup::cleanup_browser_and_data(retry, dir).await {
+                    warn!("Failed to clean up retry pass browser: {e}");
+                }
+            }
+            Err(_) => warn!("Retry pass browser still in use, cleanup will happen on drop"),
+        }
+        handler.abort();
+    } else {
+        drop(page_browser);
+    }
+
+    // Aborted: nobody will read the output, so skip the finishing work
+    // (the link rewriter shares the abort token and drops its pending batch)
+    let aborted = control.is_aborted();
+    if aborted {
+        info!("Crawl aborted, skipping pending link rewrites and reports");
+    }
+
+    // Apply inbound rewrites still waiting in the batching window, after any
+    // flush already in flight so the two never rewrite side by side
+    link_rewriter.wait_for_flushes().await;
+    let flushed = link_rewriter.flush_pending().await;
+    if flushed.inbound_updated > 0 {
+        debug!("Flushed batched link rewrites for {} pages", flushed.inbound_updated);
+    }
+
+    // Graph analysis and reports, on whichever backend holds the link graph
+    if !aborted {
+        let index = link_rewriter.index();
+        // Write broken link report from the link graph and recorded fetch outcomes
+        match index.write_broken_links_report().await {
+            Ok((report, path)) => {
+                if report.total() > 0 {
+                    info!(
+                        "Broken link report: {} HTTP errors, {} uncrawled internal links ({})",
+                        report.http_errors.len(),
+                        report.uncrawled_internal.len(),
+                        path.display()
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to write broken link report: {e}"),
+        }
+
+        // Score pages by link centrality for search ranking
+        if let Err(e) = index
+            .compute_page_rank(
+                crate::link_index::graph::DEFAULT_DAMPING,
+                crate::link_index::graph::DEFAULT_MAX_ITERATIONS,
+            )
+            .await
+        {
+            warn!("Failed to compute PageRank: {e}");
+        }
+
+        // Emit sitemap.xml for the mirrored pages at the output root
+        if config.sitemap() {
+            match config.sitemap_base_url().map(url::Url::parse).transpose() {
+                Ok(base) => {
+                    if let Err(e) = index.write_sitemap(base.as_ref()).await {
+                        warn!("Failed to write sitemap: {e}");
+                    }
+                }
+                Err(e) => warn!("Invalid sitemap base URL, sitemap not written: {e}"),
+            }
         }
     }
 
     // Publish LinkRewriteCompleted if rewriting happened
     let urls_registered = link_rewriter.get_registration_count().await;
     let total_pages_final = total_pages.load(Ordering::Relaxed);
-    if urls_registered > 0 {
+    if config.save_raw_html() && urls_registered > 0 {
         if let Some(bus) = &event_bus {
             let event = CrawlEvent::link_rewrite_completed(
                 config.start_url.clone(),
@@ -460,8 +743,12 @@ pub async fn crawl_pages<P: ProgressReporter>(
         }
 
         // Graceful shutdown
-        bus.shutdown_gracefully(crate::crawl_events::types::ShutdownReason::CrawlCompleted)
-            .await;
+        let reason = if cancelled {
+            crate::crawl_events::types::ShutdownReason::Cancelled
+        } else {
+            crate::crawl_events::types::ShutdownReason::CrawlCompleted
+        };
+        bus.shutdown_gracefully(reason).await;
     }
 
     progress.report_cleanup_started();
@@ -3,8 +3,13 @@
 //! This module provides functions to enhance browser pages with
 //! stealth features and performance optimizations.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chromiumoxide::cdp::browser_protocol::network::{CookieParam, SetCookiesParams, TimeSinceEpoch};
 use chromiumoxide::{Page, cdp};
+use url::Url;
+
+use super::interception::{OriginOverrides, RequestInterceptor};
+use crate::config::{Cookie, CrawlConfig, SecretValue};
 
 /// Enhance a page with stealth features and optimizations
 pub async fn enhance_page(page: Page) -> Result<()> {
@@ -36,3 +41,95 @@ pub async fn enhance_page(page: Page) -> Result<()> {
 
     Ok(())
 }
+
+/// Extra headers and cookies of a crawl with their secrets resolved
+///
+/// Resolved once when the crawl starts and shared by every page, so secret
+/// stores are queried once per crawl rather than once per page.
+#[derive(Debug, Default)]
+pub struct RequestOverrides {
+    headers: Vec<(String, SecretValue)>,
+    cookies: Vec<(Cookie, SecretValue)>,
+}
+
+impl RequestOverrides {
+    /// Resolve the headers and cookies of `config`
+    ///
+    /// Errors name the header or cookie and the secret reference, never a value.
+    pub fn resolve(config: &CrawlConfig) -> Result<Self> {
+        let headers = config
+            .headers()
+            .iter()
+            .map(|(name, secret)| {
+                let value = secret
+                    .resolve()
+                    .with_context(|| format!("Failed to resolve header '{name}'"))?;
+                Ok((name.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        let cookies = config
+            .cookies()
+            .iter()
+            .map(|cookie| {
+                let value = cookie
+                    .value
+                    .resolve()
+                    .with_context(|| format!("Failed to resolve cookie '{}'", cookie.name))?;
+                Ok((cookie.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { headers, cookies })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.cookies.is_empty()
+    }
+}
+
+/// Apply extra headers and cookies to a page before it navigates to `url`
+///
+/// Headers go through `interceptor` and are only sent to `url`'s origin, never
+/// to third-party subresources. Requires the Network domain to be enabled.
+/// Cookies without a domain are scoped to `url`.
+pub async fn apply_request_overrides(
+    page: &Page,
+    interceptor: &RequestInterceptor,
+    overrides: &RequestOverrides,
+    url: &str,
+) -> Result<()> {
+    if !overrides.headers.is_empty() {
+        let target = Url::parse(url).with_context(|| format!("Invalid URL '{url}'"))?;
+        let headers = overrides
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.expose().to_string()))
+            .collect();
+        interceptor
+            .set_overrides(OriginOverrides::headers(target, headers))
+            .await?;
+    }
+
+    if !overrides.cookies.is_empty() {
+        let cookies = overrides
+            .cookies
+            .iter()
+            .map(|(cookie, value)| cookie_param(cookie, value, url))
+            .collect();
+        page.execute(SetCookiesParams::new(cookies)).await?;
+    }
+    Ok(())
+}
+
+fn cookie_param(cookie: &Cookie, value: &SecretValue, url: &str) -> CookieParam {
+    let mut param = CookieParam::new(cookie.name.clone(), value.expose().to_string());
+    match &cookie.domain {
+        Some(domain) => param.domain = Some(domain.clone()),
+        None => param.url = Some(url.to_string()),
+    }
+    param.path.clone_from(&cookie.path);
+    param.secure = Some(cookie.secure);
+    param.http_only = Some(cookie.http_only);
+    param.expires = cookie.expires.map(TimeSinceEpoch::new);
+    param
+}
@@ -17,26 +17,34 @@ use chromiumoxide::cdp::browser_protocol::network::{
     EventResponseReceived,
 };
 use futures::StreamExt;
-use log::{debug, error, warn};
+use imstr::ImString;
+use log::{debug, error, info, warn};
 use rand::Rng;
 use std::collections::VecDeque;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
+use tracing::Instrument;
 
-use super::content_validator::validate_page_content;
-use super::crawl_types::{CrawlQueue, FailureKind};
+use super::content_validator::{is_challenge_page, validate_page_content};
+use super::crawl_types::{CrawlError, CrawlQueue};
+use super::interception::RequestInterceptor;
 use super::{CircuitBreaker, extract_domain};
+use crate::imurl::ImUrl;
 use crate::inline_css::domain_queue::CachedResponse;
 use super::page_timeout::with_page_timeout;
 use crate::config::CrawlConfig;
 use crate::content_saver;
-use crate::content_saver::{read_cached_etag, check_etag_from_events};
-use crate::content_saver::markdown_converter::{ConversionOptions, convert_html_to_markdown};
-use crate::crawl_events::{CrawlEventBus, types::{CrawlEvent, PageCrawlMetadata}};
+use crate::content_saver::{
+    ResponseValidators, check_etag_from_events, extract_validators_from_headers, read_cached_metadata,
+};
+use crate::content_saver::markdown_converter::convert_page;
+use crate::crawl_events::{CrawlEventBus, types::{CrawlEvent, PageCrawlMetadata, PhaseTimings}};
+use crate::link_index::AliasKind;
 use crate::link_rewriter::LinkRewriter;
 use crate::page_extractor;
+use crate::utils::UrlInterner;
 
 /// Result of processing a single page
 ///
@@ -48,7 +56,7 @@ use crate::page_extractor;
 #[derive(Debug)]
 pub enum PageResult {
     /// Page processed successfully
-    Success(String),
+    Success(ImString),
     
     /// Circuit breaker is open - queue for later retry via RetryQueue
     /// This waits for the circuit to transition to HalfOpen
@@ -59,13 +67,15 @@ pub enum PageResult {
     FailedRetryable {
         item: CrawlQueue,
         error: anyhow::Error,
-        failure_kind: FailureKind,
+        /// `error` classified, see [`CrawlError::classify`]
+        failure: CrawlError,
     },
     
     /// Permanent failure - should NOT be retried
-    /// Content validation failure, parse errors, etc.
+    /// Content validation failure, parse errors, etc. Transient kinds (5xx
+    /// responses, bot challenges) still get one attempt in the retry pass.
     FailedPermanent {
-        url: String,
+        item: CrawlQueue,
         error: anyhow::Error,
     },
 }
@@ -221,13 +231,17 @@ pub struct PageProcessorContext {
     pub total_pages: Arc<AtomicUsize>,
     pub queue: Arc<tokio::sync::Mutex<VecDeque<CrawlQueue>>>,
     pub indexing_sender: Option<Arc<crate::search::IndexingSender>>,
-    pub visited: Arc<DashSet<String>>,
+    pub visited: Arc<DashSet<ImString>>,
+    /// Interner for URLs discovered by this crawl
+    pub urls: Arc<UrlInterner>,
     /// User-Agent string extracted from browser (used for HTTP requests during resource inlining)
     pub user_agent: String,
     /// Shared cache for HTTP error responses (enables cross-page caching of failed static asset URLs)
     pub http_error_cache: Arc<DashMap<String, CachedResponse>>,
     /// Shared domain download queues (enables cross-page worker sharing for static assets)
     pub domain_queues: Arc<DashMap<String, Arc<crate::inline_css::domain_queue::DomainDownloadQueue>>>,
+    /// Extra headers and cookies with secrets resolved, set before each navigation
+    pub request_overrides: Arc<super::page_enhancer::RequestOverrides>,
 }
 
 /// Navigate to a URL with timeout and circuit breaker error handling
@@ -240,6 +254,7 @@ pub struct PageProcessorContext {
 ///
 /// # Arguments
 /// * `page` - Chromiumoxide Page instance to navigate
+/// * `interceptor` - The page's request interceptor, which knows why a URL was refused
 /// * `url` - Target URL to navigate to
 /// * `timeout_secs` - Maximum navigation timeout in seconds
 /// * `circuit_breaker` - Optional circuit breaker for failure tracking
@@ -253,8 +268,10 @@ pub struct PageProcessorContext {
 /// 1. Logs warning with URL and error details
 /// 2. Records failure in circuit breaker (if available) for domain
 /// 3. Propagates error to caller for handling
+#[tracing::instrument(name = "crawl.navigate", skip_all)]
 async fn navigate_to_page(
     page: &Page,
+    interceptor: &RequestInterceptor,
     url: &str,
     timeout_secs: u64,
     circuit_breaker: &Option<Arc<CircuitBreaker>>,
@@ -270,6 +287,12 @@ async fn navigate_to_page(
     )
     .await
     {
+        if let Some(reason) = interceptor.blocked() {
+            return Err(CrawlError::Blocked {
+                message: format!("Refused to load {url}: {reason}"),
+            }
+            .into());
+        }
         warn!("Navigation failed for {}: {}", url, e);
         if let Some(cb) = circuit_breaker
             && let Ok(domain) = extract_domain(url)
@@ -281,6 +304,36 @@ async fn navigate_to_page(
     Ok(())
 }
 
+/// Page result for a failed navigation; URLs the policy refused are not retried
+fn navigation_failure(item: CrawlQueue, error: anyhow::Error) -> PageResult {
+    let failure = CrawlError::classify(&error);
+    if matches!(failure, CrawlError::Blocked { .. }) {
+        return PageResult::FailedPermanent { item, error };
+    }
+    PageResult::FailedRetryable { item, error, failure }
+}
+
+/// Publish a per-page progress event
+///
+/// These events are for progress displays; having no subscriber (or a lagging
+/// one) is expected and only logged at debug level.
+pub(super) async fn publish_event(bus: Option<&Arc<CrawlEventBus>>, event: CrawlEvent) {
+    if let Some(bus) = bus
+        && let Err(e) = bus.publish(event).await
+    {
+        debug!("Failed to publish crawl event: {e}");
+    }
+}
+
+/// Frontier key of a URL: fragment removed, then normalized like the link index
+///
+/// `#section` anchors and other spellings of one page map to the same key,
+/// so they are queued (and counted) once. `None` if the URL does not parse.
+pub(crate) fn frontier_url(url: &str) -> Option<String> {
+    let page = ImUrl::parse(url).and_then(|url| url.without_fragment()).ok()?;
+    Some(crate::link_index::normalize_url(page.as_str()))
+}
+
 /// Process a single page concurrently
 ///
 /// This function handles all aspects of crawling a single URL:
@@ -327,7 +380,7 @@ pub async fn process_single_page(
         let domain = match extract_domain(&item.url) {
             Ok(d) => d,
             Err(e) => return PageResult::FailedPermanent { 
-                url: item.url, 
+                item, 
                 error: anyhow::anyhow!("{e}") 
             },
         };
@@ -338,12 +391,12 @@ pub async fn process_single_page(
     }
 
     // ═══════════════════════════════════════════════════════════════
-    // CACHE CHECK: Read cached ETag (if exists) for later comparison
+    // CACHE CHECK: Read cached metadata (if exists) for later comparison
     // ═══════════════════════════════════════════════════════════════
-    let cached_etag: Option<String> = match read_cached_etag(&item.url, &ctx.config.storage_dir).await {
-        Ok(Some(etag)) => {
-            debug!("Found cached ETag for {}: {}", item.url, etag);
-            Some(etag)
+    let cached_metadata = match read_cached_metadata(&item.url, &ctx.config.storage_dir).await {
+        Ok(Some(metadata)) => {
+            debug!("Found cached ETag for {}: {}", item.url, metadata.etag);
+            Some(metadata)
         }
         Ok(None) => {
             debug!("No cached content for {}", item.url);
@@ -359,8 +412,12 @@ pub async fn process_single_page(
     debug!("Crawling [depth {}]: {}", item.depth, item.url);
 
     // Create page - wrap in RAII guard for automatic cleanup
-    let page_guard = match browser.new_page("about:blank").await {
-        Ok(p) => PageGuard::new(p, item.url.clone()),
+    let page_guard = match browser
+        .new_page("about:blank")
+        .instrument(tracing::info_span!("crawl.acquire", resource = "page"))
+        .await
+    {
+        Ok(p) => PageGuard::new(p, item.url.to_string()),
         Err(e) => {
             warn!("Failed to create page for {}: {}", item.url, e);
             if let Some(ref cb) = ctx.circuit_breaker
@@ -369,8 +426,8 @@ pub async fn process_single_page(
                 cb.record_failure(&domain, &e.to_string());
             }
             let error = anyhow::Error::from(e);
-            let failure_kind = FailureKind::classify(&error);
-            return PageResult::FailedRetryable { item, error, failure_kind };
+            let failure = CrawlError::classify(&error);
+            return PageResult::FailedRetryable { item, error, failure };
         }
     };
 
@@ -383,15 +440,31 @@ pub async fn process_single_page(
     // Extract page reference for subsequent operations
     let page = page_guard.page();
 
+    // Vet every request, redirects and subresources included, against the
+    // server's URL policy; extra headers are scoped through it too
+    let interceptor = match RequestInterceptor::install(page).await {
+        Ok(interceptor) => interceptor,
+        Err(error) => {
+            let failure = CrawlError::classify(&error);
+            return PageResult::FailedRetryable { item, error, failure };
+        }
+    };
+
     // Enable Network domain to receive network events
     if let Err(e) = page.execute(EnableParams::default()).await {
         warn!("Failed to enable Network domain for {}: {}", item.url, e);
     }
 
+    // Extra headers and cookies must be in place before the first request
+    let overrides = &ctx.request_overrides;
+    if let Err(e) = super::page_enhancer::apply_request_overrides(page, &interceptor, overrides, &item.url).await {
+        warn!("Failed to apply request headers/cookies for {}: {}", item.url, e);
+    }
+
     // ═══════════════════════════════════════════════════════════════
     // NETWORK EVENT HANDLING: HTTP status capture + ETag cache check
     // ═══════════════════════════════════════════════════════════════
-    let (http_status, cache_hit) = if let Some(ref expected_etag) = cached_etag {
+    let (http_status, cache_hit, validators) = if let Some(ref cached) = cached_metadata {
         // ─────────────────────────────────────────────────────────────
         // CACHE CHECK PATH: Use check_etag_from_events for cache validation
         // ─────────────────────────────────────────────────────────────
@@ -399,49 +472,48 @@ pub async fn process_single_page(
             Ok(mut response_events) => {
                 // Navigate to page first (cache check happens during navigation)
                 let page_load_timeout = ctx.config.page_load_timeout_secs();
-                if let Err(e) = navigate_to_page(&page_guard, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
-                    let failure_kind = FailureKind::classify(&e);
-                    return PageResult::FailedRetryable { item, error: e, failure_kind };
+                if let Err(e) = navigate_to_page(&page_guard, &interceptor, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
+                    return navigation_failure(item, e);
                 }
 
                 // Use check_etag_from_events which handles multiple Document resources
                 // by matching normalized URLs (not just "first Document")
-                let etag_match = check_etag_from_events(
+                let check = check_etag_from_events(
                     &mut response_events,
                     &item.url,
-                    expected_etag,
+                    cached,
                     Duration::from_secs(ctx.config.page_load_timeout_secs()),
                 ).await;
 
-                if etag_match {
-                    debug!("Cache HIT: ETag matches for {}", item.url);
-                    (None, true)
+                if check.hit {
+                    debug!("Cache HIT: cached copy still valid for {}", item.url);
                 } else {
-                    debug!("Cache MISS: ETag mismatch for {}", item.url);
-                    (None, false)
+                    debug!("Cache MISS: no matching validator or stale copy for {}", item.url);
                 }
+                (None, check.hit, check.response)
             }
             Err(e) => {
                 warn!("Failed to subscribe to ResponseReceived events for {}: {}", item.url, e);
                 
                 // Navigate to page anyway (fallback without cache check)
                 let page_load_timeout = ctx.config.page_load_timeout_secs();
-                if let Err(e) = navigate_to_page(&page_guard, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
-                    let failure_kind = FailureKind::classify(&e);
-                    return PageResult::FailedRetryable { item, error: e, failure_kind };
+                if let Err(e) = navigate_to_page(&page_guard, &interceptor, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
+                    return navigation_failure(item, e);
                 }
                 
-                (None, false) // No cache check possible, proceed with full processing
+                (None, false, ResponseValidators::default()) // No cache check possible, proceed with full processing
             }
         }
     } else {
         // ─────────────────────────────────────────────────────────────
         // STANDARD PATH: HTTP status capture (no cached ETag to compare)
         // ─────────────────────────────────────────────────────────────
-        let status = match page.event_listener::<EventResponseReceived>().await {
+        let (status, validators) = match page.event_listener::<EventResponseReceived>().await {
             Ok(mut response_events) => {
                 // Create channel to capture HTTP status from background task
-                let (status_tx, status_rx) = tokio::sync::oneshot::channel::<u16>();
+                // (status, validator headers) of the navigation response
+                let (status_tx, status_rx) =
+                    tokio::sync::oneshot::channel::<(u16, ResponseValidators)>();
 
                 // Spawn background task and STORE the JoinHandle for cleanup
                 let target_url = item.url.clone();
@@ -485,7 +557,8 @@ pub async fn process_single_page(
                                         event.response.url,
                                         event.response.mime_type
                                     );
-                                    let _ = status_tx.send(status);
+                                    let validators = extract_validators_from_headers(&event.response.headers);
+                                    let _ = status_tx.send((status, validators));
                                     break; // Exit: Found main document response
                                 }
                                 
@@ -526,11 +599,10 @@ pub async fn process_single_page(
 
                 // Navigate to page with timeout and circuit breaker error handling
                 let page_load_timeout = ctx.config.page_load_timeout_secs();
-                if let Err(e) = navigate_to_page(&page_guard, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
+                if let Err(e) = navigate_to_page(&page_guard, &interceptor, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
                     // CRITICAL: Abort the status capture task
                     status_task_handle.abort();
-                    let failure_kind = FailureKind::classify(&e);
-                    return PageResult::FailedRetryable { item, error: e, failure_kind };
+                    return navigation_failure(item, e);
                 }
 
                 // Wait for HTTP status (with timeout to avoid blocking)
@@ -541,9 +613,9 @@ pub async fn process_single_page(
                 ).await;
                 
                 match status_result {
-                    Ok(Ok(status)) => {
+                    Ok(Ok((status, validators))) => {
                         debug!("HTTP status captured: {} for {}", status, item.url);
-                        Some(status)
+                        (Some(status), validators)
                     }
                     Ok(Err(_)) => {
                         debug!(
@@ -552,7 +624,7 @@ pub async fn process_single_page(
                         );
                         // Task should have already exited, but abort for safety
                         status_task_handle.abort();
-                        None
+                        (None, ResponseValidators::default())
                     }
                     Err(_timeout_elapsed) => {
                         debug!(
@@ -562,7 +634,7 @@ pub async fn process_single_page(
                         // Task has internal 10s timeout and should exit on its own,
                         // but abort as defensive programming
                         status_task_handle.abort();
-                        None
+                        (None, ResponseValidators::default())
                     }
                 }
             }
@@ -571,17 +643,23 @@ pub async fn process_single_page(
                 
                 // Navigate to page anyway (fallback without HTTP status capture)
                 let page_load_timeout = ctx.config.page_load_timeout_secs();
-                if let Err(e) = navigate_to_page(&page_guard, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
-                    let failure_kind = FailureKind::classify(&e);
-                    return PageResult::FailedRetryable { item, error: e, failure_kind };
+                if let Err(e) = navigate_to_page(&page_guard, &interceptor, &item.url, page_load_timeout, &ctx.circuit_breaker).await {
+                    return navigation_failure(item, e);
                 }
                 
-                None // No HTTP status available in fallback path
+                (None, ResponseValidators::default()) // No HTTP status available in fallback path
             }
         };
-        (status, false) // No cache hit in standard path
+        (status, false, validators) // No cache hit in standard path
     };
 
+    // Record the fetch outcome for broken link reporting
+    if let Some(status) = http_status
+        && let Err(e) = ctx.link_rewriter.index().record_fetch_result(&item.url, Some(status), None).await
+    {
+        debug!("Failed to record fetch result for {}: {}", item.url, e);
+    }
+
     // ═══════════════════════════════════════════════════════════════
     // EARLY RETURN ON CACHE HIT
     // ═══════════════════════════════════════════════════════════════
@@ -598,7 +676,7 @@ pub async fn process_single_page(
         
         // Publish cache hit event for monitoring
         if let Some(bus) = &ctx.event_bus {
-            let event = CrawlEvent::cache_hit(item.url.clone());
+            let event = CrawlEvent::cache_hit(item.url.to_string());
             if let Err(e) = bus.publish(event).await {
                 warn!("Failed to publish CacheHit event for {}: {}", item.url, e);
             }
@@ -630,9 +708,13 @@ pub async fn process_single_page(
         {
             cb.record_failure(&domain, &e.to_string());
         }
-        let failure_kind = FailureKind::classify(&e);
-        return PageResult::FailedRetryable { item, error: e, failure_kind };
+        let failure = CrawlError::classify(&e);
+        return PageResult::FailedRetryable { item, error: e, failure };
     }
+    let mut phases = PhaseTimings {
+        fetch: page_start.elapsed(),
+        ..PhaseTimings::default()
+    };
 
     // Retry configuration constants
     const MAX_RETRIES: u32 = 3;
@@ -664,6 +746,11 @@ pub async fn process_single_page(
         user_agent: ctx.user_agent.clone(),
         http_error_cache: Arc::clone(&ctx.http_error_cache),
         domain_queues: Arc::clone(&ctx.domain_queues),
+        ready_conditions: ctx.config.page_ready_conditions(),
+        ready_timeout_secs: ctx.config.navigation_timeout_secs(),
+        max_html_bytes: crate::utils::constants::MAX_PAGE_HTML_BYTES,
+        dismiss_overlays: ctx.config.dismiss_overlays(),
+        reject_consent: ctx.config.reject_consent(),
     };
 
     for attempt in 0..MAX_RETRIES {
@@ -674,13 +761,16 @@ pub async fn process_single_page(
             item.url
         );
 
-        let extracted_data = match page_extractor::extract_page_data(
+        let extract_start = Instant::now();
+        let extracted = page_extractor::extract_page_data(
             page_guard.page().clone(),
-            item.url.clone(),
+            item.url.to_string(),
             &extract_config,
         )
-        .await
-        {
+        .instrument(tracing::info_span!("crawl.extract", attempt))
+        .await;
+        phases.extract += extract_start.elapsed();
+        let mut extracted_data = match extracted {
             Ok(data) => data,
             Err(e) => {
                 warn!(
@@ -705,21 +795,36 @@ pub async fn process_single_page(
                         cb.record_failure(&domain, &e.to_string());
                     }
                     return PageResult::FailedPermanent {
-                        url: item.url,
-                        error: e,
+                        item,
+                        error: CrawlError::ExtractionFailed { message: format!("{e:#}") }.into(),
                     };
                 }
             }
         };
 
         // Convert HTML to markdown
-        let conversion_options = ConversionOptions {
-            base_url: Some(item.url.clone()),
-            ..ConversionOptions::default()
-        };
+        let conversion_options = ctx.config.conversion_options(&item.url);
 
-        let markdown = match convert_html_to_markdown(&extracted_data.content, &conversion_options).await {
-            Ok(md) => md,
+        let convert_start = Instant::now();
+        let converted = convert_page(&extracted_data.content, &conversion_options)
+            .instrument(tracing::info_span!("crawl.convert", attempt))
+            .await;
+        let markdown = match converted {
+            Ok(conversion) => {
+                debug!(
+                    "Converted {} with the {} extractor (requested {})",
+                    item.url, conversion.extraction.used, conversion.extraction.requested
+                );
+                if conversion.extraction.low_quality {
+                    info!(
+                        "Low extraction quality for {}{}",
+                        item.url,
+                        if conversion.extraction.unfiltered_fallback { ", kept the unfiltered conversion" } else { "" }
+                    );
+                }
+                extracted_data.metadata.extraction = Some(conversion.extraction);
+                conversion.markdown
+            }
             Err(e) => {
                 warn!(
                     "Attempt {}/{} markdown conversion failed for {}: {}, using htmd fallback",
@@ -731,6 +836,7 @@ pub async fn process_single_page(
                 crate::content_saver::markdown_converter::htmd::convert(&extracted_data.content).unwrap_or_default()
             }
         };
+        phases.convert += convert_start.elapsed();
 
         // CRITICAL: Validate content BEFORE saving
         let validation = validate_page_content(
@@ -790,13 +896,21 @@ pub async fn process_single_page(
                     );
                 }
 
+                let message = format!(
+                    "Content validation failed after {} retries: {}",
+                    MAX_RETRIES,
+                    validation.reason.unwrap_or_else(|| "Unknown error".to_string())
+                );
+                let error = match http_status {
+                    Some(_) if is_challenge_page(&extracted_data.content) => {
+                        CrawlError::ChallengeDetected { message }
+                    }
+                    Some(status) => CrawlError::Http { status, message },
+                    None => CrawlError::ExtractionFailed { message },
+                };
                 return PageResult::FailedPermanent {
-                    url: item.url,
-                    error: anyhow::anyhow!(
-                        "Content validation failed after {} retries: {}",
-                        MAX_RETRIES,
-                        validation.reason.unwrap_or_else(|| "Unknown error".to_string())
-                    ),
+                    item,
+                    error: error.into(),
                 };
             }
         }
@@ -807,11 +921,11 @@ pub async fn process_single_page(
         Some(data) => data,
         None => {
             return PageResult::FailedPermanent {
-                url: item.url,
-                error: anyhow::anyhow!(
-                    "Failed to extract valid page data after {} retries",
-                    MAX_RETRIES
-                ),
+                item,
+                error: CrawlError::ExtractionFailed {
+                    message: format!("Failed to extract valid page data after {MAX_RETRIES} retries"),
+                }
+                .into(),
             };
         }
     };
@@ -819,18 +933,43 @@ pub async fn process_single_page(
         Some(md) => md,
         None => {
             return PageResult::FailedPermanent {
-                url: item.url,
-                error: anyhow::anyhow!(
-                    "Failed to produce valid markdown after {} retries",
-                    MAX_RETRIES
-                ),
+                item,
+                error: CrawlError::ExtractionFailed {
+                    message: format!("Failed to produce valid markdown after {MAX_RETRIES} retries"),
+                }
+                .into(),
             };
         }
     };
 
     // NOTE: ensure_h1_at_start removed - htmd element handlers now produce correct headings
 
+    let content_hash = format!("{:016x}", xxhash_rust::xxh3::xxh3_64(processed_markdown.as_bytes()));
+
     let html_size = page_data.content.len();
+    publish_event(
+        ctx.event_bus.as_ref(),
+        CrawlEvent::page_fetched(item.url.to_string(), http_status, html_size),
+    )
+    .await;
+
+    let rewrite_start = Instant::now();
+    let rewrite_span = tracing::info_span!("crawl.rewrite");
+
+    // The URL a redirect ended on and the declared canonical URL become
+    // aliases of the requested URL the page is saved under
+    let final_url = page_guard
+        .page()
+        .url()
+        .await
+        .ok()
+        .flatten()
+        .filter(|url| url.starts_with("http"));
+    let canonical_url = page_data
+        .metadata
+        .canonical_url
+        .clone()
+        .filter(|url| url.starts_with("http"));
 
     // EVENT-DRIVEN LINK REWRITING: Register page and rewrite links
     // This happens AFTER HTML is saved to disk (in extract_page_data)
@@ -838,18 +977,47 @@ pub async fn process_single_page(
         // Get the local path where HTML was saved
         // storage_dir is guaranteed absolute by CrawlConfigBuilder
         if let Ok(local_path) = crate::utils::get_mirror_path(&item.url, &ctx.config.storage_dir, "index.html").await {
+            // Mirror referenced assets first so the saved page renders offline
+            if ctx.config.mirror_assets() {
+                match ctx
+                    .link_rewriter
+                    .mirror_assets(&item.url, &local_path, &ctx.user_agent)
+                    .instrument(tracing::info_span!(parent: &rewrite_span, "crawl.rewrite.assets"))
+                    .await
+                {
+                    Ok(count) if count > 0 => debug!("Mirrored {} assets for {}", count, item.url),
+                    Ok(_) => {}
+                    Err(e) => warn!("Asset mirroring failed for {}: {}", item.url, e),
+                }
+            }
+
             // Extract outbound links from the HTML content
-            let outbound_links = crate::link_rewriter::extract_links_from_html(&page_data.content, &item.url);
+            let outbound_links = crate::link_rewriter::extract_links_with_text_from_html(&page_data.content, &item.url);
 
             // Trigger event-driven link rewriting
-            match ctx.link_rewriter.on_page_saved(&item.url, &local_path, outbound_links).await {
+            match ctx
+                .link_rewriter
+                .on_page_saved(&item.url, &local_path, outbound_links)
+                .instrument(tracing::info_span!(parent: &rewrite_span, "crawl.rewrite.links"))
+                .await
+            {
                 Ok(result) => {
-                    if result.outbound_rewritten > 0 || result.inbound_updated > 0 {
+                    if result.outbound_rewritten > 0 || result.inbound_updated > 0 || result.inbound_scheduled > 0 {
                         debug!(
-                            "Link rewriting for {}: {} outbound rewritten, {} inbound pages updated",
-                            item.url, result.outbound_rewritten, result.inbound_updated
+                            "Link rewriting for {}: {} outbound rewritten, {} inbound pages updated, {} queued",
+                            item.url, result.outbound_rewritten, result.inbound_updated, result.inbound_scheduled
                         );
                     }
+                    publish_event(
+                        ctx.event_bus.as_ref(),
+                        CrawlEvent::link_rewritten(
+                            item.url.to_string(),
+                            local_path.clone(),
+                            result.outbound_rewritten,
+                            result.inbound_updated,
+                        ),
+                    )
+                    .await;
                 }
                 Err(e) => {
                     warn!("Link rewriting failed for {}: {}", item.url, e);
@@ -857,19 +1025,53 @@ pub async fn process_single_page(
                 }
             }
         }
+    } else if let Ok(local_path) = crate::utils::get_mirror_path(&item.url, &ctx.config.storage_dir, "index.md").await {
+        // No HTML on disk to rewrite, but still record the link graph so
+        // post-crawl reports (broken links) cover markdown-only crawls
+        let outbound_links = crate::link_rewriter::extract_links_with_text_from_html(&page_data.content, &item.url);
+        if let Err(e) = ctx
+            .link_rewriter
+            .index()
+            .register_page_with_anchors(&item.url, &local_path, &outbound_links)
+            .instrument(tracing::info_span!(parent: &rewrite_span, "crawl.rewrite.links"))
+            .await
+        {
+            debug!("Failed to register page in link index for {}: {}", item.url, e);
+        }
     }
 
+    // Record aliases once the page is registered so links to them resolve to
+    // its local copy
+    let aliases = [(final_url, AliasKind::Redirect), (canonical_url, AliasKind::Canonical)];
+    for (alias, kind) in aliases.iter().filter_map(|(url, kind)| Some((url.as_deref()?, *kind))) {
+        let recorded = if ctx.config.save_raw_html() {
+            ctx.link_rewriter.on_alias_discovered(alias, &item.url, kind).await.map(|_| ())
+        } else {
+            ctx.link_rewriter.index().register_alias(alias, &item.url, kind).await.map(|_| ())
+        };
+        if let Err(e) = recorded {
+            debug!("Failed to record {} alias {} → {}: {}", kind.as_str(), alias, item.url, e);
+        }
+    }
+
+    phases.rewrite = rewrite_start.elapsed();
+    drop(rewrite_span);
+    let save_start = Instant::now();
+    let save_span = tracing::info_span!("crawl.save");
+
     // Save markdown if requested (only executed if validation passed)
     if ctx.config.save_markdown() {
         match content_saver::save_markdown_content(
             processed_markdown,
-            item.url.clone(),
+            item.url.to_string(),
             ctx.config.storage_dir.clone(),
             crate::search::MessagePriority::Normal,
             ctx.indexing_sender.clone(),
             ctx.config.compress_output,
             ctx.config.compression_threshold_bytes(),
+            validators,
         )
+        .instrument(tracing::info_span!(parent: &save_span, "crawl.save.markdown"))
         .await
         {
             Ok(()) => debug!("Markdown saved for {}", item.url),
@@ -886,10 +1088,11 @@ pub async fn process_single_page(
         page_data.links = extracted_links.clone();
         match content_saver::save_page_data(
             page_data,
-            item.url.clone(),
+            item.url.to_string(),
             ctx.config.storage_dir.clone(),
             ctx.config.compression_threshold_bytes(),
         )
+        .instrument(tracing::info_span!(parent: &save_span, "crawl.save.json"))
         .await
         {
             Ok(()) => debug!("Page data saved for {}", item.url),
@@ -897,6 +1100,31 @@ pub async fn process_single_page(
         }
     }
 
+    // Run the extraction schema matching this URL, if any, into data.json
+    if let Some(extraction) = ctx.config.extraction_schema_for(&item.url) {
+        let saved = async {
+            let mut records = page_extractor::extract_structured(page_guard.page(), extraction).await?;
+            let data = if extraction.spec().item_selector.is_some() || records.len() != 1 {
+                serde_json::Value::Array(records)
+            } else {
+                records.swap_remove(0)
+            };
+            content_saver::save_structured_data(
+                data,
+                item.url.to_string(),
+                ctx.config.storage_dir.clone(),
+                ctx.config.compression_threshold_bytes(),
+            )
+            .await
+        }
+        .instrument(tracing::info_span!(parent: &save_span, "crawl.save.structured"))
+        .await;
+        match saved {
+            Ok(()) => debug!("Structured data saved for {}", item.url),
+            Err(e) => warn!("Failed to extract structured data for {}: {}", item.url, e),
+        }
+    }
+
     // Capture screenshot if requested
     let mut screenshot_captured = false;
     if ctx.config.save_screenshots() {
@@ -906,6 +1134,7 @@ pub async fn process_single_page(
             ctx.config.storage_dir(),
             ctx.config.compression_threshold_bytes(),
         )
+        .instrument(tracing::info_span!(parent: &save_span, "crawl.save.screenshot"))
         .await
         {
             Ok(()) => {
@@ -916,6 +1145,16 @@ pub async fn process_single_page(
         }
     }
 
+    phases.save = save_start.elapsed();
+    drop(save_span);
+
+    // All requested output files are written; report the page under the file
+    // it is registered as in the link index
+    let saved_file = if ctx.config.save_raw_html() { "index.html" } else { "index.md" };
+    if let Ok(saved_path) = crate::content_saver::get_mirror_path_sync(&item.url, &ctx.config.storage_dir, saved_file) {
+        publish_event(ctx.event_bus.as_ref(), CrawlEvent::page_saved(item.url.to_string(), saved_path)).await;
+    }
+
     // Process page links and add discovered URLs to the crawl queue
     // Uses already-extracted links from page_data instead of re-extracting from browser
     let links_found = {
@@ -932,25 +1171,30 @@ pub async fn process_single_page(
                 filtered_urls.len()
             );
             
-            // Batch deduplication within this page's links
-            let mut seen_in_batch: HashSet<String> = HashSet::new();
+            // Batch deduplication within this page's links; links back to
+            // this page (including its `#section` anchors) are not queued
+            let mut seen_in_batch: HashSet<ImString> = HashSet::new();
+            if let Some(own_url) = frontier_url(&item.url) {
+                seen_in_batch.insert(ctx.urls.intern(&own_url));
+            }
             let mut results = Vec::new();
             
             for link_url in filtered_urls {
-                let normalized_url = crate::link_index::normalize_url(&link_url);
+                let Some(key) = frontier_url(&link_url) else {
+                    continue;
+                };
+                let normalized_url = ctx.urls.intern(&key);
                 
                 // Skip duplicates within this batch
                 if !seen_in_batch.insert(normalized_url.clone()) {
                     continue;
                 }
                 
-                if url::Url::parse(&normalized_url).is_ok() {
-                    results.push(CrawlQueue {
-                        url: normalized_url,
-                        depth: item.depth + 1,
-                        retry_count: 0,
-                    });
-                }
+                results.push(CrawlQueue {
+                    url: normalized_url,
+                    depth: item.depth + 1,
+                    retry_count: 0,
+                });
             }
             results
         } else {
@@ -965,6 +1209,11 @@ pub async fn process_single_page(
             for new_link in new_links {
                 // Skip if URL already visited (orchestrator handles insert at dequeue time)
                 if !ctx.visited.contains(&new_link.url) {
+                    publish_event(
+                        ctx.event_bus.as_ref(),
+                        CrawlEvent::page_queued(new_link.url.to_string(), u32::from(new_link.depth)),
+                    )
+                    .await;
                     q.push_back(new_link);
                     actually_queued += 1;
                 }
@@ -1000,6 +1249,9 @@ pub async fn process_single_page(
             links_for_crawling: links_found,
             screenshot_captured,
             processing_duration: page_start.elapsed(),
+            phase_timings: phases,
+            queue_size: ctx.queue.lock().await.len(),
+            content_hash: Some(content_hash),
         };
 
         let local_path = match crate::content_saver::get_mirror_path_sync(
@@ -1025,7 +1277,7 @@ pub async fn process_single_page(
         };
 
         let event = CrawlEvent::page_crawled(
-            item.url.clone(),
+            item.url.to_string(),
             local_path,
             u32::from(item.depth),
             metadata,
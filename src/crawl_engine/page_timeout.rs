@@ -7,6 +7,8 @@ use anyhow::Result;
 use std::future::Future;
 use std::time::Duration;
 
+use super::crawl_types::CrawlError;
+
 /// Helper function to wrap async page operations with explicit timeout
 ///
 /// Prevents indefinite hangs on page operations by applying `tokio::time::timeout`.
@@ -20,6 +22,7 @@ use std::time::Duration;
 /// # Returns
 /// * `Ok(T)` - Operation completed successfully
 /// * `Err` - Either the operation failed or the timeout was reached
+///   ([`CrawlError::NavTimeout`])
 pub async fn with_page_timeout<F, T>(
     operation: F,
     timeout_secs: u64,
@@ -30,8 +33,9 @@ where
 {
     match tokio::time::timeout(Duration::from_secs(timeout_secs), operation).await {
         Ok(result) => result,
-        Err(_) => Err(anyhow::anyhow!(
-            "{operation_name} timeout after {timeout_secs} seconds"
-        )),
+        Err(_) => Err(CrawlError::NavTimeout {
+            message: format!("{operation_name} timeout after {timeout_secs} seconds"),
+        }
+        .into()),
     }
 }
@@ -1,7 +1,13 @@
 //! Progress reporting abstraction for crawl operations
 //!
 //! Defines the `ProgressReporter` trait for lifecycle event reporting
-//! and provides a no-op implementation for simple use cases.
+//! and provides a no-op implementation for simple use cases, plus
+//! [`CallbackProgress`] for embedders that want updates as they happen.
+
+use std::sync::Arc;
+
+use super::crawl_types::CrawlProgress;
+use crate::crawl_events::CrawlEvent;
 
 /// Trait for reporting crawl progress at key lifecycle events
 ///
@@ -72,3 +78,131 @@ impl ProgressReporter for NoOpProgress {
     #[inline(always)]
     fn report_error(&self, _error: &str) {}
 }
+
+/// Progress reporter that passes every update to a callback
+///
+/// Lifecycle updates come from the orchestrator through [`ProgressReporter`];
+/// per-page updates are translated from crawl events with [`Self::report_event`].
+#[derive(Clone)]
+pub struct CallbackProgress {
+    callback: Arc<dyn Fn(CrawlProgress) + Send + Sync>,
+}
+
+impl CallbackProgress {
+    pub fn new(callback: impl Fn(CrawlProgress) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Arc::new(callback),
+        }
+    }
+
+    fn send(&self, progress: CrawlProgress) {
+        (self.callback)(progress);
+    }
+
+    /// Report the page-level events; lifecycle events are reported by the
+    /// orchestrator and are ignored here
+    pub fn report_event(&self, event: &CrawlEvent) {
+        match event {
+            CrawlEvent::PageFetched { url, .. } => self.report_page_loaded(url),
+            CrawlEvent::PageCrawled {
+                url,
+                local_path,
+                metadata,
+                ..
+            } => self.send(CrawlProgress::PageCrawled {
+                url: url.clone(),
+                local_path: local_path.clone(),
+                queue_size: metadata.queue_size,
+            }),
+            CrawlEvent::Error { url, message, .. } => self.send(CrawlProgress::PageFailed {
+                url: url.clone(),
+                error: message.clone(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Debug for CallbackProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CallbackProgress").finish_non_exhaustive()
+    }
+}
+
+impl ProgressReporter for CallbackProgress {
+    fn report_initializing(&self) {
+        self.send(CrawlProgress::Initializing);
+    }
+
+    fn report_browser_launched(&self) {
+        self.send(CrawlProgress::BrowserLaunched);
+    }
+
+    fn report_navigation_started(&self, url: &str) {
+        self.send(CrawlProgress::NavigationStarted(url.to_string()));
+    }
+
+    fn report_page_loaded(&self, url: &str) {
+        self.send(CrawlProgress::PageLoaded(url.to_string()));
+    }
+
+    fn report_extracting_data(&self) {
+        self.send(CrawlProgress::ExtractingData);
+    }
+
+    fn report_taking_screenshot(&self) {
+        self.send(CrawlProgress::TakingScreenshot);
+    }
+
+    fn report_cleanup_started(&self) {
+        self.send(CrawlProgress::CleanupStarted);
+    }
+
+    fn report_completed(&self) {
+        self.send(CrawlProgress::Completed);
+    }
+
+    fn report_error(&self, error: &str) {
+        self.send(CrawlProgress::Error(error.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawl_events::{PageCrawlMetadata, PhaseTimings};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_callback_receives_page_events() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let progress = CallbackProgress::new(move |p| sink.lock().unwrap().push(format!("{p:?}")));
+
+        progress.report_initializing();
+        progress.report_event(&CrawlEvent::page_queued("https://example.com/a".into(), 1));
+        progress.report_event(&CrawlEvent::page_crawled(
+            "https://example.com/a".into(),
+            "out/a/index.md".into(),
+            1,
+            PageCrawlMetadata {
+                html_size: 10,
+                compressed_size: 5,
+                links_found: 0,
+                links_for_crawling: 0,
+                screenshot_captured: false,
+                processing_duration: std::time::Duration::ZERO,
+                queue_size: 3,
+                phase_timings: PhaseTimings::default(),
+                content_hash: None,
+            },
+        ));
+        progress.report_event(&CrawlEvent::error("https://example.com/b".into(), "HTTP 500".into()));
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 3, "queued pages are not reported: {seen:?}");
+        assert_eq!(seen[0], "Initializing");
+        assert!(seen[1].starts_with("PageCrawled") && seen[1].contains("queue_size: 3"));
+        assert!(seen[2].starts_with("PageFailed") && seen[2].contains("HTTP 500"));
+    }
+}
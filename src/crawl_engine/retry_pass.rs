@@ -0,0 +1,224 @@
+//! End-of-crawl retry pass for pages that failed transiently
+//!
+//! Pages that run out of retries on a transient failure (see
+//! [`CrawlError::is_transient`]) are held here instead of being reported as
+//! failed. Once the crawl queue drains they are queued once more, with longer
+//! timeouts and optionally a different browser, and only the pages that fail
+//! again are reported.
+
+use imstr::ImString;
+use std::collections::HashMap;
+
+use super::crawl_types::{CrawlError, CrawlQueue};
+use crate::config::CrawlConfig;
+
+/// A page held back for the retry pass, with the failure that put it there
+#[derive(Debug)]
+pub struct DeferredFailure {
+    pub item: CrawlQueue,
+    pub error: anyhow::Error,
+    /// Attempts made, when the page's per-page retries were exhausted
+    pub attempts: Option<u8>,
+}
+
+/// Pages collected for, and recovered by, the retry pass
+#[derive(Debug, Default)]
+pub struct RetryPass {
+    deferred: Vec<DeferredFailure>,
+    /// Pages re-queued by `start` whose outcome is still open, with the
+    /// failure that deferred them
+    attempted: HashMap<ImString, DeferredFailure>,
+    recovered: Vec<String>,
+    failed: Vec<String>,
+    started: bool,
+}
+
+impl RetryPass {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a page that failed with `error` should wait for the retry pass
+    ///
+    /// Only transient failures qualify, and only until the pass starts.
+    #[must_use]
+    pub fn accepts(&self, error: &anyhow::Error) -> bool {
+        !self.started && CrawlError::classify(error).is_transient()
+    }
+
+    /// Hold a failed page back for the retry pass
+    pub fn defer(&mut self, failure: DeferredFailure) {
+        self.deferred.push(failure);
+    }
+
+    /// Whether pages are waiting for a pass that has not started
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        !self.started && !self.deferred.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Start the pass, returning the pages to queue again
+    pub fn start(&mut self) -> Vec<CrawlQueue> {
+        self.started = true;
+        let items: Vec<CrawlQueue> = self.deferred.iter().map(|failure| failure.item.clone()).collect();
+        self.attempted
+            .extend(self.deferred.drain(..).map(|failure| (failure.item.url.clone(), failure)));
+        items
+    }
+
+    /// Note a page that was crawled successfully
+    pub fn record_success(&mut self, url: &ImString) {
+        if self.attempted.remove(url).is_some() {
+            self.recovered.push(url.to_string());
+        }
+    }
+
+    /// Note a page that failed again (and was reported as failed)
+    pub fn record_failure(&mut self, url: &ImString) {
+        if self.attempted.remove(url).is_some() {
+            self.failed.push(url.to_string());
+        }
+    }
+
+    /// Pages without an outcome, e.g. because the crawl was cancelled: those
+    /// never retried and those retried but not finished
+    ///
+    /// Each keeps its original failure, to be reported as such.
+    pub fn take_unfinished(&mut self) -> Vec<DeferredFailure> {
+        let mut unfinished = std::mem::take(&mut self.deferred);
+        for (url, failure) in self.attempted.drain() {
+            self.failed.push(url.to_string());
+            unfinished.push(failure);
+        }
+        unfinished
+    }
+
+    /// Recovered and still-failing URLs of a started pass, each sorted
+    #[must_use]
+    pub fn finish(self) -> Option<(Vec<String>, Vec<String>)> {
+        if !self.started {
+            return None;
+        }
+        let mut recovered = self.recovered;
+        let mut failed = self.failed;
+        failed.extend(self.attempted.into_keys().map(|url| url.to_string()));
+        recovered.sort();
+        failed.sort();
+        Some((recovered, failed))
+    }
+}
+
+/// The crawl configuration for the retry pass
+///
+/// Page load and navigation timeouts are scaled by
+/// `retry_pass_timeout_multiplier`.
+#[must_use]
+pub fn retry_pass_config(config: &CrawlConfig) -> CrawlConfig {
+    let multiplier = config.retry_pass_timeout_multiplier();
+    let scale = |secs: u64| (secs as f64 * multiplier).round() as u64;
+    let mut retry_config = config.clone();
+    retry_config.page_load_timeout_secs = Some(scale(config.page_load_timeout_secs()));
+    retry_config.navigation_timeout_secs = Some(scale(config.navigation_timeout_secs()));
+    retry_config
+}
+
+/// Whether the retry pass needs its own browser rather than the crawl's
+#[must_use]
+pub fn needs_own_browser(config: &CrawlConfig) -> bool {
+    config.retry_pass_headless() != config.headless() || config.retry_pass_proxy().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(url: &str) -> CrawlQueue {
+        CrawlQueue {
+            url: ImString::from(url),
+            depth: 1,
+            retry_count: 3,
+        }
+    }
+
+    fn failure(url: &str, message: &str) -> DeferredFailure {
+        DeferredFailure {
+            item: item(url),
+            error: anyhow::anyhow!("{message}"),
+            attempts: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_accepts_only_transient_failures_before_start() {
+        let mut pass = RetryPass::new();
+        assert!(pass.accepts(&anyhow::anyhow!("Page navigation timeout after 30 seconds")));
+        assert!(pass.accepts(&anyhow::anyhow!("HTTP error: 503")));
+        assert!(!pass.accepts(&anyhow::anyhow!("HTTP error: 404")));
+
+        pass.defer(failure("https://example.com/a", "net::ERR_CONNECTION_RESET"));
+        assert!(pass.is_pending());
+        pass.start();
+        assert!(!pass.is_pending());
+        assert!(!pass.accepts(&anyhow::anyhow!("Page navigation timeout after 30 seconds")));
+    }
+
+    #[test]
+    fn test_reports_recovered_and_failed_pages() {
+        assert!(RetryPass::new().finish().is_none());
+
+        let mut pass = RetryPass::new();
+        pass.defer(failure("https://example.com/b", "HTTP error: 502"));
+        pass.defer(failure("https://example.com/a", "Page navigation timeout after 30 seconds"));
+        let items = pass.start();
+        assert_eq!(items.len(), 2);
+
+        pass.record_success(&ImString::from("https://example.com/a"));
+        pass.record_success(&ImString::from("https://example.com/other"));
+        let (recovered, failed) = pass.finish().unwrap();
+        assert_eq!(recovered, vec!["https://example.com/a"]);
+        assert_eq!(failed, vec!["https://example.com/b"]);
+    }
+
+    #[test]
+    fn test_unfinished_pages_keep_their_failure() {
+        let mut pass = RetryPass::new();
+        pass.defer(failure("https://example.com/a", "HTTP error: 502"));
+        pass.defer(failure("https://example.com/b", "HTTP error: 503"));
+        pass.defer(failure("https://example.com/c", "HTTP error: 504"));
+        pass.start();
+        pass.record_success(&ImString::from("https://example.com/a"));
+        pass.record_failure(&ImString::from("https://example.com/b"));
+
+        // Cancelled before c finished: only c is left to report
+        let unfinished = pass.take_unfinished();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].item.url.as_str(), "https://example.com/c");
+        assert_eq!(unfinished[0].error.to_string(), "HTTP error: 504");
+        assert!(pass.take_unfinished().is_empty());
+
+        let (recovered, failed) = pass.finish().unwrap();
+        assert_eq!(recovered, vec!["https://example.com/a"]);
+        assert_eq!(failed, vec!["https://example.com/b", "https://example.com/c"]);
+    }
+
+    #[test]
+    fn test_retry_pass_config_scales_timeouts() {
+        let mut config = CrawlConfig::builder()
+            .storage_dir("/tmp/retry-pass")
+            .start_url("https://example.com")
+            .retry_pass_timeout_multiplier(2.5)
+            .build()
+            .unwrap();
+        config.page_load_timeout_secs = Some(20);
+        let retry_config = retry_pass_config(&config);
+        assert_eq!(retry_config.page_load_timeout_secs(), 50);
+        assert_eq!(retry_config.navigation_timeout_secs(), 75);
+        assert!(!needs_own_browser(&config));
+    }
+}
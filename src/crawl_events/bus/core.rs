@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize};
-use tokio::sync::{Mutex, Notify, broadcast};
+use tokio::sync::{Mutex, Notify, broadcast, mpsc};
 
 use crate::crawl_events::config::EventBusConfig;
 use crate::crawl_events::metrics::EventBusMetrics;
@@ -12,6 +12,8 @@ use crate::crawl_events::types::CrawlEvent;
 #[derive(Debug)]
 pub struct CrawlEventBus {
     pub(super) sender: broadcast::Sender<CrawlEvent>,
+    /// Lossless subscribers, see [`CrawlEventBus::subscribe_lossless`]
+    pub(super) lossless: Arc<std::sync::Mutex<Vec<mpsc::UnboundedSender<CrawlEvent>>>>,
     pub(super) config: Arc<EventBusConfig>,
     pub(super) metrics: EventBusMetrics,
     pub(super) shutdown: Arc<Notify>,
@@ -54,6 +56,7 @@ impl CrawlEventBus {
         let num_instances = Arc::new(AtomicUsize::new(1));
         Self {
             sender,
+            lossless: Arc::default(),
             config: Arc::new(config),
             metrics,
             shutdown,
@@ -18,6 +18,7 @@ impl Clone for CrawlEventBus {
         self.num_instances.fetch_add(1, Ordering::Relaxed);
         Self {
             sender: self.sender.clone(),
+            lossless: self.lossless.clone(),
             config: self.config.clone(),
             metrics: self.metrics.clone(),
             shutdown: self.shutdown.clone(),
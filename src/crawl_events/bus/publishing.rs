@@ -10,6 +10,25 @@ use crate::crawl_events::types::{BatchPublishResult, CrawlEvent};
 use super::core::CrawlEventBus;
 
 impl CrawlEventBus {
+    /// Deliver `event` to the lossless subscribers, then broadcast it
+    ///
+    /// Returns the number of subscribers reached, `None` if there were none.
+    fn send(&self, event: CrawlEvent) -> Option<usize> {
+        let lossless = {
+            let mut lossless = self
+                .lossless
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            lossless.retain(|tx| tx.send(event.clone()).is_ok());
+            lossless.len()
+        };
+        match self.sender.send(event) {
+            Ok(count) => Some(count + lossless),
+            Err(_) if lossless > 0 => Some(lossless),
+            Err(_) => None,
+        }
+    }
+
     /// Publish an event to all subscribers
     ///
     /// # Arguments
@@ -19,7 +38,7 @@ impl CrawlEventBus {
     /// * `Ok(usize)` - Number of active subscribers that received the event
     /// * `Err(EventBusError)` - If publishing failed
     pub async fn publish(&self, event: CrawlEvent) -> Result<usize, EventBusError> {
-        if let Ok(subscriber_count) = self.sender.send(event) {
+        if let Some(subscriber_count) = self.send(event) {
             if self.config.enable_metrics {
                 self.metrics.increment_published();
                 self.metrics.update_subscriber_count(subscriber_count);
@@ -143,7 +162,7 @@ impl CrawlEventBus {
                     }
 
                     // Now publish (should succeed since we have space)
-                    if let Ok(subscriber_count) = self.sender.send(event) {
+                    if let Some(subscriber_count) = self.send(event) {
                         if self.config.enable_metrics {
                             self.metrics.increment_published();
                             self.metrics.update_subscriber_count(subscriber_count);
@@ -208,7 +227,7 @@ impl CrawlEventBus {
                 }
 
                 // Send with reserved slot (protected by lock)
-                if let Ok(subscriber_count) = self.sender.send(event) {
+                if let Some(subscriber_count) = self.send(event) {
                     if self.config.enable_metrics {
                         self.metrics.increment_published();
                         self.metrics.update_subscriber_count(subscriber_count);
@@ -265,6 +284,9 @@ impl CrawlEventBus {
     ///     links_for_crawling: 5,
     ///     screenshot_captured: true,
     ///     processing_duration: Duration::from_millis(100),
+    ///     queue_size: 0,
+    ///     phase_timings: Default::default(),
+    ///     content_hash: None,
     /// };
     ///
     /// let events = vec![
@@ -293,7 +315,7 @@ impl CrawlEventBus {
         let mut max_subscribers = 0;
 
         for event in events {
-            if let Ok(count) = self.sender.send(event) {
+            if let Some(count) = self.send(event) {
                 published += 1;
                 max_subscribers = std::cmp::max(max_subscribers, count);
 
@@ -1,6 +1,6 @@
 //! Subscription operations for the CrawlEventBus
 
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
 use crate::crawl_events::streaming::FilteredReceiver;
 use crate::crawl_events::types::CrawlEvent;
@@ -17,10 +17,32 @@ impl CrawlEventBus {
         self.sender.subscribe()
     }
 
+    /// Subscribe to every event
+    ///
+    /// Unlike [`Self::subscribe`], the receiver never lags: events queue
+    /// without bound until it reads them. Meant for consumers that must see
+    /// every event, such as the event journal and crawl manifests.
+    #[must_use]
+    pub fn subscribe_lossless(&self) -> mpsc::UnboundedReceiver<CrawlEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.lossless
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(tx);
+        rx
+    }
+
     /// Get the number of active subscribers
     #[must_use]
     pub fn subscriber_count(&self) -> usize {
-        let count = self.sender.receiver_count();
+        let lossless = self
+            .lossless
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .filter(|tx| !tx.is_closed())
+            .count();
+        let count = self.sender.receiver_count() + lossless;
         if self.config.enable_metrics {
             self.metrics.update_subscriber_count(count);
         }
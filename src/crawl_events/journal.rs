@@ -0,0 +1,327 @@
+//! Append-only JSONL journal of crawl events
+//!
+//! Every event of a crawl is written as one JSON line to
+//! `.citescrape/events.jsonl` in the output directory. The journal outlives
+//! the crawl, so a failed or interrupted run can be examined afterwards and
+//! [`JournalSummary`] can tell which queued pages were never saved.
+//!
+//! When the active file grows past its size limit it is renamed to
+//! `events.1.jsonl` (older files shift to `events.2.jsonl`, ...) and a fresh
+//! file is started; only the newest rotations are kept.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use super::types::CrawlEvent;
+
+/// Size at which the active journal file is rotated
+pub const DEFAULT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Rotated journal files kept besides the active one
+pub const DEFAULT_KEEP_ROTATIONS: usize = 4;
+
+/// Writer for a crawl output directory's event journal
+pub struct EventJournal {
+    path: PathBuf,
+    file: tokio::fs::File,
+    written: u64,
+    max_bytes: u64,
+    keep_rotations: usize,
+}
+
+impl EventJournal {
+    /// Path of the active journal file for `output_dir`
+    #[must_use]
+    pub fn journal_path(output_dir: &Path) -> PathBuf {
+        output_dir.join(".citescrape").join("events.jsonl")
+    }
+
+    /// Open the journal of `output_dir` for appending, creating it if needed
+    pub async fn open(output_dir: &Path) -> Result<Self> {
+        let path = Self::journal_path(output_dir);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = open_append(&path).await?;
+        let written = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes: DEFAULT_MAX_BYTES,
+            keep_rotations: DEFAULT_KEEP_ROTATIONS,
+        })
+    }
+
+    /// Rotate at `max_bytes`, keeping `keep_rotations` older files
+    #[must_use]
+    pub fn with_rotation(mut self, max_bytes: u64, keep_rotations: usize) -> Self {
+        self.max_bytes = max_bytes.max(1);
+        self.keep_rotations = keep_rotations;
+        self
+    }
+
+    /// Append one event as a JSON line
+    pub async fn append(&mut self, event: &CrawlEvent) -> Result<()> {
+        let mut line = serde_json::to_vec(event).context("Failed to serialize crawl event")?;
+        line.push(b'\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate().await?;
+        }
+        self.file
+            .write_all(&line)
+            .await
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        self.file.flush().await?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Write events from `receiver` until the crawl shuts the bus down or
+    /// `stop` fires, then drain what is already buffered
+    ///
+    /// `receiver` should come from [`super::CrawlEventBus::subscribe_lossless`]
+    /// so no event is skipped however far the writer falls behind.
+    pub fn spawn(
+        mut self,
+        mut receiver: mpsc::UnboundedReceiver<CrawlEvent>,
+        mut stop: oneshot::Receiver<()>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    event = receiver.recv() => event,
+                    _ = &mut stop => break,
+                };
+                let Some(event) = event else { return };
+                let last = matches!(event, CrawlEvent::Shutdown { .. });
+                self.record(&event).await;
+                if last {
+                    return;
+                }
+            }
+            while let Ok(event) = receiver.try_recv() {
+                self.record(&event).await;
+            }
+        })
+    }
+
+    async fn record(&mut self, event: &CrawlEvent) {
+        if let Err(e) = self.append(event).await {
+            log::warn!("Failed to journal crawl event: {e:#}");
+        }
+    }
+
+    async fn rotate(&mut self) -> Result<()> {
+        for n in (1..=self.keep_rotations).rev() {
+            let from = if n == 1 {
+                self.path.clone()
+            } else {
+                rotated_path(&self.path, n - 1)
+            };
+            if from.exists() {
+                tokio::fs::rename(&from, rotated_path(&self.path, n))
+                    .await
+                    .with_context(|| format!("Failed to rotate {}", from.display()))?;
+            }
+        }
+        if self.keep_rotations == 0 {
+            tokio::fs::remove_file(&self.path).await.ok();
+        }
+        self.file = open_append(&self.path).await?;
+        self.written = 0;
+        Ok(())
+    }
+
+    /// Read every journaled event of `output_dir`, oldest first
+    ///
+    /// Lines that do not parse (such as one torn by a crash mid-write) are
+    /// skipped.
+    pub fn read(output_dir: &Path) -> Result<Vec<CrawlEvent>> {
+        let path = Self::journal_path(output_dir);
+        let mut files: Vec<PathBuf> = (1..)
+            .map(|n| rotated_path(&path, n))
+            .take_while(|p| p.exists())
+            .collect();
+        files.reverse();
+        files.push(path);
+
+        let mut events = Vec::new();
+        for file in files.iter().filter(|p| p.exists()) {
+            let text = std::fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                match serde_json::from_str(line) {
+                    Ok(event) => events.push(event),
+                    Err(e) => log::debug!("Skipping unreadable journal line in {}: {e}", file.display()),
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
+/// Outcome of the most recent crawl recorded in a journal
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalSummary {
+    /// Start URL of the crawl, if its `CrawlStarted` event was journaled
+    pub start_url: Option<String>,
+    /// URLs that were queued
+    pub queued: BTreeSet<String>,
+    /// URLs whose output files were written
+    pub saved: BTreeSet<String>,
+    /// URLs that failed for good, with the last error
+    pub failed: BTreeMap<String, String>,
+    /// Whether the crawl reached `CrawlCompleted`
+    pub completed: bool,
+}
+
+impl JournalSummary {
+    /// Summarize the events after the last `CrawlStarted`
+    #[must_use]
+    pub fn from_events(events: &[CrawlEvent]) -> Self {
+        let start = events
+            .iter()
+            .rposition(|e| matches!(e, CrawlEvent::CrawlStarted { .. }))
+            .unwrap_or(0);
+
+        let mut summary = Self::default();
+        for event in &events[start..] {
+            match event {
+                CrawlEvent::CrawlStarted { start_url, .. } => summary.start_url = Some(start_url.clone()),
+                CrawlEvent::PageQueued { url, .. } => {
+                    summary.queued.insert(url.clone());
+                }
+                CrawlEvent::PageSaved { url, .. } | CrawlEvent::PageCrawled { url, .. } => {
+                    summary.failed.remove(url);
+                    summary.saved.insert(url.clone());
+                }
+                CrawlEvent::Error { url, message, .. } => {
+                    summary.failed.insert(url.clone(), message.clone());
+                }
+                CrawlEvent::CrawlCompleted { .. } => summary.completed = true,
+                _ => {}
+            }
+        }
+        summary
+    }
+
+    /// Summarize the most recent crawl journaled in `output_dir`
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        Ok(Self::from_events(&EventJournal::read(output_dir)?))
+    }
+
+    /// Queued URLs that were neither saved nor failed: what a resumed crawl
+    /// still has to visit
+    pub fn pending(&self) -> impl Iterator<Item = &str> {
+        self.queued
+            .iter()
+            .filter(|url| !self.saved.contains(*url) && !self.failed.contains_key(*url))
+            .map(String::as_str)
+    }
+}
+
+/// `events.jsonl` -> `events.{n}.jsonl`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("{n}.jsonl"))
+}
+
+async fn open_append(path: &Path) -> Result<tokio::fs::File> {
+    tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_rotation_keeps_order_and_limit() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut journal = EventJournal::open(temp_dir.path()).await?.with_rotation(200, 2);
+        for i in 0..20 {
+            journal.append(&CrawlEvent::page_queued(format!("https://example.com/{i}"), 1)).await?;
+        }
+
+        let path = EventJournal::journal_path(temp_dir.path());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        let urls: Vec<String> = EventJournal::read(temp_dir.path())?
+            .into_iter()
+            .filter_map(|e| match e {
+                CrawlEvent::PageQueued { url, .. } => Some(url),
+                _ => None,
+            })
+            .collect();
+        assert!(urls.len() < 20, "oldest rotations are dropped");
+        assert_eq!(urls.last().map(String::as_str), Some("https://example.com/19"));
+        assert!(urls.windows(2).all(|w| {
+            let n = |u: &str| u.rsplit('/').next().and_then(|s| s.parse::<u32>().ok());
+            n(&w[0]) < n(&w[1])
+        }));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_writer_keeps_every_event_of_a_small_bus() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let bus = crate::crawl_events::CrawlEventBus::new(2);
+        let (_stop_tx, stop_rx) = oneshot::channel();
+        let writer = EventJournal::open(temp_dir.path()).await?.spawn(bus.subscribe_lossless(), stop_rx);
+        for i in 0..100 {
+            bus.publish(CrawlEvent::page_queued(format!("https://example.com/{i}"), 1)).await?;
+        }
+        bus.publish(CrawlEvent::shutdown(crate::crawl_events::types::ShutdownReason::CrawlCompleted))
+            .await?;
+        writer.await?;
+
+        let summary = JournalSummary::load(temp_dir.path())?;
+        assert_eq!(summary.queued.len(), 100);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_summary_of_last_crawl() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let mut journal = EventJournal::open(temp_dir.path()).await?;
+        let events = [
+            CrawlEvent::crawl_started("https://old.example".into(), temp_dir.path().into(), 1),
+            CrawlEvent::page_queued("https://old.example/x".into(), 1),
+            CrawlEvent::crawl_started("https://example.com".into(), temp_dir.path().into(), 2),
+            CrawlEvent::page_queued("https://example.com".into(), 0),
+            CrawlEvent::page_queued("https://example.com/a".into(), 1),
+            CrawlEvent::page_queued("https://example.com/b".into(), 1),
+            CrawlEvent::page_saved("https://example.com".into(), temp_dir.path().join("index.md")),
+            CrawlEvent::error("https://example.com/b".into(), "HTTP 500".into()),
+        ];
+        for event in &events {
+            journal.append(event).await?;
+        }
+        // A write torn by a crash does not hide earlier events
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(EventJournal::journal_path(temp_dir.path()))
+            .and_then(|mut f| std::io::Write::write_all(&mut f, b"{\"PageQue"))?;
+
+        let summary = JournalSummary::load(temp_dir.path())?;
+        assert_eq!(summary.start_url.as_deref(), Some("https://example.com"));
+        assert_eq!(summary.queued.len(), 3);
+        assert_eq!(summary.failed.get("https://example.com/b").map(String::as_str), Some("HTTP 500"));
+        assert_eq!(summary.pending().collect::<Vec<_>>(), ["https://example.com/a"]);
+        assert!(!summary.completed);
+        Ok(())
+    }
+}
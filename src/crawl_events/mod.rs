@@ -7,14 +7,18 @@
 pub mod bus;
 pub mod config;
 pub mod errors;
+pub mod journal;
 pub mod metrics;
 pub mod streaming;
+pub mod throughput;
 pub mod types;
 
 // Re-exports for public API
 pub use bus::CrawlEventBus;
 pub use config::EventBusConfig;
 pub use errors::EventBusError;
+pub use journal::{EventJournal, JournalSummary};
 pub use metrics::EventBusMetrics;
 pub use streaming::FilteredReceiver;
-pub use types::{BatchPublishResult, CrawlEvent, PageCrawlMetadata, ShutdownReason};
+pub use throughput::{CrawlThroughput, PhaseAverages, ThroughputTracker};
+pub use types::{BatchPublishResult, CrawlEvent, PageCrawlMetadata, PhaseTimings, ShutdownReason};
@@ -0,0 +1,185 @@
+//! Rolling throughput and latency figures computed from crawl events
+//!
+//! [`ThroughputTracker`] keeps the pages completed in the last
+//! [`THROUGHPUT_WINDOW`] and turns them into a [`CrawlThroughput`] snapshot:
+//! pages and bytes per second, average page latency and where that time went.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::types::{CrawlEvent, PhaseTimings};
+
+/// Span of recent pages the rates and averages are computed over
+pub const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Average milliseconds per page spent in each processing phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseAverages {
+    /// Rate limiting, page setup, navigation and load
+    pub fetch_ms: f64,
+    /// DOM extraction, including inlining resources and writing raw HTML
+    pub extract_ms: f64,
+    /// HTML to markdown conversion
+    pub convert_ms: f64,
+    /// Writing markdown, JSON and screenshots
+    pub save_ms: f64,
+    /// Alias registration, asset mirroring and link rewriting
+    pub rewrite_ms: f64,
+}
+
+/// Throughput over the most recent pages of a crawl
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlThroughput {
+    /// Pages completed per second
+    pub pages_per_sec: f64,
+    /// HTML bytes downloaded per second
+    pub bytes_per_sec: f64,
+    /// Average time from dequeue to completion of a page
+    pub avg_page_latency_ms: f64,
+    /// Average time per page in each phase
+    pub phases: PhaseAverages,
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    at: Instant,
+    bytes: usize,
+    latency: Duration,
+    phases: PhaseTimings,
+}
+
+/// Rolling window of completed pages
+#[derive(Debug, Clone)]
+pub struct ThroughputTracker {
+    started: Instant,
+    window: Duration,
+    samples: VecDeque<Sample>,
+}
+
+impl Default for ThroughputTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThroughputTracker {
+    /// Tracker over [`THROUGHPUT_WINDOW`], starting now
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_window(THROUGHPUT_WINDOW)
+    }
+
+    /// Tracker over a custom window, starting now
+    #[must_use]
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Account for a crawl event; only `PageCrawled` carries timings
+    pub fn record(&mut self, event: &CrawlEvent) {
+        if let CrawlEvent::PageCrawled { metadata, .. } = event {
+            self.record_page(metadata.html_size, metadata.processing_duration, metadata.phase_timings);
+        }
+    }
+
+    /// Account for one completed page
+    pub fn record_page(&mut self, bytes: usize, latency: Duration, phases: PhaseTimings) {
+        let now = Instant::now();
+        self.samples.push_back(Sample {
+            at: now,
+            bytes,
+            latency,
+            phases,
+        });
+        self.prune(now);
+    }
+
+    /// Rates and averages over the window ending now
+    #[must_use]
+    pub fn snapshot(&self) -> CrawlThroughput {
+        self.snapshot_at(Instant::now())
+    }
+
+    fn snapshot_at(&self, now: Instant) -> CrawlThroughput {
+        let cutoff = now.checked_sub(self.window);
+        let recent: Vec<&Sample> = self
+            .samples
+            .iter()
+            .filter(|s| cutoff.is_none_or(|cutoff| s.at >= cutoff))
+            .collect();
+        if recent.is_empty() {
+            return CrawlThroughput::default();
+        }
+
+        // Rates cover the whole window, or the time since the crawl started
+        let span = now.duration_since(self.started).min(self.window).as_secs_f64().max(1.0);
+        let count = recent.len() as f64;
+        let avg_ms = |f: fn(&Sample) -> Duration| {
+            recent.iter().map(|s| f(s).as_secs_f64()).sum::<f64>() * 1000.0 / count
+        };
+
+        CrawlThroughput {
+            pages_per_sec: count / span,
+            bytes_per_sec: recent.iter().map(|s| s.bytes as f64).sum::<f64>() / span,
+            avg_page_latency_ms: avg_ms(|s| s.latency),
+            phases: PhaseAverages {
+                fetch_ms: avg_ms(|s| s.phases.fetch),
+                extract_ms: avg_ms(|s| s.phases.extract),
+                convert_ms: avg_ms(|s| s.phases.convert),
+                save_ms: avg_ms(|s| s.phases.save),
+                rewrite_ms: avg_ms(|s| s.phases.rewrite),
+            },
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(front) = self.samples.front() {
+            if now.duration_since(front.at) <= self.window {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_averages_recent_pages() {
+        let mut tracker = ThroughputTracker::new();
+        assert_eq!(tracker.snapshot(), CrawlThroughput::default());
+
+        let phases = |fetch_ms, save_ms| PhaseTimings {
+            fetch: Duration::from_millis(fetch_ms),
+            save: Duration::from_millis(save_ms),
+            ..PhaseTimings::default()
+        };
+        tracker.record_page(1000, Duration::from_millis(300), phases(200, 20));
+        tracker.record_page(3000, Duration::from_millis(500), phases(400, 40));
+
+        // Less than a second into the crawl, rates are per one second
+        let snapshot = tracker.snapshot();
+        assert!((snapshot.pages_per_sec - 2.0).abs() < 1e-9);
+        assert!((snapshot.bytes_per_sec - 4000.0).abs() < 1e-9);
+        assert!((snapshot.avg_page_latency_ms - 400.0).abs() < 1e-6);
+        assert!((snapshot.phases.fetch_ms - 300.0).abs() < 1e-6);
+        assert!((snapshot.phases.save_ms - 30.0).abs() < 1e-6);
+        assert!(snapshot.phases.convert_ms.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_old_pages_leave_the_window() {
+        let mut tracker = ThroughputTracker::with_window(Duration::from_secs(10));
+        tracker.record_page(500, Duration::from_millis(100), PhaseTimings::default());
+        let later = Instant::now() + Duration::from_secs(11);
+        assert_eq!(tracker.snapshot_at(later), CrawlThroughput::default());
+    }
+}
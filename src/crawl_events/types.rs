@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::crawl_engine::CrawlError;
+
 /// Reason for event bus shutdown
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ShutdownReason {
@@ -27,6 +29,46 @@ pub enum CrawlEvent {
         max_depth: u32,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// Emitted when a URL is added to the crawl queue
+    PageQueued {
+        url: String,
+        depth: u32,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Emitted when a page has loaded and its content passed validation
+    PageFetched {
+        url: String,
+        /// HTTP status of the main document, when the browser reported one
+        status_code: Option<u16>,
+        /// Size of the rendered HTML in bytes
+        html_size: usize,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Emitted when one output file of a page has been written
+    PageSaved {
+        url: String,
+        local_path: PathBuf,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Emitted when links in a saved page have been rewritten to local paths
+    LinkRewritten {
+        url: String,
+        local_path: PathBuf,
+        /// Outbound links in this page now pointing at local copies
+        outbound_rewritten: usize,
+        /// Previously saved pages updated to point at this page
+        inbound_updated: usize,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Emitted when a page fails and will not be retried
+    Error {
+        url: String,
+        message: String,
+        /// Kind of failure, absent in journals written before it was recorded
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<CrawlError>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
     /// Emitted when a page has been successfully crawled and saved
     PageCrawled {
         url: String,
@@ -59,6 +101,18 @@ pub enum CrawlEvent {
         url: String,
         attempts: u8,
         final_error: String,
+        /// Kind of the final failure
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<CrawlError>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+    /// Emitted after the end-of-crawl retry pass re-attempted pages that had
+    /// failed transiently
+    RetryPassCompleted {
+        /// Pages that succeeded on the final attempt
+        recovered: Vec<String>,
+        /// Pages that failed again
+        failed: Vec<String>,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
     /// Signals that the event bus is shutting down
@@ -85,6 +139,35 @@ pub struct PageCrawlMetadata {
     pub screenshot_captured: bool,
     /// Time taken to process the page
     pub processing_duration: std::time::Duration,
+    /// Crawl queue length after this page's links were queued
+    #[serde(default)]
+    pub queue_size: usize,
+    /// Time spent in each processing phase
+    #[serde(default)]
+    pub phase_timings: PhaseTimings,
+    /// xxh3 hash of the page's markdown (16 hex digits), to tell whether a
+    /// recrawl changed the content
+    #[serde(default)]
+    pub content_hash: Option<String>,
+}
+
+/// Time a page spent in each processing phase
+///
+/// Phases that were retried include every attempt; phases that did not run
+/// (for example saving JSON when it is disabled) are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhaseTimings {
+    /// Rate limiting, page setup, navigation and load
+    pub fetch: std::time::Duration,
+    /// DOM extraction, including inlining resources and writing raw HTML
+    pub extract: std::time::Duration,
+    /// HTML to markdown conversion
+    pub convert: std::time::Duration,
+    /// Writing markdown, JSON and screenshots
+    pub save: std::time::Duration,
+    /// Alias registration, asset mirroring and link rewriting
+    pub rewrite: std::time::Duration,
 }
 
 /// Result of publishing a batch of events
@@ -156,6 +239,65 @@ impl CrawlEvent {
         }
     }
 
+    /// Create a `PageQueued` event
+    #[must_use]
+    pub fn page_queued(url: String, depth: u32) -> Self {
+        Self::PageQueued {
+            url,
+            depth,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create a `PageFetched` event
+    #[must_use]
+    pub fn page_fetched(url: String, status_code: Option<u16>, html_size: usize) -> Self {
+        Self::PageFetched {
+            url,
+            status_code,
+            html_size,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create a `PageSaved` event
+    #[must_use]
+    pub fn page_saved(url: String, local_path: PathBuf) -> Self {
+        Self::PageSaved {
+            url,
+            local_path,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create a `LinkRewritten` event
+    #[must_use]
+    pub fn link_rewritten(
+        url: String,
+        local_path: PathBuf,
+        outbound_rewritten: usize,
+        inbound_updated: usize,
+    ) -> Self {
+        Self::LinkRewritten {
+            url,
+            local_path,
+            outbound_rewritten,
+            inbound_updated,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Create an `Error` event, inferring the failure kind from `message`
+    #[must_use]
+    pub fn error(url: String, message: String) -> Self {
+        Self::Error {
+            url,
+            error: Some(CrawlError::from_message(message.as_str())),
+            message,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
     /// Create a `PageCrawled` event
     #[must_use]
     pub fn page_crawled(
@@ -212,17 +354,46 @@ impl CrawlEvent {
         }
     }
 
-    /// Create a RetryExhausted event
+    /// Create a RetryExhausted event, inferring the failure kind from `final_error`
     #[must_use]
     pub fn retry_exhausted(url: String, attempts: u8, final_error: String) -> Self {
         Self::RetryExhausted {
             url,
             attempts,
+            error: Some(CrawlError::from_message(final_error.as_str())),
             final_error,
             timestamp: chrono::Utc::now(),
         }
     }
 
+    /// Set the failure kind of an `Error` or `RetryExhausted` event
+    #[must_use]
+    pub fn with_error(mut self, kind: CrawlError) -> Self {
+        if let Self::Error { error, .. } | Self::RetryExhausted { error, .. } = &mut self {
+            *error = Some(kind);
+        }
+        self
+    }
+
+    /// Failure kind of an `Error` or `RetryExhausted` event
+    #[must_use]
+    pub fn crawl_error(&self) -> Option<&CrawlError> {
+        match self {
+            Self::Error { error, .. } | Self::RetryExhausted { error, .. } => error.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Create a `RetryPassCompleted` event
+    #[must_use]
+    pub fn retry_pass_completed(recovered: Vec<String>, failed: Vec<String>) -> Self {
+        Self::RetryPassCompleted {
+            recovered,
+            failed,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
     /// Create a Shutdown event
     #[must_use]
     pub fn shutdown(reason: ShutdownReason) -> Self {
@@ -0,0 +1,364 @@
+//! Packaging a crawl output directory into a single archive
+//!
+//! Produces `.tar.zst` (streamed through zstd) or `.zip` archives of a
+//! mirror so it can be pulled off the server in one transfer. Archive paths
+//! are relative to the crawl output directory.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result, bail};
+use jwalk::WalkDir;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+/// zstd level used for `.tar.zst` archives
+const ZSTD_LEVEL: i32 = 9;
+
+/// Archive container format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    /// Tar archive compressed with zstd
+    #[default]
+    TarZst,
+    /// Zip archive (deflate)
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// File extension without a leading dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::TarZst => "tar.zst",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// Which files of the output directory go into the archive
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Only include markdown files (`.md`, `.md.gz`)
+    pub markdown_only: bool,
+    /// Include the Tantivy search index (`.search_index/`)
+    pub include_search_index: bool,
+    /// Abandon the export, removing the partial archive, once cancelled
+    pub cancel: Option<CancellationToken>,
+}
+
+impl ExportOptions {
+    fn includes(&self, relative: &Path) -> bool {
+        if !self.include_search_index && relative.starts_with(".search_index") {
+            return false;
+        }
+        let name = relative.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if name.ends_with(".tmp") {
+            return false;
+        }
+        !self.markdown_only || name.ends_with(".md") || name.ends_with(".md.gz")
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            bail!("Export cancelled");
+        }
+        Ok(())
+    }
+}
+
+/// Result of an export
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSummary {
+    /// Path of the written archive
+    pub archive_path: PathBuf,
+    /// Archive size in bytes
+    pub archive_bytes: u64,
+    /// Number of files archived
+    pub files: usize,
+    /// Total uncompressed size of the archived files
+    pub source_bytes: u64,
+}
+
+/// Default archive location: next to the output directory, named after it
+pub fn default_archive_path(output_dir: &Path, format: ArchiveFormat) -> PathBuf {
+    let name = output_dir
+        .file_name()
+        .map_or_else(|| "crawl".to_string(), |n| n.to_string_lossy().to_string());
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let file_name = format!("{name}-{stamp}.{}", format.extension());
+    output_dir.parent().unwrap_or(output_dir).join(file_name)
+}
+
+/// Archive the files of `output_dir` selected by `options` into `archive_path`
+///
+/// Blocking; call from `spawn_blocking` in async code. The archive is written
+/// to a temporary file and renamed into place, and is skipped if it lies
+/// inside `output_dir`.
+pub fn export_crawl(
+    output_dir: &Path,
+    archive_path: &Path,
+    format: ArchiveFormat,
+    options: &ExportOptions,
+) -> Result<ExportSummary> {
+    if !output_dir.is_dir() {
+        bail!("Crawl output directory not found: {}", output_dir.display());
+    }
+
+    let files = collect_files(output_dir, archive_path, options)?;
+    if files.is_empty() {
+        bail!("No files to export in {}", output_dir.display());
+    }
+
+    if let Some(parent) = archive_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let temp_path = archive_path.with_extension("partial");
+    let out = BufWriter::new(
+        File::create(&temp_path).with_context(|| format!("Failed to create {}", temp_path.display()))?,
+    );
+
+    let written = match format {
+        ArchiveFormat::TarZst => write_tar_zst(out, output_dir, &files, options),
+        ArchiveFormat::Zip => write_zip(out, output_dir, &files, options),
+    };
+    let source_bytes = match written {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+
+    std::fs::rename(&temp_path, archive_path)
+        .with_context(|| format!("Failed to move archive to {}", archive_path.display()))?;
+    let archive_bytes = std::fs::metadata(archive_path)?.len();
+
+    Ok(ExportSummary {
+        archive_path: archive_path.to_path_buf(),
+        archive_bytes,
+        files: files.len(),
+        source_bytes,
+    })
+}
+
+/// Relative paths (with `/` separators) of the files to archive, sorted
+fn collect_files(output_dir: &Path, archive_path: &Path, options: &ExportOptions) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(output_dir).sort(true).skip_hidden(false).follow_links(false) {
+        let entry = entry.context("Failed to walk crawl output directory")?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        if path == archive_path {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(output_dir) else {
+            continue;
+        };
+        if !options.includes(relative) {
+            continue;
+        }
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        files.push(components.join("/"));
+    }
+    Ok(files)
+}
+
+/// Open a file to archive, returning it with its size and mtime (Unix seconds)
+fn open_source(output_dir: &Path, relative: &str) -> Result<(File, u64, u64)> {
+    let path = output_dir.join(relative);
+    let file = File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let metadata = file.metadata()?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    Ok((file, metadata.len(), mtime))
+}
+
+/// Reads exactly `size` bytes, failing if the source ends early
+///
+/// A tar header records the size up front, so a file that shrinks while it
+/// is archived must fail the export rather than corrupt the archive.
+struct ExactReader<R> {
+    inner: io::Take<R>,
+    path: String,
+}
+
+impl<R: Read> Read for ExactReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() && self.inner.limit() > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} shrank while archiving", self.path),
+            ));
+        }
+        Ok(read)
+    }
+}
+
+fn write_tar_zst(out: impl Write, output_dir: &Path, files: &[String], options: &ExportOptions) -> Result<u64> {
+    let encoder = zstd::stream::write::Encoder::new(out, ZSTD_LEVEL)?;
+    let mut tar = tar::Builder::new(encoder);
+    let mut total = 0;
+    for relative in files {
+        options.check_cancelled()?;
+        let (file, size, mtime) = open_source(output_dir, relative)?;
+        // GNU headers store paths past 100 bytes in a long-name entry
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(size);
+        header.set_mtime(mtime);
+        header.set_mode(0o644);
+        let data = ExactReader {
+            inner: file.take(size),
+            path: relative.clone(),
+        };
+        tar.append_data(&mut header, relative, data)
+            .with_context(|| format!("Failed to archive {relative}"))?;
+        total += size;
+    }
+    tar.into_inner()?.finish()?.flush()?;
+    Ok(total)
+}
+
+fn write_zip(
+    out: impl Write + std::io::Seek,
+    output_dir: &Path,
+    files: &[String],
+    export_options: &ExportOptions,
+) -> Result<u64> {
+    use zip::CompressionMethod;
+    use zip::write::SimpleFileOptions;
+
+    let mut zip = zip::ZipWriter::new(out);
+    let mut total = 0;
+    for relative in files {
+        export_options.check_cancelled()?;
+        let (mut file, size, _) = open_source(output_dir, relative)?;
+        // Already-compressed files are stored as-is
+        let method = if is_compressed(relative) {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        };
+        let options = SimpleFileOptions::default()
+            .compression_method(method)
+            .large_file(size >= u64::from(u32::MAX));
+        zip.start_file(relative.as_str(), options)?;
+        io::copy(&mut file, &mut zip).with_context(|| format!("Failed to archive {relative}"))?;
+        total += size;
+    }
+    zip.finish()?.flush()?;
+    Ok(total)
+}
+
+fn is_compressed(path: &str) -> bool {
+    const EXTENSIONS: &[&str] = &[".gz", ".zst", ".png", ".jpg", ".jpeg", ".webp", ".gif", ".woff2"];
+    let lower = path.to_ascii_lowercase();
+    EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample_crawl() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let site = dir.path().join("example.com");
+        std::fs::create_dir_all(site.join("guide")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".search_index")).unwrap();
+        std::fs::write(site.join("index.md"), "# Home").unwrap();
+        std::fs::write(site.join("guide/index.md"), "# Guide").unwrap();
+        std::fs::write(site.join("guide/index.html"), "<h1>Guide</h1>").unwrap();
+        std::fs::write(dir.path().join(".search_index/meta.json"), "{}").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_zip_markdown_only() -> Result<()> {
+        let crawl = sample_crawl();
+        let out = TempDir::new()?;
+        let archive = out.path().join("mirror.zip");
+        let options = ExportOptions {
+            markdown_only: true,
+            ..Default::default()
+        };
+
+        let summary = export_crawl(crawl.path(), &archive, ArchiveFormat::Zip, &options)?;
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.archive_bytes, std::fs::metadata(&archive)?.len());
+
+        let mut zip = zip::ZipArchive::new(File::open(&archive)?)?;
+        let names: Vec<String> = zip.file_names().map(str::to_string).collect();
+        assert!(names.contains(&"example.com/guide/index.md".to_string()));
+        assert!(!names.iter().any(|n| n.ends_with(".html") || n.starts_with(".search_index")));
+
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut zip.by_name("example.com/index.md")?, &mut content)?;
+        assert_eq!(content, "# Home");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tar_zst_full_export() -> Result<()> {
+        let crawl = sample_crawl();
+        // Deep mirror paths must survive intact
+        let deep = format!("example.com/{}", "x".repeat(150));
+        std::fs::create_dir_all(crawl.path().join(&deep))?;
+        std::fs::write(crawl.path().join(&deep).join("index.md"), "# Deep")?;
+        let archive = default_archive_path(crawl.path(), ArchiveFormat::TarZst);
+        let options = ExportOptions {
+            include_search_index: true,
+            ..Default::default()
+        };
+
+        let summary = export_crawl(crawl.path(), &archive, ArchiveFormat::TarZst, &options)?;
+        assert_eq!(summary.files, 5);
+
+        let mut tar = tar::Archive::new(zstd::stream::read::Decoder::new(File::open(&archive)?)?);
+        let mut entries = Vec::new();
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            entries.push((entry.path()?.to_string_lossy().into_owned(), content));
+        }
+        // Entries follow the sorted file order
+        assert_eq!(entries[0].0, ".search_index/meta.json");
+        assert_eq!(entries[1], ("example.com/guide/index.html".to_string(), "<h1>Guide</h1>".to_string()));
+        assert!(entries.contains(&(format!("{deep}/index.md"), "# Deep".to_string())));
+        std::fs::remove_file(archive)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancelled_export_leaves_no_archive() {
+        let crawl = sample_crawl();
+        let out = TempDir::new().unwrap();
+        let archive = out.path().join("mirror.zip");
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let options = ExportOptions {
+            cancel: Some(cancel),
+            ..Default::default()
+        };
+
+        let err = export_crawl(crawl.path(), &archive, ArchiveFormat::Zip, &options).unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+        assert!(!archive.exists());
+        assert!(!archive.with_extension("partial").exists());
+    }
+}
@@ -0,0 +1,324 @@
+//! RSS / Atom feed parsing and discovery
+//!
+//! Normalizes RSS 2.0, RSS 1.0 (RDF) and Atom documents into one entry shape
+//! with absolute links and RFC 3339 dates, and finds feed links advertised by
+//! HTML pages via `<link rel="alternate">`.
+
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::utils::http_fetch::fetch_text;
+use crate::utils::xml_tree::XmlElement;
+
+/// Feed MIME types recognized during autodiscovery
+const FEED_TYPES: &[&str] = &["application/rss+xml", "application/atom+xml", "application/rdf+xml"];
+
+/// Longest summary kept per entry, in characters
+const MAX_SUMMARY_CHARS: usize = 1000;
+
+/// Feed dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedKind {
+    Rss,
+    Rdf,
+    Atom,
+}
+
+/// One feed entry
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FeedEntry {
+    /// Entry title
+    pub title: Option<String>,
+    /// Absolute link to the entry
+    pub link: Option<String>,
+    /// Stable identifier (guid / id), falling back to the link
+    pub id: Option<String>,
+    /// Publication time (RFC 3339 when parseable)
+    pub published: Option<String>,
+    /// Last update time (RFC 3339 when parseable)
+    pub updated: Option<String>,
+    /// Summary as plain text, truncated
+    pub summary: Option<String>,
+    /// Author names
+    pub authors: Vec<String>,
+    /// Categories / tags
+    pub categories: Vec<String>,
+}
+
+/// A parsed feed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Feed {
+    /// Feed dialect
+    pub kind: FeedKind,
+    /// Feed title
+    pub title: Option<String>,
+    /// Site the feed belongs to
+    pub link: Option<String>,
+    /// Feed description / subtitle
+    pub description: Option<String>,
+    /// Last update time of the feed
+    pub updated: Option<String>,
+    /// Entries in document order
+    pub entries: Vec<FeedEntry>,
+}
+
+/// Parse an RSS, RDF or Atom document; relative links resolve against `base`
+pub fn parse_feed(xml: &str, base: &Url) -> Result<Feed> {
+    let root = XmlElement::parse(xml)?;
+    match root.name.as_str() {
+        "rss" => {
+            let Some(channel) = root.child("channel") else {
+                bail!("RSS document has no <channel>");
+            };
+            Ok(parse_rss(FeedKind::Rss, channel, channel.children_named("item"), base))
+        }
+        "RDF" => {
+            let Some(channel) = root.child("channel") else {
+                bail!("RDF document has no <channel>");
+            };
+            Ok(parse_rss(FeedKind::Rdf, channel, root.children_named("item"), base))
+        }
+        "feed" => Ok(parse_atom(&root, base)),
+        other => bail!("Not a feed: root element is <{other}>"),
+    }
+}
+
+fn parse_rss<'a>(
+    kind: FeedKind,
+    channel: &XmlElement,
+    items: impl Iterator<Item = &'a XmlElement>,
+    base: &Url,
+) -> Feed {
+    let entries = items
+        .map(|item| {
+            let link = item.child_text("link").and_then(|l| resolve(base, &l));
+            FeedEntry {
+                title: item.child_text("title"),
+                id: item.child_text("guid").or_else(|| link.clone()),
+                link,
+                published: item
+                    .child_text("pubDate")
+                    .or_else(|| item.child_text("date"))
+                    .map(|d| normalize_date(&d)),
+                updated: None,
+                summary: item
+                    .child_text("description")
+                    .or_else(|| item.child_text("encoded"))
+                    .map(|s| plain_text(&s)),
+                authors: item
+                    .children
+                    .iter()
+                    .filter(|c| c.name == "author" || c.name == "creator")
+                    .map(|c| c.text.trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect(),
+                categories: item
+                    .children_named("category")
+                    .map(|c| c.text.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Feed {
+        kind,
+        title: channel.child_text("title"),
+        link: channel.child_text("link").and_then(|l| resolve(base, &l)),
+        description: channel.child_text("description"),
+        updated: channel
+            .child_text("lastBuildDate")
+            .or_else(|| channel.child_text("pubDate"))
+            .or_else(|| channel.child_text("date"))
+            .map(|d| normalize_date(&d)),
+        entries,
+    }
+}
+
+fn parse_atom(feed: &XmlElement, base: &Url) -> Feed {
+    let entries = feed
+        .children_named("entry")
+        .map(|entry| {
+            let link = atom_link(entry, base);
+            FeedEntry {
+                title: entry.child_text("title"),
+                id: entry.child_text("id").or_else(|| link.clone()),
+                link,
+                published: entry.child_text("published").map(|d| normalize_date(&d)),
+                updated: entry.child_text("updated").map(|d| normalize_date(&d)),
+                summary: entry
+                    .child_text("summary")
+                    .or_else(|| entry.child_text("content"))
+                    .map(|s| plain_text(&s)),
+                authors: entry
+                    .children_named("author")
+                    .filter_map(|a| a.child_text("name"))
+                    .collect(),
+                categories: entry
+                    .children_named("category")
+                    .filter_map(|c| c.attr("term").map(str::to_string))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Feed {
+        kind: FeedKind::Atom,
+        title: feed.child_text("title"),
+        link: atom_link(feed, base),
+        description: feed.child_text("subtitle"),
+        updated: feed.child_text("updated").map(|d| normalize_date(&d)),
+        entries,
+    }
+}
+
+/// `href` of the `rel="alternate"` (or rel-less) `<link>`
+fn atom_link(element: &XmlElement, base: &Url) -> Option<String> {
+    element
+        .children_named("link")
+        .find(|l| l.attr("rel").is_none_or(|rel| rel == "alternate"))
+        .and_then(|l| l.attr("href"))
+        .and_then(|href| resolve(base, href))
+}
+
+fn resolve(base: &Url, link: &str) -> Option<String> {
+    base.join(link.trim()).ok().map(String::from)
+}
+
+/// RFC 3339 form of an RFC 3339 or RFC 2822 date; other input is kept as-is
+fn normalize_date(date: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(date)
+        .or_else(|_| chrono::DateTime::parse_from_rfc2822(date))
+        .map_or_else(|_| date.to_string(), |d| d.to_rfc3339())
+}
+
+/// Strip tags, collapse whitespace and truncate
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    crate::utils::safe_truncate_chars(&collapsed, MAX_SUMMARY_CHARS).to_string()
+}
+
+/// `<link rel="alternate">` selector, compiled once rather than for every page
+static ALTERNATE_LINK_SELECTOR: LazyLock<scraper::Selector> = LazyLock::new(|| {
+    scraper::Selector::parse("link[rel~='alternate'][href]").expect("hardcoded selector is valid")
+});
+
+/// Feed URLs advertised by an HTML page, resolved against `base`
+pub fn discover_feed_links(html: &str, base: &Url) -> Vec<String> {
+    let document = scraper::Html::parse_document(html);
+    document
+        .select(&ALTERNATE_LINK_SELECTOR)
+        .filter(|link| {
+            link.value()
+                .attr("type")
+                .is_some_and(|t| FEED_TYPES.contains(&t.trim().to_ascii_lowercase().as_str()))
+        })
+        .filter_map(|link| link.value().attr("href").and_then(|href| resolve(base, href)))
+        .collect()
+}
+
+/// Fetch and parse the feed at `url`
+///
+/// If `url` serves HTML rather than a feed, the first feed advertised by the
+/// page is fetched instead. Returns the URL the feed was read from.
+pub async fn fetch_feed(client: &reqwest::Client, url: &Url) -> Result<(Url, Feed)> {
+    let body = fetch_text(client, url).await?;
+    let not_a_feed = match parse_feed(&body, url) {
+        Ok(feed) => return Ok((url.clone(), feed)),
+        Err(e) => e,
+    };
+
+    let Some(feed_url) = discover_feed_links(&body, url).into_iter().next() else {
+        return Err(not_a_feed.context(format!("{url} is not a feed and advertises no feed link")));
+    };
+    let feed_url = Url::parse(&feed_url)?;
+    let body = fetch_text(client, &feed_url).await?;
+    let feed = parse_feed(&body, &feed_url).with_context(|| format!("Failed to parse feed {feed_url}"))?;
+    Ok((feed_url, feed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://blog.example.com/feed.xml").unwrap()
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let xml = r#"<rss version="2.0"><channel>
+            <title>Example</title><link>https://blog.example.com/</link>
+            <item>
+              <title>Release 1.2</title><link>/posts/1-2</link>
+              <pubDate>Tue, 03 Jun 2025 09:00:00 GMT</pubDate>
+              <description>&lt;p&gt;New &lt;b&gt;stuff&lt;/b&gt;&lt;/p&gt;</description>
+              <category>release</category>
+            </item>
+        </channel></rss>"#;
+        let feed = parse_feed(xml, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Example"));
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.link.as_deref(), Some("https://blog.example.com/posts/1-2"));
+        assert_eq!(entry.id, entry.link);
+        assert_eq!(entry.published.as_deref(), Some("2025-06-03T09:00:00+00:00"));
+        assert_eq!(entry.summary.as_deref(), Some("New stuff"));
+        assert_eq!(entry.categories, ["release"]);
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+            <title>Changelog</title>
+            <link rel="self" href="/atom.xml"/><link href="/"/>
+            <entry>
+              <title>v2</title><id>tag:example,2025:v2</id>
+              <link rel="alternate" href="https://blog.example.com/v2"/>
+              <updated>2025-06-01T12:00:00Z</updated>
+              <author><name>Sam</name></author>
+              <category term="changelog"/>
+            </entry>
+        </feed>"#;
+        let feed = parse_feed(xml, &base()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Atom);
+        assert_eq!(feed.link.as_deref(), Some("https://blog.example.com/"));
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id.as_deref(), Some("tag:example,2025:v2"));
+        assert_eq!(entry.link.as_deref(), Some("https://blog.example.com/v2"));
+        assert_eq!(entry.updated.as_deref(), Some("2025-06-01T12:00:00+00:00"));
+        assert_eq!(entry.authors, ["Sam"]);
+        assert_eq!(entry.categories, ["changelog"]);
+    }
+
+    #[test]
+    fn test_discover_feed_links() {
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="/rss.xml">
+            <link rel="alternate" hreflang="de" href="/de/">
+            <link rel="stylesheet" href="/style.css">
+        </head></html>"#;
+        assert_eq!(discover_feed_links(html, &base()), ["https://blog.example.com/rss.xml"]);
+        assert!(parse_feed(html, &base()).is_err());
+    }
+}
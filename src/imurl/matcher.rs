@@ -0,0 +1,268 @@
+//! Pattern-based URL rules
+//!
+//! A [`UrlMatcher`] combines optional conditions on the host, the path, the
+//! whole URL and individual query parameters; a URL matches when every
+//! condition set on the matcher holds. Subsystems that filter or classify URLs
+//! build matchers instead of hand-rolling string and regex checks.
+//!
+//! Globs use `*` for any run of characters (including `/` and `.`); every other
+//! character is literal, so `?` in a URL glob matches a query separator.
+//!
+//! # Migrating `excluded_patterns`
+//!
+//! Excluded patterns used to be regexes with `*` rewritten to `.*`, anchored
+//! at both ends, so `.` matched any character and `?`, `+`, `(…|…)` and
+//! character classes kept their regex meaning. Plain patterns are now globs.
+//! A pattern that relied on regex syntax keeps its old meaning when written
+//! as `re:^…$` with each `*` spelled `.*`; [`UrlMatcher::legacy_equivalent`]
+//! produces that rewrite and config loading logs it for patterns that look
+//! like regexes.
+
+use anyhow::{Result, anyhow, bail};
+use regex::Regex;
+use url::Url;
+
+/// Condition on one query parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamPredicate {
+    /// Parameter appears, with any value
+    Present(String),
+    /// Parameter does not appear
+    Absent(String),
+    /// Parameter appears with exactly this value
+    Equals(String, String),
+}
+
+impl ParamPredicate {
+    fn holds(&self, url: &Url) -> bool {
+        let mut pairs = url.query_pairs();
+        match self {
+            Self::Present(name) => pairs.any(|(k, _)| k == name.as_str()),
+            Self::Absent(name) => !pairs.any(|(k, _)| k == name.as_str()),
+            Self::Equals(name, value) => pairs.any(|(k, v)| k == name.as_str() && v == value.as_str()),
+        }
+    }
+}
+
+/// A set of conditions a URL must all satisfy
+///
+/// ```
+/// # use kodegen_tools_citescrape::imurl::UrlMatcher;
+/// # fn main() -> anyhow::Result<()> {
+/// let matcher = UrlMatcher::new()
+///     .host_glob("*.example.com")?
+///     .path_glob("/api/*")?
+///     .param_absent("preview");
+/// assert!(matcher.matches_str("https://docs.example.com/api/v1"));
+/// assert!(!matcher.matches_str("https://docs.example.com/api/v1?preview=1"));
+/// assert!(!matcher.matches_str("https://example.org/api/v1"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UrlMatcher {
+    /// Source text for display and error messages
+    source: String,
+    host: Option<Regex>,
+    path: Option<Regex>,
+    url: Option<Regex>,
+    params: Vec<ParamPredicate>,
+}
+
+impl UrlMatcher {
+    /// Matcher with no conditions; matches every URL
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matcher for a filter pattern as written in crawl configs
+    ///
+    /// `re:<regex>` is a regular expression searched in the full URL; anything
+    /// else is a glob matched against the full URL.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let matcher = match pattern.strip_prefix("re:") {
+            Some(regex) => Self::new().url_regex(regex)?,
+            None => Self::new().url_glob(pattern)?,
+        };
+        Ok(matcher.with_source(pattern))
+    }
+
+    /// The `re:` pattern matching what a glob-like `pattern` matched before
+    /// plain patterns became globs
+    ///
+    /// Returns `None` for `re:` patterns and for globs without regex-only
+    /// syntax (`\`, `[`, `(`, `|`, `^`, `$`, `{`, `+`), whose old and new
+    /// meanings only differ in how `.` and `?` are treated.
+    #[must_use]
+    pub fn legacy_equivalent(pattern: &str) -> Option<String> {
+        if pattern.starts_with("re:") || !pattern.contains(['\\', '[', '(', '|', '^', '$', '{', '+']) {
+            return None;
+        }
+        Some(format!("re:^{}$", pattern.replace('*', ".*")))
+    }
+
+    /// Require the host to match `glob` (case-insensitive), e.g. `*.example.com`
+    pub fn host_glob(mut self, glob: &str) -> Result<Self> {
+        self.host = Some(compile_glob(glob, true)?);
+        Ok(self.with_source(&format!("host:{glob}")))
+    }
+
+    /// Require the path to match `glob`, e.g. `/docs/*`
+    pub fn path_glob(mut self, glob: &str) -> Result<Self> {
+        if !glob.starts_with(['/', '*']) {
+            bail!("Invalid path glob '{glob}': must start with '/' or '*'");
+        }
+        self.path = Some(compile_glob(glob, false)?);
+        Ok(self.with_source(&format!("path:{glob}")))
+    }
+
+    /// Require the full URL to match `glob`
+    pub fn url_glob(mut self, glob: &str) -> Result<Self> {
+        self.url = Some(compile_glob(glob, false)?);
+        Ok(self.with_source(glob))
+    }
+
+    /// Require `regex` to match somewhere in the full URL
+    pub fn url_regex(mut self, regex: &str) -> Result<Self> {
+        self.url = Some(Regex::new(regex).map_err(|e| anyhow!("Invalid URL regex '{regex}': {e}"))?);
+        Ok(self.with_source(&format!("re:{regex}")))
+    }
+
+    /// Require query parameter `name` to be present
+    #[must_use]
+    pub fn param_present(self, name: impl Into<String>) -> Self {
+        self.with_param(ParamPredicate::Present(name.into()))
+    }
+
+    /// Require query parameter `name` to be absent
+    #[must_use]
+    pub fn param_absent(self, name: impl Into<String>) -> Self {
+        self.with_param(ParamPredicate::Absent(name.into()))
+    }
+
+    /// Require query parameter `name` to equal `value`
+    #[must_use]
+    pub fn param_equals(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.with_param(ParamPredicate::Equals(name.into(), value.into()))
+    }
+
+    /// Add a query parameter condition
+    #[must_use]
+    pub fn with_param(mut self, predicate: ParamPredicate) -> Self {
+        self.params.push(predicate);
+        self
+    }
+
+    /// Whether `url` satisfies every condition
+    #[must_use]
+    pub fn matches(&self, url: &Url) -> bool {
+        if let Some(host) = &self.host
+            && !url.host_str().is_some_and(|h| host.is_match(h))
+        {
+            return false;
+        }
+        if let Some(path) = &self.path
+            && !path.is_match(url.path())
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.url
+            && !pattern.is_match(url.as_str())
+        {
+            return false;
+        }
+        self.params.iter().all(|p| p.holds(url))
+    }
+
+    /// Parse `url` and match it; unparseable URLs never match
+    #[must_use]
+    pub fn matches_str(&self, url: &str) -> bool {
+        Url::parse(url).is_ok_and(|url| self.matches(&url))
+    }
+
+    /// The pattern this matcher was built from
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    fn with_source(mut self, part: &str) -> Self {
+        if self.source.is_empty() {
+            self.source = part.to_string();
+        } else if self.source != part {
+            self.source = format!("{} {part}", self.source);
+        }
+        self
+    }
+}
+
+/// Compile a `*` glob into an anchored regex
+fn compile_glob(glob: &str, case_insensitive: bool) -> Result<Regex> {
+    let mut pattern = String::from(if case_insensitive { "(?i)^" } else { "^" });
+    for (i, literal) in glob.split('*').enumerate() {
+        if i > 0 {
+            pattern.push_str(".*");
+        }
+        pattern.push_str(&regex::escape(literal));
+    }
+    pattern.push('$');
+    Regex::new(&pattern).map_err(|e| anyhow!("Invalid glob pattern '{glob}': {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_glob_is_literal_except_star() {
+        let matcher = UrlMatcher::parse("*/src/*.rs?*").unwrap();
+        assert!(matcher.matches_str("https://docs.rs/src/lib.rs?plain=1"));
+        assert!(!matcher.matches_str("https://docs.rs/src/lib.rs"));
+        assert!(!matcher.matches_str("https://docs.rs/src/libxrs?plain=1"));
+        assert_eq!(matcher.as_str(), "*/src/*.rs?*");
+    }
+
+    #[test]
+    fn test_legacy_equivalent_keeps_regex_meaning() {
+        assert_eq!(UrlMatcher::legacy_equivalent("*/src/*"), None);
+        assert_eq!(UrlMatcher::legacy_equivalent("re:/v[0-9]/"), None);
+
+        let legacy = UrlMatcher::legacy_equivalent("*/v[0-9]/*").unwrap();
+        assert_eq!(legacy, "re:^.*/v[0-9]/.*$");
+        let matcher = UrlMatcher::parse(&legacy).unwrap();
+        assert!(matcher.matches_str("https://example.com/v2/guide"));
+        assert!(!UrlMatcher::parse("*/v[0-9]/*").unwrap().matches_str("https://example.com/v2/guide"));
+    }
+
+    #[test]
+    fn test_regex_pattern() {
+        let matcher = UrlMatcher::parse(r"re:/page/\d+$").unwrap();
+        assert!(matcher.matches_str("https://example.com/blog/page/12"));
+        assert!(!matcher.matches_str("https://example.com/blog/page/next"));
+        assert!(UrlMatcher::parse("re:(").is_err());
+    }
+
+    #[test]
+    fn test_host_and_path_globs() {
+        let matcher = UrlMatcher::new().host_glob("*.Example.com").unwrap().path_glob("/docs/*").unwrap();
+        assert!(matcher.matches_str("https://www.example.com/docs/intro"));
+        assert!(!matcher.matches_str("https://example.com/docs/intro"));
+        assert!(!matcher.matches_str("https://www.example.com/blog"));
+        assert!(UrlMatcher::new().path_glob("docs").is_err());
+    }
+
+    #[test]
+    fn test_param_predicates() {
+        let matcher = UrlMatcher::new()
+            .param_present("id")
+            .param_equals("view", "full")
+            .param_absent("session");
+        assert!(matcher.matches_str("https://example.com/?id=1&view=full"));
+        assert!(!matcher.matches_str("https://example.com/?id=1&view=short"));
+        assert!(!matcher.matches_str("https://example.com/?id=1&view=full&session=x"));
+        assert!(!matcher.matches_str("https://example.com/?view=full"));
+        assert!(UrlMatcher::new().matches_str("https://example.com/"));
+        assert!(!UrlMatcher::new().matches_str("not a url"));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod matcher;
+
+pub use matcher::{ParamPredicate, UrlMatcher};
+
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -5,9 +9,10 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::str::FromStr;
-use url::Url;
+use url::{Host, Url};
 
 use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
 
 /// An immutable, cheaply-cloneable URL wrapper.
 /// 
@@ -21,8 +26,15 @@ pub struct ImUrl {
 }
 
 impl ImUrl {
+    /// Parses `input`, bringing the path into canonical form (see [`canonical_path`]).
+    ///
+    /// Hosts are IDNA-mapped to lowercase punycode by the parser, so every
+    /// spelling of an internationalized URL yields the same `ImUrl`.
     pub fn parse(input: &str) -> Result<Self> {
-        let parsed_url = Url::parse(input).context("Failed to parse URL")?;
+        let mut parsed_url = Url::parse(input).context("Failed to parse URL")?;
+        if let Cow::Owned(path) = canonical_path(parsed_url.path()) {
+            parsed_url.set_path(&path);
+        }
         let url_str = Cow::Owned(parsed_url.as_str().to_string());
         let url = Arc::new(parsed_url);
         Ok(Self { url_str, url })
@@ -56,6 +68,29 @@ impl ImUrl {
         self.url.fragment()
     }
 
+    /// Returns the registrable domain (eTLD+1) of the host.
+    ///
+    /// Uses the public suffix list, so `www.example.co.uk` yields
+    /// `example.co.uk`. IP addresses and hosts that are themselves a public
+    /// suffix (such as `localhost`) are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kodegen_tools_citescrape::imurl::ImUrl;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let url = ImUrl::parse("https://docs.example.co.uk/guide")?;
+    /// assert_eq!(url.registrable_domain(), Some("example.co.uk"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn registrable_domain(&self) -> Option<&str> {
+        match self.url.host()? {
+            Host::Domain(domain) => Some(registrable_domain(domain)),
+            Host::Ipv4(_) | Host::Ipv6(_) => self.url.host_str(),
+        }
+    }
+
     /// Returns a normalized URL with the fragment removed.
     ///
     /// This is essential for URL deduplication in web crawling, where
@@ -78,6 +113,30 @@ impl ImUrl {
         Self::parse(url.as_str())
     }
 
+    /// Resolves `input` against this URL, as [`Url::join`] does.
+    ///
+    /// Relative links found on a page are resolved against the page URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kodegen_tools_citescrape::imurl::ImUrl;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let page = ImUrl::parse("https://example.com/docs/intro.html")?;
+    /// let link = page.join("../api/index.html")?;
+    /// assert_eq!(link.as_str(), "https://example.com/api/index.html");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join(&self, input: &str) -> Result<Self> {
+        let joined = self
+            .url
+            .join(input)
+            .with_context(|| format!("Failed to resolve '{input}' against {}", self.url_str))?;
+        let url_str = Cow::Owned(joined.as_str().to_string());
+        Ok(Self { url_str, url: Arc::new(joined) })
+    }
+
     pub fn with_path(&self, path: &str) -> Result<Self> {
         let mut url = (*self.url).clone();
         url.set_path(path);
@@ -144,6 +203,80 @@ impl ImUrl {
     }
 }
 
+/// Returns the registrable domain (eTLD+1) of a domain name.
+///
+/// Falls back to `domain` itself when it has no registrable part, i.e. when
+/// it is a public suffix or a single label. Expects a lowercase host as
+/// produced by URL parsing.
+pub fn registrable_domain(domain: &str) -> &str {
+    psl::domain_str(domain).unwrap_or(domain)
+}
+
+/// Returns `path` with its percent-encoding in canonical form.
+///
+/// Encoded unreserved characters (`%41`, `%7E`) are decoded, remaining
+/// escapes use uppercase hex, and non-ASCII text is NFC-normalized and
+/// percent-encoded as UTF-8. A URL written with raw Unicode, lowercase
+/// escapes or decomposed accents therefore maps to a single path.
+pub fn canonical_path(path: &str) -> Cow<'_, str> {
+    fn is_unreserved(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+    }
+
+    fn hex_value(digit: u8) -> Option<u8> {
+        (digit as char).to_digit(16).and_then(|d| u8::try_from(d).ok())
+    }
+
+    fn push_encoded(out: &mut Vec<u8>, byte: u8) {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+        out.extend_from_slice(&[b'%', HEX[usize::from(byte >> 4)], HEX[usize::from(byte & 0x0F)]]);
+    }
+
+    // Decode escapes of unreserved and non-ASCII bytes; keep the rest as uppercase escapes
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| Some((hex_value(*bytes.get(i + 1)?)? << 4) | hex_value(*bytes.get(i + 2)?)?))
+            .flatten();
+        match escaped {
+            Some(value) if is_unreserved(value) || !value.is_ascii() => decoded.push(value),
+            Some(value) => push_encoded(&mut decoded, value),
+            None => decoded.push(bytes[i]),
+        }
+        i += if escaped.is_some() { 3 } else { 1 };
+    }
+
+    // Compose Unicode text (combining marks may follow an ASCII letter), then re-encode it
+    let mut out = Vec::with_capacity(decoded.len());
+    match String::from_utf8(decoded) {
+        Ok(text) => {
+            for ch in text.nfc() {
+                if ch.is_ascii() {
+                    out.push(ch as u8);
+                } else {
+                    let mut buf = [0u8; 4];
+                    ch.encode_utf8(&mut buf).bytes().for_each(|byte| push_encoded(&mut out, byte));
+                }
+            }
+        }
+        Err(invalid) => {
+            for byte in invalid.into_bytes() {
+                if byte.is_ascii() {
+                    out.push(byte);
+                } else {
+                    push_encoded(&mut out, byte);
+                }
+            }
+        }
+    }
+
+    // Only ASCII was written
+    let out = String::from_utf8(out).unwrap_or_default();
+    if out == path { Cow::Borrowed(path) } else { Cow::Owned(out) }
+}
+
 impl fmt::Display for ImUrl {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.url_str)
@@ -269,6 +402,56 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    #[test]
+    fn test_join() {
+        let base = ImUrl::parse("https://example.com/docs/guide/page.html?x=1").unwrap();
+        assert_eq!(base.join("other.html").unwrap().as_str(), "https://example.com/docs/guide/other.html");
+        assert_eq!(base.join("/root").unwrap().as_str(), "https://example.com/root");
+        assert_eq!(base.join("#top").unwrap().as_str(), "https://example.com/docs/guide/page.html?x=1#top");
+        assert_eq!(base.join("//cdn.example.org/a.js").unwrap().as_str(), "https://cdn.example.org/a.js");
+        assert_eq!(base.join("http://other.test/").unwrap().host(), Some("other.test"));
+        assert!(base.join("http://[::1").is_err());
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        let url = ImUrl::parse("https://www.example.co.uk/page").unwrap();
+        assert_eq!(url.registrable_domain(), Some("example.co.uk"));
+        let url = ImUrl::parse("https://a.b.github.io/").unwrap();
+        assert_eq!(url.registrable_domain(), Some("b.github.io"));
+        let url = ImUrl::parse("http://127.0.0.1:8080/").unwrap();
+        assert_eq!(url.registrable_domain(), Some("127.0.0.1"));
+        let url = ImUrl::parse("http://[::1]/").unwrap();
+        assert_eq!(url.registrable_domain(), Some("[::1]"));
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("example.com"), "example.com");
+    }
+
+    #[test]
+    fn test_canonical_path() {
+        assert!(matches!(canonical_path("/docs/a%2Fb"), Cow::Borrowed(_)));
+        assert_eq!(canonical_path("/%7euser/%41bc"), "/~user/Abc");
+        assert_eq!(canonical_path("/a%2fb%3f"), "/a%2Fb%3F");
+        assert_eq!(canonical_path("/stra%c3%9fe"), "/stra%C3%9Fe");
+        // Decomposed "é" (e + U+0301) and raw Unicode normalize to the composed escape
+        assert_eq!(canonical_path("/caf%65%CC%81"), "/caf%C3%A9");
+        assert_eq!(canonical_path("/café"), "/caf%C3%A9");
+        // Invalid UTF-8 and stray percent signs are kept
+        assert_eq!(canonical_path("/%ff%zz"), "/%FF%zz");
+    }
+
+    #[test]
+    fn test_idn_urls_share_one_form() {
+        let spellings = [
+            "https://MÜNCHEN.de/Stra%c3%9Fe",
+            "https://xn--mnchen-3ya.de/Straße",
+            "https://münchen.de/Stra%C3%9Fe",
+        ];
+        let urls: Vec<ImUrl> = spellings.iter().map(|s| ImUrl::parse(s).unwrap()).collect();
+        assert_eq!(urls[0].as_str(), "https://xn--mnchen-3ya.de/Stra%C3%9Fe");
+        assert!(urls.iter().all(|u| u == &urls[0]));
+    }
+
     #[test]
     fn test_from_str() {
         let url: ImUrl = "https://example.com".parse().unwrap();
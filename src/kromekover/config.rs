@@ -11,6 +11,39 @@ pub struct Config {
     pub hardware_concurrency: u32,
 }
 
+impl Config {
+    /// Default config with language settings for `language_tag` (e.g. `de-DE`)
+    ///
+    /// Sets `Accept-Language` to prefer the tag, then its primary language,
+    /// then English, and mirrors that in `navigator.language(s)`.
+    #[must_use]
+    pub fn for_language(language_tag: &str) -> Self {
+        let primary = language_tag.split('-').next().unwrap_or(language_tag).to_string();
+        let mut languages = vec![language_tag.to_string()];
+        if primary != language_tag {
+            languages.push(primary);
+        }
+        if !languages.iter().any(|l| l == "en") {
+            languages.push("en".to_string());
+        }
+        let accept_language = languages
+            .iter()
+            .enumerate()
+            .map(|(i, lang)| match i {
+                0 => lang.clone(),
+                _ => format!("{lang};q={:.1}", 1.0 - 0.1 * i as f64),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        Self {
+            accept_language,
+            language: language_tag.to_string(),
+            languages,
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
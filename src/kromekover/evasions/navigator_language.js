@@ -1,9 +1,9 @@
 // Mock navigator language properties
 Object.defineProperties(navigator, {
   'language': {
-    get: () => 'en-US'
+    get: () => (window.grokConfig && window.grokConfig.language) || 'en-US'
   },
   'languages': {
-    get: () => ['en-US', 'en']
+    get: () => (window.grokConfig && window.grokConfig.languages) || ['en-US', 'en']
   }
 });
@@ -6,7 +6,7 @@ use tokio::fs;
 use tracing::{debug, warn};
 
 mod config;
-use config::Config;
+pub use config::Config;
 
 // Order matters! Scripts are injected in this sequence for maximum stealth
 const EVASION_SCRIPTS: &[&str] = &[
@@ -38,14 +38,18 @@ const EVASION_SCRIPTS: &[&str] = &[
 ];
 
 pub async fn inject(page: &Page) -> Result<()> {
+    inject_with_config(page, &Config::default()).await
+}
+
+/// Inject stealth scripts using `config` for the spoofed browser identity
+/// (e.g. a non-English `Accept-Language` from [`Config::for_language`])
+pub async fn inject_with_config(page: &Page, config: &Config) -> Result<()> {
     // Generate per-session seed for canvas fingerprinting
     let session_seed: Vec<u8> = (0..16).map(|_| rand::random::<u8>()).collect();
     let session_seed_hex = hex::encode(&session_seed);
 
     debug!("Injecting stealth scripts");
 
-    let config = Config::default();
-
     let kromekover_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("src")
         .join("kromekover");
@@ -5,14 +5,24 @@ pub mod config;
 pub mod content_saver;
 pub mod crawl_engine;
 pub mod crawl_events;
+pub mod export;
+pub mod feed;
 pub mod inline_css;
 pub mod kromekover;
 pub mod link_index;
 pub mod link_rewriter;
+pub mod logging;
+pub mod markdown_diff;
 pub mod mcp;
+pub mod output_layout;
 pub mod page_extractor;
+pub mod preview;
+pub mod robots;
 pub mod runtime;
 pub mod search;
+pub mod sitemap_probe;
+pub mod telemetry;
+pub mod url_policy;
 pub mod utils;
 pub mod web_search;
 pub mod imurl;
@@ -21,11 +31,12 @@ pub mod browser_profile;
 
 pub use browser_setup::{
     apply_stealth_measures, download_managed_browser, find_browser_executable, launch_browser,
+    launch_browser_with_proxy,
 };
 pub use config::CrawlConfig;
 pub use content_saver::{CacheMetadata, save_json_data};
 pub use crawl_engine::{
-    ChromiumoxideCrawler, CrawlError, CrawlProgress, CrawlQueue, CrawlResult, Crawler,
+    ChromiumoxideCrawler, CrawlControl, CrawlError, CrawlProgress, CrawlQueue, CrawlResult, Crawler,
 };
 pub use page_extractor::schema::*;
 pub use runtime::{AsyncJsonSave, AsyncStream, BrowserAction, CrawlRequest};
@@ -53,18 +64,44 @@ pub use mcp::{
     // Types
     ActiveCrawlSession,
     ConfigSummary,
+    ConversionSummary,
     CrawlManifest,
+    CrawlQuota,
+    CrawlSessionProgress,
     CrawlStatus,
+    PageOutcome,
+    PageStatus,
     // Managers
     CrawlSessionManager,
     ManifestManager,
     SearchEngineCache,
+    SessionStore,
     // Registry (NEW)
     CrawlRegistry,
     CrawlSession,
     // Tools
+    BatchFetchTool,
+    BrokenLinksTool,
+    CrawlCancelTool,
+    CrawlPauseTool,
+    CrawlResourcesTool,
+    CrawlStatusTool,
+    DiffPagesTool,
+    ExecuteJsTool,
+    ExportCrawlTool,
+    ExtractStructuredTool,
     FetchTool,
+    FetchFeedTool,
+    GetManifestTool,
+    InteractTool,
+    LinkIndexAdminTool,
+    ListCrawlsTool,
+    LocatePageTool,
+    RenderPdfTool,
+    RobotsCheckTool,
     ScrapeUrlTool,
+    SearchDocsTool,
+    SitemapProbeTool,
     WebSearchTool,
     // Utilities
     url_to_output_dir,
@@ -101,6 +138,28 @@ pub async fn crawl(config: CrawlConfig) -> Result<(), CrawlError> {
     crawler.crawl().await
 }
 
+/// Crawl, passing progress updates to `on_progress` as they happen
+///
+/// See [`ChromiumoxideCrawler::crawl_with_progress`] for the updates sent.
+pub async fn crawl_with_progress(
+    config: CrawlConfig,
+    on_progress: impl Fn(CrawlProgress) + Send + Sync + 'static,
+) -> Result<(), CrawlError> {
+    ChromiumoxideCrawler::new(config).crawl_with_progress(on_progress).await
+}
+
+/// Crawl until done or until `token` is cancelled
+///
+/// Cancelling aborts the crawl: in-flight pages are dropped and pending link
+/// rewrites and end-of-crawl reports are skipped.
+pub async fn crawl_with_cancellation(
+    config: CrawlConfig,
+    token: &tokio_util::sync::CancellationToken,
+) -> Result<(), CrawlError> {
+    let crawler = ChromiumoxideCrawler::new(config).with_cancellation(token);
+    crawler.crawl().await
+}
+
 // Shutdown hook wrapper for BrowserPool
 struct BrowserPoolWrapper(std::sync::Arc<crate::BrowserPool>);
 
@@ -181,10 +240,18 @@ pub async fn start_server_with_listener(
             let engine_cache = Arc::new(crate::SearchEngineCache::new());
 
             // Create crawl registry with browser pool
-            let crawl_registry = Arc::new(crate::CrawlRegistry::new(
-                engine_cache.clone(),
-                browser_pool.clone(),
-            ));
+            // Per-connection limits come from CITESCRAPE_* environment variables
+            let mut crawl_registry = crate::CrawlRegistry::new(engine_cache.clone(), browser_pool.clone())
+                .with_quota(crate::CrawlQuota::from_env());
+            // Crawls left running by a previous process are resumed or reported
+            if let Some(path) = crate::SessionStore::default_path() {
+                let store = Arc::new(crate::SessionStore::open(path).await);
+                crawl_registry = crawl_registry.with_session_store(store);
+            }
+            let crawl_registry = Arc::new(crawl_registry);
+            crawl_registry
+                .restore_sessions(crate::SessionStore::resume_from_env())
+                .await;
 
             // Register browser pool for shutdown
             managers.register(BrowserPoolWrapper(browser_pool.clone())).await;
@@ -197,11 +264,11 @@ pub async fn start_server_with_listener(
                 crate::ScrapeUrlTool::new(crawl_registry.clone()),
             );
 
-            // web_search tool now uses shared browser pool
+            // web_search tool uses the registry for its browser pool and deep-mode crawls
             (tool_router, prompt_router) = register_tool(
                 tool_router,
                 prompt_router,
-                crate::WebSearchTool::new(browser_pool.clone()),
+                crate::WebSearchTool::new(crawl_registry.clone()),
             );
 
             // Register fetch tool (simplified single-page fetcher)
@@ -211,6 +278,142 @@ pub async fn start_server_with_listener(
                 crate::FetchTool::new(crawl_registry.clone()),
             );
 
+            // Register batch_fetch tool (concurrent multi-page fetch via browser pool)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::BatchFetchTool::new(browser_pool.clone()),
+            );
+
+            // Register render_pdf tool (print a page to PDF via CDP)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::RenderPdfTool::new(browser_pool.clone()),
+            );
+
+            // Register fetch_feed tool (RSS/Atom parsing with optional crawl of entries)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::FetchFeedTool::new(crawl_registry.clone()),
+            );
+
+            // Register broken_links tool (post-crawl link report)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::BrokenLinksTool::new(),
+            );
+
+            // Register locate_page tool (output file back to its URL and crawl)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::LocatePageTool::new(),
+            );
+
+            // Register robots_check tool (robots.txt rules and crawl delay for a URL)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::RobotsCheckTool::new(),
+            );
+
+            // Register sitemap_probe tool (sitemap URL counts for crawl scoping)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::SitemapProbeTool::new(),
+            );
+
+            // Register crawl_status tool (live progress of registry crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CrawlStatusTool::new(crawl_registry.clone()),
+            );
+
+            // Register crawl_cancel / crawl_pause tools (stop or pause registry crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CrawlCancelTool::new(crawl_registry.clone()),
+            );
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CrawlPauseTool::new(crawl_registry.clone()),
+            );
+
+            // Register search_docs tool (search previously crawled content)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::SearchDocsTool::new(crawl_registry.clone()),
+            );
+
+            // Register list_crawls / get_manifest tools (discover mirrored crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ListCrawlsTool::new(crawl_registry.clone()),
+            );
+
+            // Register crawl_resources tool (crawl output as citescrape:// resources)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::CrawlResourcesTool::new().with_engine_cache(engine_cache.clone()),
+            );
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::GetManifestTool::new(),
+            );
+
+            // Register export_crawl tool (archive a crawl for download)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ExportCrawlTool::new(),
+            );
+
+            // Register extract_structured tool (selector-to-JSON scraping via browser pool)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ExtractStructuredTool::new(browser_pool.clone()),
+            );
+
+            // Register execute_js tool (evaluate a script on a pooled browser page)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::ExecuteJsTool::new(browser_pool.clone()),
+            );
+
+            // Register interact tool (click/type/submit steps, then extraction)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::InteractTool::new(browser_pool.clone()),
+            );
+
+            // Register diff_pages tool (markdown diff against a URL or crawl snapshot)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::DiffPagesTool::new(browser_pool.clone()).with_engine_cache(engine_cache.clone()),
+            );
+
+            // Register link_index_admin tool (stats, prune, vacuum)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                crate::LinkIndexAdminTool::new(),
+            );
+
             // CRITICAL: Start cleanup tasks after all tools are registered
             engine_cache.start_cleanup_task();
 
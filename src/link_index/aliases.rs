@@ -0,0 +1,189 @@
+//! URL aliases: redirect sources and canonical alternates.
+//!
+//! A page reachable under several URLs (`/docs` redirecting to `/docs/`,
+//! `?view=print` variants declaring a canonical URL) should have a single
+//! local copy. Pages are saved under the URL they were requested as; the
+//! `aliases` table maps every alternate URL (the URL a redirect ended on, the
+//! declared canonical URL) to that saved URL so `LinkRewriter` can point
+//! links at any alias to the copy.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+
+use super::{LinkIndex, normalize_url};
+
+/// Why a URL is an alias of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AliasKind {
+    /// Navigating to the alias ended on the target URL
+    Redirect,
+    /// The page at the alias declares the target as `<link rel="canonical">`
+    Canonical,
+}
+
+impl AliasKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Redirect => "redirect",
+            Self::Canonical => "canonical",
+        }
+    }
+}
+
+impl LinkIndex {
+    /// Record `alias_url` as an alternate URL of `target_url`.
+    ///
+    /// Chains are flattened: if the target is itself an alias without a saved
+    /// page of its own, the final URL is stored, and existing aliases of
+    /// `alias_url` are repointed to it.
+    /// Returns `false` when nothing was recorded (the URLs are equal after
+    /// normalization, or the alias would form a cycle).
+    pub async fn register_alias(&self, alias_url: &str, target_url: &str, kind: AliasKind) -> Result<bool> {
+        let alias = normalize_url(alias_url);
+        let target = normalize_url(target_url);
+        let target = if self.filter_existing(std::slice::from_ref(&target)).await?.is_empty() {
+            self.resolve_alias(&target).await?.unwrap_or(target)
+        } else {
+            target
+        };
+        if alias == target {
+            return Ok(false);
+        }
+
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO aliases (alias_url, target_url, kind, recorded_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(alias_url) DO UPDATE SET
+                target_url = excluded.target_url,
+                kind = excluded.kind,
+                recorded_at = excluded.recorded_at
+            "#
+        )
+        .bind(&alias)
+        .bind(&target)
+        .bind(kind.as_str())
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to upsert alias")?;
+
+        sqlx::query("UPDATE aliases SET target_url = ? WHERE target_url = ?")
+            .bind(&target)
+            .bind(&alias)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to repoint chained aliases")?;
+
+        tx.commit().await.context("Failed to commit alias")?;
+        Ok(true)
+    }
+
+    /// Get the final URL an alias points to, or `None` if `url` is not an alias.
+    pub async fn resolve_alias(&self, url: &str) -> Result<Option<String>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT target_url FROM aliases WHERE alias_url = ?")
+            .bind(normalize_url(url))
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to resolve alias")?;
+
+        Ok(row.map(|(target,)| target))
+    }
+
+    /// Get all aliases that point at `target_url`.
+    pub async fn get_aliases(&self, target_url: &str) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT alias_url FROM aliases WHERE target_url = ? ORDER BY alias_url")
+                .bind(normalize_url(target_url))
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to query aliases")?;
+
+        Ok(rows.into_iter().map(|(alias,)| alias).collect())
+    }
+
+    /// Map each URL with a local copy to the URL whose copy it should link to.
+    ///
+    /// Saved URLs map to themselves, even when they are also an alias of
+    /// another page; other aliases whose target has been saved map to that
+    /// target. URLs without any local copy are omitted.
+    /// Keys and values are normalized.
+    pub async fn resolve_existing(&self, urls: &[String]) -> Result<HashMap<String, String>> {
+        let mut resolved: HashMap<String, String> = self
+            .filter_existing(urls)
+            .await?
+            .into_iter()
+            .map(|url| (url.clone(), url))
+            .collect();
+
+        let normalized: HashSet<String> = urls.iter().map(|u| normalize_url(u)).collect();
+        let normalized: Vec<String> = normalized.into_iter().collect();
+
+        for chunk in normalized.chunks(500) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let query_str = format!(
+                "SELECT a.alias_url, a.target_url FROM aliases a \
+                 JOIN pages p ON p.url = a.target_url \
+                 WHERE a.alias_url IN ({placeholders})"
+            );
+
+            let mut query = sqlx::query_as::<_, (String, String)>(&query_str);
+            for url in chunk {
+                query = query.bind(url);
+            }
+
+            let rows = query.fetch_all(&self.pool).await.context("Failed to resolve aliases")?;
+            for (alias, target) in rows {
+                resolved.entry(alias).or_insert(target);
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_aliases_resolve_to_saved_target() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let old = "https://example.com/old";
+        let moved = "https://example.com/moved";
+        let target = "https://example.com/docs/";
+        let variant = "https://example.com/docs/?view=print";
+
+        assert!(index.register_alias(old, moved, AliasKind::Redirect).await?);
+        // moved → target repoints old → target as well
+        assert!(index.register_alias(moved, target, AliasKind::Redirect).await?);
+        assert!(index.register_alias(variant, target, AliasKind::Canonical).await?);
+        assert!(!index.register_alias(target, "https://example.com/docs", AliasKind::Canonical).await?);
+
+        assert_eq!(index.resolve_alias(old).await?, Some(normalize_url(target)));
+        assert_eq!(index.get_aliases(target).await?.len(), 3);
+
+        // Nothing saved yet: aliases do not resolve to a local copy
+        let urls = vec![old.to_string(), variant.to_string()];
+        assert!(index.resolve_existing(&urls).await?.is_empty());
+
+        index.register_page(target, &temp_dir.path().join("docs.html"), &[]).await?;
+        let resolved = index.resolve_existing(&urls).await?;
+        assert_eq!(resolved.get(&normalize_url(old)), Some(&normalize_url(target)));
+        assert_eq!(resolved.get(&normalize_url(variant)), Some(&normalize_url(target)));
+
+        // A URL with its own saved page links to that page, not its alias target
+        index.register_page(variant, &temp_dir.path().join("print.html"), &[]).await?;
+        let resolved = index.resolve_existing(&urls).await?;
+        assert_eq!(resolved.get(&normalize_url(variant)), Some(&normalize_url(variant)));
+
+        index.close().await;
+        Ok(())
+    }
+}
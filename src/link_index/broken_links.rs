@@ -0,0 +1,117 @@
+//! Broken link reporting over the link index.
+//!
+//! Combines the link graph with recorded fetch outcomes to find:
+//! - Targets that were fetched but answered with a 4xx/5xx status (or failed outright)
+//! - Internal link targets that were never crawled at all
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{LinkStore, extract_domain};
+use crate::content_saver::sink::note_output_changed;
+
+/// File name of the report written into the crawl output directory.
+pub const BROKEN_LINKS_FILENAME: &str = "broken_links.json";
+
+/// A single broken link target and the pages that reference it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BrokenLink {
+    /// Normalized target URL
+    pub url: String,
+    /// HTTP status returned when the target was fetched, if any
+    pub status_code: Option<u16>,
+    /// Terminal error recorded for the target, if any
+    pub error: Option<String>,
+    /// Normalized URLs of crawled pages linking to this target
+    pub referrers: Vec<String>,
+}
+
+/// Broken link report for a crawl output directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct BrokenLinksReport {
+    /// Unix timestamp (seconds) when the report was generated
+    pub generated_at: i64,
+    /// Targets that were attempted but returned 4xx/5xx or failed
+    pub http_errors: Vec<BrokenLink>,
+    /// Same-domain link targets that were never crawled
+    pub uncrawled_internal: Vec<BrokenLink>,
+}
+
+impl BrokenLinksReport {
+    /// Total number of broken targets in the report.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.http_errors.len() + self.uncrawled_internal.len()
+    }
+}
+
+/// Build a broken link report from the current contents of `store`.
+pub(super) async fn build_report<S: LinkStore + ?Sized>(store: &S) -> Result<BrokenLinksReport> {
+    let pages = store.all_pages().await.context("Failed to load pages")?;
+    let links = store.all_links().await.context("Failed to load links")?;
+    let fetches = store.all_fetch_results().await.context("Failed to load fetch results")?;
+
+    // Each link target with the sorted pages referencing it
+    let mut referrers: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (source, target) in &links {
+        referrers.entry(target).or_default().push(source.clone());
+    }
+    for sources in referrers.values_mut() {
+        sources.sort();
+    }
+    let referrers_of = |url: &str| referrers.get(url).cloned().unwrap_or_default();
+
+    // Attempted targets that ended in an HTTP error or a terminal failure
+    let http_errors = fetches
+        .iter()
+        .filter(|f| f.status_code.is_some_and(|s| s >= 400) || (f.status_code.is_none() && f.error.is_some()))
+        .map(|f| BrokenLink {
+            url: f.url.clone(),
+            status_code: f.status_code.and_then(|s| u16::try_from(s).ok()),
+            error: f.error.clone(),
+            referrers: referrers_of(&f.url),
+        })
+        .collect();
+
+    // Link targets never saved nor attempted, restricted to crawled domains
+    let saved: HashSet<&str> = pages.iter().map(|p| p.url.as_str()).collect();
+    let attempted: HashSet<&str> = fetches.iter().map(|f| f.url.as_str()).collect();
+    let crawled_domains: HashSet<&str> = pages.iter().map(|p| p.domain.as_str()).collect();
+    let uncrawled_internal = referrers
+        .keys()
+        .filter(|url| !saved.contains(*url) && !attempted.contains(*url))
+        .filter(|url| crawled_domains.contains(extract_domain(url).as_str()))
+        .map(|url| BrokenLink {
+            url: (*url).to_string(),
+            status_code: None,
+            error: None,
+            referrers: referrers_of(url),
+        })
+        .collect();
+
+    Ok(BrokenLinksReport {
+        generated_at: chrono::Utc::now().timestamp(),
+        http_errors,
+        uncrawled_internal,
+    })
+}
+
+/// Build the broken link report of `store` and write it as
+/// `broken_links.json` in its output directory.
+pub(super) async fn write_report<S: LinkStore + ?Sized>(store: &S) -> Result<(BrokenLinksReport, PathBuf)> {
+    let report = build_report(store).await?;
+    let path = store.output_dir().join(BROKEN_LINKS_FILENAME);
+
+    let json = serde_json::to_vec_pretty(&report)
+        .context("Failed to serialize broken links report")?;
+    tokio::fs::write(&path, json)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    note_output_changed(&path);
+
+    Ok((report, path))
+}
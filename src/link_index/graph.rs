@@ -0,0 +1,287 @@
+//! Graph analysis over the crawl link graph.
+//!
+//! Computes PageRank for crawled pages using only edges between pages that
+//! were actually saved, and stores the scores with the pages so search can
+//! boost well-linked pages (see `SearchQueryBuilder::page_ranks`).
+//!
+//! Also provides site-audit queries: orphan pages (nothing links to them) and
+//! dead ends (no links to other pages on the same site).
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{LinkIndex, LinkStore, PageRecord, extract_domain, normalize_url};
+
+/// Standard PageRank damping factor
+pub const DEFAULT_DAMPING: f64 = 0.85;
+
+/// Upper bound on power iterations
+pub const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+/// Convergence threshold on the L1 change between iterations
+const CONVERGENCE_EPSILON: f64 = 1e-9;
+
+/// Site-audit findings derived from the link graph.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SiteAudit {
+    /// Crawled pages that no other crawled page links to (start page excluded)
+    pub orphan_pages: Vec<String>,
+    /// Crawled pages without any outbound link to their own domain
+    pub dead_end_pages: Vec<String>,
+}
+
+/// Compute PageRank over a graph given as node count and edges between node indices.
+///
+/// Dangling nodes (no outgoing edges) spread their rank evenly across all
+/// nodes. Duplicate edges are counted once per occurrence. Scores sum to 1.
+pub fn page_rank(
+    node_count: usize,
+    edges: &[(usize, usize)],
+    damping: f64,
+    max_iterations: usize,
+) -> Vec<f64> {
+    if node_count == 0 {
+        return Vec::new();
+    }
+
+    let n = node_count as f64;
+    let mut out_degree = vec![0usize; node_count];
+    for &(source, _) in edges {
+        out_degree[source] += 1;
+    }
+
+    let mut ranks = vec![1.0 / n; node_count];
+    for _ in 0..max_iterations {
+        let dangling_mass: f64 = ranks
+            .iter()
+            .zip(&out_degree)
+            .filter(|&(_, &degree)| degree == 0)
+            .map(|(rank, _)| rank)
+            .sum();
+
+        let base = (1.0 - damping) / n + damping * dangling_mass / n;
+        let mut next = vec![base; node_count];
+        for &(source, target) in edges {
+            next[target] += damping * ranks[source] / out_degree[source] as f64;
+        }
+
+        let delta: f64 = next.iter().zip(&ranks).map(|(a, b)| (a - b).abs()).sum();
+        ranks = next;
+        if delta < CONVERGENCE_EPSILON {
+            break;
+        }
+    }
+
+    ranks
+}
+
+/// Compute PageRank for all pages of `store` and store the scores.
+///
+/// Only links whose target is itself a crawled page take part; links to
+/// external or uncrawled URLs are ignored. Returns the number of pages scored.
+pub(super) async fn compute_page_rank<S: LinkStore + ?Sized>(
+    store: &S,
+    damping: f64,
+    max_iterations: usize,
+) -> Result<usize> {
+    let pages = store.all_pages().await.context("Failed to load pages for PageRank")?;
+    let links = store.all_links().await.context("Failed to load links for PageRank")?;
+
+    let node_of: HashMap<&str, usize> = pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| (page.url.as_str(), i))
+        .collect();
+
+    let edges: Vec<(usize, usize)> = links
+        .iter()
+        .filter(|(source, target)| source != target)
+        .filter_map(|(source, target)| {
+            Some((*node_of.get(source.as_str())?, *node_of.get(target.as_str())?))
+        })
+        .collect();
+
+    let ranks = page_rank(pages.len(), &edges, damping, max_iterations);
+    let scores: Vec<(String, f64)> = pages.into_iter().map(|page| page.url).zip(ranks).collect();
+    store.set_page_ranks(&scores).await.context("Failed to store PageRank")?;
+    Ok(scores.len())
+}
+
+/// Crawled pages with no inbound links from other crawled pages.
+fn orphan_pages(pages: &[PageRecord], links: &[(String, String)]) -> Vec<String> {
+    let saved: HashSet<&str> = pages.iter().map(|page| page.url.as_str()).collect();
+    let linked: HashSet<&str> = links
+        .iter()
+        .filter(|(source, target)| source != target && saved.contains(source.as_str()))
+        .map(|(_, target)| target.as_str())
+        .collect();
+
+    pages
+        .iter()
+        .filter(|page| !linked.contains(page.url.as_str()))
+        .map(|page| page.url.clone())
+        .collect()
+}
+
+/// Crawled pages with no outbound links to other URLs on their own domain.
+fn dead_end_pages(pages: &[PageRecord], links: &[(String, String)]) -> Vec<String> {
+    let domain_of: HashMap<&str, &str> = pages
+        .iter()
+        .map(|page| (page.url.as_str(), page.domain.as_str()))
+        .collect();
+
+    let has_internal: HashSet<&str> = links
+        .iter()
+        .filter(|(source, target)| {
+            source != target
+                && domain_of
+                    .get(source.as_str())
+                    .is_some_and(|domain| extract_domain(target) == *domain)
+        })
+        .map(|(source, _)| source.as_str())
+        .collect();
+
+    pages
+        .iter()
+        .filter(|page| !has_internal.contains(page.url.as_str()))
+        .map(|page| page.url.clone())
+        .collect()
+}
+
+/// Run orphan and dead-end detection over `store`.
+///
+/// `start_url` is excluded from orphans since the crawl entry point is
+/// expected to have no inbound links.
+pub(super) async fn site_audit<S: LinkStore + ?Sized>(store: &S, start_url: Option<&str>) -> Result<SiteAudit> {
+    let pages = store.all_pages().await.context("Failed to load pages for the site audit")?;
+    let links = store.all_links().await.context("Failed to load links for the site audit")?;
+
+    let start = start_url.map(normalize_url);
+    let mut orphan_pages = orphan_pages(&pages, &links);
+    orphan_pages.retain(|url| Some(url) != start.as_ref());
+
+    Ok(SiteAudit {
+        orphan_pages,
+        dead_end_pages: dead_end_pages(&pages, &links),
+    })
+}
+
+impl LinkIndex {
+    /// Store PageRank scores as `(url, score)` in `pages.page_rank`.
+    pub async fn set_page_ranks(&self, ranks: &[(String, f64)]) -> Result<()> {
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+        for (url, rank) in ranks {
+            sqlx::query("UPDATE pages SET page_rank = ? WHERE url = ?")
+                .bind(rank)
+                .bind(url)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to store PageRank")?;
+        }
+        tx.commit().await.context("Failed to commit PageRank scores")?;
+        Ok(())
+    }
+
+    /// Get the stored PageRank of a page, if it has been computed.
+    pub async fn get_page_rank(&self, url: &str) -> Result<Option<f64>> {
+        let row: Option<(Option<f64>,)> = sqlx::query_as("SELECT page_rank FROM pages WHERE url = ?")
+            .bind(normalize_url(url))
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to query PageRank")?;
+
+        Ok(row.and_then(|(rank,)| rank))
+    }
+
+    /// Get the stored PageRank of each of `urls` that has one, keyed by the
+    /// URL as given.
+    pub async fn page_ranks(&self, urls: &[&str]) -> Result<HashMap<String, f64>> {
+        let mut ranks = HashMap::with_capacity(urls.len());
+        for url in urls {
+            if let Some(rank) = self.get_page_rank(url).await? {
+                ranks.insert((*url).to_string(), rank);
+            }
+        }
+        Ok(ranks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_page_rank_sums_to_one_and_favors_hubs() {
+        // 0 → 2, 1 → 2, 2 → 0, 3 is dangling
+        let ranks = page_rank(4, &[(0, 2), (1, 2), (2, 0)], DEFAULT_DAMPING, DEFAULT_MAX_ITERATIONS);
+        let total: f64 = ranks.iter().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(ranks[2] > ranks[0]);
+        assert!(ranks[0] > ranks[1]);
+        assert!(page_rank(0, &[], DEFAULT_DAMPING, DEFAULT_MAX_ITERATIONS).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compute_page_rank_stores_scores() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let home = "https://example.com/";
+        let guide = "https://example.com/guide";
+        let api = "https://example.com/api";
+        index
+            .register_page(home, &temp_dir.path().join("a.html"), &[guide.into(), api.into()])
+            .await?;
+        index
+            .register_page(guide, &temp_dir.path().join("b.html"), &[home.into(), api.into()])
+            .await?;
+        index
+            .register_page(api, &temp_dir.path().join("c.html"), &["https://other.com/".into()])
+            .await?;
+
+        assert_eq!(index.get_page_rank(api).await?, None);
+        assert_eq!(index.compute_page_rank(DEFAULT_DAMPING, DEFAULT_MAX_ITERATIONS).await?, 3);
+
+        let ranks = index.page_ranks(&[home, guide, api, "https://example.com/missing"]).await?;
+        assert_eq!(ranks.len(), 3);
+        assert!(ranks[api] > ranks[home] && ranks[api] > ranks[guide]);
+
+        index.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_site_audit() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let home = "https://example.com/";
+        let guide = "https://example.com/guide";
+        let orphan = "https://example.com/orphan";
+        index
+            .register_page(home, &temp_dir.path().join("a.html"), &[guide.into()])
+            .await?;
+        // Guide only links externally and to itself: dead end
+        index
+            .register_page(guide, &temp_dir.path().join("b.html"), &[guide.into(), "https://other.com/".into()])
+            .await?;
+        index
+            .register_page(orphan, &temp_dir.path().join("c.html"), &[home.into()])
+            .await?;
+
+        let audit = index.site_audit(Some(guide)).await?;
+        assert_eq!(audit.orphan_pages, vec![normalize_url(orphan)]);
+        assert_eq!(audit.dead_end_pages, vec![normalize_url(guide)]);
+
+        // The start URL is only excluded from orphans when given
+        let audit = index.site_audit(Some(orphan)).await?;
+        assert!(audit.orphan_pages.is_empty());
+
+        index.close().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! Maintenance operations for long-lived link indexes.
+//!
+//! Indexes are reused across crawls of the same output directory, so pages
+//! and links from deleted or outdated crawls accumulate. These methods prune
+//! them, reclaim disk space, and report what the index currently holds.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::LinkIndex;
+
+/// Counts and sizes describing a link index.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LinkIndexStats {
+    /// Number of saved pages
+    pub pages: i64,
+    /// Number of link edges
+    pub links: i64,
+    /// Number of recorded fetch outcomes
+    pub fetch_results: i64,
+    /// Page count per domain, largest first
+    pub domains: Vec<DomainStats>,
+    /// Oldest page save time (Unix seconds)
+    pub oldest_saved_at: Option<i64>,
+    /// Newest page save time (Unix seconds)
+    pub newest_saved_at: Option<i64>,
+    /// Size of the database file in bytes
+    pub db_size_bytes: u64,
+}
+
+/// Page count for one domain.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DomainStats {
+    pub domain: String,
+    pub pages: i64,
+}
+
+/// Rows removed by a prune operation.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PruneResult {
+    pub pages_removed: u64,
+    pub links_removed: u64,
+    pub fetch_results_removed: u64,
+}
+
+impl LinkIndex {
+    /// Collect page, link and per-domain counts plus the database size.
+    pub async fn stats(&self) -> Result<LinkIndexStats> {
+        let (pages, oldest_saved_at, newest_saved_at): (i64, Option<i64>, Option<i64>) =
+            sqlx::query_as("SELECT COUNT(*), MIN(saved_at), MAX(saved_at) FROM pages")
+                .fetch_one(&self.pool)
+                .await
+                .context("Failed to query page stats")?;
+
+        let (fetch_results,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM fetch_results")
+            .fetch_one(&self.pool)
+            .await
+            .context("Failed to count fetch results")?;
+
+        let domains: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT domain, COUNT(*) AS n FROM pages GROUP BY domain ORDER BY n DESC, domain"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query domain stats")?;
+
+        let db_size_bytes = tokio::fs::metadata(Self::db_path(&self.output_dir))
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        Ok(LinkIndexStats {
+            pages,
+            links: self.link_count().await?,
+            fetch_results,
+            domains: domains
+                .into_iter()
+                .map(|(domain, pages)| DomainStats { domain, pages })
+                .collect(),
+            oldest_saved_at,
+            newest_saved_at,
+            db_size_bytes,
+        })
+    }
+
+    /// Remove all pages of a domain together with their outbound links.
+    pub async fn prune_domain(&self, domain: &str) -> Result<PruneResult> {
+        let domain = domain.to_lowercase();
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        let links_removed = sqlx::query(
+            "DELETE FROM links WHERE source_url IN (SELECT url FROM pages WHERE domain = ?)"
+        )
+        .bind(&domain)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to prune domain links")?
+        .rows_affected();
+
+        sqlx::query(
+            "DELETE FROM link_positions WHERE source_url IN (SELECT url FROM pages WHERE domain = ?)"
+        )
+        .bind(&domain)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to prune domain link positions")?;
+
+        let pages_removed = sqlx::query("DELETE FROM pages WHERE domain = ?")
+            .bind(&domain)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to prune domain pages")?
+            .rows_affected();
+
+        let fetch_results_removed = sqlx::query("DELETE FROM fetch_results WHERE domain = ?")
+            .bind(&domain)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to prune domain fetch results")?
+            .rows_affected();
+
+        tx.commit().await.context("Failed to commit domain prune")?;
+        self.path_cache.write().await.clear();
+
+        Ok(PruneResult { pages_removed, links_removed, fetch_results_removed })
+    }
+
+    /// Remove pages saved before `cutoff` (Unix seconds) and stale fetch outcomes.
+    ///
+    /// Links whose source page no longer exists are removed as well, which
+    /// also cleans up edges left behind by earlier, interrupted crawls.
+    pub async fn prune_older_than(&self, cutoff: i64) -> Result<PruneResult> {
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        let pages_removed = sqlx::query("DELETE FROM pages WHERE saved_at < ?")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to prune old pages")?
+            .rows_affected();
+
+        let links_removed = sqlx::query(
+            "DELETE FROM links WHERE source_url NOT IN (SELECT url FROM pages)"
+        )
+        .execute(&mut *tx)
+        .await
+        .context("Failed to prune dangling links")?
+        .rows_affected();
+
+        sqlx::query("DELETE FROM link_positions WHERE source_url NOT IN (SELECT url FROM pages)")
+            .execute(&mut *tx)
+            .await
+            .context("Failed to prune dangling link positions")?;
+
+        let fetch_results_removed = sqlx::query("DELETE FROM fetch_results WHERE attempted_at < ?")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to prune old fetch results")?
+            .rows_affected();
+
+        tx.commit().await.context("Failed to commit age prune")?;
+        self.path_cache.write().await.clear();
+
+        Ok(PruneResult { pages_removed, links_removed, fetch_results_removed })
+    }
+
+    /// Point pages saved at `from` to `to` after the file was moved.
+    ///
+    /// Returns the number of pages updated.
+    pub async fn relocate_page_path(&self, from: &Path, to: &Path) -> Result<u64> {
+        let relocated = sqlx::query("UPDATE pages SET local_path = ? WHERE local_path = ?")
+            .bind(to.to_string_lossy().as_ref())
+            .bind(from.to_string_lossy().as_ref())
+            .execute(&self.pool)
+            .await
+            .context("Failed to relocate page path")?
+            .rows_affected();
+        if relocated > 0 {
+            self.path_cache.write().await.clear();
+        }
+        Ok(relocated)
+    }
+
+    /// Rebuild the database file to reclaim space freed by pruning.
+    pub async fn vacuum(&self) -> Result<()> {
+        sqlx::query("VACUUM")
+            .execute(&self.pool)
+            .await
+            .context("Failed to vacuum link index")?;
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&self.pool)
+            .await
+            .context("Failed to checkpoint link index WAL")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_prune_and_stats() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        index
+            .register_page("https://a.com/", &temp_dir.path().join("a.html"), &["https://b.com/".into()])
+            .await?;
+        index
+            .register_page("https://a.com/x", &temp_dir.path().join("ax.html"), &["https://a.com/".into()])
+            .await?;
+        index
+            .register_page("https://b.com/", &temp_dir.path().join("b.html"), &["https://a.com/".into()])
+            .await?;
+        index.record_fetch_result("https://b.com/", Some(200), None).await?;
+        index.record_fetch_result("https://sub.b.com/", Some(404), None).await?;
+        // Recorded before fetch_results had a domain column; backfilled on open
+        sqlx::query("INSERT INTO fetch_results (url, status_code, attempted_at) VALUES ('https://b.com:8443/old', 500, 0)")
+            .execute(&index.pool)
+            .await?;
+        index.close().await;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let stats = index.stats().await?;
+        assert_eq!(stats.pages, 3);
+        assert_eq!(stats.links, 3);
+        assert_eq!(stats.fetch_results, 3);
+        assert_eq!(stats.domains[0].domain, "a.com");
+        assert_eq!(stats.domains[0].pages, 2);
+
+        let pruned = index.prune_domain("B.com").await?;
+        assert_eq!(pruned.pages_removed, 1);
+        assert_eq!(pruned.links_removed, 1);
+        // Subdomains are separate domains
+        assert_eq!(pruned.fetch_results_removed, 2);
+        assert!(index.get_local_path("https://b.com/").await?.is_none());
+
+        // Everything left was saved "now", so a future cutoff removes it all
+        let cutoff = chrono::Utc::now().timestamp() + 60;
+        let pruned = index.prune_older_than(cutoff).await?;
+        assert_eq!(pruned.pages_removed, 2);
+        assert_eq!(pruned.links_removed, 2);
+
+        index.vacuum().await?;
+        assert_eq!(index.stats().await?.pages, 0);
+
+        index.close().await;
+        Ok(())
+    }
+}
@@ -7,10 +7,41 @@
 //! This enables efficient queries like:
 //! - "Does this URL have a local copy?" (O(log n) indexed lookup)
 //! - "Which pages link to this URL?" (for retroactive rewriting)
+//! - "Which URLs redirect to / are canonical alternates of this one?" (see `aliases`)
+//! - "Which links are broken?" (see `broken_links`)
+//! - "Which pages are most central?" (see `graph`)
+//! - "Which URL was saved at this path?" (see `reverse`)
+//!
+//! The index also drives `sitemap.xml` generation for the mirror (see `sitemap`)
+//! and offers pruning/vacuum for long-lived output directories (see `maintenance`).
+//!
+//! The crawl goes through the `LinkStore` trait (see `store`), which also
+//! has a shared Postgres backend behind the `postgres` feature; the reports
+//! and graph analyses are written against the trait and run on both.
+
+pub mod aliases;
+pub mod broken_links;
+pub mod graph;
+pub mod maintenance;
+pub mod positions;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod reverse;
+pub mod sitemap;
+pub mod store;
+
+pub use aliases::AliasKind;
+pub use broken_links::{BROKEN_LINKS_FILENAME, BrokenLink, BrokenLinksReport};
+pub use graph::SiteAudit;
+pub use maintenance::{LinkIndexStats, PruneResult};
+pub use positions::{HrefPatch, HrefPosition, LinkPositions};
+pub use reverse::SavedPage;
+pub use sitemap::SITEMAP_FILENAME;
+pub use store::{FetchRecord, LinkStore, PageRecord, external_backend, open_link_store, open_local_index};
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use anyhow::{Context, Result};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteJournalMode, SqliteSynchronous};
@@ -31,6 +62,9 @@ CREATE TABLE IF NOT EXISTS pages (
 -- Index for domain-scoped queries (find all pages from example.com)
 CREATE INDEX IF NOT EXISTS idx_pages_domain ON pages(domain);
 
+-- Index for reverse lookups (which URL was saved at this path?)
+CREATE INDEX IF NOT EXISTS idx_pages_local_path ON pages(local_path);
+
 -- Link graph edges: tracks which pages link to which
 CREATE TABLE IF NOT EXISTS links (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -44,8 +78,68 @@ CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_url);
 
 -- Index for inbound queries (who links to page X?) - critical for retroactive rewriting
 CREATE INDEX IF NOT EXISTS idx_links_target ON links(target_url);
+
+-- Fetch outcomes: HTTP status and terminal errors for every attempted URL
+CREATE TABLE IF NOT EXISTS fetch_results (
+    url TEXT PRIMARY KEY,
+    status_code INTEGER,
+    error TEXT,
+    attempted_at INTEGER NOT NULL
+);
+
+-- URL aliases: redirect sources and canonical alternates → final URL
+CREATE TABLE IF NOT EXISTS aliases (
+    alias_url TEXT PRIMARY KEY,
+    target_url TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_aliases_target ON aliases(target_url);
+
+-- Byte ranges of href values in saved HTML files, for in-place inbound rewrites
+CREATE TABLE IF NOT EXISTS link_positions (
+    source_url TEXT NOT NULL,
+    target_url TEXT NOT NULL,
+    start INTEGER NOT NULL,
+    len INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_link_positions_source ON link_positions(source_url, start);
 "#;
 
+/// Rows per multi-row link INSERT.
+///
+/// Each row binds 3 parameters; 300 rows stays under SQLite's historical
+/// 999-variable limit so batching works with any bundled SQLite version.
+const LINK_INSERT_BATCH_ROWS: usize = 300;
+
+/// Columns added after the initial schema, applied to existing databases on open.
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves older tables untouched, so new columns
+/// are added here with `ALTER TABLE` when missing.
+const COLUMN_MIGRATIONS: &[(&str, &str, &str)] = &[
+    ("pages", "page_rank", "REAL"),
+    ("links", "anchor_text", "TEXT"),
+    ("pages", "html_len", "INTEGER"),
+    ("fetch_results", "domain", "TEXT"),
+];
+
+/// An outbound link with the visible text used to link it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundLink {
+    /// Absolute target URL
+    pub url: String,
+    /// Collapsed anchor text, if the link had any
+    pub anchor_text: Option<String>,
+}
+
+impl From<String> for OutboundLink {
+    fn from(url: String) -> Self {
+        Self { url, anchor_text: None }
+    }
+}
+
 /// Persistent index of crawled pages and their link relationships.
 ///
 /// Uses SQLite with WAL mode for:
@@ -62,16 +156,21 @@ pub struct LinkIndex {
 }
 
 impl LinkIndex {
+    /// Path of the index database for an output directory.
+    pub fn db_path(output_dir: &Path) -> PathBuf {
+        output_dir.join(".citescrape").join("link_index.sqlite")
+    }
+
     /// Open existing index or create new one.
     ///
     /// The database is stored at `{output_dir}/.citescrape/link_index.sqlite`
     pub async fn open(output_dir: &Path) -> Result<Self> {
-        let db_dir = output_dir.join(".citescrape");
-        tokio::fs::create_dir_all(&db_dir)
-            .await
-            .context("Failed to create .citescrape directory")?;
-
-        let db_path = db_dir.join("link_index.sqlite");
+        let db_path = Self::db_path(output_dir);
+        if let Some(db_dir) = db_path.parent() {
+            tokio::fs::create_dir_all(db_dir)
+                .await
+                .context("Failed to create .citescrape directory")?;
+        }
 
         // Configure SQLite for optimal concurrent performance
         let options = SqliteConnectOptions::new()
@@ -93,6 +192,11 @@ impl LinkIndex {
             .await
             .context("Failed to initialize database schema")?;
 
+        for (table, column, decl) in COLUMN_MIGRATIONS {
+            ensure_column(&pool, table, column, decl).await?;
+        }
+        backfill_fetch_result_domains(&pool).await?;
+
         // LRU cache for path lookups (1000 entries should cover most cases)
         let path_cache = Arc::new(RwLock::new(lru::LruCache::new(
             std::num::NonZeroUsize::new(1000).unwrap(),
@@ -139,6 +243,22 @@ impl LinkIndex {
         Ok(path)
     }
 
+    /// Get the URL saved at `local_path`, the reverse of [`Self::get_local_path`].
+    ///
+    /// Mirror paths of long or unusual URLs are shortened and hashed, so the
+    /// original URL cannot always be recovered from the path alone.
+    pub async fn get_url_for_path(&self, local_path: &Path) -> Result<Option<String>> {
+        let result: Option<(String,)> = sqlx::query_as(
+            "SELECT url FROM pages WHERE local_path = ? ORDER BY saved_at DESC LIMIT 1"
+        )
+        .bind(local_path.to_string_lossy().as_ref())
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to query URL for path")?;
+
+        Ok(result.map(|(url,)| url))
+    }
+
     /// Get all pages that link TO a given target URL.
     ///
     /// Returns Vec of (source_url, source_local_path) for retroactive rewriting.
@@ -194,16 +314,32 @@ impl LinkIndex {
         url: &str,
         local_path: &Path,
         outbound_links: &[String],
+    ) -> Result<()> {
+        let links: Vec<OutboundLink> = outbound_links.iter().cloned().map(OutboundLink::from).collect();
+        self.register_page_with_anchors(url, local_path, &links).await
+    }
+
+    /// Register a page and its outbound links together with their anchor text.
+    ///
+    /// Same semantics as `register_page`; when a page links to the same target
+    /// several times, the first non-empty anchor text is kept.
+    pub async fn register_page_with_anchors(
+        &self,
+        url: &str,
+        local_path: &Path,
+        outbound_links: &[OutboundLink],
     ) -> Result<()> {
         let normalized_url = normalize_url(url);
         let domain = extract_domain(url);
         let local_path_str = local_path.to_string_lossy().to_string();
         let timestamp = chrono::Utc::now().timestamp();
 
-        // Normalize all outbound links
-        let normalized_outbound: Vec<String> = outbound_links
+        // Normalize all outbound links; links back to the page itself
+        // (`#section` anchors, self-references) are not edges
+        let normalized_outbound: Vec<(String, Option<&str>)> = outbound_links
             .iter()
-            .map(|u| normalize_url(u))
+            .map(|link| (normalize_url(&link.url), link.anchor_text.as_deref()))
+            .filter(|(target, _)| *target != normalized_url)
             .collect();
 
         let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
@@ -215,7 +351,8 @@ impl LinkIndex {
             VALUES (?, ?, ?, ?)
             ON CONFLICT(url) DO UPDATE SET
                 local_path = excluded.local_path,
-                saved_at = excluded.saved_at
+                saved_at = excluded.saved_at,
+                html_len = NULL
             "#
         )
         .bind(&normalized_url)
@@ -226,23 +363,37 @@ impl LinkIndex {
         .await
         .context("Failed to upsert page")?;
 
-        // Clear old outbound links
+        // Clear old outbound links and their positions in the previous copy
         sqlx::query("DELETE FROM links WHERE source_url = ?")
             .bind(&normalized_url)
             .execute(&mut *tx)
             .await
             .context("Failed to delete old links")?;
-
-        // Insert new outbound links
-        for target in &normalized_outbound {
-            sqlx::query(
-                "INSERT OR IGNORE INTO links (source_url, target_url) VALUES (?, ?)"
-            )
+        sqlx::query("DELETE FROM link_positions WHERE source_url = ?")
             .bind(&normalized_url)
-            .bind(target)
             .execute(&mut *tx)
             .await
-            .context("Failed to insert link")?;
+            .context("Failed to delete old link positions")?;
+
+        // Insert new outbound links in multi-row batches
+        for chunk in normalized_outbound.chunks(LINK_INSERT_BATCH_ROWS) {
+            let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+                "INSERT INTO links (source_url, target_url, anchor_text) "
+            );
+            builder.push_values(chunk, |mut row, (target, anchor_text)| {
+                row.push_bind(&normalized_url)
+                    .push_bind(target)
+                    .push_bind(*anchor_text);
+            });
+            builder.push(
+                " ON CONFLICT(source_url, target_url) DO UPDATE SET \
+                 anchor_text = COALESCE(links.anchor_text, excluded.anchor_text)"
+            );
+            builder
+                .build()
+                .execute(&mut *tx)
+                .await
+                .context("Failed to insert links")?;
         }
 
         tx.commit().await.context("Failed to commit transaction")?;
@@ -256,6 +407,23 @@ impl LinkIndex {
         Ok(())
     }
 
+    /// Get the anchor texts other pages use when linking to a URL.
+    ///
+    /// Returns `(source_url, anchor_text)` pairs, skipping links without text.
+    pub async fn get_anchor_texts(&self, target_url: &str) -> Result<Vec<(String, String)>> {
+        sqlx::query_as(
+            r#"
+            SELECT source_url, anchor_text FROM links
+            WHERE target_url = ? AND anchor_text IS NOT NULL AND source_url != target_url
+            ORDER BY source_url
+            "#
+        )
+        .bind(normalize_url(target_url))
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query anchor texts")
+    }
+
     /// Batch check which URLs from a list exist in the index.
     ///
     /// Returns the subset of URLs that have local copies saved.
@@ -331,6 +499,77 @@ impl LinkIndex {
         Ok(row.0)
     }
 
+    /// All saved pages, ordered by URL.
+    pub async fn all_pages(&self) -> Result<Vec<PageRecord>> {
+        let rows: Vec<(String, String, i64)> =
+            sqlx::query_as("SELECT url, domain, saved_at FROM pages ORDER BY url")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to load pages")?;
+        Ok(rows
+            .into_iter()
+            .map(|(url, domain, saved_at)| PageRecord { url, domain, saved_at })
+            .collect())
+    }
+
+    /// All links as `(source_url, target_url)`, ordered by target then source.
+    pub async fn all_links(&self) -> Result<Vec<(String, String)>> {
+        sqlx::query_as("SELECT source_url, target_url FROM links ORDER BY target_url, source_url")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to load links")
+    }
+
+    /// All recorded fetch outcomes, ordered by URL.
+    pub async fn all_fetch_results(&self) -> Result<Vec<FetchRecord>> {
+        let rows: Vec<(String, Option<i64>, Option<String>)> =
+            sqlx::query_as("SELECT url, status_code, error FROM fetch_results ORDER BY url")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to load fetch results")?;
+        Ok(rows
+            .into_iter()
+            .map(|(url, status_code, error)| FetchRecord { url, status_code, error })
+            .collect())
+    }
+
+    /// Record the outcome of fetching a URL.
+    ///
+    /// Called once the HTTP status is known and again if the page fails
+    /// terminally. A later call without a status keeps the previously
+    /// recorded status, so a 404 followed by a validation error still
+    /// reports as a 404.
+    pub async fn record_fetch_result(
+        &self,
+        url: &str,
+        status_code: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let normalized = normalize_url(url);
+        let timestamp = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            r#"
+            INSERT INTO fetch_results (url, status_code, error, attempted_at, domain)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(url) DO UPDATE SET
+                status_code = COALESCE(excluded.status_code, fetch_results.status_code),
+                error = COALESCE(excluded.error, fetch_results.error),
+                attempted_at = excluded.attempted_at
+            "#
+        )
+        .bind(&normalized)
+        .bind(status_code.map(i64::from))
+        .bind(error)
+        .bind(timestamp)
+        .bind(extract_domain(&normalized))
+        .execute(&self.pool)
+        .await
+        .context("Failed to record fetch result")?;
+
+        Ok(())
+    }
+
     /// Get the output directory this index is associated with.
     pub fn output_dir(&self) -> &Path {
         &self.output_dir
@@ -342,14 +581,99 @@ impl LinkIndex {
     }
 }
 
+/// Add a column to a table if it does not exist yet.
+async fn ensure_column(pool: &SqlitePool, table: &str, column: &str, decl: &str) -> Result<()> {
+    let columns = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(pool)
+        .await
+        .with_context(|| format!("Failed to inspect table {table}"))?;
+
+    if columns.iter().any(|row| row.get::<String, _>("name") == column) {
+        return Ok(());
+    }
+
+    sqlx::query(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"))
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to add column {table}.{column}"))?;
+
+    Ok(())
+}
+
+/// Fill `fetch_results.domain` for rows recorded before the column existed
+/// and index it for domain-scoped pruning.
+async fn backfill_fetch_result_domains(pool: &SqlitePool) -> Result<()> {
+    let urls: Vec<(String,)> = sqlx::query_as("SELECT url FROM fetch_results WHERE domain IS NULL")
+        .fetch_all(pool)
+        .await
+        .context("Failed to query fetch results without a domain")?;
+
+    if !urls.is_empty() {
+        let mut tx = pool.begin().await.context("Failed to begin transaction")?;
+        for (url,) in &urls {
+            sqlx::query("UPDATE fetch_results SET domain = ? WHERE url = ?")
+                .bind(extract_domain(url))
+                .bind(url)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to backfill fetch result domain")?;
+        }
+        tx.commit().await.context("Failed to commit fetch result domains")?;
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_fetch_results_domain ON fetch_results(domain)")
+        .execute(pool)
+        .await
+        .context("Failed to index fetch result domains")?;
+
+    Ok(())
+}
+
+/// Query parameters [`normalize_url`] strips unless [`set_tracking_params`] says otherwise.
+///
+/// A trailing `*` matches every parameter with that prefix.
+pub const DEFAULT_TRACKING_PARAMS: &[&str] = &[
+    "utm_*", "gclid", "dclid", "fbclid", "msclkid", "yclid", "mc_cid", "mc_eid", "_ga", "ref",
+];
+
+/// Server-wide tracking parameter list set at startup (see [`set_tracking_params`])
+static TRACKING_PARAMS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Strip `params` instead of [`DEFAULT_TRACKING_PARAMS`] when normalizing URLs
+///
+/// An empty list keeps every query parameter. Only the first call takes
+/// effect, since changing the list would orphan URLs already in the index;
+/// returns `false` when a list was already set.
+pub fn set_tracking_params(params: Vec<String>) -> bool {
+    let params = params.into_iter().map(|p| p.trim().to_ascii_lowercase()).filter(|p| !p.is_empty()).collect();
+    TRACKING_PARAMS.set(params).is_ok()
+}
+
+/// Whether `name` is a tracking parameter [`normalize_url`] strips
+pub fn is_tracking_param(name: &str) -> bool {
+    fn matches(pattern: &str, name: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => name.len() >= prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix),
+            None => name.eq_ignore_ascii_case(pattern),
+        }
+    }
+
+    match TRACKING_PARAMS.get() {
+        Some(params) => params.iter().any(|p| matches(p, name)),
+        None => DEFAULT_TRACKING_PARAMS.iter().any(|p| matches(p, name)),
+    }
+}
+
 /// Normalize URL for consistent matching across different representations.
 ///
 /// Handles:
 /// - Lowercase scheme and host
 /// - Remove default ports (80, 443)
 /// - Remove trailing slash from path (unless root)
+/// - Remove tracking query parameters (see [`is_tracking_param`])
 /// - Remove fragment
 /// - Decode unnecessary percent-encoding (but keep encoded chars that need it)
+/// - Internationalized hosts as punycode, Unicode paths NFC-normalized and encoded
 pub fn normalize_url(url: &str) -> String {
     // Try to parse as URL
     let parsed = match Url::parse(url) {
@@ -384,20 +708,29 @@ pub fn normalize_url(url: &str) -> String {
         }
     }
 
-    // Path (remove trailing slash unless root)
-    let path = parsed.path();
+    // Path (canonical percent-encoding, remove trailing slash unless root)
+    let path = crate::imurl::canonical_path(parsed.path());
     if path.len() > 1 && path.ends_with('/') {
         normalized.push_str(&path[..path.len() - 1]);
     } else if path.is_empty() {
         normalized.push('/');
     } else {
-        normalized.push_str(path);
+        normalized.push_str(&path);
     }
 
-    // Query string (keep as-is, it's significant)
+    // Query string (significant, except for campaign/click tracking parameters)
     if let Some(query) = parsed.query() {
-        normalized.push('?');
-        normalized.push_str(query);
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+                !is_tracking_param(name)
+            })
+            .collect();
+        if !kept.is_empty() {
+            normalized.push('?');
+            normalized.push_str(&kept.join("&"));
+        }
     }
 
     // Fragment is omitted (not significant for page identity)
@@ -413,6 +746,15 @@ pub fn extract_domain(url: &str) -> String {
         .unwrap_or_default()
 }
 
+/// Extract the registrable domain (eTLD+1) from URL, e.g. `example.co.uk`
+/// for `https://www.example.co.uk/`.
+pub fn extract_registrable_domain(url: &str) -> String {
+    crate::imurl::ImUrl::parse(url)
+        .ok()
+        .and_then(|u| u.registrable_domain().map(str::to_string))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -446,11 +788,39 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_normalize_url_idn() {
+        let expected = "https://xn--mnchen-3ya.de/caf%C3%A9";
+        assert_eq!(normalize_url("https://München.de/café/"), expected);
+        assert_eq!(normalize_url("https://xn--mnchen-3ya.de/caf%c3%a9"), expected);
+        assert_eq!(normalize_url("https://münchen.de/cafe%CC%81"), expected);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_url_strips_tracking_params() {
+        assert_eq!(
+            normalize_url("https://example.com/post?utm_source=feed&UTM_Medium=rss"),
+            "https://example.com/post"
+        );
+        assert_eq!(
+            normalize_url("https://example.com/post?id=7&gclid=abc&ref=hn&page=2"),
+            "https://example.com/post?id=7&page=2"
+        );
+        assert_eq!(
+            normalize_url("https://example.com/post?referrer=x&fbclid"),
+            "https://example.com/post?referrer=x"
+        );
+        assert!(is_tracking_param("utm_campaign"));
+        assert!(!is_tracking_param("utm"));
+    }
+
     #[tokio::test]
     async fn test_extract_domain() {
         assert_eq!(extract_domain("https://Example.Com/path"), "example.com");
         assert_eq!(extract_domain("http://sub.domain.org:8080/"), "sub.domain.org");
         assert_eq!(extract_domain("invalid-url"), "");
+        assert_eq!(extract_registrable_domain("https://www.Example.co.uk/path"), "example.co.uk");
+        assert_eq!(extract_registrable_domain("invalid-url"), "");
     }
 
     #[tokio::test]
@@ -484,6 +854,10 @@ mod tests {
         let missing = index.get_local_path("https://nowhere.com").await?;
         assert_eq!(missing, None);
 
+        // Reverse lookup from the saved path
+        assert_eq!(index.get_url_for_path(&local_path).await?, Some(page_url.to_string()));
+        assert_eq!(index.get_url_for_path(&temp_dir.path().join("other.html")).await?, None);
+
         // Check outbound links
         let outbound_result = index.get_outbound_links(page_url).await?;
         assert_eq!(outbound_result.len(), 2);
@@ -502,6 +876,32 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_self_and_fragment_links_not_stored() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let page_url = "https://example.com/guide";
+        let outbound = vec![
+            "https://example.com/guide#install".to_string(),
+            "https://example.com/guide/".to_string(),
+            "https://example.com/api#intro".to_string(),
+            "https://example.com/api#usage".to_string(),
+        ];
+        index
+            .register_page(page_url, &temp_dir.path().join("guide.html"), &outbound)
+            .await?;
+
+        assert_eq!(index.link_count().await?, 1);
+        assert_eq!(
+            index.get_outbound_links(page_url).await?,
+            vec!["https://example.com/api".to_string()]
+        );
+
+        index.close().await;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_inbound_links() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -598,4 +998,113 @@ mod tests {
         index.close().await;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_broken_links_report() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let home = "https://example.com/";
+        index
+            .register_page(
+                home,
+                &temp_dir.path().join("index.html"),
+                &[
+                    "https://example.com/missing".to_string(),
+                    "https://example.com/timeout".to_string(),
+                    "https://example.com/never-visited".to_string(),
+                    "https://other.com/external".to_string(),
+                ],
+            )
+            .await?;
+        index.record_fetch_result(home, Some(200), None).await?;
+        index.record_fetch_result("https://example.com/missing", Some(404), None).await?;
+        // Terminal error without status keeps an earlier status if one was recorded
+        index.record_fetch_result("https://example.com/missing", None, Some("validation failed")).await?;
+        index.record_fetch_result("https://example.com/timeout", None, Some("navigation timeout")).await?;
+
+        let (report, path) = index.write_broken_links_report().await?;
+        assert!(path.exists());
+
+        assert_eq!(report.http_errors.len(), 2);
+        let missing = &report.http_errors[0];
+        assert_eq!(missing.url, "https://example.com/missing");
+        assert_eq!(missing.status_code, Some(404));
+        assert_eq!(missing.referrers, vec![normalize_url(home)]);
+        let timeout = &report.http_errors[1];
+        assert_eq!(timeout.status_code, None);
+        assert_eq!(timeout.error.as_deref(), Some("navigation timeout"));
+
+        // External targets are not reported as uncrawled internal links
+        assert_eq!(report.uncrawled_internal.len(), 1);
+        assert_eq!(report.uncrawled_internal[0].url, "https://example.com/never-visited");
+
+        index.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_anchor_texts() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let target = "https://example.com/install";
+        let link = |text: Option<&str>| OutboundLink {
+            url: target.to_string(),
+            anchor_text: text.map(str::to_string),
+        };
+
+        index
+            .register_page_with_anchors(
+                "https://example.com/a",
+                &temp_dir.path().join("a.html"),
+                &[link(None), link(Some("Installation guide")), link(Some("install"))],
+            )
+            .await?;
+        index
+            .register_page_with_anchors(
+                "https://example.com/b",
+                &temp_dir.path().join("b.html"),
+                &[link(Some("Getting started"))],
+            )
+            .await?;
+        index
+            .register_page("https://example.com/c", &temp_dir.path().join("c.html"), &[target.to_string()])
+            .await?;
+
+        let anchors = index.get_anchor_texts(target).await?;
+        assert_eq!(
+            anchors,
+            vec![
+                ("https://example.com/a".to_string(), "Installation guide".to_string()),
+                ("https://example.com/b".to_string(), "Getting started".to_string()),
+            ]
+        );
+
+        index.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_page_with_many_links() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        // Spans several insert batches, with a duplicate target across batches
+        let mut outbound: Vec<String> = (0..2500)
+            .map(|i| format!("https://example.com/page/{i}"))
+            .collect();
+        outbound.push("https://example.com/page/0".to_string());
+
+        let page_url = "https://example.com/";
+        index.register_page(page_url, &temp_dir.path().join("index.html"), &outbound).await?;
+        assert_eq!(index.link_count().await?, 2500);
+
+        // Re-registering replaces the previous edges
+        index.register_page(page_url, &temp_dir.path().join("index.html"), &outbound[..10]).await?;
+        assert_eq!(index.get_outbound_links(page_url).await?.len(), 10);
+
+        index.close().await;
+        Ok(())
+    }
 }
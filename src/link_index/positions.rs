@@ -0,0 +1,208 @@
+//! Byte positions of links inside saved HTML files.
+//!
+//! Retroactive inbound rewrites change one `href` in a page that may be
+//! megabytes long. The `link_positions` table records where each `<a href>`
+//! value sits in the saved file (and `pages.html_len` the file's length at
+//! that point), so `LinkRewriter` can overwrite just those bytes instead of
+//! re-parsing and re-writing the whole file. Positions are hints: the
+//! rewriter checks them against the file before patching and re-records them
+//! whenever it has to rewrite a file in full.
+
+use anyhow::{Context, Result};
+
+use super::{LinkIndex, normalize_url};
+
+/// Rows per multi-row position INSERT (4 binds each, under SQLite's 999 limit)
+const POSITION_INSERT_BATCH_ROWS: usize = 200;
+
+/// Location of one `<a href>` value in a saved HTML file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HrefPosition {
+    /// Normalized URL the href resolves to
+    pub target_url: String,
+    /// Byte offset of the value (after the opening quote, if any)
+    pub start: u64,
+    /// Length of the value in bytes
+    pub len: u64,
+}
+
+/// All href positions of a saved HTML file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkPositions {
+    /// File length the positions were recorded for
+    pub file_len: u64,
+    pub hrefs: Vec<HrefPosition>,
+}
+
+/// An in-place rewrite of some hrefs of a saved file, to be reflected in its positions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HrefPatch {
+    /// Number of hrefs rewritten
+    pub count: usize,
+    /// Targets whose hrefs now point at local copies
+    pub targets: Vec<String>,
+    /// `(original start, length change)` of every rewritten value that changed length
+    pub shifts: Vec<(u64, i64)>,
+    /// File length after the patch
+    pub file_len: u64,
+}
+
+impl LinkIndex {
+    /// Replace the recorded href positions of a saved page.
+    pub async fn set_link_positions(&self, source_url: &str, positions: &LinkPositions) -> Result<()> {
+        let source = normalize_url(source_url);
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        sqlx::query("DELETE FROM link_positions WHERE source_url = ?")
+            .bind(&source)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete old link positions")?;
+
+        for chunk in positions.hrefs.chunks(POSITION_INSERT_BATCH_ROWS) {
+            let mut builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+                "INSERT INTO link_positions (source_url, target_url, start, len) "
+            );
+            builder.push_values(chunk, |mut row, href| {
+                row.push_bind(&source)
+                    .push_bind(&href.target_url)
+                    .push_bind(href.start as i64)
+                    .push_bind(href.len as i64);
+            });
+            builder
+                .build()
+                .execute(&mut *tx)
+                .await
+                .context("Failed to insert link positions")?;
+        }
+
+        sqlx::query("UPDATE pages SET html_len = ? WHERE url = ?")
+            .bind(positions.file_len as i64)
+            .bind(&source)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record saved file length")?;
+
+        tx.commit().await.context("Failed to commit link positions")?;
+        Ok(())
+    }
+
+    /// Get the recorded href positions of a saved page, ordered by offset.
+    ///
+    /// Returns `None` when none were recorded since the page was last registered.
+    pub async fn get_link_positions(&self, source_url: &str) -> Result<Option<LinkPositions>> {
+        let source = normalize_url(source_url);
+
+        let file_len: Option<(Option<i64>,)> = sqlx::query_as("SELECT html_len FROM pages WHERE url = ?")
+            .bind(&source)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to query saved file length")?;
+        let Some((Some(file_len),)) = file_len else {
+            return Ok(None);
+        };
+
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT target_url, start, len FROM link_positions WHERE source_url = ? ORDER BY start"
+        )
+        .bind(&source)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query link positions")?;
+
+        Ok(Some(LinkPositions {
+            file_len: file_len as u64,
+            hrefs: rows
+                .into_iter()
+                .map(|(target_url, start, len)| HrefPosition {
+                    target_url,
+                    start: start as u64,
+                    len: len as u64,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Update the positions of a page after `patch` rewrote some of its hrefs in place.
+    pub async fn record_link_patch(&self, source_url: &str, patch: &HrefPatch) -> Result<()> {
+        let source = normalize_url(source_url);
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        for target in &patch.targets {
+            sqlx::query("DELETE FROM link_positions WHERE source_url = ? AND target_url = ?")
+                .bind(&source)
+                .bind(target)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to delete patched link positions")?;
+        }
+
+        // Last edit first, so every shift is applied in the original coordinates
+        let mut shifts = patch.shifts.clone();
+        shifts.sort_unstable_by_key(|&(start, _)| std::cmp::Reverse(start));
+        for (start, delta) in shifts {
+            sqlx::query("UPDATE link_positions SET start = start + ? WHERE source_url = ? AND start > ?")
+                .bind(delta)
+                .bind(&source)
+                .bind(start as i64)
+                .execute(&mut *tx)
+                .await
+                .context("Failed to shift link positions")?;
+        }
+
+        sqlx::query("UPDATE pages SET html_len = ? WHERE url = ?")
+            .bind(patch.file_len as i64)
+            .bind(&source)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record saved file length")?;
+
+        tx.commit().await.context("Failed to commit link patch")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn href(target_url: &str, start: u64, len: u64) -> HrefPosition {
+        HrefPosition { target_url: target_url.to_string(), start, len }
+    }
+
+    #[tokio::test]
+    async fn test_link_patch_shifts_later_positions() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+        let page = "https://example.com/";
+        index.register_page(page, &temp_dir.path().join("index.html"), &[]).await?;
+        assert_eq!(index.get_link_positions(page).await?, None);
+
+        let a = "https://example.com/a";
+        let b = "https://example.com/b";
+        let positions = LinkPositions {
+            file_len: 500,
+            hrefs: vec![href(a, 10, 20), href(b, 100, 20), href(a, 200, 20), href(b, 300, 20)],
+        };
+        index.set_link_positions(page, &positions).await?;
+        assert_eq!(index.get_link_positions(page).await?, Some(positions));
+
+        // Both links to `a` grew by 5 bytes
+        let patch = HrefPatch {
+            count: 2,
+            targets: vec![a.to_string()],
+            shifts: vec![(10, 5), (200, 5)],
+            file_len: 510,
+        };
+        index.record_link_patch(page, &patch).await?;
+        assert_eq!(
+            index.get_link_positions(page).await?,
+            Some(LinkPositions { file_len: 510, hrefs: vec![href(b, 105, 20), href(b, 310, 20)] })
+        );
+
+        // Registering the page again drops its positions
+        index.register_page(page, &temp_dir.path().join("index.html"), &[]).await?;
+        assert_eq!(index.get_link_positions(page).await?, None);
+        Ok(())
+    }
+}
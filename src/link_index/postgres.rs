@@ -0,0 +1,555 @@
+//! Postgres backend for the link graph (feature `postgres`).
+//!
+//! Lets crawlers on several machines share one link graph. Local paths are
+//! stored relative to the crawl output directory, so every host resolves
+//! them against its own (shared or synced) output directory.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+
+use anyhow::{Context, Result};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+use super::store::{FetchRecord, LinkStore, PageRecord, StoreFuture};
+use super::{AliasKind, OutboundLink, extract_domain, normalize_url};
+
+/// Postgres schema, equivalent to the SQLite schema of `LinkIndex`
+const PG_SCHEMA_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS pages (
+    url TEXT PRIMARY KEY,
+    local_path TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    saved_at BIGINT NOT NULL,
+    page_rank DOUBLE PRECISION
+);
+CREATE INDEX IF NOT EXISTS idx_pages_domain ON pages(domain);
+
+CREATE TABLE IF NOT EXISTS links (
+    id BIGSERIAL PRIMARY KEY,
+    source_url TEXT NOT NULL,
+    target_url TEXT NOT NULL,
+    anchor_text TEXT,
+    UNIQUE(source_url, target_url)
+);
+CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_url);
+CREATE INDEX IF NOT EXISTS idx_links_target ON links(target_url);
+
+CREATE TABLE IF NOT EXISTS fetch_results (
+    url TEXT PRIMARY KEY,
+    status_code INTEGER,
+    error TEXT,
+    attempted_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS aliases (
+    alias_url TEXT PRIMARY KEY,
+    target_url TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    recorded_at BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_aliases_target ON aliases(target_url);
+"#;
+
+/// Rows per multi-row link INSERT (3 binds each, well under Postgres' 65535 limit)
+const PG_LINK_INSERT_BATCH_ROWS: usize = 1000;
+
+/// Link graph stored in a shared Postgres database.
+#[derive(Clone)]
+pub struct PgLinkIndex {
+    pool: PgPool,
+    output_dir: PathBuf,
+}
+
+impl PgLinkIndex {
+    /// Connect to `database_url` and create the schema if needed.
+    pub async fn connect(database_url: &str, output_dir: &Path) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(8)
+            .connect(database_url)
+            .await
+            .context("Failed to connect to Postgres link index")?;
+
+        sqlx::raw_sql(PG_SCHEMA_SQL)
+            .execute(&pool)
+            .await
+            .context("Failed to initialize Postgres link index schema")?;
+
+        Ok(Self {
+            pool,
+            output_dir: output_dir.to_path_buf(),
+        })
+    }
+
+    /// Store paths relative to the output directory when possible.
+    fn stored_path(&self, local_path: &Path) -> String {
+        local_path
+            .strip_prefix(&self.output_dir)
+            .unwrap_or(local_path)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn resolve_stored_path(&self, stored: &str) -> PathBuf {
+        self.output_dir.join(stored)
+    }
+
+    async fn register(&self, url: &str, local_path: &Path, outbound_links: &[OutboundLink]) -> Result<()> {
+        let normalized_url = normalize_url(url);
+        let normalized_outbound: Vec<(String, Option<&str>)> = outbound_links
+            .iter()
+            .map(|link| (normalize_url(&link.url), link.anchor_text.as_deref()))
+            .filter(|(target, _)| *target != normalized_url)
+            .collect();
+
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO pages (url, local_path, domain, saved_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(url) DO UPDATE SET
+                local_path = excluded.local_path,
+                saved_at = excluded.saved_at
+            "#
+        )
+        .bind(&normalized_url)
+        .bind(self.stored_path(local_path))
+        .bind(extract_domain(url))
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to upsert page")?;
+
+        sqlx::query("DELETE FROM links WHERE source_url = $1")
+            .bind(&normalized_url)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete old links")?;
+
+        // Postgres rejects a multi-row upsert touching the same key twice,
+        // so duplicates are collapsed first (keeping the first anchor text)
+        let mut position: HashMap<String, usize> = HashMap::new();
+        let mut unique: Vec<(String, Option<&str>)> = Vec::new();
+        for (target, anchor_text) in normalized_outbound {
+            match position.get(&target) {
+                Some(&i) => {
+                    if unique[i].1.is_none() {
+                        unique[i].1 = anchor_text;
+                    }
+                }
+                None => {
+                    position.insert(target.clone(), unique.len());
+                    unique.push((target, anchor_text));
+                }
+            }
+        }
+
+        for chunk in unique.chunks(PG_LINK_INSERT_BATCH_ROWS) {
+            let mut builder = sqlx::QueryBuilder::<sqlx::Postgres>::new(
+                "INSERT INTO links (source_url, target_url, anchor_text) "
+            );
+            builder.push_values(chunk, |mut row, (target, anchor_text)| {
+                row.push_bind(&normalized_url)
+                    .push_bind(target)
+                    .push_bind(*anchor_text);
+            });
+            builder.push(
+                " ON CONFLICT(source_url, target_url) DO UPDATE SET \
+                 anchor_text = COALESCE(links.anchor_text, excluded.anchor_text)"
+            );
+            builder
+                .build()
+                .execute(&mut *tx)
+                .await
+                .context("Failed to insert links")?;
+        }
+
+        tx.commit().await.context("Failed to commit transaction")?;
+        Ok(())
+    }
+
+    async fn local_path(&self, url: &str) -> Result<Option<PathBuf>> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT local_path FROM pages WHERE url = $1")
+            .bind(normalize_url(url))
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to query local path")?;
+
+        Ok(row.map(|(p,)| self.resolve_stored_path(&p)))
+    }
+
+    async fn inbound_links(&self, target_url: &str) -> Result<Vec<(String, PathBuf)>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT l.source_url, p.local_path
+            FROM links l
+            JOIN pages p ON l.source_url = p.url
+            WHERE l.target_url = $1
+            "#
+        )
+        .bind(normalize_url(target_url))
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query inbound links")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(url, path)| (url, self.resolve_stored_path(&path)))
+            .collect())
+    }
+
+    async fn outbound_links(&self, source_url: &str) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT target_url FROM links WHERE source_url = $1")
+            .bind(normalize_url(source_url))
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to query outbound links")?;
+
+        Ok(rows.into_iter().map(|(url,)| url).collect())
+    }
+
+    async fn existing(&self, urls: &[String]) -> Result<HashSet<String>> {
+        if urls.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let normalized: Vec<String> = urls.iter().map(|u| normalize_url(u)).collect();
+        let rows: Vec<(String,)> = sqlx::query_as("SELECT url FROM pages WHERE url = ANY($1)")
+            .bind(&normalized)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to filter existing URLs")?;
+
+        Ok(rows.into_iter().map(|(url,)| url).collect())
+    }
+
+    async fn resolve(&self, urls: &[String]) -> Result<HashMap<String, String>> {
+        let mut resolved: HashMap<String, String> = self
+            .existing(urls)
+            .await?
+            .into_iter()
+            .map(|url| (url.clone(), url))
+            .collect();
+        if urls.is_empty() {
+            return Ok(resolved);
+        }
+
+        let normalized: Vec<String> = urls.iter().map(|u| normalize_url(u)).collect();
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT a.alias_url, a.target_url FROM aliases a
+            JOIN pages p ON p.url = a.target_url
+            WHERE a.alias_url = ANY($1)
+            "#
+        )
+        .bind(&normalized)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to resolve aliases")?;
+
+        for (alias, target) in rows {
+            resolved.entry(alias).or_insert(target);
+        }
+        Ok(resolved)
+    }
+
+    async fn alias(&self, alias_url: &str, target_url: &str, kind: AliasKind) -> Result<bool> {
+        let alias = normalize_url(alias_url);
+        let target = normalize_url(target_url);
+
+        let mut tx = self.pool.begin().await.context("Failed to begin transaction")?;
+
+        // Flatten chains: store the target's own final URL if it is an alias
+        // without a saved page of its own
+        let final_target: Option<(String,)> = sqlx::query_as(
+            "SELECT target_url FROM aliases WHERE alias_url = $1 \
+             AND NOT EXISTS (SELECT 1 FROM pages WHERE url = $1)",
+        )
+        .bind(&target)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to resolve alias")?;
+        let target = final_target.map_or(target, |(t,)| t);
+        if alias == target {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO aliases (alias_url, target_url, kind, recorded_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(alias_url) DO UPDATE SET
+                target_url = excluded.target_url,
+                kind = excluded.kind,
+                recorded_at = excluded.recorded_at
+            "#
+        )
+        .bind(&alias)
+        .bind(&target)
+        .bind(kind.as_str())
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to upsert alias")?;
+
+        sqlx::query("UPDATE aliases SET target_url = $1 WHERE target_url = $2")
+            .bind(&target)
+            .bind(&alias)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to repoint chained aliases")?;
+
+        tx.commit().await.context("Failed to commit alias")?;
+        Ok(true)
+    }
+
+    async fn aliases_of(&self, target_url: &str) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT alias_url FROM aliases WHERE target_url = $1 ORDER BY alias_url")
+                .bind(normalize_url(target_url))
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to query aliases")?;
+
+        Ok(rows.into_iter().map(|(alias,)| alias).collect())
+    }
+
+    async fn fetch_result(&self, url: &str, status_code: Option<u16>, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO fetch_results (url, status_code, error, attempted_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT(url) DO UPDATE SET
+                status_code = COALESCE(excluded.status_code, fetch_results.status_code),
+                error = COALESCE(excluded.error, fetch_results.error),
+                attempted_at = excluded.attempted_at
+            "#
+        )
+        .bind(normalize_url(url))
+        .bind(status_code.map(i32::from))
+        .bind(error)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&self.pool)
+        .await
+        .context("Failed to record fetch result")?;
+
+        Ok(())
+    }
+
+    async fn pages(&self) -> Result<Vec<PageRecord>> {
+        let rows: Vec<(String, String, i64)> =
+            sqlx::query_as("SELECT url, domain, saved_at FROM pages ORDER BY url")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to load pages")?;
+        Ok(rows
+            .into_iter()
+            .map(|(url, domain, saved_at)| PageRecord { url, domain, saved_at })
+            .collect())
+    }
+
+    async fn links(&self) -> Result<Vec<(String, String)>> {
+        sqlx::query_as("SELECT source_url, target_url FROM links ORDER BY target_url, source_url")
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to load links")
+    }
+
+    async fn fetch_results(&self) -> Result<Vec<FetchRecord>> {
+        let rows: Vec<(String, Option<i32>, Option<String>)> =
+            sqlx::query_as("SELECT url, status_code, error FROM fetch_results ORDER BY url")
+                .fetch_all(&self.pool)
+                .await
+                .context("Failed to load fetch results")?;
+        Ok(rows
+            .into_iter()
+            .map(|(url, status_code, error)| FetchRecord {
+                url,
+                status_code: status_code.map(i64::from),
+                error,
+            })
+            .collect())
+    }
+
+    async fn page_ranks(&self, ranks: &[(String, f64)]) -> Result<()> {
+        let (urls, scores): (Vec<&str>, Vec<f64>) = ranks.iter().map(|(url, rank)| (url.as_str(), *rank)).unzip();
+        sqlx::query(
+            r#"
+            UPDATE pages SET page_rank = r.rank
+            FROM UNNEST($1::TEXT[], $2::DOUBLE PRECISION[]) AS r(url, rank)
+            WHERE pages.url = r.url
+            "#
+        )
+        .bind(&urls)
+        .bind(&scores)
+        .execute(&self.pool)
+        .await
+        .context("Failed to store PageRank")?;
+        Ok(())
+    }
+
+    async fn count(&self, sql: &'static str) -> Result<i64> {
+        let (n,): (i64,) = sqlx::query_as(sql)
+            .fetch_one(&self.pool)
+            .await
+            .with_context(|| format!("Failed to run count query: {sql}"))?;
+        Ok(n)
+    }
+}
+
+impl LinkStore for PgLinkIndex {
+    fn register_page_with_anchors<'a>(
+        &'a self,
+        url: &'a str,
+        local_path: &'a Path,
+        outbound_links: &'a [OutboundLink],
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(self.register(url, local_path, outbound_links))
+    }
+
+    fn get_local_path<'a>(&'a self, url: &'a str) -> StoreFuture<'a, Option<PathBuf>> {
+        Box::pin(self.local_path(url))
+    }
+
+    fn get_inbound_links<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<(String, PathBuf)>> {
+        Box::pin(self.inbound_links(target_url))
+    }
+
+    fn get_outbound_links<'a>(&'a self, source_url: &'a str) -> StoreFuture<'a, Vec<String>> {
+        Box::pin(self.outbound_links(source_url))
+    }
+
+    fn filter_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashSet<String>> {
+        Box::pin(self.existing(urls))
+    }
+
+    fn resolve_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashMap<String, String>> {
+        Box::pin(self.resolve(urls))
+    }
+
+    fn register_alias<'a>(
+        &'a self,
+        alias_url: &'a str,
+        target_url: &'a str,
+        kind: AliasKind,
+    ) -> StoreFuture<'a, bool> {
+        Box::pin(self.alias(alias_url, target_url, kind))
+    }
+
+    fn get_aliases<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<String>> {
+        Box::pin(self.aliases_of(target_url))
+    }
+
+    fn record_fetch_result<'a>(
+        &'a self,
+        url: &'a str,
+        status_code: Option<u16>,
+        error: Option<&'a str>,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(self.fetch_result(url, status_code, error))
+    }
+
+    fn page_count(&self) -> StoreFuture<'_, i64> {
+        Box::pin(self.count("SELECT COUNT(*) FROM pages"))
+    }
+
+    fn link_count(&self) -> StoreFuture<'_, i64> {
+        Box::pin(self.count("SELECT COUNT(*) FROM links"))
+    }
+
+    fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    fn all_pages(&self) -> StoreFuture<'_, Vec<PageRecord>> {
+        Box::pin(self.pages())
+    }
+
+    fn all_links(&self) -> StoreFuture<'_, Vec<(String, String)>> {
+        Box::pin(self.links())
+    }
+
+    fn all_fetch_results(&self) -> StoreFuture<'_, Vec<FetchRecord>> {
+        Box::pin(self.fetch_results())
+    }
+
+    fn set_page_ranks<'a>(&'a self, ranks: &'a [(String, f64)]) -> StoreFuture<'a, ()> {
+        Box::pin(self.page_ranks(ranks))
+    }
+
+    fn close(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(self.pool.close())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Database the test may create and drop a scratch schema in
+    const TEST_DATABASE_ENV: &str = "CITESCRAPE_TEST_POSTGRES_URL";
+
+    #[tokio::test]
+    async fn test_reports_on_postgres() -> Result<()> {
+        let Ok(database_url) = std::env::var(TEST_DATABASE_ENV) else {
+            eprintln!("Skipping: {TEST_DATABASE_ENV} is not set");
+            return Ok(());
+        };
+        let schema = format!("citescrape_test_{}", std::process::id());
+        let admin = PgPool::connect(&database_url).await?;
+        sqlx::raw_sql(&format!("DROP SCHEMA IF EXISTS {schema} CASCADE; CREATE SCHEMA {schema}"))
+            .execute(&admin)
+            .await?;
+
+        let separator = if database_url.contains('?') { '&' } else { '?' };
+        let scoped_url = format!("{database_url}{separator}options[search_path]={schema}");
+        let temp_dir = TempDir::new()?;
+        let store = PgLinkIndex::connect(&scoped_url, temp_dir.path()).await?;
+
+        let home = "https://example.com/";
+        let guide = "https://example.com/guide";
+        let orphan = "https://example.com/orphan";
+        let page = |name: &str| temp_dir.path().join(name);
+        let link = |url: &str| OutboundLink::from(url.to_string());
+        store
+            .register_page_with_anchors(home, &page("a.html"), &[link(guide), link("https://example.com/gone")])
+            .await?;
+        store
+            .register_page_with_anchors(guide, &page("b.html"), &[link(home), link("https://example.com/later")])
+            .await?;
+        store
+            .register_page_with_anchors(orphan, &page("c.html"), &[link("https://other.com/")])
+            .await?;
+        store.record_fetch_result("https://example.com/gone", Some(404), None).await?;
+
+        let (report, path) = store.write_broken_links_report().await?;
+        assert!(path.exists());
+        assert_eq!(report.http_errors.len(), 1);
+        assert_eq!(report.http_errors[0].status_code, Some(404));
+        assert_eq!(report.http_errors[0].referrers, vec![normalize_url(home)]);
+        let uncrawled: Vec<&str> = report.uncrawled_internal.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(uncrawled, vec![normalize_url("https://example.com/later")]);
+
+        assert_eq!(store.compute_page_rank(0.85, 100).await?, 3);
+        let (ranked,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM pages WHERE page_rank IS NOT NULL")
+            .fetch_one(&store.pool)
+            .await?;
+        assert_eq!(ranked, 3);
+
+        let audit = store.site_audit(Some(home)).await?;
+        assert_eq!(audit.orphan_pages, vec![normalize_url(orphan)]);
+        assert_eq!(audit.dead_end_pages, vec![normalize_url(orphan)]);
+
+        let written = store.write_sitemap(None).await?;
+        let xml = tokio::fs::read_to_string(&written[0]).await?;
+        assert_eq!(xml.matches("<loc>").count(), 3);
+
+        store.close().await;
+        sqlx::raw_sql(&format!("DROP SCHEMA {schema} CASCADE")).execute(&admin).await?;
+        admin.close().await;
+        Ok(())
+    }
+}
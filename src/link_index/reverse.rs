@@ -0,0 +1,115 @@
+//! Reverse lookups: from a file in the output tree back to the page it holds.
+//!
+//! Mirror paths cannot always be turned back into URLs by string manipulation:
+//! query strings are dropped, unsafe names are escaped and long paths are
+//! hashed. The index records where every page was saved, so any file in a
+//! page's directory (`index.html`, `index.md`, `index.json`, screenshots)
+//! resolves to the URL saved there and when it was saved.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::LinkIndex;
+
+/// Files a page registers under, in the page's mirror directory.
+const PAGE_FILENAMES: &[&str] = &["index.html", "index.md"];
+
+/// A page as recorded in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SavedPage {
+    /// Normalized URL of the page
+    pub url: String,
+    /// File the page was registered under
+    pub local_path: PathBuf,
+    /// When the page was saved (Unix seconds)
+    pub saved_at: i64,
+}
+
+impl LinkIndex {
+    /// Find the page saved at `path`.
+    ///
+    /// `path` may be the registered file, any other file in the page's
+    /// directory, or the directory itself. Relative paths are resolved against
+    /// the index's output directory.
+    pub async fn find_saved_page(&self, path: &Path) -> Result<Option<SavedPage>> {
+        let path = if path.is_relative() {
+            self.output_dir.join(path)
+        } else {
+            path.to_path_buf()
+        };
+        let dir = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+
+        let mut candidates = vec![path.to_string_lossy().to_string()];
+        candidates.extend(
+            PAGE_FILENAMES
+                .iter()
+                .map(|name| dir.join(name).to_string_lossy().to_string()),
+        );
+
+        let row: Option<(String, String, i64)> = sqlx::query_as(
+            "SELECT url, local_path, saved_at FROM pages \
+             WHERE local_path IN (?, ?, ?) ORDER BY saved_at DESC LIMIT 1",
+        )
+        .bind(&candidates[0])
+        .bind(&candidates[1])
+        .bind(&candidates[2])
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to look up saved page")?;
+
+        Ok(row.map(|(url, local_path, saved_at)| SavedPage {
+            url,
+            local_path: PathBuf::from(local_path),
+            saved_at,
+        }))
+    }
+
+    /// The crawl output directory containing `path`, found by walking up to
+    /// the nearest directory with a link index.
+    #[must_use]
+    pub fn find_output_dir(path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .find(|dir| Self::db_path(dir).is_file())
+            .map(Path::to_path_buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_find_saved_page_from_any_file_in_page_dir() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        let page_dir = temp_dir.path().join("example.com").join("docs");
+        std::fs::create_dir_all(&page_dir)?;
+        let html = page_dir.join("index.html");
+        index.register_page("https://example.com/docs?lang=en", &html, &[]).await?;
+
+        for path in [html.clone(), page_dir.join("index.md.gz"), page_dir.clone()] {
+            let page = index.find_saved_page(&path).await?.expect("page found");
+            assert_eq!(page.url, "https://example.com/docs?lang=en");
+            assert_eq!(page.local_path, html);
+            assert!(page.saved_at > 0);
+        }
+        let relative = index.find_saved_page(Path::new("example.com/docs/index.json")).await?;
+        assert_eq!(relative.map(|p| p.local_path), Some(html.clone()));
+        assert!(index.find_saved_page(&temp_dir.path().join("example.com")).await?.is_none());
+
+        assert_eq!(LinkIndex::find_output_dir(&html).as_deref(), Some(temp_dir.path()));
+        assert_eq!(LinkIndex::find_output_dir(Path::new("/nonexistent/x/index.md")), None);
+
+        index.close().await;
+        Ok(())
+    }
+}
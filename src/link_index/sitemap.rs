@@ -0,0 +1,310 @@
+//! Sitemap generation from the link index.
+//!
+//! Emits `sitemap.xml` for every saved page at the output root. Crawls above
+//! the 50,000 URL protocol limit are split into numbered sitemap files
+//! referenced from a sitemap index written as `sitemap.xml`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use url::Url;
+
+use super::LinkStore;
+use crate::content_saver::sink::note_output_changed;
+use crate::utils::url_utils::mirror_relative_path;
+
+/// File name of the sitemap (or sitemap index) at the output root.
+pub const SITEMAP_FILENAME: &str = "sitemap.xml";
+
+/// Maximum URLs per sitemap file (sitemaps.org protocol limit)
+pub const MAX_URLS_PER_SITEMAP: usize = 50_000;
+
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+const SITEMAP_NS: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
+
+/// Escape the five XML special characters.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Map a crawled URL onto the URL its mirror directory is served from.
+///
+/// `https://docs.example.com/guide?x=1` with base `https://archive.org/docs/`
+/// becomes `https://archive.org/docs/docs.example.com/guide/`: the host stays
+/// the first directory and names are spelled as the mirror saved them (see
+/// [`mirror_relative_path`]), so the query, which the mirror does not keep,
+/// is dropped. Returns `None` for URLs that have no mirror path.
+fn rehost_url(url: &str, base: &Url) -> Option<String> {
+    let relative = mirror_relative_path(&Url::parse(url).ok()?).ok()?;
+
+    let mut rehosted = base.clone();
+    rehosted.set_query(None);
+    rehosted.set_fragment(None);
+    {
+        let mut segments = rehosted.path_segments_mut().ok()?;
+        segments.pop_if_empty();
+        for component in relative.components() {
+            segments.push(&component.as_os_str().to_string_lossy());
+        }
+        segments.push("");
+    }
+    Some(rehosted.to_string())
+}
+
+/// Whether `url` can be a `<loc>`: sitemaps only list absolute HTTP(S) URLs.
+fn is_absolute_http(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Whether `name` is a numbered shard (`sitemap-N.xml`) of a split sitemap.
+fn is_shard_name(name: &str) -> bool {
+    name.strip_prefix("sitemap-")
+        .and_then(|rest| rest.strip_suffix(".xml"))
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Remove shards in `dir` left by an earlier, larger sitemap.
+async fn remove_stale_shards(dir: &Path, written: &[PathBuf]) -> Result<()> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let stale = entry.file_name().to_str().is_some_and(is_shard_name) && !written.contains(&path);
+        if stale {
+            tokio::fs::remove_file(&path)
+                .await
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Format a Unix timestamp as a W3C datetime for `<lastmod>`.
+fn format_lastmod(timestamp: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+/// Render a `<urlset>` document.
+fn render_urlset(entries: &[(String, i64)]) -> String {
+    let mut xml = String::with_capacity(entries.len() * 120 + 128);
+    xml.push_str(XML_HEADER);
+    let _ = writeln!(xml, "<urlset xmlns=\"{SITEMAP_NS}\">");
+    for (loc, saved_at) in entries {
+        let _ = write!(xml, "  <url>\n    <loc>{}</loc>\n", xml_escape(loc));
+        if let Some(lastmod) = format_lastmod(*saved_at) {
+            let _ = writeln!(xml, "    <lastmod>{lastmod}</lastmod>");
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Render a `<sitemapindex>` document.
+fn render_sitemap_index(locations: &[String]) -> String {
+    let mut xml = String::from(XML_HEADER);
+    let _ = writeln!(xml, "<sitemapindex xmlns=\"{SITEMAP_NS}\">");
+    for loc in locations {
+        let _ = writeln!(xml, "  <sitemap>\n    <loc>{}</loc>\n  </sitemap>", xml_escape(loc));
+    }
+    xml.push_str("</sitemapindex>\n");
+    xml
+}
+
+/// Generate `sitemap.xml` for all saved pages of `store` at its output root.
+///
+/// With `rehost_base`, page URLs are rewritten onto that base so the
+/// sitemap describes the re-hosted mirror instead of the original site.
+/// Only absolute HTTP(S) URLs are listed. Above `MAX_URLS_PER_SITEMAP`
+/// pages, numbered `sitemap-N.xml` files are written and `sitemap.xml`
+/// becomes a sitemap index pointing at them by absolute URL, which needs
+/// `rehost_base`. Shards left by an earlier, larger sitemap are removed.
+///
+/// Returns the paths of all files written, the root sitemap first.
+pub(super) async fn write_sitemap<S: LinkStore + ?Sized>(
+    store: &S,
+    rehost_base: Option<&Url>,
+) -> Result<Vec<PathBuf>> {
+    if let Some(base) = rehost_base
+        && (base.cannot_be_a_base() || !matches!(base.scheme(), "http" | "https"))
+    {
+        bail!("Sitemap base must be an absolute HTTP(S) URL: {base}");
+    }
+
+    let pages = store.all_pages().await.context("Failed to load pages for sitemap")?;
+
+    // Pages sharing a mirror directory are listed once, with the latest save
+    let mut locations: BTreeMap<String, i64> = BTreeMap::new();
+    for page in pages {
+        let loc = match rehost_base {
+            Some(base) => rehost_url(&page.url, base),
+            None => is_absolute_http(&page.url).then_some(page.url),
+        };
+        if let Some(loc) = loc {
+            let saved_at = locations.entry(loc).or_insert(page.saved_at);
+            *saved_at = (*saved_at).max(page.saved_at);
+        }
+    }
+    let entries: Vec<(String, i64)> = locations.into_iter().collect();
+
+    let output_dir = store.output_dir();
+    let root_path = output_dir.join(SITEMAP_FILENAME);
+
+    if entries.len() <= MAX_URLS_PER_SITEMAP {
+        tokio::fs::write(&root_path, render_urlset(&entries))
+            .await
+            .with_context(|| format!("Failed to write {}", root_path.display()))?;
+        note_output_changed(&root_path);
+        let written = vec![root_path];
+        remove_stale_shards(output_dir, &written).await?;
+        return Ok(written);
+    }
+
+    let Some(base) = rehost_base else {
+        bail!(
+            "{} pages exceed the {MAX_URLS_PER_SITEMAP} URL sitemap limit; set a sitemap base URL \
+             so the sitemap index can reference its shards",
+            entries.len()
+        );
+    };
+
+    let mut written = vec![root_path.clone()];
+    let mut locations = Vec::new();
+    for (i, chunk) in entries.chunks(MAX_URLS_PER_SITEMAP).enumerate() {
+        let file_name = format!("sitemap-{}.xml", i + 1);
+        let path = output_dir.join(&file_name);
+        tokio::fs::write(&path, render_urlset(chunk))
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let loc = base
+            .join(&file_name)
+            .with_context(|| format!("Failed to resolve {file_name} against {base}"))?;
+        locations.push(loc.to_string());
+        written.push(path);
+    }
+
+    tokio::fs::write(&root_path, render_sitemap_index(&locations))
+        .await
+        .with_context(|| format!("Failed to write {}", root_path.display()))?;
+    remove_stale_shards(output_dir, &written).await?;
+    for path in &written {
+        note_output_changed(path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_index::LinkIndex;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rehost_url() {
+        let base = Url::parse("https://archive.example.org/docs/").unwrap();
+        assert_eq!(
+            rehost_url("https://docs.rs/guide/intro?lang=en", &base).as_deref(),
+            Some("https://archive.example.org/docs/docs.rs/guide/intro/")
+        );
+        let root = Url::parse("https://mirror.local").unwrap();
+        assert_eq!(rehost_url("https://docs.rs/", &root).as_deref(), Some("https://mirror.local/docs.rs/"));
+        // Escaped mirror names are escaped again so servers map them back to the file name
+        assert_eq!(
+            rehost_url("https://example.com/a:b/", &root).as_deref(),
+            Some("https://mirror.local/example.com/a%253Ab/")
+        );
+        assert_eq!(rehost_url("not a url", &root), None);
+    }
+
+    #[test]
+    fn test_is_shard_name() {
+        assert!(is_shard_name("sitemap-1.xml"));
+        assert!(is_shard_name("sitemap-12.xml"));
+        assert!(!is_shard_name("sitemap.xml"));
+        assert!(!is_shard_name("sitemap-.xml"));
+        assert!(!is_shard_name("sitemap-news.xml"));
+    }
+
+    #[test]
+    fn test_render_sitemap_index() {
+        let xml = render_sitemap_index(&["sitemap-1.xml".to_string(), "sitemap-2.xml".to_string()]);
+        assert!(xml.starts_with(XML_HEADER));
+        assert!(xml.contains("<sitemapindex"));
+        assert_eq!(xml.matches("<sitemap>").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_sitemap() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+
+        index
+            .register_page("https://example.com/", &temp_dir.path().join("a.html"), &[])
+            .await?;
+        index
+            .register_page("https://example.com/search?q=a&b=c", &temp_dir.path().join("b.html"), &[])
+            .await?;
+
+        let written = index.write_sitemap(None).await?;
+        assert_eq!(written, vec![temp_dir.path().join(SITEMAP_FILENAME)]);
+
+        let xml = tokio::fs::read_to_string(&written[0]).await?;
+        assert!(xml.contains("<urlset"));
+        assert!(xml.contains("<loc>https://example.com/</loc>"));
+        assert!(xml.contains("<loc>https://example.com/search?q=a&amp;b=c</loc>"));
+        assert_eq!(xml.matches("<lastmod>").count(), 2);
+
+        index.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_sitemap_rehosts_and_removes_stale_shards() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index = LinkIndex::open(temp_dir.path()).await?;
+        let stale = temp_dir.path().join("sitemap-3.xml");
+        tokio::fs::write(&stale, "<urlset/>").await?;
+
+        index
+            .register_page("https://a.example/guide?x=1", &temp_dir.path().join("a.html"), &[])
+            .await?;
+        index
+            .register_page("https://b.example/guide", &temp_dir.path().join("b.html"), &[])
+            .await?;
+
+        let base = Url::parse("https://mirror.example.org/docs/")?;
+        let written = index.write_sitemap(Some(&base)).await?;
+        assert_eq!(written, vec![temp_dir.path().join(SITEMAP_FILENAME)]);
+        assert!(!stale.exists());
+
+        let xml = tokio::fs::read_to_string(&written[0]).await?;
+        assert!(xml.contains("<loc>https://mirror.example.org/docs/a.example/guide/</loc>"));
+        assert!(xml.contains("<loc>https://mirror.example.org/docs/b.example/guide/</loc>"));
+        assert!(!xml.contains("x=1"));
+
+        let relative = Url::parse("mailto:docs@example.com")?;
+        assert!(index.write_sitemap(Some(&relative)).await.is_err());
+
+        index.close().await;
+        Ok(())
+    }
+}
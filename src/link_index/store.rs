@@ -0,0 +1,403 @@
+//! Storage abstraction for the crawl link graph.
+//!
+//! `LinkRewriter` and the crawl engine only need page registration, path
+//! lookups and fetch outcome recording, so they talk to a `LinkStore` instead
+//! of a concrete database. The per-output-directory SQLite `LinkIndex` is the
+//! default; the Postgres backend (feature `postgres`) lets crawlers on several
+//! machines share one link graph.
+//!
+//! The analyses (broken link reports, PageRank, sitemaps, site audits) are
+//! provided methods written against the graph snapshots every backend
+//! exposes, so they run the same on SQLite and Postgres. Tools that open an
+//! output directory's SQLite index directly go through `open_local_index`,
+//! which refuses directories whose crawl kept its graph elsewhere.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+
+use url::Url;
+
+use super::graph::SiteAudit;
+use super::{AliasKind, BrokenLinksReport, HrefPatch, LinkIndex, LinkPositions, OutboundLink};
+
+/// Boxed future returned by `LinkStore` methods.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Marker naming the external backend a crawl kept its link graph in.
+const EXTERNAL_BACKEND_MARKER: &str = "link_store";
+
+/// A saved page as the graph analyses see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRecord {
+    pub url: String,
+    pub domain: String,
+    pub saved_at: i64,
+}
+
+/// A recorded fetch outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchRecord {
+    pub url: String,
+    pub status_code: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Backend-agnostic operations on the link graph.
+pub trait LinkStore: Send + Sync {
+    /// Register a page, its local path and its outbound links (replacing old ones).
+    fn register_page_with_anchors<'a>(
+        &'a self,
+        url: &'a str,
+        local_path: &'a Path,
+        outbound_links: &'a [OutboundLink],
+    ) -> StoreFuture<'a, ()>;
+
+    /// Get local path for URL if it has been saved.
+    fn get_local_path<'a>(&'a self, url: &'a str) -> StoreFuture<'a, Option<PathBuf>>;
+
+    /// Get `(source_url, source_local_path)` for all saved pages linking to `target_url`.
+    fn get_inbound_links<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<(String, PathBuf)>>;
+
+    /// Get all URLs a page links to.
+    fn get_outbound_links<'a>(&'a self, source_url: &'a str) -> StoreFuture<'a, Vec<String>>;
+
+    /// Return the subset of `urls` (normalized) that have been saved.
+    fn filter_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashSet<String>>;
+
+    /// Map URLs with a local copy (directly or via an alias) to the URL to link to.
+    fn resolve_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashMap<String, String>>;
+
+    /// Record `alias_url` as a redirect source or canonical alternate of `target_url`.
+    fn register_alias<'a>(
+        &'a self,
+        alias_url: &'a str,
+        target_url: &'a str,
+        kind: AliasKind,
+    ) -> StoreFuture<'a, bool>;
+
+    /// Get all aliases that point at `target_url`.
+    fn get_aliases<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<String>>;
+
+    /// Record the HTTP status and/or terminal error of a fetch.
+    fn record_fetch_result<'a>(
+        &'a self,
+        url: &'a str,
+        status_code: Option<u16>,
+        error: Option<&'a str>,
+    ) -> StoreFuture<'a, ()>;
+
+    /// Record where the hrefs of a saved page sit in its HTML file.
+    ///
+    /// Positions let inbound rewrites patch files in place; backends that do
+    /// not keep them make `LinkRewriter` rewrite whole files instead.
+    fn set_link_positions<'a>(&'a self, _source_url: &'a str, _positions: &'a LinkPositions) -> StoreFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Get the recorded href positions of a saved page.
+    fn get_link_positions<'a>(&'a self, _source_url: &'a str) -> StoreFuture<'a, Option<LinkPositions>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Update the recorded positions after an in-place rewrite.
+    fn record_link_patch<'a>(&'a self, _source_url: &'a str, _patch: &'a HrefPatch) -> StoreFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Get total number of indexed pages.
+    fn page_count(&self) -> StoreFuture<'_, i64>;
+
+    /// Get total number of indexed links.
+    fn link_count(&self) -> StoreFuture<'_, i64>;
+
+    /// Output directory local paths are resolved against.
+    fn output_dir(&self) -> &Path;
+
+    /// Close the underlying connection pool.
+    fn close(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+
+    /// All saved pages, ordered by URL.
+    fn all_pages(&self) -> StoreFuture<'_, Vec<PageRecord>>;
+
+    /// All links as `(source_url, target_url)`, ordered by target then source.
+    fn all_links(&self) -> StoreFuture<'_, Vec<(String, String)>>;
+
+    /// All recorded fetch outcomes, ordered by URL.
+    fn all_fetch_results(&self) -> StoreFuture<'_, Vec<FetchRecord>>;
+
+    /// Store PageRank scores as `(url, score)`.
+    fn set_page_ranks<'a>(&'a self, ranks: &'a [(String, f64)]) -> StoreFuture<'a, ()>;
+
+    /// Build a broken link report from the current graph (see `broken_links`).
+    fn broken_links_report(&self) -> StoreFuture<'_, BrokenLinksReport> {
+        Box::pin(super::broken_links::build_report(self))
+    }
+
+    /// Build the broken link report and write it as `broken_links.json` in
+    /// the output directory, returning it with the path written.
+    fn write_broken_links_report(&self) -> StoreFuture<'_, (BrokenLinksReport, PathBuf)> {
+        Box::pin(super::broken_links::write_report(self))
+    }
+
+    /// Compute and store PageRank for all saved pages (see `graph`);
+    /// returns the number of pages scored.
+    fn compute_page_rank(&self, damping: f64, max_iterations: usize) -> StoreFuture<'_, usize> {
+        Box::pin(super::graph::compute_page_rank(self, damping, max_iterations))
+    }
+
+    /// Find orphan and dead-end pages; `start_url` is never an orphan.
+    fn site_audit<'a>(&'a self, start_url: Option<&'a str>) -> StoreFuture<'a, SiteAudit> {
+        Box::pin(super::graph::site_audit(self, start_url))
+    }
+
+    /// Write `sitemap.xml` for all saved pages (see `sitemap`), returning
+    /// the files written, the root sitemap first.
+    fn write_sitemap<'a>(&'a self, rehost_base: Option<&'a Url>) -> StoreFuture<'a, Vec<PathBuf>> {
+        Box::pin(super::sitemap::write_sitemap(self, rehost_base))
+    }
+
+    /// Access the SQLite index, if this is one.
+    fn as_sqlite(&self) -> Option<&LinkIndex> {
+        None
+    }
+}
+
+impl LinkStore for LinkIndex {
+    fn register_page_with_anchors<'a>(
+        &'a self,
+        url: &'a str,
+        local_path: &'a Path,
+        outbound_links: &'a [OutboundLink],
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(LinkIndex::register_page_with_anchors(self, url, local_path, outbound_links))
+    }
+
+    fn get_local_path<'a>(&'a self, url: &'a str) -> StoreFuture<'a, Option<PathBuf>> {
+        Box::pin(LinkIndex::get_local_path(self, url))
+    }
+
+    fn get_inbound_links<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<(String, PathBuf)>> {
+        Box::pin(LinkIndex::get_inbound_links(self, target_url))
+    }
+
+    fn get_outbound_links<'a>(&'a self, source_url: &'a str) -> StoreFuture<'a, Vec<String>> {
+        Box::pin(LinkIndex::get_outbound_links(self, source_url))
+    }
+
+    fn filter_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashSet<String>> {
+        Box::pin(LinkIndex::filter_existing(self, urls))
+    }
+
+    fn resolve_existing<'a>(&'a self, urls: &'a [String]) -> StoreFuture<'a, HashMap<String, String>> {
+        Box::pin(LinkIndex::resolve_existing(self, urls))
+    }
+
+    fn register_alias<'a>(
+        &'a self,
+        alias_url: &'a str,
+        target_url: &'a str,
+        kind: AliasKind,
+    ) -> StoreFuture<'a, bool> {
+        Box::pin(LinkIndex::register_alias(self, alias_url, target_url, kind))
+    }
+
+    fn get_aliases<'a>(&'a self, target_url: &'a str) -> StoreFuture<'a, Vec<String>> {
+        Box::pin(LinkIndex::get_aliases(self, target_url))
+    }
+
+    fn record_fetch_result<'a>(
+        &'a self,
+        url: &'a str,
+        status_code: Option<u16>,
+        error: Option<&'a str>,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(LinkIndex::record_fetch_result(self, url, status_code, error))
+    }
+
+    fn set_link_positions<'a>(&'a self, source_url: &'a str, positions: &'a LinkPositions) -> StoreFuture<'a, ()> {
+        Box::pin(LinkIndex::set_link_positions(self, source_url, positions))
+    }
+
+    fn get_link_positions<'a>(&'a self, source_url: &'a str) -> StoreFuture<'a, Option<LinkPositions>> {
+        Box::pin(LinkIndex::get_link_positions(self, source_url))
+    }
+
+    fn record_link_patch<'a>(&'a self, source_url: &'a str, patch: &'a HrefPatch) -> StoreFuture<'a, ()> {
+        Box::pin(LinkIndex::record_link_patch(self, source_url, patch))
+    }
+
+    fn page_count(&self) -> StoreFuture<'_, i64> {
+        Box::pin(LinkIndex::page_count(self))
+    }
+
+    fn link_count(&self) -> StoreFuture<'_, i64> {
+        Box::pin(LinkIndex::link_count(self))
+    }
+
+    fn output_dir(&self) -> &Path {
+        LinkIndex::output_dir(self)
+    }
+
+    fn close(&self) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(LinkIndex::close(self))
+    }
+
+    fn all_pages(&self) -> StoreFuture<'_, Vec<PageRecord>> {
+        Box::pin(LinkIndex::all_pages(self))
+    }
+
+    fn all_links(&self) -> StoreFuture<'_, Vec<(String, String)>> {
+        Box::pin(LinkIndex::all_links(self))
+    }
+
+    fn all_fetch_results(&self) -> StoreFuture<'_, Vec<FetchRecord>> {
+        Box::pin(LinkIndex::all_fetch_results(self))
+    }
+
+    fn set_page_ranks<'a>(&'a self, ranks: &'a [(String, f64)]) -> StoreFuture<'a, ()> {
+        Box::pin(LinkIndex::set_page_ranks(self, ranks))
+    }
+
+    fn as_sqlite(&self) -> Option<&LinkIndex> {
+        Some(self)
+    }
+}
+
+/// Open the link store for a crawl.
+///
+/// Without `database_url` this is the SQLite index under `output_dir`.
+/// `postgres://` / `postgresql://` URLs connect to a shared Postgres database
+/// and require the `postgres` feature; the output directory then records
+/// that its graph lives there (see `open_local_index`).
+pub async fn open_link_store(
+    output_dir: &Path,
+    database_url: Option<&str>,
+) -> Result<Arc<dyn LinkStore>> {
+    match database_url {
+        None => {
+            let index = LinkIndex::open(output_dir).await?;
+            match tokio::fs::remove_file(marker_path(output_dir)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e).context("Failed to clear the external link store marker");
+                }
+                _ => {}
+            }
+            Ok(Arc::new(index))
+        }
+        Some(url) if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+            #[cfg(feature = "postgres")]
+            {
+                let store = super::postgres::PgLinkIndex::connect(url, output_dir).await?;
+                let marker = marker_path(output_dir);
+                if let Some(parent) = marker.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(&marker, "postgres\n")
+                    .await
+                    .context("Failed to record the external link store")?;
+                Ok(Arc::new(store))
+            }
+            #[cfg(not(feature = "postgres"))]
+            {
+                bail!("Postgres link index requires building with the `postgres` feature")
+            }
+        }
+        Some(url) => {
+            let scheme = url.split(':').next().unwrap_or(url);
+            bail!("Unsupported link index URL scheme: {scheme}")
+        }
+    }
+}
+
+fn marker_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(".citescrape").join(EXTERNAL_BACKEND_MARKER)
+}
+
+/// The external backend the crawl in `output_dir` kept its link graph in,
+/// if it did not use the local SQLite index.
+#[must_use]
+pub fn external_backend(output_dir: &Path) -> Option<String> {
+    let backend = std::fs::read_to_string(marker_path(output_dir)).ok()?;
+    Some(backend.trim().to_string())
+}
+
+/// Open the SQLite index of `output_dir` for tools that read it directly.
+///
+/// Returns `None` when no crawl wrote one. Fails when the crawl kept its
+/// graph in an external database, since the local index would be empty and
+/// every report on it falsely clean.
+pub async fn open_local_index(output_dir: &Path) -> Result<Option<LinkIndex>> {
+    if let Some(backend) = external_backend(output_dir) {
+        bail!(
+            "The link graph of {} is stored in {backend} (link_index_url), not in the local link index",
+            output_dir.display()
+        );
+    }
+    if !LinkIndex::db_path(output_dir).exists() {
+        return Ok(None);
+    }
+    LinkIndex::open(output_dir).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_sqlite_store_via_trait() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let store = open_link_store(temp_dir.path(), None).await?;
+        assert!(store.as_sqlite().is_some());
+
+        let page = temp_dir.path().join("a.html");
+        let links = vec![OutboundLink::from("https://example.com/b".to_string())];
+        store
+            .register_page_with_anchors("https://example.com/a", &page, &links)
+            .await?;
+
+        assert_eq!(store.get_local_path("https://example.com/a").await?, Some(page.clone()));
+        assert_eq!(store.get_inbound_links("https://example.com/b").await?.len(), 1);
+        assert_eq!(store.page_count().await?, 1);
+        assert_eq!(store.link_count().await?, 1);
+        assert_eq!(store.output_dir(), temp_dir.path());
+
+        store.close().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_open_local_index_refuses_external_graph() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        assert!(open_local_index(temp_dir.path()).await?.is_none());
+
+        open_link_store(temp_dir.path(), None).await?.close().await;
+        let index = open_local_index(temp_dir.path()).await?.expect("index was created");
+        index.close().await;
+
+        std::fs::write(marker_path(temp_dir.path()), "postgres\n")?;
+        assert_eq!(external_backend(temp_dir.path()).as_deref(), Some("postgres"));
+        let err = open_local_index(temp_dir.path()).await.err().expect("external graph must fail");
+        assert!(err.to_string().contains("postgres"), "{err}");
+
+        // Going back to SQLite clears the marker
+        open_link_store(temp_dir.path(), None).await?.close().await;
+        assert!(external_backend(temp_dir.path()).is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_open_link_store_rejects_unknown_scheme() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = open_link_store(temp_dir.path(), Some("mysql://localhost/links"))
+            .await
+            .err()
+            .expect("unsupported scheme must fail");
+        assert!(err.to_string().contains("mysql"));
+    }
+}
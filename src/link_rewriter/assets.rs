@@ -0,0 +1,589 @@
+//! Asset mirroring for saved HTML pages.
+//!
+//! Downloads the images, scripts, stylesheets and media a page references into
+//! the mirror tree and rewrites their attributes to relative local paths, so the
+//! saved HTML renders offline. Resources inlined as data URIs are untouched.
+//!
+//! Responsive images are covered too: every candidate in `img[srcset]` and
+//! `<picture><source srcset>` is mirrored and rewritten individually.
+//!
+//! Asset URLs come from crawled pages, so each one is checked against the
+//! server's [`UrlPolicy`] before it is requested.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use futures::StreamExt;
+use lol_html::{HtmlRewriter, Settings, element};
+
+use crate::content_saver::sink::note_output_changed;
+use crate::link_index::normalize_url;
+use crate::url_policy::UrlPolicy;
+use crate::utils::http_fetch::host_slot;
+use crate::utils::url_utils::{mirror_relative_path, safe_segment};
+
+/// Maximum size of a single mirrored asset (bytes)
+const MAX_ASSET_SIZE: usize = 20 * 1024 * 1024;
+
+/// Maximum concurrent asset downloads per page
+const ASSET_DOWNLOAD_CONCURRENCY: usize = 8;
+
+/// Overall timeout for one asset download
+const ASSET_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Element/attribute pairs that reference assets
+const ASSET_ATTRIBUTES: &[(&str, &str)] = &[
+    ("img[src]", "src"),
+    ("source[src]", "src"),
+    ("script[src]", "src"),
+    ("link[href]", "href"),
+];
+
+/// `<link rel>` values that reference renderable assets (not other pages)
+const ASSET_LINK_RELS: &[&str] = &[
+    "stylesheet",
+    "icon",
+    "shortcut",
+    "apple-touch-icon",
+    "mask-icon",
+    "preload",
+    "modulepreload",
+    "manifest",
+];
+
+/// Elements whose `srcset` lists responsive image candidates
+const SRCSET_SELECTORS: &[&str] = &["img[srcset]", "picture source[srcset]"];
+
+/// `ASSET_ATTRIBUTES` with compiled selectors, built once and reused for every page
+static ASSET_SELECTORS: LazyLock<Vec<(scraper::Selector, &str)>> = LazyLock::new(|| {
+    ASSET_ATTRIBUTES
+        .iter()
+        .filter_map(|(selector, attr)| Some((scraper::Selector::parse(selector).ok()?, *attr)))
+        .collect()
+});
+
+/// Compiled `SRCSET_SELECTORS`
+static COMPILED_SRCSET_SELECTORS: LazyLock<Vec<scraper::Selector>> = LazyLock::new(|| {
+    SRCSET_SELECTORS
+        .iter()
+        .filter_map(|selector| scraper::Selector::parse(selector).ok())
+        .collect()
+});
+
+/// A single image candidate from a `srcset` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrcsetCandidate {
+    /// Candidate URL as written in the attribute
+    pub url: String,
+    /// Width/density descriptor (`480w`, `2x`), if any
+    pub descriptor: Option<String>,
+}
+
+/// Parse a `srcset` attribute into its image candidates.
+///
+/// Follows the HTML candidate parsing rules: URLs end at whitespace, a URL
+/// ending in commas terminates its candidate, and descriptors run to the next
+/// comma outside parentheses. This keeps URLs containing commas intact.
+pub fn parse_srcset(value: &str) -> Vec<SrcsetCandidate> {
+    let mut candidates = Vec::new();
+    let mut rest = value;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            break;
+        }
+
+        let url_end = rest.find(|c: char| c.is_ascii_whitespace()).unwrap_or(rest.len());
+        let (raw_url, after) = rest.split_at(url_end);
+        rest = after;
+
+        let url = raw_url.trim_end_matches(',');
+        if url.len() != raw_url.len() {
+            // Trailing comma ends the candidate without descriptors
+            candidates.push(SrcsetCandidate { url: url.to_string(), descriptor: None });
+            continue;
+        }
+
+        let mut depth = 0usize;
+        let desc_end = rest
+            .char_indices()
+            .find(|&(_, c)| match c {
+                '(' => {
+                    depth += 1;
+                    false
+                }
+                ')' => {
+                    depth = depth.saturating_sub(1);
+                    false
+                }
+                ',' => depth == 0,
+                _ => false,
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let descriptor = rest[..desc_end].trim();
+        rest = &rest[desc_end..];
+
+        candidates.push(SrcsetCandidate {
+            url: url.to_string(),
+            descriptor: (!descriptor.is_empty()).then(|| descriptor.to_string()),
+        });
+    }
+
+    candidates
+}
+
+/// Serialize image candidates back into a `srcset` attribute value.
+pub fn format_srcset(candidates: &[SrcsetCandidate]) -> String {
+    candidates
+        .iter()
+        .map(|c| match &c.descriptor {
+            Some(descriptor) => format!("{} {}", c.url, descriptor),
+            None => c.url.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check whether a `<link>` element's `rel` marks it as an asset reference.
+fn is_asset_link_rel(rel: Option<&str>) -> bool {
+    rel.is_some_and(|rel| {
+        rel.split_ascii_whitespace()
+            .any(|token| ASSET_LINK_RELS.iter().any(|r| token.eq_ignore_ascii_case(r)))
+    })
+}
+
+/// Resolve an attribute value against the page URL, keeping only http(s) targets.
+fn resolve_asset_url(base: &url::Url, value: &str) -> Option<url::Url> {
+    let value = value.trim();
+    if value.is_empty() || value.starts_with("data:") || value.starts_with('#') {
+        return None;
+    }
+    base.join(value)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+}
+
+/// Extract all mirrorable asset URLs from HTML.
+///
+/// Returns absolute URLs, deduplicated in document order.
+pub fn extract_asset_urls(html: &str, base_url: &str) -> Vec<String> {
+    let Ok(base) = url::Url::parse(base_url) else {
+        return Vec::new();
+    };
+
+    let document = scraper::Html::parse_document(html);
+    let mut seen = HashSet::new();
+    let mut urls = Vec::new();
+
+    for (selector, attr) in ASSET_SELECTORS.iter() {
+        for element in document.select(selector) {
+            if element.value().name() == "link" && !is_asset_link_rel(element.value().attr("rel")) {
+                continue;
+            }
+            if let Some(resolved) = element
+                .value()
+                .attr(attr)
+                .and_then(|value| resolve_asset_url(&base, value))
+            {
+                let url = resolved.to_string();
+                if seen.insert(url.clone()) {
+                    urls.push(url);
+                }
+            }
+        }
+    }
+
+    for selector in COMPILED_SRCSET_SELECTORS.iter() {
+        for element in document.select(selector) {
+            let Some(srcset) = element.value().attr("srcset") else {
+                continue;
+            };
+            for candidate in parse_srcset(srcset) {
+                if let Some(resolved) = resolve_asset_url(&base, &candidate.url) {
+                    let url = resolved.to_string();
+                    if seen.insert(url.clone()) {
+                        urls.push(url);
+                    }
+                }
+            }
+        }
+    }
+
+    urls
+}
+
+/// Local mirror path for an asset URL.
+///
+/// Mirrors `https://host/a/b.css` to `{output_dir}/host/a/b.css`, with names
+/// made safe and overlong paths shortened the way page paths are (see
+/// [`mirror_relative_path`]). Directory-style URLs get an `index` file name,
+/// and query strings are folded into the file name as a short hash so
+/// distinct variants do not overwrite each other.
+pub fn asset_mirror_path(url: &str, output_dir: &Path) -> Option<PathBuf> {
+    let parsed = url::Url::parse(url).ok()?;
+    let mut relative = mirror_relative_path(&parsed).ok()?;
+
+    let directory_style = parsed.path().ends_with('/');
+    let file = if directory_style {
+        "index".to_string()
+    } else {
+        relative.file_name()?.to_string_lossy().into_owned()
+    };
+    let file = match parsed.query() {
+        Some(query) => {
+            let hash = xxhash_rust::xxh3::xxh3_64(query.as_bytes());
+            match file.rsplit_once('.') {
+                Some((stem, ext)) => format!("{stem}-{hash:016x}.{ext}"),
+                None => format!("{file}-{hash:016x}"),
+            }
+        }
+        None => file,
+    };
+    if directory_style {
+        relative.push(safe_segment(&file));
+    } else {
+        relative.set_file_name(safe_segment(&file));
+    }
+
+    Some(output_dir.join(relative))
+}
+
+/// Download a single asset to `dest`, writing via a temporary file.
+///
+/// Fails without sending anything if `policy` refuses `url`.
+async fn download_asset(
+    client: &reqwest::Client,
+    policy: &UrlPolicy,
+    url: &str,
+    user_agent: &str,
+    dest: &Path,
+    rate_rps: Option<f64>,
+) -> Result<()> {
+    let parsed = policy.check_url(url).await?;
+    let _slot = host_slot(&parsed, rate_rps).await?;
+    let mut response = client
+        .get(parsed)
+        .header(reqwest::header::USER_AGENT, user_agent)
+        .timeout(ASSET_TIMEOUT)
+        .send()
+        .await
+        .with_context(|| format!("Failed to request asset {url}"))?
+        .error_for_status()
+        .with_context(|| format!("Asset request failed for {url}"))?;
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_ASSET_SIZE as u64)
+    {
+        return Err(anyhow!("Asset {url} exceeds {MAX_ASSET_SIZE} bytes"));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("Failed to read asset body for {url}"))?
+    {
+        if body.len() + chunk.len() > MAX_ASSET_SIZE {
+            return Err(anyhow!("Asset {url} exceeds {MAX_ASSET_SIZE} bytes"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create asset directory {}", parent.display()))?;
+    }
+    let temp_path = dest.with_extension("tmp-download");
+    tokio::fs::write(&temp_path, &body)
+        .await
+        .context("Failed to write asset")?;
+    tokio::fs::rename(&temp_path, dest)
+        .await
+        .context("Failed to move asset into place")?;
+    note_output_changed(dest);
+
+    Ok(())
+}
+
+/// Download every asset referenced by `html` that is not mirrored yet.
+///
+/// Returns a map of normalized asset URL → local mirror path for every asset
+/// that is available on disk afterwards. Individual download failures are
+/// logged and skipped, as are assets `policy` refuses. Use a client that
+/// checks redirects against the same policy (see
+/// [`guarded_client`](crate::utils::http_fetch::guarded_client)).
+pub(crate) async fn mirror_asset_files(
+    client: &reqwest::Client,
+    policy: &UrlPolicy,
+    html: &str,
+    page_url: &str,
+    output_dir: &Path,
+    user_agent: &str,
+    rate_rps: Option<f64>,
+) -> HashMap<String, PathBuf> {
+    let assets: Vec<(String, PathBuf)> = extract_asset_urls(html, page_url)
+        .into_iter()
+        .filter_map(|url| asset_mirror_path(&url, output_dir).map(|path| (url, path)))
+        .collect();
+
+    futures::stream::iter(assets)
+        .map(|(url, path)| async move {
+            if !tokio::fs::try_exists(&path).await.unwrap_or(false)
+                && let Err(e) = download_asset(client, policy, &url, user_agent, &path, rate_rps).await
+            {
+                log::debug!("Skipping asset {url}: {e}");
+                return None;
+            }
+            Some((normalize_url(&url), path))
+        })
+        .buffer_unordered(ASSET_DOWNLOAD_CONCURRENCY)
+        .filter_map(|entry| async move { entry })
+        .collect()
+        .await
+}
+
+/// Rewrite asset attributes in HTML to point at local copies.
+///
+/// # Arguments
+/// * `html` - The HTML content to rewrite
+/// * `base_url` - The URL of the page being rewritten (for resolving relative URLs)
+/// * `url_to_relative` - Map of normalized asset URL → relative local path
+///
+/// # Returns
+/// Tuple of (rewritten HTML, number of URLs rewritten; each `srcset` candidate counts)
+pub fn rewrite_asset_urls_in_html(
+    html: &str,
+    base_url: &str,
+    url_to_relative: &HashMap<String, String>,
+) -> Result<(String, usize)> {
+    let base = url::Url::parse(base_url).context("Invalid base URL")?;
+    let mut output = Vec::with_capacity(html.len());
+    let rewrite_count = std::sync::atomic::AtomicUsize::new(0);
+
+    let rewrite_attr = |el: &mut lol_html::html_content::Element, attr: &str| {
+        if let Some(relative) = el
+            .get_attribute(attr)
+            .and_then(|value| resolve_asset_url(&base, &value))
+            .and_then(|resolved| url_to_relative.get(&normalize_url(resolved.as_str())))
+        {
+            el.set_attribute(attr, relative)?;
+            rewrite_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+        Ok::<_, lol_html::errors::AttributeNameError>(())
+    };
+
+    let rewrite_srcset = |el: &mut lol_html::html_content::Element| {
+        let Some(srcset) = el.get_attribute("srcset") else {
+            return Ok(());
+        };
+        let mut candidates = parse_srcset(&srcset);
+        let mut changed = 0;
+        for candidate in &mut candidates {
+            if let Some(relative) = resolve_asset_url(&base, &candidate.url)
+                .and_then(|resolved| url_to_relative.get(&normalize_url(resolved.as_str())))
+            {
+                candidate.url.clone_from(relative);
+                changed += 1;
+            }
+        }
+        if changed > 0 {
+            el.set_attribute("srcset", &format_srcset(&candidates))?;
+            rewrite_count.fetch_add(changed, std::sync::atomic::Ordering::Relaxed);
+        }
+        Ok::<_, lol_html::errors::AttributeNameError>(())
+    };
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![
+                element!("img[src]", |el| Ok(rewrite_attr(el, "src")?)),
+                element!("img[srcset]", |el| Ok(rewrite_srcset(el)?)),
+                element!("source[src]", |el| Ok(rewrite_attr(el, "src")?)),
+                element!("picture source[srcset]", |el| Ok(rewrite_srcset(el)?)),
+                element!("script[src]", |el| Ok(rewrite_attr(el, "src")?)),
+                element!("link[href]", |el| {
+                    if is_asset_link_rel(el.get_attribute("rel").as_deref()) {
+                        rewrite_attr(el, "href")?;
+                    }
+                    Ok(())
+                }),
+            ],
+            ..Settings::default()
+        },
+        |c: &[u8]| output.extend_from_slice(c),
+    );
+
+    rewriter
+        .write(html.as_bytes())
+        .map_err(|e| anyhow!("HTML rewrite error: {}", e))?;
+    rewriter
+        .end()
+        .map_err(|e| anyhow!("HTML rewrite finalization error: {}", e))?;
+
+    let result = String::from_utf8(output).context("Invalid UTF-8 in rewritten HTML")?;
+    let count = rewrite_count.load(std::sync::atomic::Ordering::Relaxed);
+
+    Ok((result, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_asset_urls() {
+        let html = r#"
+            <html><head>
+                <link rel="stylesheet" href="/css/site.css">
+                <link rel="canonical" href="https://example.com/page">
+                <link rel="icon" href="favicon.ico">
+                <script src="https://cdn.example.com/app.js"></script>
+            </head><body>
+                <img src="img/logo.png">
+                <img src="data:image/png;base64,AAAA">
+                <video><source src="/media/clip.mp4" type="video/mp4"></video>
+                <img src="img/logo.png">
+            </body></html>
+        "#;
+
+        let urls = extract_asset_urls(html, "https://example.com/docs/page");
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/docs/img/logo.png",
+                "https://example.com/media/clip.mp4",
+                "https://cdn.example.com/app.js",
+                "https://example.com/css/site.css",
+                "https://example.com/docs/favicon.ico",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_srcset() {
+        let parsed = parse_srcset("small.jpg 480w, large.jpg 1080w,hero@2x.jpg 2x");
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[0], SrcsetCandidate { url: "small.jpg".into(), descriptor: Some("480w".into()) });
+        assert_eq!(parsed[2], SrcsetCandidate { url: "hero@2x.jpg".into(), descriptor: Some("2x".into()) });
+
+        // Commas inside URLs survive; a trailing comma ends a descriptor-less candidate
+        let parsed = parse_srcset("https://cdn.example.com/w_400,h_300/img.jpg 1x, fallback.jpg,");
+        assert_eq!(parsed[0].url, "https://cdn.example.com/w_400,h_300/img.jpg");
+        assert_eq!(parsed[1], SrcsetCandidate { url: "fallback.jpg".into(), descriptor: None });
+
+        assert!(parse_srcset("  ").is_empty());
+        assert_eq!(format_srcset(&parse_srcset("a.jpg 1x,b.jpg   2x")), "a.jpg 1x, b.jpg 2x");
+    }
+
+    #[test]
+    fn test_srcset_and_picture_rewriting() {
+        let html = r#"<picture><source srcset="/img/hero.webp 1x, /img/hero@2x.webp 2x" type="image/webp"><img src="/img/hero.jpg" srcset="/img/hero.jpg 1x, https://cdn.other.com/hero@2x.jpg 2x"></picture>"#;
+
+        let urls = extract_asset_urls(html, "https://example.com/page");
+        assert!(urls.contains(&"https://example.com/img/hero.webp".to_string()));
+        assert!(urls.contains(&"https://example.com/img/hero@2x.webp".to_string()));
+        assert!(urls.contains(&"https://cdn.other.com/hero@2x.jpg".to_string()));
+
+        let mut map = HashMap::new();
+        map.insert(normalize_url("https://example.com/img/hero.webp"), "../img/hero.webp".to_string());
+        map.insert(normalize_url("https://example.com/img/hero@2x.webp"), "../img/hero@2x.webp".to_string());
+        map.insert(normalize_url("https://example.com/img/hero.jpg"), "../img/hero.jpg".to_string());
+
+        let (result, count) = rewrite_asset_urls_in_html(html, "https://example.com/page", &map).unwrap();
+        assert_eq!(count, 4);
+        assert!(result.contains(r#"srcset="../img/hero.webp 1x, ../img/hero@2x.webp 2x""#));
+        assert!(result.contains(r#"src="../img/hero.jpg""#));
+        // Candidates without a local copy keep their remote URL
+        assert!(result.contains(r#"srcset="../img/hero.jpg 1x, https://cdn.other.com/hero@2x.jpg 2x""#));
+    }
+
+    #[test]
+    fn test_asset_mirror_path() {
+        let out = Path::new("/out");
+        assert_eq!(
+            asset_mirror_path("https://example.com/css/site.css", out),
+            Some(PathBuf::from("/out/example.com/css/site.css"))
+        );
+        assert_eq!(
+            asset_mirror_path("https://example.com/assets/", out),
+            Some(PathBuf::from("/out/example.com/assets/index"))
+        );
+
+        let v1 = asset_mirror_path("https://example.com/app.js?v=1", out).unwrap();
+        let v2 = asset_mirror_path("https://example.com/app.js?v=2", out).unwrap();
+        assert_ne!(v1, v2);
+        assert!(v1.to_string_lossy().ends_with(".js"));
+
+        // Names are made safe like page paths
+        assert_eq!(
+            asset_mirror_path("https://example.com/img/a%3Ab.png", out),
+            Some(PathBuf::from("/out/example.com/img/a%3Ab.png"))
+        );
+        assert_eq!(
+            asset_mirror_path("https://example.com/con.js", out),
+            Some(PathBuf::from("/out/example.com/con_.js"))
+        );
+        let long = format!("https://example.com/{}.css", "x".repeat(300));
+        let path = asset_mirror_path(&long, out).unwrap();
+        assert!(path.file_name().unwrap().len() <= crate::utils::url_utils::MAX_MIRROR_SEGMENT_BYTES);
+    }
+
+    #[test]
+    fn test_rewrite_asset_urls_in_html() {
+        let html = r#"<link rel="stylesheet" href="/css/site.css"><link rel="canonical" href="/css/site.css"><img src="logo.png"><script src="https://cdn.example.com/app.js"></script><a href="logo.png">x</a>"#;
+
+        let mut map = HashMap::new();
+        map.insert(normalize_url("https://example.com/css/site.css"), "../css/site.css".to_string());
+        map.insert(normalize_url("https://example.com/docs/logo.png"), "logo.png".to_string());
+
+        let (result, count) =
+            rewrite_asset_urls_in_html(html, "https://example.com/docs/page", &map).unwrap();
+
+        assert_eq!(count, 2);
+        assert!(result.contains(r#"<link rel="stylesheet" href="../css/site.css">"#));
+        // Non-asset link relations and anchors are left alone
+        assert!(result.contains(r#"<link rel="canonical" href="/css/site.css">"#));
+        assert!(result.contains(r#"<a href="logo.png">"#));
+        assert!(result.contains(r#"src="https://cdn.example.com/app.js""#));
+    }
+
+    #[tokio::test]
+    async fn test_private_assets_skipped_under_policy() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\nPNG")
+                    .await;
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let html = format!(r#"<img src="http://{addr}/latest/meta-data/logo.png">"#);
+        let asset = asset_mirror_path(&format!("http://{addr}/latest/meta-data/logo.png"), dir.path()).unwrap();
+        let client = reqwest::Client::new();
+        let mirror = |policy: UrlPolicy| {
+            let (client, html, dir) = (client.clone(), html.clone(), dir.path().to_path_buf());
+            async move { mirror_asset_files(&client, &policy, &html, "https://example.com/", &dir, "test", None).await }
+        };
+
+        let blocked = mirror(UrlPolicy::new(None, true)).await;
+        assert!(blocked.is_empty());
+        assert!(!asset.exists(), "private asset fetched despite the policy");
+
+        let allowed = mirror(UrlPolicy::default()).await;
+        assert_eq!(allowed.len(), 1);
+        assert_eq!(std::fs::read(&asset).unwrap(), b"PNG");
+    }
+}
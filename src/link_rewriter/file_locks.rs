@@ -0,0 +1,101 @@
+//! Per-file mutexes with reference-counted eviction.
+//!
+//! Rewrites of the same file must be serialized, but keeping one mutex per
+//! path for the whole crawl leaks memory on million-page crawls. Entries are
+//! removed when the last guard for a path is dropped and nobody is waiting.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+type LockMap = DashMap<PathBuf, Arc<Mutex<()>>>;
+
+/// Map of per-file locks that only holds entries for files in use.
+#[derive(Clone, Default)]
+pub struct FileLocks {
+    locks: Arc<LockMap>,
+}
+
+/// Exclusive access to one file; evicts its map entry on drop if unused.
+pub struct FileLockGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    path: PathBuf,
+    locks: Arc<LockMap>,
+}
+
+impl FileLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock `path`, waiting for any other holder to finish.
+    pub async fn lock(&self, path: &Path) -> FileLockGuard {
+        let mutex = self
+            .locks
+            .entry(path.to_path_buf())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+
+        FileLockGuard {
+            guard: Some(mutex.lock_owned().await),
+            path: path.to_path_buf(),
+            locks: Arc::clone(&self.locks),
+        }
+    }
+
+    /// Number of paths currently locked or waited on.
+    pub fn len(&self) -> usize {
+        self.locks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty()
+    }
+}
+
+impl Drop for FileLockGuard {
+    fn drop(&mut self) {
+        // Release the mutex first so its Arc no longer counts as a user
+        drop(self.guard.take());
+
+        // Only the map holds the mutex now: nobody holds or waits for it.
+        // `remove_if` runs under the shard lock, the same lock `entry()` takes
+        // to hand out clones, so a concurrent locker either already holds a
+        // clone (count > 1) or will insert a fresh entry after removal.
+        self.locks
+            .remove_if(&self.path, |_, mutex| Arc::strong_count(mutex) == 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_entries_evicted_after_last_guard() {
+        let locks = FileLocks::new();
+        let path = PathBuf::from("/out/page/index.html");
+
+        let guard = locks.lock(&path).await;
+        assert_eq!(locks.len(), 1);
+
+        // A waiter keeps the entry alive after the first guard drops
+        let waiter = {
+            let locks = locks.clone();
+            let path = path.clone();
+            tokio::spawn(async move {
+                let _guard = locks.lock(&path).await;
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(guard);
+        assert_eq!(locks.len(), 1);
+
+        waiter.await.unwrap();
+        assert!(locks.is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Markdown link rewriting on top of a CommonMark parser.
+//!
+//! Links are located with pulldown-cmark and only their destination bytes are
+//! replaced in the original text, so formatting, titles and everything the
+//! parser does not treat as a link (code spans, code blocks, raw HTML) are
+//! left exactly as written.
+
+use std::ops::Range;
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+
+/// Parser options matching the GFM output of the markdown converter
+fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+/// An inline link or image whose end has not been seen yet.
+struct OpenLink<'a> {
+    link_type: LinkType,
+    dest: pulldown_cmark::CowStr<'a>,
+    range: Range<usize>,
+    /// End of the last child event (link text / alt text)
+    content_end: usize,
+}
+
+/// Locate `dest` in `markdown[from..end]` at the start of a link destination.
+///
+/// Skips leading whitespace and an opening `<`. Returns `None` when the raw
+/// text differs from the parsed destination (e.g. backslash escapes or
+/// entities), in which case the link is left untouched.
+fn find_destination(markdown: &str, from: usize, end: usize, dest: &str) -> Option<Range<usize>> {
+    let window = markdown.get(from..end)?;
+    let offset = window.len() - window.trim_start().len();
+    let mut start = from + offset;
+    if markdown[start..end].starts_with('<') {
+        start += 1;
+    }
+    markdown[start..end]
+        .starts_with(dest)
+        .then(|| start..start + dest.len())
+}
+
+/// Rewrite link and image destinations in a markdown document.
+///
+/// `resolve` receives each destination URL and returns the replacement
+/// destination for links that should be rewritten. Handles inline links and
+/// images (including multi-line titles and images nested in links),
+/// reference definitions, and autolinks (which become `[name](path)` links).
+/// Text inside code spans and code blocks is never touched.
+///
+/// # Returns
+/// Tuple of (rewritten markdown, number of destinations rewritten)
+pub fn rewrite_markdown_links<F>(markdown: &str, resolve: F) -> (String, usize)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut parser = Parser::new_ext(markdown, parser_options()).into_offset_iter();
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+
+    // Reference definitions: `[label]: dest "title"`
+    for (_, def) in parser.reference_definitions().iter() {
+        let Some(replacement) = resolve(&def.dest) else {
+            continue;
+        };
+        let Some(colon) = markdown[def.span.clone()].find("]:") else {
+            continue;
+        };
+        let from = def.span.start + colon + 2;
+        if let Some(range) = find_destination(markdown, from, def.span.end, &def.dest) {
+            edits.push((range, replacement));
+        }
+    }
+
+    let mut open: Vec<OpenLink<'_>> = Vec::new();
+    for (event, range) in parser.by_ref() {
+        match event {
+            Event::Start(Tag::Link { link_type, dest_url, .. } | Tag::Image { link_type, dest_url, .. }) => {
+                open.push(OpenLink {
+                    link_type,
+                    dest: dest_url,
+                    content_end: range.start,
+                    range,
+                });
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) => {
+                let Some(link) = open.pop() else {
+                    continue;
+                };
+                if let Some(parent) = open.last_mut() {
+                    parent.content_end = parent.content_end.max(link.range.end);
+                }
+
+                match link.link_type {
+                    LinkType::Inline => {
+                        let Some(replacement) = resolve(&link.dest) else {
+                            continue;
+                        };
+                        // The destination follows the `](` after the link text
+                        let Some(bracket) = markdown[link.content_end..link.range.end].find("](") else {
+                            continue;
+                        };
+                        let from = link.content_end + bracket + 2;
+                        if let Some(dest_range) = find_destination(markdown, from, link.range.end, &link.dest) {
+                            edits.push((dest_range, replacement));
+                        }
+                    }
+                    LinkType::Autolink => {
+                        if let Some(replacement) = resolve(&link.dest) {
+                            let name = replacement.rsplit('/').next().unwrap_or(&replacement).to_string();
+                            edits.push((link.range, format!("[{name}]({replacement})")));
+                        }
+                    }
+                    // Reference links are rewritten through their definitions
+                    _ => {}
+                }
+            }
+            _ => {
+                if let Some(link) = open.last_mut() {
+                    link.content_end = link.content_end.max(range.end);
+                }
+            }
+        }
+    }
+
+    if edits.is_empty() {
+        return (markdown.to_string(), 0);
+    }
+
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut output = String::with_capacity(markdown.len() + edits.len() * 16);
+    let mut cursor = 0;
+    let mut count = 0;
+    for (range, replacement) in edits {
+        if range.start < cursor {
+            continue; // overlapping edit, keep the first
+        }
+        output.push_str(&markdown[cursor..range.start]);
+        output.push_str(&replacement);
+        cursor = range.end;
+        count += 1;
+    }
+    output.push_str(&markdown[cursor..]);
+
+    (output, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(url: &str) -> Option<String> {
+        (url == "https://example.com/guide").then(|| "../guide/index.md".to_string())
+    }
+
+    #[test]
+    fn test_inline_links_and_images() {
+        let md = "See [the guide](https://example.com/guide \"Guide\nsecond line\") and \
+                  ![diagram](https://example.com/guide).\n\n\
+                  [![badge](https://example.com/badge.svg)](https://example.com/guide)\n";
+        let (out, count) = rewrite_markdown_links(md, resolve);
+        assert_eq!(count, 3);
+        assert!(out.contains("[the guide](../guide/index.md \"Guide\nsecond line\")"));
+        assert!(out.contains("![diagram](../guide/index.md)"));
+        assert!(out.contains("[![badge](https://example.com/badge.svg)](../guide/index.md)"));
+    }
+
+    #[test]
+    fn test_code_is_left_alone() {
+        let md = "Use `[x](https://example.com/guide)` literally.\n\n\
+                  ```\n[x](https://example.com/guide)\n```\n";
+        let (out, count) = rewrite_markdown_links(md, resolve);
+        assert_eq!(count, 0);
+        assert_eq!(out, md);
+    }
+
+    #[test]
+    fn test_reference_definitions_and_autolinks() {
+        let md = "Read [the guide][g] or <https://example.com/guide>.\n\n\
+                  [g]: <https://example.com/guide> \"The Guide\"\n";
+        let (out, count) = rewrite_markdown_links(md, resolve);
+        assert_eq!(count, 2);
+        assert!(out.contains("[g]: <../guide/index.md> \"The Guide\""));
+        assert!(out.contains("or [index.md](../guide/index.md)."));
+        assert!(out.contains("[the guide][g]"));
+    }
+}
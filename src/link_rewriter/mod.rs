@@ -5,55 +5,42 @@
 //! 2. When a page is saved, retroactively update all existing pages that link TO this new page
 //!
 //! The rewriting is event-driven: triggered AFTER pages are saved to disk.
-
-use std::collections::{HashMap, HashSet};
+//! Where each page's links sit in its file is recorded in the link store, so
+//! inbound updates patch just those bytes when they can (see `patch`).
+//!
+//! When asset mirroring is enabled, `mirror_assets` additionally downloads the
+//! images, scripts and stylesheets a page references (see `assets`).
+
+pub mod assets;
+pub mod file_locks;
+pub mod markdown;
+pub mod patch;
+pub mod scheduler;
+
+pub use assets::{
+    SrcsetCandidate, asset_mirror_path, extract_asset_urls, format_srcset, parse_srcset,
+    rewrite_asset_urls_in_html,
+};
+pub use file_locks::{FileLockGuard, FileLocks};
+pub use markdown::rewrite_markdown_links;
+pub use patch::{href_positions, patch_hrefs};
+pub use scheduler::{DEFAULT_REWRITE_WINDOW, RewriteScheduler};
+
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use lol_html::{HtmlRewriter, Settings, element};
-use dashmap::DashMap;
-use regex::Regex;
-use tokio::sync::{Mutex, Semaphore};
-#[allow(unused_imports)]  // Used by rewrite_single_link_in_markdown (Aho-Corasick for single-link case)
-use aho_corasick::AhoCorasick;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
-
-use crate::link_index::{LinkIndex, normalize_url};
-
-// =============================================================================
-// MARKDOWN LINK PATTERNS - Regex for comprehensive link rewriting
-// =============================================================================
-
-/// Matches inline markdown links: ](url) or ](url "title") or ](<url>)
-/// Captures the URL in group 1
-/// Bounded quantifiers prevent catastrophic backtracking (max 2000 char URL)
-static INLINE_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
-    // Pattern breakdown:
-    // \]\(       - literal ](
-    // <?         - optional opening angle bracket
-    // ([^>\s\)"']{1,2000}) - capture URL (bounded, no whitespace/>/)/"/') 
-    // >?         - optional closing angle bracket
-    // (?:[\s\t]+["'][^"']*["'])? - optional title with quotes
-    // \)         - literal )
-    Regex::new(r#"\]\(<?([^>\s\)"']{1,2000})>?(?:[\s\t]+["'][^"']*["'])?\)"#)
-        .expect("INLINE_LINK_RE: hardcoded regex is valid")
-});
-
-/// Matches reference-style link definitions: [ref]: url or [ref]: <url> "title"
-/// Captures: group 1 = reference label, group 2 = URL
-static REFERENCE_DEF_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"^\[([^\]]{1,200})\]:\s*<?([^>\s]{1,2000})>?(?:\s+["'][^"']*["'])?$"#)
-        .expect("REFERENCE_DEF_RE: hardcoded regex is valid")
-});
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 
-/// Matches autolinks: <https://example.com> or <http://example.com>
-/// Captures the URL in group 1
-static AUTOLINK_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"<(https?://[^>]{1,2000})>")
-        .expect("AUTOLINK_RE: hardcoded regex is valid")
-});
+use crate::content_saver::sink::note_output_changed;
+use crate::imurl::ImUrl;
+use crate::url_policy::url_policy;
+use crate::utils::http_fetch::guarded_client;
+use crate::link_index::{AliasKind, LinkStore, OutboundLink, normalize_url};
 
 /// Result of a link rewriting operation.
 #[derive(Debug, Clone, Default)]
@@ -62,6 +49,8 @@ pub struct RewriteResult {
     pub outbound_rewritten: usize,
     /// Number of existing pages updated with links to this new page
     pub inbound_updated: usize,
+    /// Number of existing pages queued for a batched inbound rewrite
+    pub inbound_scheduled: usize,
     /// Errors encountered during inbound updates (non-fatal)
     pub inbound_errors: Vec<String>,
 }
@@ -69,49 +58,88 @@ pub struct RewriteResult {
 /// Event-driven link rewriter.
 ///
 /// Uses lol_html for efficient streaming HTML rewriting.
-/// Coordinates with LinkIndex for URL → path lookups.
+/// Coordinates with a `LinkStore` for URL → path lookups.
 #[derive(Clone)]
 pub struct LinkRewriter {
-    index: Arc<LinkIndex>,
-    #[allow(dead_code)] // Reserved for future use (e.g., path calculations)
+    index: Arc<dyn LinkStore>,
     output_dir: PathBuf,
     /// Limit concurrent file rewrites to prevent fd exhaustion
     rewrite_semaphore: Arc<Semaphore>,
     /// Per-file locks to serialize concurrent rewrites to the SAME file
-    /// Key: Canonical file path, Value: Mutex guard for that file
-    file_locks: Arc<DashMap<PathBuf, Arc<Mutex<()>>>>,
+    /// (entries are evicted once no rewrite holds or waits for them)
+    file_locks: FileLocks,
+    /// Per-domain rate for asset downloads (`None` = unlimited)
+    asset_rate_rps: Option<f64>,
+    /// Batches retroactive inbound rewrites per source file
+    scheduler: Arc<RewriteScheduler>,
+    /// Flushes spawned at the end of batching windows
+    flush_tasks: TaskTracker,
+    /// Cancelled by [`Self::wait_for_flushes`] so waiting flushes run at once
+    flush_now: CancellationToken,
+    /// Stops pending and in-progress inbound rewrites once cancelled
+    cancel: CancellationToken,
 }
 
 impl LinkRewriter {
     /// Create a new LinkRewriter.
     ///
     /// # Arguments
-    /// * `index` - Shared LinkIndex for URL → path lookups
+    /// * `index` - Shared link store (SQLite or Postgres) for URL → path lookups
     /// * `output_dir` - Base output directory for relative path calculation
-    pub fn new(index: Arc<LinkIndex>, output_dir: PathBuf) -> Self {
+    pub fn new(index: Arc<dyn LinkStore>, output_dir: PathBuf) -> Self {
         Self {
             index,
             output_dir,
             // Limit to 32 concurrent file rewrites to avoid fd exhaustion
             rewrite_semaphore: Arc::new(Semaphore::new(32)),
-            file_locks: Arc::new(DashMap::new()),
+            file_locks: FileLocks::new(),
+            asset_rate_rps: None,
+            scheduler: Arc::new(RewriteScheduler::new(DEFAULT_REWRITE_WINDOW)),
+            flush_tasks: TaskTracker::new(),
+            flush_now: CancellationToken::new(),
+            cancel: CancellationToken::new(),
         }
     }
 
-    /// Acquire a mutex lock for a specific file path.
+    /// Stop rewriting other pages once `token` is cancelled.
+    ///
+    /// Files already being rewritten are finished; the remaining inbound
+    /// rewrites (batched or immediate) are dropped.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Rate-limit asset downloads per domain, sharing the crawl's limiter.
+    #[must_use]
+    pub fn with_asset_rate_limit(mut self, rate_rps: Option<f64>) -> Self {
+        self.asset_rate_rps = rate_rps;
+        self
+    }
+
+    /// Set the window for batching inbound rewrites.
     ///
-    /// Uses DashMap for lock-free concurrent access to DIFFERENT files,
-    /// while serializing access to the SAME file via per-path Mutex.
+    /// Pages linking to newly saved pages are rewritten at most once per
+    /// window. `Duration::ZERO` rewrites them immediately on every save.
+    #[must_use]
+    pub fn with_rewrite_window(mut self, window: std::time::Duration) -> Self {
+        self.scheduler = Arc::new(RewriteScheduler::new(window));
+        self
+    }
+
+    /// Acquire the lock for a specific file path.
     ///
-    /// # Lock Semantics
-    /// - First access to a path creates a new Mutex (lazy initialization)
-    /// - Subsequent accesses return the same Mutex (via Arc clone)
-    /// - Lock must be held across entire read-modify-write cycle
-    fn get_file_lock(&self, path: &Path) -> Arc<Mutex<()>> {
-        self.file_locks
-            .entry(path.to_path_buf())
-            .or_insert_with(|| Arc::new(Mutex::new(())))
-            .clone()
+    /// Concurrent access to DIFFERENT files proceeds in parallel, while
+    /// access to the SAME file is serialized. The guard must be held across
+    /// the entire read-modify-write cycle.
+    async fn lock_file(&self, path: &Path) -> FileLockGuard {
+        self.file_locks.lock(path).await
+    }
+
+    /// Number of files with an active or pending per-file lock.
+    pub fn locked_file_count(&self) -> usize {
+        self.file_locks.len()
     }
 
     /// Get the count of pages registered in the index.
@@ -126,11 +154,12 @@ impl LinkRewriter {
     /// 1. Registers the page in the index (atomic)
     /// 2. Rewrites outbound links in the new page to point to existing local copies
     /// 3. Retroactively updates all pages that link TO this newly saved page
+    ///    (or to one of its redirect/canonical aliases)
     ///
     /// # Arguments
     /// * `page_url` - The canonical URL of the saved page
     /// * `local_path` - The local file path where the HTML was saved
-    /// * `outbound_links` - All HTTP/HTTPS links found in the page
+    /// * `outbound_links` - All HTTP/HTTPS links found in the page, with anchor text
     ///
     /// # Returns
     /// RewriteResult with statistics about the rewriting operation
@@ -138,71 +167,33 @@ impl LinkRewriter {
         &self,
         page_url: &str,
         local_path: &Path,
-        outbound_links: Vec<String>,
+        outbound_links: Vec<OutboundLink>,
     ) -> Result<RewriteResult> {
         let mut result = RewriteResult::default();
 
         // 1. Register page and its outbound links in the index (atomic transaction)
         self.index
-            .register_page(page_url, local_path, &outbound_links)
+            .register_page_with_anchors(page_url, local_path, &outbound_links)
             .await
             .context("Failed to register page in link index")?;
 
-        // 2. Check which outbound links have local copies
-        let existing_destinations = self.index.filter_existing(&outbound_links).await?;
+        // 2. Check which outbound links have local copies (directly or via an alias)
+        let outbound_urls: Vec<String> = outbound_links.into_iter().map(|link| link.url).collect();
+        let existing_destinations = self.index.resolve_existing(&outbound_urls).await?;
 
-        // 3. Rewrite outbound links in the NEW page's HTML (if any exist locally)
-        if !existing_destinations.is_empty() {
-            let count = self
-                .rewrite_outbound_links(page_url, local_path, &existing_destinations)
-                .await
-                .context("Failed to rewrite outbound links")?;
-            result.outbound_rewritten = count;
-        }
-
-        // 4. Find all pages that link TO this newly saved page
-        let inbound = self.index.get_inbound_links(page_url).await?;
-
-        // 5. Rewrite link to this page in all those files (parallel, bounded, file-locked)
-        if !inbound.is_empty() {
-            let page_url = page_url.to_string();
-
-            let update_futures: Vec<_> = inbound
-                .into_iter()
-                .map(|(source_url, source_path)| {
-                    let sem = self.rewrite_semaphore.clone();
-                    let page_url = page_url.clone();
-                    let index = self.index.clone();
-                    let file_locks = self.file_locks.clone();
-
-                    async move {
-                        // 1. Acquire global concurrency permit (limits total parallel I/O)
-                        let _permit = sem.acquire().await.map_err(|e| anyhow!("Semaphore error: {}", e))?;
-
-                        // 2. Acquire per-file lock (serializes access to same file)
-                        let file_lock = file_locks
-                            .entry(source_path.clone())
-                            .or_insert_with(|| Arc::new(Mutex::new(())))
-                            .clone();
-                        let _file_guard = file_lock.lock().await;
-
-                        // 3. Perform rewrite while holding both locks
-                        rewrite_single_link(&source_url, &source_path, &page_url, &index).await
-                    }
-                })
-                .collect();
-
-            let results = futures::future::join_all(update_futures).await;
-
-            for res in results {
-                match res {
-                    Ok(_) => result.inbound_updated += 1,
-                    Err(e) => {
-                        log::warn!("Failed to rewrite inbound link: {e}");
-                        result.inbound_errors.push(e.to_string());
-                    }
-                }
-            }
+        // 3. Rewrite outbound links in the NEW page's HTML (if any exist
+        //    locally) and record where its links sit in the file
+        result.outbound_rewritten = self
+            .rewrite_outbound_links(page_url, local_path, &existing_destinations)
+            .await
+            .context("Failed to rewrite outbound links")?;
+
+        // 4-5. Rewrite links to this page, and to any of its aliases, in all
+        // pages that link to them
+        let mut linked_urls = vec![normalize_url(page_url)];
+        linked_urls.extend(self.index.get_aliases(page_url).await?);
+        for linked_url in &linked_urls {
+            self.rewrite_inbound_links(linked_url, page_url, &mut result).await?;
         }
 
         log::debug!(
@@ -215,12 +206,187 @@ impl LinkRewriter {
         Ok(result)
     }
 
+    /// Record `alias_url` (where a redirect ended, or a declared canonical
+    /// URL) as another URL of the page saved as `target_url`.
+    ///
+    /// If the target already has a local copy, pages that link to the alias
+    /// are rewritten to point at it.
+    ///
+    /// # Returns
+    /// Number of pages whose links were updated or queued for rewriting
+    pub async fn on_alias_discovered(
+        &self,
+        alias_url: &str,
+        target_url: &str,
+        kind: AliasKind,
+    ) -> Result<usize> {
+        if !self.index.register_alias(alias_url, target_url, kind).await? {
+            return Ok(0);
+        }
+
+        let target_url = normalize_url(target_url);
+        let existing = self.index.resolve_existing(std::slice::from_ref(&target_url)).await?;
+        let Some(target_url) = existing.get(&target_url) else {
+            return Ok(0);
+        };
+
+        let mut result = RewriteResult::default();
+        self.rewrite_inbound_links(&normalize_url(alias_url), target_url, &mut result)
+            .await?;
+        Ok(result.inbound_updated + result.inbound_scheduled)
+    }
+
+    /// Rewrite links to `linked_url` in every page linking to it so they
+    /// point at the local copy of `target_url`.
+    async fn rewrite_inbound_links(
+        &self,
+        linked_url: &str,
+        target_url: &str,
+        result: &mut RewriteResult,
+    ) -> Result<()> {
+        let inbound = self.index.get_inbound_links(linked_url).await?;
+        if inbound.is_empty() {
+            return Ok(());
+        }
+
+        if self.scheduler.is_enabled() {
+            for (source_url, source_path) in inbound {
+                if self.scheduler.enqueue(source_path, &source_url, linked_url, target_url).await {
+                    self.schedule_flush();
+                }
+                result.inbound_scheduled += 1;
+            }
+            return Ok(());
+        }
+
+        // Rewrite in parallel, bounded by the semaphore and serialized per file
+        let update_futures: Vec<_> = inbound
+            .into_iter()
+            .map(|(source_url, source_path)| {
+                let sem = self.rewrite_semaphore.clone();
+                let index = self.index.clone();
+                let file_locks = self.file_locks.clone();
+                let cancel = self.cancel.clone();
+
+                async move {
+                    // 1. Acquire global concurrency permit (limits total parallel I/O)
+                    let _permit = sem.acquire().await.map_err(|e| anyhow!("Semaphore error: {}", e))?;
+                    if cancel.is_cancelled() {
+                        bail!("Link rewriting cancelled");
+                    }
+
+                    // 2. Acquire per-file lock (serializes access to same file)
+                    let _file_guard = file_locks.lock(&source_path).await;
+
+                    // 3. Perform rewrite while holding both locks
+                    rewrite_single_link(&source_url, &source_path, linked_url, target_url, index.as_ref()).await
+                }
+            })
+            .collect();
+
+        let results = futures::future::join_all(update_futures).await;
+
+        for res in results {
+            match res {
+                Ok(_) => result.inbound_updated += 1,
+                // Skipped after cancellation; nothing to report
+                Err(_) if self.cancel.is_cancelled() => {}
+                Err(e) => {
+                    log::warn!("Failed to rewrite inbound link: {e}");
+                    result.inbound_errors.push(e.to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flush pending inbound rewrites once the current window has elapsed.
+    fn schedule_flush(&self) {
+        let rewriter = self.clone();
+        self.flush_tasks.spawn(async move {
+            tokio::select! {
+                () = tokio::time::sleep(rewriter.scheduler.window()) => {}
+                () = rewriter.flush_now.cancelled() => {}
+                () = rewriter.cancel.cancelled() => return,
+            }
+            let result = rewriter.flush_pending().await;
+            if result.inbound_updated > 0 || !result.inbound_errors.is_empty() {
+                log::debug!(
+                    "Batched inbound rewrite: {} pages updated, {} errors",
+                    result.inbound_updated,
+                    result.inbound_errors.len()
+                );
+            }
+        });
+    }
+
+    /// Run the flushes waiting for their batching window now and wait for them.
+    ///
+    /// Call before the final [`Self::flush_pending`] and before closing the
+    /// link store: a flush still writing would otherwise race the final one
+    /// and lose its index updates. Flushes scheduled later run immediately
+    /// and are tracked as well.
+    pub async fn wait_for_flushes(&self) {
+        self.flush_now.cancel();
+        self.flush_tasks.close();
+        self.flush_tasks.wait().await;
+    }
+
+    /// Rewrite all pending inbound links now, each source file once.
+    ///
+    /// Called automatically at the end of every batching window; call it
+    /// directly before reading the output (e.g. at the end of a crawl).
+    pub async fn flush_pending(&self) -> RewriteResult {
+        let mut result = RewriteResult::default();
+        let pending = self.scheduler.take().await;
+        if pending.is_empty() || self.cancel.is_cancelled() {
+            return result;
+        }
+
+        let update_futures: Vec<_> = pending
+            .into_iter()
+            .map(|(source_path, rewrite)| {
+                let sem = self.rewrite_semaphore.clone();
+                let file_locks = self.file_locks.clone();
+                let index = self.index.clone();
+                let cancel = self.cancel.clone();
+
+                async move {
+                    let _permit = sem.acquire().await.map_err(|e| anyhow!("Semaphore error: {}", e))?;
+                    if cancel.is_cancelled() {
+                        bail!("Link rewriting cancelled");
+                    }
+                    let _file_guard = file_locks.lock(&source_path).await;
+                    rewrite_batched_links(&source_path, &rewrite, index.as_ref()).await
+                }
+            })
+            .collect();
+
+        for res in futures::future::join_all(update_futures).await {
+            match res {
+                Ok(_) => result.inbound_updated += 1,
+                // Skipped after cancellation; nothing to report
+                Err(_) if self.cancel.is_cancelled() => {}
+                Err(e) => {
+                    log::warn!("Failed to rewrite inbound links: {e}");
+                    result.inbound_errors.push(e.to_string());
+                }
+            }
+        }
+
+        result
+    }
+
     /// Rewrite all links in a file that point to known local destinations.
     ///
+    /// Also records the positions of the file's links for later inbound
+    /// rewrites.
+    ///
     /// # Arguments
     /// * `page_url` - The URL of the page being rewritten (for resolving relative links)
     /// * `file_path` - Path to the HTML file to rewrite
-    /// * `destinations` - Set of normalized URLs that have local copies
+    /// * `destinations` - Map of normalized linked URL → normalized URL of its local copy
     ///
     /// # Returns
     /// Number of links rewritten
@@ -228,40 +394,44 @@ impl LinkRewriter {
         &self,
         page_url: &str,
         file_path: &Path,
-        destinations: &HashSet<String>,
+        destinations: &HashMap<String, String>,
     ) -> Result<usize> {
         // Build URL → relative path map
         let mut url_to_relative: HashMap<String, String> = HashMap::new();
 
-        for url in destinations {
+        for (url, local_url) in destinations {
             // FIX: Compute dest_path using get_mirror_path() instead of reading from DB
             // This ensures BOTH paths use the SAME function with SAME output_dir, guaranteeing type consistency
-            let dest_path = crate::utils::get_mirror_path(url, &self.output_dir, "index.html").await?;
+            let dest_path = crate::utils::get_mirror_path(local_url, &self.output_dir, "index.html").await?;
             
             if let Some(relative) = compute_relative_path(file_path, &dest_path) {
                 url_to_relative.insert(url.clone(), relative);
             }
         }
 
-        if url_to_relative.is_empty() {
-            return Ok(0);
-        }
-
         // Acquire file lock before any file I/O
-        let file_lock = self.get_file_lock(file_path);
-        let _guard = file_lock.lock().await;
+        let _guard = self.lock_file(file_path).await;
 
         // Read, rewrite, write (now protected by lock)
-        let html = tokio::fs::read_to_string(file_path)
-            .await
-            .context("Failed to read HTML file")?;
-
-        let (rewritten, count) = rewrite_links_in_html(&html, page_url, &url_to_relative)?;
+        let html = match tokio::fs::read_to_string(file_path).await {
+            Ok(html) => html,
+            // Nothing to rewrite; the page just gets no position hints
+            Err(_) if url_to_relative.is_empty() => return Ok(0),
+            Err(e) => return Err(e).context("Failed to read HTML file"),
+        };
+
+        let (rewritten, count) = if url_to_relative.is_empty() {
+            (html, 0)
+        } else {
+            rewrite_links_in_html(&html, page_url, &url_to_relative)?
+        };
+        record_href_positions(self.index.as_ref(), page_url, &rewritten).await;
 
         if count > 0 {
             tokio::fs::write(file_path, rewritten)
                 .await
                 .context("Failed to write rewritten HTML")?;
+            note_output_changed(file_path);
             
             // RESTORE: Markdown link rewriting (broken in original implementation)
             // HTML and markdown share directory: /path/page/index.{html,md}
@@ -291,21 +461,83 @@ impl LinkRewriter {
         Ok(count)
     }
 
-    /// Get reference to the underlying LinkIndex.
-    pub fn index(&self) -> &Arc<LinkIndex> {
+    /// Mirror the assets referenced by a saved HTML page and point the page at them.
+    ///
+    /// Downloads images, scripts, stylesheets and media that are not mirrored
+    /// yet, then rewrites their attributes in the saved file to relative paths.
+    /// Assets that fail to download keep their remote URLs.
+    ///
+    /// The file lock is not held during the downloads, so link rewriting of
+    /// the same page is not stalled behind slow asset hosts; the page is read
+    /// again under the lock before its asset URLs are rewritten.
+    ///
+    /// # Returns
+    /// Number of asset references rewritten
+    pub async fn mirror_assets(
+        &self,
+        page_url: &str,
+        local_path: &Path,
+        user_agent: &str,
+    ) -> Result<usize> {
+        let html = {
+            let _guard = self.lock_file(local_path).await;
+            tokio::fs::read_to_string(local_path)
+                .await
+                .context("Failed to read HTML file")?
+        };
+
+        let mirrored = assets::mirror_asset_files(
+            &guarded_client()?,
+            &url_policy(),
+            &html,
+            page_url,
+            &self.output_dir,
+            user_agent,
+            self.asset_rate_rps,
+        )
+        .await;
+
+        let url_to_relative: HashMap<String, String> = mirrored
+            .into_iter()
+            .filter_map(|(url, path)| compute_relative_path(local_path, &path).map(|rel| (url, rel)))
+            .collect();
+
+        if url_to_relative.is_empty() {
+            return Ok(0);
+        }
+
+        let _guard = self.lock_file(local_path).await;
+        let html = tokio::fs::read_to_string(local_path)
+            .await
+            .context("Failed to read HTML file")?;
+        let (rewritten, count) = rewrite_asset_urls_in_html(&html, page_url, &url_to_relative)?;
+        if count > 0 {
+            tokio::fs::write(local_path, rewritten)
+                .await
+                .context("Failed to write rewritten HTML")?;
+            note_output_changed(local_path);
+        }
+
+        Ok(count)
+    }
+
+    /// Get reference to the underlying link store.
+    pub fn index(&self) -> &Arc<dyn LinkStore> {
         &self.index
     }
 }
 
 /// Rewrite a single link in a source file to point to a newly saved target.
 ///
-/// This is used for retroactive inbound link updates.
+/// This is used for retroactive inbound link updates. `linked_url` is the
+/// URL as linked from the source (the target itself or one of its aliases).
 /// Optimized to avoid HashMap overhead for the common single-link case.
 async fn rewrite_single_link(
     source_url: &str,
     source_path: &Path,
+    linked_url: &str,
     target_url: &str,
-    index: &LinkIndex,
+    index: &dyn LinkStore,
 ) -> Result<()> {
     // Compute target path deterministically from URL for consistency
     let target_path = crate::utils::get_mirror_path(target_url, index.output_dir(), "index.html").await?;
@@ -313,21 +545,37 @@ async fn rewrite_single_link(
     let relative = compute_relative_path(source_path, &target_path)
         .ok_or_else(|| anyhow!("Cannot compute relative path from {:?} to {:?}", source_path, target_path))?;
 
-    let html = tokio::fs::read_to_string(source_path)
-        .await
-        .context("Failed to read source file")?;
-
-    // Pre-normalize target_url ONCE (eliminates redundant normalization)
-    let normalized_target = normalize_url(target_url);
-
-    // Use optimized single-link function - NO HASHMAP ALLOCATION
-    let (rewritten, count) = rewrite_single_link_in_html(&html, source_url, &normalized_target, &relative)?;
+    // Pre-normalize linked_url ONCE (eliminates redundant normalization)
+    let normalized_target = normalize_url(linked_url);
+
+    let patched = patch_recorded_links(
+        source_url,
+        source_path,
+        &HashMap::from([(normalized_target.clone(), relative.clone())]),
+        index,
+    )
+    .await?;
+    let count = match patched {
+        Some(count) => count,
+        None => {
+            let html = tokio::fs::read_to_string(source_path)
+                .await
+                .context("Failed to read source file")?;
+
+            // Use optimized single-link function - NO HASHMAP ALLOCATION
+            let (rewritten, count) = rewrite_single_link_in_html(&html, source_url, &normalized_target, &relative)?;
+            record_href_positions(index, source_url, &rewritten).await;
+            if count > 0 {
+                tokio::fs::write(source_path, rewritten)
+                    .await
+                    .context("Failed to write rewritten source file")?;
+                note_output_changed(source_path);
+            }
+            count
+        }
+    };
 
     if count > 0 {
-        tokio::fs::write(source_path, rewritten)
-            .await
-            .context("Failed to write rewritten source file")?;
-
         // Retroactive markdown inbound link updates (also optimized)
         let md_path = source_path.with_extension("md");
         if tokio::fs::try_exists(&md_path).await.unwrap_or(false) {
@@ -345,6 +593,96 @@ async fn rewrite_single_link(
     Ok(())
 }
 
+/// Rewrite every pending link in one source file in a single pass.
+///
+/// Used by the rewrite scheduler; `rewrite.targets` maps each linked URL to
+/// the URL whose local copy it should point at.
+async fn rewrite_batched_links(
+    source_path: &Path,
+    rewrite: &scheduler::PendingRewrite,
+    index: &dyn LinkStore,
+) -> Result<usize> {
+    let mut url_to_relative: HashMap<String, String> = HashMap::new();
+    for (linked_url, target_url) in &rewrite.targets {
+        let target_path = crate::utils::get_mirror_path(target_url, index.output_dir(), "index.html").await?;
+        if let Some(relative) = compute_relative_path(source_path, &target_path) {
+            url_to_relative.insert(linked_url.clone(), relative);
+        }
+    }
+
+    if url_to_relative.is_empty() {
+        return Ok(0);
+    }
+
+    let count = match patch_recorded_links(&rewrite.source_url, source_path, &url_to_relative, index).await? {
+        Some(count) => count,
+        None => {
+            let html = tokio::fs::read_to_string(source_path)
+                .await
+                .context("Failed to read source file")?;
+
+            let (rewritten, count) = rewrite_links_in_html(&html, &rewrite.source_url, &url_to_relative)?;
+            record_href_positions(index, &rewrite.source_url, &rewritten).await;
+            if count > 0 {
+                tokio::fs::write(source_path, rewritten)
+                    .await
+                    .context("Failed to write rewritten source file")?;
+                note_output_changed(source_path);
+            }
+            count
+        }
+    };
+
+    if count > 0 {
+        let md_path = source_path.with_extension("md");
+        if tokio::fs::try_exists(&md_path).await.unwrap_or(false)
+            && let Err(e) = rewrite_links_in_markdown(&md_path, &url_to_relative).await
+        {
+            log::warn!("Batched markdown link update failed for {:?}: {}", md_path, e);
+        }
+    }
+
+    Ok(count)
+}
+
+/// Rewrite links of a saved page in place at their recorded positions.
+///
+/// Returns the number of links rewritten, or `None` when the store has no
+/// usable positions for the file and the caller must rewrite it in full.
+async fn patch_recorded_links(
+    source_url: &str,
+    source_path: &Path,
+    url_to_relative: &HashMap<String, String>,
+    index: &dyn LinkStore,
+) -> Result<Option<usize>> {
+    let Some(positions) = index.get_link_positions(source_url).await? else {
+        return Ok(None);
+    };
+    let Some(patch) = patch_hrefs(source_path, source_url, &positions, url_to_relative).await? else {
+        return Ok(None);
+    };
+    if patch.count > 0
+        && let Err(e) = index.record_link_patch(source_url, &patch).await
+    {
+        // Stale positions are detected and replaced on the next rewrite
+        log::warn!("Failed to update link positions of {source_url}: {e}");
+    }
+    Ok(Some(patch.count))
+}
+
+/// Record where the links of `html`, just written for `page_url`, sit in the file.
+///
+/// Positions are only hints, so failures are logged and otherwise ignored.
+async fn record_href_positions(index: &dyn LinkStore, page_url: &str, html: &str) {
+    let result = match href_positions(html, page_url) {
+        Ok(positions) => index.set_link_positions(page_url, &positions).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::debug!("Failed to record link positions of {page_url}: {e}");
+    }
+}
+
 /// Compute relative path from source file to destination file.
 ///
 /// Returns None if the path cannot be computed (e.g., different drives on Windows).
@@ -510,18 +848,12 @@ fn html_path_to_markdown(html_path: &str) -> String {
         .replace(".html", ".md")
 }
 
-/// Rewrite markdown links via regex-based pattern matching with URL normalization.
+/// Rewrite markdown links whose destinations have local copies.
 ///
-/// Architecture:
-/// - Reads file line-by-line with O(1) memory footprint
-/// - Uses static compiled regexes for pattern matching
-/// - Normalizes URLs before lookup (strips fragments, normalizes case)
-/// - Atomic file replacement via POSIX rename(2) syscall
-///
-/// Handles all markdown link patterns:
-/// - Inline links: `](url)`, `](url "title")`, `](<url>)`
-/// - Reference definitions: `[ref]: url`, `[ref]: url "title"`
-/// - Autolinks: `<https://example.com>`
+/// Parses the file with pulldown-cmark (see `markdown`) and replaces only
+/// link destinations, so code spans, multi-line titles and images are
+/// handled correctly. URLs are normalized before lookup (fragments stripped).
+/// The file is replaced atomically via rename(2).
 ///
 /// # Arguments
 /// * `file_path` - Path to markdown file requiring link rewriting
@@ -533,8 +865,6 @@ async fn rewrite_links_in_markdown(
     file_path: &Path,
     url_to_relative: &HashMap<String, String>,
 ) -> Result<usize> {
-    use tokio::fs::File;
-    
     // Pre-compute normalized lookup map: normalize URLs + convert HTML paths to markdown
     let normalized_map: HashMap<String, String> = url_to_relative
         .iter()
@@ -542,96 +872,18 @@ async fn rewrite_links_in_markdown(
             (normalize_url_for_lookup(url), html_path_to_markdown(html_path))
         })
         .collect();
-    
+
     if normalized_map.is_empty() {
         return Ok(0);
     }
-    
-    // Open input file for streaming read
-    let input_file = File::open(file_path).await
-        .context("Failed to open markdown file for reading")?;
-    let reader = BufReader::new(input_file);
-    
-    // Create temporary output file for atomic replacement
-    let temp_path = file_path.with_extension("md.tmp");
-    let output_file = File::create(&temp_path).await
-        .context("Failed to create temporary markdown file")?;
-    let mut writer = BufWriter::new(output_file);
-    
-    let mut rewrite_count = 0;
-    let mut lines = reader.lines();
-    
-    // Stream through file one line at a time (O(1) memory)
-    while let Some(line_result) = lines.next_line().await? {
-        let mut line = line_result;
-        
-        // Pattern 1: Inline links ](url) or ](url "title") or ](<url>)
-        let mut inline_count = 0;
-        line = INLINE_LINK_RE.replace_all(&line, |caps: &regex::Captures| {
-            let url = &caps[1];
-            let normalized = normalize_url_for_lookup(url);
-            if let Some(md_relative) = normalized_map.get(&normalized) {
-                inline_count += 1;
-                format!("]({})", md_relative)
-            } else {
-                caps[0].to_string()
-            }
-        }).to_string();
-        rewrite_count += inline_count;
-        
-        // Pattern 2: Reference definitions [ref]: url (only at start of line)
-        if let Some(caps) = REFERENCE_DEF_RE.captures(&line) {
-            let ref_label = &caps[1];
-            let url = &caps[2];
-            let normalized = normalize_url_for_lookup(url);
-            if let Some(md_relative) = normalized_map.get(&normalized) {
-                rewrite_count += 1;
-                line = format!("[{}]: {}", ref_label, md_relative);
-            }
-        }
-        
-        // Pattern 3: Autolinks <https://...>
-        let mut autolink_count = 0;
-        line = AUTOLINK_RE.replace_all(&line, |caps: &regex::Captures| {
-            let url = &caps[1];
-            let normalized = normalize_url_for_lookup(url);
-            if let Some(md_relative) = normalized_map.get(&normalized) {
-                autolink_count += 1;
-                // Autolinks become regular markdown links when rewritten to local paths
-                // Use filename as link text for readability
-                let link_text = md_relative.rsplit('/').next().unwrap_or(md_relative);
-                format!("[{}]({})", link_text, md_relative)
-            } else {
-                caps[0].to_string()
-            }
-        }).to_string();
-        rewrite_count += autolink_count;
-        
-        // Write rewritten line to temporary file
-        writer.write_all(line.as_bytes()).await?;
-        writer.write_all(b"\n").await?;
-    }
-    
-    // Flush buffer to ensure all data written
-    writer.flush().await?;
-    drop(writer);  // Explicit close before rename
-    
-    // Atomic file replacement (crash-safe)
-    if rewrite_count > 0 {
-        tokio::fs::rename(&temp_path, file_path).await
-            .context("Failed to atomically replace markdown file")?;
-    } else {
-        // No rewrites performed - cleanup temporary file
-        let _ = tokio::fs::remove_file(&temp_path).await;
-    }
-    
-    Ok(rewrite_count)
+
+    rewrite_markdown_file(file_path, |url| {
+        normalized_map.get(&normalize_url_for_lookup(url)).cloned()
+    })
+    .await
 }
 
-/// Optimized single-link markdown rewriting without HashMap overhead.
-///
-/// For retroactive inbound link updates where exactly one link needs rewriting,
-/// this avoids HashMap allocation and iteration overhead.
+/// Single-link markdown rewriting for retroactive inbound link updates.
 ///
 /// # Arguments
 /// * `file_path` - Path to markdown file requiring link rewriting
@@ -645,75 +897,110 @@ async fn rewrite_single_link_in_markdown(
     target_url: &str,
     html_relative: &str,
 ) -> Result<usize> {
-    use tokio::fs::File;
+    let md_relative = html_path_to_markdown(html_relative);
+    let target = normalize_url_for_lookup(target_url);
 
-    // Transform HTML path to markdown path once (not per-URL like HashMap version)
-    let md_relative = html_relative
-        .replace("/index.html", "/index.md")
-        .replace(".html", ".md");
+    rewrite_markdown_file(file_path, |url| {
+        (normalize_url_for_lookup(url) == target).then(|| md_relative.clone())
+    })
+    .await
+}
 
-    // Open input file for streaming read
-    let input_file = File::open(file_path)
+/// Apply `rewrite_markdown_links` to a file, replacing it atomically when changed.
+async fn rewrite_markdown_file<F>(file_path: &Path, resolve: F) -> Result<usize>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let markdown = tokio::fs::read_to_string(file_path)
         .await
-        .context("Failed to open markdown file for reading")?;
-    let reader = BufReader::new(input_file);
+        .context("Failed to read markdown file")?;
 
-    // Create temporary output file for atomic replacement
+    let (rewritten, count) = rewrite_markdown_links(&markdown, resolve);
+    if count == 0 {
+        return Ok(0);
+    }
+
+    // Write to a temporary file and rename for crash-safe replacement
     let temp_path = file_path.with_extension("md.tmp");
-    let output_file = File::create(&temp_path)
+    tokio::fs::write(&temp_path, rewritten)
         .await
-        .context("Failed to create temporary markdown file")?;
-    let mut writer = BufWriter::new(output_file);
-
-    let mut rewrite_count = 0;
-    let mut lines = reader.lines();
-
-    // Build patterns once (not per-line like HashMap iteration)
-    let pattern = format!("]({})", target_url);
-    let replacement = format!("]({})", md_relative);
-    let pattern_space = format!("]({} ", target_url);
-    let replacement_space = format!("]({} ", md_relative);
-
-    // Stream through file one line at a time (O(1) memory)
-    while let Some(line_result) = lines.next_line().await? {
-        let mut line = line_result;
-
-        // Direct pattern matching - no HashMap iteration
-        if line.contains(&pattern) {
-            line = line.replace(&pattern, &replacement);
-            rewrite_count += 1;
-        }
-        if line.contains(&pattern_space) {
-            line = line.replace(&pattern_space, &replacement_space);
-        }
+        .context("Failed to write temporary markdown file")?;
+    tokio::fs::rename(&temp_path, file_path)
+        .await
+        .context("Failed to atomically replace markdown file")?;
+    note_output_changed(file_path);
 
-        writer.write_all(line.as_bytes()).await?;
-        writer.write_all(b"\n").await?;
-    }
+    Ok(count)
+}
 
-    // Flush buffer to ensure all data written
-    writer.flush().await?;
-    drop(writer); // Explicit close before rename
+/// `<a href>` selector, compiled once rather than for every page
+static ANCHOR_SELECTOR: LazyLock<scraper::Selector> = LazyLock::new(|| {
+    scraper::Selector::parse("a[href]").expect("hardcoded selector 'a[href]' is valid")
+});
 
-    // Atomic file replacement (crash-safe)
-    if rewrite_count > 0 {
-        tokio::fs::rename(&temp_path, file_path)
-            .await
-            .context("Failed to atomically replace markdown file")?;
+/// Resolve an `href` to an absolute HTTP(S) URL, or `None` if it is not a page link.
+///
+/// - Empty and fragment-only hrefs are skipped.
+/// - Protocol-relative hrefs (`//host/path`) take the page's scheme.
+/// - Any other explicit scheme (`data:`, `blob:`, `javascript:`, `mailto:`,
+///   `tel:`, `ftp:`, ...) is skipped unless it is `http` or `https`; schemes
+///   are matched case-insensitively.
+/// - Everything else is resolved against `base`.
+///
+/// The fragment is dropped (`page#a` and `page#b` are one link). Hrefs that
+/// do not parse are skipped, so only URLs with a host ever reach the link
+/// index or `get_mirror_path`.
+fn resolve_link_href(base: &url::Url, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') {
+        return None;
+    }
+
+    let resolved = if let Some(rest) = href.strip_prefix("//") {
+        url::Url::parse(&format!("{}://{rest}", base.scheme())).ok()?
+    } else if let Some(scheme) = href_scheme(href) {
+        if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+            return None;
+        }
+        url::Url::parse(href).ok()?
     } else {
-        // No rewrites performed - cleanup temporary file
-        let _ = tokio::fs::remove_file(&temp_path).await;
+        base.join(href).ok()?
+    };
+
+    let is_page = matches!(resolved.scheme(), "http" | "https") && resolved.host_str().is_some();
+    if !is_page {
+        return None;
     }
+    let page = ImUrl::parse(resolved.as_str()).and_then(|url| url.without_fragment()).ok()?;
+    Some(page.as_str().to_string())
+}
 
-    Ok(rewrite_count)
+/// The scheme of `href` if it starts with one (RFC 3986: `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"`)
+fn href_scheme(href: &str) -> Option<&str> {
+    let (scheme, _) = href.split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some(scheme)
 }
 
 /// Extract all HTTP/HTTPS links from HTML.
 ///
-/// This is used to find outbound links before calling on_page_saved.
 /// Only extracts links from <a href="..."> tags.
 pub fn extract_links_from_html(html: &str, base_url: &str) -> Vec<String> {
-    let mut links = Vec::new();
+    extract_links_with_text_from_html(html, base_url)
+        .into_iter()
+        .map(|link| link.url)
+        .collect()
+}
+
+/// Extract all HTTP/HTTPS links from HTML together with their anchor text.
+///
+/// This is used to find outbound links before calling on_page_saved.
+/// Anchor text is whitespace-collapsed, falling back to the `title` or
+/// `aria-label` attribute; duplicate targets keep the first non-empty text.
+pub fn extract_links_with_text_from_html(html: &str, base_url: &str) -> Vec<OutboundLink> {
+    let mut links: Vec<OutboundLink> = Vec::new();
 
     // Parse base URL for resolving relative links
     let base = match url::Url::parse(base_url) {
@@ -723,44 +1010,46 @@ pub fn extract_links_from_html(html: &str, base_url: &str) -> Vec<String> {
 
     // Use scraper for extraction (simpler than lol_html for read-only)
     let document = scraper::Html::parse_document(html);
-    let selector = scraper::Selector::parse("a[href]").unwrap();
-
-    for element in document.select(&selector) {
+    for element in document.select(&ANCHOR_SELECTOR) {
         if let Some(href) = element.value().attr("href") {
-            // Skip empty, javascript:, mailto:, tel:, and fragment-only links
-            let href = href.trim();
-            if href.is_empty()
-                || href.starts_with("javascript:")
-                || href.starts_with("mailto:")
-                || href.starts_with("tel:")
-                || href.starts_with('#')
-            {
+            let Some(url) = resolve_link_href(&base, href) else {
                 continue;
-            }
+            };
+
+            let text = element.text().collect::<Vec<_>>().join(" ");
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let anchor_text = Some(text)
+                .filter(|t| !t.is_empty())
+                .or_else(|| {
+                    ["title", "aria-label"]
+                        .iter()
+                        .find_map(|attr| element.value().attr(attr))
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                });
+
+            links.push(OutboundLink { url, anchor_text });
+        }
+    }
 
-            // Resolve relative URLs against base
-            match base.join(href) {
-                Ok(resolved) => {
-                    // Only include http/https links
-                    if resolved.scheme() == "http" || resolved.scheme() == "https" {
-                        links.push(resolved.to_string());
-                    }
-                }
-                Err(_) => {
-                    // If it looks like an absolute HTTP URL, include it directly
-                    if href.starts_with("http://") || href.starts_with("https://") {
-                        links.push(href.to_string());
-                    }
+    // Deduplicate while preserving order, keeping the first non-empty anchor text
+    let mut position: HashMap<String, usize> = HashMap::new();
+    let mut deduped: Vec<OutboundLink> = Vec::with_capacity(links.len());
+    for link in links {
+        match position.get(&link.url) {
+            Some(&i) => {
+                if deduped[i].anchor_text.is_none() {
+                    deduped[i].anchor_text = link.anchor_text;
                 }
             }
+            None => {
+                position.insert(link.url.clone(), deduped.len());
+                deduped.push(link);
+            }
         }
     }
 
-    // Deduplicate while preserving order
-    let mut seen = HashSet::new();
-    links.retain(|url| seen.insert(url.clone()));
-
-    links
+    deduped
 }
 
 #[cfg(test)]
@@ -857,6 +1146,61 @@ mod tests {
         assert!(links.contains(&"https://example.com/docs/sibling.html".to_string()));
     }
 
+    #[test]
+    fn test_extract_links_skips_non_page_schemes() {
+        let html = r#"
+            <a href="data:text/html;base64,PGgxPkhpPC9oMT4=">Data</a>
+            <a href="blob:https://example.com/0b8e6c1d-4b3a-4d7e-9a44-6b1b3d3f2a10">Blob</a>
+            <a href="JavaScript:alert(1)">Upper-case JS</a>
+            <a href="ftp://files.example.com/archive.zip">FTP</a>
+            <a href="about:blank">About</a>
+            <a href="chrome-extension://abcdef/options.html">Extension</a>
+            <a href="HTTPS://Example.com/Upper">Upper-case scheme</a>
+            <a href="  /padded  ">Padded</a>
+        "#;
+
+        let links = extract_links_from_html(html, "https://example.com/docs/");
+
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/Upper".to_string(),
+                "https://example.com/padded".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_links_protocol_relative_uses_page_scheme() {
+        let html = r#"<a href="//cdn.example.org/guide/">CDN</a>"#;
+
+        assert_eq!(
+            extract_links_from_html(html, "https://example.com/"),
+            vec!["https://cdn.example.org/guide/".to_string()]
+        );
+        assert_eq!(
+            extract_links_from_html(html, "http://example.com/"),
+            vec!["http://cdn.example.org/guide/".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_extract_links_with_text() {
+        let html = r#"
+            <a href="/guide"><img src="icon.png"></a>
+            <a href="/guide">  Read the
+                <b>guide</b> </a>
+            <a href="/api" title="API reference"></a>
+        "#;
+
+        let links = extract_links_with_text_from_html(html, "https://example.com/");
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].url, "https://example.com/guide");
+        assert_eq!(links[0].anchor_text.as_deref(), Some("Read the guide"));
+        assert_eq!(links[1].anchor_text.as_deref(), Some("API reference"));
+    }
+
     #[test]
     fn test_extract_links_deduplicates() {
         let html = r#"
@@ -870,6 +1214,19 @@ mod tests {
         assert_eq!(links.len(), 2);
     }
 
+    #[test]
+    fn test_extract_links_drops_fragments() {
+        let html = r##"
+            <a href="/guide#install">Install</a>
+            <a href="/guide#usage">Usage</a>
+            <a href="#top">Top</a>
+        "##;
+
+        let links = extract_links_from_html(html, "https://example.com/");
+
+        assert_eq!(links, vec!["https://example.com/guide".to_string()]);
+    }
+
     #[test]
     fn test_url_normalization_in_rewrite() {
         let html = r#"<a href="HTTPS://Example.COM/Page/">Link</a>"#;
@@ -884,4 +1241,152 @@ mod tests {
         assert_eq!(count, 1);
         assert!(rewritten.contains(r#"href="page.html""#));
     }
+
+    #[tokio::test]
+    async fn test_alias_links_rewritten_to_final_copy() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let index = crate::link_index::LinkIndex::open(temp_dir.path()).await?;
+        let rewriter = LinkRewriter::new(Arc::new(index), temp_dir.path().to_path_buf());
+
+        async fn save(rewriter: &LinkRewriter, out: &Path, url: &str, html: &str) -> Result<PathBuf> {
+            let path = crate::utils::get_mirror_path(url, out, "index.html").await?;
+            tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+            tokio::fs::write(&path, html).await?;
+            rewriter
+                .on_page_saved(url, &path, extract_links_with_text_from_html(html, url))
+                .await?;
+            Ok(path)
+        }
+
+        // Same order as the crawl: a page linking to the final URL, then the
+        // requested URL saved and the URL it redirected to recorded as alias
+        let source_path = save(&rewriter, temp_dir.path(), "https://example.com/a", r#"<a href="/new">New</a>"#).await?;
+        save(&rewriter, temp_dir.path(), "https://example.com/old", "<p>moved</p>").await?;
+
+        let updated = rewriter
+            .on_alias_discovered("https://example.com/new", "https://example.com/old", AliasKind::Redirect)
+            .await?;
+        assert_eq!(updated, 1);
+        assert_eq!(rewriter.flush_pending().await.inbound_updated, 1);
+        let html = tokio::fs::read_to_string(&source_path).await?;
+        assert!(html.contains(r#"href="../old/index.html""#), "{html}");
+
+        // Pages saved later resolve the alias directly
+        let later = save(&rewriter, temp_dir.path(), "https://example.com/b", r#"<a href="/new">New</a>"#).await?;
+        let html = tokio::fs::read_to_string(&later).await?;
+        assert!(html.contains(r#"href="../old/index.html""#), "{html}");
+
+        // Once the final URL is crawled itself, its own copy wins
+        save(&rewriter, temp_dir.path(), "https://example.com/new", "<p>new</p>").await?;
+        let last = save(&rewriter, temp_dir.path(), "https://example.com/c", r#"<a href="/new">New</a>"#).await?;
+        let html = tokio::fs::read_to_string(&last).await?;
+        assert!(html.contains(r#"href="../new/index.html""#), "{html}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inbound_rewrites_patch_recorded_positions() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let index = crate::link_index::LinkIndex::open(temp_dir.path()).await?;
+        let rewriter = LinkRewriter::new(Arc::new(index), temp_dir.path().to_path_buf())
+            .with_rewrite_window(std::time::Duration::ZERO);
+
+        let nav_url = "https://example.com/";
+        let nav_html = r#"<a href="https://example.com/guide/">Guide</a><a href="https://example.com/api/">API</a>"#;
+        let nav_path = crate::utils::get_mirror_path(nav_url, temp_dir.path(), "index.html").await?;
+        tokio::fs::create_dir_all(nav_path.parent().unwrap()).await?;
+        tokio::fs::write(&nav_path, nav_html).await?;
+        rewriter
+            .on_page_saved(nav_url, &nav_path, extract_links_with_text_from_html(nav_html, nav_url))
+            .await?;
+
+        for page in ["guide", "api"] {
+            let url = format!("https://example.com/{page}/");
+            let path = crate::utils::get_mirror_path(&url, temp_dir.path(), "index.html").await?;
+            tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+            tokio::fs::write(&path, "<p>page</p>").await?;
+            let result = rewriter.on_page_saved(&url, &path, Vec::new()).await?;
+            assert_eq!(result.inbound_updated, 1);
+
+            // Shorter relative paths are written over the absolute URLs
+            let html = tokio::fs::read_to_string(&nav_path).await?;
+            assert_eq!(html.len(), nav_html.len());
+            assert!(html.contains(&format!(r#"href="{page}/index.html""#)), "{html}");
+        }
+
+        let positions = rewriter.index().get_link_positions(nav_url).await?.unwrap();
+        assert_eq!(positions.file_len, nav_html.len() as u64);
+        assert!(positions.hrefs.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_inbound_rewrites_coalesced_per_file() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let index = crate::link_index::LinkIndex::open(temp_dir.path()).await?;
+        let rewriter = LinkRewriter::new(Arc::new(index), temp_dir.path().to_path_buf())
+            .with_rewrite_window(std::time::Duration::from_secs(3600));
+
+        let nav_url = "https://example.com/";
+        let nav_html = r#"<a href="/a">A</a><a href="/b">B</a><a href="/c">C</a>"#;
+        let nav_path = crate::utils::get_mirror_path(nav_url, temp_dir.path(), "index.html").await?;
+        tokio::fs::create_dir_all(nav_path.parent().unwrap()).await?;
+        tokio::fs::write(&nav_path, nav_html).await?;
+        rewriter
+            .on_page_saved(nav_url, &nav_path, extract_links_with_text_from_html(nav_html, nav_url))
+            .await?;
+
+        for page in ["a", "b", "c"] {
+            let url = format!("https://example.com/{page}");
+            let path = crate::utils::get_mirror_path(&url, temp_dir.path(), "index.html").await?;
+            tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+            tokio::fs::write(&path, "<p>page</p>").await?;
+            let result = rewriter.on_page_saved(&url, &path, Vec::new()).await?;
+            assert_eq!((result.inbound_updated, result.inbound_scheduled), (0, 1));
+        }
+
+        // Nothing written until the flush, then the nav page is rewritten once
+        assert_eq!(tokio::fs::read_to_string(&nav_path).await?, nav_html);
+        let flushed = rewriter.flush_pending().await;
+        assert_eq!(flushed.inbound_updated, 1);
+
+        let html = tokio::fs::read_to_string(&nav_path).await?;
+        for page in ["a", "b", "c"] {
+            assert!(html.contains(&format!(r#"href="{page}/index.html""#)), "{html}");
+        }
+
+        // No per-file lock entries outlive the rewrites
+        assert_eq!(rewriter.locked_file_count(), 0);
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_wait_for_flushes_runs_waiting_flush() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let index = crate::link_index::LinkIndex::open(temp_dir.path()).await?;
+        let rewriter = LinkRewriter::new(Arc::new(index), temp_dir.path().to_path_buf())
+            .with_rewrite_window(std::time::Duration::from_secs(3600));
+
+        let nav_url = "https://example.com/";
+        let nav_html = r#"<a href="/a">A</a>"#;
+        let nav_path = crate::utils::get_mirror_path(nav_url, temp_dir.path(), "index.html").await?;
+        tokio::fs::create_dir_all(nav_path.parent().unwrap()).await?;
+        tokio::fs::write(&nav_path, nav_html).await?;
+        rewriter
+            .on_page_saved(nav_url, &nav_path, extract_links_with_text_from_html(nav_html, nav_url))
+            .await?;
+
+        let url = "https://example.com/a";
+        let path = crate::utils::get_mirror_path(url, temp_dir.path(), "index.html").await?;
+        tokio::fs::create_dir_all(path.parent().unwrap()).await?;
+        tokio::fs::write(&path, "<p>page</p>").await?;
+        assert_eq!(rewriter.on_page_saved(url, &path, Vec::new()).await?.inbound_scheduled, 1);
+
+        // The scheduled flush runs without waiting out the window
+        tokio::time::timeout(std::time::Duration::from_secs(10), rewriter.wait_for_flushes()).await?;
+        let html = tokio::fs::read_to_string(&nav_path).await?;
+        assert!(html.contains(r#"href="a/index.html""#), "{html}");
+        assert_eq!(rewriter.flush_pending().await.inbound_updated, 0);
+        Ok(())
+    }
 }
@@ -0,0 +1,279 @@
+//! In-place href rewrites using recorded link positions.
+//!
+//! When a page is saved, `href_positions` records where each `<a href>`
+//! value sits in its HTML file (stored through `LinkStore`). A later inbound
+//! rewrite then reads back and overwrites only those bytes with
+//! `patch_hrefs`, instead of parsing and re-writing the whole file.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::SeekFrom;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use lol_html::{HtmlRewriter, Settings, element};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use url::Url;
+
+use crate::link_index::{HrefPatch, HrefPosition, LinkPositions, normalize_url};
+
+/// Normalized URL an href points at, resolved like the full-file rewrite does.
+fn resolve_href(href: &str, base: Option<&Url>) -> String {
+    let absolute = base
+        .and_then(|base| base.join(href).ok())
+        .map_or_else(|| href.to_string(), String::from);
+    normalize_url(&absolute)
+}
+
+/// Whether `value` can be written as an attribute value without quoting or escaping.
+fn is_plain_value(value: &str) -> bool {
+    !value.is_empty()
+        && !value
+            .bytes()
+            .any(|b| b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b'&' | b'<' | b'>' | b'`' | b'='))
+}
+
+/// Record the byte range of every `<a href>` value in `html`.
+pub fn href_positions(html: &str, base_url: &str) -> Result<LinkPositions> {
+    let base = Url::parse(base_url).ok();
+    let mut hrefs = Vec::new();
+
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![element!("a[href]", |el| {
+                // Browsers use the first of duplicate attributes, as does `get_attribute`
+                let Some(attr) = el.attributes().iter().find(|attr| attr.name() == "href") else {
+                    return Ok(());
+                };
+                if let Some(location) = attr.value_source_location() {
+                    let range = location.bytes();
+                    hrefs.push(HrefPosition {
+                        target_url: resolve_href(&attr.value(), base.as_ref()),
+                        start: range.start as u64,
+                        len: range.len() as u64,
+                    });
+                }
+                Ok(())
+            })],
+            ..Settings::default()
+        },
+        |_: &[u8]| {},
+    );
+    rewriter
+        .write(html.as_bytes())
+        .map_err(|e| anyhow!("HTML parse error: {}", e))?;
+    rewriter
+        .end()
+        .map_err(|e| anyhow!("HTML parse finalization error: {}", e))?;
+
+    Ok(LinkPositions { file_len: html.len() as u64, hrefs })
+}
+
+/// One href to rewrite, as found in the file.
+struct Edit<'a> {
+    start: u64,
+    len: u64,
+    /// Quote around the value, if it is quoted
+    quote: Option<u8>,
+    target_url: &'a str,
+    replacement: &'a str,
+}
+
+/// Point the hrefs in `positions` that target a key of `url_to_relative` at its value.
+///
+/// Every value is read back and checked against its recorded target first.
+/// Returns `None` when the file no longer matches `positions` (it changed
+/// since they were recorded) or a replacement would need escaping; the
+/// caller then rewrites the whole file.
+///
+/// Replacements that fit are written over the old value, padding the tag
+/// with spaces after it; otherwise the file is rewritten from the first
+/// changed value on.
+pub async fn patch_hrefs(
+    path: &Path,
+    base_url: &str,
+    positions: &LinkPositions,
+    url_to_relative: &HashMap<String, String>,
+) -> Result<Option<HrefPatch>> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .await
+        .context("Failed to open HTML file")?;
+    let file_len = file.metadata().await.context("Failed to stat HTML file")?.len();
+    if file_len != positions.file_len {
+        return Ok(None);
+    }
+
+    let base = Url::parse(base_url).ok();
+    let mut edits = Vec::new();
+    for href in &positions.hrefs {
+        let Some(replacement) = url_to_relative.get(&href.target_url) else {
+            continue;
+        };
+        if !is_plain_value(replacement) || href.start == 0 || href.start + href.len >= file_len {
+            return Ok(None);
+        }
+
+        // The value with the byte before (`=` or a quote) and after it
+        let mut raw = vec![0; usize::try_from(href.len)? + 2];
+        file.seek(SeekFrom::Start(href.start - 1)).await?;
+        file.read_exact(&mut raw).await.context("Failed to read href")?;
+        let (before, after) = (raw[0], raw[raw.len() - 1]);
+        let Ok(value) = std::str::from_utf8(&raw[1..raw.len() - 1]) else {
+            return Ok(None);
+        };
+        if resolve_href(value, base.as_ref()) != href.target_url {
+            return Ok(None);
+        }
+
+        edits.push(Edit {
+            start: href.start,
+            len: href.len,
+            quote: (matches!(before, b'"' | b'\'') && after == before).then_some(before),
+            target_url: &href.target_url,
+            replacement,
+        });
+    }
+    edits.sort_by_key(|edit| edit.start);
+
+    let targets: BTreeSet<&str> = edits.iter().map(|edit| edit.target_url).collect();
+    let mut patch = HrefPatch {
+        count: edits.len(),
+        targets: targets.into_iter().map(String::from).collect(),
+        shifts: Vec::new(),
+        file_len,
+    };
+    let Some(first) = edits.first() else {
+        return Ok(Some(patch));
+    };
+
+    if edits.iter().all(|edit| edit.replacement.len() as u64 <= edit.len) {
+        for edit in &edits {
+            // Move the closing quote up and pad between attributes
+            let width = edit.len as usize + usize::from(edit.quote.is_some());
+            let mut bytes = Vec::with_capacity(width);
+            bytes.extend_from_slice(edit.replacement.as_bytes());
+            bytes.extend(edit.quote);
+            bytes.resize(width, b' ');
+            file.seek(SeekFrom::Start(edit.start)).await?;
+            file.write_all(&bytes).await.context("Failed to write href")?;
+        }
+    } else {
+        let from = first.start;
+        let mut tail = Vec::with_capacity(usize::try_from(file_len - from)?);
+        file.seek(SeekFrom::Start(from)).await?;
+        file.read_to_end(&mut tail).await.context("Failed to read HTML file")?;
+
+        let mut patched = Vec::with_capacity(tail.len() + edits.len() * first.replacement.len());
+        let mut cursor = 0;
+        for edit in &edits {
+            let start = (edit.start - from) as usize;
+            patched.extend_from_slice(&tail[cursor..start]);
+            patched.extend_from_slice(edit.replacement.as_bytes());
+            cursor = start + edit.len as usize;
+            let delta = edit.replacement.len() as i64 - edit.len as i64;
+            if delta != 0 {
+                patch.shifts.push((edit.start, delta));
+            }
+        }
+        patched.extend_from_slice(&tail[cursor..]);
+
+        file.seek(SeekFrom::Start(from)).await?;
+        file.write_all(&patched).await.context("Failed to write HTML file")?;
+        patch.file_len = from + patched.len() as u64;
+        file.set_len(patch.file_len).await.context("Failed to truncate HTML file")?;
+    }
+    file.flush().await.context("Failed to flush HTML file")?;
+
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://example.com/docs/";
+
+    async fn patch(html: &str, replacements: &[(&str, &str)]) -> Result<(Option<HrefPatch>, String)> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("index.html");
+        tokio::fs::write(&path, html).await?;
+        let positions = href_positions(html, BASE)?;
+        let url_to_relative = replacements
+            .iter()
+            .map(|(url, relative)| (normalize_url(url), relative.to_string()))
+            .collect();
+        let patch = patch_hrefs(&path, BASE, &positions, &url_to_relative).await?;
+        Ok((patch, tokio::fs::read_to_string(&path).await?))
+    }
+
+    #[test]
+    fn test_href_positions_cover_values() -> Result<()> {
+        let html = r#"<p><a class=x href="/a">A</a> <a href='b#top'>B</a> <a HREF=https://other.org/c>C</a></p>"#;
+        let positions = href_positions(html, BASE)?;
+        assert_eq!(positions.file_len, html.len() as u64);
+
+        let found: Vec<(&str, &str)> = positions
+            .hrefs
+            .iter()
+            .map(|h| (h.target_url.as_str(), &html[h.start as usize..(h.start + h.len) as usize]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (normalize_url("https://example.com/a").as_str(), "/a"),
+                (normalize_url("https://example.com/docs/b#top").as_str(), "b#top"),
+                (normalize_url("https://other.org/c").as_str(), "https://other.org/c"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_patch_in_place_pads_after_value() -> Result<()> {
+        let html = r#"<a href="https://example.com/guide">G</a><a href=https://example.com/guide>G</a><a href="/api">A</a>"#;
+        let (patch, patched) = patch(html, &[("https://example.com/guide", "../guide/index.html")]).await?;
+        let patch = patch.expect("positions match the file");
+
+        assert_eq!(patch.count, 2);
+        assert!(patch.shifts.is_empty());
+        assert_eq!(patch.file_len, html.len() as u64);
+        let pad = " ".repeat(6);
+        assert_eq!(
+            patched,
+            format!(r#"<a href="../guide/index.html"{pad}>G</a><a href=../guide/index.html{pad}>G</a><a href="/api">A</a>"#)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_patch_longer_value_rewrites_tail() -> Result<()> {
+        let html = r#"<a href="/a">A</a><a href="/b">B</a>"#;
+        let (patch, patched) = patch(html, &[("https://example.com/a", "../a/index.html")]).await?;
+        let patch = patch.expect("positions match the file");
+
+        assert_eq!(patched, r#"<a href="../a/index.html">A</a><a href="/b">B</a>"#);
+        assert_eq!(patch.shifts, [(9, 13)]);
+        assert_eq!(patch.file_len, patched.len() as u64);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_patch_rejects_changed_file() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join("index.html");
+        let html = r#"<a href="/a">A</a>"#;
+        let positions = href_positions(html, BASE)?;
+        let url_to_relative = HashMap::from([(normalize_url("https://example.com/a"), "a.html".to_string())]);
+
+        // Same length, different link
+        tokio::fs::write(&path, r#"<a href="/z">Z</a>"#).await?;
+        assert_eq!(patch_hrefs(&path, BASE, &positions, &url_to_relative).await?, None);
+
+        // Different length
+        tokio::fs::write(&path, r#"<p><a href="/a">A</a>"#).await?;
+        assert_eq!(patch_hrefs(&path, BASE, &positions, &url_to_relative).await?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! Coalescing of retroactive inbound link rewrites.
+//!
+//! When many pages are saved in quick succession, each one triggers a rewrite
+//! of every page linking to it, so popular source pages (navigation, indexes)
+//! are reopened and rewritten over and over. The scheduler collects pending
+//! `linked URL → target URL` rewrites per source file during a short window
+//! and lets `LinkRewriter` rewrite each file once per window.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+
+/// Default batching window for inbound rewrites.
+pub const DEFAULT_REWRITE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Rewrites waiting for one source file.
+#[derive(Debug, Clone, Default)]
+pub struct PendingRewrite {
+    /// URL of the source page (base for resolving its relative links)
+    pub source_url: String,
+    /// Normalized URL as linked from the source → URL of the local copy
+    pub targets: HashMap<String, String>,
+}
+
+/// Pending inbound rewrites grouped by source file.
+#[derive(Debug)]
+pub struct RewriteScheduler {
+    window: Duration,
+    pending: Mutex<HashMap<PathBuf, PendingRewrite>>,
+    /// Set while a flush is scheduled for the current window
+    flush_scheduled: AtomicBool,
+}
+
+impl RewriteScheduler {
+    /// Create a scheduler; a zero window disables batching.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Mutex::new(HashMap::new()),
+            flush_scheduled: AtomicBool::new(false),
+        }
+    }
+
+    /// Batching window (zero when rewrites run immediately).
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Whether rewrites are batched at all.
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Queue a rewrite of links to `linked_url` in `source_path`.
+    ///
+    /// Returns `true` if the caller should schedule a flush after the window
+    /// (i.e. no flush was pending yet).
+    pub async fn enqueue(
+        &self,
+        source_path: PathBuf,
+        source_url: &str,
+        linked_url: &str,
+        target_url: &str,
+    ) -> bool {
+        {
+            let mut pending = self.pending.lock().await;
+            let entry = pending.entry(source_path).or_insert_with(|| PendingRewrite {
+                source_url: source_url.to_string(),
+                targets: HashMap::new(),
+            });
+            entry
+                .targets
+                .insert(linked_url.to_string(), target_url.to_string());
+        }
+        !self.flush_scheduled.swap(true, Ordering::AcqRel)
+    }
+
+    /// Take all pending rewrites, opening a new window for later enqueues.
+    pub async fn take(&self) -> HashMap<PathBuf, PendingRewrite> {
+        self.flush_scheduled.store(false, Ordering::Release);
+        std::mem::take(&mut *self.pending.lock().await)
+    }
+
+    /// Number of source files with pending rewrites.
+    pub async fn pending_files(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enqueue_coalesces_per_file() {
+        let scheduler = RewriteScheduler::new(DEFAULT_REWRITE_WINDOW);
+        let nav = PathBuf::from("/out/example.com/index.html");
+
+        assert!(scheduler.enqueue(nav.clone(), "https://example.com/", "https://example.com/a", "https://example.com/a").await);
+        assert!(!scheduler.enqueue(nav.clone(), "https://example.com/", "https://example.com/b", "https://example.com/b").await);
+        assert!(!scheduler.enqueue(PathBuf::from("/out/other.html"), "https://example.com/o", "https://example.com/a", "https://example.com/a").await);
+        assert_eq!(scheduler.pending_files().await, 2);
+
+        let batch = scheduler.take().await;
+        assert_eq!(batch[&nav].targets.len(), 2);
+        assert_eq!(scheduler.pending_files().await, 0);
+
+        // A new window starts after take()
+        assert!(scheduler.enqueue(nav, "https://example.com/", "https://example.com/c", "https://example.com/c").await);
+        assert!(!RewriteScheduler::new(Duration::ZERO).is_enabled());
+    }
+}
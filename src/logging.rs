@@ -0,0 +1,235 @@
+//! Structured JSON log output
+//!
+//! With `logging.format = "json"` the server writes every log record, from
+//! `log` macros and `tracing` events alike, to stderr as one JSON object per
+//! line. Fields of the enclosing spans are merged into each record, so
+//! anything logged while a crawl runs carries its `crawl_id` and
+//! `connection_id`, and records of a page carry its `url`. Levels follow
+//! `RUST_LOG` as in the default text output.
+
+use std::io::Write;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::filter::{FilterExt, LevelFilter, Targets};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, Filter, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Targets kept quiet regardless of `RUST_LOG`, as in the text output
+const QUIET_TARGETS: [(&str, LevelFilter); 7] = [
+    ("chromiumoxide::handler", LevelFilter::OFF),
+    ("chromiumoxide::conn", LevelFilter::OFF),
+    ("tantivy::indexer::index_writer", LevelFilter::WARN),
+    ("tantivy::indexer::prepared_commit", LevelFilter::WARN),
+    ("tantivy::indexer::segment_updater", LevelFilter::WARN),
+    ("tantivy::directory::managed_directory", LevelFilter::WARN),
+    ("tantivy::directory::file_watcher", LevelFilter::WARN),
+];
+
+/// Record levels from `RUST_LOG` (errors only when unset or invalid)
+///
+/// Spans always pass so their fields are known to the records they enclose.
+pub fn env_filter<S: Subscriber>() -> impl Filter<S> {
+    let targets = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|directives| directives.parse::<Targets>().ok())
+        .unwrap_or_else(|| Targets::new().with_default(LevelFilter::ERROR));
+    let targets = QUIET_TARGETS
+        .into_iter()
+        .fold(targets, |targets, (target, level)| targets.with_target(target, level));
+    targets.or(tracing_subscriber::filter::filter_fn(|metadata| metadata.is_span()))
+}
+
+/// Layer writing each event as a JSON line with the fields of its spans
+pub struct JsonLayer<W> {
+    make_writer: W,
+}
+
+impl JsonLayer<fn() -> std::io::Stderr> {
+    /// Write to stderr
+    #[must_use]
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr)
+    }
+}
+
+impl<W> JsonLayer<W> {
+    #[must_use]
+    pub fn new(make_writer: W) -> Self {
+        Self { make_writer }
+    }
+}
+
+/// Fields recorded on a span, including those inherited through `follows_from`
+struct SpanFields(Map<String, Value>);
+
+/// Collects field values as JSON, skipping the `log.*` fields of bridged `log` records
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl JsonVisitor<'_> {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if !field.name().starts_with("log.") {
+            self.0.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, format!("{value:?}").into());
+    }
+}
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+            values.record(&mut JsonVisitor(fields));
+        }
+    }
+
+    // Page spans are trace roots that follow from their crawl; keep the crawl's fields
+    fn on_follows_from(&self, id: &Id, follows: &Id, ctx: Context<'_, S>) {
+        let (Some(span), Some(cause)) = (ctx.span(id), ctx.span(follows)) else {
+            return;
+        };
+        let mut inherited = Map::new();
+        for ancestor in cause.scope().from_root() {
+            if let Some(SpanFields(fields)) = ancestor.extensions().get::<SpanFields>() {
+                inherited.extend(fields.clone());
+            }
+        }
+        if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+            for (key, value) in inherited {
+                fields.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let normalized = event.normalized_metadata();
+        let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+
+        let mut record = Map::new();
+        record.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+                .into(),
+        );
+        record.insert("level".to_string(), metadata.level().as_str().into());
+        record.insert("target".to_string(), metadata.target().into());
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    record.extend(fields.clone());
+                }
+            }
+        }
+        event.record(&mut JsonVisitor(&mut record));
+
+        let Ok(mut line) = serde_json::to_vec(&record) else { return };
+        line.push(b'\n');
+        let _ = self.make_writer.make_writer_for(metadata).write_all(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_records_carry_crawl_and_page_fields() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(move || writer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let session = tracing::info_span!("crawl_session", crawl_id = 7_u32, connection_id = "conn-1");
+            let _session = session.enter();
+            let crawl = tracing::info_span!("crawl", start_url = "https://docs.rs/");
+            let _crawl = crawl.enter();
+            tracing::info!("crawl started");
+
+            let page = tracing::info_span!(parent: None, "crawl.page", url = "https://docs.rs/about");
+            page.follows_from(&crawl);
+            let _page = page.enter();
+            tracing::warn!(attempt = 2, "page failed");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0]["message"], "crawl started");
+        assert_eq!(records[0]["level"], "INFO");
+        assert_eq!(records[0]["crawl_id"], 7);
+        assert_eq!(records[0]["start_url"], "https://docs.rs/");
+        assert!(records[0].get("url").is_none());
+
+        assert_eq!(records[1]["message"], "page failed");
+        assert_eq!(records[1]["level"], "WARN");
+        assert_eq!(records[1]["crawl_id"], 7);
+        assert_eq!(records[1]["connection_id"], "conn-1");
+        assert_eq!(records[1]["url"], "https://docs.rs/about");
+        assert_eq!(records[1]["attempt"], 2);
+    }
+}
@@ -2,15 +2,273 @@
 //
 // This binary serves web crawling and search tools over HTTP/HTTPS transport.
 // Managed by kodegend daemon, typically running on port kodegen_config::PORT_CITESCRAPE (30439).
+//
+// Without an MCP client the same binary works standalone: `crawl <url>` mirrors a
+// site, `search <query>` queries a finished crawl's index and `export <crawl>`
+// archives its output directory, `migrate <crawl>` upgrades a mirror written by an
+// older build to the current layout. `serve` (or no subcommand) starts the server.
+//
+// Settings are layered: compiled-in defaults < config file < CITESCRAPE_* env < flags.
+// `--print-config` prints the effective configuration and exits. When a config file is
+// used, edits to pool bounds, crawl limits and search pacing apply without a restart.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
 use kodegen_config::CATEGORY_CITESCRAPE;
 use kodegen_server_http::{ServerBuilder, Managers, RouterSet, ShutdownHook, register_tool, ConnectionCleanupFn};
+use kodegen_tools_citescrape::config::{ConfigFormat, ConfigReloader, CrawlConfigFile, ReloadTargets, ServerConfig};
+use kodegen_tools_citescrape::export::{ArchiveFormat, ExportOptions, default_archive_path, export_crawl};
+use kodegen_tools_citescrape::mcp::manager::{resolve_crawl_dir, url_to_output_dir};
+use kodegen_tools_citescrape::output_layout::{LAYOUT_VERSION, migrate_output_dir};
+use kodegen_tools_citescrape::link_index::open_local_index;
+use kodegen_tools_citescrape::search::query::SearchQueryBuilder;
+use kodegen_tools_citescrape::utils::url_utils::mirror_relative_path;
+use kodegen_tools_citescrape::{CrawlConfig, CrawlProgress};
 use rmcp::handler::server::router::{prompt::PromptRouter, tool::ToolRouter};
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::future::Future;
 use std::pin::Pin;
 
+/// Citescrape: web crawling and search tools, standalone or as an MCP server over HTTP(S)
+#[derive(Debug, Clone, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Server flags, accepted without a subcommand for kodegend
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Crawl a site into a local markdown mirror with a search index
+    Crawl(CrawlArgs),
+    /// Search the index of a finished crawl
+    Search(SearchArgs),
+    /// Package a crawl's output directory into a .tar.zst or .zip archive
+    Export(ExportArgs),
+    /// Browse a finished mirror in a local web browser
+    Preview(PreviewArgs),
+    /// Move a crawl written by an older build to the current output layout
+    Migrate(MigrateArgs),
+    /// Serve the tools over MCP (the default without a subcommand)
+    Serve(ServeArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+struct CrawlArgs {
+    /// URL to start crawling from
+    url: String,
+
+    /// Crawl definition file (TOML or YAML); its start_url is replaced by <URL>
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Output directory (default: <output root>/<domain>)
+    #[arg(long, value_name = "PATH")]
+    output_dir: Option<PathBuf>,
+
+    /// Most pages to crawl
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Link depth to follow from the start URL
+    #[arg(long, value_name = "N")]
+    max_depth: Option<u8>,
+
+    /// Skip building the search index
+    #[arg(long)]
+    no_search: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct SearchArgs {
+    /// Search query (supports phrases, AND/OR/NOT and field:value terms)
+    query: String,
+
+    /// URL that was crawled (locates the output directory)
+    #[arg(long, value_name = "URL")]
+    url: Option<String>,
+
+    /// Crawl output directory (takes precedence over --url)
+    #[arg(long, value_name = "PATH")]
+    output_dir: Option<String>,
+
+    /// Only return pages from this domain (also locates the crawl without --url/--output-dir)
+    #[arg(long, value_name = "DOMAIN")]
+    domain: Option<String>,
+
+    /// Number of results
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Args)]
+struct ExportArgs {
+    /// Crawl to export: the crawled URL or its output directory
+    crawl: String,
+
+    /// Archive format: tar_zst or zip
+    #[arg(long, value_name = "FORMAT", default_value = "tar_zst", value_parser = parse_archive_format)]
+    format: ArchiveFormat,
+
+    /// Only include markdown files
+    #[arg(long)]
+    markdown_only: bool,
+
+    /// Include the search index (.search_index/)
+    #[arg(long)]
+    include_search_index: bool,
+
+    /// Archive file to write (default: next to the output directory, timestamped)
+    #[arg(long, value_name = "PATH")]
+    destination: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+struct MigrateArgs {
+    /// Crawl to migrate: the crawled URL or its output directory
+    crawl: String,
+
+    /// Report what would move without changing anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+struct PreviewArgs {
+    /// Crawl to serve: the crawled URL or its output directory
+    crawl: String,
+
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8000")]
+    listen: SocketAddr,
+}
+
+fn parse_archive_format(value: &str) -> Result<ArchiveFormat, String> {
+    match value {
+        "tar_zst" | "tar.zst" => Ok(ArchiveFormat::TarZst),
+        "zip" => Ok(ArchiveFormat::Zip),
+        other => Err(format!("unknown archive format '{other}' (expected tar_zst or zip)")),
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+struct ServeArgs {
+    /// Server config file (TOML or YAML); defaults to $CITESCRAPE_CONFIG
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Print the effective configuration as TOML and exit
+    #[arg(long)]
+    print_config: bool,
+
+    /// HTTP server bind address (e.g., 127.0.0.1:30439)
+    #[arg(long, value_name = "ADDRESS")]
+    http: Option<SocketAddr>,
+
+    /// Path to TLS certificate file (enables HTTPS)
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// Path to TLS private key file
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Graceful shutdown timeout in seconds
+    #[arg(long, value_name = "SECONDS")]
+    shutdown_timeout_secs: Option<u64>,
+
+    /// Accepted for compatibility with other kodegen servers; sessions use the server default
+    #[arg(long, value_name = "SECONDS", hide = true)]
+    keep_alive: Option<u64>,
+
+    /// Browsers kept warm in the pool
+    #[arg(long, value_name = "N")]
+    pool_min_size: Option<usize>,
+
+    /// Most browsers the pool may launch
+    #[arg(long, value_name = "N")]
+    pool_max_size: Option<usize>,
+
+    /// Run pooled browsers headless (true/false)
+    #[arg(long, value_name = "BOOL")]
+    headless: Option<bool>,
+
+    /// Directory for crawl output when a request names none
+    #[arg(long, value_name = "PATH")]
+    output_root: Option<PathBuf>,
+
+    /// Comma-separated query parameters stripped from crawled URLs ("utm_*" matches a prefix)
+    #[arg(long, value_name = "PARAMS", value_delimiter = ',')]
+    tracking_params: Option<Vec<String>>,
+
+    /// Concurrent crawls per MCP connection
+    #[arg(long, value_name = "N")]
+    max_crawls_per_connection: Option<usize>,
+
+    /// Page cap applied to every crawl
+    #[arg(long, value_name = "N")]
+    max_pages_per_crawl: Option<usize>,
+
+    /// Comma-separated domains crawls may target (subdomains included)
+    #[arg(long, value_name = "DOMAINS", value_delimiter = ',')]
+    allowed_domains: Option<Vec<String>>,
+
+    /// Reject crawls of loopback, private and link-local hosts
+    #[arg(long)]
+    block_private_hosts: bool,
+}
+
+impl ServeArgs {
+    /// Apply flags on top of the file and environment layers
+    fn apply(&self, config: &mut ServerConfig) {
+        if let Some(http) = self.http {
+            config.server.http = Some(http);
+        }
+        if let Some(cert) = &self.tls_cert {
+            config.server.tls_cert = Some(cert.clone());
+        }
+        if let Some(key) = &self.tls_key {
+            config.server.tls_key = Some(key.clone());
+        }
+        if let Some(secs) = self.shutdown_timeout_secs {
+            config.server.shutdown_timeout_secs = secs;
+        }
+        if let Some(n) = self.pool_min_size {
+            config.pool.min_size = n;
+        }
+        if let Some(n) = self.pool_max_size {
+            config.pool.max_size = n;
+        }
+        if let Some(headless) = self.headless {
+            config.pool.headless = headless;
+        }
+        if let Some(root) = &self.output_root {
+            config.output.root = Some(root.clone());
+        }
+        if let Some(params) = &self.tracking_params {
+            config.output.tracking_params = Some(params.clone());
+        }
+        if let Some(n) = self.max_crawls_per_connection {
+            config.crawl.max_crawls_per_connection = Some(n);
+        }
+        if let Some(n) = self.max_pages_per_crawl {
+            config.crawl.max_pages_per_crawl = Some(n);
+        }
+        if let Some(domains) = &self.allowed_domains {
+            config.crawl.allowed_domains = Some(domains.clone());
+        }
+        if self.block_private_hosts {
+            config.crawl.block_private_hosts = true;
+        }
+    }
+}
+
 // Wrapper to impl ShutdownHook for Arc<BrowserPool>
 struct BrowserPoolWrapper(Arc<kodegen_tools_citescrape::BrowserPool>);
 
@@ -23,20 +281,333 @@ impl ShutdownHook for BrowserPoolWrapper {
     }
 }
 
+// Wrapper to impl ShutdownHook for Arc<CrawlRegistry>
+struct CrawlRegistryWrapper(Arc<kodegen_tools_citescrape::CrawlRegistry>);
+
+impl ShutdownHook for CrawlRegistryWrapper {
+    fn shutdown(&self) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + '_>> {
+        let registry = self.0.clone();
+        Box::pin(async move {
+            registry.shutdown().await;
+            Ok(())
+        })
+    }
+}
+
+/// Wait for Ctrl+C or SIGTERM
+async fn wait_for_shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = sigterm.recv() => log::info!("Received SIGTERM, shutting down"),
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                log::info!("Received SIGINT, shutting down");
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        log::info!("Received Ctrl+C, shutting down");
+    }
+    Ok(())
+}
+
+/// Crawl definition for `crawl`: the file (if any) with `url` as its start URL
+fn crawl_config(args: &CrawlArgs) -> Result<CrawlConfig> {
+    let url = if args.url.contains("://") {
+        args.url.clone()
+    } else {
+        format!("https://{}", args.url)
+    };
+    let output_dir = match &args.output_dir {
+        Some(dir) => dir.clone(),
+        None => url_to_output_dir(&url, None, None)?,
+    };
+    let search_index_dir = (!args.no_search).then(|| output_dir.join(".search_index"));
+
+    let Some(path) = &args.config else {
+        let mut builder = CrawlConfig::builder()
+            .storage_dir(output_dir)
+            .start_url(url)
+            .limit(args.limit)
+            .search_index_dir(search_index_dir);
+        if let Some(depth) = args.max_depth {
+            builder = builder.max_depth(depth);
+        }
+        return builder.build();
+    };
+
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file '{}'", path.display()))?;
+    let mut file = CrawlConfigFile::parse(&text, ConfigFormat::from_path(path)?)
+        .with_context(|| format!("Invalid config file '{}'", path.display()))?;
+    file.start_url = url;
+    if let Some(dir) = &args.output_dir {
+        file.storage_dir = std::path::absolute(dir)?;
+    }
+    if args.limit.is_some() {
+        file.budgets.limit = args.limit;
+    }
+    if args.max_depth.is_some() {
+        file.budgets.max_depth = args.max_depth;
+    }
+    if args.no_search {
+        file.output.search_index_dir = None;
+    } else if file.output.search_index_dir.is_none() {
+        file.output.search_index_dir = Some(file.storage_dir.join(".search_index"));
+    }
+    let base_dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map_or_else(|| PathBuf::from("."), std::path::Path::to_path_buf);
+    file.into_config(&base_dir)
+        .with_context(|| format!("Invalid config file '{}'", path.display()))
+}
+
+/// `citescrape crawl`: mirror a site, printing each page as it is saved
+async fn run_crawl(args: CrawlArgs) -> Result<()> {
+    let mut config = crawl_config(&args)?;
+    let output_dir = config.storage_dir().clone();
+
+    let engine_cache = kodegen_tools_citescrape::SearchEngineCache::new();
+    if !args.no_search {
+        let entry = engine_cache.get_or_init(output_dir.clone(), &config).await?;
+        if let Some(indexing_sender) = entry.indexing_sender {
+            config = config.with_indexing_sender(indexing_sender);
+        }
+    }
+
+    eprintln!("Crawling {} into {}", config.start_url(), output_dir.display());
+    let result = kodegen_tools_citescrape::crawl_with_progress(config, |progress| match progress {
+        CrawlProgress::PageCrawled { url, queue_size, .. } => {
+            eprintln!("  saved {url} ({queue_size} queued)");
+        }
+        CrawlProgress::PageFailed { url, error } => eprintln!("  failed {url}: {error}"),
+        _ => {}
+    })
+    .await;
+    engine_cache.shutdown().await;
+    result.context("Crawl failed")?;
+
+    println!("{}", output_dir.display());
+    Ok(())
+}
+
+/// `citescrape search`: query the index written by a crawl with search enabled
+async fn run_search(args: SearchArgs) -> Result<()> {
+    let output_dir = match (&args.url, &args.output_dir, &args.domain) {
+        (None, None, Some(domain)) => url_to_output_dir(&format!("https://{domain}/"), None, None)?,
+        (url, output_dir, _) => resolve_crawl_dir(url.as_deref(), output_dir.as_deref(), None)?,
+    };
+    let search_index_dir = output_dir.join(".search_index");
+    if !search_index_dir.join("meta.json").exists() {
+        anyhow::bail!(
+            "Search index not found in {}. Crawl the site first.",
+            output_dir.display()
+        );
+    }
+
+    // Minimal config: the engine only needs the storage and index locations
+    let config = CrawlConfig::builder()
+        .storage_dir(output_dir.clone())
+        .start_url("http://localhost")
+        .search_index_dir(Some(search_index_dir))
+        .build()?;
+    let engine = kodegen_tools_citescrape::search::SearchEngine::create(&config).await?;
+    let results = SearchQueryBuilder::new(&args.query)
+        .limit(args.limit.max(1))
+        .highlight(true)
+        .domain_filter(args.domain.clone())
+        .page_ranks(open_local_index(&output_dir).await.ok().flatten())
+        .execute_with_metadata(engine)
+        .await?;
+
+    println!(
+        "{} of {} results for \"{}\" in {}",
+        results.results.len(),
+        results.total_count,
+        args.query,
+        output_dir.display()
+    );
+    for (rank, hit) in results.results.iter().enumerate() {
+        println!("\n{}. {} ({:.2})\n   {}\n   {}", rank + 1, hit.title, hit.score, hit.url, hit.path);
+        let excerpt = hit.excerpt.trim();
+        if !excerpt.is_empty() {
+            println!("   {}", excerpt.replace('\n', " "));
+        }
+    }
+    Ok(())
+}
+
+/// `citescrape export`: archive a crawl located by its URL or output directory
+async fn run_export(args: ExportArgs) -> Result<()> {
+    let output_dir = if args.crawl.starts_with("http://") || args.crawl.starts_with("https://") {
+        resolve_crawl_dir(Some(&args.crawl), None, None)?
+    } else {
+        resolve_crawl_dir(None, Some(&args.crawl), None)?
+    };
+    let archive_path = args
+        .destination
+        .clone()
+        .unwrap_or_else(|| default_archive_path(&output_dir, args.format));
+    let options = ExportOptions {
+        markdown_only: args.markdown_only,
+        include_search_index: args.include_search_index,
+        cancel: None,
+    };
+
+    let format = args.format;
+    let summary = tokio::task::spawn_blocking(move || export_crawl(&output_dir, &archive_path, format, &options))
+        .await
+        .context("Export task panicked")??;
+    eprintln!(
+        "Archived {} files ({} bytes) into {} bytes",
+        summary.files, summary.source_bytes, summary.archive_bytes
+    );
+    println!("{}", summary.archive_path.display());
+    Ok(())
+}
+
+/// `citescrape migrate`: bring a crawl's output directory to the current layout
+async fn run_migrate(args: MigrateArgs) -> Result<()> {
+    let output_dir = if args.crawl.starts_with("http://") || args.crawl.starts_with("https://") {
+        resolve_crawl_dir(Some(&args.crawl), None, None)?
+    } else {
+        resolve_crawl_dir(None, Some(&args.crawl), None)?
+    };
+    if !output_dir.is_dir() {
+        anyhow::bail!("No crawl output at {}", output_dir.display());
+    }
+
+    let report = migrate_output_dir(&output_dir, args.dry_run).await?;
+    if report.is_noop() {
+        eprintln!("{} already uses layout version {LAYOUT_VERSION}", output_dir.display());
+        return Ok(());
+    }
+    let verb = if report.dry_run { "Would move" } else { "Moved" };
+    eprintln!(
+        "{verb} {} files from layout version {} to {} in {}",
+        report.files_moved,
+        report.from_version,
+        report.to_version,
+        output_dir.display()
+    );
+    if !report.dry_run {
+        eprintln!("Relocated {} link index pages", report.pages_relocated);
+    }
+    for path in &report.conflicts {
+        eprintln!("Left in place (new path already taken): {}", path.display());
+    }
+    if report.files_moved > 0 && !report.dry_run {
+        eprintln!("Crawl the site again to refresh links between moved pages and the search index");
+    }
+    Ok(())
+}
+
+/// `citescrape preview`: serve a mirror over HTTP until Ctrl+C or SIGTERM
+async fn run_preview(args: PreviewArgs) -> Result<()> {
+    let output_dir = if args.crawl.starts_with("http://") || args.crawl.starts_with("https://") {
+        resolve_crawl_dir(Some(&args.crawl), None, None)?
+    } else {
+        resolve_crawl_dir(None, Some(&args.crawl), None)?
+    };
+    if !output_dir.is_dir() {
+        anyhow::bail!("No mirror at {}. Crawl the site first.", output_dir.display());
+    }
+    let listener = tokio::net::TcpListener::bind(args.listen)
+        .await
+        .with_context(|| format!("Failed to bind preview listener on {}", args.listen))?;
+    let addr = listener.local_addr()?;
+
+    // Point at the crawled page itself when the crawl was given by URL
+    let page = url::Url::parse(&args.crawl)
+        .ok()
+        .and_then(|url| mirror_relative_path(&url).ok())
+        .map(|path| format!("{}/", path.to_string_lossy().replace('\\', "/")))
+        .unwrap_or_default();
+    let url = format!("http://{addr}/{page}");
+    eprintln!("Serving {} at {url} (Ctrl+C to stop)", output_dir.display());
+
+    let shutdown = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(kodegen_tools_citescrape::preview::serve(listener, output_dir, shutdown.clone()));
+    wait_for_shutdown_signal().await?;
+    shutdown.cancel();
+    server.await.context("Preview server panicked")?;
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    ServerBuilder::new()
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Serve(cli.serve));
+    if !matches!(command, Command::Serve(_)) {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    }
+    match command {
+        Command::Crawl(args) => run_crawl(args).await,
+        Command::Search(args) => run_search(args).await,
+        Command::Export(args) => run_export(args).await,
+        Command::Preview(args) => run_preview(args).await,
+        Command::Migrate(args) => run_migrate(args).await,
+        Command::Serve(args) => serve(args).await,
+    }
+}
+
+/// `citescrape serve`: run the MCP server until Ctrl+C or SIGTERM
+async fn serve(cli: ServeArgs) -> Result<()> {
+    let mut config = ServerConfig::load(cli.config.as_deref())?;
+    cli.apply(&mut config);
+    config.validate().context("Invalid server configuration")?;
+
+    if cli.print_config {
+        print!("{}", config.to_toml()?);
+        return Ok(());
+    }
+    if cli.keep_alive.is_some() {
+        eprintln!("citescrape: --keep-alive is ignored; sessions use the server default");
+    }
+
+    // Flushes buffered spans when main returns
+    let _telemetry = kodegen_tools_citescrape::telemetry::init_tracing(&config.telemetry, &config.logging)?;
+
+    if let Some(root) = &config.output.root {
+        kodegen_tools_citescrape::mcp::manager::set_output_root(root);
+    }
+    if let Some(params) = &config.output.tracking_params {
+        kodegen_tools_citescrape::link_index::set_tracking_params(params.clone());
+    }
+    let pool_config = config.pool_config();
+    let crawl_quota = config.crawl_quota();
+    let pacing_config = config.pacing_config();
+    let reload_targets = Arc::new(std::sync::OnceLock::new());
+    let registered_targets = reload_targets.clone();
+
+    let addr = config.http_address()?;
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("Failed to bind to {addr}"))?;
+    let mut builder = ServerBuilder::new()
         .category(CATEGORY_CITESCRAPE)
-        .register_tools(|| async {
+        .with_listener(listener);
+    if let Some((cert, key)) = config.tls_paths() {
+        builder = builder.with_tls_config(cert, key);
+    }
+
+    let handle = builder
+        .register_tools(move || async move {
             let mut tool_router = ToolRouter::new();
             let mut prompt_router = PromptRouter::new();
             let managers = Managers::new();
 
             // Create managers
             let engine_cache = Arc::new(kodegen_tools_citescrape::SearchEngineCache::new());
+            engine_cache.web_search_pacer().reconfigure(pacing_config);
 
             // Create browser pool for pre-warmed Chrome instances
-            let pool_config = kodegen_tools_citescrape::BrowserPoolConfig::default();
             let browser_pool = kodegen_tools_citescrape::BrowserPool::new(pool_config);
             if let Err(e) = browser_pool.start().await {
                 log::error!("Failed to start browser pool: {}", e);
@@ -44,14 +615,32 @@ async fn main() -> Result<()> {
             }
 
             // Create crawl registry (NEW - replaces CrawlSessionManager)
-            let crawl_registry = Arc::new(kodegen_tools_citescrape::CrawlRegistry::new(
-                engine_cache.clone(),
-                browser_pool.clone(),
-            ));
+            // Per-connection limits come from the layered server configuration
+            let mut crawl_registry = kodegen_tools_citescrape::CrawlRegistry::new(engine_cache.clone(), browser_pool.clone())
+                .with_quota(crawl_quota);
+            // Crawls left running by a previous process are resumed or reported
+            if let Some(path) = kodegen_tools_citescrape::SessionStore::default_path() {
+                let store = Arc::new(kodegen_tools_citescrape::SessionStore::open(path).await);
+                crawl_registry = crawl_registry.with_session_store(store);
+            }
+            let crawl_registry = Arc::new(crawl_registry);
+            crawl_registry
+                .restore_sessions(kodegen_tools_citescrape::SessionStore::resume_from_env())
+                .await;
+
+            // Components the config file watcher updates at runtime
+            let _ = registered_targets.set(ReloadTargets {
+                browser_pool: browser_pool.clone(),
+                crawl_registry: crawl_registry.clone(),
+                search_pacer: engine_cache.web_search_pacer().clone(),
+            });
 
             // Register browser pool for graceful shutdown
             managers.register(BrowserPoolWrapper(browser_pool.clone())).await;
 
+            // Drain crawl sessions before the pool closes (hooks run in reverse order)
+            managers.register(CrawlRegistryWrapper(crawl_registry.clone())).await;
+
             // Register tools
             use kodegen_tools_citescrape::*;
 
@@ -62,11 +651,11 @@ async fn main() -> Result<()> {
                 ScrapeUrlTool::new(crawl_registry.clone()),
             );
 
-            // web_search tool now uses shared browser pool
+            // web_search tool uses the registry for its browser pool and deep-mode crawls
             (tool_router, prompt_router) = register_tool(
                 tool_router,
                 prompt_router,
-                WebSearchTool::new(browser_pool.clone()),
+                WebSearchTool::new(crawl_registry.clone()),
             );
 
             // Register fetch tool (simplified single-page fetcher)
@@ -76,6 +665,142 @@ async fn main() -> Result<()> {
                 FetchTool::new(crawl_registry.clone()),
             );
 
+            // Register batch_fetch tool (concurrent multi-page fetch via browser pool)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                BatchFetchTool::new(browser_pool.clone()),
+            );
+
+            // Register render_pdf tool (print a page to PDF via CDP)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                RenderPdfTool::new(browser_pool.clone()),
+            );
+
+            // Register fetch_feed tool (RSS/Atom parsing with optional crawl of entries)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                FetchFeedTool::new(crawl_registry.clone()),
+            );
+
+            // Register broken_links tool (post-crawl link report)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                BrokenLinksTool::new(),
+            );
+
+            // Register locate_page tool (output file back to its URL and crawl)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                LocatePageTool::new(),
+            );
+
+            // Register robots_check tool (robots.txt rules and crawl delay for a URL)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                RobotsCheckTool::new(),
+            );
+
+            // Register sitemap_probe tool (sitemap URL counts for crawl scoping)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                SitemapProbeTool::new(),
+            );
+
+            // Register crawl_status tool (live progress of registry crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                CrawlStatusTool::new(crawl_registry.clone()),
+            );
+
+            // Register crawl_cancel / crawl_pause tools (stop or pause registry crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                CrawlCancelTool::new(crawl_registry.clone()),
+            );
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                CrawlPauseTool::new(crawl_registry.clone()),
+            );
+
+            // Register search_docs tool (search previously crawled content)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                SearchDocsTool::new(crawl_registry.clone()),
+            );
+
+            // Register list_crawls / get_manifest tools (discover mirrored crawls)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                ListCrawlsTool::new(crawl_registry.clone()),
+            );
+
+            // Register crawl_resources tool (crawl output as citescrape:// resources)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                CrawlResourcesTool::new().with_engine_cache(engine_cache.clone()),
+            );
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                GetManifestTool::new(),
+            );
+
+            // Register export_crawl tool (archive a crawl for download)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                ExportCrawlTool::new(),
+            );
+
+            // Register extract_structured tool (selector-to-JSON scraping via browser pool)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                ExtractStructuredTool::new(browser_pool.clone()),
+            );
+
+            // Register execute_js tool (evaluate a script on a pooled browser page)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                ExecuteJsTool::new(browser_pool.clone()),
+            );
+
+            // Register interact tool (click/type/submit steps, then extraction)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                InteractTool::new(browser_pool.clone()),
+            );
+
+            // Register diff_pages tool (markdown diff against a URL or crawl snapshot)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                DiffPagesTool::new(browser_pool.clone()).with_engine_cache(engine_cache.clone()),
+            );
+
+            // Register link_index_admin tool (stats, prune, vacuum)
+            (tool_router, prompt_router) = register_tool(
+                tool_router,
+                prompt_router,
+                LinkIndexAdminTool::new(),
+            );
+
             // CRITICAL: Start cleanup tasks after all tools are registered
             engine_cache.start_cleanup_task();
 
@@ -96,6 +821,76 @@ async fn main() -> Result<()> {
             router_set.connection_cleanup = Some(cleanup);
             Ok(router_set)
         })
-        .run()
+        .serve()
+        .await?;
+
+    let reloader = match (ServerConfig::file_path(cli.config.as_deref()), reload_targets.get()) {
+        (Some(path), Some(targets)) => {
+            log::info!("Watching {} for setting changes", path.display());
+            let overrides = cli.clone();
+            let reloader = ConfigReloader::new(path, config.clone(), targets.clone())
+                .with_overrides(move |config| overrides.apply(config));
+            Some(reloader.spawn())
+        }
+        _ => None,
+    };
+
+    // Stops the metrics, REST and gRPC listeners
+    let endpoints_shutdown = tokio_util::sync::CancellationToken::new();
+    let mut endpoints = kodegen_tools_citescrape::mcp::endpoints::Endpoints::new();
+    if let (Some(addr), Some(targets)) = (config.metrics.http, reload_targets.get()) {
+        log::info!("Serving Prometheus metrics on http://{addr}/metrics");
+        endpoints.add(
+            addr,
+            kodegen_tools_citescrape::mcp::metrics::router(targets.crawl_registry.clone()),
+        );
+    }
+
+    let api_token = config
+        .api
+        .token
+        .as_ref()
+        .filter(|_| config.api.http.is_some() || config.api.grpc.is_some())
+        .map(|token| token.resolve())
+        .transpose()
+        .context("api.token")?;
+    if let (Some(addr), Some(targets)) = (config.api.http, reload_targets.get()) {
+        let router = kodegen_tools_citescrape::mcp::rest_api::router(targets.crawl_registry.clone(), api_token.clone())
+            .await
+            .context("REST API")?;
+        log::info!("Serving REST API on http://{addr} (POST /crawls, GET /crawls/{{id}}, GET /search)");
+        endpoints.add(addr, router);
+    }
+    endpoints.spawn(endpoints_shutdown.clone()).await?;
+
+    #[cfg(feature = "grpc")]
+    if let (Some(addr), Some(targets)) = (config.api.grpc, reload_targets.get()) {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind gRPC service to {addr}"))?;
+        let service = kodegen_tools_citescrape::mcp::grpc::service(targets.crawl_registry.clone(), api_token.clone())
+            .await
+            .context("gRPC service")?;
+        log::info!("Serving gRPC citescrape.v1.CrawlControl on {addr}");
+        tokio::spawn(kodegen_tools_citescrape::mcp::grpc::serve(
+            listener,
+            service,
+            endpoints_shutdown.clone(),
+        ));
+    }
+
+    log::info!("Press Ctrl+C or send SIGTERM to initiate graceful shutdown");
+    wait_for_shutdown_signal().await?;
+
+    if let Some(reloader) = reloader {
+        reloader.abort();
+    }
+    endpoints_shutdown.cancel();
+    let timeout = config.shutdown_timeout();
+    log::info!("Initiating graceful shutdown (timeout: {timeout:?})");
+    handle.cancel();
+    handle
+        .wait_for_completion(timeout)
         .await
+        .context("citescrape server did not shut down cleanly")
 }
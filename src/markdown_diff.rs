@@ -0,0 +1,286 @@
+//! Structural diff of converted markdown
+//!
+//! Splits two markdown documents into heading-delimited sections and reports
+//! which sections were added, removed or changed, along with a unified line
+//! diff. Section identity is the heading path ("Guide > Install"), so edits
+//! inside a section are attributed to it even when other sections move.
+//! [`normalize_links`] spells links the same way on both sides first, so a
+//! saved page whose links point at local copies compares equal to the live
+//! page linking to the originals.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use url::Url;
+
+use crate::link_index::normalize_url;
+use crate::link_rewriter::rewrite_markdown_links;
+use crate::utils::url_utils::mirror_relative_path;
+
+/// Heading path of content before the first heading
+const PREAMBLE: &str = "(preamble)";
+
+/// One heading-delimited section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownSection {
+    /// Heading texts from the top level down, joined with " > "
+    pub path: String,
+    /// Section body, including its heading line
+    pub body: String,
+}
+
+/// Line counts for a section whose content changed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SectionChange {
+    /// Heading path of the section
+    pub path: String,
+    /// Lines present only in the new version
+    pub lines_added: usize,
+    /// Lines present only in the old version
+    pub lines_removed: usize,
+}
+
+/// Result of comparing two markdown documents
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MarkdownDiff {
+    /// Whether the documents are identical
+    pub identical: bool,
+    /// Sections only in the new document
+    pub sections_added: Vec<String>,
+    /// Sections only in the old document
+    pub sections_removed: Vec<String>,
+    /// Sections present in both whose content differs
+    pub sections_changed: Vec<SectionChange>,
+    /// Total lines added
+    pub lines_added: usize,
+    /// Total lines removed
+    pub lines_removed: usize,
+    /// Unified diff of the whole documents
+    pub unified: String,
+}
+
+/// Split markdown into sections at ATX headings outside fenced code blocks
+pub fn split_sections(markdown: &str) -> Vec<MarkdownSection> {
+    let mut sections = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut path = PREAMBLE.to_string();
+    let mut body = String::new();
+    let mut fence: Option<&str> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+        } else if let Some((level, title)) = parse_heading(trimmed) {
+            if !body.trim().is_empty() {
+                sections.push(MarkdownSection {
+                    path: path.clone(),
+                    body: std::mem::take(&mut body),
+                });
+            }
+            body.clear();
+            stack.retain(|(l, _)| *l < level);
+            stack.push((level, title.to_string()));
+            path = stack.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>().join(" > ");
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    if !body.trim().is_empty() {
+        sections.push(MarkdownSection { path, body });
+    }
+    sections
+}
+
+/// `(level, title)` of an ATX heading line
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let title = rest.trim().trim_end_matches('#').trim();
+    Some((level, title))
+}
+
+/// Compare `old` and `new`, with `context` lines around each hunk of the unified diff
+pub fn diff_markdown(old: &str, new: &str, context: usize) -> MarkdownDiff {
+    if old == new {
+        return MarkdownDiff {
+            identical: true,
+            ..Default::default()
+        };
+    }
+
+    let old_sections = index_sections(old);
+    let new_sections = index_sections(new);
+    let old_keys: BTreeSet<&String> = old_sections.keys().collect();
+    let new_keys: BTreeSet<&String> = new_sections.keys().collect();
+
+    let sections_added = new_keys.difference(&old_keys).map(|k| (*k).clone()).collect();
+    let sections_removed = old_keys.difference(&new_keys).map(|k| (*k).clone()).collect();
+    let sections_changed = old_keys
+        .intersection(&new_keys)
+        .filter_map(|path| {
+            let (before, after) = (&old_sections[*path], &new_sections[*path]);
+            if before == after {
+                return None;
+            }
+            let (lines_added, lines_removed) = count_changes(&TextDiff::from_lines(before, after));
+            Some(SectionChange {
+                path: (*path).clone(),
+                lines_added,
+                lines_removed,
+            })
+        })
+        .collect();
+
+    let diff = TextDiff::from_lines(old, new);
+    let (lines_added, lines_removed) = count_changes(&diff);
+    let unified = diff
+        .unified_diff()
+        .context_radius(context)
+        .header("old", "new")
+        .to_string();
+
+    MarkdownDiff {
+        identical: false,
+        sections_added,
+        sections_removed,
+        sections_changed,
+        lines_added,
+        lines_removed,
+        unified,
+    }
+}
+
+/// Rewrite every link destination of the page at `page_url` to its normalized absolute URL
+///
+/// Relative destinations are resolved against `page_url`, except local
+/// markdown copies a crawl linked to (`../guide/index.md`), which are mapped
+/// back to the URL of the page mirrored there. Normalization drops fragments,
+/// trailing slashes and tracking parameters (see [`normalize_url`]), which the
+/// crawl's link rewriting does not keep either.
+pub fn normalize_links(markdown: &str, page_url: &Url) -> String {
+    let page_dir: Vec<String> = mirror_relative_path(page_url)
+        .map(|dir| dir.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    let (normalized, _) = rewrite_markdown_links(markdown, |dest| {
+        if dest.starts_with('#') {
+            return None;
+        }
+        if let Some(url) = mirrored_page_url(dest, page_url, &page_dir) {
+            return Some(normalize_url(&url));
+        }
+        let url = page_url.join(dest).ok()?;
+        matches!(url.scheme(), "http" | "https").then(|| normalize_url(url.as_str()))
+    });
+    normalized
+}
+
+/// URL of the page whose local markdown copy `dest` names, relative to `page_dir`
+fn mirrored_page_url(dest: &str, page_url: &Url, page_dir: &[String]) -> Option<String> {
+    if page_dir.is_empty() || Url::parse(dest).is_ok() || dest.starts_with('/') {
+        return None;
+    }
+    let path = dest.split(['#', '?']).next()?;
+    if !path.ends_with(".md") {
+        return None;
+    }
+
+    let mut segments = page_dir.to_vec();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            _ => segments.push(segment.to_string()),
+        }
+    }
+    let file = segments.pop()?;
+    if let Some(stem) = file.strip_suffix(".md").filter(|stem| *stem != "index") {
+        segments.push(stem.to_string());
+    }
+    let (host, rest) = segments.split_first()?;
+    Some(format!("{}://{host}/{}", page_url.scheme(), rest.join("/")))
+}
+
+/// Sections keyed by path; repeated paths get a " (2)", " (3)" suffix
+fn index_sections(markdown: &str) -> BTreeMap<String, String> {
+    let mut sections = BTreeMap::new();
+    let mut seen: BTreeMap<String, usize> = BTreeMap::new();
+    for section in split_sections(markdown) {
+        let count = seen.entry(section.path.clone()).or_insert(0);
+        *count += 1;
+        let key = if *count == 1 {
+            section.path
+        } else {
+            format!("{} ({count})", section.path)
+        };
+        sections.insert(key, section.body);
+    }
+    sections
+}
+
+fn count_changes<'a>(diff: &TextDiff<'a, 'a, 'a, str>) -> (usize, usize) {
+    diff.iter_all_changes().fold((0, 0), |(added, removed), change| match change.tag() {
+        ChangeTag::Insert => (added + 1, removed),
+        ChangeTag::Delete => (added, removed + 1),
+        ChangeTag::Equal => (added, removed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "Intro text\n\n# Guide\n\n## Install\n\nRun `cargo add foo`.\n\n\
+                       ```sh\n# not a heading\n```\n\n## Usage\n\nCall foo().\n";
+    const NEW: &str = "Intro text\n\n# Guide\n\n## Install\n\nRun `cargo add foo@2`.\n\n\
+                       ```sh\n# not a heading\n```\n\n## Configuration\n\nSet FOO=1.\n";
+
+    #[test]
+    fn test_split_sections() {
+        let paths: Vec<String> = split_sections(OLD).into_iter().map(|s| s.path).collect();
+        assert_eq!(paths, ["(preamble)", "Guide", "Guide > Install", "Guide > Usage"]);
+    }
+
+    #[test]
+    fn test_diff_markdown() {
+        let diff = diff_markdown(OLD, NEW, 1);
+        assert!(!diff.identical);
+        assert_eq!(diff.sections_added, ["Guide > Configuration"]);
+        assert_eq!(diff.sections_removed, ["Guide > Usage"]);
+        assert_eq!(diff.sections_changed.len(), 1);
+        assert_eq!(diff.sections_changed[0].path, "Guide > Install");
+        assert_eq!(diff.lines_added, 3);
+        assert_eq!(diff.lines_removed, 3);
+        assert!(diff.unified.contains("+Run `cargo add foo@2`."));
+
+        assert!(diff_markdown(OLD, OLD, 3).identical);
+    }
+
+    #[test]
+    fn test_normalize_links() {
+        let page = Url::parse("https://docs.example.com/guide/install/").unwrap();
+        let saved = "See [usage](../usage/index.md), [home](../../index.md) and [api](https://docs.example.com/api/#top).\n";
+        let live = "See [usage](https://docs.example.com/guide/usage/#intro), [home](/) and [api](/api?utm_source=x).\n";
+        let (saved, live) = (normalize_links(saved, &page), normalize_links(live, &page));
+        assert_eq!(saved, live);
+        assert!(saved.contains("[usage](https://docs.example.com/guide/usage)"));
+        assert!(saved.contains("[home](https://docs.example.com/)"));
+
+        let unchanged = "[top](#top) and `[code](../x/index.md)`\n";
+        assert_eq!(normalize_links(unchanged, &page), unchanged);
+    }
+}
@@ -0,0 +1,263 @@
+//! `batch_fetch` MCP tool - Fetch many pages in one call
+//!
+//! Loads a list of URLs through the browser pool with a per-call concurrency
+//! cap, saves each page's markdown like `fetch` does and reports every page
+//! as it completes via progress notifications.
+
+use futures::StreamExt;
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::browser_page::{RequestOverrides, StealthPage, validate_web_url};
+use super::fetch::{markdown_title, save_fetched_markdown};
+use crate::browser_pool::BrowserPool;
+
+/// Tool name for batch fetching
+pub const BATCH_FETCH: &str = "batch_fetch";
+
+/// Most URLs accepted in one call
+const MAX_URLS: usize = 50;
+
+/// Upper bound for `concurrency`
+const MAX_CONCURRENCY: usize = 8;
+
+fn default_concurrency() -> usize {
+    4
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+/// Arguments for the `batch_fetch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchFetchArgs {
+    /// URLs to fetch (max 50; duplicates are fetched once)
+    pub urls: Vec<String>,
+
+    /// Pages loaded at the same time (default: 4, max: 8)
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+
+    /// Per-page load timeout in milliseconds (default: 30000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Include the markdown of each page in the result (default: false; read `path` instead)
+    #[serde(default)]
+    pub include_content: bool,
+
+    /// Extra request headers, sent only to each URL's own origin
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Outcome for one URL
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchFetchItem {
+    /// URL that was fetched
+    pub url: String,
+    /// Saved markdown file
+    pub path: Option<String>,
+    /// Page title if available
+    pub title: Option<String>,
+    /// Markdown length in bytes
+    pub content_length: usize,
+    /// Markdown, when `include_content` is set
+    pub content: Option<String>,
+    /// Error message if the fetch failed
+    pub error: Option<String>,
+    /// Time spent on this URL in milliseconds
+    pub elapsed_ms: u64,
+}
+
+/// Output of the `batch_fetch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchFetchOutput {
+    /// Pages fetched successfully
+    pub succeeded: usize,
+    /// Pages that failed
+    pub failed: usize,
+    /// Results in completion order
+    pub results: Vec<BatchFetchItem>,
+}
+
+impl ToolArgs for BatchFetchArgs {
+    type Output = BatchFetchOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = BATCH_FETCH;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Fetch up to 50 pages concurrently and save each as markdown";
+}
+
+/// Concurrent multi-page fetch tool
+#[derive(Clone)]
+pub struct BatchFetchTool {
+    browser_pool: Arc<BrowserPool>,
+}
+
+impl BatchFetchTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self { browser_pool }
+    }
+
+    /// Load one page and save its markdown
+    async fn fetch_one(
+        &self,
+        url: &str,
+        overrides: &RequestOverrides,
+        timeout: Duration,
+        ctx: &ToolExecutionContext,
+    ) -> anyhow::Result<(String, String)> {
+        let parsed = url::Url::parse(url)?;
+        let page = StealthPage::open(&self.browser_pool, format!("batch_fetch:{url}")).await?;
+        page.intercept(&parsed, overrides.clone()).await?;
+        let markdown = match page.load(url, None, timeout).await {
+            Ok(()) => page.markdown(url).await,
+            Err(e) => Err(e),
+        };
+        page.close().await;
+        let markdown = markdown?;
+
+        let path = save_fetched_markdown(url, &markdown, ctx.pwd())
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        Ok((path.to_string_lossy().to_string(), markdown))
+    }
+}
+
+impl Tool for BatchFetchTool {
+    type Args = BatchFetchArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        BATCH_FETCH
+    }
+
+    fn description() -> &'static str {
+        "Fetch up to 50 URLs in one call, loading at most `concurrency` pages at \
+         a time through the shared browser pool. Each page is converted to \
+         markdown and saved like fetch does; every completion is reported as a \
+         progress notification so partial results arrive before the batch ends. \
+         Failed URLs are reported with their error without failing the batch.\n\n\
+         batch_fetch({urls: ['https://docs.rs/tokio', 'https://docs.rs/serde'], concurrency: 2})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<BatchFetchOutput>, McpError> {
+        let mut seen = HashSet::new();
+        let urls: Vec<String> = args.urls.into_iter().filter(|u| seen.insert(u.clone())).collect();
+        if urls.is_empty() {
+            return Err(McpError::invalid_arguments("urls must not be empty"));
+        }
+        if urls.len() > MAX_URLS {
+            return Err(McpError::invalid_arguments(format!(
+                "{} URLs given, limit is {MAX_URLS}",
+                urls.len()
+            )));
+        }
+        for url in &urls {
+            validate_web_url(url)?;
+        }
+
+        let concurrency = args.concurrency.clamp(1, MAX_CONCURRENCY);
+        let timeout = Duration::from_millis(args.timeout_ms.max(1000));
+        let overrides = RequestOverrides {
+            headers: args.headers,
+            ..Default::default()
+        };
+        let total = urls.len();
+
+        let mut pending = futures::stream::iter(urls)
+            .map(|url| {
+                let (overrides, ctx) = (&overrides, &ctx);
+                async move {
+                    let started = Instant::now();
+                    let result = if ctx.is_cancelled() {
+                        Err(anyhow::anyhow!("Cancelled"))
+                    } else {
+                        self.fetch_one(&url, overrides, timeout, ctx).await
+                    };
+                    (url, result, started.elapsed())
+                }
+            })
+            .buffer_unordered(concurrency);
+
+        let mut results = Vec::with_capacity(total);
+        while let Some((url, result, elapsed)) = pending.next().await {
+            let item = match result {
+                Ok((path, markdown)) => BatchFetchItem {
+                    title: markdown_title(&markdown),
+                    content_length: markdown.len(),
+                    content: args.include_content.then_some(markdown),
+                    path: Some(path),
+                    url,
+                    error: None,
+                    elapsed_ms: elapsed.as_millis() as u64,
+                },
+                Err(e) => BatchFetchItem {
+                    url,
+                    path: None,
+                    title: None,
+                    content_length: 0,
+                    content: None,
+                    error: Some(format!("{e:#}")),
+                    elapsed_ms: elapsed.as_millis() as u64,
+                },
+            };
+            let message = match (&item.path, &item.error) {
+                (Some(path), _) => format!("fetched {} -> {path}", item.url),
+                (None, Some(error)) => format!("failed {}: {error}", item.url),
+                (None, None) => format!("fetched {}", item.url),
+            };
+            results.push(item);
+            let _ = ctx.update(results.len() as f64, total as f64, message).await;
+        }
+
+        let succeeded = results.iter().filter(|r| r.error.is_none()).count();
+        let failed = results.len() - succeeded;
+        let mut summary = format!("Fetched {succeeded}/{total} pages ({failed} failed)");
+        for item in &results {
+            match (&item.path, &item.error) {
+                (Some(path), _) => {
+                    let _ = write!(summary, "\n  ok   {} -> {path}", item.url);
+                }
+                (None, error) => {
+                    let _ = write!(summary, "\n  fail {}: {}", item.url, error.as_deref().unwrap_or("unknown error"));
+                }
+            }
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            BatchFetchOutput {
+                succeeded,
+                failed,
+                results,
+            },
+        ))
+    }
+}
@@ -0,0 +1,148 @@
+//! `broken_links` MCP tool - Broken link report for a completed crawl
+//!
+//! Reads the crawl's link index and reports targets that returned 4xx/5xx
+//! (or failed outright) plus internal links pointing to pages never crawled.
+//! The report is also written to `broken_links.json` in the output directory.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use super::manager::resolve_crawl_dir;
+use crate::link_index::{BrokenLink, LinkStore, open_local_index};
+
+/// Tool name for the broken link report
+pub const BROKEN_LINKS: &str = "broken_links";
+
+/// Arguments for the `broken_links` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BrokenLinksArgs {
+    /// URL that was crawled (used to locate the output directory)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Explicit crawl output directory (takes precedence over `url`)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+/// Output of the `broken_links` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BrokenLinksOutput {
+    /// Crawl output directory the report was generated for
+    pub output_dir: String,
+    /// Path of the written `broken_links.json`
+    pub report_path: String,
+    /// Targets that were attempted but returned 4xx/5xx or failed
+    pub http_errors: Vec<BrokenLink>,
+    /// Same-domain link targets that were never crawled
+    pub uncrawled_internal: Vec<BrokenLink>,
+}
+
+impl ToolArgs for BrokenLinksArgs {
+    type Output = BrokenLinksOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = BROKEN_LINKS;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Report broken links (4xx/5xx targets and uncrawled internal links) for a crawl";
+}
+
+/// Broken link report tool
+#[derive(Clone, Default)]
+pub struct BrokenLinksTool;
+
+impl BrokenLinksTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for BrokenLinksTool {
+    type Args = BrokenLinksArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        BROKEN_LINKS
+    }
+
+    fn description() -> &'static str {
+        "Generate a broken-links report for a previous crawl. Lists target URLs \
+         that returned 4xx/5xx or failed, and internal links pointing to pages \
+         that were never crawled, each with the pages referencing them. \
+         Writes broken_links.json to the crawl output directory."
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<BrokenLinksOutput>, McpError> {
+        let output_dir = resolve_crawl_dir(args.url.as_deref(), args.output_dir.as_deref(), ctx.pwd())?;
+
+        let index = open_local_index(&output_dir)
+            .await
+            .map_err(McpError::Other)?
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!(
+                    "Link index not found in {}. Crawl the site first.",
+                    output_dir.display()
+                ))
+            })?;
+        let result = index.write_broken_links_report().await;
+        index.close().await;
+        let (report, report_path) = result.map_err(McpError::Other)?;
+
+        let mut summary = format!(
+            "Broken links for {}: {} HTTP errors, {} uncrawled internal links\n",
+            output_dir.display(),
+            report.http_errors.len(),
+            report.uncrawled_internal.len()
+        );
+        for link in &report.http_errors {
+            let reason = match (link.status_code, &link.error) {
+                (Some(status), _) => status.to_string(),
+                (None, Some(error)) => error.clone(),
+                (None, None) => "failed".to_string(),
+            };
+            let _ = writeln!(
+                summary,
+                "  [{reason}] {} (linked from {} pages)",
+                link.url,
+                link.referrers.len()
+            );
+        }
+        for link in &report.uncrawled_internal {
+            let _ = writeln!(
+                summary,
+                "  [uncrawled] {} (linked from {} pages)",
+                link.url,
+                link.referrers.len()
+            );
+        }
+        let _ = write!(summary, "Report written to {}", report_path.display());
+
+        let output = BrokenLinksOutput {
+            output_dir: output_dir.to_string_lossy().to_string(),
+            report_path: report_path.to_string_lossy().to_string(),
+            http_errors: report.http_errors,
+            uncrawled_internal: report.uncrawled_internal,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,192 @@
+//! Stealth browser pages for single-page MCP tools
+//!
+//! Tools that drive one page directly (extraction, script evaluation,
+//! interaction) borrow a browser from the shared pool, open a blank page with
+//! kromekover stealth applied and navigate it themselves. Every request of the
+//! page is vetted by a [`RequestInterceptor`], so the server's URL policy
+//! covers redirects and subresources as well as the URL a tool was given.
+
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use chromiumoxide::Page;
+use chromiumoxide::cdp::browser_protocol::network::CookieParam;
+use url::Url;
+
+use crate::browser_pool::{BrowserPool, PooledBrowserGuard};
+use crate::content_saver::markdown_converter::{ConversionOptions, convert_html_to_markdown};
+use crate::crawl_engine::interception::{OriginOverrides, RequestInterceptor};
+use crate::crawl_engine::page_processor::PageGuard;
+
+/// Poll interval while waiting for a selector
+const WAIT_POLL_MS: u64 = 100;
+
+/// A stealth page holding its pooled browser until dropped
+pub(crate) struct StealthPage {
+    // Declared first so the page closes before the browser returns to the pool
+    page: PageGuard,
+    interceptor: RequestInterceptor,
+    _browser: PooledBrowserGuard,
+}
+
+impl StealthPage {
+    /// Acquire a browser and open a blank page with stealth injected
+    pub(crate) async fn open(pool: &std::sync::Arc<BrowserPool>, label: String) -> Result<Self> {
+        let browser = pool.acquire().await.context("Failed to acquire browser from pool")?;
+        let page = PageGuard::new(
+            browser
+                .browser()
+                .new_page("about:blank")
+                .await
+                .context("Failed to create blank page")?,
+            label,
+        );
+        tokio::time::timeout(Duration::from_secs(5), crate::kromekover::inject(&page))
+            .await
+            .context("Stealth injection timeout after 5s")?
+            .context("Stealth injection failed")?;
+        let interceptor = RequestInterceptor::install(&page).await?;
+        Ok(Self {
+            page,
+            interceptor,
+            _browser: browser,
+        })
+    }
+
+    /// Navigate to `url` and wait for the load (and `wait_for`, if given)
+    pub(crate) async fn load(&self, url: &str, wait_for: Option<&str>, timeout: Duration) -> Result<()> {
+        let loaded = tokio::time::timeout(timeout, async {
+            self.goto(url).await.with_context(|| format!("Failed to navigate to {url}"))?;
+            self.wait_for_navigation().await.context("Failed to wait for page load")?;
+            anyhow::Ok(())
+        })
+        .await
+        .with_context(|| format!("Timed out loading {url}"))
+        .and_then(|loaded| loaded);
+        if let Err(e) = loaded {
+            return Err(match self.interceptor.blocked() {
+                Some(reason) => anyhow::anyhow!("Refused to load {url}: {reason}"),
+                None => e,
+            });
+        }
+
+        if let Some(selector) = wait_for {
+            wait_for_selector(self, selector, timeout).await?;
+        }
+        Ok(())
+    }
+
+    /// Current document converted to markdown, with links resolved against `base_url`
+    pub(crate) async fn markdown(&self, base_url: &str) -> Result<String> {
+        let options = ConversionOptions {
+            base_url: Some(base_url.to_string()),
+            ..ConversionOptions::default()
+        };
+        self.markdown_with(&options).await
+    }
+
+    /// Current document converted to markdown with `options`
+    pub(crate) async fn markdown_with(&self, options: &ConversionOptions) -> Result<String> {
+        let html = self.content().await.context("Failed to read page content")?;
+        convert_html_to_markdown(&html, options).await
+    }
+
+    /// Apply `overrides` to requests for `url`'s origin
+    ///
+    /// Cookies are set for `url`. Headers are added to every request to the
+    /// same origin (never to third parties); method and body replace those of
+    /// the first document request for `url`.
+    pub(crate) async fn intercept(&self, url: &Url, overrides: RequestOverrides) -> Result<()> {
+        if !overrides.cookies.is_empty() {
+            let cookies = overrides
+                .cookies
+                .iter()
+                .map(|(name, value)| {
+                    CookieParam::builder()
+                        .name(name)
+                        .value(value)
+                        .url(url.as_str())
+                        .build()
+                        .map_err(anyhow::Error::msg)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.set_cookies(cookies).await.context("Failed to set cookies")?;
+        }
+        if overrides.headers.is_empty() && overrides.method.is_none() && overrides.body.is_none() {
+            return Ok(());
+        }
+
+        self.interceptor
+            .set_overrides(OriginOverrides {
+                target: url.clone(),
+                headers: overrides.headers.into_iter().collect(),
+                method: overrides.method,
+                body: overrides.body,
+            })
+            .await
+    }
+
+    /// Close the page explicitly, ignoring close failures
+    pub(crate) async fn close(self) {
+        let _ = self.page.close().await;
+    }
+}
+
+impl Deref for StealthPage {
+    type Target = Page;
+
+    fn deref(&self) -> &Self::Target {
+        self.page.page()
+    }
+}
+
+/// Poll until `selector` matches an element or `timeout` passes
+pub(crate) async fn wait_for_selector(page: &Page, selector: &str, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    while page.find_element(selector).await.is_err() {
+        if Instant::now() >= deadline {
+            bail!("Selector '{selector}' did not appear within {}ms", timeout.as_millis());
+        }
+        tokio::time::sleep(Duration::from_millis(WAIT_POLL_MS)).await;
+    }
+    Ok(())
+}
+
+/// Reject URLs a pooled browser should not be pointed at
+///
+/// Checks the scheme and, without resolving the host, the server's URL
+/// policy; resolved addresses are checked when the request is made.
+pub(crate) fn validate_web_url(url: &str) -> Result<url::Url, kodegen_mcp_schema::McpError> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| kodegen_mcp_schema::McpError::InvalidUrl(format!("Invalid URL '{url}': {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(kodegen_mcp_schema::McpError::InvalidUrl(format!(
+            "Only http and https URLs are supported, got '{url}'"
+        )));
+    }
+    crate::url_policy::url_policy()
+        .check_navigation(&parsed)
+        .map_err(|e| kodegen_mcp_schema::McpError::InvalidUrl(e.to_string()))?;
+    Ok(parsed)
+}
+
+/// Custom request settings applied by [`StealthPage::intercept`]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestOverrides {
+    /// HTTP method for the document request (e.g. "POST")
+    pub method: Option<String>,
+    /// Headers added to same-origin requests, replacing any of the same name
+    pub headers: BTreeMap<String, String>,
+    /// Cookies set for the URL before loading
+    pub cookies: BTreeMap<String, String>,
+    /// Body of the document request
+    pub body: Option<String>,
+}
+
+impl RequestOverrides {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.method.is_none() && self.headers.is_empty() && self.cookies.is_empty() && self.body.is_none()
+    }
+}
@@ -0,0 +1,119 @@
+//! `crawl_cancel` MCP tool - Stop a running crawl
+//!
+//! Cancels a crawl started with `scrape_url` by crawl_id. In-flight page loads
+//! are aborted; pages already saved stay on disk, pending link rewrites are
+//! flushed and the manifest is written. Unlike `scrape_url` KILL, the session
+//! is kept so its final progress can still be read with `crawl_status`.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::registry::CrawlRegistry;
+use super::types::CrawlSessionProgress;
+
+/// Tool name for crawl cancellation
+pub const CRAWL_CANCEL: &str = "crawl_cancel";
+
+/// Arguments for the `crawl_cancel` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlCancelArgs {
+    /// Crawl instance to cancel
+    #[serde(default)]
+    pub crawl_id: u32,
+}
+
+/// Output of the `crawl_cancel` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlCancelOutput {
+    /// Whether a running crawl was cancelled (false if it had already finished)
+    pub cancelled: bool,
+    /// Progress of the crawl at cancellation time
+    pub progress: CrawlSessionProgress,
+}
+
+impl ToolArgs for CrawlCancelArgs {
+    type Output = CrawlCancelOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = CRAWL_CANCEL;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Cancel a running crawl, keeping the pages saved so far";
+}
+
+/// Crawl cancellation tool backed by the shared crawl registry
+#[derive(Clone)]
+pub struct CrawlCancelTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl CrawlCancelTool {
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl Tool for CrawlCancelTool {
+    type Args = CrawlCancelArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        CRAWL_CANCEL
+    }
+
+    fn description() -> &'static str {
+        "Cancel a running crawl by crawl_id. In-flight page loads are aborted; \
+         pages already saved are kept, their links rewritten and the crawl \
+         manifest written. The session stays listed so crawl_status can report \
+         its final progress.\n\n\
+         crawl_cancel({crawl_id: 0})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<CrawlCancelOutput>, McpError> {
+        let connection_id = ctx.connection_id().unwrap_or("default");
+
+        let (was_running, progress) = self
+            .registry
+            .cancel_crawl(connection_id, args.crawl_id)
+            .await
+            .map_err(McpError::Other)?
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!("Crawl {} not found for this connection", args.crawl_id))
+            })?;
+
+        let summary = if was_running {
+            format!(
+                "Cancelled crawl {} after {} pages ({} still queued)",
+                args.crawl_id, progress.pages_crawled, progress.pages_queued
+            )
+        } else {
+            format!("Crawl {} was not running (status: {})", args.crawl_id, progress.status)
+        };
+
+        Ok(ToolResponse::new(
+            summary,
+            CrawlCancelOutput {
+                cancelled: was_running,
+                progress,
+            },
+        ))
+    }
+}
@@ -0,0 +1,111 @@
+//! `crawl_pause` MCP tool - Pause or resume a running crawl
+//!
+//! Pausing stops new pages from being scheduled; pages already loading finish
+//! and are saved. Resuming continues from the crawl queue where it stopped.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::registry::CrawlRegistry;
+use super::types::CrawlSessionProgress;
+
+/// Tool name for pausing/resuming crawls
+pub const CRAWL_PAUSE: &str = "crawl_pause";
+
+/// Arguments for the `crawl_pause` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlPauseArgs {
+    /// Crawl instance to pause or resume
+    #[serde(default)]
+    pub crawl_id: u32,
+
+    /// Resume a paused crawl instead of pausing it
+    #[serde(default)]
+    pub resume: bool,
+}
+
+/// Output of the `crawl_pause` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlPauseOutput {
+    /// Whether the crawl changed state (false if it was already paused/running or finished)
+    pub changed: bool,
+    /// Progress of the crawl after the change
+    pub progress: CrawlSessionProgress,
+}
+
+impl ToolArgs for CrawlPauseArgs {
+    type Output = CrawlPauseOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = CRAWL_PAUSE;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Pause or resume scheduling of new pages in a running crawl";
+}
+
+/// Crawl pause/resume tool backed by the shared crawl registry
+#[derive(Clone)]
+pub struct CrawlPauseTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl CrawlPauseTool {
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl Tool for CrawlPauseTool {
+    type Args = CrawlPauseArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        CRAWL_PAUSE
+    }
+
+    fn description() -> &'static str {
+        "Pause a running crawl by crawl_id (pages already loading still finish), \
+         or resume it with resume: true. The crawl queue is kept while paused.\n\n\
+         crawl_pause({crawl_id: 0})\n\
+         crawl_pause({crawl_id: 0, resume: true})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<CrawlPauseOutput>, McpError> {
+        let connection_id = ctx.connection_id().unwrap_or("default");
+
+        let (changed, progress) = self
+            .registry
+            .pause_crawl(connection_id, args.crawl_id, !args.resume)
+            .await
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!("Crawl {} not found for this connection", args.crawl_id))
+            })?;
+
+        let action = if args.resume { "Resumed" } else { "Paused" };
+        let summary = if changed {
+            format!("{action} crawl {} at {} pages", args.crawl_id, progress.pages_crawled)
+        } else {
+            format!("Crawl {} unchanged (status: {})", args.crawl_id, progress.status)
+        };
+
+        Ok(ToolResponse::new(summary, CrawlPauseOutput { changed, progress }))
+    }
+}
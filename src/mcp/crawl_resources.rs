@@ -0,0 +1,209 @@
+//! `crawl_resources` MCP tool - Browse crawl output as MCP resources
+//!
+//! Lists and reads the `citescrape://` resources defined in
+//! [`super::resources`]. The HTTP server answers `resources/list` and
+//! `resources/read` itself and offers no hook for category servers, so the
+//! same catalog is served through this tool; results use the MCP resource
+//! shapes so clients can treat them as resources. Indexed-page URIs are read
+//! from the crawl's search index through the shared [`SearchEngineCache`].
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use rmcp::model::{Resource, ResourceContents, ResourceTemplate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::manager::{SearchEngineCache, crawl_base_dir};
+use super::resources::CrawlResources;
+
+/// Tool name for crawl resource browsing
+pub const CRAWL_RESOURCES: &str = "crawl_resources";
+
+/// Arguments for the `crawl_resources` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlResourcesArgs {
+    /// Resource to read (e.g. "citescrape://docs.rs/docs.rs/tokio/index.md",
+    /// or "citescrape://docs.rs/page?url=https%3A%2F%2Fdocs.rs%2Ftokio" for a
+    /// page from the search index); omit to list resources
+    #[serde(default)]
+    pub uri: Option<String>,
+
+    /// Cursor from a previous listing's `next_cursor`
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Base directory holding one output directory per crawled domain
+    /// (defaults to the directory `scrape_url` writes to)
+    #[serde(default)]
+    pub base_dir: Option<String>,
+}
+
+/// Output of the `crawl_resources` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlResourcesOutput {
+    /// Listed resources (empty when reading)
+    pub resources: Vec<Resource>,
+    /// Cursor for the next page of the listing
+    pub next_cursor: Option<String>,
+    /// URI templates of the resource layout (listing only)
+    pub resource_templates: Vec<ResourceTemplate>,
+    /// Contents of the read resource (empty when listing)
+    pub contents: Vec<ResourceContents>,
+}
+
+impl ToolArgs for CrawlResourcesArgs {
+    type Output = CrawlResourcesOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = CRAWL_RESOURCES;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "List or read crawled pages and metadata as citescrape:// MCP resources";
+}
+
+/// Crawl resource browsing tool
+#[derive(Clone, Default)]
+pub struct CrawlResourcesTool {
+    engine_cache: Option<Arc<SearchEngineCache>>,
+}
+
+impl CrawlResourcesTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve indexed-page URIs from the search engines in `engine_cache`
+    #[must_use]
+    pub fn with_engine_cache(mut self, engine_cache: Arc<SearchEngineCache>) -> Self {
+        self.engine_cache = Some(engine_cache);
+        self
+    }
+
+    /// Markdown of `url` from the search index of `crawl_dir`
+    async fn read_indexed_page(
+        &self,
+        uri: &str,
+        crawl_dir: PathBuf,
+        url: String,
+    ) -> Result<Vec<ResourceContents>, McpError> {
+        let not_found = || McpError::ResourceNotFound(format!("Resource not found: {uri}"));
+        let Some(engine_cache) = &self.engine_cache else {
+            return Err(not_found());
+        };
+        let Some(entry) = engine_cache.get_existing(crawl_dir).await? else {
+            return Err(not_found());
+        };
+        let document = tokio::task::spawn_blocking(move || entry.engine.get_document(&url))
+            .await
+            .map_err(|e| McpError::Other(e.into()))?
+            .map_err(McpError::Other)?
+            .ok_or_else(not_found)?;
+        Ok(vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some("text/markdown".to_string()),
+            text: document.markdown,
+            meta: None,
+        }])
+    }
+}
+
+impl Tool for CrawlResourcesTool {
+    type Args = CrawlResourcesArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        CRAWL_RESOURCES
+    }
+
+    fn description() -> &'static str {
+        "Browse crawl output as MCP resources. Without uri, lists the markdown \
+         (index.md) and metadata (index.json) of every mirrored page plus each \
+         crawl's manifest.json and sitemap.xml as citescrape:// URIs, 500 per \
+         page (pass next_cursor for more). With uri, returns that resource's \
+         text; compressed files are decompressed. citescrape://{crawl}/page?url=... \
+         reads a page's markdown from the crawl's search index by exact URL.\n\n\
+         crawl_resources({})\n\
+         crawl_resources({uri: 'citescrape://docs.rs/docs.rs/tokio/latest/tokio/index.md'})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<CrawlResourcesOutput>, McpError> {
+        let catalog = CrawlResources::new(crawl_base_dir(args.base_dir.as_deref(), ctx.pwd())?);
+
+        if let Some(uri) = args.uri {
+            let indexed = catalog
+                .indexed_page(&uri)
+                .map_err(|e| McpError::invalid_arguments(format!("{e:#}")))?;
+            let contents = match indexed {
+                Some((crawl_dir, url)) => self.read_indexed_page(&uri, crawl_dir, url).await?,
+                None => {
+                    tokio::task::spawn_blocking({
+                        let uri = uri.clone();
+                        move || catalog.read(&uri)
+                    })
+                    .await
+                    .map_err(|e| McpError::Other(e.into()))?
+                    .map_err(|e| McpError::ResourceNotFound(format!("{e:#}")))?
+                    .contents
+                }
+            };
+            let bytes: usize = contents
+                .iter()
+                .map(|c| match c {
+                    ResourceContents::TextResourceContents { text, .. } => text.len(),
+                    ResourceContents::BlobResourceContents { blob, .. } => blob.len(),
+                })
+                .sum();
+            return Ok(ToolResponse::new(
+                format!("Read {uri} ({bytes} bytes)"),
+                CrawlResourcesOutput {
+                    resources: Vec::new(),
+                    next_cursor: None,
+                    resource_templates: Vec::new(),
+                    contents,
+                },
+            ));
+        }
+
+        let cursor = args.cursor;
+        let listed = tokio::task::spawn_blocking(move || catalog.list(cursor.as_deref()))
+            .await
+            .map_err(|e| McpError::Other(e.into()))?
+            .map_err(|e| McpError::invalid_arguments(format!("{e:#}")))?;
+
+        let mut summary = format!("{} resources", listed.resources.len());
+        if let Some(cursor) = &listed.next_cursor {
+            let _ = write!(summary, " (more: cursor {cursor})");
+        }
+        for resource in &listed.resources {
+            let _ = write!(summary, "\n  {}", resource.uri);
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            CrawlResourcesOutput {
+                resources: listed.resources,
+                next_cursor: listed.next_cursor,
+                resource_templates: CrawlResources::templates().resource_templates,
+                contents: Vec::new(),
+            },
+        ))
+    }
+}
@@ -0,0 +1,201 @@
+//! `crawl_status` MCP tool - Live progress of running crawls
+//!
+//! Reports per-session counters tracked from the crawl event bus (pages
+//! crawled/queued/failed, bytes, elapsed time, current URL). With `watch_ms`
+//! the call stays open and sends MCP progress notifications until the crawls
+//! finish or the watch window ends, instead of agents polling the output
+//! directory.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::registry::CrawlRegistry;
+use super::types::CrawlSessionProgress;
+
+/// Tool name for crawl progress
+pub const CRAWL_STATUS: &str = "crawl_status";
+
+/// Upper bound for `watch_ms` (10 minutes)
+const MAX_WATCH_MS: u64 = 600_000;
+
+/// Lower bound for `interval_ms` to avoid flooding the client
+const MIN_INTERVAL_MS: u64 = 100;
+
+fn default_interval_ms() -> u64 {
+    1000
+}
+
+/// Arguments for the `crawl_status` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlStatusArgs {
+    /// Crawl instance to report (all crawls of this connection if omitted)
+    #[serde(default)]
+    pub crawl_id: Option<u32>,
+
+    /// Keep streaming progress notifications for up to this many
+    /// milliseconds while crawls are running (0 = return immediately)
+    #[serde(default)]
+    pub watch_ms: u64,
+
+    /// Milliseconds between progress notifications while watching
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+}
+
+/// Output of the `crawl_status` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlStatusOutput {
+    /// Progress of each matching crawl session, sorted by crawl ID
+    pub crawls: Vec<CrawlSessionProgress>,
+    /// Whether any reported crawl is still running
+    pub running: bool,
+    /// Number of progress notifications sent while watching
+    pub notifications_sent: usize,
+}
+
+impl ToolArgs for CrawlStatusArgs {
+    type Output = CrawlStatusOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = CRAWL_STATUS;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Report live progress of crawls started with scrape_url, optionally streaming progress notifications";
+}
+
+/// Crawl progress tool backed by the shared crawl registry
+#[derive(Clone)]
+pub struct CrawlStatusTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl CrawlStatusTool {
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+/// Aggregate (pages done, pages known) across crawls for progress notifications
+fn progress_totals(crawls: &[CrawlSessionProgress]) -> (f64, f64) {
+    let done: usize = crawls.iter().map(|c| c.pages_crawled + c.pages_failed).sum();
+    let queued: usize = crawls.iter().map(|c| c.pages_queued).sum();
+    (done as f64, (done + queued) as f64)
+}
+
+/// One-line description of a crawl's progress
+fn describe(progress: &CrawlSessionProgress) -> String {
+    let mut line = format!(
+        "crawl {} [{}]: {} crawled, {} queued, {} failed, {} KiB in {:.1}s",
+        progress.crawl_id,
+        progress.status,
+        progress.pages_crawled,
+        progress.pages_queued,
+        progress.pages_failed,
+        progress.bytes_downloaded / 1024,
+        progress.elapsed_ms as f64 / 1000.0
+    );
+    let throughput = &progress.throughput;
+    if throughput.pages_per_sec > 0.0 {
+        let phases = &throughput.phases;
+        let _ = write!(
+            line,
+            ", {:.2} pages/s, {} KiB/s, {:.0} ms/page (fetch {:.0}, extract {:.0}, convert {:.0}, save {:.0}, rewrite {:.0})",
+            throughput.pages_per_sec,
+            (throughput.bytes_per_sec / 1024.0).round(),
+            throughput.avg_page_latency_ms,
+            phases.fetch_ms,
+            phases.extract_ms,
+            phases.convert_ms,
+            phases.save_ms,
+            phases.rewrite_ms
+        );
+    }
+    if let Some(url) = &progress.current_url {
+        let _ = write!(line, " - {url}");
+    }
+    line
+}
+
+impl Tool for CrawlStatusTool {
+    type Args = CrawlStatusArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        CRAWL_STATUS
+    }
+
+    fn description() -> &'static str {
+        "Report live progress of crawls on this connection: pages crawled, queued \
+         and failed, cache hits, bytes downloaded, elapsed time and current URL. \
+         Set crawl_id to report a single crawl. Set watch_ms to keep the call open \
+         and receive progress notifications every interval_ms until the crawls \
+         finish or watch_ms elapses.\n\n\
+         crawl_status({crawl_id: 0, watch_ms: 60000})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<CrawlStatusOutput>, McpError> {
+        let connection_id = ctx.connection_id().unwrap_or("default").to_string();
+
+        let mut crawls = self.registry.crawl_status(&connection_id, args.crawl_id).await;
+        if crawls.is_empty()
+            && let Some(crawl_id) = args.crawl_id
+        {
+            return Err(McpError::ResourceNotFound(format!(
+                "Crawl {crawl_id} not found for this connection"
+            )));
+        }
+
+        let deadline = Instant::now() + Duration::from_millis(args.watch_ms.min(MAX_WATCH_MS));
+        let interval = Duration::from_millis(args.interval_ms.max(MIN_INTERVAL_MS));
+        let mut notifications_sent = 0;
+
+        while crawls.iter().any(CrawlSessionProgress::is_running) && Instant::now() < deadline {
+            let (done, total) = progress_totals(&crawls);
+            let message = crawls.iter().map(describe).collect::<Vec<_>>().join("; ");
+            if ctx.update(done, total, message).await.is_ok() {
+                notifications_sent += 1;
+            }
+
+            tokio::select! {
+                _ = ctx.cancellation_token().cancelled() => break,
+                _ = tokio::time::sleep_until(deadline.min(Instant::now() + interval)) => {}
+            }
+            crawls = self.registry.crawl_status(&connection_id, args.crawl_id).await;
+        }
+
+        let running = crawls.iter().any(CrawlSessionProgress::is_running);
+        let mut summary = format!("{} crawl(s), {}", crawls.len(), if running { "in progress" } else { "none running" });
+        for progress in &crawls {
+            let _ = write!(summary, "\n  {}", describe(progress));
+        }
+
+        let output = CrawlStatusOutput {
+            crawls,
+            running,
+            notifications_sent,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
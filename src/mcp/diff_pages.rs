@@ -0,0 +1,308 @@
+//! `diff_pages` MCP tool - What changed between two versions of a page
+//!
+//! Renders the current page through the markdown conversion pipeline and
+//! compares it, section by section, with either another live URL or the
+//! snapshot saved by an earlier crawl. Live pages are converted with the
+//! conversion settings recorded in the crawl's manifest, and links on both
+//! sides are normalized before diffing so links the crawl pointed at local
+//! copies do not show up as changes.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::browser_page::{StealthPage, validate_web_url};
+use super::manager::{ManifestManager, SearchEngineCache, resolve_crawl_dir};
+use super::types::ConversionSummary;
+use crate::browser_pool::BrowserPool;
+use crate::link_index::LinkIndex;
+use crate::markdown_diff::{MarkdownDiff, diff_markdown, normalize_links};
+
+/// Tool name for page diffs
+pub const DIFF_PAGES: &str = "diff_pages";
+
+/// Largest unified diff returned before truncation
+const MAX_UNIFIED_BYTES: usize = 256 * 1024;
+
+fn default_context_lines() -> usize {
+    3
+}
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+/// Arguments for the `diff_pages` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiffPagesArgs {
+    /// Page to fetch now (the "new" side)
+    pub url: String,
+
+    /// Second live URL to compare against (the "old" side)
+    #[serde(default)]
+    pub compare_url: Option<String>,
+
+    /// Saved markdown file (`index.md` or `index.md.gz`) to compare against
+    #[serde(default)]
+    pub snapshot_path: Option<String>,
+
+    /// Crawl output directory holding the snapshot of `url`; used when neither
+    /// `compare_url` nor `snapshot_path` is given (defaults to the crawl
+    /// directory of `url`'s domain). The crawl's search index is consulted
+    /// first, then the mirrored `index.md`
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    /// Unchanged lines shown around each change in the unified diff (default: 3)
+    #[serde(default = "default_context_lines")]
+    pub context_lines: usize,
+
+    /// Page load timeout in milliseconds (default: 30000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Output of the `diff_pages` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiffPagesOutput {
+    /// Where the old side came from (URL or snapshot path)
+    pub old_source: String,
+    /// URL of the new side
+    pub new_source: String,
+    /// Structural and line diff
+    #[serde(flatten)]
+    pub diff: MarkdownDiff,
+    /// Whether `unified` was cut short
+    pub unified_truncated: bool,
+}
+
+impl ToolArgs for DiffPagesArgs {
+    type Output = DiffPagesOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = DIFF_PAGES;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Diff the markdown of a page against another URL or a previously crawled snapshot";
+}
+
+/// Page diff tool
+#[derive(Clone)]
+pub struct DiffPagesTool {
+    browser_pool: Arc<BrowserPool>,
+    engine_cache: Option<Arc<SearchEngineCache>>,
+}
+
+impl DiffPagesTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self {
+            browser_pool,
+            engine_cache: None,
+        }
+    }
+
+    /// Look default snapshots up in the crawl's search index before the mirror tree
+    #[must_use]
+    pub fn with_engine_cache(mut self, engine_cache: Arc<SearchEngineCache>) -> Self {
+        self.engine_cache = Some(engine_cache);
+        self
+    }
+
+    /// Source and markdown of `url` as the crawl in `output_dir` indexed it
+    async fn indexed_snapshot(&self, url: &str, output_dir: &Path) -> Result<Option<(String, String)>, McpError> {
+        let Some(engine_cache) = &self.engine_cache else {
+            return Ok(None);
+        };
+        let Some(entry) = engine_cache.get_existing(output_dir.to_path_buf()).await? else {
+            return Ok(None);
+        };
+        let url = url.to_string();
+        let document = tokio::task::spawn_blocking(move || entry.engine.get_document(&url))
+            .await
+            .map_err(|e| McpError::Other(e.into()))?
+            .map_err(McpError::Other)?;
+        Ok(document.map(|document| (document.path, document.markdown)))
+    }
+
+    /// Render `url` and convert it to markdown the way `conversion` describes
+    async fn fetch_markdown(&self, url: &str, timeout: Duration, conversion: &ConversionSummary) -> anyhow::Result<String> {
+        let page = StealthPage::open(&self.browser_pool, format!("diff_pages:{url}")).await?;
+        let result = match page.load(url, None, timeout).await {
+            Ok(()) => page.markdown_with(&conversion.options(url)).await,
+            Err(e) => Err(e),
+        };
+        page.close().await;
+        result
+    }
+}
+
+/// Read a saved markdown snapshot, decompressing `.gz` files
+async fn read_snapshot(path: PathBuf) -> anyhow::Result<String> {
+    tokio::task::spawn_blocking(move || {
+        let bytes = std::fs::read(&path)?;
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            let mut text = String::new();
+            flate2::read::GzDecoder::new(&bytes[..]).read_to_string(&mut text)?;
+            Ok(text)
+        } else {
+            Ok(String::from_utf8(bytes)?)
+        }
+    })
+    .await?
+}
+
+/// Conversion settings of the crawl in `crawl_dir`, or the crawl defaults
+async fn crawl_conversion(crawl_dir: Option<&Path>) -> ConversionSummary {
+    match crawl_dir {
+        Some(dir) => ManifestManager::load(dir)
+            .await
+            .map(|manifest| manifest.config_summary.conversion)
+            .unwrap_or_default(),
+        None => ConversionSummary::default(),
+    }
+}
+
+/// Source and text of the snapshot at `path`
+async fn load_snapshot(path: PathBuf) -> Result<(String, String), McpError> {
+    let text = read_snapshot(path.clone())
+        .await
+        .map_err(|e| McpError::Other(e.context(format!("Failed to read snapshot {}", path.display()))))?;
+    Ok((path.to_string_lossy().to_string(), text))
+}
+
+/// Saved markdown of `url` in `output_dir`, plain or compressed
+fn find_snapshot(url: &str, output_dir: &Path) -> Result<PathBuf, McpError> {
+    let md_path = crate::content_saver::cache_check::get_mirror_path_sync(url, output_dir, "index.md")?;
+    let gz_path = md_path.with_extension("md.gz");
+    [md_path, gz_path]
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            McpError::ResourceNotFound(format!(
+                "No saved snapshot of {url} in {}. Crawl it first or pass compare_url.",
+                output_dir.display()
+            ))
+        })
+}
+
+impl Tool for DiffPagesTool {
+    type Args = DiffPagesArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        DIFF_PAGES
+    }
+
+    fn description() -> &'static str {
+        "Fetch a page, convert it to markdown and diff it against either another \
+         URL (compare_url), a saved markdown file (snapshot_path) or, by default, \
+         the snapshot of the same URL from an earlier crawl. Returns sections \
+         added/removed/changed (by heading path) plus a unified diff.\n\n\
+         diff_pages({url: 'https://ratatui.rs/installation/'})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<DiffPagesOutput>, McpError> {
+        let url = validate_web_url(&args.url)?;
+        let timeout = Duration::from_millis(args.timeout_ms.max(1000));
+
+        // Live pages are converted the way the crawl being compared with converted its pages
+        let snapshot_path = match &args.snapshot_path {
+            Some(path) => Some(resolve_crawl_dir(None, Some(path), ctx.pwd())?),
+            None => None,
+        };
+        let crawl_dir = match (&args.compare_url, &snapshot_path, &args.output_dir) {
+            (None, Some(path), _) => LinkIndex::find_output_dir(path),
+            (None, None, output_dir) => Some(resolve_crawl_dir(Some(&args.url), output_dir.as_deref(), ctx.pwd())?),
+            (Some(_), _, Some(output_dir)) => Some(resolve_crawl_dir(None, Some(output_dir), ctx.pwd())?),
+            (Some(_), _, None) => None,
+        };
+        let conversion = crawl_conversion(crawl_dir.as_deref()).await;
+
+        let (old_source, old, old_url) = if let Some(compare_url) = &args.compare_url {
+            let old_url = validate_web_url(compare_url)?;
+            let old = self.fetch_markdown(compare_url, timeout, &conversion).await?;
+            (compare_url.clone(), old, old_url)
+        } else if let Some(path) = snapshot_path {
+            let (source, old) = load_snapshot(path).await?;
+            (source, old, url.clone())
+        } else {
+            let output_dir = resolve_crawl_dir(Some(&args.url), args.output_dir.as_deref(), ctx.pwd())?;
+            let (source, old) = match self.indexed_snapshot(&args.url, &output_dir).await? {
+                Some(snapshot) => snapshot,
+                None => load_snapshot(find_snapshot(&args.url, &output_dir)?).await?,
+            };
+            (source, old, url.clone())
+        };
+        let new = self.fetch_markdown(&args.url, timeout, &conversion).await?;
+
+        let old = normalize_links(&old, &old_url);
+        let new = normalize_links(&new, &url);
+        let mut diff = diff_markdown(&old, &new, args.context_lines);
+        let unified_truncated = diff.unified.len() > MAX_UNIFIED_BYTES;
+        if unified_truncated {
+            let mut end = MAX_UNIFIED_BYTES;
+            while !diff.unified.is_char_boundary(end) {
+                end -= 1;
+            }
+            diff.unified.truncate(end);
+        }
+
+        let mut summary = if diff.identical {
+            format!("No changes between {old_source} and {}", args.url)
+        } else {
+            format!(
+                "{old_source} -> {}: +{} -{} lines, {} sections added, {} removed, {} changed",
+                args.url,
+                diff.lines_added,
+                diff.lines_removed,
+                diff.sections_added.len(),
+                diff.sections_removed.len(),
+                diff.sections_changed.len()
+            )
+        };
+        for path in &diff.sections_added {
+            let _ = write!(summary, "\n  + {path}");
+        }
+        for path in &diff.sections_removed {
+            let _ = write!(summary, "\n  - {path}");
+        }
+        for change in &diff.sections_changed {
+            let _ = write!(
+                summary,
+                "\n  ~ {} (+{} -{})",
+                change.path, change.lines_added, change.lines_removed
+            );
+        }
+
+        let output = DiffPagesOutput {
+            old_source,
+            new_source: args.url,
+            diff,
+            unified_truncated,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
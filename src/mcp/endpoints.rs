@@ -0,0 +1,51 @@
+//! HTTP server for the endpoints next to the MCP server
+//!
+//! The Prometheus metrics ([`super::metrics::router`]) and the REST API
+//! ([`super::rest_api::router`]) are axum routes; routes configured on the same
+//! address are merged into one router and served from one listener.
+
+use std::net::SocketAddr;
+
+use anyhow::{Context, Result};
+use axum::Router;
+use tokio_util::sync::CancellationToken;
+
+/// Routes grouped by the address they are served on
+#[derive(Default)]
+pub struct Endpoints {
+    servers: Vec<(SocketAddr, Router)>,
+}
+
+impl Endpoints {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `router` on `addr`, next to any routes already added there
+    pub fn add(&mut self, addr: SocketAddr, router: Router) {
+        match self.servers.iter_mut().find(|(existing, _)| *existing == addr) {
+            Some((_, shared)) => *shared = std::mem::take(shared).merge(router),
+            None => self.servers.push((addr, router)),
+        }
+    }
+
+    /// Bind every address and serve its routes until `shutdown` is cancelled
+    pub async fn spawn(self, shutdown: CancellationToken) -> Result<()> {
+        for (addr, router) in self.servers {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("Failed to bind endpoint server to {addr}"))?;
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                let served = axum::serve(listener, router)
+                    .with_graceful_shutdown(shutdown.cancelled_owned())
+                    .await;
+                if let Err(e) = served {
+                    log::warn!("Endpoint server on {addr} failed: {e}");
+                }
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+//! `execute_js` MCP tool - Evaluate JavaScript on a rendered page
+//!
+//! Loads a URL in a fresh stealth page from the browser pool, evaluates a
+//! caller-provided expression in the page context and returns its JSON value.
+//! The script only ever sees that one page; evaluation time and result size
+//! are bounded.
+
+use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::browser_page::{StealthPage, validate_web_url};
+use crate::browser_pool::BrowserPool;
+
+/// Tool name for JavaScript evaluation
+pub const EXECUTE_JS: &str = "execute_js";
+
+/// Largest script accepted
+const MAX_SCRIPT_BYTES: usize = 64 * 1024;
+
+/// Upper bound for `max_result_bytes`
+const MAX_RESULT_LIMIT: usize = 10 * 1024 * 1024;
+
+fn default_timeout_ms() -> u64 {
+    15_000
+}
+
+fn default_eval_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_max_result_bytes() -> usize {
+    1024 * 1024
+}
+
+/// Arguments for the `execute_js` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExecuteJsArgs {
+    /// Page to load before evaluating
+    pub url: String,
+
+    /// JavaScript expression to evaluate. Promises are awaited; an arrow or
+    /// function expression is called with no arguments.
+    pub script: String,
+
+    /// Selector to wait for before evaluating (for client-rendered content)
+    #[serde(default)]
+    pub wait_for: Option<String>,
+
+    /// Navigation and wait timeout in milliseconds (default: 15000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// Evaluation timeout in milliseconds (default: 10000)
+    #[serde(default = "default_eval_timeout_ms")]
+    pub eval_timeout_ms: u64,
+
+    /// Maximum size of the JSON result in bytes (default: 1 MiB, max: 10 MiB)
+    #[serde(default = "default_max_result_bytes")]
+    pub max_result_bytes: usize,
+}
+
+/// Output of the `execute_js` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExecuteJsOutput {
+    /// Page the script ran on
+    pub url: String,
+    /// Value returned by the script (`null` for undefined or non-serializable
+    /// values; a JSON prefix string when truncated)
+    pub result: Value,
+    /// Size of the serialized result in bytes before truncation
+    pub result_bytes: usize,
+    /// Whether the result exceeded `max_result_bytes`
+    pub truncated: bool,
+    /// Evaluation time in milliseconds
+    pub elapsed_ms: u64,
+}
+
+impl ToolArgs for ExecuteJsArgs {
+    type Output = ExecuteJsOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = EXECUTE_JS;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Evaluate a JavaScript expression on a rendered page and return its JSON result";
+}
+
+/// JavaScript evaluation tool
+#[derive(Clone)]
+pub struct ExecuteJsTool {
+    browser_pool: Arc<BrowserPool>,
+}
+
+impl ExecuteJsTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self { browser_pool }
+    }
+}
+
+impl Tool for ExecuteJsTool {
+    type Args = ExecuteJsArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        EXECUTE_JS
+    }
+
+    fn description() -> &'static str {
+        "Load a URL in a fresh stealth browser page and evaluate a JavaScript \
+         expression there, returning the JSON-serialized result. Promises are \
+         awaited and function expressions are called. Use it when extraction \
+         needs site-specific logic that CSS selectors cannot express. Evaluation \
+         is limited by eval_timeout_ms and the result by max_result_bytes.\n\n\
+         execute_js({url: 'https://example.com', script: \
+         '[...document.querySelectorAll(\"h2\")].map(h => h.textContent)'})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<ExecuteJsOutput>, McpError> {
+        validate_web_url(&args.url)?;
+        if args.script.trim().is_empty() {
+            return Err(McpError::invalid_arguments("script must not be empty"));
+        }
+        if args.script.len() > MAX_SCRIPT_BYTES {
+            return Err(McpError::invalid_arguments(format!(
+                "script is {} bytes, limit is {MAX_SCRIPT_BYTES}",
+                args.script.len()
+            )));
+        }
+        let max_result_bytes = args.max_result_bytes.clamp(1, MAX_RESULT_LIMIT);
+
+        let page = StealthPage::open(&self.browser_pool, format!("execute_js:{}", args.url)).await?;
+        page.load(
+            &args.url,
+            args.wait_for.as_deref(),
+            Duration::from_millis(args.timeout_ms.max(1000)),
+        )
+        .await?;
+
+        let params = EvaluateParams::builder()
+            .expression(args.script.as_str())
+            .await_promise(true)
+            .return_by_value(true)
+            .eval_as_function_fallback(true)
+            .build()
+            .map_err(|e| McpError::Other(anyhow::anyhow!(e)))?;
+
+        let started = std::time::Instant::now();
+        let evaluation = tokio::time::timeout(
+            Duration::from_millis(args.eval_timeout_ms.max(1)),
+            page.evaluate(params),
+        )
+        .await;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        page.close().await;
+
+        let result = evaluation
+            .map_err(|_| {
+                McpError::Other(anyhow::anyhow!(
+                    "Script did not finish within {}ms",
+                    args.eval_timeout_ms
+                ))
+            })?
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Script failed: {e}")))?;
+        let value = result.value().cloned().unwrap_or(Value::Null);
+
+        let serialized = serde_json::to_string(&value).map_err(|e| McpError::Other(e.into()))?;
+        let result_bytes = serialized.len();
+        let truncated = result_bytes > max_result_bytes;
+        let value = if truncated {
+            let mut end = max_result_bytes;
+            while !serialized.is_char_boundary(end) {
+                end -= 1;
+            }
+            Value::String(serialized[..end].to_string())
+        } else {
+            value
+        };
+
+        let preview: String = serialized.chars().take(500).collect();
+        let summary = format!(
+            "Evaluated script on {} in {elapsed_ms}ms ({result_bytes} bytes{})\n{preview}",
+            args.url,
+            if truncated { ", truncated" } else { "" }
+        );
+
+        let output = ExecuteJsOutput {
+            url: args.url,
+            result: value,
+            result_bytes,
+            truncated,
+            elapsed_ms,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,166 @@
+//! `export_crawl` MCP tool - Package a crawl into one archive
+//!
+//! Writes a `.tar.zst` or `.zip` of a crawl's output directory (optionally
+//! markdown only) and reports where it was written and how large it is.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::manager::resolve_crawl_dir;
+use crate::export::{ArchiveFormat, ExportOptions, default_archive_path, export_crawl};
+
+/// Tool name for crawl export
+pub const EXPORT_CRAWL: &str = "export_crawl";
+
+/// Arguments for the `export_crawl` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportCrawlArgs {
+    /// URL that was crawled (used to locate the output directory)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Explicit crawl output directory (takes precedence over `url`)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    /// Archive format: "tar_zst" (default) or "zip"
+    #[serde(default)]
+    pub format: ArchiveFormat,
+
+    /// Only include markdown files
+    #[serde(default)]
+    pub markdown_only: bool,
+
+    /// Include the search index (.search_index/)
+    #[serde(default)]
+    pub include_search_index: bool,
+
+    /// Archive file to write (default: next to the output directory, timestamped)
+    #[serde(default)]
+    pub destination: Option<String>,
+}
+
+/// Output of the `export_crawl` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportCrawlOutput {
+    /// Crawl output directory that was archived
+    pub output_dir: String,
+    /// Path of the written archive
+    pub archive_path: String,
+    /// Archive format
+    pub format: ArchiveFormat,
+    /// Archive size in bytes
+    pub archive_bytes: u64,
+    /// Number of files archived
+    pub files: usize,
+    /// Total uncompressed size of the archived files
+    pub source_bytes: u64,
+}
+
+impl ToolArgs for ExportCrawlArgs {
+    type Output = ExportCrawlOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = EXPORT_CRAWL;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Package a crawl's output directory into a .tar.zst or .zip archive";
+}
+
+/// Crawl archive export tool
+#[derive(Clone, Default)]
+pub struct ExportCrawlTool;
+
+impl ExportCrawlTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for ExportCrawlTool {
+    type Args = ExportCrawlArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        EXPORT_CRAWL
+    }
+
+    fn description() -> &'static str {
+        "Package a crawl's output directory into a single .tar.zst (default) or \
+         .zip archive and return its path and size. Set markdown_only to archive \
+         just the markdown files. The archive is written next to the output \
+         directory unless destination is given.\n\n\
+         export_crawl({url: 'https://ratatui.rs', format: 'zip', markdown_only: true})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<ExportCrawlOutput>, McpError> {
+        let output_dir = resolve_crawl_dir(args.url.as_deref(), args.output_dir.as_deref(), ctx.pwd())?;
+        if !output_dir.is_dir() {
+            return Err(McpError::ResourceNotFound(format!(
+                "Crawl output directory {} not found. Crawl the site first.",
+                output_dir.display()
+            )));
+        }
+
+        let archive_path = match args.destination.as_deref() {
+            Some(dest) => {
+                let dest = PathBuf::from(dest);
+                match ctx.pwd() {
+                    Some(pwd) if dest.is_relative() => pwd.join(dest),
+                    _ => dest,
+                }
+            }
+            None => default_archive_path(&output_dir, args.format),
+        };
+        let options = ExportOptions {
+            markdown_only: args.markdown_only,
+            include_search_index: args.include_search_index,
+            cancel: Some(ctx.cancellation_token().clone()),
+        };
+
+        let format = args.format;
+        let dir = output_dir.clone();
+        let summary = tokio::task::spawn_blocking(move || export_crawl(&dir, &archive_path, format, &options))
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Export task failed: {e}")))?
+            .map_err(McpError::Other)?;
+
+        let text = format!(
+            "Exported {} files ({} KiB) from {} to {} ({} KiB)",
+            summary.files,
+            summary.source_bytes / 1024,
+            output_dir.display(),
+            summary.archive_path.display(),
+            summary.archive_bytes / 1024
+        );
+
+        let output = ExportCrawlOutput {
+            output_dir: output_dir.to_string_lossy().to_string(),
+            archive_path: summary.archive_path.to_string_lossy().to_string(),
+            format,
+            archive_bytes: summary.archive_bytes,
+            files: summary.files,
+            source_bytes: summary.source_bytes,
+        };
+
+        Ok(ToolResponse::new(text, output))
+    }
+}
@@ -0,0 +1,208 @@
+//! `extract_structured` MCP tool - Selector-to-JSON scraping
+//!
+//! Renders a page in a pooled stealth browser, reads the requested fields via
+//! CSS selectors and returns them as JSON records, optionally following a
+//! "next page" link for paginated listings.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::browser_page::{StealthPage, validate_web_url};
+use crate::browser_pool::BrowserPool;
+use crate::page_extractor::structured::{ExtractionSpec, FieldSpec, extract_structured, next_page_url};
+
+/// Tool name for structured extraction
+pub const EXTRACT_STRUCTURED: &str = "extract_structured";
+
+/// Upper bound on pages visited by one call
+const MAX_PAGES_LIMIT: usize = 20;
+
+/// Follow a "next page" link after each extraction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PaginationSpec {
+    /// Selector of the link/button leading to the next page (e.g. "a[rel=next]")
+    pub next_selector: String,
+
+    /// Maximum number of pages to visit, including the first (default: 5, max: 20)
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+}
+
+fn default_max_pages() -> usize {
+    5
+}
+
+fn default_timeout_ms() -> u64 {
+    15_000
+}
+
+/// Arguments for the `extract_structured` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExtractStructuredArgs {
+    /// Page to extract from
+    pub url: String,
+
+    /// Field name to selector mapping, e.g.
+    /// {"title": {"selector": "h2"}, "link": {"selector": "a", "attribute": "href"}}
+    pub fields: BTreeMap<String, FieldSpec>,
+
+    /// Selector of repeating item elements; each match becomes one record
+    #[serde(default)]
+    pub item_selector: Option<String>,
+
+    /// Selector to wait for before extracting (for client-rendered content)
+    #[serde(default)]
+    pub wait_for: Option<String>,
+
+    /// Follow "next page" links and extract from each page
+    #[serde(default)]
+    pub pagination: Option<PaginationSpec>,
+
+    /// Per-page navigation and wait timeout in milliseconds (default: 15000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Output of the `extract_structured` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExtractStructuredOutput {
+    /// URL extraction started from
+    pub url: String,
+    /// Pages extracted, in visit order
+    pub pages: Vec<String>,
+    /// Extracted records (one per item, or one per page without `item_selector`)
+    pub records: Vec<Value>,
+}
+
+impl ToolArgs for ExtractStructuredArgs {
+    type Output = ExtractStructuredOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = EXTRACT_STRUCTURED;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Extract structured JSON from a page by mapping field names to CSS selectors";
+}
+
+/// Structured extraction tool
+#[derive(Clone)]
+pub struct ExtractStructuredTool {
+    browser_pool: Arc<BrowserPool>,
+}
+
+impl ExtractStructuredTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self { browser_pool }
+    }
+}
+
+impl Tool for ExtractStructuredTool {
+    type Args = ExtractStructuredArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        EXTRACT_STRUCTURED
+    }
+
+    fn description() -> &'static str {
+        "Render a page in a stealth browser and extract structured JSON using CSS \
+         selectors. Each field reads text (default), html, outer_html or any \
+         attribute (href/src are made absolute); set multiple to collect every \
+         match. With item_selector, each matching element becomes one record and \
+         field selectors are relative to it. pagination.next_selector follows \
+         next-page links up to max_pages.\n\n\
+         extract_structured({url: 'https://news.ycombinator.com', \
+         item_selector: 'tr.athing', fields: {title: {selector: '.titleline > a'}, \
+         link: {selector: '.titleline > a', attribute: 'href'}}, \
+         pagination: {next_selector: 'a.morelink', max_pages: 3}})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<ExtractStructuredOutput>, McpError> {
+        validate_web_url(&args.url)?;
+        let spec = ExtractionSpec {
+            fields: args.fields,
+            item_selector: args.item_selector,
+        };
+        let extraction = spec.compile().map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+
+        let max_pages = args
+            .pagination
+            .as_ref()
+            .map_or(1, |p| p.max_pages.clamp(1, MAX_PAGES_LIMIT));
+        let timeout = Duration::from_millis(args.timeout_ms.max(1000));
+
+        let page = StealthPage::open(&self.browser_pool, format!("extract_structured:{}", args.url)).await?;
+
+        let mut pages = Vec::new();
+        let mut records = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = Some(args.url.clone());
+
+        while let Some(current) = next.take() {
+            if ctx.is_cancelled() || pages.len() >= max_pages || !visited.insert(current.clone()) {
+                break;
+            }
+            if let Err(e) = page.load(&current, args.wait_for.as_deref(), timeout).await {
+                // The first page failing is an error; later pages end pagination
+                if pages.is_empty() {
+                    return Err(McpError::Other(e));
+                }
+                tracing::warn!("Stopping pagination at {current}: {e:#}");
+                break;
+            }
+
+            records.extend(extract_structured(&page, &extraction).await?);
+            pages.push(current);
+            let _ = ctx.notify(pages.len() as f64, Some(max_pages as f64), None).await;
+
+            if let Some(pagination) = &args.pagination {
+                next = next_page_url(&page, &pagination.next_selector).await.unwrap_or(None);
+            }
+        }
+        page.close().await;
+
+        let mut summary = format!(
+            "Extracted {} records from {} page(s) of {}",
+            records.len(),
+            pages.len(),
+            args.url
+        );
+        for record in records.iter().take(5) {
+            let _ = write!(summary, "\n  {record}");
+        }
+        if records.len() > 5 {
+            let _ = write!(summary, "\n  ... {} more", records.len() - 5);
+        }
+
+        let output = ExtractStructuredOutput {
+            url: args.url,
+            pages,
+            records,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
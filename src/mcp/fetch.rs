@@ -2,20 +2,33 @@
 //!
 //! Wraps `scrape_url` with `max_depth: 0`, `limit: 1` for single-page retrieval.
 //! Returns ANSI syntax-highlighted markdown for beautiful terminal display.
+//!
+//! Requests that need custom headers, cookies, a method or a body (API docs
+//! behind a token, form results) bypass the crawl and load the page directly
+//! in a pooled browser with CDP request interception.
 
 use kodegen_mcp_schema::citescrape::{
-    FetchArgs, FetchOutput, FetchPrompts, FETCH,
+    FetchOutput, FetchPrompts, FETCH,
     ScrapeAction, ScrapeUrlArgs,
 };
-use kodegen_mcp_schema::{McpError, Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
 use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 use syntect::easy::HighlightLines;
 use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
 use syntect::util::as_24_bit_terminal_escaped;
 
+use super::browser_page::{RequestOverrides, StealthPage, validate_web_url};
+use super::manager::url_to_output_dir;
 use super::registry::CrawlRegistry;
-use super::start_crawl::ScrapeUrlTool;
+use super::start_crawl::{ScrapeUrlTool, ScrapeUrlToolArgs};
+use crate::content_saver::markdown_converter::Typography;
+use crate::search::MessagePriority;
 
 /// Global syntax set for markdown highlighting (loaded once)
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
@@ -23,19 +36,164 @@ static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_
 /// Global theme set for highlighting (loaded once)
 static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
 
+/// Page load timeout for requests with custom options
+const CUSTOM_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Fetched pages kept for repeat requests
+const CACHE_CAPACITY: NonZeroUsize = NonZeroUsize::new(64).expect("non-zero");
+
+/// How long a cached page is served before it is fetched again
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Arguments for the `fetch` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FetchArgs {
+    /// URL to fetch (required)
+    pub url: String,
+
+    /// HTTP method for the page request (default: GET)
+    #[serde(default)]
+    pub method: Option<String>,
+
+    /// Extra request headers, e.g. {"Authorization": "Bearer ..."}; sent only to the URL's origin
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Cookies to set for the URL before loading
+    #[serde(default)]
+    pub cookies: BTreeMap<String, String>,
+
+    /// Request body (JSON bodies get a JSON content type unless one is given)
+    #[serde(default)]
+    pub body: Option<String>,
+
+    /// Fetch the page again even if it was fetched in the last few minutes
+    #[serde(default)]
+    pub force_refresh: bool,
+}
+
+impl FetchArgs {
+    fn overrides(&self) -> RequestOverrides {
+        RequestOverrides {
+            method: self.method.clone(),
+            headers: self.headers.clone(),
+            cookies: self.cookies.clone(),
+            body: self.body.clone(),
+        }
+    }
+}
+
+impl ToolArgs for FetchArgs {
+    type Output = FetchOutput;
+    type Prompts = FetchPrompts;
+
+    const NAME: &'static str = FETCH;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Fetch a single web page and display as ANSI-highlighted markdown. Simplified wrapper around scrape_url for quick page retrieval.";
+}
+
+/// Save markdown of a page loaded outside a crawl where a crawl would put it
+///
+/// The file is not added to the search index.
+pub(crate) async fn save_fetched_markdown(
+    url: &str,
+    markdown: &str,
+    client_pwd: Option<&std::path::Path>,
+) -> Result<std::path::PathBuf, McpError> {
+    let output_dir = url_to_output_dir(url, None, client_pwd)?;
+    crate::content_saver::save_markdown_content(
+        markdown.to_string(),
+        url.to_string(),
+        output_dir.clone(),
+        MessagePriority::Normal,
+        None,
+        false,
+        0,
+        crate::content_saver::ResponseValidators::default(),
+    )
+    .await?;
+    Ok(crate::utils::get_mirror_path(url, &output_dir, "index.md").await?)
+}
+
+/// Text of the first `# ` heading
+pub(crate) fn markdown_title(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .find(|line| line.starts_with("# "))
+        .map(|line| line.trim_start_matches("# ").to_string())
+}
+
+/// A converted page kept for repeat `fetch` calls
+struct CachedFetch {
+    display: String,
+    output: FetchOutput,
+    fetched_at: Instant,
+}
+
+/// `(connection id, URL)` a cached fetch answers
+type FetchCacheKey = (String, String);
+
 /// Simplified fetch tool for single-page retrieval
 #[derive(Clone)]
 pub struct FetchTool {
     scrape_tool: ScrapeUrlTool,
+    registry: Arc<CrawlRegistry>,
+    /// Recent plain fetches by connection and exact URL; requests with custom options are never cached
+    ///
+    /// Keyed per connection because the response names the crawl id the
+    /// connection fetched the page under, and by the URL as given because
+    /// normalization drops parts (tracking parameters, trailing slashes) that
+    /// some sites serve different pages for.
+    cache: Arc<parking_lot::Mutex<lru::LruCache<FetchCacheKey, CachedFetch>>>,
 }
 
 impl FetchTool {
     pub fn new(registry: Arc<CrawlRegistry>) -> Self {
         Self {
-            scrape_tool: ScrapeUrlTool::new(registry),
+            scrape_tool: ScrapeUrlTool::new(registry.clone()),
+            registry,
+            cache: Arc::new(parking_lot::Mutex::new(lru::LruCache::new(CACHE_CAPACITY))),
         }
     }
 
+    /// Cached response for `key` if it is younger than `CACHE_TTL`
+    fn cached(&self, key: &FetchCacheKey) -> Option<ToolResponse<FetchOutput>> {
+        let mut cache = self.cache.lock();
+        let entry = cache.get(key)?;
+        if entry.fetched_at.elapsed() > CACHE_TTL {
+            cache.pop(key);
+            return None;
+        }
+        Some(ToolResponse::new(entry.display.clone(), entry.output.clone()))
+    }
+
+    /// Load the page directly with request overrides and save its markdown
+    async fn fetch_with_overrides(
+        &self,
+        args: &FetchArgs,
+        ctx: &ToolExecutionContext,
+    ) -> Result<(String, String), McpError> {
+        let url = validate_web_url(&args.url)?;
+        if let Some(method) = &args.method
+            && !method.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(McpError::invalid_arguments(format!("Invalid HTTP method '{method}'")));
+        }
+
+        let page = StealthPage::open(self.registry.browser_pool(), format!("fetch:{}", args.url)).await?;
+        page.intercept(&url, args.overrides()).await?;
+        let markdown = match page.load(&args.url, None, CUSTOM_REQUEST_TIMEOUT).await {
+            Ok(()) => page.markdown(&args.url).await,
+            Err(e) => Err(e),
+        };
+        page.close().await;
+        let markdown = markdown?;
+
+        let md_path = save_fetched_markdown(&args.url, &markdown, ctx.pwd()).await?;
+
+        Ok((md_path.to_string_lossy().to_string(), markdown))
+    }
+
     /// Convert markdown to ANSI-highlighted string for terminal display
     fn highlight_markdown_to_ansi(markdown: &str) -> String {
         let syntax = SYNTAX_SET
@@ -92,7 +250,11 @@ impl Tool for FetchTool {
     fn description() -> &'static str {
         "Fetch a single web page and display as ANSI-highlighted markdown. \
          Returns syntax-colored content for terminal display plus metadata \
-         including file path and search helper for follow-up queries."
+         including file path and search helper for follow-up queries. \
+         Optional headers, cookies, method and body customize the page request \
+         (e.g. an Authorization header for API docs); headers are only sent to \
+         the URL's origin. Repeat fetches of a URL within 5 minutes return the \
+         cached result unless force_refresh is set."
     }
 
     fn read_only() -> bool {
@@ -112,6 +274,27 @@ impl Tool for FetchTool {
         args: Self::Args,
         ctx: ToolExecutionContext,
     ) -> Result<ToolResponse<FetchOutput>, McpError> {
+        if !args.overrides().is_empty() {
+            let (path, markdown_content) = self.fetch_with_overrides(&args, &ctx).await?;
+            let title = markdown_title(&markdown_content);
+            let output = FetchOutput {
+                path,
+                search_helper: "Not indexed: pages fetched with custom request options are saved but not searchable"
+                    .to_string(),
+                url: args.url,
+                title,
+                content_length: markdown_content.len(),
+            };
+            return Ok(ToolResponse::new(Self::highlight_markdown_to_ansi(&markdown_content), output));
+        }
+
+        let cache_key = (ctx.connection_id().unwrap_or("default").to_string(), args.url.clone());
+        if !args.force_refresh
+            && let Some(response) = self.cached(&cache_key)
+        {
+            return Ok(response);
+        }
+
         // Build scrape_url args for single-page fetch
         let scrape_args = ScrapeUrlArgs {
             action: ScrapeAction::Crawl,
@@ -134,6 +317,7 @@ impl Tool for FetchTool {
         };
 
         // Execute scrape_url
+        let scrape_args = ScrapeUrlToolArgs { scrape: scrape_args, profile: None, typography: Typography::default() };
         let scrape_result = self.scrape_tool.execute(scrape_args, ctx).await?;
         let scrape_output = scrape_result.metadata;
 
@@ -168,10 +352,7 @@ impl Tool for FetchTool {
         };
 
         // Extract title from first # heading
-        let title = markdown_content
-            .lines()
-            .find(|line| line.starts_with("# "))
-            .map(|line| line.trim_start_matches("# ").to_string());
+        let title = markdown_title(&markdown_content);
 
         // Generate ANSI-highlighted display
         let display = Self::highlight_markdown_to_ansi(&markdown_content);
@@ -187,6 +368,15 @@ impl Tool for FetchTool {
             content_length: markdown_content.len(),
         };
 
+        self.cache.lock().put(
+            cache_key,
+            CachedFetch {
+                display: display.clone(),
+                output: output.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+
         Ok(ToolResponse::new(display, output))
     }
 }
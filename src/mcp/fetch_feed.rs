@@ -0,0 +1,237 @@
+//! `fetch_feed` MCP tool - Read RSS/Atom feeds
+//!
+//! Parses the feed at a URL (or the feed a page advertises) into normalized
+//! entries and can hand the entry URLs to a background crawl, which makes
+//! monitoring blogs and release pages a single call.
+
+use kodegen_mcp_schema::citescrape::{ScrapeUrlArgs, ScrapeUrlPrompts};
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use super::browser_page::validate_web_url;
+use super::manager::resolve_crawl_dir;
+use super::registry::CrawlRegistry;
+use super::types::crawl_tool_error;
+use crate::content_saver::markdown_converter::Typography;
+use crate::feed::{FeedEntry, FeedKind, fetch_feed};
+use crate::utils::http_fetch::guarded_client;
+
+/// Tool name for feed fetching
+pub const FETCH_FEED: &str = "fetch_feed";
+
+fn default_max_entries() -> usize {
+    50
+}
+
+/// Arguments for the `fetch_feed` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FetchFeedArgs {
+    /// Feed URL, or a page that links to its feed
+    pub url: String,
+
+    /// Most entries returned, newest first as listed by the feed (default: 50)
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+
+    /// Start a background crawl of the returned entry URLs (default: false)
+    #[serde(default)]
+    pub crawl: bool,
+
+    /// Crawl instance to use when `crawl` is set (default: 0)
+    #[serde(default)]
+    pub crawl_id: u32,
+
+    /// Link depth to follow from each entry when crawling (default: 0 = entries only)
+    #[serde(default)]
+    pub max_depth: u8,
+
+    /// Crawl output directory (defaults to the crawl directory of the first entry's domain)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+/// Output of the `fetch_feed` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FetchFeedOutput {
+    /// URL the feed was read from (differs from the input after autodiscovery)
+    pub feed_url: String,
+    /// Feed dialect
+    pub kind: FeedKind,
+    /// Feed title
+    pub title: Option<String>,
+    /// Site the feed belongs to
+    pub link: Option<String>,
+    /// Feed description
+    pub description: Option<String>,
+    /// Last update time of the feed
+    pub updated: Option<String>,
+    /// Entries in the feed before `max_entries` was applied
+    pub total_entries: usize,
+    /// Returned entries
+    pub entries: Vec<FeedEntry>,
+    /// Crawl instance the entry URLs were queued in
+    pub crawl_id: Option<u32>,
+    /// Output directory of that crawl
+    pub output_dir: Option<String>,
+}
+
+impl ToolArgs for FetchFeedArgs {
+    type Output = FetchFeedOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = FETCH_FEED;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Parse an RSS/Atom feed (autodiscovered from a page if needed) and optionally crawl its entries";
+}
+
+/// RSS/Atom feed tool
+#[derive(Clone)]
+pub struct FetchFeedTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl FetchFeedTool {
+    #[must_use]
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// Queue `urls` in a background crawl and return its id and output directory
+    async fn crawl_entries(
+        &self,
+        urls: Vec<String>,
+        args: &FetchFeedArgs,
+        ctx: &ToolExecutionContext,
+    ) -> Result<(u32, String), McpError> {
+        let mut urls = urls.into_iter();
+        let Some(start_url) = urls.next() else {
+            return Err(McpError::invalid_arguments("Feed has no entry links to crawl"));
+        };
+        let output_dir = resolve_crawl_dir(Some(&start_url), args.output_dir.as_deref(), ctx.pwd())?;
+
+        let crawl_args: ScrapeUrlArgs = serde_json::from_value(serde_json::json!({
+            "action": "CRAWL",
+            "crawl_id": args.crawl_id,
+            "url": start_url,
+            "output_dir": output_dir.to_string_lossy(),
+            "max_depth": args.max_depth,
+            "await_completion_ms": 0,
+        }))
+        .map_err(|e| McpError::Other(e.into()))?;
+
+        let connection_id = ctx.connection_id().unwrap_or("default");
+        let _admission = self
+            .registry
+            .admit_crawl(connection_id, args.crawl_id)
+            .await
+            .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+        let session = self
+            .registry
+            .find_or_create_crawl(connection_id, args.crawl_id, output_dir.clone())
+            .await
+            .map_err(McpError::Other)?;
+        session
+            .execute_crawl_with_seeds(crawl_args, None, Typography::default(), 0, urls.collect())
+            .await
+            .map_err(crawl_tool_error)?;
+        Ok((args.crawl_id, output_dir.to_string_lossy().to_string()))
+    }
+}
+
+impl Tool for FetchFeedTool {
+    type Args = FetchFeedArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        FETCH_FEED
+    }
+
+    fn description() -> &'static str {
+        "Fetch and parse an RSS 2.0, RSS 1.0 or Atom feed into normalized entries \
+         (title, link, id, published/updated as RFC 3339, summary, authors, \
+         categories). If the URL is a regular page, the feed it advertises via \
+         <link rel=\"alternate\"> is used. With crawl: true the entry URLs are \
+         queued in a background crawl (check it with scrape_url READ).\n\n\
+         fetch_feed({url: 'https://blog.rust-lang.org/', max_entries: 10})\n\
+         fetch_feed({url: 'https://github.com/tokio-rs/tokio/releases.atom', crawl: true})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<FetchFeedOutput>, McpError> {
+        let url = validate_web_url(&args.url)?;
+        let (feed_url, feed) = fetch_feed(&guarded_client().map_err(McpError::Other)?, &url).await?;
+
+        let total_entries = feed.entries.len();
+        let mut entries = feed.entries;
+        entries.truncate(args.max_entries);
+
+        let (crawl_id, output_dir) = if args.crawl {
+            let mut seen = HashSet::new();
+            let urls = entries
+                .iter()
+                .filter_map(|e| e.link.clone())
+                .filter(|link| validate_web_url(link).is_ok() && seen.insert(link.clone()))
+                .collect();
+            let (id, dir) = self.crawl_entries(urls, &args, &ctx).await?;
+            (Some(id), Some(dir))
+        } else {
+            (None, None)
+        };
+
+        let mut summary = format!(
+            "{} ({} of {total_entries} entries)",
+            feed.title.as_deref().unwrap_or(feed_url.as_str()),
+            entries.len()
+        );
+        if feed_url != url {
+            let _ = write!(summary, "\nDiscovered feed: {feed_url}");
+        }
+        for entry in &entries {
+            let _ = write!(
+                summary,
+                "\n  {} {} {}",
+                entry.published.as_deref().or(entry.updated.as_deref()).unwrap_or("-"),
+                entry.title.as_deref().unwrap_or("(untitled)"),
+                entry.link.as_deref().unwrap_or("")
+            );
+        }
+        if let (Some(id), Some(dir)) = (crawl_id, &output_dir) {
+            let _ = write!(summary, "\nCrawl {id} started for entry URLs -> {dir}");
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            FetchFeedOutput {
+                feed_url: feed_url.to_string(),
+                kind: feed.kind,
+                title: feed.title,
+                link: feed.link,
+                description: feed.description,
+                updated: feed.updated,
+                total_entries,
+                entries,
+                crawl_id,
+                output_dir,
+            },
+        ))
+    }
+}
@@ -0,0 +1,110 @@
+//! `get_manifest` MCP tool - Read the manifest of a crawl
+//!
+//! Returns the `CrawlManifest` saved in a crawl's output directory: start URL,
+//! status, page count, timestamps, configuration summary, site audit and the
+//! outcome of every page (status, depth, content hash, output files, timing,
+//! error).
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::manager::{ManifestManager, resolve_crawl_dir};
+use super::types::{CrawlManifest, CrawlStatus};
+
+/// Tool name for reading a crawl manifest
+pub const GET_MANIFEST: &str = "get_manifest";
+
+/// Arguments for the `get_manifest` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GetManifestArgs {
+    /// URL that was crawled (used to locate the output directory)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Explicit crawl output directory (takes precedence over `url`)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+impl ToolArgs for GetManifestArgs {
+    type Output = CrawlManifest;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = GET_MANIFEST;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Read the manifest (status, pages, timestamps, config, site audit) of a crawl";
+}
+
+/// Crawl manifest reader
+#[derive(Clone, Default)]
+pub struct GetManifestTool;
+
+impl GetManifestTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for GetManifestTool {
+    type Args = GetManifestArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        GET_MANIFEST
+    }
+
+    fn description() -> &'static str {
+        "Read the manifest.json of a crawl located by url or output_dir: start URL, \
+         final status, page count, start/end timestamps (Unix seconds), crawl \
+         configuration and orphan/dead-end site audit."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<CrawlManifest>, McpError> {
+        let output_dir = resolve_crawl_dir(args.url.as_deref(), args.output_dir.as_deref(), ctx.pwd())?;
+
+        if !ManifestManager::exists(&output_dir).await {
+            return Err(McpError::ResourceNotFound(format!(
+                "No crawl manifest in {}. The crawl may not have finished yet.",
+                output_dir.display()
+            )));
+        }
+        let manifest = ManifestManager::load(&output_dir).await?;
+
+        let status = match &manifest.status {
+            CrawlStatus::Running => "running".to_string(),
+            CrawlStatus::Completed => "completed".to_string(),
+            CrawlStatus::Failed { error, cause: Some(cause) } => format!("failed ({}): {error}", cause.kind()),
+            CrawlStatus::Failed { error, cause: None } => format!("failed: {error}"),
+            CrawlStatus::Interrupted => {
+                format!("interrupted by a server restart, resume with {}", manifest.resume_hint())
+            }
+        };
+        let summary = format!(
+            "Crawl {} of {} - {status}, {} pages, started {}",
+            manifest.crawl_id,
+            manifest.start_url,
+            manifest.total_pages,
+            manifest.start_time.to_rfc3339()
+        );
+
+        Ok(ToolResponse::new(summary, manifest))
+    }
+}
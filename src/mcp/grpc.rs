@@ -0,0 +1,352 @@
+//! gRPC control service for crawl orchestration (`grpc` feature)
+//!
+//! Serves `citescrape.v1.CrawlControl` as described in
+//! `proto/citescrape.proto`: `StartCrawl`, `GetCrawl`, `ListCrawls`,
+//! `CancelCrawl` and `Search`. Orchestrators driving many crawls get binary
+//! framing and HTTP/2 multiplexing instead of one JSON request per
+//! connection. `build.rs` generates the messages and server stub
+//! ([`proto`]) from the proto file.
+//!
+//! Like the REST API, calls go through the shared [`CrawlRegistry`], quota
+//! and search engines, crawls belong to the connection
+//! [`GRPC_CONNECTION_ID`], and the same restrictions apply: with a token
+//! configured every call must carry `authorization: Bearer <token>` metadata,
+//! and an `output_dir` must lie under the server's output root.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::Result;
+use kodegen_mcp_schema::McpError;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status};
+
+use self::proto::crawl_control_server::{CrawlControl, CrawlControlServer};
+use self::proto::{
+    CrawlRef, CrawlStatus, ListCrawlsRequest, ListCrawlsResponse, SearchHit, SearchRequest, SearchResponse,
+    StartCrawlRequest,
+};
+use super::manager::crawl_base_dir;
+use super::registry::{BackgroundCrawlError, CrawlRegistry};
+use super::rest_api::{confine_output_dir, is_authorized};
+use super::search_docs::{SearchDocsArgs, SearchDocsOutput, resolve_search_dir, search_crawl_index};
+use super::start_crawl::ScrapeUrlToolArgs;
+use super::types::CrawlSessionProgress;
+use crate::config::SecretValue;
+
+/// Connection that crawls started over gRPC belong to
+pub const GRPC_CONNECTION_ID: &str = "grpc";
+
+/// Messages and server generated from `proto/citescrape.proto` by `build.rs`
+pub mod proto {
+    tonic::include_proto!("citescrape.v1");
+}
+
+impl From<CrawlSessionProgress> for CrawlStatus {
+    fn from(progress: CrawlSessionProgress) -> Self {
+        Self {
+            crawl_id: progress.crawl_id,
+            status: progress.status,
+            current_url: progress.current_url,
+            pages_crawled: progress.pages_crawled as u64,
+            pages_queued: progress.pages_queued as u64,
+            pages_failed: progress.pages_failed as u64,
+            cache_hits: progress.cache_hits as u64,
+            bytes_downloaded: progress.bytes_downloaded,
+            elapsed_ms: progress.elapsed_ms,
+            output_dir: progress.output_dir,
+        }
+    }
+}
+
+impl From<SearchDocsOutput> for SearchResponse {
+    fn from(output: SearchDocsOutput) -> Self {
+        Self {
+            query: output.query,
+            output_dir: output.output_dir,
+            total_count: output.total_count as u64,
+            next_offset: output.next_offset.map(|offset| offset as u64),
+            results: output
+                .results
+                .into_iter()
+                .map(|hit| SearchHit {
+                    url: hit.url,
+                    title: hit.title,
+                    path: hit.path,
+                    score: hit.score,
+                    snippet: hit.snippet,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `scrape_url` arguments for a `StartCrawl` request
+///
+/// `options_json` supplies any further `scrape_url` fields; the typed
+/// request fields win over it. The `output_dir` must lie under `output_root`.
+fn start_crawl_args(request: StartCrawlRequest, output_root: &Path) -> Result<ScrapeUrlToolArgs, Status> {
+    let mut options = if request.options_json.trim().is_empty() {
+        serde_json::Map::new()
+    } else {
+        match serde_json::from_str(&request.options_json) {
+            Ok(serde_json::Value::Object(options)) => options,
+            Ok(_) => return Err(Status::invalid_argument("options_json: expected a JSON object")),
+            Err(e) => return Err(Status::invalid_argument(format!("options_json: {e}"))),
+        }
+    };
+    if request.url.is_empty() {
+        return Err(Status::invalid_argument("url is required"));
+    }
+    options.insert("url".into(), request.url.into());
+    // `options_json` may not name a directory outside the output root either
+    let output_dir = request
+        .output_dir
+        .or_else(|| options.get("output_dir").and_then(|dir| dir.as_str()).map(str::to_string));
+    if let Some(output_dir) = output_dir {
+        let confined = confine_output_dir(output_root, &output_dir).map_err(|e| status_from_mcp_error(&e))?;
+        options.insert("output_dir".into(), confined.to_string_lossy().into_owned().into());
+    }
+    if let Some(max_depth) = request.max_depth {
+        options.insert("max_depth".into(), max_depth.into());
+    }
+    if let Some(limit) = request.limit {
+        options.insert("limit".into(), limit.into());
+    }
+    if let Some(profile) = request.profile {
+        options.insert("profile".into(), profile.into());
+    }
+    serde_json::from_value(options.into()).map_err(|e| Status::invalid_argument(format!("invalid crawl request: {e}")))
+}
+
+/// `search_docs` arguments for a `Search` request
+///
+/// The `output_dir` must lie under `output_root`.
+fn search_args(request: SearchRequest, output_root: &Path) -> Result<SearchDocsArgs, Status> {
+    if request.query.trim().is_empty() {
+        return Err(Status::invalid_argument("query is required"));
+    }
+    let output_dir = request
+        .output_dir
+        .map(|dir| confine_output_dir(output_root, &dir).map_err(|e| status_from_mcp_error(&e)))
+        .transpose()?;
+    Ok(SearchDocsArgs {
+        query: request.query,
+        crawl_id: request.crawl_id,
+        domain: request.domain,
+        url: request.url,
+        output_dir: output_dir.map(|dir| dir.to_string_lossy().into_owned()),
+        top_k: request.limit.map_or(10, |limit| limit as usize),
+        offset: request.offset as usize,
+        snippets: request.snippets.unwrap_or(true),
+    })
+}
+
+/// gRPC status for a tool error
+fn status_from_mcp_error(error: &McpError) -> Status {
+    match error {
+        McpError::InvalidArguments(_) | McpError::InvalidUrl(_) => Status::invalid_argument(error.to_string()),
+        McpError::ResourceNotFound(_) => Status::not_found(error.to_string()),
+        _ => Status::internal(error.to_string()),
+    }
+}
+
+/// State shared by the `CrawlControl` handlers
+pub struct GrpcApi {
+    registry: Arc<CrawlRegistry>,
+    token: Option<SecretValue>,
+    /// Directory every requested `output_dir` must lie under
+    output_root: PathBuf,
+    next_crawl_id: AtomicU32,
+}
+
+impl GrpcApi {
+    /// Reject calls without the configured bearer token
+    fn authorize<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let Some(token) = &self.token else {
+            return Ok(());
+        };
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        if is_authorized(authorization, token.expose()) {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated("missing or invalid bearer token"))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl CrawlControl for GrpcApi {
+    async fn start_crawl(&self, request: Request<StartCrawlRequest>) -> Result<Response<CrawlStatus>, Status> {
+        self.authorize(&request)?;
+        let args = start_crawl_args(request.into_inner(), &self.output_root)?;
+        let crawl_id = self.next_crawl_id.fetch_add(1, Ordering::Relaxed);
+        match self.registry.start_background_crawl(GRPC_CONNECTION_ID, crawl_id, args).await {
+            Ok(progress) => Ok(Response::new(progress.into())),
+            Err(BackgroundCrawlError::InvalidArguments(e)) => Err(status_from_mcp_error(&e)),
+            Err(e @ BackgroundCrawlError::Forbidden(_)) => Err(Status::permission_denied(e.to_string())),
+            Err(e @ BackgroundCrawlError::LimitReached(_)) => Err(Status::resource_exhausted(e.to_string())),
+            Err(e @ BackgroundCrawlError::Failed(_)) => Err(Status::internal(e.to_string())),
+        }
+    }
+
+    async fn get_crawl(&self, request: Request<CrawlRef>) -> Result<Response<CrawlStatus>, Status> {
+        self.authorize(&request)?;
+        let crawl_id = request.into_inner().crawl_id;
+        match self.registry.crawl_status(GRPC_CONNECTION_ID, Some(crawl_id)).await.pop() {
+            Some(progress) => Ok(Response::new(progress.into())),
+            None => Err(Status::not_found(format!("crawl {crawl_id} not found"))),
+        }
+    }
+
+    async fn list_crawls(&self, request: Request<ListCrawlsRequest>) -> Result<Response<ListCrawlsResponse>, Status> {
+        self.authorize(&request)?;
+        let crawls = self.registry.crawl_status(GRPC_CONNECTION_ID, None).await;
+        Ok(Response::new(ListCrawlsResponse {
+            crawls: crawls.into_iter().map(CrawlStatus::from).collect(),
+        }))
+    }
+
+    async fn cancel_crawl(&self, request: Request<CrawlRef>) -> Result<Response<CrawlStatus>, Status> {
+        self.authorize(&request)?;
+        let crawl_id = request.into_inner().crawl_id;
+        match self.registry.cancel_crawl(GRPC_CONNECTION_ID, crawl_id).await {
+            Ok(Some((_, progress))) => Ok(Response::new(progress.into())),
+            Ok(None) => Err(Status::not_found(format!("crawl {crawl_id} not found"))),
+            Err(e) => Err(Status::internal(format!("failed to cancel crawl: {e}"))),
+        }
+    }
+
+    async fn search(&self, request: Request<SearchRequest>) -> Result<Response<SearchResponse>, Status> {
+        self.authorize(&request)?;
+        let args = search_args(request.into_inner(), &self.output_root)?;
+        let output_dir = resolve_search_dir(&self.registry, GRPC_CONNECTION_ID, &args, None)
+            .await
+            .map_err(|e| status_from_mcp_error(&e))?;
+        let output = search_crawl_index(&self.registry, output_dir, &args)
+            .await
+            .map_err(|e| status_from_mcp_error(&e))?;
+        Ok(Response::new(output.into()))
+    }
+}
+
+/// The `CrawlControl` service, ready to be served by [`serve`]
+///
+/// With `token`, calls without matching bearer metadata get `UNAUTHENTICATED`.
+pub async fn service(registry: Arc<CrawlRegistry>, token: Option<SecretValue>) -> Result<CrawlControlServer<GrpcApi>> {
+    // Continue numbering after crawls restored from a previous run
+    let next_crawl_id = registry
+        .crawl_status(GRPC_CONNECTION_ID, None)
+        .await
+        .last()
+        .map_or(0, |progress| progress.crawl_id + 1);
+    Ok(CrawlControlServer::new(GrpcApi {
+        registry,
+        token,
+        output_root: crawl_base_dir(None, None)?,
+        next_crawl_id: AtomicU32::new(next_crawl_id),
+    }))
+}
+
+/// Answer gRPC calls on `listener` until `shutdown` is cancelled
+pub async fn serve(listener: TcpListener, service: CrawlControlServer<GrpcApi>, shutdown: CancellationToken) {
+    let incoming = tonic::transport::server::TcpIncoming::from(listener);
+    if let Err(e) = tonic::transport::Server::builder()
+        .add_service(service)
+        .serve_with_incoming_shutdown(incoming, shutdown.cancelled_owned())
+        .await
+    {
+        log::warn!("gRPC server stopped: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn test_start_crawl_args() {
+        let root = Path::new("/srv/citescrape");
+        let request = StartCrawlRequest {
+            url: "https://docs.rs/tokio".to_string(),
+            limit: Some(50),
+            profile: Some("fast".to_string()),
+            options_json: r#"{"limit": 5, "crawl_rate_rps": 1.5}"#.to_string(),
+            ..Default::default()
+        };
+        let args = start_crawl_args(request, root).unwrap();
+        assert_eq!(args.scrape.url.as_deref(), Some("https://docs.rs/tokio"));
+        assert_eq!(args.scrape.limit, Some(50));
+        assert_eq!(args.scrape.crawl_rate_rps, 1.5);
+        assert!(args.profile.is_some());
+
+        let err = start_crawl_args(StartCrawlRequest::default(), root).unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        let request = StartCrawlRequest {
+            url: "https://docs.rs".to_string(),
+            options_json: "[1]".to_string(),
+            ..Default::default()
+        };
+        let err = start_crawl_args(request, root).unwrap_err();
+        assert!(err.message().starts_with("options_json"), "{}", err.message());
+    }
+
+    #[test]
+    fn test_output_dir_confined_to_root() {
+        let root = Path::new("/srv/citescrape");
+        let request = |output_dir: Option<&str>, options_json: &str| StartCrawlRequest {
+            url: "https://docs.rs".to_string(),
+            output_dir: output_dir.map(str::to_string),
+            options_json: options_json.to_string(),
+            ..Default::default()
+        };
+        let args = start_crawl_args(request(Some("docs.rs"), ""), root).unwrap();
+        assert_eq!(args.scrape.output_dir.as_deref(), Some("/srv/citescrape/docs.rs"));
+
+        for (output_dir, options_json) in [(Some("../etc"), ""), (None, r#"{"output_dir": "/etc"}"#)] {
+            let err = start_crawl_args(request(output_dir, options_json), root).unwrap_err();
+            assert_eq!(err.code(), tonic::Code::InvalidArgument, "{}", err.message());
+        }
+
+        let search = |output_dir: &str| SearchRequest {
+            query: "runtime".to_string(),
+            output_dir: Some(output_dir.to_string()),
+            ..Default::default()
+        };
+        let args = search_args(search("docs.rs"), root).unwrap();
+        assert_eq!(args.output_dir.as_deref(), Some("/srv/citescrape/docs.rs"));
+        assert!(search_args(search("/etc"), root).is_err());
+    }
+
+    #[test]
+    fn test_search_request_defaults() {
+        let root = Path::new("/srv/citescrape");
+        let request = SearchRequest {
+            query: "runtime".to_string(),
+            domain: Some("docs.rs".to_string()),
+            ..Default::default()
+        };
+        let args = search_args(request, root).unwrap();
+        assert_eq!((args.top_k, args.offset, args.snippets), (10, 0, true));
+        assert!(search_args(SearchRequest::default(), root).is_err());
+    }
+
+    #[test]
+    fn test_message_wire_format() {
+        // Field numbers must match proto/citescrape.proto
+        let bytes = CrawlRef { crawl_id: 7 }.encode_to_vec();
+        assert_eq!(bytes, [0x08, 0x07]);
+        let status = CrawlStatus {
+            crawl_id: 1,
+            output_dir: "/out".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(CrawlStatus::decode(status.encode_to_vec().as_slice()).unwrap(), status);
+    }
+}
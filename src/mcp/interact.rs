@@ -0,0 +1,198 @@
+//! `interact` MCP tool - Click/type/submit, then extract
+//!
+//! Replays a list of interaction steps on a pooled stealth page and then
+//! extracts either selector-mapped records or the page as markdown, for
+//! content that only appears after dismissing a dialog, switching a tab or
+//! submitting a search form.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::browser_page::{StealthPage, validate_web_url};
+use crate::browser_pool::BrowserPool;
+use crate::page_extractor::interaction::{InteractionStep, run_steps};
+use crate::page_extractor::structured::{ExtractionSpec, FieldSpec, extract_structured};
+
+/// Tool name for page interaction
+pub const INTERACT: &str = "interact";
+
+/// Most steps accepted in one call
+const MAX_STEPS: usize = 50;
+
+fn default_timeout_ms() -> u64 {
+    15_000
+}
+
+/// Arguments for the `interact` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InteractArgs {
+    /// Page to open
+    pub url: String,
+
+    /// Steps to perform in order, e.g.
+    /// [{"action": "click", "selector": "#accept"},
+    ///  {"action": "type", "selector": "input[name=q]", "text": "tokio"},
+    ///  {"action": "press", "key": "Enter"},
+    ///  {"action": "wait_for", "selector": ".results"}]
+    pub steps: Vec<InteractionStep>,
+
+    /// Fields to extract after the steps (same format as extract_structured).
+    /// Without fields, the page is returned as markdown.
+    #[serde(default)]
+    pub fields: Option<BTreeMap<String, FieldSpec>>,
+
+    /// Selector of repeating item elements for `fields`
+    #[serde(default)]
+    pub item_selector: Option<String>,
+
+    /// Selector to wait for after the initial load
+    #[serde(default)]
+    pub wait_for: Option<String>,
+
+    /// Initial navigation timeout in milliseconds (default: 15000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Output of the `interact` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InteractOutput {
+    /// URL that was opened
+    pub url: String,
+    /// URL after the steps ran
+    pub final_url: String,
+    /// Number of steps performed
+    pub steps_run: usize,
+    /// Extracted records, when `fields` was given
+    pub records: Option<Vec<Value>>,
+    /// Final page as markdown, when `fields` was not given
+    pub markdown: Option<String>,
+}
+
+impl ToolArgs for InteractArgs {
+    type Output = InteractOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = INTERACT;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Click, type, press keys and submit forms on a page, then extract the result";
+}
+
+/// Page interaction tool
+#[derive(Clone)]
+pub struct InteractTool {
+    browser_pool: Arc<BrowserPool>,
+}
+
+impl InteractTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self { browser_pool }
+    }
+}
+
+impl Tool for InteractTool {
+    type Args = InteractArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        INTERACT
+    }
+
+    fn description() -> &'static str {
+        "Open a page in a stealth browser, perform an ordered list of steps and \
+         extract the result. Step actions: click {selector, wait_for_navigation}, \
+         type {selector, text, clear}, press {key, selector}, submit {selector}, \
+         wait_for {selector, timeout_ms}, wait {ms}. Afterwards, fields (as in \
+         extract_structured) are extracted, or the page is returned as markdown.\n\n\
+         interact({url: 'https://docs.rs', steps: [{action: 'type', selector: \
+         'input[name=query]', text: 'tokio'}, {action: 'press', key: 'Enter'}, \
+         {action: 'wait_for', selector: '.search-results'}]})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<InteractOutput>, McpError> {
+        validate_web_url(&args.url)?;
+        if args.steps.len() > MAX_STEPS {
+            return Err(McpError::invalid_arguments(format!(
+                "{} steps given, limit is {MAX_STEPS}",
+                args.steps.len()
+            )));
+        }
+        let extraction = args
+            .fields
+            .map(|fields| ExtractionSpec { fields, item_selector: args.item_selector }.compile())
+            .transpose()
+            .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+
+        let page = StealthPage::open(&self.browser_pool, format!("interact:{}", args.url)).await?;
+        page.load(
+            &args.url,
+            args.wait_for.as_deref(),
+            Duration::from_millis(args.timeout_ms.max(1000)),
+        )
+        .await?;
+
+        if let Err(e) = run_steps(&page, &args.steps).await {
+            page.close().await;
+            return Err(McpError::Other(e));
+        }
+
+        let final_url = page
+            .url()
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| args.url.clone());
+
+        let steps_run = args.steps.len();
+        let (records, markdown, summary) = match &extraction {
+            Some(extraction) => {
+                let records = extract_structured(&page, extraction).await?;
+                let summary = format!(
+                    "Ran {steps_run} steps on {}, extracted {} records from {final_url}",
+                    args.url,
+                    records.len()
+                );
+                (Some(records), None, summary)
+            }
+            None => {
+                let markdown = page.markdown(&final_url).await?;
+                let summary = format!("Ran {steps_run} steps on {}, now at {final_url}\n\n{markdown}", args.url);
+                (None, Some(markdown), summary)
+            }
+        };
+        page.close().await;
+
+        let output = InteractOutput {
+            url: args.url,
+            final_url,
+            steps_run,
+            records,
+            markdown,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
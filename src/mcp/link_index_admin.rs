@@ -0,0 +1,187 @@
+//! `link_index_admin` MCP tool - Maintenance for a crawl's link index
+//!
+//! Actions: STATS, PRUNE_DOMAIN, PRUNE_OLDER_THAN, VACUUM.
+//! Keeps long-lived output directories from accumulating pages and links
+//! left over from deleted or outdated crawls.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::manager::resolve_crawl_dir;
+use crate::link_index::{LinkIndex, open_local_index};
+use crate::link_index::maintenance::{LinkIndexStats, PruneResult};
+
+/// Tool name for link index maintenance
+pub const LINK_INDEX_ADMIN: &str = "link_index_admin";
+
+/// Maintenance action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LinkIndexAction {
+    /// Report page/link counts, per-domain totals and database size (default)
+    #[default]
+    Stats,
+    /// Remove all pages (and their links) of `domain`
+    PruneDomain,
+    /// Remove pages saved more than `older_than_days` days ago
+    PruneOlderThan,
+    /// Compact the database file
+    Vacuum,
+}
+
+/// Arguments for the `link_index_admin` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkIndexAdminArgs {
+    /// Action to perform
+    #[serde(default)]
+    pub action: LinkIndexAction,
+
+    /// URL that was crawled (used to locate the output directory)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Explicit crawl output directory (takes precedence over `url`)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    /// Domain to remove (PRUNE_DOMAIN)
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// Age threshold in days (PRUNE_OLDER_THAN)
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+}
+
+/// Output of the `link_index_admin` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkIndexAdminOutput {
+    pub action: LinkIndexAction,
+    pub output_dir: String,
+    /// Rows removed (prune actions only)
+    pub pruned: Option<PruneResult>,
+    /// Index statistics after the action
+    pub stats: LinkIndexStats,
+}
+
+impl ToolArgs for LinkIndexAdminArgs {
+    type Output = LinkIndexAdminOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = LINK_INDEX_ADMIN;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Inspect, prune and vacuum a crawl's link index";
+}
+
+/// Link index maintenance tool
+#[derive(Clone, Default)]
+pub struct LinkIndexAdminTool;
+
+impl LinkIndexAdminTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn run(index: &LinkIndex, args: &LinkIndexAdminArgs) -> Result<Option<PruneResult>, McpError> {
+        match args.action {
+            LinkIndexAction::Stats => Ok(None),
+            LinkIndexAction::PruneDomain => {
+                let domain = args.domain.as_deref().ok_or_else(|| {
+                    McpError::InvalidArguments("'domain' is required for PRUNE_DOMAIN".to_string())
+                })?;
+                index.prune_domain(domain).await.map(Some).map_err(McpError::Other)
+            }
+            LinkIndexAction::PruneOlderThan => {
+                let days = args.older_than_days.ok_or_else(|| {
+                    McpError::InvalidArguments(
+                        "'older_than_days' is required for PRUNE_OLDER_THAN".to_string(),
+                    )
+                })?;
+                let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 86_400;
+                index.prune_older_than(cutoff).await.map(Some).map_err(McpError::Other)
+            }
+            LinkIndexAction::Vacuum => index.vacuum().await.map(|()| None).map_err(McpError::Other),
+        }
+    }
+}
+
+impl Tool for LinkIndexAdminTool {
+    type Args = LinkIndexAdminArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        LINK_INDEX_ADMIN
+    }
+
+    fn description() -> &'static str {
+        "Maintain the link index of a crawl output directory. \
+         \n\n\
+         **Actions:**\n\
+         - STATS: Page/link counts, pages per domain, database size (default)\n\
+         - PRUNE_DOMAIN: Remove a domain's pages and links (requires domain)\n\
+         - PRUNE_OLDER_THAN: Remove pages saved more than older_than_days ago\n\
+         - VACUUM: Compact the database after pruning"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        true
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<LinkIndexAdminOutput>, McpError> {
+        let output_dir = resolve_crawl_dir(args.url.as_deref(), args.output_dir.as_deref(), ctx.pwd())?;
+
+        let index = open_local_index(&output_dir)
+            .await
+            .map_err(McpError::Other)?
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!(
+                    "Link index not found in {}. Crawl the site first.",
+                    output_dir.display()
+                ))
+            })?;
+        let result = match Self::run(&index, &args).await {
+            Ok(pruned) => index.stats().await.map(|stats| (pruned, stats)).map_err(McpError::Other),
+            Err(e) => Err(e),
+        };
+        index.close().await;
+        let (pruned, stats) = result?;
+
+        let mut summary = format!(
+            "Link index {}: {} pages, {} links, {} fetch results, {} bytes",
+            output_dir.display(),
+            stats.pages,
+            stats.links,
+            stats.fetch_results,
+            stats.db_size_bytes
+        );
+        if let Some(p) = pruned {
+            summary.push_str(&format!(
+                "\nRemoved {} pages, {} links, {} fetch results",
+                p.pages_removed, p.links_removed, p.fetch_results_removed
+            ));
+        }
+
+        let output = LinkIndexAdminOutput {
+            action: args.action,
+            output_dir: output_dir.to_string_lossy().to_string(),
+            pruned,
+            stats,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
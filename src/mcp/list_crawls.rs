@@ -0,0 +1,184 @@
+//! `list_crawls` MCP tool - Enumerate mirrored sites and active crawls
+//!
+//! Finished crawls are discovered from the `manifest.json` files under the
+//! crawl base directory; crawls still running on this connection come from
+//! the `CrawlRegistry`.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use super::manager::{ManifestManager, crawl_base_dir};
+use super::registry::CrawlRegistry;
+use super::types::{CrawlManifest, CrawlSessionProgress, CrawlStatus};
+
+/// Tool name for listing crawls
+pub const LIST_CRAWLS: &str = "list_crawls";
+
+/// Arguments for the `list_crawls` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListCrawlsArgs {
+    /// Base directory holding one output directory per crawled domain
+    /// (defaults to the directory `scrape_url` writes to)
+    #[serde(default)]
+    pub base_dir: Option<String>,
+
+    /// Only list crawls whose start URL contains this text
+    #[serde(default)]
+    pub url_contains: Option<String>,
+}
+
+/// Summary of one finished crawl
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlListing {
+    /// Crawl identifier recorded in the manifest
+    pub crawl_id: String,
+    /// URL the crawl started from
+    pub start_url: String,
+    /// Crawl output directory (pass to get_manifest / search_docs)
+    pub output_dir: String,
+    /// Final crawl status
+    pub status: CrawlStatus,
+    /// Pages saved by the crawl
+    pub total_pages: usize,
+    /// Start time (Unix seconds)
+    pub start_time: i64,
+    /// End time (Unix seconds)
+    pub end_time: Option<i64>,
+    /// Whether a search index was built
+    pub search_enabled: bool,
+    /// Call that continues the crawl if it was interrupted by a server restart
+    pub resume: Option<String>,
+}
+
+impl From<CrawlManifest> for CrawlListing {
+    fn from(manifest: CrawlManifest) -> Self {
+        let resume = (manifest.status == CrawlStatus::Interrupted).then(|| manifest.resume_hint());
+        Self {
+            crawl_id: manifest.crawl_id,
+            start_url: manifest.start_url,
+            output_dir: manifest.output_dir.to_string_lossy().to_string(),
+            status: manifest.status,
+            total_pages: manifest.total_pages,
+            start_time: manifest.start_time.timestamp(),
+            end_time: manifest.end_time.map(|t| t.timestamp()),
+            search_enabled: manifest.config_summary.enable_search,
+            resume,
+        }
+    }
+}
+
+/// Output of the `list_crawls` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ListCrawlsOutput {
+    /// Directory that was scanned for manifests
+    pub base_dir: String,
+    /// Crawls with a manifest on disk, newest first
+    pub crawls: Vec<CrawlListing>,
+    /// Crawls of this connection tracked in memory (running or recently finished)
+    pub active: Vec<CrawlSessionProgress>,
+}
+
+impl ToolArgs for ListCrawlsArgs {
+    type Output = ListCrawlsOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = LIST_CRAWLS;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "List sites already mirrored on this server and crawls currently running";
+}
+
+/// Crawl discovery tool
+#[derive(Clone)]
+pub struct ListCrawlsTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl ListCrawlsTool {
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl Tool for ListCrawlsTool {
+    type Args = ListCrawlsArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        LIST_CRAWLS
+    }
+
+    fn description() -> &'static str {
+        "List crawls available on this server: finished crawls found via their \
+         manifest.json (start URL, status, page count, output directory, \
+         timestamps) and crawls of this connection that are still tracked in \
+         memory. Use it to check what is already mirrored before crawling again."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<ListCrawlsOutput>, McpError> {
+        let connection_id = ctx.connection_id().unwrap_or("default");
+        let base_dir = crawl_base_dir(args.base_dir.as_deref(), ctx.pwd())?;
+
+        let crawls: Vec<CrawlListing> = ManifestManager::list(&base_dir)
+            .await?
+            .into_iter()
+            .filter(|m| {
+                args.url_contains
+                    .as_deref()
+                    .is_none_or(|needle| m.start_url.contains(needle))
+            })
+            .map(CrawlListing::from)
+            .collect();
+        let active = self.registry.crawl_status(connection_id, None).await;
+
+        let mut summary = format!(
+            "{} mirrored crawls in {}, {} active",
+            crawls.len(),
+            base_dir.display(),
+            active.iter().filter(|c| c.is_running()).count()
+        );
+        for crawl in &crawls {
+            let status = match &crawl.status {
+                CrawlStatus::Running => "running",
+                CrawlStatus::Completed => "completed",
+                CrawlStatus::Failed { .. } => "failed",
+                CrawlStatus::Interrupted => "interrupted",
+            };
+            let _ = write!(
+                summary,
+                "\n  [{status}] {} - {} pages ({})",
+                crawl.start_url, crawl.total_pages, crawl.output_dir
+            );
+            if let Some(resume) = &crawl.resume {
+                let _ = write!(summary, "\n      resume: {resume}");
+            }
+        }
+
+        let output = ListCrawlsOutput {
+            base_dir: base_dir.to_string_lossy().to_string(),
+            crawls,
+            active,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
@@ -0,0 +1,162 @@
+//! `locate_page` MCP tool - Original URL of a file in a crawl output tree
+//!
+//! Given any file under a crawl output directory (markdown, HTML, JSON or a
+//! screenshot), looks the page up in the crawl's link index and reports the
+//! URL it was saved from, when it was saved and which crawl saved it.
+
+use chrono::{DateTime, Utc};
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::manager::ManifestManager;
+use crate::link_index::{LinkIndex, open_local_index};
+
+/// Tool name for the reverse path lookup
+pub const LOCATE_PAGE: &str = "locate_page";
+
+/// Arguments for the `locate_page` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocatePageArgs {
+    /// File or page directory in a crawl output tree
+    pub path: String,
+
+    /// Crawl output directory; found by walking up from `path` when omitted
+    #[serde(default)]
+    pub output_dir: Option<String>,
+}
+
+/// Output of the `locate_page` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocatePageOutput {
+    /// Crawl output directory holding the link index
+    pub output_dir: String,
+    /// URL the page was saved from
+    pub url: String,
+    /// File the page was registered under
+    pub local_path: String,
+    /// When the page was saved (Unix seconds)
+    pub saved_at: i64,
+    /// Crawl that saved the page, when it is the crawl recorded in the manifest
+    pub crawl_id: Option<String>,
+}
+
+impl ToolArgs for LocatePageArgs {
+    type Output = LocatePageOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = LOCATE_PAGE;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Find the original URL, crawl and save time of a file in a crawl output directory";
+}
+
+/// Reverse path lookup tool
+#[derive(Clone, Default)]
+pub struct LocatePageTool;
+
+impl LocatePageTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Tool for LocatePageTool {
+    type Args = LocatePageArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        LOCATE_PAGE
+    }
+
+    fn description() -> &'static str {
+        "Given a file from a crawl output directory (index.md, index.html, \
+         index.json, a screenshot or the page directory), return the URL it was \
+         crawled from, when it was saved and the crawl id. Uses the crawl's link \
+         index, so it works for paths that were shortened or escaped on disk."
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<LocatePageOutput>, McpError> {
+        let resolve = |path: &str| {
+            let path = PathBuf::from(path);
+            match ctx.pwd() {
+                Some(pwd) if path.is_relative() => pwd.join(path),
+                _ => path,
+            }
+        };
+        let path = resolve(&args.path);
+
+        let output_dir = match args.output_dir.as_deref() {
+            Some(dir) => resolve(dir),
+            None => LinkIndex::find_output_dir(&path).ok_or_else(|| {
+                McpError::ResourceNotFound(format!(
+                    "{} is not inside a crawl output directory (no link index found above it)",
+                    path.display()
+                ))
+            })?,
+        };
+        let index = open_local_index(&output_dir)
+            .await
+            .map_err(McpError::Other)?
+            .ok_or_else(|| {
+                McpError::ResourceNotFound(format!(
+                    "Link index not found in {}. Crawl the site first.",
+                    output_dir.display()
+                ))
+            })?;
+        let result = index.find_saved_page(&path).await;
+        index.close().await;
+        let page = result.map_err(McpError::Other)?.ok_or_else(|| {
+            McpError::ResourceNotFound(format!(
+                "No page in the link index of {} was saved at {}",
+                output_dir.display(),
+                path.display()
+            ))
+        })?;
+
+        // The manifest describes the most recent crawl; it saved the page if
+        // the save time falls inside that crawl's run
+        let crawl_id = ManifestManager::load(&output_dir).await.ok().and_then(|manifest| {
+            let saved_at = DateTime::<Utc>::from_timestamp(page.saved_at, 0)?;
+            let ended = manifest.end_time.unwrap_or_else(Utc::now);
+            (manifest.start_time.timestamp() <= page.saved_at && saved_at <= ended).then_some(manifest.crawl_id)
+        });
+
+        let saved = DateTime::<Utc>::from_timestamp(page.saved_at, 0)
+            .map_or_else(|| page.saved_at.to_string(), |t| t.to_rfc3339());
+        let summary = format!(
+            "{} was saved from {} at {}{}",
+            page.local_path.display(),
+            page.url,
+            saved,
+            crawl_id.as_deref().map(|id| format!(" by crawl {id}")).unwrap_or_default()
+        );
+
+        let output = LocatePageOutput {
+            output_dir: output_dir.to_string_lossy().to_string(),
+            url: page.url,
+            local_path: page.local_path.to_string_lossy().to_string(),
+            saved_at: page.saved_at,
+            crawl_id,
+        };
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
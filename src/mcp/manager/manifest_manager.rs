@@ -79,6 +79,41 @@ impl ManifestManager {
         Ok(manifest)
     }
 
+    /// Load the manifests of all crawls under `base_dir` (one output directory per domain)
+    ///
+    /// Directories without a readable manifest are skipped. Newest crawls first.
+    pub async fn list(base_dir: &Path) -> Result<Vec<CrawlManifest>, McpError> {
+        let mut manifests = Vec::new();
+
+        let mut entries = match fs::read_dir(base_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(manifests),
+            Err(e) => {
+                return Err(McpError::Manifest(format!(
+                    "Failed to read crawl directory {base_dir:?}: {e}"
+                )));
+            }
+        };
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| McpError::Manifest(format!("Failed to read crawl directory entry: {e}")))?
+        {
+            let output_dir = entry.path();
+            if !Self::exists(&output_dir).await {
+                continue;
+            }
+            match Self::load(&output_dir).await {
+                Ok(manifest) => manifests.push(manifest),
+                Err(e) => log::warn!("Skipping unreadable manifest in {}: {e}", output_dir.display()),
+            }
+        }
+
+        manifests.sort_by_key(|m| std::cmp::Reverse(m.start_time));
+        Ok(manifests)
+    }
+
     /// Check if manifest exists for `output_dir`
     pub async fn exists(output_dir: &Path) -> bool {
         let manifest_path = output_dir.join(Self::MANIFEST_FILENAME);
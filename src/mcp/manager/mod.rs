@@ -9,6 +9,7 @@
 //! - `search_cache`: Search engine caching with LRU eviction
 //! - `manifest_manager`: Atomic manifest file persistence
 //! - `path_utils`: URL to filesystem path conversion
+//! - `session_store`: Running crawl records that survive server restarts
 
 // Module declarations
 mod timestamp_utils;
@@ -16,6 +17,7 @@ mod session_manager;
 mod search_cache;
 mod manifest_manager;
 mod path_utils;
+mod session_store;
 
 // Re-export types from parent module for internal use
 use super::types;
@@ -24,4 +26,5 @@ use super::types;
 pub use session_manager::CrawlSessionManager;
 pub use search_cache::{SearchEngineCache, SearchEngineCacheEntry};
 pub use manifest_manager::ManifestManager;
-pub use path_utils::url_to_output_dir;
+pub use path_utils::{crawl_base_dir, output_root, resolve_crawl_dir, set_output_root, url_to_output_dir};
+pub use session_store::{SessionRecord, SessionStore};
@@ -5,9 +5,32 @@
 
 use kodegen_config::KodegenConfig;
 use kodegen_mcp_schema::McpError;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use url::Url;
 
+/// Server-wide output root set at startup (see [`set_output_root`])
+static OUTPUT_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `root` for every crawl that does not name its own output directory
+///
+/// Relative paths are resolved against the server's current directory. Only
+/// the first call takes effect; returns `false` when a root was already set.
+pub fn set_output_root(root: impl AsRef<Path>) -> bool {
+    let root = root.as_ref();
+    let root = if root.is_absolute() {
+        root.to_path_buf()
+    } else {
+        std::env::current_dir().map_or_else(|_| root.to_path_buf(), |cwd| cwd.join(root))
+    };
+    OUTPUT_ROOT.set(root).is_ok()
+}
+
+/// The output root set with [`set_output_root`], if any
+pub fn output_root() -> Option<&'static Path> {
+    OUTPUT_ROOT.get().map(PathBuf::as_path)
+}
+
 /// Convert URL to filesystem-safe output directory path
 ///
 /// Extracts domain from URL and sanitizes for filesystem use.
@@ -60,12 +83,27 @@ pub fn url_to_output_dir(
         .replace([':', '/', '\\'], "_") // Windows path separator
         .replace("..", "_"); // Directory traversal protection
 
-    // Determine base directory with proper precedence:
-    // 1. Explicit base_dir parameter (highest priority)
-    // 2. ${git_root}/.kodegen/citescrape (if in git repo)
-    // 3. ${data_dir}/citescrape (fallback)
+    Ok(crawl_base_dir(base_dir, client_pwd)?.join(safe_domain))
+}
+
+/// Directory holding one output directory per crawled domain
+///
+/// Precedence:
+/// 1. Explicit `base_dir` parameter (highest priority)
+/// 2. Server output root (see [`set_output_root`])
+/// 3. `${git_root}/.kodegen/citescrape` (if in git repo)
+/// 4. `${data_dir}/citescrape` (fallback)
+///
+/// Relative paths are resolved against the client PWD (or the server's
+/// current directory when there is none).
+pub fn crawl_base_dir(
+    base_dir: Option<&str>,
+    client_pwd: Option<&std::path::Path>,
+) -> Result<PathBuf, McpError> {
     let base = if let Some(dir) = base_dir {
         PathBuf::from(dir)
+    } else if let Some(root) = output_root() {
+        root.to_path_buf()
     } else if let Ok(local_config) = KodegenConfig::local_config_dir() {
         local_config.join("citescrape")
     } else {
@@ -74,11 +112,9 @@ pub fn url_to_output_dir(
             .unwrap_or_else(|_| PathBuf::from(".kodegen/citescrape"))
     };
 
-    let output_dir = base.join(safe_domain);
-
     // Convert to absolute path using client's PWD (if available)
-    let output_dir = if output_dir.is_absolute() {
-        output_dir
+    let base = if base.is_absolute() {
+        base
     } else {
         // Use client's pwd if available (HTTP MCP case), fallback to server's pwd (library/test case)
         let base_path = if let Some(pwd) = client_pwd {
@@ -88,8 +124,37 @@ pub fn url_to_output_dir(
             std::env::current_dir()
                 .map_err(|e| McpError::InvalidUrl(format!("Failed to get current directory: {e}")))?
         };
-        base_path.join(&output_dir)
+        base_path.join(&base)
     };
 
-    Ok(output_dir)
+    Ok(base)
+}
+
+/// Resolve the crawl output directory for tools that operate on a finished crawl
+///
+/// An explicit `output_dir` wins (relative paths resolve against the client PWD),
+/// otherwise the directory is derived from `url` via [`url_to_output_dir`].
+pub fn resolve_crawl_dir(
+    url: Option<&str>,
+    output_dir: Option<&str>,
+    client_pwd: Option<&std::path::Path>,
+) -> Result<PathBuf, McpError> {
+    if let Some(dir) = output_dir {
+        let dir = PathBuf::from(dir);
+        if dir.is_absolute() {
+            return Ok(dir);
+        }
+        let base_path = match client_pwd {
+            Some(pwd) => pwd.to_path_buf(),
+            None => std::env::current_dir()
+                .map_err(|e| McpError::InvalidUrl(format!("Failed to get current directory: {e}")))?,
+        };
+        Ok(base_path.join(dir))
+    } else if let Some(url) = url {
+        url_to_output_dir(url, None, client_pwd)
+    } else {
+        Err(McpError::InvalidArguments(
+            "Either 'url' or 'output_dir' is required".to_string(),
+        ))
+    }
 }
@@ -1,11 +1,14 @@
 //! Search engine caching with LRU eviction and lock-free timestamp tracking
 //!
 //! Provides efficient caching of Tantivy search engines with automatic cleanup
-//! of idle engines and LRU eviction when cache reaches capacity.
+//! of idle engines and LRU eviction when cache reaches capacity. The cache also
+//! carries the health of the web search engines used by `web_search`, its
+//! short-lived cache of search results and its per-engine request pacing.
 
 use super::timestamp_utils::{instant_to_nanos, nanos_to_instant};
 use crate::config::CrawlConfig;
 use crate::search::{IndexingSender, SearchEngine};
+use crate::web_search::{EngineHealth, EnginePacer, SerpCache};
 use kodegen_mcp_schema::McpError;
 use log::{debug, error, info};
 use std::collections::HashMap;
@@ -73,6 +76,12 @@ pub struct SearchEngineCacheEntry {
 #[derive(Clone)]
 pub struct SearchEngineCache {
     engines: Arc<Mutex<HashMap<PathBuf, SearchEngineCacheEntry>>>,
+    /// Failure and CAPTCHA tracking for web search engines
+    web_engine_health: Arc<EngineHealth>,
+    /// Recent `web_search` results (TTL from `CITESCRAPE_SERP_CACHE_TTL_SECS`)
+    serp_cache: Arc<SerpCache>,
+    /// Per-engine `web_search` pacing (`CITESCRAPE_SEARCH_*` settings)
+    web_search_pacer: Arc<EnginePacer>,
 }
 
 impl SearchEngineCache {
@@ -89,9 +98,49 @@ impl SearchEngineCache {
             engines: Arc::new(Mutex::new(HashMap::with_capacity(
                 SEARCH_CACHE_INITIAL_CAPACITY,
             ))),
+            web_engine_health: Arc::new(EngineHealth::default()),
+            serp_cache: Arc::new(SerpCache::from_env()),
+            web_search_pacer: Arc::new(EnginePacer::from_env()),
         }
     }
 
+    /// Health of the web search engines, shared by every `web_search` call
+    #[must_use]
+    pub fn web_engine_health(&self) -> &Arc<EngineHealth> {
+        &self.web_engine_health
+    }
+
+    /// Recent web search results, shared by every `web_search` call
+    #[must_use]
+    pub fn serp_cache(&self) -> &Arc<SerpCache> {
+        &self.serp_cache
+    }
+
+    /// Request pacing for the web search engines, shared by every `web_search` call
+    #[must_use]
+    pub fn web_search_pacer(&self) -> &Arc<EnginePacer> {
+        &self.web_search_pacer
+    }
+
+    /// Engine over the search index already built in `output_dir`
+    ///
+    /// Returns `None` when the crawl was not indexed; otherwise opens (or
+    /// reuses) the engine with a minimal config naming only the storage and
+    /// index locations.
+    pub async fn get_existing(&self, output_dir: PathBuf) -> Result<Option<SearchEngineCacheEntry>, McpError> {
+        let search_index_dir = output_dir.join(".search_index");
+        if !search_index_dir.join("meta.json").exists() {
+            return Ok(None);
+        }
+        let config = CrawlConfig {
+            storage_dir: output_dir.clone(),
+            start_url: "http://localhost".to_string(),
+            search_index_dir: Some(search_index_dir),
+            ..Default::default()
+        };
+        self.get_or_init(output_dir, &config).await.map(Some)
+    }
+
     /// Get cached engine or initialize new one
     ///
     /// Returns both the `SearchEngine` and optional `IndexingSender` for use in `CrawlConfig`
@@ -100,7 +100,7 @@ impl CrawlSessionManager {
             let age = now.signed_duration_since(session.start_time);
             let is_terminal = matches!(
                 session.status,
-                CrawlStatus::Completed | CrawlStatus::Failed { .. }
+                CrawlStatus::Completed | CrawlStatus::Failed { .. } | CrawlStatus::Interrupted
             );
 
             // Keep running sessions, remove terminal sessions older than cutoff
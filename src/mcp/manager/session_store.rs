@@ -0,0 +1,208 @@
+//! Persistence of running crawl sessions across server restarts
+//!
+//! `CrawlRegistry` records every crawl it starts in `sessions.json` and drops
+//! the record once the crawl task finishes. Records still present when the
+//! server starts belong to crawls the previous process never finished; they
+//! are either resumed or marked interrupted in their manifest.
+
+use super::types::CrawlManifest;
+use crate::config::CrawlProfile;
+use crate::content_saver::markdown_converter::Typography;
+use anyhow::{Context, Result};
+use kodegen_config::KodegenConfig;
+use kodegen_mcp_schema::citescrape::ScrapeUrlArgs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// A crawl that was running when its record was last written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// MCP connection that started the crawl
+    pub connection_id: String,
+    /// Crawl instance ID within the connection
+    pub crawl_id: u32,
+    /// Arguments the crawl was started with
+    pub args: ScrapeUrlArgs,
+    /// Named crawl profile applied on top of `args`
+    #[serde(default)]
+    pub profile: Option<CrawlProfile>,
+    /// Markdown typography options the crawl was started with
+    #[serde(default)]
+    pub typography: Typography,
+    /// Extra URLs queued at depth 0
+    #[serde(default)]
+    pub seed_urls: Vec<String>,
+    /// Manifest as of the crawl start
+    pub manifest: CrawlManifest,
+}
+
+/// JSON file of [`SessionRecord`]s for crawls in progress
+pub struct SessionStore {
+    path: PathBuf,
+    records: Mutex<Vec<SessionRecord>>,
+}
+
+impl SessionStore {
+    const FILENAME: &'static str = "sessions.json";
+
+    /// Store file location
+    ///
+    /// `CITESCRAPE_SESSION_STORE` overrides the default
+    /// `${data_dir}/citescrape/sessions.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var("CITESCRAPE_SESSION_STORE") {
+            return Some(PathBuf::from(path));
+        }
+        KodegenConfig::data_dir()
+            .ok()
+            .map(|data| data.join("citescrape").join(Self::FILENAME))
+    }
+
+    /// Whether interrupted crawls should be resumed at startup
+    /// (`CITESCRAPE_RESUME_CRAWLS=1`) rather than only reported
+    pub fn resume_from_env() -> bool {
+        std::env::var("CITESCRAPE_RESUME_CRAWLS")
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+    }
+
+    /// Open the store at `path`, loading records left by a previous process
+    ///
+    /// A missing file is an empty store; an unreadable one is logged and
+    /// replaced.
+    pub async fn open(path: PathBuf) -> Self {
+        let records = match tokio::fs::read_to_string(&path).await {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable crawl session store {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                log::warn!("Failed to read crawl session store {}: {e}", path.display());
+                Vec::new()
+            }
+        };
+        Self {
+            path,
+            records: Mutex::new(records),
+        }
+    }
+
+    /// Store file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Remove and return every stored record
+    ///
+    /// Called once at startup, when all stored crawls are interrupted ones.
+    pub async fn take_all(&self) -> Result<Vec<SessionRecord>> {
+        let mut records = self.records.lock().await;
+        let taken = std::mem::take(&mut *records);
+        if !taken.is_empty() {
+            Self::write(&self.path, &records).await?;
+        }
+        Ok(taken)
+    }
+
+    /// Record a crawl as running, replacing an older record of the same crawl
+    pub async fn insert(&self, record: SessionRecord) -> Result<()> {
+        let mut records = self.records.lock().await;
+        records.retain(|r| !(r.connection_id == record.connection_id && r.crawl_id == record.crawl_id));
+        records.push(record);
+        Self::write(&self.path, &records).await
+    }
+
+    /// Forget a crawl that finished (or was cancelled)
+    pub async fn remove(&self, connection_id: &str, crawl_id: u32) -> Result<()> {
+        let mut records = self.records.lock().await;
+        let before = records.len();
+        records.retain(|r| !(r.connection_id == connection_id && r.crawl_id == crawl_id));
+        if records.len() == before {
+            return Ok(());
+        }
+        Self::write(&self.path, &records).await
+    }
+
+    /// Write records atomically (temp file + rename)
+    async fn write(path: &Path, records: &[SessionRecord]) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(records).context("Failed to serialize crawl sessions")?;
+        let temp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&temp_path, json)
+            .await
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        tokio::fs::rename(&temp_path, path)
+            .await
+            .with_context(|| format!("Failed to replace {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::types::{ConfigSummary, CrawlStatus};
+
+    fn record(crawl_id: u32) -> SessionRecord {
+        let args: ScrapeUrlArgs = serde_json::from_value(serde_json::json!({
+            "action": "CRAWL",
+            "crawl_id": crawl_id,
+            "url": "https://docs.rs/tokio",
+        }))
+        .unwrap();
+        SessionRecord {
+            connection_id: "conn".to_string(),
+            crawl_id,
+            args,
+            profile: None,
+            typography: Typography::default(),
+            seed_urls: Vec::new(),
+            manifest: CrawlManifest {
+                crawl_id: crawl_id.to_string(),
+                start_url: "https://docs.rs/tokio".to_string(),
+                output_dir: PathBuf::from("/tmp/docs.rs"),
+                search_index_dir: PathBuf::from("/tmp/docs.rs/.search_index"),
+                start_time: chrono::Utc::now(),
+                end_time: None,
+                status: CrawlStatus::Running,
+                total_pages: 0,
+                config_summary: ConfigSummary {
+                    start_url: "https://docs.rs/tokio".to_string(),
+                    max_depth: 3,
+                    limit: None,
+                    save_markdown: true,
+                    save_screenshots: false,
+                    enable_search: true,
+                    crawl_rate_rps: 2.0,
+                    conversion: Default::default(),
+                },
+                site_audit: None,
+                pages: Default::default(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_survive_reopen() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sessions.json");
+
+        let store = SessionStore::open(path.clone()).await;
+        store.insert(record(0)).await.unwrap();
+        store.insert(record(1)).await.unwrap();
+        store.insert(record(1)).await.unwrap();
+        store.remove("conn", 0).await.unwrap();
+
+        let reopened = SessionStore::open(path.clone()).await;
+        let interrupted = reopened.take_all().await.unwrap();
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].crawl_id, 1);
+        assert_eq!(interrupted[0].args.url.as_deref(), Some("https://docs.rs/tokio"));
+
+        assert!(SessionStore::open(path).await.take_all().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+//! Prometheus metrics for the citescrape server
+//!
+//! Counters for crawls, pages and searches are kept process-wide in
+//! [`metrics()`] and updated by crawl sessions and the search tools. Gauges
+//! (running crawls, browser pool, index sizes) and the web search engines'
+//! health counters are read from the [`CrawlRegistry`] when scraped. [`router`] answers `GET /metrics` in the
+//! Prometheus text format on the endpoint server (see [`super::endpoints`]).
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use axum::Router;
+use axum::http::header;
+use axum::routing::get;
+
+use crate::crawl_events::CrawlEvent;
+use crate::mcp::registry::CrawlRegistry;
+use crate::web_search::EngineHealthSnapshot;
+
+/// Upper bounds (seconds) of the search latency histogram buckets
+const LATENCY_BUCKETS: [f64; 10] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+static METRICS: LazyLock<ServerMetrics> = LazyLock::new(ServerMetrics::default);
+
+/// Process-wide counters
+pub fn metrics() -> &'static ServerMetrics {
+    &METRICS
+}
+
+/// Which search a latency sample belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchKind {
+    /// Full-text search of a crawled site's index
+    Local,
+    /// `web_search` through a search engine
+    Web,
+}
+
+impl SearchKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Web => "web",
+        }
+    }
+}
+
+/// Request count, errors and latency histogram of one kind of search
+#[derive(Debug, Default)]
+pub struct SearchMetrics {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    errors: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl SearchMetrics {
+    fn record(&self, elapsed: Duration, ok: bool) {
+        let secs = elapsed.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            if secs <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.sum_micros.fetch_add(micros, Ordering::Relaxed);
+        if !ok {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Counters of crawl and search activity since the server started
+#[derive(Debug, Default)]
+pub struct ServerMetrics {
+    crawls_started: AtomicU64,
+    crawls_completed: AtomicU64,
+    crawls_failed: AtomicU64,
+    pages_crawled: AtomicU64,
+    pages_failed: AtomicU64,
+    cache_hits: AtomicU64,
+    bytes_downloaded: AtomicU64,
+    local_search: SearchMetrics,
+    web_search: SearchMetrics,
+}
+
+impl ServerMetrics {
+    /// Count a crawl event
+    pub fn record_event(&self, event: &CrawlEvent) {
+        match event {
+            CrawlEvent::CrawlStarted { .. } => self.crawls_started.fetch_add(1, Ordering::Relaxed),
+            CrawlEvent::PageCrawled { metadata, .. } => {
+                self.bytes_downloaded
+                    .fetch_add(metadata.html_size as u64, Ordering::Relaxed);
+                self.pages_crawled.fetch_add(1, Ordering::Relaxed)
+            }
+            CrawlEvent::Error { .. } => self.pages_failed.fetch_add(1, Ordering::Relaxed),
+            CrawlEvent::CacheHit { .. } => self.cache_hits.fetch_add(1, Ordering::Relaxed),
+            _ => 0,
+        };
+    }
+
+    /// Count a crawl that ran to its end, successfully or not
+    pub fn record_crawl_finished(&self, ok: bool) {
+        let counter = if ok { &self.crawls_completed } else { &self.crawls_failed };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record one search request
+    pub fn record_search(&self, kind: SearchKind, elapsed: Duration, ok: bool) {
+        match kind {
+            SearchKind::Local => self.local_search.record(elapsed, ok),
+            SearchKind::Web => self.web_search.record(elapsed, ok),
+        }
+    }
+
+    /// Append the counters in Prometheus text format
+    pub fn write_to(&self, out: &mut String) {
+        let counters = [
+            ("citescrape_crawls_started_total", "Crawls started", &self.crawls_started),
+            ("citescrape_crawls_completed_total", "Crawls that finished successfully", &self.crawls_completed),
+            ("citescrape_crawls_failed_total", "Crawls that ended with an error", &self.crawls_failed),
+            ("citescrape_pages_crawled_total", "Pages saved", &self.pages_crawled),
+            ("citescrape_pages_failed_total", "Pages that failed for good", &self.pages_failed),
+            ("citescrape_cache_hits_total", "Pages skipped because their ETag matched", &self.cache_hits),
+            ("citescrape_downloaded_bytes_total", "HTML bytes downloaded", &self.bytes_downloaded),
+        ];
+        for (name, help, value) in counters {
+            write_metric(out, name, help, "counter", &[(None, value.load(Ordering::Relaxed) as f64)]);
+        }
+
+        let kinds = [(SearchKind::Local, &self.local_search), (SearchKind::Web, &self.web_search)];
+        let samples = |f: fn(&SearchMetrics) -> f64| {
+            kinds
+                .iter()
+                .map(|(kind, m)| (Some(format!("kind=\"{}\"", kind.label())), f(m)))
+                .collect::<Vec<_>>()
+        };
+        write_metric(
+            out,
+            "citescrape_search_errors_total",
+            "Search requests that failed",
+            "counter",
+            &samples(|m| m.errors.load(Ordering::Relaxed) as f64),
+        );
+
+        let name = "citescrape_search_duration_seconds";
+        let _ = writeln!(out, "# HELP {name} Search request latency");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (kind, m) in kinds {
+            let kind = kind.label();
+            for (bucket, bound) in m.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{kind=\"{kind}\",le=\"{bound}\"}} {}",
+                    bucket.load(Ordering::Relaxed)
+                );
+            }
+            let count = m.count.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}_bucket{{kind=\"{kind}\",le=\"+Inf\"}} {count}");
+            let sum = m.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+            let _ = writeln!(out, "{name}_sum{{kind=\"{kind}\"}} {sum}");
+            let _ = writeln!(out, "{name}_count{{kind=\"{kind}\"}} {count}");
+        }
+    }
+}
+
+/// One metric family with its samples; `labels` is the text between braces
+fn write_metric(out: &mut String, name: &str, help: &str, kind: &str, samples: &[(Option<String>, f64)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        match labels {
+            Some(labels) => {
+                let _ = writeln!(out, "{name}{{{labels}}} {value}");
+            }
+            None => {
+                let _ = writeln!(out, "{name} {value}");
+            }
+        }
+    }
+}
+
+/// Append the web search engines' health counters, one sample per engine
+fn write_engine_health(out: &mut String, engines: &[EngineHealthSnapshot]) {
+    type Field = fn(&EngineHealthSnapshot) -> f64;
+    let families: [(&str, &str, &str, Field); 5] = [
+        ("citescrape_web_search_engine_successes_total", "Engine searches that returned results", "counter", |e| e.successes as f64),
+        ("citescrape_web_search_engine_failures_total", "Engine searches that failed", "counter", |e| e.failures as f64),
+        ("citescrape_web_search_engine_captchas_total", "Engine searches blocked by a CAPTCHA", "counter", |e| e.captchas as f64),
+        (
+            "citescrape_web_search_selector_fallbacks_total",
+            "Engine searches answered by a fallback selector strategy",
+            "counter",
+            |e| e.selector_fallbacks as f64,
+        ),
+        ("citescrape_web_search_engine_demoted_seconds", "Seconds left in the engine's cooldown", "gauge", |e| e.demoted_for_secs as f64),
+    ];
+    for (name, help, kind, value) in families {
+        let samples: Vec<_> = engines
+            .iter()
+            .map(|e| (Some(format!("engine=\"{}\"", e.engine.as_str())), value(e)))
+            .collect();
+        write_metric(out, name, help, kind, &samples);
+    }
+}
+
+/// All metrics in Prometheus text format
+pub async fn render(registry: &CrawlRegistry) -> String {
+    let mut out = String::new();
+    metrics().write_to(&mut out);
+
+    let sessions = registry.sessions().await;
+    let mut running = 0;
+    let mut output_dirs: Vec<PathBuf> = Vec::new();
+    for session in &sessions {
+        if session.progress().await.is_running() {
+            running += 1;
+        }
+        output_dirs.push(session.output_dir().to_path_buf());
+    }
+    output_dirs.sort();
+    output_dirs.dedup();
+
+    let pool = registry.browser_pool();
+    let gauges = [
+        ("citescrape_crawl_sessions", "Crawl sessions registered", sessions.len()),
+        ("citescrape_crawls_running", "Crawls running or paused", running),
+        ("citescrape_browser_pool_in_use", "Browsers checked out of the pool", pool.in_use()),
+        ("citescrape_browser_pool_available", "Warm browsers waiting in the pool", pool.available()),
+        ("citescrape_browser_pool_waiting", "Acquire calls waiting for a browser", pool.waiting()),
+        ("citescrape_browser_pool_max", "Maximum browsers the pool may hold", pool.max_pool_size()),
+        ("citescrape_search_engines_cached", "Search indexes held open", registry.engine_cache().cache_size().await),
+    ];
+    for (name, help, value) in gauges {
+        write_metric(&mut out, name, help, "gauge", &[(None, value as f64)]);
+    }
+    write_engine_health(&mut out, &registry.engine_cache().web_engine_health().snapshot());
+
+    // Walking index directories is blocking file system work
+    let sizes = tokio::task::spawn_blocking(move || {
+        output_dirs
+            .into_iter()
+            .map(|dir| {
+                let search = dir_size(&dir.join(".search_index"));
+                let links = dir_size(&dir.join(".citescrape").join("link_index.sqlite"));
+                (dir, search, links)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+    let label = |dir: &Path| Some(format!("output_dir=\"{}\"", escape_label(&dir.to_string_lossy())));
+    write_metric(
+        &mut out,
+        "citescrape_search_index_bytes",
+        "Size of the Tantivy search index",
+        "gauge",
+        &sizes.iter().map(|(dir, search, _)| (label(dir), *search as f64)).collect::<Vec<_>>(),
+    );
+    write_metric(
+        &mut out,
+        "citescrape_link_index_bytes",
+        "Size of the SQLite link index",
+        "gauge",
+        &sizes.iter().map(|(dir, _, links)| (label(dir), *links as f64)).collect::<Vec<_>>(),
+    );
+    out
+}
+
+/// Size of a file, or of every file under a directory
+fn dir_size(path: &Path) -> u64 {
+    if path.is_file() {
+        return path.metadata().map_or(0, |m| m.len());
+    }
+    jwalk::WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// `GET /metrics`, ready to be served by the endpoint server
+pub fn router(registry: Arc<CrawlRegistry>) -> Router {
+    Router::new().route(
+        "/metrics",
+        get(move || async move {
+            (
+                [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+                render(&registry).await,
+            )
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_and_histogram_text() {
+        let m = ServerMetrics::default();
+        m.record_event(&CrawlEvent::crawl_started("https://example.com".into(), "out".into(), 2));
+        m.record_event(&CrawlEvent::cache_hit("https://example.com".into()));
+        m.record_crawl_finished(true);
+        m.record_search(SearchKind::Local, Duration::from_millis(30), true);
+        m.record_search(SearchKind::Local, Duration::from_secs(2), false);
+
+        let mut out = String::new();
+        m.write_to(&mut out);
+        assert!(out.contains("# TYPE citescrape_crawls_started_total counter\ncitescrape_crawls_started_total 1\n"));
+        assert!(out.contains("citescrape_cache_hits_total 1\n"));
+        assert!(out.contains("citescrape_crawls_completed_total 1\n"));
+        assert!(out.contains("citescrape_search_errors_total{kind=\"local\"} 1\n"));
+        assert!(out.contains("citescrape_search_duration_seconds_bucket{kind=\"local\",le=\"0.01\"} 0\n"));
+        assert!(out.contains("citescrape_search_duration_seconds_bucket{kind=\"local\",le=\"0.05\"} 1\n"));
+        assert!(out.contains("citescrape_search_duration_seconds_bucket{kind=\"local\",le=\"+Inf\"} 2\n"));
+        assert!(out.contains("citescrape_search_duration_seconds_count{kind=\"web\"} 0\n"));
+    }
+
+    #[test]
+    fn test_engine_health_text() {
+        use crate::web_search::{EngineHealth, SearchEngineKind};
+
+        let health = EngineHealth::default();
+        health.record_success(SearchEngineKind::Bing);
+        health.record_selector_fallback(SearchEngineKind::Bing, "json-ld");
+        health.record_failure(SearchEngineKind::DuckDuckGo, "DuckDuckGo CAPTCHA detected", true);
+
+        let mut out = String::new();
+        write_engine_health(&mut out, &health.snapshot());
+        let bing = SearchEngineKind::Bing.as_str();
+        let ddg = SearchEngineKind::DuckDuckGo.as_str();
+        assert!(out.contains(&format!(
+            "# TYPE citescrape_web_search_selector_fallbacks_total counter\n\
+             citescrape_web_search_selector_fallbacks_total{{engine=\"{bing}\"}} 1\n"
+        )));
+        assert!(out.contains(&format!("citescrape_web_search_engine_successes_total{{engine=\"{bing}\"}} 1\n")));
+        assert!(out.contains(&format!("citescrape_web_search_engine_captchas_total{{engine=\"{ddg}\"}} 1\n")));
+        assert!(!out.contains(&format!("citescrape_web_search_engine_demoted_seconds{{engine=\"{ddg}\"}} 0\n")));
+    }
+}
@@ -65,7 +65,7 @@
 //! // Create tools
 //! let scrape_tool = ScrapeUrlTool::new(registry.clone());
 //! let fetch_tool = FetchTool::new(registry.clone());
-//! let search_tool = WebSearchTool::new(browser_pool.clone());
+//! let search_tool = WebSearchTool::new(registry.clone());
 //!
 //! // Tools are ready to use
 //! # Ok(())
@@ -110,25 +110,77 @@
 //!
 //! Handle errors appropriately in your MCP server implementation.
 
+pub mod batch_fetch;
+pub mod broken_links;
+pub(crate) mod browser_page;
+pub mod crawl_cancel;
+pub mod crawl_pause;
+pub mod crawl_resources;
+pub mod crawl_status;
+pub mod diff_pages;
+pub mod execute_js;
+pub mod export_crawl;
+pub mod extract_structured;
+pub mod endpoints;
 pub mod fetch;
+pub mod fetch_feed;
+pub mod get_manifest;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod interact;
+pub mod link_index_admin;
+pub mod list_crawls;
+pub mod locate_page;
 pub mod manager;
+pub mod metrics;
+pub mod quota;
 pub mod registry;        // NEW
+pub mod resources;
+pub mod render_pdf;
+pub mod rest_api;
+pub mod robots_check;
+pub mod search_docs;
 pub mod session;         // NEW
+pub mod sitemap_probe;
 pub mod start_crawl;     // REFACTORED
 pub mod types;
 pub mod validation;
 pub mod web_search;
 
 // Re-export main types for convenience
-pub use types::{ActiveCrawlSession, ConfigSummary, CrawlManifest, CrawlStatus};
+pub use types::{
+    ActiveCrawlSession, ConfigSummary, ConversionSummary, CrawlManifest, CrawlSessionProgress, CrawlStatus,
+    PageOutcome, PageStatus,
+};
 
 // Re-export managers and utilities
-pub use manager::{CrawlSessionManager, ManifestManager, SearchEngineCache, url_to_output_dir};
+pub use manager::{CrawlSessionManager, ManifestManager, SearchEngineCache, SessionStore, url_to_output_dir};
+pub use quota::{CrawlQuota, SharedQuota};
 pub use registry::CrawlRegistry;   // NEW
 pub use session::CrawlSession;     // NEW
 pub use validation::ErrorContext;
 
 // Re-export tools
+pub use batch_fetch::BatchFetchTool;
+pub use broken_links::BrokenLinksTool;
+pub use crawl_cancel::CrawlCancelTool;
+pub use crawl_pause::CrawlPauseTool;
+pub use crawl_resources::CrawlResourcesTool;
+pub use crawl_status::CrawlStatusTool;
+pub use diff_pages::DiffPagesTool;
+pub use execute_js::ExecuteJsTool;
+pub use export_crawl::ExportCrawlTool;
+pub use extract_structured::ExtractStructuredTool;
 pub use fetch::FetchTool;
-pub use start_crawl::ScrapeUrlTool;
+pub use fetch_feed::FetchFeedTool;
+pub use get_manifest::GetManifestTool;
+pub use interact::InteractTool;
+pub use link_index_admin::LinkIndexAdminTool;
+pub use list_crawls::ListCrawlsTool;
+pub use locate_page::LocatePageTool;
+pub use render_pdf::RenderPdfTool;
+pub use robots_check::RobotsCheckTool;
+pub use search_docs::SearchDocsTool;
+pub use sitemap_probe::SitemapProbeTool;
+pub use start_crawl::{ScrapeUrlTool, ScrapeUrlToolArgs};
 pub use web_search::WebSearchTool;
@@ -0,0 +1,178 @@
+//! Per-connection crawl limits
+//!
+//! Server-side limits applied by [`super::registry::CrawlRegistry`] so a single
+//! MCP client cannot monopolize the browser pool or point the crawler at
+//! internal hosts. Limits are read from the environment at startup:
+//!
+//! - `CITESCRAPE_MAX_CRAWLS_PER_CONNECTION`: concurrent crawls per connection
+//! - `CITESCRAPE_MAX_PAGES_PER_CRAWL`: page cap for every crawl
+//! - `CITESCRAPE_ALLOWED_DOMAINS`: comma-separated domains (subdomains included)
+//! - `CITESCRAPE_BLOCK_PRIVATE_HOSTS`: `1`/`true` rejects loopback, private and
+//!   link-local addresses and `localhost` / `.local` / `.internal` names
+//!
+//! Unset variables mean no limit. The domain and private-host rules become
+//! the process-wide [`UrlPolicy`] when the registry takes the quota, so they
+//! apply to every navigation, not only to crawl start URLs.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use anyhow::Result;
+
+use crate::url_policy::UrlPolicy;
+
+/// Crawl limits enforced for every MCP connection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrawlQuota {
+    /// Crawls one connection may run at the same time
+    pub max_concurrent_crawls: Option<usize>,
+    /// Upper bound on pages per crawl, applied on top of the requested limit
+    pub max_pages_per_crawl: Option<usize>,
+    /// Domains crawls may target; `None` allows any public domain
+    pub allowed_domains: Option<Vec<String>>,
+    /// Reject hosts on loopback, private and link-local networks
+    pub block_private_hosts: bool,
+}
+
+impl CrawlQuota {
+    /// Limits from `CITESCRAPE_*` environment variables
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let number = |name: &str| {
+            let value = lookup(name)?;
+            match value.trim().parse() {
+                Ok(n) => Some(n),
+                Err(_) => {
+                    log::warn!("Ignoring {name}={value}: not a number");
+                    None
+                }
+            }
+        };
+        let allowed_domains = lookup("CITESCRAPE_ALLOWED_DOMAINS").map(|value| parse_domain_list(&value));
+        Self {
+            max_concurrent_crawls: number("CITESCRAPE_MAX_CRAWLS_PER_CONNECTION"),
+            max_pages_per_crawl: number("CITESCRAPE_MAX_PAGES_PER_CRAWL"),
+            allowed_domains,
+            block_private_hosts: lookup("CITESCRAPE_BLOCK_PRIVATE_HOSTS").is_some_and(|v| is_truthy(&v)),
+        }
+    }
+
+    /// Requested page limit capped by `max_pages_per_crawl`
+    #[must_use]
+    pub fn cap_limit(&self, requested: Option<usize>) -> Option<usize> {
+        match (requested, self.max_pages_per_crawl) {
+            (Some(requested), Some(max)) => Some(requested.min(max)),
+            (requested, max) => requested.or(max),
+        }
+    }
+
+    /// The URL rules of this quota
+    #[must_use]
+    pub fn url_policy(&self) -> UrlPolicy {
+        UrlPolicy::new(self.allowed_domains.clone(), self.block_private_hosts)
+    }
+
+    /// Fail if `url` may not be crawled under this quota
+    ///
+    /// The host is resolved, so names pointing at private addresses fail
+    /// when private hosts are blocked.
+    pub async fn check_url(&self, url: &str) -> Result<()> {
+        self.url_policy().check_url(url).await.map(|_| ())
+    }
+}
+
+/// Quota shared by the registry and its sessions, replaceable at runtime
+///
+/// Crawls read the current quota when they start, so a replaced quota applies
+/// to every crawl started afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct SharedQuota(Arc<RwLock<Arc<CrawlQuota>>>);
+
+impl SharedQuota {
+    #[must_use]
+    pub fn new(quota: CrawlQuota) -> Self {
+        Self(Arc::new(RwLock::new(Arc::new(quota))))
+    }
+
+    /// The quota in effect now
+    #[must_use]
+    pub fn current(&self) -> Arc<CrawlQuota> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// Replace the quota for crawls started from now on
+    pub fn replace(&self, quota: CrawlQuota) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(quota);
+    }
+}
+
+/// Normalize a comma-separated domain list (`*.` prefixes dropped, lowercased)
+pub(crate) fn parse_domain_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|d| d.trim().trim_start_matches("*.").to_ascii_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// `1`, `true` or `yes`, case-insensitive
+pub(crate) fn is_truthy(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_from_env_and_checks() {
+        let quota = CrawlQuota::from_lookup(|name| match name {
+            "CITESCRAPE_MAX_CRAWLS_PER_CONNECTION" => Some("2".to_string()),
+            "CITESCRAPE_MAX_PAGES_PER_CRAWL" => Some("100".to_string()),
+            "CITESCRAPE_ALLOWED_DOMAINS" => Some("docs.rs, *.example.com,".to_string()),
+            "CITESCRAPE_BLOCK_PRIVATE_HOSTS" => Some("true".to_string()),
+            _ => None,
+        });
+        assert_eq!(quota.max_concurrent_crawls, Some(2));
+        assert_eq!(
+            quota.allowed_domains,
+            Some(vec!["docs.rs".to_string(), "example.com".to_string()])
+        );
+
+        assert_eq!(quota.cap_limit(None), Some(100));
+        assert_eq!(quota.cap_limit(Some(500)), Some(100));
+        assert_eq!(quota.cap_limit(Some(5)), Some(5));
+
+        let policy = quota.url_policy();
+        let check = |url: &str| policy.check_navigation(&url::Url::parse(url).unwrap());
+        assert!(check("https://docs.rs/tokio").is_ok());
+        assert!(check("https://api.example.com/").is_ok());
+        assert!(check("https://notexample.com/").is_err());
+        assert!(check("https://evil.com/").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_private_hosts() {
+        let quota = CrawlQuota {
+            block_private_hosts: true,
+            ..Default::default()
+        };
+        for url in [
+            "http://localhost:8080/",
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://192.168.0.1/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/",
+            "http://[::ffff:10.0.0.1]/",
+            "http://printer.local/",
+        ] {
+            assert!(quota.check_url(url).await.is_err(), "{url} should be blocked");
+        }
+        assert!(quota.check_url("https://8.8.8.8/").await.is_ok());
+        assert!(CrawlQuota::default().check_url("http://localhost/").await.is_ok());
+    }
+}
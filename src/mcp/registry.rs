@@ -2,17 +2,68 @@
 //!
 //! Pattern based on: packages/kodegen-tools-terminal/src/registry.rs
 
+use crate::crawl_events::{CrawlEvent, JournalSummary};
 use crate::mcp::session::CrawlSession;
-use crate::mcp::manager::SearchEngineCache;
-use kodegen_mcp_schema::citescrape::{CrawlSnapshot, ScrapeUrlOutput};
+use crate::mcp::manager::{ManifestManager, SearchEngineCache, SessionRecord, SessionStore, resolve_crawl_dir};
+use crate::mcp::quota::{CrawlQuota, SharedQuota};
+use crate::mcp::start_crawl::ScrapeUrlToolArgs;
+use crate::mcp::types::CrawlSessionProgress;
+use kodegen_mcp_schema::McpError;
+use kodegen_mcp_schema::citescrape::{CrawlSnapshot, ScrapeAction, ScrapeUrlOutput};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
+use tokio_util::sync::CancellationToken;
+
+/// Why [`CrawlRegistry::start_background_crawl`] did not start a crawl
+#[derive(Debug, thiserror::Error)]
+pub enum BackgroundCrawlError {
+    /// The arguments name no URL or no usable output directory
+    #[error("{0}")]
+    InvalidArguments(McpError),
+    /// The crawl quota does not allow the URL
+    #[error("{0}")]
+    Forbidden(anyhow::Error),
+    /// The connection already runs as many crawls as it may
+    #[error("{0}")]
+    LimitReached(anyhow::Error),
+    /// The session could not be created or the crawl not launched
+    #[error("failed to start crawl: {0}")]
+    Failed(anyhow::Error),
+}
 
 /// Registry key: (connection_id, crawl_id)
 type CrawlMap = HashMap<(String, u32), Arc<CrawlSession>>;
 
+/// Crawl slots reserved by [`CrawlRegistry::admit_crawl`], with their holder counts
+type Admissions = Arc<std::sync::Mutex<HashMap<(String, u32), usize>>>;
+
+/// A crawl slot reserved for a connection, released when dropped
+///
+/// Hold it until the crawl has started; from then on the running session
+/// itself counts against the connection's limit.
+#[must_use = "the slot is released as soon as the admission is dropped"]
+pub struct CrawlAdmission {
+    admissions: Option<Admissions>,
+    key: (String, u32),
+}
+
+impl Drop for CrawlAdmission {
+    fn drop(&mut self) {
+        let Some(admissions) = &self.admissions else {
+            return;
+        };
+        let mut admissions = admissions.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(holders) = admissions.get_mut(&self.key) {
+            *holders -= 1;
+            if *holders == 0 {
+                admissions.remove(&self.key);
+            }
+        }
+    }
+}
+
 /// Registry for managing multiple crawl instances keyed by (connection_id, crawl_id)
 ///
 /// **Connection Isolation:**
@@ -24,12 +75,30 @@ type CrawlMap = HashMap<(String, u32), Arc<CrawlSession>>;
 /// - Users can run parallel crawls: crawl:0, crawl:1, crawl:2...
 /// - Each instance is stateful and reusable
 /// - Same pattern as terminal tool
+///
+/// **Quotas:**
+/// - [`CrawlQuota`] caps concurrent crawls per connection and pages per crawl,
+///   and restricts which hosts crawls may target
+///
+/// **Restarts:**
+/// - With a [`SessionStore`], running crawls are recorded on disk and
+///   [`Self::restore_sessions`] resumes or reports them after a restart
 #[derive(Clone)]
 pub struct CrawlRegistry {
     crawls: Arc<Mutex<CrawlMap>>,
     engine_cache: Arc<SearchEngineCache>,
     /// Shared browser pool for pre-warmed Chrome instances
     browser_pool: Arc<crate::browser_pool::BrowserPool>,
+    /// Server-side crawl limits shared by every session
+    quota: SharedQuota,
+    /// Records of running crawls kept across server restarts
+    session_store: Option<Arc<SessionStore>>,
+    /// Per-connection tokens, cancelled when the connection is cleaned up
+    connection_tokens: Arc<std::sync::Mutex<HashMap<String, CancellationToken>>>,
+    /// Crawl slots reserved but not yet running
+    admissions: Admissions,
+    /// Serializes admission checks so two calls cannot take the same slot
+    admission_lock: Arc<Mutex<()>>,
 }
 
 impl CrawlRegistry {
@@ -42,14 +111,71 @@ impl CrawlRegistry {
             crawls: Arc::new(Mutex::new(HashMap::new())),
             engine_cache,
             browser_pool,
+            quota: SharedQuota::default(),
+            session_store: None,
+            connection_tokens: Arc::default(),
+            admissions: Arc::default(),
+            admission_lock: Arc::default(),
         }
     }
 
+    /// Record running crawls in `store` so they survive a server restart
+    #[must_use]
+    pub fn with_session_store(mut self, store: Arc<SessionStore>) -> Self {
+        self.session_store = Some(store);
+        self
+    }
+
+    /// Apply server-side crawl limits to every session
+    ///
+    /// The quota's URL rules also become the process-wide
+    /// [`crate::url_policy::UrlPolicy`].
+    #[must_use]
+    pub fn with_quota(mut self, quota: CrawlQuota) -> Self {
+        crate::url_policy::set_url_policy(quota.url_policy());
+        self.quota = SharedQuota::new(quota);
+        self
+    }
+
+    /// Server-side crawl limits in effect now
+    pub fn quota(&self) -> Arc<CrawlQuota> {
+        self.quota.current()
+    }
+
+    /// Replace the crawl limits for crawls started from now on
+    pub fn set_quota(&self, quota: CrawlQuota) {
+        crate::url_policy::set_url_policy(quota.url_policy());
+        self.quota.replace(quota);
+    }
+
     /// Get reference to the browser pool
     pub fn browser_pool(&self) -> &Arc<crate::browser_pool::BrowserPool> {
         &self.browser_pool
     }
 
+    /// Get reference to the shared search engine cache
+    pub fn engine_cache(&self) -> &Arc<SearchEngineCache> {
+        &self.engine_cache
+    }
+
+    /// Token owning all work started for `connection_id`
+    ///
+    /// Crawls, exports and indexing tied to this token are aborted by
+    /// [`Self::cleanup_connection`].
+    pub fn connection_token(&self, connection_id: &str) -> CancellationToken {
+        self.connection_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(connection_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Every registered session, across connections
+    pub async fn sessions(&self) -> Vec<Arc<CrawlSession>> {
+        self.crawls.lock().await.values().cloned().collect()
+    }
+
     /// Find or create a crawl session
     ///
     /// Pattern from: terminal/registry.rs:25-47
@@ -66,19 +192,196 @@ impl CrawlRegistry {
             return Ok(session.clone());
         }
 
-        let session = Arc::new(
-            CrawlSession::new(
-                crawl_id,
-                output_dir,
-                self.engine_cache.clone(),
-                self.browser_pool.clone(),
-            )
-        );
+        let mut session = CrawlSession::new(
+            crawl_id,
+            output_dir,
+            self.engine_cache.clone(),
+            self.browser_pool.clone(),
+            self.quota.clone(),
+        )
+        .with_connection_id(connection_id)
+        .with_cancellation(self.connection_token(connection_id));
+        if let Some(store) = &self.session_store {
+            session = session.with_session_store(store.clone(), connection_id.to_string());
+        }
+        let session = Arc::new(session);
 
         crawls.insert(key, session.clone());
         Ok(session)
     }
 
+    /// Handle crawls a previous server process left unfinished
+    ///
+    /// With `resume`, each crawl is started again in the background under its
+    /// original connection and crawl ID (its manifest shows it as running);
+    /// otherwise, or if restarting fails, its manifest is marked interrupted
+    /// with instructions to resume it. Returns the records that were handled.
+    pub async fn restore_sessions(&self, resume: bool) -> Vec<SessionRecord> {
+        let Some(store) = &self.session_store else {
+            return Vec::new();
+        };
+        let records = match store.take_all().await {
+            Ok(records) => records,
+            Err(e) => {
+                log::warn!("Failed to load interrupted crawl sessions: {e}");
+                return Vec::new();
+            }
+        };
+
+        for record in &records {
+            let mut manifest = record.manifest.clone();
+            if resume {
+                match self.resume_session(record).await {
+                    Ok(()) => {
+                        log::info!("Resumed interrupted crawl of {}", manifest.start_url);
+                        if let Err(e) = ManifestManager::save(&manifest).await {
+                            log::warn!("Failed to save crawl manifest: {e}");
+                        }
+                        continue;
+                    }
+                    Err(e) => log::warn!("Failed to resume crawl of {}: {e}", manifest.start_url),
+                }
+            }
+            manifest.interrupt();
+            log::info!(
+                "Crawl of {} was interrupted by a restart; resume with {}",
+                manifest.start_url,
+                manifest.resume_hint()
+            );
+            if let Err(e) = ManifestManager::save(&manifest).await {
+                log::warn!("Failed to save crawl manifest: {e}");
+            }
+        }
+        records
+    }
+
+    /// Restart a recorded crawl in the background
+    ///
+    /// Pages the crawl's event journal shows as queued but neither saved nor
+    /// failed are added to its seeds, so they are visited even when no
+    /// revisited page links to them.
+    async fn resume_session(&self, record: &SessionRecord) -> Result<(), anyhow::Error> {
+        let output_dir = record.manifest.output_dir.clone();
+        let mut seed_urls = record.seed_urls.clone();
+        match tokio::task::spawn_blocking(move || JournalSummary::load(&output_dir)).await? {
+            Ok(summary) if summary.start_url.as_deref() == Some(record.manifest.start_url.as_str()) => {
+                let pending: Vec<String> = summary
+                    .pending()
+                    .filter(|url| !seed_urls.iter().any(|seed| seed == url))
+                    .map(str::to_string)
+                    .collect();
+                log::info!("Resuming crawl of {} with {} pending pages", record.manifest.start_url, pending.len());
+                seed_urls.extend(pending);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to read event journal of {}: {e:#}", record.manifest.start_url),
+        }
+        let session = self
+            .find_or_create_crawl(
+                &record.connection_id,
+                record.crawl_id,
+                record.manifest.output_dir.clone(),
+            )
+            .await?;
+        session
+            .execute_crawl_with_seeds(record.args.clone(), record.profile, record.typography, 0, seed_urls)
+            .await?;
+        Ok(())
+    }
+
+    /// Reserve a slot for `connection_id` to start crawl `crawl_id`
+    ///
+    /// Fails when the connection already runs or is starting
+    /// `max_concurrent_crawls` other crawls. Restarting `crawl_id` itself does
+    /// not count against the limit. The check and the reservation are one
+    /// step, so concurrent calls cannot both take the last slot.
+    pub async fn admit_crawl(&self, connection_id: &str, crawl_id: u32) -> Result<CrawlAdmission, anyhow::Error> {
+        let key = (connection_id.to_string(), crawl_id);
+        let Some(max) = self.quota.current().max_concurrent_crawls else {
+            return Ok(CrawlAdmission { admissions: None, key });
+        };
+        let _admitting = self.admission_lock.lock().await;
+
+        let others: Vec<(u32, Arc<CrawlSession>)> = {
+            let crawls = self.crawls.lock().await;
+            crawls
+                .iter()
+                .filter(|((conn_id, id), _)| conn_id == connection_id && *id != crawl_id)
+                .map(|((_, id), session)| (*id, session.clone()))
+                .collect()
+        };
+        let mut busy: std::collections::HashSet<u32> = self
+            .admissions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .keys()
+            .filter(|(conn_id, id)| conn_id == connection_id && *id != crawl_id)
+            .map(|(_, id)| *id)
+            .collect();
+        for (id, session) in others {
+            if session.progress().await.is_running() {
+                busy.insert(id);
+            }
+        }
+        let running = busy.len();
+        if running >= max {
+            anyhow::bail!(
+                "Crawl limit reached: {running} crawls already running for this connection (max {max}). \
+                 Wait for one to finish or cancel it first."
+            );
+        }
+
+        *self
+            .admissions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(key.clone())
+            .or_default() += 1;
+        Ok(CrawlAdmission {
+            admissions: Some(self.admissions.clone()),
+            key,
+        })
+    }
+
+    /// Start crawl `crawl_id` of `connection_id` without waiting for it
+    ///
+    /// Used by the REST and gRPC endpoints, whose clients poll for progress
+    /// instead of holding a tool call open. Applies the same quota checks as
+    /// `scrape_url` and returns the crawl's progress right after launch.
+    pub async fn start_background_crawl(
+        &self,
+        connection_id: &str,
+        crawl_id: u32,
+        args: ScrapeUrlToolArgs,
+    ) -> Result<CrawlSessionProgress, BackgroundCrawlError> {
+        let ScrapeUrlToolArgs { scrape: mut args, profile, typography } = args;
+        let Some(url) = args.url.clone() else {
+            return Err(BackgroundCrawlError::InvalidArguments(McpError::InvalidArguments(
+                "url is required".to_string(),
+            )));
+        };
+        self.quota.current().check_url(&url).await.map_err(BackgroundCrawlError::Forbidden)?;
+        let output_dir = resolve_crawl_dir(Some(&url), args.output_dir.as_deref(), None)
+            .map_err(BackgroundCrawlError::InvalidArguments)?;
+        let _admission = self
+            .admit_crawl(connection_id, crawl_id)
+            .await
+            .map_err(BackgroundCrawlError::LimitReached)?;
+
+        args.action = ScrapeAction::Crawl;
+        args.crawl_id = crawl_id;
+        args.await_completion_ms = 0;
+        let session = self
+            .find_or_create_crawl(connection_id, crawl_id, output_dir)
+            .await
+            .map_err(BackgroundCrawlError::Failed)?;
+        session
+            .execute_crawl_with_timeout(args, profile, typography, 0)
+            .await
+            .map_err(BackgroundCrawlError::Failed)?;
+        Ok(session.progress().await)
+    }
+
     /// List all active crawls for a connection with their current states
     ///
     /// Pattern from: terminal/registry.rs:49-79
@@ -97,7 +400,7 @@ impl CrawlRegistry {
                     status: state.status.clone(),
                     url: state.current_url.clone(),
                     pages_crawled: state.pages_crawled,
-                    elapsed_ms: 0,
+                    elapsed_ms: state.elapsed().as_millis() as u64,
                 });
             }
         }
@@ -120,6 +423,85 @@ impl CrawlRegistry {
         })
     }
 
+    /// Progress of a connection's crawls, or of one crawl if `crawl_id` is given
+    ///
+    /// Sorted by crawl ID; empty if no matching session exists.
+    pub async fn crawl_status(
+        &self,
+        connection_id: &str,
+        crawl_id: Option<u32>,
+    ) -> Vec<CrawlSessionProgress> {
+        let sessions: Vec<Arc<CrawlSession>> = {
+            let crawls = self.crawls.lock().await;
+            crawls
+                .iter()
+                .filter(|((conn_id, id), _)| {
+                    conn_id == connection_id && crawl_id.is_none_or(|wanted| *id == wanted)
+                })
+                .map(|(_, session)| session.clone())
+                .collect()
+        };
+
+        let mut progress = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            progress.push(session.progress().await);
+        }
+        progress.sort_by_key(|p| p.crawl_id);
+        progress
+    }
+
+    /// Get a crawl session without creating it
+    pub async fn get_crawl(&self, connection_id: &str, crawl_id: u32) -> Option<Arc<CrawlSession>> {
+        self.crawls
+            .lock()
+            .await
+            .get(&(connection_id.to_string(), crawl_id))
+            .cloned()
+    }
+
+    /// Subscribe to the events of a crawl session
+    ///
+    /// Returns `None` if no such crawl exists. See [`CrawlSession::subscribe`].
+    pub async fn subscribe(
+        &self,
+        connection_id: &str,
+        crawl_id: u32,
+    ) -> Option<broadcast::Receiver<CrawlEvent>> {
+        Some(self.get_crawl(connection_id, crawl_id).await?.subscribe())
+    }
+
+    /// Cancel a running crawl but keep its session (and progress) around
+    ///
+    /// Returns whether the crawl was running plus its progress after
+    /// cancelling, or `None` if no such crawl exists.
+    pub async fn cancel_crawl(
+        &self,
+        connection_id: &str,
+        crawl_id: u32,
+    ) -> Result<Option<(bool, CrawlSessionProgress)>, anyhow::Error> {
+        let Some(session) = self.get_crawl(connection_id, crawl_id).await else {
+            return Ok(None);
+        };
+        let was_running = session.progress().await.is_running();
+        session.cancel().await?;
+        Ok(Some((was_running, session.progress().await)))
+    }
+
+    /// Pause (or resume) a running crawl
+    ///
+    /// Returns whether the state changed plus the session's progress, or
+    /// `None` if no such crawl exists.
+    pub async fn pause_crawl(
+        &self,
+        connection_id: &str,
+        crawl_id: u32,
+        paused: bool,
+    ) -> Option<(bool, CrawlSessionProgress)> {
+        let session = self.get_crawl(connection_id, crawl_id).await?;
+        let changed = session.set_paused(paused).await;
+        Some((changed, session.progress().await))
+    }
+
     /// Kill a crawl and cleanup all resources
     ///
     /// Pattern from: terminal/registry.rs:81-104
@@ -165,10 +547,36 @@ impl CrawlRegistry {
         }
     }
 
+    /// Drain every session before the server exits
+    ///
+    /// Running crawls are stopped and checkpointed (see
+    /// [`CrawlSession::shutdown`]): each flushes its link rewrites, writes an
+    /// interrupted manifest and closes its link index. With a session store
+    /// the crawls are resumed or reported on the next start. Returns the
+    /// number of sessions drained.
+    pub async fn shutdown(&self) -> usize {
+        let sessions: Vec<Arc<CrawlSession>> =
+            self.crawls.lock().await.drain().map(|(_, session)| session).collect();
+        let count = sessions.len();
+        futures::future::join_all(sessions.iter().map(|session| session.shutdown())).await;
+        log::info!("Drained {count} crawl sessions");
+        count
+    }
+
     /// Cleanup all crawls for a connection (called on connection drop)
     ///
-    /// Cancels running crawls but preserves output directories at docs/<domain>/
+    /// Aborts running crawls (skipping their final link rewrites and reports)
+    /// but preserves output directories at docs/<domain>/
     pub async fn cleanup_connection(&self, connection_id: &str) -> usize {
+        let token = self
+            .connection_tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(connection_id);
+        if let Some(token) = token {
+            token.cancel();
+        }
+
         let mut crawls = self.crawls.lock().await;
         let to_remove: Vec<(String, u32)> = crawls
             .keys()
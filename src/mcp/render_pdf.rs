@@ -0,0 +1,257 @@
+//! `render_pdf` MCP tool - Print a page to PDF
+//!
+//! Loads a URL in a stealth page from the browser pool and prints it with
+//! Chrome's print-to-PDF. By default the PDF is saved next to the page's
+//! markdown in the crawl layout (`index.pdf`), like crawl screenshots are.
+
+use chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams;
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::browser_page::{StealthPage, validate_web_url};
+use super::manager::url_to_output_dir;
+use crate::browser_pool::BrowserPool;
+
+/// Tool name for PDF rendering
+pub const RENDER_PDF: &str = "render_pdf";
+
+fn default_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_margin_inches() -> f64 {
+    0.4
+}
+
+/// Paper sizes for `render_pdf`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaperFormat {
+    #[default]
+    Letter,
+    Legal,
+    Tabloid,
+    A3,
+    A4,
+    A5,
+}
+
+impl PaperFormat {
+    /// Portrait width and height in inches
+    fn size_inches(self) -> (f64, f64) {
+        match self {
+            Self::Letter => (8.5, 11.0),
+            Self::Legal => (8.5, 14.0),
+            Self::Tabloid => (11.0, 17.0),
+            Self::A3 => (11.69, 16.54),
+            Self::A4 => (8.27, 11.69),
+            Self::A5 => (5.83, 8.27),
+        }
+    }
+}
+
+/// Arguments for the `render_pdf` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenderPdfArgs {
+    /// Page to print
+    pub url: String,
+
+    /// Paper size (default: letter)
+    #[serde(default)]
+    pub paper: PaperFormat,
+
+    /// Landscape orientation (default: false)
+    #[serde(default)]
+    pub landscape: bool,
+
+    /// Print background colors and images (default: true)
+    #[serde(default = "default_true")]
+    pub print_background: bool,
+
+    /// Rendering scale, 0.1 to 2.0 (default: 1.0)
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+
+    /// Margin on every side in inches (default: 0.4)
+    #[serde(default = "default_margin_inches")]
+    pub margin_inches: f64,
+
+    /// Pages to print, e.g. "1-5, 8" (default: all)
+    #[serde(default)]
+    pub page_ranges: Option<String>,
+
+    /// Use the page's CSS `@page` size instead of `paper` when it declares one
+    #[serde(default)]
+    pub prefer_css_page_size: bool,
+
+    /// Selector to wait for before printing
+    #[serde(default)]
+    pub wait_for: Option<String>,
+
+    /// Where to write the PDF (default: `index.pdf` in the page's crawl directory)
+    #[serde(default)]
+    pub output_path: Option<String>,
+
+    /// Page load timeout in milliseconds (default: 30000)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+/// Output of the `render_pdf` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RenderPdfOutput {
+    /// URL that was printed
+    pub url: String,
+    /// Saved PDF file
+    pub path: String,
+    /// PDF size in bytes
+    pub bytes: usize,
+}
+
+impl ToolArgs for RenderPdfArgs {
+    type Output = RenderPdfOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = RENDER_PDF;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Print a page to PDF and return the saved file path";
+}
+
+/// PDF rendering tool
+#[derive(Clone)]
+pub struct RenderPdfTool {
+    browser_pool: Arc<BrowserPool>,
+}
+
+impl RenderPdfTool {
+    #[must_use]
+    pub fn new(browser_pool: Arc<BrowserPool>) -> Self {
+        Self { browser_pool }
+    }
+}
+
+/// Print options for chromium
+fn print_params(args: &RenderPdfArgs) -> PrintToPdfParams {
+    let (width, height) = args.paper.size_inches();
+    let margin = args.margin_inches;
+    let mut params = PrintToPdfParams::builder()
+        .landscape(args.landscape)
+        .print_background(args.print_background)
+        .scale(args.scale)
+        .paper_width(width)
+        .paper_height(height)
+        .margin_top(margin)
+        .margin_bottom(margin)
+        .margin_left(margin)
+        .margin_right(margin)
+        .prefer_css_page_size(args.prefer_css_page_size);
+    if let Some(ranges) = &args.page_ranges {
+        params = params.page_ranges(ranges.clone());
+    }
+    params.build()
+}
+
+impl Tool for RenderPdfTool {
+    type Args = RenderPdfArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        RENDER_PDF
+    }
+
+    fn description() -> &'static str {
+        "Load a URL in a stealth browser page and print it to PDF for archiving \
+         or sharing. Supports paper size (letter, legal, tabloid, a3, a4, a5), \
+         orientation, margins, scale, page ranges and background printing. The \
+         PDF is saved as index.pdf in the page's crawl directory unless \
+         output_path is given; the file path is returned.\n\n\
+         render_pdf({url: 'https://ratatui.rs/installation/', paper: 'a4'})"
+    }
+
+    fn read_only() -> bool {
+        false
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<RenderPdfOutput>, McpError> {
+        validate_web_url(&args.url)?;
+        if !(0.1..=2.0).contains(&args.scale) {
+            return Err(McpError::invalid_arguments("scale must be between 0.1 and 2.0"));
+        }
+        if !(0.0..=4.0).contains(&args.margin_inches) {
+            return Err(McpError::invalid_arguments("margin_inches must be between 0 and 4"));
+        }
+
+        let path = match &args.output_path {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                match ctx.pwd() {
+                    Some(pwd) if path.is_relative() => pwd.join(path),
+                    _ => path,
+                }
+            }
+            None => {
+                let output_dir = url_to_output_dir(&args.url, None, ctx.pwd())?;
+                let path = crate::utils::get_mirror_path(&args.url, &output_dir, "index.pdf").await?;
+                crate::utils::ensure_domain_gitignore(&path, &output_dir).await?;
+                path
+            }
+        };
+
+        let timeout = Duration::from_millis(args.timeout_ms.max(1000));
+        let page = StealthPage::open(&self.browser_pool, format!("render_pdf:{}", args.url)).await?;
+        let pdf = match page.load(&args.url, args.wait_for.as_deref(), timeout).await {
+            Ok(()) => page
+                .pdf(print_params(&args))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to print {} to PDF: {e}", args.url)),
+            Err(e) => Err(e),
+        };
+        page.close().await;
+        let pdf = pdf?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to create {}: {e}", parent.display())))?;
+        }
+        tokio::fs::write(&path, &pdf)
+            .await
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to write {}: {e}", path.display())))?;
+
+        let path = path.to_string_lossy().to_string();
+        let summary = format!("Printed {} to {path} ({} KB)", args.url, pdf.len().div_ceil(1024));
+        Ok(ToolResponse::new(
+            summary,
+            RenderPdfOutput {
+                url: args.url,
+                path,
+                bytes: pdf.len(),
+            },
+        ))
+    }
+}
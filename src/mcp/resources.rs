@@ -0,0 +1,321 @@
+//! MCP resources over crawl output directories
+//!
+//! Maps saved crawl files onto `citescrape://` resource URIs so MCP clients can
+//! browse and read mirrored pages without a filesystem tool:
+//!
+//! - `citescrape://{crawl}/manifest.json` and `citescrape://{crawl}/sitemap.xml`
+//!   describe a crawl
+//! - `citescrape://{crawl}/{host}/{path}/index.md` is a page's markdown and
+//!   `.../index.json` its metadata
+//! - `citescrape://{crawl}/page?url={url}` is the markdown of `url` as the
+//!   crawl's search index stored it, looked up by exact URL
+//!
+//! `{crawl}` is the crawl directory under the crawl base directory (normally
+//! the crawled domain). Compressed (`.gz`) files are listed under their plain
+//! name and decompressed on read.
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use jwalk::WalkDir;
+use rmcp::model::{
+    AnnotateAble, ListResourceTemplatesResult, ListResourcesResult, RawResource, RawResourceTemplate,
+    ReadResourceResult, ResourceContents,
+};
+
+/// URI scheme of crawl resources
+pub const RESOURCE_SCHEME: &str = "citescrape://";
+
+/// Resources returned per page of a listing
+pub const RESOURCE_PAGE_SIZE: usize = 500;
+
+/// Crawl-level files exposed at the root of each crawl directory
+const CRAWL_FILES: &[(&str, &str)] = &[("manifest.json", "application/json"), ("sitemap.xml", "application/xml")];
+
+/// Per-page files exposed inside the mirror tree
+const PAGE_FILES: &[(&str, &str)] = &[("index.md", "text/markdown"), ("index.json", "application/json")];
+
+/// Resource catalog rooted at the crawl base directory
+#[derive(Debug, Clone)]
+pub struct CrawlResources {
+    base_dir: PathBuf,
+}
+
+impl CrawlResources {
+    #[must_use]
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self { base_dir }
+    }
+
+    /// Resource templates describing the URI layout
+    #[must_use]
+    pub fn templates() -> ListResourceTemplatesResult {
+        let template = |uri: &str, name: &str, description: &str, mime: &str| {
+            RawResourceTemplate {
+                uri_template: uri.to_string(),
+                name: name.to_string(),
+                title: None,
+                description: Some(description.to_string()),
+                mime_type: Some(mime.to_string()),
+            }
+            .no_annotation()
+        };
+        ListResourceTemplatesResult::with_all_items(vec![
+            template(
+                "citescrape://{crawl}/{+path}/index.md",
+                "page-markdown",
+                "Markdown of a crawled page",
+                "text/markdown",
+            ),
+            template(
+                "citescrape://{crawl}/{+path}/index.json",
+                "page-metadata",
+                "Metadata of a crawled page",
+                "application/json",
+            ),
+            template(
+                "citescrape://{crawl}/page{?url}",
+                "indexed-page",
+                "Markdown of a crawled page from the crawl's search index, by exact URL",
+                "text/markdown",
+            ),
+            template(
+                "citescrape://{crawl}/manifest.json",
+                "crawl-manifest",
+                "Manifest of a crawl",
+                "application/json",
+            ),
+        ])
+    }
+
+    /// One page of resources, starting after `cursor`
+    pub fn list(&self, cursor: Option<&str>) -> Result<ListResourcesResult> {
+        let offset = match cursor {
+            Some(cursor) => cursor.parse::<usize>().context("Invalid resource cursor")?,
+            None => 0,
+        };
+        let all = self.collect()?;
+        let resources = all
+            .iter()
+            .skip(offset)
+            .take(RESOURCE_PAGE_SIZE)
+            .map(|(uri, path, mime)| {
+                let mut resource = RawResource::new(uri.clone(), resource_name(uri));
+                resource.mime_type = Some((*mime).to_string());
+                resource.size = std::fs::metadata(path).ok().and_then(|m| u32::try_from(m.len()).ok());
+                resource.no_annotation()
+            })
+            .collect();
+        let next = offset + RESOURCE_PAGE_SIZE;
+        Ok(ListResourcesResult {
+            meta: None,
+            next_cursor: (next < all.len()).then(|| next.to_string()),
+            resources,
+        })
+    }
+
+    /// Read the resource at `uri`
+    pub fn read(&self, uri: &str) -> Result<ReadResourceResult> {
+        let path = self.path_for(uri)?;
+        let mime = mime_for(&path).with_context(|| format!("Not a crawl resource: {uri}"))?;
+        let gz_path = with_gz(&path);
+        let text = if path.is_file() {
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+        } else if gz_path.is_file() {
+            let file = std::fs::File::open(&gz_path)?;
+            let mut text = String::new();
+            flate2::read::GzDecoder::new(file)
+                .read_to_string(&mut text)
+                .with_context(|| format!("Failed to decompress {}", gz_path.display()))?;
+            text
+        } else {
+            bail!("Resource not found: {uri}");
+        };
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime.to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+
+    /// Crawl directory and page URL named by an indexed-page URI
+    ///
+    /// Returns `None` when `uri` names a file resource instead.
+    pub fn indexed_page(&self, uri: &str) -> Result<Option<(PathBuf, String)>> {
+        let Some((crawl, query)) = uri
+            .strip_prefix(RESOURCE_SCHEME)
+            .and_then(|relative| relative.split_once("/page?"))
+        else {
+            return Ok(None);
+        };
+        if crawl.is_empty() || crawl.contains('/') {
+            return Ok(None);
+        }
+        if crawl.starts_with('.') || crawl.contains('\\') {
+            bail!("Invalid resource path: {uri}");
+        }
+        let url = url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "url")
+            .map(|(_, value)| value.into_owned())
+            .with_context(|| format!("Indexed page URIs need a url parameter: {uri}"))?;
+        Ok(Some((self.base_dir.join(crawl), url)))
+    }
+
+    /// Local file for `uri`, rejecting anything that escapes the base directory
+    fn path_for(&self, uri: &str) -> Result<PathBuf> {
+        let Some(relative) = uri.strip_prefix(RESOURCE_SCHEME) else {
+            bail!("Resource URIs must start with {RESOURCE_SCHEME}");
+        };
+        let relative = Path::new(relative);
+        let hidden_or_special = |c: Component<'_>| match c {
+            Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+            _ => true,
+        };
+        if relative.components().any(hidden_or_special) {
+            bail!("Invalid resource path: {uri}");
+        }
+        Ok(self.base_dir.join(relative))
+    }
+
+    /// Sorted (uri, path, mime) of every exposed file
+    fn collect(&self) -> Result<Vec<(String, PathBuf, &'static str)>> {
+        let mut resources = Vec::new();
+        if !self.base_dir.is_dir() {
+            return Ok(resources);
+        }
+        for entry in WalkDir::new(&self.base_dir).sort(true).follow_links(false) {
+            let entry = entry.context("Failed to walk crawl directory")?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(&self.base_dir) else {
+                continue;
+            };
+            let plain = strip_gz(relative);
+            let depth = plain.components().count();
+            let Some(name) = plain.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let files = if depth == 2 { CRAWL_FILES } else { PAGE_FILES };
+            let Some((_, mime)) = files.iter().find(|(file, _)| *file == name) else {
+                continue;
+            };
+            // A compressed copy is listed only when there is no plain file
+            if depth < 2 || (relative != plain && self.base_dir.join(&plain).is_file()) {
+                continue;
+            }
+            resources.push((format!("{RESOURCE_SCHEME}{}", to_slash_path(&plain)), path, *mime));
+        }
+        Ok(resources)
+    }
+}
+
+/// MIME type of an exposed file name
+fn mime_for(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    CRAWL_FILES
+        .iter()
+        .chain(PAGE_FILES)
+        .find(|(file, _)| *file == name)
+        .map(|(_, mime)| *mime)
+}
+
+/// Page URL (or crawl file) a resource stands for, used as its name
+fn resource_name(uri: &str) -> String {
+    let relative = uri.trim_start_matches(RESOURCE_SCHEME);
+    let mut parts: Vec<&str> = relative.split('/').collect();
+    if parts.len() <= 2 {
+        return relative.to_string();
+    }
+    let file = parts.pop().unwrap_or_default();
+    let page = parts[1..].join("/");
+    match file {
+        "index.json" => format!("https://{page}/ (metadata)"),
+        _ => format!("https://{page}/"),
+    }
+}
+
+fn with_gz(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+fn strip_gz(path: &Path) -> PathBuf {
+    match path.to_str().and_then(|p| p.strip_suffix(".gz")) {
+        Some(plain) => PathBuf::from(plain),
+        None => path.to_path_buf(),
+    }
+}
+
+fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_list_and_read() {
+        let base = tempfile::TempDir::new().unwrap();
+        let crawl = base.path().join("docs.rs");
+        let page = crawl.join("docs.rs/tokio");
+        std::fs::create_dir_all(&page).unwrap();
+        std::fs::create_dir_all(crawl.join(".search_index")).unwrap();
+        std::fs::write(crawl.join("manifest.json"), "{}").unwrap();
+        std::fs::write(crawl.join(".search_index/meta.json"), "{}").unwrap();
+        std::fs::write(page.join("index.md"), "# Tokio").unwrap();
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(b"{\"url\":\"https://docs.rs/tokio\"}").unwrap();
+        std::fs::write(page.join("index.json.gz"), gz.finish().unwrap()).unwrap();
+
+        let resources = CrawlResources::new(base.path().to_path_buf());
+        let listed = resources.list(None).unwrap();
+        let uris: Vec<&str> = listed.resources.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            [
+                "citescrape://docs.rs/docs.rs/tokio/index.json",
+                "citescrape://docs.rs/docs.rs/tokio/index.md",
+                "citescrape://docs.rs/manifest.json",
+            ]
+        );
+        assert_eq!(listed.resources[1].name, "https://docs.rs/tokio/");
+        assert!(listed.next_cursor.is_none());
+
+        let read = resources.read("citescrape://docs.rs/docs.rs/tokio/index.json").unwrap();
+        let ResourceContents::TextResourceContents { text, mime_type, .. } = &read.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert!(text.contains("docs.rs/tokio"));
+        assert_eq!(mime_type.as_deref(), Some("application/json"));
+
+        assert!(resources.read("citescrape://docs.rs/../../etc/passwd").is_err());
+        assert!(resources.read("citescrape://docs.rs/.search_index/meta.json").is_err());
+    }
+
+    #[test]
+    fn test_indexed_page_uri() {
+        let resources = CrawlResources::new(PathBuf::from("/crawls"));
+        let (crawl_dir, url) = resources
+            .indexed_page("citescrape://docs.rs/page?url=https%3A%2F%2Fdocs.rs%2Ftokio%3Fv%3D1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(crawl_dir, Path::new("/crawls/docs.rs"));
+        assert_eq!(url, "https://docs.rs/tokio?v=1");
+
+        assert!(resources.indexed_page("citescrape://docs.rs/docs.rs/tokio/index.md").unwrap().is_none());
+        assert!(resources.indexed_page("citescrape://docs.rs/page?q=tokio").is_err());
+        assert!(resources.indexed_page("citescrape://../page?url=x").is_err());
+    }
+}
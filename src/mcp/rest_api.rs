@@ -0,0 +1,299 @@
+//! REST endpoints for clients that cannot speak MCP
+//!
+//! CI jobs and dashboards can start crawls and query their indexes over plain
+//! HTTP with JSON bodies:
+//!
+//! - `POST /crawls` takes `scrape_url` arguments (`url` required, `profile`
+//!   and `typography` optional), starts the crawl in the background and answers `202 Accepted`
+//!   with its progress and `Location: /crawls/{id}`
+//! - `GET /crawls/{id}` returns the crawl's progress (as `crawl_status`)
+//! - `GET /search?q=...` searches a crawl's index (as `search_docs`); the crawl
+//!   is located by `crawl_id`, `url`, `output_dir` or `domain`, and `limit`,
+//!   `offset` and `snippets` page and shape the results
+//!
+//! Crawls started here go through the same [`CrawlRegistry`], quota and
+//! search engines as MCP tool calls and belong to the connection
+//! [`REST_CONNECTION_ID`]. [`router`] returns the routes for the endpoint
+//! server (see [`super::endpoints`]), which also carries `/metrics` when both
+//! share an address. With a token configured every request must carry
+//! `Authorization: Bearer <token>`; the server refuses to start without one
+//! unless the API is bound to a loopback address. An `output_dir` must lie
+//! under the server's output root. Errors are `{"error": "..."}`.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anyhow::{Result, bail};
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path as RoutePath, RawQuery, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use kodegen_mcp_schema::McpError;
+use serde::Serialize;
+
+use super::manager::crawl_base_dir;
+use super::registry::{BackgroundCrawlError, CrawlRegistry};
+use super::search_docs::{SearchDocsArgs, resolve_search_dir, search_crawl_index};
+use super::start_crawl::ScrapeUrlToolArgs;
+use crate::config::SecretValue;
+
+/// Connection that crawls started over REST belong to
+pub const REST_CONNECTION_ID: &str = "rest";
+
+/// Largest request body accepted
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Whether `authorization` is `Bearer <token>`
+pub(super) fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|value| {
+        let (scheme, credentials) = value.split_once(' ')?;
+        scheme.eq_ignore_ascii_case("bearer").then(|| credentials.trim())
+    }) else {
+        return false;
+    };
+    // Compare every byte so the time taken does not reveal the matching prefix
+    given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// `output_dir` of a remote request, resolved under `root`
+///
+/// Relative paths resolve against `root`. Paths that leave it, lexically or
+/// through a symlink, are rejected.
+pub(super) fn confine_output_dir(root: &Path, requested: &str) -> Result<PathBuf, McpError> {
+    let outside = || {
+        McpError::InvalidArguments(format!(
+            "output_dir: '{requested}' is outside the output root {}",
+            root.display()
+        ))
+    };
+    let mut confined = PathBuf::new();
+    for component in root.join(requested).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                confined.pop();
+            }
+            other => confined.push(other),
+        }
+    }
+    if !confined.starts_with(root) {
+        return Err(outside());
+    }
+    // The deepest existing ancestor must still be inside the root once symlinks are resolved
+    if let Some(existing) = confined.ancestors().find(|dir| dir.exists())
+        && let (Ok(existing), Ok(root)) = (existing.canonicalize(), root.canonicalize())
+        && !existing.starts_with(root)
+    {
+        return Err(outside());
+    }
+    Ok(confined)
+}
+
+/// `search_docs` arguments from a `/search` query string
+fn search_args(query: &str) -> Result<SearchDocsArgs> {
+    fn number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+        value.parse().map_err(|_| anyhow::anyhow!("{key}: invalid value '{value}'"))
+    }
+
+    let mut args = SearchDocsArgs {
+        query: String::new(),
+        crawl_id: None,
+        domain: None,
+        url: None,
+        output_dir: None,
+        top_k: 10,
+        offset: 0,
+        snippets: true,
+    };
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "q" | "query" => args.query = value.into_owned(),
+            "crawl_id" => args.crawl_id = Some(number(&key, &value)?),
+            "domain" => args.domain = Some(value.into_owned()),
+            "url" => args.url = Some(value.into_owned()),
+            "output_dir" => args.output_dir = Some(value.into_owned()),
+            "limit" | "top_k" => args.top_k = number(&key, &value)?,
+            "offset" => args.offset = number(&key, &value)?,
+            "snippets" => args.snippets = number(&key, &value)?,
+            _ => {}
+        }
+    }
+    if args.query.trim().is_empty() {
+        bail!("q: a search query is required");
+    }
+    Ok(args)
+}
+
+/// JSON response with `status`
+fn json(status: StatusCode, body: &impl Serialize) -> Response {
+    (status, axum::Json(serde_json::to_value(body).unwrap_or_default())).into_response()
+}
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> Response {
+    json(status, &serde_json::json!({ "error": message.to_string() }))
+}
+
+/// Status for a tool error: bad input is the client's fault, a missing crawl or index is 404
+fn mcp_error(e: &McpError) -> Response {
+    let status = match e {
+        McpError::InvalidArguments(_) | McpError::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+        McpError::ResourceNotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    error(status, e)
+}
+
+/// State shared by the REST handlers
+struct RestApi {
+    registry: Arc<CrawlRegistry>,
+    token: Option<SecretValue>,
+    /// Directory every requested `output_dir` must lie under
+    output_root: PathBuf,
+    next_crawl_id: AtomicU32,
+}
+
+/// The REST routes, ready to be served by the endpoint server
+///
+/// With `token`, requests without a matching bearer token get `401`.
+pub async fn router(registry: Arc<CrawlRegistry>, token: Option<SecretValue>) -> Result<Router> {
+    // Continue numbering after crawls restored from a previous run
+    let next_crawl_id = registry
+        .crawl_status(REST_CONNECTION_ID, None)
+        .await
+        .last()
+        .map_or(0, |progress| progress.crawl_id + 1);
+    let api = Arc::new(RestApi {
+        registry,
+        token,
+        output_root: crawl_base_dir(None, None)?,
+        next_crawl_id: AtomicU32::new(next_crawl_id),
+    });
+
+    Ok(Router::new()
+        .route("/crawls", post(start_crawl))
+        .route("/crawls/{id}", get(crawl_status))
+        .route("/search", get(search))
+        .route_layer(middleware::from_fn_with_state(api.clone(), authorize))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(api))
+}
+
+async fn authorize(State(api): State<Arc<RestApi>>, request: Request, next: Next) -> Response {
+    if let Some(token) = &api.token {
+        let authorization = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        if !is_authorized(authorization, token.expose()) {
+            return error(StatusCode::UNAUTHORIZED, "missing or invalid bearer token");
+        }
+    }
+    next.run(request).await
+}
+
+/// `POST /crawls`
+async fn start_crawl(State(api): State<Arc<RestApi>>, body: Bytes) -> Response {
+    let mut args: ScrapeUrlToolArgs = match serde_json::from_slice(&body) {
+        Ok(args) => args,
+        Err(e) => return error(StatusCode::BAD_REQUEST, format!("invalid crawl request: {e}")),
+    };
+    if let Some(dir) = &args.scrape.output_dir {
+        match confine_output_dir(&api.output_root, dir) {
+            Ok(dir) => args.scrape.output_dir = Some(dir.to_string_lossy().into_owned()),
+            Err(e) => return mcp_error(&e),
+        }
+    }
+    let crawl_id = api.next_crawl_id.fetch_add(1, Ordering::Relaxed);
+    match api.registry.start_background_crawl(REST_CONNECTION_ID, crawl_id, args).await {
+        Ok(progress) => (
+            [(header::LOCATION, format!("/crawls/{crawl_id}"))],
+            json(StatusCode::ACCEPTED, &progress),
+        )
+            .into_response(),
+        Err(BackgroundCrawlError::InvalidArguments(e)) => mcp_error(&e),
+        Err(e @ BackgroundCrawlError::Forbidden(_)) => error(StatusCode::FORBIDDEN, e),
+        Err(e @ BackgroundCrawlError::LimitReached(_)) => error(StatusCode::TOO_MANY_REQUESTS, e),
+        Err(e @ BackgroundCrawlError::Failed(_)) => error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// `GET /crawls/{id}`
+async fn crawl_status(State(api): State<Arc<RestApi>>, RoutePath(id): RoutePath<String>) -> Response {
+    let Ok(crawl_id) = id.parse::<u32>() else {
+        return error(StatusCode::NOT_FOUND, "crawl IDs are numbers");
+    };
+    match api.registry.crawl_status(REST_CONNECTION_ID, Some(crawl_id)).await.first() {
+        Some(progress) => json(StatusCode::OK, progress),
+        None => error(StatusCode::NOT_FOUND, format!("crawl {crawl_id} not found")),
+    }
+}
+
+/// `GET /search`
+async fn search(State(api): State<Arc<RestApi>>, RawQuery(query): RawQuery) -> Response {
+    let mut args = match search_args(query.as_deref().unwrap_or_default()) {
+        Ok(args) => args,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e),
+    };
+    if let Some(dir) = &args.output_dir {
+        match confine_output_dir(&api.output_root, dir) {
+            Ok(dir) => args.output_dir = Some(dir.to_string_lossy().into_owned()),
+            Err(e) => return mcp_error(&e),
+        }
+    }
+    match resolve_search_dir(&api.registry, REST_CONNECTION_ID, &args, None).await {
+        Ok(dir) => match search_crawl_index(&api.registry, dir, &args).await {
+            Ok(output) => json(StatusCode::OK, &output),
+            Err(e) => mcp_error(&e),
+        },
+        Err(e) => mcp_error(&e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearer_token() {
+        assert!(is_authorized(Some("Bearer s3cret"), "s3cret"));
+        assert!(is_authorized(Some("bearer s3cret"), "s3cret"));
+        assert!(!is_authorized(Some("Bearer s3cre"), "s3cret"));
+        assert!(!is_authorized(Some("Basic s3cret"), "s3cret"));
+        assert!(!is_authorized(None, "s3cret"));
+    }
+
+    #[test]
+    fn test_output_dir_confined_to_root() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        assert_eq!(confine_output_dir(root, "docs.rs").unwrap(), root.join("docs.rs"));
+        assert_eq!(confine_output_dir(root, "a/../b").unwrap(), root.join("b"));
+        let inside = root.join("x").to_string_lossy().into_owned();
+        assert_eq!(confine_output_dir(root, &inside).unwrap(), root.join("x"));
+
+        assert!(confine_output_dir(root, "../elsewhere").is_err());
+        assert!(confine_output_dir(root, "/etc").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/tmp", root.join("escape")).unwrap();
+            assert!(confine_output_dir(root, "escape/crawl").is_err());
+        }
+    }
+
+    #[test]
+    fn test_search_args_from_query() {
+        let args = search_args("q=layout+constraints&domain=ratatui.rs&limit=5&offset=10&snippets=false").unwrap();
+        assert_eq!(args.query, "layout constraints");
+        assert_eq!(args.domain.as_deref(), Some("ratatui.rs"));
+        assert_eq!((args.top_k, args.offset, args.snippets), (5, 10, false));
+
+        assert!(search_args("domain=ratatui.rs").is_err());
+        let err = search_args("q=x&crawl_id=first").unwrap_err();
+        assert!(err.to_string().starts_with("crawl_id"), "{err}");
+    }
+}
@@ -0,0 +1,201 @@
+//! `robots_check` MCP tool - Interpret a site's robots.txt
+//!
+//! Fetches robots.txt for a URL's origin and reports whether the URL (and any
+//! extra paths) may be crawled, the applicable crawl delay and the sitemaps
+//! the site advertises.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use super::browser_page::validate_web_url;
+use crate::robots::fetch_robots;
+use crate::utils::http_fetch::guarded_client;
+use crate::utils::HTTP_USER_AGENT;
+
+/// Tool name for robots.txt checks
+pub const ROBOTS_CHECK: &str = "robots_check";
+
+/// Most extra paths checked in one call
+const MAX_PATHS: usize = 100;
+
+/// Arguments for the `robots_check` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RobotsCheckArgs {
+    /// URL to check; robots.txt is read from its origin
+    pub url: String,
+
+    /// Further paths or URLs on the same site to check (max 100)
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// User agent to evaluate the rules for (default: the citescrape user agent)
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+/// Verdict for one URL
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RobotsPathCheck {
+    /// URL that was checked
+    pub url: String,
+    /// Whether the user agent may crawl it
+    pub allowed: bool,
+    /// Rule that decided it, e.g. "Disallow: /private/"
+    pub rule: Option<String>,
+}
+
+/// Output of the `robots_check` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RobotsCheckOutput {
+    /// Location of robots.txt
+    pub robots_url: String,
+    /// HTTP status of robots.txt (`None` if it could not be fetched)
+    pub status: Option<u16>,
+    /// User agent the rules were evaluated for
+    pub user_agent: String,
+    /// User-agent line of the group that applied ("*" for the default group)
+    pub matched_agent: Option<String>,
+    /// Crawl-delay for that group, in seconds
+    pub crawl_delay_secs: Option<f64>,
+    /// Sitemaps listed in robots.txt
+    pub sitemaps: Vec<String>,
+    /// Verdicts for `url` followed by `paths`
+    pub checks: Vec<RobotsPathCheck>,
+}
+
+impl ToolArgs for RobotsCheckArgs {
+    type Output = RobotsCheckOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = ROBOTS_CHECK;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Check robots.txt rules, crawl delay and sitemaps for a URL";
+}
+
+/// robots.txt diagnostic tool
+#[derive(Clone)]
+pub struct RobotsCheckTool;
+
+impl RobotsCheckTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for RobotsCheckTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for RobotsCheckTool {
+    type Args = RobotsCheckArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        ROBOTS_CHECK
+    }
+
+    fn description() -> &'static str {
+        "Fetch robots.txt for a URL's site and report whether the URL (and any \
+         extra paths) may be crawled by our user agent, which rule decided it, \
+         the Crawl-delay and the sitemaps listed. Follows RFC 9309: a missing \
+         robots.txt allows everything, a 5xx response disallows everything.\n\n\
+         robots_check({url: 'https://docs.rs/tokio', paths: ['/crate/tokio/latest/source/']})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<RobotsCheckOutput>, McpError> {
+        let url = validate_web_url(&args.url)?;
+        if args.paths.len() > MAX_PATHS {
+            return Err(McpError::invalid_arguments(format!(
+                "{} paths given, limit is {MAX_PATHS}",
+                args.paths.len()
+            )));
+        }
+        let mut targets = vec![url.clone()];
+        for path in &args.paths {
+            let target = url
+                .join(path)
+                .map_err(|e| McpError::InvalidUrl(format!("Invalid path '{path}': {e}")))?;
+            if target.origin() != url.origin() {
+                return Err(McpError::invalid_arguments(format!(
+                    "'{path}' is not on {}",
+                    url.origin().ascii_serialization()
+                )));
+            }
+            targets.push(target);
+        }
+
+        let user_agent = args.user_agent.unwrap_or_else(|| HTTP_USER_AGENT.to_string());
+        let fetched = fetch_robots(&guarded_client().map_err(McpError::Other)?, &url).await?;
+        let robots = &fetched.robots;
+
+        let checks: Vec<RobotsPathCheck> = targets
+            .iter()
+            .map(|target| {
+                let verdict = robots.check(&user_agent, target);
+                RobotsPathCheck {
+                    url: target.to_string(),
+                    allowed: verdict.allowed,
+                    rule: verdict.rule.map(|r| r.to_string()),
+                }
+            })
+            .collect();
+
+        let status = fetched
+            .status
+            .map_or_else(|| "unreachable".to_string(), |s| format!("HTTP {s}"));
+        let mut summary = format!("{} ({status})", fetched.url);
+        let matched_agent = robots.matched_agent(&user_agent);
+        let crawl_delay_secs = robots.crawl_delay(&user_agent);
+        if let Some(agent) = &matched_agent {
+            let _ = write!(summary, "\nGroup: User-agent: {agent}");
+        }
+        if let Some(delay) = crawl_delay_secs {
+            let _ = write!(summary, "\nCrawl-delay: {delay}s");
+        }
+        for check in &checks {
+            let verdict = if check.allowed { "allowed" } else { "blocked" };
+            let _ = write!(summary, "\n  {verdict:<7} {}", check.url);
+            if let Some(rule) = &check.rule {
+                let _ = write!(summary, " ({rule})");
+            }
+        }
+        for sitemap in &robots.sitemaps {
+            let _ = write!(summary, "\nSitemap: {sitemap}");
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            RobotsCheckOutput {
+                robots_url: fetched.url.to_string(),
+                status: fetched.status,
+                user_agent,
+                matched_agent,
+                crawl_delay_secs,
+                sitemaps: robots.sitemaps.clone(),
+                checks,
+            },
+        ))
+    }
+}
@@ -0,0 +1,248 @@
+//! `search_docs` MCP tool - Full-text search over previously crawled content
+//!
+//! Queries the Tantivy index of a crawl (`<output_dir>/.search_index`) through
+//! the shared `SearchEngineCache`, so agents can look up mirrored docs without
+//! re-fetching pages. Unlike `scrape_url` SEARCH it never starts a crawl.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::manager::{resolve_crawl_dir, url_to_output_dir};
+use super::registry::CrawlRegistry;
+use crate::link_index::open_local_index;
+use crate::search::query::SearchQueryBuilder;
+
+/// Tool name for crawl index search
+pub const SEARCH_DOCS: &str = "search_docs";
+
+/// Maximum number of hits per call
+const MAX_TOP_K: usize = 100;
+
+fn default_top_k() -> usize {
+    10
+}
+
+fn default_snippets() -> bool {
+    true
+}
+
+/// Arguments for the `search_docs` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchDocsArgs {
+    /// Search query (supports phrases, AND/OR/NOT and field:value terms)
+    pub query: String,
+
+    /// Search the index of this connection's crawl instance
+    #[serde(default)]
+    pub crawl_id: Option<u32>,
+
+    /// Only return pages from this domain (also locates the crawl if no
+    /// crawl_id, url or output_dir is given)
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// URL that was crawled (used to locate the output directory)
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Explicit crawl output directory (takes precedence over `url`)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    /// Number of results to return (max 100)
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+
+    /// Number of results to skip (for pagination)
+    #[serde(default)]
+    pub offset: usize,
+
+    /// Include highlighted snippets of the matching text
+    #[serde(default = "default_snippets")]
+    pub snippets: bool,
+}
+
+/// A single search hit
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchDocsHit {
+    /// Original page URL
+    pub url: String,
+    /// Page title
+    pub title: String,
+    /// Local markdown file of the page
+    pub path: String,
+    /// Relevance score
+    pub score: f32,
+    /// Matching excerpt (omitted when `snippets` is false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+/// Output of the `search_docs` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchDocsOutput {
+    /// Query as executed
+    pub query: String,
+    /// Crawl output directory that was searched
+    pub output_dir: String,
+    /// Total number of matching pages
+    pub total_count: usize,
+    /// Offset to pass for the next page of results, if any
+    pub next_offset: Option<usize>,
+    /// Matching pages, best first
+    pub results: Vec<SearchDocsHit>,
+}
+
+impl ToolArgs for SearchDocsArgs {
+    type Output = SearchDocsOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = SEARCH_DOCS;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Full-text search over previously crawled documentation";
+}
+
+/// Crawl index search tool backed by the shared search engine cache
+#[derive(Clone)]
+pub struct SearchDocsTool {
+    registry: Arc<CrawlRegistry>,
+}
+
+impl SearchDocsTool {
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+/// Locate the crawl output directory holding the search index for `args`
+///
+/// `crawl_id` refers to a crawl of `connection_id`; otherwise the directory
+/// comes from `output_dir`, `url` or `domain`, relative paths resolving
+/// against `client_pwd`.
+pub async fn resolve_search_dir(
+    registry: &CrawlRegistry,
+    connection_id: &str,
+    args: &SearchDocsArgs,
+    client_pwd: Option<&Path>,
+) -> Result<PathBuf, McpError> {
+    if let Some(crawl_id) = args.crawl_id {
+        let session = registry.get_crawl(connection_id, crawl_id).await.ok_or_else(|| {
+            McpError::ResourceNotFound(format!("Crawl {crawl_id} not found for this connection"))
+        })?;
+        return Ok(session.output_dir().to_path_buf());
+    }
+
+    match (&args.url, &args.output_dir, &args.domain) {
+        (None, None, Some(domain)) => url_to_output_dir(&format!("https://{domain}/"), None, client_pwd),
+        (url, output_dir, _) => resolve_crawl_dir(url.as_deref(), output_dir.as_deref(), client_pwd),
+    }
+}
+
+/// Run `args` against the search index in `output_dir`
+///
+/// Shared by the tool, the REST API's `GET /search` and the gRPC `Search` call; `args.query` must be
+/// non-empty and only the paging, domain and snippet fields are read.
+pub async fn search_crawl_index(
+    registry: &CrawlRegistry,
+    output_dir: PathBuf,
+    args: &SearchDocsArgs,
+) -> Result<SearchDocsOutput, McpError> {
+    let Some(entry) = registry.engine_cache().get_existing(output_dir.clone()).await? else {
+        return Err(McpError::ResourceNotFound(format!(
+            "Search index not found in {}. Crawl the site with enable_search first.",
+            output_dir.display()
+        )));
+    };
+
+    let results = SearchQueryBuilder::new(&args.query)
+        .limit(args.top_k.clamp(1, MAX_TOP_K))
+        .offset(args.offset)
+        .highlight(args.snippets)
+        .domain_filter(args.domain.clone())
+        .page_ranks(open_local_index(&output_dir).await.ok().flatten())
+        .execute_with_metadata((*entry.engine).clone())
+        .await
+        .map_err(McpError::Other)?;
+
+    let next_offset = results.next_offset();
+    let hits: Vec<SearchDocsHit> = results
+        .results
+        .into_iter()
+        .map(|item| SearchDocsHit {
+            url: item.url,
+            title: item.title,
+            path: item.path,
+            score: item.score,
+            snippet: args.snippets.then_some(item.excerpt),
+        })
+        .collect();
+
+    Ok(SearchDocsOutput {
+        query: args.query.clone(),
+        output_dir: output_dir.to_string_lossy().to_string(),
+        total_count: results.total_count,
+        next_offset,
+        results: hits,
+    })
+}
+
+impl Tool for SearchDocsTool {
+    type Args = SearchDocsArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        SEARCH_DOCS
+    }
+
+    fn description() -> &'static str {
+        "Search documentation that was already crawled with search enabled. \
+         Locate the crawl by crawl_id, url, output_dir or domain; filter by \
+         domain; page with top_k/offset. Returns URLs, titles, local markdown \
+         paths and highlighted snippets. Does not crawl - use scrape_url first.\n\n\
+         search_docs({query: 'layout constraints', domain: 'ratatui.rs', top_k: 5})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        false
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<SearchDocsOutput>, McpError> {
+        if args.query.trim().is_empty() {
+            return Err(McpError::InvalidArguments("query must not be empty".to_string()));
+        }
+
+        let connection_id = ctx.connection_id().unwrap_or("default");
+        let output_dir = resolve_search_dir(&self.registry, connection_id, &args, ctx.pwd()).await?;
+        let output = search_crawl_index(&self.registry, output_dir, &args).await?;
+
+        let mut summary = format!(
+            "{} of {} results for \"{}\" in {}",
+            output.results.len(),
+            output.total_count,
+            output.query,
+            output.output_dir
+        );
+        for (rank, hit) in output.results.iter().enumerate() {
+            let _ = write!(summary, "\n  {}. {} - {} ({:.2})", args.offset + rank + 1, hit.title, hit.url, hit.score);
+        }
+
+        Ok(ToolResponse::new(summary, output))
+    }
+}
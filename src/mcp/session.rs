@@ -2,34 +2,104 @@
 
 use crate::ChromiumoxideCrawler;
 use crate::Crawler;  // Import the Crawler trait
-use crate::config::CrawlConfig;
-use crate::mcp::manager::SearchEngineCache;
+use crate::config::{CrawlConfig, CrawlProfile};
+use crate::content_saver::markdown_converter::Typography;
+use crate::crawl_engine::CrawlControl;
+use crate::crawl_events::{CrawlEvent, ThroughputTracker};
+use crate::link_index::{LinkStore, SiteAudit, open_local_index};
+use crate::mcp::manager::{ManifestManager, SearchEngineCache, SessionRecord, SessionStore};
+use crate::mcp::metrics::{SearchKind, metrics};
+use crate::mcp::quota::SharedQuota;
+use crate::mcp::types::{ConfigSummary, CrawlManifest, CrawlSessionProgress, CrawlStatus};
 use crate::utils::get_mirror_path;
 use anyhow::Result;
 use kodegen_mcp_schema::citescrape::{ScrapeSearchResult, ScrapeUrlOutput};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{Mutex, broadcast};
 use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::Instrument;
+
+/// `crawl_rate_rps` when a `scrape_url` call leaves it out
+const SCRAPE_URL_DEFAULT_RATE_RPS: f64 = 2.0;
+
+/// Events buffered per receiver before slow subscribers start lagging
+const EVENT_CAPACITY: usize = 1000;
+
+/// Minimum time between manifest saves while a crawl runs
+const MANIFEST_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long cancelled crawls get to checkpoint on server shutdown
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(30);
+
+/// How long aborted crawls get to stop once the grace period has passed
+const SHUTDOWN_ABORT_GRACE: Duration = Duration::from_secs(5);
 
 /// Crawl session state
 #[derive(Debug, Clone)]
 pub struct CrawlState {
     pub output_dir: PathBuf,
-    pub status: String,  // "idle", "running", "completed", "failed", "cancelled"
+    pub status: String,  // "idle", "running", "paused", "completed", "failed", "cancelled", "interrupted"
     pub pages_crawled: usize,
+    /// Crawl queue length reported with the latest page
+    pub pages_queued: usize,
+    /// Pages that exhausted their retries
+    pub pages_failed: usize,
+    /// Pages skipped because their cached copy was still fresh
+    pub cache_hits: usize,
+    /// Total HTML bytes of crawled pages
+    pub bytes_downloaded: u64,
     pub current_url: Option<String>,
     pub start_time: Option<std::time::Instant>,
+    /// Set when the crawl task finished (success or failure)
+    pub end_time: Option<std::time::Instant>,
+    /// Rolling rates and phase timings of recently crawled pages
+    pub throughput: ThroughputTracker,
+}
+
+impl CrawlState {
+    /// Whether a crawl is in progress (running or paused)
+    pub fn is_running(&self) -> bool {
+        self.status == "running" || self.status == "paused"
+    }
+
+    /// Time since the crawl started, frozen once it finished
+    pub fn elapsed(&self) -> Duration {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => end.duration_since(start),
+            (Some(start), None) => start.elapsed(),
+            _ => Duration::ZERO,
+        }
+    }
 }
 
 /// Crawl session wrapping ChromiumoxideCrawler with timeout and state management
 pub struct CrawlSession {
     crawl_id: u32,
+    /// MCP connection owning the session, reported in crawl log records
+    connection_id: Option<String>,
     output_dir: PathBuf,
     state: Arc<Mutex<CrawlState>>,
     engine_cache: Arc<SearchEngineCache>,
     /// Shared browser pool for pre-warmed Chrome instances
     browser_pool: Arc<crate::browser_pool::BrowserPool>,
+    /// Cancellation/pause handle of the current (or last) crawl
+    control: std::sync::Mutex<CrawlControl>,
+    /// Cancelling this aborts every crawl of the session
+    parent: CancellationToken,
+    /// Server-side limits, read when each crawl of this session starts
+    quota: SharedQuota,
+    /// Store that keeps running crawls across restarts, with the owning connection
+    persistence: Option<(Arc<SessionStore>, String)>,
+    /// Events of every crawl run by this session, for [`Self::subscribe`]
+    events: broadcast::Sender<CrawlEvent>,
+    /// Crawl tasks of this session, awaited by [`Self::shutdown`]
+    tasks: TaskTracker,
+    /// Set by [`Self::shutdown`]: stopped crawls are left resumable instead of finished
+    checkpoint: Arc<AtomicBool>,
 }
 
 impl CrawlSession {
@@ -39,22 +109,81 @@ impl CrawlSession {
         output_dir: PathBuf,
         engine_cache: Arc<SearchEngineCache>,
         browser_pool: Arc<crate::browser_pool::BrowserPool>,
+        quota: SharedQuota,
     ) -> Self {
         Self {
             crawl_id,
+            connection_id: None,
             output_dir: output_dir.clone(),
             state: Arc::new(Mutex::new(CrawlState {
                 output_dir,
                 status: "idle".to_string(),
                 pages_crawled: 0,
+                pages_queued: 0,
+                pages_failed: 0,
+                cache_hits: 0,
+                bytes_downloaded: 0,
                 current_url: None,
                 start_time: None,
+                end_time: None,
+                throughput: ThroughputTracker::new(),
             })),
             engine_cache,
             browser_pool,
+            control: std::sync::Mutex::new(CrawlControl::new()),
+            parent: CancellationToken::new(),
+            quota,
+            persistence: None,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            tasks: TaskTracker::new(),
+            checkpoint: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Subscribe to the events of this session's crawls
+    ///
+    /// The receiver sees every crawl started after subscribing; each crawl ends
+    /// with a `Shutdown` event. Slow receivers skip events rather than
+    /// holding the crawl back (`RecvError::Lagged`).
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<CrawlEvent> {
+        self.events.subscribe()
+    }
+
+    /// Record crawls of this session in `store` while they run
+    #[must_use]
+    pub fn with_session_store(mut self, store: Arc<SessionStore>, connection_id: String) -> Self {
+        self.persistence = Some((store, connection_id));
+        self
+    }
+
+    /// Tag log records of this session's crawls with `connection_id`
+    #[must_use]
+    pub fn with_connection_id(mut self, connection_id: impl Into<String>) -> Self {
+        self.connection_id = Some(connection_id.into());
+        self
+    }
+
+    /// Abort this session's crawls when `token` is cancelled
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.parent = token;
+        self
+    }
+
+    /// Parent output directory of this session (holds `.search_index/`)
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    /// Control handle of the current crawl
+    fn control(&self) -> CrawlControl {
+        self.control
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
     /// Get content directory from URL and parent output_dir
     ///
     /// Uses `get_mirror_path` to calculate where content actually lives.
@@ -87,18 +216,58 @@ impl CrawlSession {
     pub async fn execute_crawl_with_timeout(
         &self,
         args: kodegen_mcp_schema::citescrape::ScrapeUrlArgs,
+        profile: Option<CrawlProfile>,
+        typography: Typography,
         await_completion_ms: u64,
+    ) -> Result<ScrapeUrlOutput> {
+        self.execute_crawl_with_seeds(args, profile, typography, await_completion_ms, Vec::new()).await
+    }
+
+    /// Execute crawl with extra URLs queued at depth 0 alongside `args.url`
+    ///
+    /// Timeout behavior matches [`Self::execute_crawl_with_timeout`].
+    pub async fn execute_crawl_with_seeds(
+        &self,
+        args: kodegen_mcp_schema::citescrape::ScrapeUrlArgs,
+        profile: Option<CrawlProfile>,
+        typography: Typography,
+        await_completion_ms: u64,
+        seed_urls: Vec<String>,
     ) -> Result<ScrapeUrlOutput> {
         use std::time::Instant;
 
+        if self.checkpoint.load(Ordering::Acquire) {
+            anyhow::bail!("Server is shutting down; crawl not started");
+        }
+        let stored_args = self.persistence.as_ref().map(|_| args.clone());
         let url = args.url.ok_or_else(|| anyhow::anyhow!("url required for CRAWL action"))?;
+        let quota = self.quota.current();
+        quota.check_url(&url).await?;
+        let mut allowed_seeds = Vec::with_capacity(seed_urls.len());
+        for seed in seed_urls {
+            match quota.check_url(&seed).await {
+                Ok(()) => allowed_seeds.push(seed),
+                Err(e) => log::warn!("Skipping seed URL: {e}"),
+            }
+        }
+        let seed_urls = allowed_seeds;
 
         // Update state to running
         {
             let mut state = self.state.lock().await;
-            state.status = "running".to_string();
-            state.current_url = Some(url.clone());
-            state.start_time = Some(Instant::now());
+            *state = CrawlState {
+                output_dir: self.output_dir.clone(),
+                status: "running".to_string(),
+                pages_crawled: 0,
+                pages_queued: 0,
+                pages_failed: 0,
+                cache_hits: 0,
+                bytes_downloaded: 0,
+                current_url: Some(url.clone()),
+                start_time: Some(Instant::now()),
+                end_time: None,
+                throughput: ThroughputTracker::new(),
+            };
         }
 
         // Create unique Chrome user data directory for this crawl session using UUID
@@ -111,7 +280,8 @@ impl CrawlSession {
         let mut config = CrawlConfig {
             storage_dir: self.output_dir.clone(),
             start_url: url.clone(),
-            limit: args.limit,
+            limit: quota.cap_limit(args.limit),
+            allowed_domains: quota.allowed_domains.clone(),
             allow_subdomains: args.allow_subdomains,
             save_screenshots: args.save_screenshots,
             save_markdown: args.save_markdown,
@@ -122,15 +292,33 @@ impl CrawlSession {
                 None
             },
             crawl_rate_rps: Some(args.crawl_rate_rps),
+            seed_urls: seed_urls.clone(),
+            typography,
             ..Default::default()
         };
 
+        // Arguments changed from their `scrape_url` defaults still win over the profile
+        if let Some(profile) = profile {
+            config.apply_profile(profile);
+            if (args.crawl_rate_rps - SCRAPE_URL_DEFAULT_RATE_RPS).abs() > f64::EPSILON {
+                config.crawl_rate_rps = Some(args.crawl_rate_rps);
+            }
+            if args.save_screenshots {
+                config.save_screenshots = true;
+            }
+        }
+
         // Attach chrome data dir to config for browser profile isolation
         config = config.with_chrome_data_dir(chrome_data_dir);
 
         // Attach browser pool for pre-warmed browser instances
         config = config.with_browser_pool(self.browser_pool.clone());
 
+        // Fresh control handle so cancel/pause target this crawl
+        let control = CrawlControl::with_parent(&self.parent);
+        *self.control.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = control.clone();
+        config = config.with_crawl_control(control);
+
         // Get or initialize search engine if enabled
         if args.enable_search {
             let entry = self.engine_cache.get_or_init(self.output_dir.clone(), &config).await?;
@@ -139,23 +327,55 @@ impl CrawlSession {
             }
         }
 
+        // Manifest is written when the crawl finishes, even if we stop waiting
+        // earlier, and periodically while it runs so page outcomes are on disk
+        let manifest = Arc::new(Mutex::new(CrawlManifest {
+            crawl_id: self.crawl_id.to_string(),
+            start_url: url.clone(),
+            output_dir: self.output_dir.clone(),
+            search_index_dir: self.output_dir.join(".search_index"),
+            start_time: chrono::Utc::now(),
+            end_time: None,
+            status: CrawlStatus::Running,
+            total_pages: 0,
+            config_summary: ConfigSummary::from(&config),
+            site_audit: None,
+            pages: Default::default(),
+        }));
+
         // Create event bus for progress tracking
-        let event_bus = Arc::new(crate::crawl_events::CrawlEventBus::new(1000));
+        let event_bus = Arc::new(crate::crawl_events::CrawlEventBus::new(EVENT_CAPACITY));
         let state_clone = self.state.clone();
-        let mut event_receiver = event_bus.subscribe();
-
-        // Spawn progress tracker
+        // Lossless: a manifest that skipped events would miss page outcomes
+        let mut event_receiver = event_bus.subscribe_lossless();
+        let subscribers = self.events.clone();
+        let page_manifest = manifest.clone();
+        // Serializes manifest writes so an older snapshot never lands last
+        let manifest_writes = Arc::new(Mutex::new(()));
+        let (dirty_tx, dirty_rx) = tokio::sync::mpsc::channel::<()>(1);
+        tokio::spawn(Self::flush_manifest(manifest.clone(), manifest_writes.clone(), dirty_rx));
+
+        // Spawn progress tracker, forwarding events to session subscribers
         tokio::spawn(async move {
-            let mut page_count = 0;
-            while let Ok(event) = event_receiver.recv().await {
+            while let Some(event) = event_receiver.recv().await {
+                let _ = subscribers.send(event.clone());
+                metrics().record_event(&event);
+                if page_manifest.lock().await.record_event(&event) {
+                    // Full means a flush is already pending
+                    let _ = dirty_tx.try_send(());
+                }
+                let mut state = state_clone.lock().await;
+                state.throughput.record(&event);
                 match event {
-                    crate::crawl_events::CrawlEvent::PageCrawled { url, .. } => {
-                        page_count += 1;
-                        let mut state = state_clone.lock().await;
-                        state.pages_crawled = page_count;
+                    CrawlEvent::PageCrawled { url, metadata, .. } => {
+                        state.pages_crawled += 1;
+                        state.pages_queued = metadata.queue_size;
+                        state.bytes_downloaded += metadata.html_size as u64;
                         state.current_url = Some(url);
                     }
-                    crate::crawl_events::CrawlEvent::Shutdown { .. } => break,
+                    CrawlEvent::CacheHit { .. } => state.cache_hits += 1,
+                    CrawlEvent::RetryExhausted { .. } => state.pages_failed += 1,
+                    CrawlEvent::Shutdown { .. } => break,
                     _ => {}
                 }
             }
@@ -163,9 +383,78 @@ impl CrawlSession {
 
         config = config.with_event_bus(event_bus);
 
-        // Create crawler and start crawl
+        // Record the crawl so a server restart can resume or report it
+        let persistence = match (&self.persistence, stored_args) {
+            (Some((store, connection_id)), Some(args)) => {
+                let record = SessionRecord {
+                    connection_id: connection_id.clone(),
+                    crawl_id: self.crawl_id,
+                    args,
+                    profile,
+                    typography,
+                    seed_urls,
+                    manifest: manifest.lock().await.clone(),
+                };
+                if let Err(e) = store.insert(record).await {
+                    log::warn!("Failed to record crawl session: {e}");
+                }
+                Some((store.clone(), connection_id.clone()))
+            }
+            _ => None,
+        };
+        let crawl_id = self.crawl_id;
+
+        // Create crawler and start crawl; spans and logs inside carry the session's ids
         let crawler = ChromiumoxideCrawler::new(config);
-        let crawl_future = crawler.crawl();
+        let span = tracing::info_span!(
+            "crawl_session",
+            crawl_id = self.crawl_id,
+            connection_id = self.connection_id.as_deref()
+        );
+        let manifest_state = self.state.clone();
+        let checkpoint = self.checkpoint.clone();
+        let crawl_future = self.tasks.spawn(async move {
+            let result = crawler.crawl().await;
+            metrics().record_crawl_finished(result.is_ok());
+            // Stopped by server shutdown: keep the session record so the next
+            // start resumes (or reports) the crawl
+            let interrupted = checkpoint.load(Ordering::Acquire);
+            let total_pages = {
+                let mut state = manifest_state.lock().await;
+                if interrupted {
+                    state.status = "interrupted".to_string();
+                } else if state.is_running() {
+                    // Keep a "cancelled" status set while the crawl was running
+                    state.status = if result.is_ok() { "completed" } else { "failed" }.to_string();
+                }
+                state.pages_queued = 0;
+                state.end_time = Some(Instant::now());
+                state.pages_crawled
+            };
+            let _writing = manifest_writes.lock().await;
+            let mut manifest = manifest.lock().await;
+            match &result {
+                _ if interrupted => {
+                    manifest.total_pages = total_pages;
+                    manifest.interrupt();
+                }
+                Ok(()) => {
+                    manifest.complete(total_pages);
+                    manifest.site_audit = Self::run_site_audit(&manifest).await;
+                }
+                Err(e) => manifest.fail(e),
+            }
+            if let Err(e) = ManifestManager::save(&manifest).await {
+                log::warn!("Failed to save crawl manifest: {e}");
+            }
+            if !interrupted
+                && let Some((store, connection_id)) = persistence
+                && let Err(e) = store.remove(&connection_id, crawl_id).await
+            {
+                log::warn!("Failed to clear crawl session record: {e}");
+            }
+            result
+        }.instrument(span));
 
         // Handle timeout
         let start = Instant::now();
@@ -174,10 +463,8 @@ impl CrawlSession {
         let content_dir = Self::get_content_dir(&url, &self.output_dir).await?;
 
         if await_completion_ms == 0 {
-            // Fire-and-forget: spawn and return immediately
-            tokio::spawn(async move {
-                let _ = crawl_future.await;
-            });
+            // Fire-and-forget: the crawl task keeps running after the handle is dropped
+            drop(crawl_future);
 
             Ok(ScrapeUrlOutput {
                 crawl_id: self.crawl_id,
@@ -195,17 +482,17 @@ impl CrawlSession {
         } else {
             // Wait with timeout
             match timeout(Duration::from_millis(await_completion_ms), crawl_future).await {
-                Ok(Ok(())) => {
-                    // Completed successfully
-                    let mut state = self.state.lock().await;
-                    state.status = "completed".to_string();
+                Ok(Ok(Ok(()))) => {
+                    // Completed successfully (status was set by the crawl task,
+                    // "cancelled" if it was stopped early)
+                    let state = self.state.lock().await;
 
                     Ok(ScrapeUrlOutput {
                         crawl_id: self.crawl_id,
-                        status: "completed".to_string(),
+                        status: state.status.clone(),
                         url: Some(url.clone()),
                         pages_crawled: state.pages_crawled,
-                        pages_queued: 0,
+                        pages_queued: state.pages_queued,
                         output_dir: Some(content_dir.clone()),
                         elapsed_ms: start.elapsed().as_millis() as u64,
                         completed: true,
@@ -214,12 +501,21 @@ impl CrawlSession {
                         search_results: None,
                     })
                 }
-                Ok(Err(e)) => {
+                Ok(Ok(Err(e))) => {
                     // Failed
                     let mut state = self.state.lock().await;
                     state.status = "failed".to_string();
 
-                    Err(anyhow::anyhow!("Crawl failed: {}", e))
+                    // Keep the CrawlError in the chain so callers can downcast it
+                    let message = format!("Crawl failed ({}): {e}", e.kind());
+                    Err(anyhow::Error::new(e).context(message))
+                }
+                Ok(Err(e)) => {
+                    // Crawl task panicked or was aborted
+                    let mut state = self.state.lock().await;
+                    state.status = "failed".to_string();
+
+                    Err(anyhow::anyhow!("Crawl task failed: {}", e))
                 }
                 Err(_) => {
                     // Timeout - return partial results
@@ -230,7 +526,7 @@ impl CrawlSession {
                         status: "timeout".to_string(),
                         url: Some(url.clone()),
                         pages_crawled: state.pages_crawled,
-                        pages_queued: 0,
+                        pages_queued: state.pages_queued,
                         output_dir: Some(content_dir.clone()),
                         elapsed_ms: start.elapsed().as_millis() as u64,
                         completed: false,
@@ -243,6 +539,48 @@ impl CrawlSession {
         }
     }
 
+    /// Save the manifest while a crawl runs, at most every [`MANIFEST_FLUSH_INTERVAL`]
+    ///
+    /// Each signal on `dirty` marks the manifest changed. Changes are batched
+    /// for an interval, then a snapshot is saved without holding the manifest
+    /// lock. Stops when the progress tracker drops its sender.
+    async fn flush_manifest(
+        manifest: Arc<Mutex<CrawlManifest>>,
+        writes: Arc<Mutex<()>>,
+        mut dirty: tokio::sync::mpsc::Receiver<()>,
+    ) {
+        while dirty.recv().await.is_some() {
+            tokio::time::sleep(MANIFEST_FLUSH_INTERVAL).await;
+            let _writing = writes.lock().await;
+            let snapshot = manifest.lock().await.clone();
+            if let Err(e) = ManifestManager::save(&snapshot).await {
+                log::warn!("Failed to save crawl manifest: {e}");
+            }
+        }
+    }
+
+    /// Run orphan/dead-end detection over the crawl's link index
+    ///
+    /// Audit failures are logged and leave the manifest without audit data.
+    async fn run_site_audit(manifest: &CrawlManifest) -> Option<SiteAudit> {
+        let index = match open_local_index(&manifest.output_dir).await {
+            Ok(Some(index)) => index,
+            Ok(None) => {
+                log::warn!("No link index in {} to audit", manifest.output_dir.display());
+                return None;
+            }
+            Err(e) => {
+                log::warn!("Skipping site audit: {e}");
+                return None;
+            }
+        };
+        let audit = index.site_audit(Some(&manifest.start_url)).await;
+        index.close().await;
+        audit
+            .inspect_err(|e| log::warn!("Site audit failed: {e}"))
+            .ok()
+    }
+
     /// Read current crawl state without executing
     ///
     /// **Pattern from:** terminal tool's read_current_state()
@@ -261,9 +599,9 @@ impl CrawlSession {
             status: state.status.clone(),
             url: state.current_url.clone(),
             pages_crawled: state.pages_crawled,
-            pages_queued: 0,
+            pages_queued: state.pages_queued,
             output_dir: output_dir_str,
-            elapsed_ms: state.start_time.map_or(0, |t| t.elapsed().as_millis() as u64),
+            elapsed_ms: state.elapsed().as_millis() as u64,
             completed: state.status == "completed",
             error: None,
             crawls: None,
@@ -286,6 +624,8 @@ impl CrawlSession {
         _offset: usize,
         highlight: bool,
         crawl_args: kodegen_mcp_schema::citescrape::ScrapeUrlArgs,
+        profile: Option<CrawlProfile>,
+        typography: Typography,
     ) -> Result<ScrapeUrlOutput> {
         use crate::search::query::SearchQueryBuilder;
 
@@ -299,7 +639,7 @@ impl CrawlSession {
                 auto_crawl_args.url = Some(url.clone());
                 auto_crawl_args.enable_search = true;
 
-                self.execute_crawl_with_timeout(auto_crawl_args, 600_000).await?;
+                self.execute_crawl_with_timeout(auto_crawl_args, profile, typography, 600_000).await?;
             } else {
                 return Err(anyhow::anyhow!(
                     "Search index not found and no URL provided for auto-crawl."
@@ -327,13 +667,17 @@ impl CrawlSession {
         });
 
         // Execute search (reuse existing SearchQueryBuilder)
+        let search_start = std::time::Instant::now();
         let search_results = SearchQueryBuilder::new(&query)
             .limit(limit)
             .offset(_offset)
             .highlight(highlight)
             .domain_filter(domain_filter)
+            .page_ranks(open_local_index(&self.output_dir).await.ok().flatten())
             .execute_with_metadata((*entry.engine).clone())
-            .await?;
+            .await;
+        metrics().record_search(SearchKind::Local, search_start.elapsed(), search_results.is_ok());
+        let search_results = search_results?;
 
         // Format results using schema type
         let results: Vec<ScrapeSearchResult> = search_results
@@ -371,14 +715,89 @@ impl CrawlSession {
     }
 
     /// Cancel the crawl
+    ///
+    /// Aborts in-flight page loads; the crawl task still flushes pending link
+    /// rewrites and writes its reports and manifest for the pages saved so far.
     pub async fn cancel(&self) -> Result<()> {
         let mut state = self.state.lock().await;
-        state.status = "cancelled".to_string();
-        // Actual cancellation would need to signal the crawler
-        // This is a simplified version
+        if state.is_running() {
+            state.status = "cancelled".to_string();
+        }
+        self.control().cancel();
         Ok(())
     }
 
+    /// Stop the session's crawls for server shutdown and wait for them to finish
+    ///
+    /// Each running crawl is cancelled and still flushes its link rewrites.
+    /// Its manifest is marked interrupted and, with a session store, its
+    /// record is kept so the next server start resumes or reports it. No
+    /// crawls can be started on the session afterwards.
+    ///
+    /// Crawls still running after [`SHUTDOWN_GRACE`] are aborted, dropping
+    /// their remaining link rewrites; shutdown stops waiting for them after
+    /// a further [`SHUTDOWN_ABORT_GRACE`].
+    pub async fn shutdown(&self) {
+        self.checkpoint.store(true, Ordering::Release);
+        {
+            let mut state = self.state.lock().await;
+            if state.is_running() {
+                state.status = "interrupted".to_string();
+            }
+        }
+        let control = self.control();
+        control.cancel();
+        self.tasks.close();
+        if timeout(SHUTDOWN_GRACE, self.tasks.wait()).await.is_ok() {
+            return;
+        }
+        log::warn!(
+            "Crawl session {} did not stop within {}s of shutdown; aborting",
+            self.crawl_id,
+            SHUTDOWN_GRACE.as_secs()
+        );
+        control.abort();
+        if timeout(SHUTDOWN_ABORT_GRACE, self.tasks.wait()).await.is_err() {
+            log::warn!("Crawl session {} did not stop after abort; not waiting for it", self.crawl_id);
+        }
+    }
+
+    /// Pause or resume scheduling of new pages
+    ///
+    /// Returns `false` if no crawl is running or it already was in that state.
+    pub async fn set_paused(&self, paused: bool) -> bool {
+        let mut state = self.state.lock().await;
+        if !state.is_running() {
+            return false;
+        }
+        let control = self.control();
+        let changed = if paused { control.pause() } else { control.resume() };
+        state.status = if paused { "paused" } else { "running" }.to_string();
+        changed
+    }
+
+    /// Snapshot of the session's progress counters
+    pub async fn progress(&self) -> CrawlSessionProgress {
+        let state = self.state.lock().await;
+        CrawlSessionProgress {
+            crawl_id: self.crawl_id,
+            status: state.status.clone(),
+            current_url: state.current_url.clone(),
+            pages_crawled: state.pages_crawled,
+            pages_queued: state.pages_queued,
+            pages_failed: state.pages_failed,
+            cache_hits: state.cache_hits,
+            bytes_downloaded: state.bytes_downloaded,
+            elapsed_ms: state.elapsed().as_millis() as u64,
+            output_dir: state.output_dir.to_string_lossy().to_string(),
+            throughput: if state.is_running() {
+                state.throughput.snapshot()
+            } else {
+                Default::default()
+            },
+        }
+    }
+
     /// Get current state (for LIST action)
     pub async fn get_current_state(&self) -> Result<CrawlState> {
         Ok(self.state.lock().await.clone())
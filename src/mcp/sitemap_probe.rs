@@ -0,0 +1,252 @@
+//! `sitemap_probe` MCP tool - Enumerate a site's sitemap URLs
+//!
+//! Finds the sitemaps a site publishes, follows sitemap indexes and summarizes
+//! the page URLs by top-level section so a crawl can be scoped (limit,
+//! max_depth, output size) before it is launched.
+
+use kodegen_mcp_schema::citescrape::ScrapeUrlPrompts;
+use kodegen_mcp_schema::{McpError, Tool, ToolArgs, ToolExecutionContext, ToolResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::browser_page::validate_web_url;
+use crate::robots::fetch_robots;
+use crate::utils::http_fetch::guarded_client;
+use crate::sitemap_probe::{SitemapEntry, SitemapFile, probe_sitemaps};
+
+/// Tool name for sitemap probing
+pub const SITEMAP_PROBE: &str = "sitemap_probe";
+
+/// Upper bound for `max_sitemaps`
+const MAX_SITEMAPS: usize = 500;
+
+/// Most page URLs collected before the probe stops
+const MAX_COLLECTED_URLS: usize = 200_000;
+
+/// Most sections reported
+const MAX_SECTIONS: usize = 50;
+
+fn default_max_sitemaps() -> usize {
+    50
+}
+
+fn default_sample_size() -> usize {
+    100
+}
+
+/// Arguments for the `sitemap_probe` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapProbeArgs {
+    /// Site URL (sitemaps are found via robots.txt or /sitemap.xml), or the
+    /// URL of a sitemap itself
+    pub url: String,
+
+    /// Most sitemap files read, including indexes (default: 50, max: 500)
+    #[serde(default = "default_max_sitemaps")]
+    pub max_sitemaps: usize,
+
+    /// Page URLs returned in `urls` (default: 100; counts always cover all URLs)
+    #[serde(default = "default_sample_size")]
+    pub sample_size: usize,
+
+    /// Only count URLs whose path starts with this prefix (e.g. "/docs/")
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+}
+
+/// URL count for one top-level section
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapSection {
+    /// First path segment, prefixed with the host for other hosts
+    pub prefix: String,
+    /// Page URLs in this section
+    pub count: usize,
+}
+
+/// Output of the `sitemap_probe` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapProbeOutput {
+    /// Where the starting sitemaps came from ("robots.txt", "default" or "argument")
+    pub source: String,
+    /// Sitemap files read
+    pub sitemaps: Vec<SitemapFile>,
+    /// Unique page URLs found (after `path_prefix`)
+    pub total_urls: usize,
+    /// Whether sitemap or URL limits stopped the probe early
+    pub truncated: bool,
+    /// URL counts per section, largest first
+    pub sections: Vec<SitemapSection>,
+    /// Newest `<lastmod>` seen
+    pub newest_lastmod: Option<String>,
+    /// Oldest `<lastmod>` seen
+    pub oldest_lastmod: Option<String>,
+    /// First `sample_size` page URLs
+    pub urls: Vec<SitemapEntry>,
+}
+
+impl ToolArgs for SitemapProbeArgs {
+    type Output = SitemapProbeOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = SITEMAP_PROBE;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "List a site's sitemap URLs with counts per section to scope a crawl";
+}
+
+/// Sitemap enumeration tool
+#[derive(Clone)]
+pub struct SitemapProbeTool;
+
+impl SitemapProbeTool {
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SitemapProbeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `url` points at a sitemap file rather than a site
+fn is_sitemap_url(url: &url::Url) -> bool {
+    let path = url.path().to_ascii_lowercase();
+    [".xml", ".xml.gz", ".txt"].iter().any(|ext| path.ends_with(ext)) && path != "/robots.txt"
+}
+
+/// Section key: first path segment, with the host when it differs from `site_host`
+fn section_of(loc: &str, site_host: Option<&str>) -> String {
+    let Ok(url) = url::Url::parse(loc) else {
+        return "(invalid)".to_string();
+    };
+    let segment = url.path_segments().and_then(|mut s| s.next()).unwrap_or("");
+    let prefix = if segment.is_empty() { "/".to_string() } else { format!("/{segment}/") };
+    match url.host_str() {
+        Some(host) if Some(host) != site_host => format!("{host}{prefix}"),
+        _ => prefix,
+    }
+}
+
+impl Tool for SitemapProbeTool {
+    type Args = SitemapProbeArgs;
+    type Prompts = ScrapeUrlPrompts;
+
+    fn name() -> &'static str {
+        SITEMAP_PROBE
+    }
+
+    fn description() -> &'static str {
+        "Find a site's sitemaps (from robots.txt, else /sitemap.xml), follow \
+         sitemap indexes and count the page URLs, grouped by top-level path \
+         section, with the lastmod range and a sample of URLs. Use it to size \
+         and scope a crawl (limit, path filters) before starting it. Gzipped \
+         and plain-text sitemaps are supported.\n\n\
+         sitemap_probe({url: 'https://docs.rs'})\n\
+         sitemap_probe({url: 'https://example.com/sitemap.xml', path_prefix: '/docs/'})"
+    }
+
+    fn read_only() -> bool {
+        true
+    }
+
+    fn destructive() -> bool {
+        false
+    }
+
+    fn open_world() -> bool {
+        true
+    }
+
+    async fn execute(
+        &self,
+        args: Self::Args,
+        _ctx: ToolExecutionContext,
+    ) -> Result<ToolResponse<SitemapProbeOutput>, McpError> {
+        let url = validate_web_url(&args.url)?;
+        let client = guarded_client().map_err(McpError::Other)?;
+
+        let (source, roots) = if is_sitemap_url(&url) {
+            ("argument", vec![url.clone()])
+        } else {
+            let robots = fetch_robots(&client, &url).await?.robots;
+            let listed: Vec<url::Url> = robots
+                .sitemaps
+                .iter()
+                .filter_map(|s| url.join(s).ok())
+                .collect();
+            if listed.is_empty() {
+                let default = url
+                    .join("/sitemap.xml")
+                    .map_err(|e| McpError::InvalidUrl(e.to_string()))?;
+                ("default", vec![default])
+            } else {
+                ("robots.txt", listed)
+            }
+        };
+
+        let max_sitemaps = args.max_sitemaps.clamp(1, MAX_SITEMAPS);
+        let probe = probe_sitemaps(&client, roots, max_sitemaps, MAX_COLLECTED_URLS).await;
+
+        let urls: Vec<SitemapEntry> = probe
+            .urls
+            .into_iter()
+            .filter(|entry| {
+                args.path_prefix.as_deref().is_none_or(|prefix| {
+                    url::Url::parse(&entry.loc).is_ok_and(|u| u.path().starts_with(prefix))
+                })
+            })
+            .collect();
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in &urls {
+            *counts.entry(section_of(&entry.loc, url.host_str())).or_default() += 1;
+        }
+        let mut sections: Vec<SitemapSection> = counts
+            .into_iter()
+            .map(|(prefix, count)| SitemapSection { prefix, count })
+            .collect();
+        sections.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.prefix.cmp(&b.prefix)));
+        sections.truncate(MAX_SECTIONS);
+
+        // lastmod values are W3C datetimes, so string order is date order
+        let lastmods = urls.iter().filter_map(|e| e.lastmod.as_deref());
+        let newest_lastmod = lastmods.clone().max().map(str::to_string);
+        let oldest_lastmod = lastmods.min().map(str::to_string);
+
+        let total_urls = urls.len();
+        let failed = probe.files.iter().filter(|f| f.error.is_some()).count();
+        let mut summary = format!(
+            "{total_urls} URLs in {} sitemaps (source: {source}{}{})",
+            probe.files.len(),
+            if failed > 0 { format!(", {failed} failed") } else { String::new() },
+            if probe.truncated { ", truncated" } else { "" }
+        );
+        if let (Some(oldest), Some(newest)) = (&oldest_lastmod, &newest_lastmod) {
+            let _ = write!(summary, "\nlastmod: {oldest} .. {newest}");
+        }
+        for section in &sections {
+            let _ = write!(summary, "\n  {:>7}  {}", section.count, section.prefix);
+        }
+        for file in probe.files.iter().filter(|f| f.error.is_some()) {
+            let _ = write!(summary, "\n  failed {}: {}", file.url, file.error.as_deref().unwrap_or(""));
+        }
+
+        Ok(ToolResponse::new(
+            summary,
+            SitemapProbeOutput {
+                source: source.to_string(),
+                sitemaps: probe.files,
+                total_urls,
+                truncated: probe.truncated,
+                sections,
+                newest_lastmod,
+                oldest_lastmod,
+                urls: urls.into_iter().take(args.sample_size).collect(),
+            },
+        ))
+    }
+}
@@ -4,13 +4,49 @@
 //! Pattern based on: packages/kodegen-tools-terminal/src/tool.rs
 
 use kodegen_mcp_schema::citescrape::{ScrapeAction, ScrapeUrlArgs, ScrapeUrlOutput, ScrapeUrlPrompts, SCRAPE_URL};
-use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::{Tool, ToolArgs, ToolExecutionContext, ToolResponse};
 use kodegen_mcp_schema::McpError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::registry::CrawlRegistry;
+use super::types::crawl_tool_error;
 use super::manager::url_to_output_dir;
+use crate::config::CrawlProfile;
+use crate::content_saver::markdown_converter::Typography;
+
+/// `scrape_url` arguments: the shared schema plus a named crawl profile and
+/// markdown typography options
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScrapeUrlToolArgs {
+    #[serde(flatten)]
+    pub scrape: ScrapeUrlArgs,
+
+    /// Crawl preset bundling concurrency, waits, evasion and output options:
+    /// "fast" (high concurrency, markdown only), "thorough" (waits for the
+    /// network, retries more, saves every format) or "stealth" (evasions, one
+    /// page per domain, slow rate). `crawl_rate_rps` and `save_screenshots`
+    /// still override it when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<CrawlProfile>,
+
+    /// Character normalizations applied to the prose of saved markdown, e.g.
+    /// `{"normalize_punctuation": true, "strip_emoji": true}`. Code blocks
+    /// and inline code are never changed.
+    #[serde(default, skip_serializing_if = "Typography::is_noop")]
+    pub typography: Typography,
+}
+
+impl ToolArgs for ScrapeUrlToolArgs {
+    type Output = ScrapeUrlOutput;
+    type Prompts = ScrapeUrlPrompts;
+
+    const NAME: &'static str = SCRAPE_URL;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = <ScrapeUrlArgs as ToolArgs>::DESCRIPTION;
+}
 
 /// Unified scrape_url tool with action-based dispatch
 #[derive(Clone)]
@@ -58,7 +94,7 @@ impl ScrapeUrlTool {
 }
 
 impl Tool for ScrapeUrlTool {
-    type Args = ScrapeUrlArgs;
+    type Args = ScrapeUrlToolArgs;
     type Prompts = ScrapeUrlPrompts;
 
     fn name() -> &'static str {
@@ -79,7 +115,11 @@ impl Tool for ScrapeUrlTool {
          **One-Step Search (auto-crawls if index missing):**\n\
          scrape_url({action: 'SEARCH', url: 'https://ratatui.rs', crawl_id: 0, query: 'layout'})\n\n\
          **Explicit Crawl:**\n\
-         scrape_url({action: 'CRAWL', crawl_id: 0, url: 'https://ratatui.rs'})"
+         scrape_url({action: 'CRAWL', crawl_id: 0, url: 'https://ratatui.rs'})\n\n\
+         **Profiles:** add profile: 'fast', 'thorough' or 'stealth' to CRAWL or SEARCH \
+         instead of tuning concurrency, waits and output options one by one\n\n\
+         **Typography:** add typography: {normalize_punctuation: true, decode_numeric_entities: true, \
+         strip_emoji: true} (any subset) to normalize the prose of saved markdown; code is left as is"
     }
 
     fn read_only() -> bool {
@@ -100,6 +140,7 @@ impl Tool for ScrapeUrlTool {
         ctx: ToolExecutionContext,
     ) -> Result<ToolResponse<ScrapeUrlOutput>, McpError> {
         let connection_id = ctx.connection_id().unwrap_or("default");
+        let ScrapeUrlToolArgs { scrape: args, profile, typography } = args;
 
         // Dispatch based on action (pattern from terminal/tool.rs:72-120)
         let result: ScrapeUrlOutput = match args.action {
@@ -141,6 +182,17 @@ impl Tool for ScrapeUrlTool {
                 })?;
                 
                 let output_dir = Self::resolve_output_dir(&args, ctx.pwd())?;
+                // Searching without an index starts a crawl, which counts against the quota
+                let _admission = if args.url.is_some() && !output_dir.join(".search_index/meta.json").exists() {
+                    let admission = self
+                        .registry
+                        .admit_crawl(connection_id, args.crawl_id)
+                        .await
+                        .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+                    Some(admission)
+                } else {
+                    None
+                };
                 let session = self
                     .registry
                     .find_or_create_crawl(connection_id, args.crawl_id, output_dir)
@@ -156,9 +208,11 @@ impl Tool for ScrapeUrlTool {
                         args.search_offset,
                         args.search_highlight,
                         args.clone(),  // Pass full args for auto-crawl config
+                        profile,
+                        typography,
                     )
                     .await
-                    .map_err(McpError::Other)?
+                    .map_err(crawl_tool_error)?
             }
 
             ScrapeAction::Crawl => {
@@ -172,6 +226,17 @@ impl Tool for ScrapeUrlTool {
                     McpError::InvalidUrl(format!("Invalid URL '{}': {}", url, e))
                 })?;
                 
+                self.registry
+                    .quota()
+                    .check_url(url)
+                    .await
+                    .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+                let _admission = self
+                    .registry
+                    .admit_crawl(connection_id, args.crawl_id)
+                    .await
+                    .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+                
                 let output_dir = Self::resolve_output_dir(&args, ctx.pwd())?;
                 let session = self
                     .registry
@@ -180,9 +245,9 @@ impl Tool for ScrapeUrlTool {
                     .map_err(McpError::Other)?;
                 
                 session
-                    .execute_crawl_with_timeout(args.clone(), args.await_completion_ms)
+                    .execute_crawl_with_timeout(args.clone(), profile, typography, args.await_completion_ms)
                     .await
-                    .map_err(McpError::Other)?
+                    .map_err(crawl_tool_error)?
             }
         };
 
@@ -1,20 +1,33 @@
 //! MCP type definitions for crawl session management
 
 use crate::config::CrawlConfig;
-use crate::crawl_engine::CrawlProgress;
+use crate::content_saver::markdown_converter::{ChromeFilter, ChromeFilterLevel, ConversionOptions, ExtractionBackend, Typography};
+use crate::crawl_engine::{CrawlError, CrawlProgress};
+use crate::crawl_events::{CrawlEvent, CrawlThroughput};
+use crate::link_index::SiteAudit;
 use chrono::{DateTime, Utc};
+use regex::RegexSet;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Status of a crawl session
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub enum CrawlStatus {
     /// Crawl is currently running
     Running,
     /// Crawl completed successfully
     Completed,
     /// Crawl failed with error message
-    Failed { error: String },
+    Failed {
+        error: String,
+        /// Kind of failure, absent in manifests written before it was recorded
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cause: Option<CrawlError>,
+    },
+    /// Server stopped before the crawl finished
+    Interrupted,
 }
 
 /// Active crawl session tracked in memory
@@ -48,8 +61,43 @@ pub struct ActiveCrawlSession {
     pub current_url: Option<String>,
 }
 
+/// Live progress of one crawl session (returned by `crawl_status`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CrawlSessionProgress {
+    /// Crawl instance ID within the connection
+    pub crawl_id: u32,
+    /// "idle", "running", "paused", "completed", "failed" or "cancelled"
+    pub status: String,
+    /// Most recently crawled URL (the start URL before the first page)
+    pub current_url: Option<String>,
+    /// Pages crawled and saved so far
+    pub pages_crawled: usize,
+    /// URLs waiting in the crawl queue
+    pub pages_queued: usize,
+    /// Pages that failed after all retries
+    pub pages_failed: usize,
+    /// Pages skipped because the cached copy was still fresh
+    pub cache_hits: usize,
+    /// Total HTML bytes of crawled pages
+    pub bytes_downloaded: u64,
+    /// Milliseconds since the crawl started
+    pub elapsed_ms: u64,
+    /// Crawl output directory
+    pub output_dir: String,
+    /// Rates, page latency and per-phase timings over the last minute
+    #[serde(default)]
+    pub throughput: CrawlThroughput,
+}
+
+impl CrawlSessionProgress {
+    /// Whether the crawl is still in progress (running or paused)
+    pub fn is_running(&self) -> bool {
+        self.status == "running" || self.status == "paused"
+    }
+}
+
 /// Lightweight configuration summary for manifest storage
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ConfigSummary {
     pub start_url: String,
     pub max_depth: u8,
@@ -58,6 +106,9 @@ pub struct ConfigSummary {
     pub save_screenshots: bool,
     pub enable_search: bool,
     pub crawl_rate_rps: f64,
+    /// How pages were converted to markdown
+    #[serde(default)]
+    pub conversion: ConversionSummary,
 }
 
 impl From<&CrawlConfig> for ConfigSummary {
@@ -72,6 +123,67 @@ impl From<&CrawlConfig> for ConfigSummary {
             // search_index_dir() has fallback logic; we want to know if it was explicitly set
             enable_search: config.search_index_dir.is_some(),
             crawl_rate_rps: config.crawl_rate_rps().unwrap_or(2.0),
+            conversion: ConversionSummary::from(config),
+        }
+    }
+}
+
+/// Markdown conversion settings of a crawl
+///
+/// Kept in the manifest so live pages compared with the crawl's snapshots
+/// (`diff_pages`) are converted the way the crawl converted them. Manifests
+/// written before these settings were recorded read as the crawl defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ConversionSummary {
+    pub extraction_backend: ExtractionBackend,
+    pub min_extraction_quality: f64,
+    pub keep_comment_patterns: Vec<String>,
+    pub chrome_filter_level: ChromeFilterLevel,
+    pub keep_chrome_patterns: Vec<String>,
+    pub drop_chrome_patterns: Vec<String>,
+    pub typography: Typography,
+}
+
+impl Default for ConversionSummary {
+    fn default() -> Self {
+        Self::from(&CrawlConfig::default())
+    }
+}
+
+impl From<&CrawlConfig> for ConversionSummary {
+    fn from(config: &CrawlConfig) -> Self {
+        Self {
+            extraction_backend: config.extraction_backend(),
+            min_extraction_quality: config.min_extraction_quality(),
+            keep_comment_patterns: config.keep_comment_patterns().to_vec(),
+            chrome_filter_level: config.chrome_filter_level(),
+            keep_chrome_patterns: config.keep_chrome_patterns().to_vec(),
+            drop_chrome_patterns: config.drop_chrome_patterns().to_vec(),
+            typography: config.typography(),
+        }
+    }
+}
+
+impl ConversionSummary {
+    /// Conversion options for a page at `base_url`; patterns that no longer compile are dropped
+    #[must_use]
+    pub fn options(&self, base_url: &str) -> ConversionOptions {
+        let compile = |patterns: &[String]| {
+            if patterns.is_empty() { None } else { RegexSet::new(patterns).ok() }
+        };
+        ConversionOptions {
+            base_url: Some(base_url.to_string()),
+            extraction_backend: self.extraction_backend,
+            min_quality_score: self.min_extraction_quality,
+            keep_comments: compile(&self.keep_comment_patterns),
+            chrome_filter: ChromeFilter {
+                level: self.chrome_filter_level,
+                keep: compile(&self.keep_chrome_patterns),
+                drop: compile(&self.drop_chrome_patterns),
+            },
+            typography: self.typography,
+            ..ConversionOptions::default()
         }
     }
 }
@@ -79,7 +191,7 @@ impl From<&CrawlConfig> for ConfigSummary {
 /// Persistent manifest for crawl metadata
 ///
 /// Saved to {`output_dir}/manifest.json` for historical queries.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CrawlManifest {
     pub crawl_id: String,
     pub start_url: String,
@@ -88,15 +200,94 @@ pub struct CrawlManifest {
 
     /// Crawl start time (serialized as Unix timestamp seconds)
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[schemars(with = "i64")]
     pub start_time: DateTime<Utc>,
 
     /// Crawl end time (serialized as Unix timestamp seconds)
     #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[schemars(with = "Option<i64>")]
     pub end_time: Option<DateTime<Utc>>,
 
     pub status: CrawlStatus,
     pub total_pages: usize,
     pub config_summary: ConfigSummary,
+
+    /// Orphan and dead-end pages found in the link graph (set on completion)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_audit: Option<SiteAudit>,
+
+    /// Outcome of every page the crawl queued, keyed by URL
+    ///
+    /// Filled from crawl events while the crawl runs; the manifest is saved
+    /// periodically so it is current even before the crawl finishes.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pages: BTreeMap<String, PageOutcome>,
+}
+
+/// Where a page got to in the crawl
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    /// Waiting in the crawl queue
+    #[default]
+    Queued,
+    /// Loaded and validated, output not written yet
+    Fetched,
+    /// Output files written
+    Saved,
+    /// Skipped because the saved copy is still current (ETag match)
+    Cached,
+    /// Failed for good
+    Failed,
+}
+
+/// Per-page record of a crawl manifest
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PageOutcome {
+    pub status: PageStatus,
+
+    /// Link depth from the start URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+
+    /// HTTP status of the main document, when the browser reported one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+
+    /// xxh3 hash of the saved markdown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+
+    /// Files written for the page
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_paths: Vec<PathBuf>,
+
+    /// When the page was queued (serialized as Unix timestamp seconds)
+    #[serde(default, with = "chrono::serde::ts_seconds_option", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<i64>")]
+    pub queued_at: Option<DateTime<Utc>>,
+
+    /// When the page was saved, cached or given up on (Unix timestamp seconds)
+    #[serde(default, with = "chrono::serde::ts_seconds_option", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<i64>")]
+    pub finished_at: Option<DateTime<Utc>>,
+
+    /// Time spent processing the page, from fetch to saved output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+
+    /// Attempts made before the page was given up on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u8>,
+
+    /// Why the page failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<CrawlError>,
+
+    /// Failed transiently during the crawl and succeeded in the end-of-crawl
+    /// retry pass
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub recovered: bool,
 }
 
 impl CrawlManifest {
@@ -113,7 +304,82 @@ impl CrawlManifest {
             status: session.status.clone(),
             total_pages: session.total_pages,
             config_summary: ConfigSummary::from(&session.config),
+            site_audit: None,
+            pages: BTreeMap::new(),
+        }
+    }
+
+    /// Update the page records with a crawl event
+    ///
+    /// Returns whether a record changed.
+    pub fn record_event(&mut self, event: &CrawlEvent) -> bool {
+        if let CrawlEvent::RetryPassCompleted { recovered, .. } = event {
+            let mut changed = false;
+            for url in recovered {
+                if let Some(page) = self.pages.get_mut(url) {
+                    page.recovered = true;
+                    changed = true;
+                }
+            }
+            return changed;
+        }
+        let (url, timestamp) = match event {
+            CrawlEvent::PageQueued { url, timestamp, .. }
+            | CrawlEvent::PageFetched { url, timestamp, .. }
+            | CrawlEvent::PageSaved { url, timestamp, .. }
+            | CrawlEvent::LinkRewritten { url, timestamp, .. }
+            | CrawlEvent::PageCrawled { url, timestamp, .. }
+            | CrawlEvent::CacheHit { url, timestamp }
+            | CrawlEvent::Error { url, timestamp, .. }
+            | CrawlEvent::RetryExhausted { url, timestamp, .. } => (url, *timestamp),
+            _ => return false,
+        };
+        let page = self.pages.entry(url.clone()).or_default();
+
+        match event {
+            CrawlEvent::PageQueued { depth, .. } => {
+                page.depth.get_or_insert(*depth);
+                page.queued_at.get_or_insert(timestamp);
+            }
+            CrawlEvent::PageFetched { status_code, .. } => {
+                page.status = PageStatus::Fetched;
+                page.http_status = *status_code;
+                page.error = None;
+            }
+            CrawlEvent::PageSaved { local_path, .. } | CrawlEvent::LinkRewritten { local_path, .. } => {
+                if !page.output_paths.contains(local_path) {
+                    page.output_paths.push(local_path.clone());
+                }
+            }
+            CrawlEvent::PageCrawled { local_path, depth, metadata, .. } => {
+                page.status = PageStatus::Saved;
+                page.depth = Some(*depth);
+                page.content_hash.clone_from(&metadata.content_hash);
+                page.duration_ms = Some(metadata.processing_duration.as_millis() as u64);
+                page.finished_at = Some(timestamp);
+                page.error = None;
+                if !page.output_paths.contains(local_path) {
+                    page.output_paths.push(local_path.clone());
+                }
+            }
+            CrawlEvent::CacheHit { .. } => {
+                page.status = PageStatus::Cached;
+                page.finished_at = Some(timestamp);
+            }
+            CrawlEvent::Error { message, error, .. } => {
+                page.status = PageStatus::Failed;
+                page.error = Some(error.clone().unwrap_or_else(|| CrawlError::from_message(message.as_str())));
+                page.finished_at = Some(timestamp);
+            }
+            CrawlEvent::RetryExhausted { attempts, final_error, error, .. } => {
+                page.status = PageStatus::Failed;
+                page.attempts = Some(*attempts);
+                page.error = Some(error.clone().unwrap_or_else(|| CrawlError::from_message(final_error.as_str())));
+                page.finished_at = Some(timestamp);
+            }
+            _ => {}
         }
+        true
     }
 
     /// Mark crawl as successfully completed
@@ -124,8 +390,64 @@ impl CrawlManifest {
     }
 
     /// Mark crawl as failed with error
-    pub fn fail(&mut self, error: String) {
+    pub fn fail(&mut self, error: &CrawlError) {
         self.end_time = Some(Utc::now());
-        self.status = CrawlStatus::Failed { error };
+        self.status = CrawlStatus::Failed {
+            error: error.to_string(),
+            cause: Some(error.clone()),
+        };
+    }
+
+    /// Mark crawl as cut short by a server restart
+    pub fn interrupt(&mut self) {
+        self.end_time = Some(Utc::now());
+        self.status = CrawlStatus::Interrupted;
+    }
+
+    /// How to continue an interrupted crawl
+    ///
+    /// Re-running the crawl into the same directory revalidates saved pages
+    /// by ETag instead of saving them again.
+    #[must_use]
+    pub fn resume_hint(&self) -> String {
+        format!(
+            "scrape_url({{action: 'CRAWL', url: '{}', output_dir: '{}'}})",
+            self.start_url,
+            self.output_dir.display()
+        )
+    }
+}
+
+/// Tool error for a failed crawl
+///
+/// MCP error responses carry only a message, so the [`CrawlError`] in
+/// `error`'s chain is appended to it as a `crawl_error: {"kind": ...}` line
+/// that clients can parse and branch on.
+pub(crate) fn crawl_tool_error(error: anyhow::Error) -> kodegen_mcp_schema::McpError {
+    let Some(json) = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<CrawlError>())
+        .and_then(|crawl_error| serde_json::to_string(crawl_error).ok())
+    else {
+        return kodegen_mcp_schema::McpError::Other(error);
+    };
+    kodegen_mcp_schema::McpError::Other(anyhow::anyhow!("{error:#}\ncrawl_error: {json}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crawl_tool_error_carries_serialized_cause() {
+        let error = anyhow::Error::new(CrawlError::Http { status: 404, message: "HTTP error: 404".to_string() })
+            .context("Crawl failed (http): HTTP error: 404");
+        let message = crawl_tool_error(error).to_string();
+        let json = message.rsplit_once("crawl_error: ").map(|(_, json)| json).unwrap();
+        let cause: CrawlError = serde_json::from_str(json).unwrap();
+        assert_eq!(cause, CrawlError::Http { status: 404, message: "HTTP error: 404".to_string() });
+
+        let plain = crawl_tool_error(anyhow::anyhow!("no url")).to_string();
+        assert!(!plain.contains("crawl_error"));
     }
 }
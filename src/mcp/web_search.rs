@@ -1,12 +1,198 @@
 //! `web_search` MCP tool implementation
 //!
 //! Performs web searches and returns structured results with titles, URLs, and snippets.
+//!
+//! The engine is chosen per call (`engine`) or by the server default
+//! (`CITESCRAPE_SEARCH_ENGINE`, else DuckDuckGo). When it fails, the other
+//! engines are tried in turn; engine health is shared through the
+//! `SearchEngineCache`, so engines that hit CAPTCHAs are demoted for a while.
+//! API engines (SerpAPI, Brave Search API, Kagi) join the chain, ahead of the
+//! scraped ones, once their keys are set.
 
-use kodegen_mcp_schema::citescrape::{WebSearchArgs, WebSearchOutput, WebSearchPrompts, WebSearchResultItem};
-use kodegen_mcp_schema::{Tool, ToolExecutionContext, ToolResponse};
+use kodegen_mcp_schema::citescrape::{
+    ScrapeAction, ScrapeUrlArgs, WEB_SEARCH, WebSearchPrompts, WebSearchResultItem,
+};
+use kodegen_mcp_schema::{Tool, ToolArgs, ToolExecutionContext, ToolResponse};
 use kodegen_mcp_schema::McpError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use super::fetch::markdown_title;
+use super::manager::{ManifestManager, SearchEngineCache, resolve_crawl_dir};
+use super::registry::CrawlRegistry;
+use super::types::{PageOutcome, PageStatus, crawl_tool_error};
+use crate::content_saver::markdown_converter::Typography;
+use crate::mcp::metrics::SearchKind;
+use crate::web_search::{
+    EngineAttempt, MAX_PAGES, MAX_RESULTS, MAX_TOTAL_RESULTS, QueryOperators, SearchEngineKind, SearchLocale,
+    SearchOptions, SearchVertical, VerticalResult,
+};
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_results() -> usize {
+    MAX_RESULTS
+}
+
+fn default_page() -> usize {
+    1
+}
+
+/// Most result pages scraped in deep mode
+const MAX_SCRAPE_RESULTS: usize = 10;
+
+/// Longest wait for the deep-mode crawl of the result pages
+const SCRAPE_TIMEOUT_MS: u64 = 120_000;
+
+// =============================================================================
+// Arguments and Output
+// =============================================================================
+
+/// Arguments for the `web_search` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSearchArgs {
+    /// Search query string (required)
+    pub query: String,
+
+    /// Engine to search: duckduckgo, duckduckgo_html, brave, bing, or the
+    /// API engines serpapi, brave_api and kagi when their keys are configured
+    /// (default: the server's configured engine)
+    #[serde(default)]
+    pub engine: Option<SearchEngineKind>,
+
+    /// Try the other engines when the chosen one fails (default: true)
+    #[serde(default = "default_true")]
+    pub fallback: bool,
+
+    /// Results to return (default: 10, max: 100); more than one SERP page
+    /// is loaded as needed, duplicates across pages are dropped
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+
+    /// SERP page to start from, 1-based (default: 1, max: 10)
+    #[serde(default = "default_page")]
+    pub page: usize,
+
+    /// Only results from the past "day", "week", "month" or "year", or from
+    /// a date range "YYYY-MM-DD..YYYY-MM-DD"
+    #[serde(default)]
+    pub freshness: Option<String>,
+
+    /// Region to search in, two-letter country code (e.g. "de", "jp")
+    #[serde(default)]
+    pub region: Option<String>,
+
+    /// Result and browser language, two-letter language code (e.g. "de", "ja")
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Only results from these domains, e.g. ["docs.rs", "github.com"]
+    #[serde(default)]
+    pub site: Vec<String>,
+
+    /// Drop results from these domains
+    #[serde(default)]
+    pub exclude_site: Vec<String>,
+
+    /// Only documents of this type, e.g. "pdf"
+    #[serde(default)]
+    pub filetype: Option<String>,
+
+    /// Words or phrases results must not contain
+    #[serde(default)]
+    pub exclude_terms: Vec<String>,
+
+    /// Deep mode: also crawl the top N result pages (max 10) and return
+    /// their markdown (default: 0, results only)
+    #[serde(default)]
+    pub scrape_top: usize,
+
+    /// Return the paths of the crawled pages instead of their markdown and
+    /// index them for search (default: false)
+    #[serde(default)]
+    pub save_pages: bool,
+
+    /// Crawl instance deep mode loads the result pages in (default: 0)
+    #[serde(default)]
+    pub crawl_id: u32,
+
+    /// Output directory of the deep-mode crawl (defaults to the crawl
+    /// directory of the first result's domain)
+    #[serde(default)]
+    pub output_dir: Option<String>,
+
+    /// Results to search for: "web" (default), "images" or "news".
+    /// Images and news come from Bing or the SerpAPI / Brave Search APIs
+    #[serde(default)]
+    pub vertical: SearchVertical,
+
+    /// Search again even if the same query was answered recently; fresh
+    /// results still replace the cached ones (default: false)
+    #[serde(default)]
+    pub bypass_cache: bool,
+}
+
+/// A result page loaded in deep mode
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScrapedResultPage {
+    /// Rank of the result the page belongs to
+    pub rank: usize,
+    pub url: String,
+    /// Page title from its first heading
+    pub title: Option<String>,
+    /// Markdown, unless `save_pages` is set
+    pub content: Option<String>,
+    /// Saved markdown file, when `save_pages` is set
+    pub path: Option<String>,
+    /// Error message if the page could not be loaded
+    pub error: Option<String>,
+}
+
+/// Output of the `web_search` tool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebSearchOutput {
+    pub success: bool,
+    pub query: String,
+    /// Engine that produced the results
+    pub engine: SearchEngineKind,
+    /// Engines that failed before `engine` answered
+    pub failed_engines: Vec<EngineAttempt>,
+    /// Results came from the SERP cache
+    pub cached: bool,
+    /// Extraction strategy used when the engine's primary selectors found
+    /// nothing, a sign its markup changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_strategy: Option<String>,
+    pub results_count: usize,
+    pub results: Vec<WebSearchResultItem>,
+    /// Vertical that was searched
+    pub vertical: SearchVertical,
+    /// The same results with their vertical-specific fields (image sizes and
+    /// thumbnails, news sources and dates), tagged by `vertical`
+    pub items: Vec<VerticalResult>,
+    /// Result pages loaded in deep mode (`scrape_top`)
+    pub pages: Vec<ScrapedResultPage>,
+    /// Crawl instance the result pages were loaded in
+    pub crawl_id: Option<u32>,
+    /// Output directory of that crawl
+    pub output_dir: Option<String>,
+}
+
+impl ToolArgs for WebSearchArgs {
+    type Output = WebSearchOutput;
+    type Prompts = WebSearchPrompts;
+
+    const NAME: &'static str = WEB_SEARCH;
+    const CATEGORY: &'static kodegen_config::Category = kodegen_config::CATEGORY_CITESCRAPE;
+    const DESCRIPTION: &'static str = "Perform web search using DuckDuckGo, Brave, Bing or a search API and return structured results with titles, URLs, and snippets";
+}
+
 // =============================================================================
 // ANSI Color Constants
 // =============================================================================
@@ -20,13 +206,200 @@ const ANSI_RESET: &str = "\x1b[0m";
 
 #[derive(Clone)]
 pub struct WebSearchTool {
-    browser_pool: Arc<crate::browser_pool::BrowserPool>,
+    /// Runs the deep-mode crawls and provides the browser pool
+    registry: Arc<CrawlRegistry>,
+    /// Engine used when a call does not pick one
+    default_engine: SearchEngineKind,
+    /// Holds the shared engine health
+    engine_cache: Arc<SearchEngineCache>,
 }
 
 impl WebSearchTool {
+    /// Create the tool with the server default engine from `CITESCRAPE_SEARCH_ENGINE`
     #[must_use]
-    pub fn new(browser_pool: Arc<crate::browser_pool::BrowserPool>) -> Self {
-        Self { browser_pool }
+    pub fn new(registry: Arc<CrawlRegistry>) -> Self {
+        Self {
+            engine_cache: registry.engine_cache().clone(),
+            registry,
+            default_engine: SearchEngineKind::from_env(),
+        }
+    }
+
+    /// Share engine health with other users of `engine_cache`
+    #[must_use]
+    pub fn with_engine_cache(mut self, engine_cache: Arc<SearchEngineCache>) -> Self {
+        self.engine_cache = engine_cache;
+        self
+    }
+
+    /// Use `engine` when a call does not pick one
+    #[must_use]
+    pub fn with_default_engine(mut self, engine: SearchEngineKind) -> Self {
+        self.default_engine = engine;
+        self
+    }
+
+    /// Deep mode: crawl the `results` pages as one crawl and report each in rank order
+    ///
+    /// The pages go through the crawl pipeline under the connection's quota:
+    /// URLs the quota rejects are reported instead of loaded, and the rest
+    /// are saved to the crawl output directory. Returns the pages and the
+    /// crawl's id and output directory, if one was started.
+    async fn scrape_results(
+        &self,
+        results: Vec<VerticalResult>,
+        args: &WebSearchArgs,
+        ctx: &ToolExecutionContext,
+    ) -> Result<(Vec<ScrapedResultPage>, Option<(u32, PathBuf)>), McpError> {
+        let quota = self.registry.quota();
+        let mut blocked = HashMap::new();
+        let mut urls = Vec::with_capacity(results.len());
+        for result in &results {
+            match quota.check_url(result.url()).await {
+                Ok(()) => urls.push(result.url().to_string()),
+                Err(e) => {
+                    blocked.insert(result.url(), format!("{e:#}"));
+                }
+            }
+        }
+
+        let crawl = if urls.is_empty() {
+            None
+        } else {
+            let _ = ctx
+                .update(0.0, results.len() as f64, format!("crawling {} result pages", urls.len()))
+                .await;
+            Some(self.crawl_pages(urls, args, ctx).await?)
+        };
+        let manifest = match &crawl {
+            Some((_, output_dir)) => ManifestManager::load(output_dir).await.ok(),
+            None => None,
+        };
+
+        let mut pages = Vec::with_capacity(results.len());
+        for result in &results {
+            let outcome = match (blocked.get(result.url()), &crawl) {
+                (Some(error), _) => Err(error.clone()),
+                (None, Some((_, output_dir))) => {
+                    let page = manifest.as_ref().and_then(|m| m.pages.get(result.url()));
+                    read_crawled_page(result.url(), output_dir, page).await
+                }
+                (None, None) => Err("Not crawled".to_string()),
+            };
+            pages.push(match outcome {
+                Ok((markdown, path)) => ScrapedResultPage {
+                    rank: result.rank(),
+                    url: result.url().to_string(),
+                    title: markdown_title(&markdown),
+                    path: args.save_pages.then(|| path.to_string_lossy().to_string()),
+                    content: (!args.save_pages).then_some(markdown),
+                    error: None,
+                },
+                Err(error) => ScrapedResultPage {
+                    rank: result.rank(),
+                    url: result.url().to_string(),
+                    title: None,
+                    content: None,
+                    path: None,
+                    error: Some(error),
+                },
+            });
+        }
+        let _ = ctx
+            .update(pages.len() as f64, results.len() as f64, "result pages crawled")
+            .await;
+        Ok((pages, crawl))
+    }
+
+    /// Crawl `urls` (depth 0) and wait for it, returning the crawl's id and output directory
+    async fn crawl_pages(
+        &self,
+        urls: Vec<String>,
+        args: &WebSearchArgs,
+        ctx: &ToolExecutionContext,
+    ) -> Result<(u32, PathBuf), McpError> {
+        let limit = urls.len();
+        let mut urls = urls.into_iter();
+        let Some(start_url) = urls.next() else {
+            return Err(McpError::invalid_arguments("No result pages to crawl"));
+        };
+        let output_dir = resolve_crawl_dir(Some(&start_url), args.output_dir.as_deref(), ctx.pwd())?;
+
+        let crawl_args = ScrapeUrlArgs {
+            action: ScrapeAction::Crawl,
+            crawl_id: args.crawl_id,
+            await_completion_ms: SCRAPE_TIMEOUT_MS,
+            url: Some(start_url),
+            output_dir: Some(output_dir.to_string_lossy().to_string()),
+            max_depth: 0,
+            limit: Some(limit),
+            save_markdown: true,
+            save_screenshots: false,
+            enable_search: args.save_pages,
+            crawl_rate_rps: 2.0,
+            allow_subdomains: false,
+            content_types: None,
+            query: None,
+            search_limit: 10,
+            search_offset: 0,
+            search_highlight: true,
+        };
+
+        let connection_id = ctx.connection_id().unwrap_or("default");
+        let _admission = self
+            .registry
+            .admit_crawl(connection_id, args.crawl_id)
+            .await
+            .map_err(|e| McpError::InvalidArguments(e.to_string()))?;
+        let session = self
+            .registry
+            .find_or_create_crawl(connection_id, args.crawl_id, output_dir.clone())
+            .await
+            .map_err(McpError::Other)?;
+        session
+            .execute_crawl_with_seeds(crawl_args, None, Typography::default(), SCRAPE_TIMEOUT_MS, urls.collect())
+            .await
+            .map_err(crawl_tool_error)?;
+        Ok((args.crawl_id, output_dir))
+    }
+}
+
+/// Markdown and path of a page saved by the deep-mode crawl
+///
+/// Uses the manifest record of the page when there is one; pages without a
+/// record are looked up where the crawl mirrors them.
+async fn read_crawled_page(
+    url: &str,
+    output_dir: &Path,
+    outcome: Option<&PageOutcome>,
+) -> Result<(String, PathBuf), String> {
+    if let Some(outcome) = outcome
+        && outcome.status == PageStatus::Failed
+    {
+        return Err(outcome
+            .error
+            .as_ref()
+            .map_or_else(|| "Page failed".to_string(), ToString::to_string));
+    }
+    let saved = outcome.and_then(|outcome| {
+        outcome
+            .output_paths
+            .iter()
+            .find(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .cloned()
+    });
+    let path = match saved {
+        Some(path) => path,
+        None => crate::utils::get_mirror_path(url, output_dir, "index.md")
+            .await
+            .map_err(|e| format!("{e:#}"))?,
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(markdown) => Ok((markdown, path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err("Page was not saved before the deep-mode timeout".to_string())
+        }
+        Err(e) => Err(format!("Failed to read {}: {e}", path.display())),
     }
 }
 
@@ -39,23 +412,48 @@ impl Tool for WebSearchTool {
     type Prompts = WebSearchPrompts;
 
     fn name() -> &'static str {
-        kodegen_mcp_schema::citescrape::WEB_SEARCH
+        WEB_SEARCH
     }
 
     fn description() -> &'static str {
-        "Perform a web search using DuckDuckGo and return structured results with titles, URLs, and snippets.\\n\\n\
-         Returns up to 10 search results with:\\n\
-         - rank: Result position (1-10)\\n\
+        "Perform a web search and return structured results with titles, URLs, and snippets.\\n\\n\
+         Returns up to max_results search results (default 10, up to 100 by paging through the SERP) with:\\n\
+         - rank: Result position\\n\
          - title: Page title\\n\
          - url: Page URL\\n\
          - snippet: Description excerpt\\n\\n\
-         Uses DuckDuckGo to avoid CAPTCHA issues. First search takes ~5-6s (browser launch), \
+         Engines: duckduckgo (default), duckduckgo_html, brave, bing; pick one with engine \
+         when another is blocked or returns poor results. If an engine fails or shows a CAPTCHA, \
+         the others are tried in turn (disable with fallback: false) and the failing engine is \
+         demoted for a few minutes. API engines serpapi, brave_api and kagi are used (first) \
+         when the server has CITESCRAPE_SERPAPI_KEY, CITESCRAPE_BRAVE_API_KEY or \
+         CITESCRAPE_KAGI_API_KEY set. First search takes ~5-6s (browser launch), \
          subsequent searches take ~3-4s.\\n\\n\
-         Example: web_search({\\\"query\\\": \\\"rust async programming\\\"})"
+         Restrict to recent results with freshness: day, week, month, year or \
+         'YYYY-MM-DD..YYYY-MM-DD' (mapped to each engine's date filter). \
+         Search a region and language with region (country code) and language (language code); \
+         the browser's Accept-Language follows the language. \
+         Restrict domains and document types with site, exclude_site, filetype and exclude_terms \
+         instead of writing engine operators into the query.\\n\\n\
+         Search images (thumbnail URLs and dimensions) or news (sources and publication dates) \
+         with vertical: images or news; the typed results are in items, tagged by vertical.\\n\\n\
+         Identical searches are answered from a short-lived cache; set bypass_cache: true \
+         to search again.\\n\\n\
+         Deep mode: scrape_top: N (max 10) also crawls the top N result pages (like scrape_url, \
+         under the server's crawl limits) into output_dir and returns their markdown in pages, \
+         or their saved paths, indexed for search, with save_pages: true.\\n\\n\
+         Example: web_search({\\\"query\\\": \\\"rust async programming\\\"})\\n\
+         Example: web_search({\\\"query\\\": \\\"tokio select\\\", \\\"engine\\\": \\\"bing\\\"})\\n\
+         Example: web_search({\\\"query\\\": \\\"rust web frameworks\\\", \\\"max_results\\\": 50})\\n\
+         Example: web_search({\\\"query\\\": \\\"rust release\\\", \\\"freshness\\\": \\\"week\\\"})\\n\
+         Example: web_search({\\\"query\\\": \\\"rust tutorial\\\", \\\"region\\\": \\\"de\\\", \\\"language\\\": \\\"de\\\"})\\n\
+         Example: web_search({\\\"query\\\": \\\"tokio runtime\\\", \\\"site\\\": [\\\"docs.rs\\\"], \\\"exclude_terms\\\": [\\\"deprecated\\\"]})\\n\
+         Example: web_search({\\\"query\\\": \\\"axum middleware\\\", \\\"scrape_top\\\": 3})"
     }
 
     fn read_only() -> bool {
-        true
+        // Deep mode can save pages
+        false
     }
 
     fn destructive() -> bool {
@@ -66,7 +464,7 @@ impl Tool for WebSearchTool {
         true
     }
 
-    async fn execute(&self, args: Self::Args, _ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
+    async fn execute(&self, args: Self::Args, ctx: ToolExecutionContext) -> Result<ToolResponse<<Self::Args as kodegen_mcp_schema::ToolArgs>::Output>, McpError> {
         // Validate query is not empty
         let query = args.query.trim();
         if query.is_empty() {
@@ -86,36 +484,171 @@ impl Tool for WebSearchTool {
             ));
         }
 
+        if !(1..=MAX_TOTAL_RESULTS).contains(&args.max_results) {
+            return Err(McpError::invalid_arguments(format!(
+                "max_results must be between 1 and {MAX_TOTAL_RESULTS}"
+            )));
+        }
+        if !(1..=MAX_PAGES).contains(&args.page) {
+            return Err(McpError::invalid_arguments(format!("page must be between 1 and {MAX_PAGES}")));
+        }
+        if args.scrape_top > MAX_SCRAPE_RESULTS {
+            return Err(McpError::invalid_arguments(format!(
+                "scrape_top must be at most {MAX_SCRAPE_RESULTS}"
+            )));
+        }
+        let freshness = args
+            .freshness
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e: anyhow::Error| McpError::invalid_arguments(e.to_string()))?;
+        let locale = if args.region.is_some() || args.language.is_some() {
+            Some(
+                SearchLocale::new(args.region.as_deref(), args.language.as_deref())
+                    .map_err(|e| McpError::invalid_arguments(e.to_string()))?,
+            )
+        } else {
+            None
+        };
+        let operators = QueryOperators {
+            sites: args.site.clone(),
+            exclude_sites: args.exclude_site.clone(),
+            filetype: args.filetype.clone(),
+            exclude_terms: args.exclude_terms.clone(),
+        };
+        let query = operators
+            .apply(query)
+            .map_err(|e| McpError::invalid_arguments(e.to_string()))?;
+        let options = SearchOptions {
+            start_page: args.page,
+            max_results: args.max_results,
+            freshness,
+            locale,
+            vertical: args.vertical,
+        };
+
         // Perform search using browser pool
-        let results = crate::web_search::search_with_pool(&self.browser_pool, args.query)
-            .await
-            .map_err(McpError::Other)?;
+        let preferred = args.engine.unwrap_or(self.default_engine);
+        let chain = if args.fallback {
+            SearchEngineKind::fallback_chain(preferred)
+        } else {
+            vec![preferred]
+        };
+        let search_start = std::time::Instant::now();
+        let searched = crate::web_search::search_with_fallback(
+            self.registry.browser_pool(),
+            query,
+            &chain,
+            &options,
+            self.engine_cache.web_engine_health(),
+            (!args.bypass_cache).then(|| self.engine_cache.serp_cache().as_ref()),
+            self.engine_cache.web_search_pacer(),
+        )
+        .await;
+        crate::mcp::metrics::metrics().record_search(SearchKind::Web, search_start.elapsed(), searched.is_ok());
+        let (results, failed_engines) = searched.map_err(McpError::Other)?;
 
         // Build summary
         let count = results.results.len();
         let first_title = if results.results.is_empty() {
             "No results"
         } else {
-            &results.results[0].title
+            results.results[0].title()
         };
 
-        let line1 = format!("{}Web Search: {}{}", ANSI_CYAN, results.query, ANSI_RESET);
+        let source = if results.cached {
+            format!("{}, cached", results.engine)
+        } else {
+            results.engine.to_string()
+        };
+        let line1 = format!("{}Web Search ({}): {}{}", ANSI_CYAN, source, results.query, ANSI_RESET);
         let line2 = format!("  Results: {} · Top: {}", count, first_title);
-        let summary = format!("{}\n{}", line1, line2);
+        let mut summary = format!("{}\n{}", line1, line2);
+        for attempt in &failed_engines {
+            let _ = write!(summary, "\n  {} failed: {}", attempt.engine, attempt.error);
+        }
+        if let Some(strategy) = &results.fallback_strategy {
+            let _ = write!(summary, "\n  Extracted with fallback selectors: {strategy}");
+        }
+
+        let top: Vec<_> = results.results.iter().take(args.scrape_top).cloned().collect();
+        let (pages, crawl) = if top.is_empty() {
+            (Vec::new(), None)
+        } else {
+            self.scrape_results(top, &args, &ctx).await?
+        };
+        if !pages.is_empty() {
+            let scraped = pages.iter().filter(|p| p.error.is_none()).count();
+            let _ = write!(summary, "\n  Scraped: {scraped}/{} pages", pages.len());
+        }
+        if let Some((id, dir)) = &crawl {
+            let _ = write!(summary, "\n  Crawl {id} -> {}", dir.display());
+        }
 
         // Build typed output
         let output = WebSearchOutput {
             success: true,
             query: results.query,
+            engine: results.engine,
+            failed_engines,
+            cached: results.cached,
+            fallback_strategy: results.fallback_strategy.clone(),
             results_count: results.results.len(),
-            results: results.results.into_iter().map(|r| WebSearchResultItem {
-                rank: r.rank as u32,
-                title: r.title,
-                url: r.url,
-                snippet: Some(r.snippet),
+            results: results.results.iter().map(|r| WebSearchResultItem {
+                rank: r.rank() as u32,
+                title: r.title().to_string(),
+                url: r.url().to_string(),
+                snippet: Some(r.snippet().to_string()),
             }).collect(),
+            vertical: options.vertical,
+            items: results.results,
+            pages,
+            crawl_id: crawl.as_ref().map(|(id, _)| *id),
+            output_dir: crawl.map(|(_, dir)| dir.to_string_lossy().to_string()),
         };
 
         Ok(ToolResponse::new(summary, output))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crawl_engine::crawl_types::CrawlError;
+
+    #[tokio::test]
+    async fn test_read_crawled_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = "https://example.com/guide";
+
+        let mirrored = crate::utils::get_mirror_path(url, dir.path(), "index.md").await.unwrap();
+        tokio::fs::create_dir_all(mirrored.parent().unwrap()).await.unwrap();
+        tokio::fs::write(&mirrored, "# Guide\n").await.unwrap();
+        let (markdown, path) = read_crawled_page(url, dir.path(), None).await.unwrap();
+        assert_eq!(markdown, "# Guide\n");
+        assert_eq!(path, mirrored);
+
+        let recorded = dir.path().join("guide.md");
+        tokio::fs::write(&recorded, "# Recorded\n").await.unwrap();
+        let outcome = PageOutcome {
+            status: PageStatus::Saved,
+            output_paths: vec![dir.path().join("guide.html"), recorded.clone()],
+            ..PageOutcome::default()
+        };
+        let (markdown, path) = read_crawled_page(url, dir.path(), Some(&outcome)).await.unwrap();
+        assert_eq!(markdown, "# Recorded\n");
+        assert_eq!(path, recorded);
+
+        let failed = PageOutcome {
+            status: PageStatus::Failed,
+            error: Some(CrawlError::from_message("navigation timed out")),
+            ..PageOutcome::default()
+        };
+        let error = read_crawled_page(url, dir.path(), Some(&failed)).await.unwrap_err();
+        assert!(error.contains("navigation timed out"), "{error}");
+
+        let missing = read_crawled_page("https://example.com/other", dir.path(), None).await;
+        assert!(missing.unwrap_err().contains("not saved"));
+    }
+}
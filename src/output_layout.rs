@@ -0,0 +1,350 @@
+//! Versioning of the crawl output directory layout
+//!
+//! The mirror path scheme has changed over time: version 1 wrote pages to
+//! `host/<raw URL path>/`, version 2 ([`mirror_relative_path`]) escapes names
+//! that are unsafe on Windows, canonicalizes percent-escapes and shortens
+//! overlong paths. Recrawling an old output directory with a newer build would
+//! otherwise leave both schemes side by side, so the directory is stamped with
+//! its layout version in `.citescrape/layout.json` and crawls refuse to write
+//! to older layouts. Migrating is an explicit step (`citescrape migrate`):
+//! moving pages leaves the relative links other pages hold to them and the
+//! search index pointing at the old paths until the site is crawled again.
+//!
+//! The `.citescrape` SQLite schema upgrades itself when opened (see
+//! [`LinkIndex::open`]); a migration opens the link index for that reason and
+//! to point its pages at their new paths.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use jwalk::WalkDir;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::link_index::LinkIndex;
+use crate::utils::url_utils::mirror_relative_path;
+
+/// Layout written by this build
+pub const LAYOUT_VERSION: u32 = 2;
+
+/// Layout of output directories written before stamping existed
+const UNSTAMPED_LAYOUT_VERSION: u32 = 1;
+
+/// Contents of `.citescrape/layout.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LayoutStamp {
+    version: u32,
+    /// Version of the citescrape build that wrote the stamp
+    written_by: String,
+}
+
+/// What [`migrate_output_dir`] did (or would do on a dry run)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Layout found in the directory
+    pub from_version: u32,
+    /// Layout after the migration
+    pub to_version: u32,
+    /// Files moved to their current mirror path
+    pub files_moved: usize,
+    /// Files left in place because their new path was already taken
+    pub conflicts: Vec<PathBuf>,
+    /// Link index pages pointed at a moved file
+    pub pages_relocated: u64,
+    /// Nothing was changed on disk
+    pub dry_run: bool,
+}
+
+impl MigrationReport {
+    /// Whether the migration changed (or would change) anything
+    pub fn is_noop(&self) -> bool {
+        self.files_moved == 0 && self.from_version == self.to_version
+    }
+}
+
+/// Path of the layout stamp for an output directory
+pub fn stamp_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(".citescrape").join("layout.json")
+}
+
+/// Layout version of an output directory
+///
+/// Returns `None` for a directory that holds no mirrored pages yet. A
+/// directory with pages but no stamp predates stamping and is version 1.
+pub fn read_layout_version(output_dir: &Path) -> Result<Option<u32>> {
+    let path = stamp_path(output_dir);
+    match std::fs::read_to_string(&path) {
+        Ok(json) => {
+            let stamp: LayoutStamp = serde_json::from_str(&json)
+                .with_context(|| format!("Invalid layout stamp {}", path.display()))?;
+            Ok(Some(stamp.version))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok((!host_dirs(output_dir)?.is_empty()).then_some(UNSTAMPED_LAYOUT_VERSION))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Record that `output_dir` uses the current layout
+pub fn stamp_layout(output_dir: &Path) -> Result<()> {
+    let path = stamp_path(output_dir);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("Failed to create .citescrape directory")?;
+    }
+    let stamp = LayoutStamp {
+        version: LAYOUT_VERSION,
+        written_by: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    std::fs::write(&path, serde_json::to_string_pretty(&stamp)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Check that an output directory uses the current layout before crawling into it
+///
+/// Empty and new directories are just stamped. Older layouts are refused
+/// until migrated with `citescrape migrate`, since a migration breaks links
+/// between saved pages; a directory written by a newer build is refused
+/// rather than mixed with this build's layout.
+pub async fn ensure_current_layout(output_dir: &Path) -> Result<()> {
+    match read_layout_version(output_dir)? {
+        Some(LAYOUT_VERSION) => Ok(()),
+        Some(version) if version > LAYOUT_VERSION => bail!(
+            "{} uses output layout version {version}, but this build only understands up to {LAYOUT_VERSION}; \
+             upgrade citescrape or crawl into a new directory",
+            output_dir.display()
+        ),
+        Some(version) => bail!(
+            "{} uses output layout version {version}, older than this build's {LAYOUT_VERSION}; \
+             run `citescrape migrate {}` (then recrawl to refresh links between moved pages) \
+             or crawl into a new directory",
+            output_dir.display(),
+            output_dir.display()
+        ),
+        None => stamp_layout(output_dir),
+    }
+}
+
+/// Move the files of an older layout to their current mirror paths
+///
+/// Each file keeps its name and moves to the directory the current scheme
+/// gives its URL. Files whose new path already exists are left in place and
+/// reported as conflicts. The link index is updated to the new paths, but
+/// relative links in other pages and the search index keep the old paths
+/// until the site is crawled again, so this only runs when asked for
+/// (`citescrape migrate`), never as part of a crawl.
+pub async fn migrate_output_dir(output_dir: &Path, dry_run: bool) -> Result<MigrationReport> {
+    let from_version = match read_layout_version(output_dir)? {
+        Some(version) if version > LAYOUT_VERSION => bail!(
+            "{} uses output layout version {version}, newer than this build ({LAYOUT_VERSION})",
+            output_dir.display()
+        ),
+        Some(version) => version,
+        None => LAYOUT_VERSION,
+    };
+
+    let root = output_dir.to_path_buf();
+    let (moves, conflicts) = tokio::task::spawn_blocking(move || plan_moves(&root))
+        .await
+        .context("Layout scan panicked")??;
+
+    let mut report = MigrationReport {
+        from_version,
+        to_version: LAYOUT_VERSION,
+        files_moved: moves.len(),
+        conflicts,
+        pages_relocated: 0,
+        dry_run,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    let root = output_dir.to_path_buf();
+    let planned = moves.clone();
+    tokio::task::spawn_blocking(move || apply_moves(&root, &planned))
+        .await
+        .context("Layout migration panicked")??;
+
+    if LinkIndex::db_path(output_dir).exists() {
+        let index = LinkIndex::open(output_dir).await?;
+        for (from, to) in &moves {
+            report.pages_relocated += index.relocate_page_path(from, to).await?;
+        }
+        index.close().await;
+    }
+
+    stamp_layout(output_dir)?;
+    Ok(report)
+}
+
+/// Top-level mirror directories (one per host), skipping `.citescrape` and
+/// other dot directories
+fn host_dirs(output_dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(output_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", output_dir.display())),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && !entry.file_name().to_string_lossy().starts_with('.') {
+            dirs.push(entry.path());
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Where the current scheme puts a file found at `relative` (`host/segments/name`)
+///
+/// `None` when the path cannot be read back as a URL, which leaves the file
+/// where it is.
+fn current_location(relative: &Path) -> Option<PathBuf> {
+    let mut components: Vec<&str> = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    let file_name = components.pop()?;
+    let (host, segments) = components.split_first()?;
+    let url = Url::parse(&format!("http://{host}/{}", segments.join("/"))).ok()?;
+    Some(mirror_relative_path(&url).ok()?.join(file_name))
+}
+
+/// Absolute (from, to) paths of a file to move
+type FileMove = (PathBuf, PathBuf);
+
+/// Files to move, and files left in place because their target is taken
+fn plan_moves(output_dir: &Path) -> Result<(Vec<FileMove>, Vec<PathBuf>)> {
+    let mut moves = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut claimed = std::collections::HashSet::new();
+    for host_dir in host_dirs(output_dir)? {
+        for entry in WalkDir::new(&host_dir).sort(true).skip_hidden(false).follow_links(false) {
+            let entry = entry.context("Failed to walk crawl output directory")?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(output_dir) else {
+                continue;
+            };
+            let Some(target) = current_location(relative) else {
+                continue;
+            };
+            if target == relative {
+                continue;
+            }
+            let target = output_dir.join(target);
+            if target.exists() || !claimed.insert(target.clone()) {
+                conflicts.push(path);
+                continue;
+            }
+            moves.push((path, target));
+        }
+    }
+    Ok((moves, conflicts))
+}
+
+fn apply_moves(output_dir: &Path, moves: &[FileMove]) -> Result<()> {
+    for (from, to) in moves {
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::rename(from, to)
+            .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))?;
+    }
+
+    // Drop directories the moves left empty, deepest first
+    let mut emptied: Vec<&Path> = moves
+        .iter()
+        .flat_map(|(from, _)| from.ancestors().skip(1))
+        .filter(|dir| dir.starts_with(output_dir) && *dir != output_dir)
+        .collect();
+    emptied.sort_by(|a, b| b.components().count().cmp(&a.components().count()).then(a.cmp(b)));
+    emptied.dedup();
+    for dir in emptied {
+        // Fails for directories that still hold files, which is what we want
+        let _ = std::fs::remove_dir(dir);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, relative).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_migrates_unstamped_layout() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        write(root, "example.com/docs/con/index.md");
+        write(root, "example.com/docs/a:b/index.html");
+        write(root, "example.com/docs/index.md");
+        assert_eq!(read_layout_version(root)?, Some(1));
+
+        let index = LinkIndex::open(root).await?;
+        let old_page = root.join("example.com/docs/con/index.md");
+        index.register_page("http://example.com/docs/con", &old_page, &[]).await?;
+        index.close().await;
+
+        let dry = migrate_output_dir(root, true).await?;
+        assert_eq!(dry.files_moved, 2);
+        assert!(old_page.exists());
+        assert_eq!(read_layout_version(root)?, Some(1));
+
+        let report = migrate_output_dir(root, false).await?;
+        assert_eq!(report.files_moved, 2);
+        assert_eq!(report.pages_relocated, 1);
+        assert!(report.conflicts.is_empty());
+        assert!(root.join("example.com/docs/con_/index.md").exists());
+        assert!(root.join("example.com/docs/a%3Ab/index.html").exists());
+        assert!(root.join("example.com/docs/index.md").exists());
+        assert!(!root.join("example.com/docs/con").exists());
+        assert_eq!(read_layout_version(root)?, Some(LAYOUT_VERSION));
+
+        let index = LinkIndex::open(root).await?;
+        assert_eq!(
+            index.get_local_path("http://example.com/docs/con").await?,
+            Some(root.join("example.com/docs/con_/index.md"))
+        );
+        index.close().await;
+
+        // Already current: nothing left to move
+        assert!(migrate_output_dir(root, false).await?.is_noop());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ensure_current_layout() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        assert_eq!(read_layout_version(root)?, None);
+        ensure_current_layout(root).await?;
+        assert_eq!(read_layout_version(root)?, Some(LAYOUT_VERSION));
+
+        std::fs::write(
+            stamp_path(root),
+            format!(r#"{{"version": {}, "written_by": "9.9.9"}}"#, LAYOUT_VERSION + 1),
+        )?;
+        assert!(ensure_current_layout(root).await.is_err());
+
+        // Older layouts wait for an explicit migration
+        let old = TempDir::new()?;
+        write(old.path(), "example.com/docs/con/index.md");
+        let error = ensure_current_layout(old.path()).await.unwrap_err();
+        assert!(error.to_string().contains("citescrape migrate"), "{error}");
+        assert!(old.path().join("example.com/docs/con/index.md").exists());
+        assert_eq!(read_layout_version(old.path())?, Some(1));
+        Ok(())
+    }
+}
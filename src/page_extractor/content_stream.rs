@@ -0,0 +1,162 @@
+//! Streamed transfer of the serialized DOM
+//!
+//! `Page::content()` returns the whole document in a single CDP response,
+//! so a multi-megabyte page exists at once as the websocket frame, the
+//! decoded JSON message and the final `String`. Here the page serializes
+//! itself into a `Blob` instead, and the blob is read back with `IO.read`
+//! in bounded chunks into one buffer sized up front.
+//!
+//! Reading stops at a byte cap, so a pathological generated page cannot
+//! pull hundreds of megabytes into the process. A capped document ends with
+//! an HTML comment recording where it was cut.
+
+use anyhow::{Context, Result, anyhow};
+use base64::Engine;
+use chromiumoxide::Page;
+use chromiumoxide::cdp::browser_protocol::io::{CloseParams, ReadParams, ResolveBlobParams};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    CallFunctionOnParams, EvaluateParams, ReleaseObjectParams, RemoteObjectId,
+};
+
+/// Serializes the document (doctype included, as `Page::content()` does) into a Blob
+const CONTENT_BLOB_SCRIPT: &str = r"(() => {
+    let html = '';
+    if (document.doctype) {
+        html = new XMLSerializer().serializeToString(document.doctype);
+    }
+    if (document.documentElement) {
+        html += document.documentElement.outerHTML;
+    }
+    return new Blob([html], { type: 'text/html' });
+})()";
+
+/// Bytes requested per `IO.read`; documents up to this size arrive in one read
+const READ_CHUNK_BYTES: i64 = 1024 * 1024;
+
+/// Get the page's serialized HTML, streamed in chunks and capped at `max_bytes`.
+///
+/// Falls back to `Page::content()` if the browser cannot stream the blob.
+pub async fn page_content(page: &Page, max_bytes: usize) -> Result<String> {
+    match stream_content(page, max_bytes).await {
+        Ok(content) => Ok(content),
+        Err(e) => {
+            log::debug!("Streamed content transfer failed, fetching in one response: {e:#}");
+            let mut content = page
+                .content()
+                .await
+                .map_err(|e| anyhow!("Failed to get page content: {e}"))?;
+            let size = content.len() as u64;
+            if content.len() > max_bytes {
+                content.truncate(content.floor_char_boundary(max_bytes));
+                push_truncation_marker(&mut content, size);
+            }
+            Ok(content)
+        }
+    }
+}
+
+/// Note where a document over the byte cap was cut off
+fn push_truncation_marker(content: &mut String, size: u64) {
+    log::warn!("Page HTML truncated to {} of {size} bytes", content.len());
+    content.push_str(&format!(
+        "\n<!-- citescrape: document truncated at {} of {size} bytes -->",
+        content.len()
+    ));
+}
+
+async fn stream_content(page: &Page, max_bytes: usize) -> Result<String> {
+    let params = EvaluateParams::builder()
+        .expression(CONTENT_BLOB_SCRIPT)
+        .return_by_value(false)
+        .build()
+        .map_err(|e| anyhow!(e))?;
+    let blob = page
+        .evaluate_expression(params)
+        .await
+        .context("Failed to serialize document")?;
+    let object_id = blob
+        .object()
+        .object_id
+        .clone()
+        .ok_or_else(|| anyhow!("Document blob has no remote object id"))?;
+
+    let result = read_blob(page, &object_id, max_bytes).await;
+    if let Err(e) = page.execute(ReleaseObjectParams::new(object_id)).await {
+        log::debug!("Failed to release document blob: {e}");
+    }
+    result
+}
+
+async fn read_blob(page: &Page, object_id: &RemoteObjectId, max_bytes: usize) -> Result<String> {
+    let size = page
+        .execute(
+            CallFunctionOnParams::builder()
+                .function_declaration("function() { return this.size; }")
+                .object_id(object_id.clone())
+                .return_by_value(true)
+                .build()
+                .map_err(|e| anyhow!(e))?,
+        )
+        .await
+        .context("Failed to get document blob size")?
+        .result
+        .result
+        .value
+        .and_then(|size| size.as_u64())
+        .ok_or_else(|| anyhow!("Document blob size is not a number"))?;
+
+    let uuid = page
+        .execute(ResolveBlobParams::new(object_id.clone()))
+        .await
+        .context("Failed to resolve document blob")?
+        .result
+        .uuid;
+    let handle = format!("blob:{uuid}");
+
+    let mut bytes = Vec::with_capacity(usize::try_from(size)?.min(max_bytes));
+    let read = async {
+        loop {
+            let chunk = page
+                .execute(
+                    ReadParams::builder()
+                        .handle(handle.clone())
+                        .size(READ_CHUNK_BYTES)
+                        .build()
+                        .map_err(|e| anyhow!(e))?,
+                )
+                .await
+                .context("Failed to read document blob")?
+                .result;
+            if chunk.base64_encoded.unwrap_or(false) {
+                base64::engine::general_purpose::STANDARD
+                    .decode_vec(chunk.data.as_bytes(), &mut bytes)
+                    .context("Invalid base64 in document blob chunk")?;
+            } else {
+                bytes.extend_from_slice(chunk.data.as_bytes());
+            }
+            if chunk.eof || bytes.len() >= max_bytes {
+                return Ok::<_, anyhow::Error>(());
+            }
+        }
+    }
+    .await;
+    if let Err(e) = page.execute(CloseParams::new(handle)).await {
+        log::debug!("Failed to close document blob stream: {e}");
+    }
+    read?;
+
+    if bytes.len() <= max_bytes && bytes.len() as u64 >= size {
+        return String::from_utf8(bytes).context("Document blob is not valid UTF-8");
+    }
+    bytes.truncate(max_bytes);
+    // The cut may split a multi-byte character; back up to the last whole one
+    let valid = match std::str::from_utf8(&bytes) {
+        Ok(_) => bytes.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(e) => return Err(e).context("Document blob is not valid UTF-8"),
+    };
+    bytes.truncate(valid);
+    let mut content = String::from_utf8(bytes).context("Document blob is not valid UTF-8")?;
+    push_truncation_marker(&mut content, size);
+    Ok(content)
+}
@@ -4,7 +4,7 @@
 //! with pre-allocated buffers and lock-free operations.
 
 use super::js_scripts::{
-    HEADINGS_SCRIPT, INTERACTIVE_ELEMENTS_SCRIPT, METADATA_SCRIPT, RESOURCES_SCRIPT, SECURITY_SCRIPT, TIMING_SCRIPT,
+    DISMISS_OVERLAYS_SCRIPT, HEADINGS_SCRIPT, INTERACTIVE_ELEMENTS_SCRIPT, METADATA_SCRIPT, RESOURCES_SCRIPT, SECURITY_SCRIPT, TIMING_SCRIPT,
 };
 use super::schema::InteractiveElement;
 use super::schema::{HeadingElement, PageMetadata, ResourceInfo, SecurityInfo, TimingInfo};
@@ -99,6 +99,137 @@ pub async fn wait_for_page_load(page: &Page, max_wait_secs: u64) -> Result<()> {
     Ok(())
 }
 
+/// Per-crawl conditions that decide when a page is "ready" for extraction
+///
+/// The fixed `wait_for_page_load()` heuristics snapshot some SPAs before
+/// their content renders. These conditions let callers name the signal
+/// the page actually gives when it is done.
+#[derive(Debug, Clone, Default)]
+pub struct PageReadyConditions {
+    /// CSS selector that must match at least one element
+    pub selector: Option<String>,
+    /// Milliseconds without new resource requests
+    pub network_idle_ms: Option<u64>,
+    /// JavaScript expression or function that must return a truthy value
+    pub function: Option<String>,
+}
+
+impl PageReadyConditions {
+    /// True when no readiness condition is configured
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.selector.is_none() && self.network_idle_ms.is_none() && self.function.is_none()
+    }
+}
+
+/// Wait for the configured page readiness conditions
+///
+/// Each condition is polled in turn (selector, then function, then network idle)
+/// against a shared deadline of `max_wait_secs`. Conditions that are not met
+/// before the deadline are logged and extraction proceeds anyway, matching
+/// the behavior of `wait_for_page_load()`.
+///
+/// # Arguments
+/// * `page` - Page to wait for
+/// * `conditions` - Readiness conditions from `CrawlConfig`
+/// * `max_wait_secs` - Maximum total time to wait
+pub async fn wait_for_ready_conditions(
+    page: &Page,
+    conditions: &PageReadyConditions,
+    max_wait_secs: u64,
+) -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    if conditions.is_empty() {
+        return Ok(());
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(max_wait_secs);
+    let poll_interval = Duration::from_millis(100);
+
+    if let Some(selector) = &conditions.selector {
+        let selector_json = serde_json::to_string(selector)?;
+        let script = format!("document.querySelector({selector_json}) !== null");
+        if poll_until_truthy(page, &script, deadline, poll_interval).await {
+            log::debug!("Selector '{}' matched", selector);
+        } else {
+            log::warn!("Timeout waiting for selector '{}', proceeding anyway", selector);
+        }
+    }
+
+    if let Some(function) = &conditions.function {
+        let script = format!(
+            "(async () => {{ const __ready = ({function}); \
+             return !!(typeof __ready === 'function' ? await __ready() : await __ready); }})()"
+        );
+        if poll_until_truthy(page, &script, deadline, poll_interval).await {
+            log::debug!("Readiness function returned truthy value");
+        } else {
+            log::warn!("Timeout waiting for readiness function, proceeding anyway");
+        }
+    }
+
+    if let Some(idle_ms) = conditions.network_idle_ms {
+        let idle = Duration::from_millis(idle_ms);
+        let count_script = "performance.getEntriesByType('resource').length";
+        let mut last_count: Option<u64> = None;
+        let mut quiet_since = Instant::now();
+
+        loop {
+            if Instant::now() >= deadline {
+                log::warn!("Timeout waiting for {}ms network idle, proceeding anyway", idle_ms);
+                break;
+            }
+
+            let count = match page.evaluate(count_script).await {
+                Ok(result) => result.into_value::<u64>().ok(),
+                Err(e) => {
+                    log::debug!("Failed to read resource entries: {}, retrying", e);
+                    None
+                }
+            };
+
+            if count.is_some() && count != last_count {
+                last_count = count;
+                quiet_since = Instant::now();
+            } else if quiet_since.elapsed() >= idle {
+                log::debug!("Network idle for {}ms", idle_ms);
+                break;
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Poll a JavaScript expression until it evaluates truthy or the deadline passes
+async fn poll_until_truthy(
+    page: &Page,
+    script: &str,
+    deadline: std::time::Instant,
+    poll_interval: std::time::Duration,
+) -> bool {
+    loop {
+        match page.evaluate(script).await {
+            Ok(result) => {
+                if result.into_value::<bool>().unwrap_or(false) {
+                    return true;
+                }
+            }
+            Err(e) => {
+                log::debug!("Readiness check failed: {}, retrying", e);
+            }
+        }
+
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
 /// Scroll to bottom of page to trigger lazy-loaded content
 ///
 /// This function scrolls the page in increments to trigger lazy-loading
@@ -154,6 +285,39 @@ pub async fn scroll_to_bottom(page: &Page, scroll_pause_ms: u64) -> Result<()> {
     Ok(())
 }
 
+/// What [`dismiss_overlays`] did on a page
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct OverlayDismissal {
+    /// Selectors or labels of the consent buttons clicked
+    pub clicked: Vec<String>,
+    /// Overlay elements removed from the DOM
+    pub removed: usize,
+}
+
+impl OverlayDismissal {
+    pub fn is_empty(&self) -> bool {
+        self.clicked.is_empty() && self.removed == 0
+    }
+}
+
+/// Dismiss cookie consent banners and paywall overlays
+///
+/// Removes consent containers, fixed modals and backdrops so the saved HTML
+/// and markdown are not dominated by banner text. With `click_reject`, first
+/// clicks the "reject" button of known consent managers (OneTrust, Cookiebot,
+/// Didomi, Quantcast, TrustArc, ...) or of a dialog labelled as one; consent
+/// is never accepted and links are never clicked.
+pub async fn dismiss_overlays(page: &Page, click_reject: bool) -> Result<OverlayDismissal> {
+    let result = page
+        .evaluate(format!("({DISMISS_OVERLAYS_SCRIPT})({click_reject})"))
+        .await
+        .context("Failed to execute overlay dismissal script")?;
+    let value = result
+        .into_value::<serde_json::Value>()
+        .map_err(|e| anyhow::anyhow!("Failed to get overlay dismissal result: {e}"))?;
+    serde_json::from_value(value).context("Failed to parse overlay dismissal result")
+}
+
 /// Capture screenshot with retry logic for transient CDP errors
 ///
 /// CDP error -32000 "Unable to capture screenshot" can occur transiently when:
@@ -463,6 +627,7 @@ pub async fn capture_screenshot(
         screenshot_data,
         &path,
         "image/png",
+        crate::content_saver::ResponseValidators::default(),
         false,
         compression_threshold,
     )
@@ -0,0 +1,176 @@
+//! Scripted page interactions
+//!
+//! An ordered list of steps (click, type, press, submit, wait) replayed on a
+//! live page so content behind cookie walls, tabs or search forms can be
+//! reached before extraction.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use chromiumoxide::Page;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Timeout for steps that wait for navigation or a selector without an explicit one
+pub const DEFAULT_STEP_TIMEOUT_MS: u64 = 10_000;
+
+/// Longest fixed pause a `wait` step may request
+const MAX_WAIT_MS: u64 = 30_000;
+
+/// One interaction step
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum InteractionStep {
+    /// Click the first element matching `selector`
+    Click {
+        selector: String,
+        /// Wait for the navigation the click triggers
+        #[serde(default)]
+        wait_for_navigation: bool,
+    },
+    /// Focus the element and type `text` into it
+    Type {
+        selector: String,
+        text: String,
+        /// Clear the current value first
+        #[serde(default)]
+        clear: bool,
+    },
+    /// Press a key (e.g. "Enter", "Escape", "Tab") on `selector` or the focused element
+    Press {
+        key: String,
+        #[serde(default)]
+        selector: Option<String>,
+    },
+    /// Submit the form containing `selector` (or the form itself) and wait for navigation
+    Submit { selector: String },
+    /// Wait until `selector` appears
+    WaitFor {
+        selector: String,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+    /// Pause for a fixed time (max 30s)
+    Wait { ms: u64 },
+}
+
+impl InteractionStep {
+    /// Short human-readable form, used in logs and error messages
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Click { selector, .. } => format!("click '{selector}'"),
+            Self::Type { selector, text, .. } => format!("type {} chars into '{selector}'", text.chars().count()),
+            Self::Press { key, .. } => format!("press {key}"),
+            Self::Submit { selector } => format!("submit '{selector}'"),
+            Self::WaitFor { selector, .. } => format!("wait for '{selector}'"),
+            Self::Wait { ms } => format!("wait {ms}ms"),
+        }
+    }
+
+    /// Perform the step on `page`
+    pub async fn run(&self, page: &Page) -> Result<()> {
+        let step_timeout = Duration::from_millis(DEFAULT_STEP_TIMEOUT_MS);
+        match self {
+            Self::Click {
+                selector,
+                wait_for_navigation,
+            } => {
+                let element = page.find_element(selector.as_str()).await?;
+                element.scroll_into_view().await?;
+                element.click().await?;
+                if *wait_for_navigation {
+                    wait_navigation(page, step_timeout).await;
+                }
+            }
+            Self::Type { selector, text, clear } => {
+                let element = page.find_element(selector.as_str()).await?;
+                if *clear {
+                    element
+                        .call_js_fn("function() { this.value = ''; }", false)
+                        .await?;
+                }
+                element.click().await?;
+                element.type_str(text).await?;
+            }
+            Self::Press { key, selector } => {
+                let selector = selector.as_deref().unwrap_or(":focus");
+                let element = match page.find_element(selector).await {
+                    Ok(element) => element,
+                    Err(_) => page.find_element("body").await?,
+                };
+                element.press_key(key).await?;
+            }
+            Self::Submit { selector } => {
+                let element = page.find_element(selector.as_str()).await?;
+                element
+                    .call_js_fn(
+                        "function() { const form = this.form || this.closest('form') || this; \
+                         if (form.requestSubmit) { form.requestSubmit(); } else { form.submit(); } }",
+                        false,
+                    )
+                    .await?;
+                wait_navigation(page, step_timeout).await;
+            }
+            Self::WaitFor { selector, timeout_ms } => {
+                let timeout = timeout_ms.map_or(step_timeout, Duration::from_millis);
+                let deadline = std::time::Instant::now() + timeout;
+                while page.find_element(selector.as_str()).await.is_err() {
+                    if std::time::Instant::now() >= deadline {
+                        bail!("'{selector}' did not appear within {}ms", timeout.as_millis());
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            }
+            Self::Wait { ms } => {
+                tokio::time::sleep(Duration::from_millis((*ms).min(MAX_WAIT_MS))).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wait for a navigation; forms and links handled in-page never navigate,
+/// so running out of time is not an error
+async fn wait_navigation(page: &Page, timeout: Duration) {
+    if tokio::time::timeout(timeout, page.wait_for_navigation()).await.is_err() {
+        log::debug!("No navigation within {}ms, continuing", timeout.as_millis());
+    }
+}
+
+/// Run `steps` in order, naming the failing step on error
+pub async fn run_steps(page: &Page, steps: &[InteractionStep]) -> Result<()> {
+    for (index, step) in steps.iter().enumerate() {
+        step.run(page)
+            .await
+            .with_context(|| format!("Step {} ({}) failed", index + 1, step.describe()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_deserialization() {
+        let steps: Vec<InteractionStep> = serde_json::from_str(
+            r##"[
+                {"action": "click", "selector": "#accept-cookies"},
+                {"action": "type", "selector": "input[name=q]", "text": "tokio", "clear": true},
+                {"action": "press", "key": "Enter"},
+                {"action": "wait_for", "selector": ".results"},
+                {"action": "wait", "ms": 500}
+            ]"##,
+        )
+        .unwrap();
+
+        assert_eq!(steps.len(), 5);
+        assert!(matches!(
+            &steps[0],
+            InteractionStep::Click { wait_for_navigation: false, .. }
+        ));
+        assert!(matches!(&steps[2], InteractionStep::Press { selector: None, .. }));
+        assert_eq!(steps[1].describe(), "type 5 chars into 'input[name=q]'");
+        assert!(serde_json::from_str::<InteractionStep>(r#"{"action": "hover", "selector": "a"}"#).is_err());
+    }
+}
@@ -297,3 +297,126 @@ pub const HEADINGS_SCRIPT: &str = r#"
         return headings;
     })()
 "#;
+
+/// JavaScript function to dismiss cookie consent banners and overlays
+///
+/// Called with one boolean argument. It always removes consent containers,
+/// paywall modals and backdrops that are on screen and re-enables scrolling
+/// locked behind them. With `true` it first clicks the "reject" button of
+/// the consent dialog, so the site records the refusal; it never clicks
+/// "accept" and never clicks links.
+pub const DISMISS_OVERLAYS_SCRIPT: &str = r#"
+    async (clickReject) => {
+        const clicked = [];
+        let removed = 0;
+
+        const isVisible = el => {
+            const rect = el.getBoundingClientRect();
+            const style = getComputedStyle(el);
+            return rect.width > 0 && rect.height > 0
+                && style.visibility !== 'hidden' && style.display !== 'none';
+        };
+        // Clicking a link navigates away from the page being extracted
+        const isClickable = el => isVisible(el) && !el.closest('a');
+
+        if (clickReject) {
+            // Reject buttons of known consent managers, one click per manager
+            const rejectButtons = [
+                // OneTrust
+                ['#onetrust-reject-all-handler', '.ot-pc-refuse-all-handler'],
+                // Cookiebot
+                ['#CybotCookiebotDialogBodyButtonDecline', '#CybotCookiebotDialogBodyLevelButtonLevelOptinDeclineAll'],
+                // Didomi
+                ['#didomi-notice-disagree-button'],
+                // Quantcast Choice
+                ['.qc-cmp2-summary-buttons button[mode="secondary"]'],
+                // TrustArc
+                ['#truste-consent-required'],
+                // Google Funding Choices
+                ['.fc-cta-do-not-consent'],
+                // Usercentrics
+                ['[data-testid="uc-deny-all-button"]'],
+                // Cookie Consent (Osano)
+                ['.cc-window .cc-deny'],
+            ];
+            for (const group of rejectButtons) {
+                const selector = group.find(selector => {
+                    const button = document.querySelector(selector);
+                    return button && isClickable(button);
+                });
+                if (selector) {
+                    document.querySelector(selector).click();
+                    clicked.push(selector);
+                }
+            }
+
+            // Unknown consent dialogs: pick a reject button by its label
+            const rejectLabel = /^(reject|decline|deny|refuse)( all)?( cookies)?$|only (necessary|essential)|necessary cookies only/i;
+            if (clicked.length === 0) {
+                const dialogs = document.querySelectorAll(
+                    '[id*="cookie" i], [class*="cookie" i], [id*="consent" i], [class*="consent" i], ' +
+                    '[role="dialog"], [aria-modal="true"]'
+                );
+                for (const dialog of dialogs) {
+                    if (!isVisible(dialog) || !/cookie|consent|gdpr/i.test(dialog.textContent || '')) continue;
+                    const label = b => (b.innerText || b.value || '').trim();
+                    const button = Array.from(dialog.querySelectorAll('button, [role="button"], input[type="button"], input[type="submit"]'))
+                        .find(b => isClickable(b) && rejectLabel.test(label(b)));
+                    if (button) {
+                        clicked.push(label(button));
+                        button.click();
+                        break;
+                    }
+                }
+            }
+        }
+
+        if (clicked.length > 0) {
+            await new Promise(resolve => setTimeout(resolve, 300));
+        }
+
+        // Whatever is left: consent containers, paywall modals and backdrops
+        const knownOverlays = [
+            '#onetrust-consent-sdk', '#CybotCookiebotDialog', '#CybotCookiebotDialogBodyUnderlay',
+            '#didomi-host', '.qc-cmp2-container', '#qc-cmp2-container', '#truste-consent-track',
+            '.truste_box_overlay', '.truste_overlay', '.fc-consent-root', '#usercentrics-root',
+            '[id^="sp_message_container"]', '.cc-window', '#cmpbox', '#cmpbox2',
+            '#cookie-law-info-bar', '.tp-modal', '.tp-backdrop',
+        ];
+        for (const el of document.querySelectorAll(knownOverlays.join(', '))) {
+            el.remove();
+            removed++;
+        }
+
+        const overlayText = /cookie|consent|gdpr|paywall|already a subscriber|subscribe to (continue|read)|create a free account|disable your ad ?blocker/i;
+        const viewportArea = window.innerWidth * window.innerHeight;
+        const candidates = Array.from(document.querySelectorAll('body div, body section, body aside, body dialog, body iframe'))
+            .slice(0, 5000);
+        for (const el of candidates) {
+            if (!el.isConnected) continue;
+            const style = getComputedStyle(el);
+            if (style.position !== 'fixed' && style.position !== 'sticky') continue;
+            const zIndex = parseInt(style.zIndex, 10);
+            if (!(zIndex >= 100) || !isVisible(el)) continue;
+            const rect = el.getBoundingClientRect();
+            const text = (el.innerText || '').trim();
+            const isConsentOrPaywall = text.length < 3000 && overlayText.test(text);
+            const isBackdrop = rect.width * rect.height >= viewportArea * 0.5 && text.length < 20;
+            if (isConsentOrPaywall || isBackdrop) {
+                el.remove();
+                removed++;
+            }
+        }
+
+        // Modals lock scrolling on <html> or <body>
+        for (const el of [document.documentElement, document.body]) {
+            if (!el) continue;
+            el.classList.remove('modal-open', 'no-scroll', 'noscroll', 'overflow-hidden', 'sp-message-open', 'tp-modal-open');
+            if (getComputedStyle(el).overflow === 'hidden') {
+                el.style.setProperty('overflow', 'auto', 'important');
+            }
+        }
+
+        return { clicked: clicked, removed: removed };
+    }
+"#;
@@ -4,11 +4,20 @@
 //! including metadata, timing information, security details, and links.
 
 // Sub-modules
+pub mod content_stream;
 pub mod extractors;
+pub mod interaction;
 pub mod js_scripts;
 pub mod page_data;
 pub mod schema;
+pub mod structured;
 
 // Re-exports for public API
-pub use extractors::{capture_screenshot, scroll_to_bottom, wait_for_page_load};
+pub use content_stream::page_content;
+pub use extractors::{
+    PageReadyConditions, capture_screenshot, scroll_to_bottom, wait_for_page_load,
+    wait_for_ready_conditions,
+};
+pub use interaction::{InteractionStep, run_steps};
 pub use page_data::extract_page_data;
+pub use structured::{CompiledExtraction, ExtractionSpec, FieldSpec, PageSchema, extract_structured};
@@ -29,6 +29,29 @@ pub struct ExtractPageDataConfig {
     pub http_error_cache: Arc<DashMap<String, CachedResponse>>,
     /// Shared domain download queues (enables cross-page worker sharing)
     pub domain_queues: Arc<DashMap<String, Arc<DomainDownloadQueue>>>,
+    /// Per-crawl conditions that must hold before the page is considered ready
+    pub ready_conditions: super::extractors::PageReadyConditions,
+    /// Maximum seconds to wait for `ready_conditions`
+    pub ready_timeout_secs: u64,
+    /// Serialized HTML beyond this many bytes is dropped
+    pub max_html_bytes: usize,
+    /// Remove cookie consent banners and paywall overlays before extraction
+    pub dismiss_overlays: bool,
+    /// Click "reject" on consent dialogs before removing them
+    pub reject_consent: bool,
+}
+
+/// Dismiss consent banners and overlays; failures only cost banner text in the output
+async fn dismiss_overlays(page: &Page, url: &str, reject_consent: bool) {
+    match super::extractors::dismiss_overlays(page, reject_consent).await {
+        Ok(dismissal) if !dismissal.is_empty() => log::debug!(
+            "Dismissed overlays on {url}: clicked {:?}, removed {} elements",
+            dismissal.clicked,
+            dismissal.removed
+        ),
+        Ok(_) => {}
+        Err(e) => log::debug!("Failed to dismiss overlays on {url}: {e}"),
+    }
 }
 
 /// Extract event handler attribute names from element attributes
@@ -223,6 +246,20 @@ pub async fn extract_page_data(
 ) -> Result<super::schema::PageData> {
     log::debug!("Starting to extract page data for URL: {url}");
 
+    // Wait for caller-specified readiness signals (selector, function, network idle)
+    // before any extraction, so SPAs are not snapshotted before they render.
+    super::extractors::wait_for_ready_conditions(
+        &page,
+        &config.ready_conditions,
+        config.ready_timeout_secs,
+    )
+    .await
+    .context("Failed to wait for page readiness conditions")?;
+
+    if config.dismiss_overlays {
+        dismiss_overlays(&page, &url, config.reject_consent).await;
+    }
+
     // Launch all extractions in parallel with tokio::try_join!
     let (metadata, resources, timing, security, title, interactive_elements_vec, links, headings) = tokio::try_join!(
         extract_metadata(page.clone()),
@@ -272,11 +309,15 @@ pub async fn extract_page_data(
     log::debug!("Page fully loaded, extracting complete HTML content for: {url}");
     // ========================================================================
 
-    // Get HTML content (now complete!)
-    let content = page
-        .content()
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to get page content: {e}"))?;
+    // Consent managers often inject their banner late, after the first pass
+    if config.dismiss_overlays {
+        dismiss_overlays(&page, &url, config.reject_consent).await;
+    }
+
+    // Get HTML content (now complete!), streamed so large documents are not
+    // transferred as one CDP message, and capped so a runaway page cannot
+    // exhaust memory
+    let content = super::content_stream::page_content(&page, config.max_html_bytes).await?;
 
     // NOTE: Link rewriting is now handled AFTER page save via the event-driven
     // LinkRewriter system. See link_rewriter module for details.
@@ -183,6 +183,10 @@ pub struct PageMetadata {
     /// Extracted from DOM in document order with position metadata
     #[serde(default)]
     pub headings: Vec<HeadingElement>,
+
+    /// Main-content extractor that produced the saved markdown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<crate::content_saver::markdown_converter::ExtractionReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
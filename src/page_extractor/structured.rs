@@ -0,0 +1,271 @@
+//! Selector-driven structured extraction
+//!
+//! Maps field names to CSS selectors (and an attribute to read) and turns a
+//! rendered page into JSON records. Extraction runs as a single script in the
+//! page so one CDP round trip covers every field of every item; field regexes
+//! are applied to the returned values afterwards. A spec is compiled once
+//! into a [`CompiledExtraction`] (script built, regexes compiled) and reused
+//! for every page it applies to.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result, bail};
+use chromiumoxide::Page;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How to read one field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldSpec {
+    /// CSS selector, relative to the item element when `item_selector` is set
+    /// (omit to read the item element itself)
+    #[serde(default)]
+    pub selector: Option<String>,
+
+    /// What to read: "text" (default), "html", "outer_html", or an attribute
+    /// name. `href` and `src` are resolved to absolute URLs.
+    #[serde(default)]
+    pub attribute: Option<String>,
+
+    /// Return every match as an array instead of the first match
+    #[serde(default)]
+    pub multiple: bool,
+
+    /// Regular expression applied to the value read; the first capture group
+    /// (or the whole match without groups) replaces it, `null` when it does
+    /// not match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+}
+
+/// Fields to extract, optionally repeated once per item element
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExtractionSpec {
+    /// Field name to selector mapping
+    pub fields: BTreeMap<String, FieldSpec>,
+
+    /// Selector of repeating item elements (e.g. "article.post"); each match
+    /// becomes one record. Without it the whole page yields one record.
+    #[serde(default)]
+    pub item_selector: Option<String>,
+}
+
+impl ExtractionSpec {
+    /// Reject specs that cannot produce anything
+    pub fn validate(&self) -> Result<()> {
+        self.compile().map(drop)
+    }
+
+    /// Validate the spec and prepare it for extraction from any number of pages
+    pub fn compile(&self) -> Result<CompiledExtraction> {
+        if self.fields.is_empty() {
+            bail!("At least one field is required");
+        }
+        if self.item_selector.is_none()
+            && let Some((name, _)) = self.fields.iter().find(|(_, f)| f.selector.is_none())
+        {
+            bail!("Field '{name}' needs a selector when item_selector is not set");
+        }
+        let regexes = self
+            .fields
+            .iter()
+            .filter_map(|(name, field)| Some((name, field.regex.as_ref()?)))
+            .map(|(name, pattern)| {
+                let regex = Regex::new(pattern).with_context(|| format!("Field '{name}' has an invalid regex"))?;
+                Ok((name.clone(), regex))
+            })
+            .collect::<Result<_>>()?;
+        Ok(CompiledExtraction {
+            spec: self.clone(),
+            script: self.script()?,
+            regexes,
+        })
+    }
+
+    /// In-page script returning an array of records
+    fn script(&self) -> Result<String> {
+        let spec = serde_json::to_string(self).context("Failed to serialize extraction spec")?;
+        Ok(format!("(() => {{ const spec = {spec}; {EXTRACT_BODY} }})()"))
+    }
+}
+
+/// An [`ExtractionSpec`] with its script built and field regexes compiled
+#[derive(Debug, Clone)]
+pub struct CompiledExtraction {
+    spec: ExtractionSpec,
+    script: String,
+    regexes: Vec<(String, Regex)>,
+}
+
+impl CompiledExtraction {
+    /// The spec this was compiled from
+    #[must_use]
+    pub fn spec(&self) -> &ExtractionSpec {
+        &self.spec
+    }
+
+    /// Apply field regexes to records returned by the extraction script
+    fn apply_regexes(&self, records: &mut [Value]) {
+        for (name, regex) in &self.regexes {
+            for value in records.iter_mut().filter_map(|record| record.get_mut(name)) {
+                match value {
+                    Value::Array(items) => items.iter_mut().for_each(|item| capture(regex, item)),
+                    item => capture(regex, item),
+                }
+            }
+        }
+    }
+}
+
+/// Replace a string value with its regex capture
+fn capture(regex: &Regex, value: &mut Value) {
+    let Value::String(text) = value else { return };
+    *value = regex
+        .captures(text)
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(0)))
+        .map_or(Value::Null, |m| Value::String(m.as_str().to_string()));
+}
+
+/// Extraction spec attached to the crawled pages whose URL matches a pattern
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PageSchema {
+    /// URL glob, or `re:<regex>`, as in `excluded_patterns`
+    pub url_pattern: String,
+
+    #[serde(flatten)]
+    pub spec: ExtractionSpec,
+}
+
+impl PageSchema {
+    /// Reject schemas with an invalid pattern or spec
+    pub fn validate(&self) -> Result<()> {
+        crate::imurl::UrlMatcher::parse(&self.url_pattern)?;
+        self.spec.validate()
+    }
+}
+
+/// Script body; `spec` holds the serialized `ExtractionSpec`
+const EXTRACT_BODY: &str = r#"
+    const read = (el, attribute) => {
+        const attr = attribute || 'text';
+        if (attr === 'text') return (el.innerText ?? el.textContent ?? '').trim();
+        if (attr === 'html') return el.innerHTML;
+        if (attr === 'outer_html') return el.outerHTML;
+        if ((attr === 'href' || attr === 'src') && typeof el[attr] === 'string' && el[attr]) return el[attr];
+        return el.getAttribute(attr);
+    };
+    const field = (root, f) => {
+        if (!f.selector) return f.multiple ? [read(root, f.attribute)] : read(root, f.attribute);
+        if (f.multiple) return Array.from(root.querySelectorAll(f.selector)).map(el => read(el, f.attribute));
+        const el = root.querySelector(f.selector);
+        return el ? read(el, f.attribute) : null;
+    };
+    const record = (root) => {
+        const out = {};
+        for (const [name, f] of Object.entries(spec.fields)) out[name] = field(root, f);
+        return out;
+    };
+    const roots = spec.item_selector
+        ? Array.from(document.querySelectorAll(spec.item_selector))
+        : [document];
+    return roots.map(record);
+"#;
+
+/// Extract records from the current document of `page`
+pub async fn extract_structured(page: &Page, extraction: &CompiledExtraction) -> Result<Vec<Value>> {
+    let result = page
+        .evaluate(extraction.script.as_str())
+        .await
+        .context("Structured extraction script failed")?;
+    let mut records = result
+        .into_value::<Vec<Value>>()
+        .context("Structured extraction returned an unexpected value")?;
+    extraction.apply_regexes(&mut records);
+    Ok(records)
+}
+
+/// Absolute URL of the first element matching `selector`, if it has one
+///
+/// Reads `href` (links) or `data-href`/`value` fallbacks so "next page"
+/// buttons that carry their target in an attribute also work.
+pub async fn next_page_url(page: &Page, selector: &str) -> Result<Option<String>> {
+    let selector = serde_json::to_string(selector)?;
+    let script = format!(
+        r"(() => {{
+            const el = document.querySelector({selector});
+            if (!el) return null;
+            const target = el.href || el.getAttribute('data-href') || el.getAttribute('href');
+            if (!target) return null;
+            try {{ return new URL(target, document.baseURI).href; }} catch (e) {{ return null; }}
+        }})()"
+    );
+    let result = page.evaluate(script).await.context("Next page lookup failed")?;
+    Ok(result.into_value::<Option<String>>().unwrap_or(None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(selector: Option<&str>) -> FieldSpec {
+        FieldSpec {
+            selector: selector.map(str::to_string),
+            attribute: None,
+            multiple: false,
+            regex: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_and_script() {
+        let mut spec = ExtractionSpec {
+            fields: BTreeMap::from([("title".to_string(), field(Some("h1")))]),
+            item_selector: None,
+        };
+        assert!(spec.validate().is_ok());
+        assert!(spec.script().unwrap().contains(r#""title":{"selector":"h1""#));
+
+        // Reading the item element itself only makes sense with items
+        spec.fields.insert("self".to_string(), field(None));
+        assert!(spec.validate().is_err());
+        spec.item_selector = Some("li".to_string());
+        assert!(spec.validate().is_ok());
+
+        spec.fields.clear();
+        assert!(spec.validate().is_err());
+    }
+
+    #[test]
+    fn test_apply_regexes() {
+        let mut price = field(Some(".price"));
+        price.regex = Some(r"([0-9]+\.[0-9]{2})".to_string());
+        let mut sku = field(Some(".sku"));
+        sku.regex = Some(r"[A-Z]{3}-\d+".to_string());
+        sku.multiple = true;
+        let spec = ExtractionSpec {
+            fields: BTreeMap::from([
+                ("price".to_string(), price),
+                ("sku".to_string(), sku),
+                ("title".to_string(), field(Some("h1"))),
+            ]),
+            item_selector: None,
+        };
+
+        let mut records = vec![serde_json::json!({
+            "price": "Now only $12.50!",
+            "sku": ["ref ABC-12", "none"],
+            "title": "Widget 9.99",
+        })];
+        spec.compile().unwrap().apply_regexes(&mut records);
+        assert_eq!(
+            records[0],
+            serde_json::json!({"price": "12.50", "sku": ["ABC-12", null], "title": "Widget 9.99"})
+        );
+
+        let mut bad = spec.clone();
+        bad.fields.get_mut("title").unwrap().regex = Some("(".to_string());
+        assert!(bad.validate().is_err());
+    }
+}
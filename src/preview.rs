@@ -0,0 +1,379 @@
+//! Local HTTP preview of a finished mirror
+//!
+//! Serves a crawl output directory read-only so the archived site can be
+//! browsed in a normal browser to check crawl fidelity. URL paths map onto
+//! the mirror layout (`/<host>/<path>/`) with the saver's name encoding
+//! ([`mirror_relative_path`]), so the relative links the link rewriter wrote
+//! resolve between saved pages. Directory requests get their `index.html`,
+//! falling back to `index.md`; gzip-compressed files (`*.gz`) are
+//! decompressed on the fly. `/` lists the mirrored hosts.
+//!
+//! The routes are an axum [`Router`] ([`router`]), like the server's other
+//! HTTP endpoints. Only `GET` and `HEAD` are answered and nothing outside the
+//! output directory is reachable. Hidden names (`.citescrape/`, `.search_index/`,
+//! the link index) are never served.
+
+use std::fmt::Write as _;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::State;
+use axum::http::{HeaderValue, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::imurl::canonical_path;
+use crate::utils::url_utils::{mirror_relative_path, safe_segment};
+
+/// Files tried, in order, for a directory request
+const INDEX_FILES: [&str; 2] = ["index.html", "index.md"];
+
+/// What a request path maps to in the mirror
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// A file to send; `gzip` when it must be decompressed first
+    File { path: PathBuf, gzip: bool },
+    /// A directory requested without its trailing slash
+    Redirect(String),
+    /// The host listing at `/`
+    Listing,
+    NotFound,
+}
+
+/// Map a request path onto the mirror under `root`
+///
+/// The first segment is the host and the rest its URL path, mapped the way the
+/// savers named directories. A path that is not a saved directory names a file
+/// in its parent's directory.
+fn resolve(root: &Path, request_path: &str) -> Target {
+    let trimmed = request_path.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return Target::Listing;
+    }
+    // No `.` or `..` segments and no hidden names, however they are spelled
+    let hidden = |segment: &str| {
+        urlencoding::decode(segment)
+            .ok()
+            .is_none_or(|decoded| decoded.starts_with('.') || decoded.contains(['/', '\\']))
+    };
+    if trimmed.split('/').any(|segment| !segment.is_empty() && hidden(segment)) {
+        return Target::NotFound;
+    }
+
+    let (host, path) = trimmed.split_once('/').unwrap_or((trimmed, ""));
+    let Some(dir) = mirror_dir(host, path) else {
+        return Target::NotFound;
+    };
+    let dir = root.join(dir);
+    if dir.is_dir() {
+        if !request_path.ends_with('/') {
+            // Built from the normalized path: `//host` would leave the mirror
+            return Target::Redirect(format!("/{trimmed}/"));
+        }
+        return INDEX_FILES
+            .iter()
+            .find_map(|name| existing_file(&dir.join(name)))
+            .unwrap_or(Target::NotFound);
+    }
+
+    let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+    if name.is_empty() {
+        return Target::NotFound;
+    }
+    let Some(parent) = mirror_dir(host, parent) else {
+        return Target::NotFound;
+    };
+    existing_file(&root.join(parent).join(safe_segment(&canonical_path(name)))).unwrap_or(Target::NotFound)
+}
+
+/// Mirror directory of `path` on `host`, spelled as [`mirror_relative_path`] spells it
+fn mirror_dir(host: &str, path: &str) -> Option<PathBuf> {
+    let url = Url::parse(&format!("http://{host}/{path}")).ok()?;
+    let dir = mirror_relative_path(&url).ok()?;
+    // The URL parser may still fold escapes into names; never leave the mirror
+    dir.components()
+        .all(|component| matches!(component, Component::Normal(name) if !name.to_string_lossy().starts_with('.')))
+        .then_some(dir)
+}
+
+/// `path` itself, or its gzip-compressed sibling `<path>.gz`
+fn existing_file(path: &Path) -> Option<Target> {
+    if path.is_file() {
+        // A requested `page.md.gz` is served as the markdown it holds
+        let gzip = path.extension().is_some_and(|ext| ext == "gz");
+        return Some(Target::File {
+            path: path.to_path_buf(),
+            gzip,
+        });
+    }
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    let gz = PathBuf::from(gz);
+    gz.is_file().then_some(Target::File { path: gz, gzip: true })
+}
+
+/// Content type of a mirrored file, judged by its name without `.gz`
+fn content_type(path: &Path) -> &'static str {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    match name.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        // Markdown is shown as text; browsers would offer text/markdown as a download
+        Some("md" | "txt") => "text/plain; charset=utf-8",
+        Some("json") => "application/json",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// HTML page linking to each mirrored host under `root`
+fn host_listing(root: &Path) -> Result<String> {
+    let mut hosts: Vec<String> = std::fs::read_dir(root)
+        .with_context(|| format!("Failed to read {}", root.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        // Search index, link index and other bookkeeping
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    hosts.sort();
+
+    let mut page = String::from("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Mirror</title></head><body>\n<h1>Mirrored sites</h1>\n<ul>\n");
+    for host in &hosts {
+        let host = html_escape::encode_text(host);
+        let _ = writeln!(page, "<li><a href=\"/{host}/\">{host}</a></li>");
+    }
+    if hosts.is_empty() {
+        page.push_str("<li>Nothing mirrored yet</li>\n");
+    }
+    page.push_str("</ul>\n</body></html>\n");
+    Ok(page)
+}
+
+/// `GET` (and `HEAD`) routes serving the mirror under `root`
+pub fn router(root: PathBuf) -> Router {
+    Router::new()
+        .route("/", get(page))
+        .route("/{*path}", get(page))
+        .with_state(Arc::new(root))
+}
+
+/// Serve the mirror under `root` on `listener` until `shutdown` is cancelled
+pub async fn serve(listener: TcpListener, root: PathBuf, shutdown: CancellationToken) {
+    let served = axum::serve(listener, router(root))
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await;
+    if let Err(e) = served {
+        log::warn!("Preview server failed: {e}");
+    }
+}
+
+async fn page(State(root): State<Arc<PathBuf>>, uri: Uri) -> Response {
+    // The raw path: `resolve` decodes segments itself to refuse hidden names
+    let path = uri.path().to_string();
+    let request_path = path.clone();
+    match tokio::task::spawn_blocking(move || reply_for(&root, &request_path)).await {
+        Ok(Ok(reply)) => reply.into_response(),
+        Ok(Err(e)) => {
+            log::warn!("Failed to serve {path}: {e:#}");
+            Reply::text(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read the mirror").into_response()
+        }
+        Err(e) => {
+            log::warn!("Preview file task for {path} panicked: {e}");
+            Reply::text(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read the mirror").into_response()
+        }
+    }
+}
+
+/// Status, content type and body of a response
+struct Reply {
+    status: StatusCode,
+    content_type: &'static str,
+    body: Vec<u8>,
+    location: Option<String>,
+}
+
+impl Reply {
+    fn text(status: StatusCode, message: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{message}\n").into_bytes(),
+            location: None,
+        }
+    }
+}
+
+impl IntoResponse for Reply {
+    fn into_response(self) -> Response {
+        let mut response = (
+            self.status,
+            [(header::CONTENT_TYPE, self.content_type), (header::CACHE_CONTROL, "no-store")],
+            self.body,
+        )
+            .into_response();
+        if let Some(location) = self.location.and_then(|location| HeaderValue::from_str(&location).ok()) {
+            response.headers_mut().insert(header::LOCATION, location);
+        }
+        response
+    }
+}
+
+/// Build the reply for `request_path` (blocking file I/O)
+fn reply_for(root: &Path, request_path: &str) -> Result<Reply> {
+    Ok(match resolve(root, request_path) {
+        Target::File { path, gzip } => {
+            let body = if gzip {
+                let mut body = Vec::new();
+                flate2::read::GzDecoder::new(std::fs::File::open(&path)?).read_to_end(&mut body)?;
+                body
+            } else {
+                std::fs::read(&path)?
+            };
+            Reply {
+                status: StatusCode::OK,
+                content_type: content_type(&path),
+                body,
+                location: None,
+            }
+        }
+        Target::Redirect(location) => Reply {
+            location: Some(location),
+            ..Reply::text(StatusCode::MOVED_PERMANENTLY, "Moved")
+        },
+        Target::Listing => Reply {
+            status: StatusCode::OK,
+            content_type: "text/html; charset=utf-8",
+            body: host_listing(root)?.into_bytes(),
+            location: None,
+        },
+        Target::NotFound => Reply::text(StatusCode::NOT_FOUND, "Not in the mirror"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn mirror() -> Result<TempDir> {
+        let dir = TempDir::new()?;
+        let guide = dir.path().join("example.com/guide");
+        std::fs::create_dir_all(&guide)?;
+        std::fs::create_dir_all(dir.path().join(".search_index"))?;
+        std::fs::write(dir.path().join("example.com/index.html"), "<a href=\"guide/index.html\">guide</a>")?;
+        let mut gz = flate2::write::GzEncoder::new(
+            std::fs::File::create(guide.join("index.md.gz"))?,
+            flate2::Compression::default(),
+        );
+        gz.write_all(b"# Guide")?;
+        gz.finish()?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn test_resolve_mirror_paths() -> Result<()> {
+        let dir = mirror()?;
+        let root = dir.path();
+
+        assert_eq!(resolve(root, "/"), Target::Listing);
+        assert_eq!(resolve(root, "/example.com"), Target::Redirect("/example.com/".to_string()));
+        assert_eq!(resolve(root, "//example.com"), Target::Redirect("/example.com/".to_string()));
+        assert_eq!(
+            resolve(root, "/example.com/"),
+            Target::File {
+                path: root.join("example.com/index.html"),
+                gzip: false
+            }
+        );
+        // No index.html: the compressed markdown stands in
+        assert_eq!(
+            resolve(root, "/example.com/guide/"),
+            Target::File {
+                path: root.join("example.com/guide/index.md.gz"),
+                gzip: true
+            }
+        );
+        assert_eq!(
+            resolve(root, "/example.com/guide/index.md"),
+            Target::File {
+                path: root.join("example.com/guide/index.md.gz"),
+                gzip: true
+            }
+        );
+        assert_eq!(resolve(root, "/example.com/missing/"), Target::NotFound);
+        assert_eq!(resolve(root, "/example.com/../../etc/passwd"), Target::NotFound);
+        assert_eq!(resolve(root, "/%2e%2e/etc/passwd"), Target::NotFound);
+        assert_eq!(resolve(root, "/.search_index/"), Target::NotFound);
+        assert_eq!(resolve(root, "/example.com/%2Ecitescrape/events.jsonl"), Target::NotFound);
+
+        // Names the saver escaped are found from the escaped links the rewriter wrote
+        let escaped = root.join("example.com/a%3Ab");
+        std::fs::create_dir_all(&escaped)?;
+        std::fs::write(escaped.join("index.html"), "<p>colon</p>")?;
+        assert_eq!(
+            resolve(root, "/example.com/a%3Ab/"),
+            Target::File {
+                path: escaped.join("index.html"),
+                gzip: false
+            }
+        );
+
+        let listing = host_listing(root)?;
+        assert!(listing.contains("<a href=\"/example.com/\">example.com</a>"));
+        assert!(!listing.contains(".search_index"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serve_mirror() -> Result<()> {
+        let dir = mirror()?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let shutdown = CancellationToken::new();
+        let server = tokio::spawn(serve(listener, dir.path().to_path_buf(), shutdown.clone()));
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        let url = |path: &str| format!("http://{addr}{path}");
+
+        let page = client.get(url("/example.com/guide/")).send().await?;
+        assert_eq!(page.status(), 200);
+        assert_eq!(page.headers()[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+        assert_eq!(page.headers()[header::CACHE_CONTROL], "no-store");
+        assert_eq!(page.text().await?, "# Guide");
+
+        let head = client.head(url("/example.com/guide/")).send().await?;
+        assert_eq!(head.status(), 200);
+        assert_eq!(head.text().await?, "");
+
+        let redirect = client.get(url("/example.com?tab=1")).send().await?;
+        assert_eq!(redirect.status(), 301);
+        assert_eq!(redirect.headers()[header::LOCATION], "/example.com/");
+        let redirect = client.get(url("//example.com")).send().await?;
+        assert_eq!(redirect.headers()[header::LOCATION], "/example.com/");
+
+        assert_eq!(client.get(url("/nowhere.org/")).send().await?.status(), 404);
+        assert_eq!(client.post(url("/example.com/")).send().await?.status(), 405);
+
+        shutdown.cancel();
+        server.await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! robots.txt parsing and evaluation
+//!
+//! Implements the matching rules of RFC 9309: the group whose user-agent token
+//! best matches the crawler is used (falling back to `*`), `*` and `$` are
+//! supported in paths, and the longest matching rule wins with `Allow`
+//! winning ties. `Crawl-delay` and `Sitemap` lines are collected as well.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::utils::http_fetch;
+
+/// One `Allow` / `Disallow` line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RobotsRule {
+    pub allow: bool,
+    pub pattern: String,
+}
+
+impl std::fmt::Display for RobotsRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let directive = if self.allow { "Allow" } else { "Disallow" };
+        write!(f, "{directive}: {}", self.pattern)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<f64>,
+}
+
+/// Outcome of checking one path
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotsVerdict {
+    /// Whether the path may be crawled
+    pub allowed: bool,
+    /// Rule that decided the outcome (`None` when no rule matched)
+    pub rule: Option<RobotsRule>,
+}
+
+/// A parsed robots.txt
+#[derive(Debug, Clone, Default)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+    /// URLs from `Sitemap:` lines
+    pub sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    /// Parse robots.txt content; unknown lines are ignored
+    pub fn parse(text: &str) -> Self {
+        let mut robots = Self::default();
+        let mut in_agent_lines = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_agent_lines {
+                        robots.groups.push(Group::default());
+                        in_agent_lines = true;
+                    }
+                    if let Some(group) = robots.groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                "sitemap" => robots.sitemaps.push(value.to_string()),
+                directive @ ("allow" | "disallow" | "crawl-delay") => {
+                    in_agent_lines = false;
+                    let Some(group) = robots.groups.last_mut() else {
+                        continue;
+                    };
+                    match directive {
+                        "crawl-delay" => group.crawl_delay = value.parse().ok(),
+                        // An empty Disallow allows everything
+                        _ if value.is_empty() => {}
+                        _ => group.rules.push(RobotsRule {
+                            allow: directive == "allow",
+                            pattern: value.to_string(),
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+        robots
+    }
+
+    /// robots.txt that disallows everything (used when the server errors)
+    pub fn disallow_all() -> Self {
+        Self {
+            groups: vec![Group {
+                agents: vec!["*".to_string()],
+                rules: vec![RobotsRule {
+                    allow: false,
+                    pattern: "/".to_string(),
+                }],
+                crawl_delay: None,
+            }],
+            sitemaps: Vec::new(),
+        }
+    }
+
+    /// Groups that apply to `user_agent`: those naming the longest token
+    /// contained in it, otherwise the `*` groups
+    fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        let user_agent = user_agent.to_ascii_lowercase();
+        let best = self
+            .groups
+            .iter()
+            .flat_map(|g| &g.agents)
+            .filter(|token| token.as_str() != "*" && !token.is_empty() && user_agent.contains(token.as_str()))
+            .max_by_key(|token| token.len())
+            .map_or("*", String::as_str);
+        self.groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|token| token == best))
+            .collect()
+    }
+
+    /// The user-agent token whose group applies to `user_agent`, if any
+    pub fn matched_agent(&self, user_agent: &str) -> Option<String> {
+        let groups = self.groups_for(user_agent);
+        let user_agent = user_agent.to_ascii_lowercase();
+        groups.first().and_then(|g| {
+            g.agents
+                .iter()
+                .find(|token| token.as_str() == "*" || user_agent.contains(token.as_str()))
+                .cloned()
+        })
+    }
+
+    /// `Crawl-delay` in seconds for `user_agent`
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<f64> {
+        self.groups_for(user_agent).iter().find_map(|g| g.crawl_delay)
+    }
+
+    /// Whether `user_agent` may fetch `url`
+    pub fn check(&self, user_agent: &str, url: &Url) -> RobotsVerdict {
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        if path == "/robots.txt" {
+            return RobotsVerdict {
+                allowed: true,
+                rule: None,
+            };
+        }
+
+        let rule = self
+            .groups_for(user_agent)
+            .into_iter()
+            .flat_map(|g| &g.rules)
+            .filter(|rule| pattern_matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .cloned();
+        RobotsVerdict {
+            allowed: rule.as_ref().is_none_or(|r| r.allow),
+            rule,
+        }
+    }
+}
+
+/// Match a robots.txt path pattern (`*` wildcard, trailing `$` anchor)
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or("")) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+/// Result of fetching robots.txt for a site
+#[derive(Debug, Clone)]
+pub struct FetchedRobots {
+    /// Location of robots.txt
+    pub url: Url,
+    /// HTTP status, `None` if the request failed
+    pub status: Option<u16>,
+    /// Effective rules
+    pub robots: RobotsTxt,
+}
+
+/// Fetch robots.txt for `site`'s origin
+///
+/// Per RFC 9309 a 4xx response (or unreachable file) means no restrictions
+/// and a 5xx response means everything is disallowed.
+pub async fn fetch_robots(client: &reqwest::Client, site: &Url) -> Result<FetchedRobots> {
+    let url = site.join("/robots.txt")?;
+    let (status, robots) = match http_fetch::get(client, &url).await {
+        Ok(response) => {
+            let status = response.status();
+            let robots = if status.is_success() {
+                RobotsTxt::parse(&response.text().await.unwrap_or_default())
+            } else if status.is_server_error() {
+                RobotsTxt::disallow_all()
+            } else {
+                RobotsTxt::default()
+            };
+            (Some(status.as_u16()), robots)
+        }
+        Err(e) => {
+            log::debug!("robots.txt unavailable for {site}: {e:#}");
+            (None, RobotsTxt::default())
+        }
+    };
+    Ok(FetchedRobots { url, status, robots })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+        User-agent: *\n\
+        Disallow: /private/\n\
+        Allow: /private/public-*.html$\n\
+        Crawl-delay: 2\n\
+        \n\
+        User-agent: kodegen-citescrape\n\
+        User-agent: otherbot\n\
+        Disallow: /drafts # unfinished\n\
+        Disallow:\n\
+        \n\
+        Sitemap: https://example.com/sitemap.xml\n";
+
+    fn check(robots: &RobotsTxt, agent: &str, path: &str) -> bool {
+        let url = Url::parse("https://example.com").unwrap().join(path).unwrap();
+        robots.check(agent, &url).allowed
+    }
+
+    #[test]
+    fn test_groups_and_precedence() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        assert_eq!(robots.sitemaps, ["https://example.com/sitemap.xml"]);
+
+        let generic = "Mozilla/5.0 (X11; Linux x86_64)";
+        assert_eq!(robots.matched_agent(generic).as_deref(), Some("*"));
+        assert_eq!(robots.crawl_delay(generic), Some(2.0));
+        assert!(!check(&robots, generic, "/private/secret.html"));
+        assert!(check(&robots, generic, "/private/public-page.html"));
+        assert!(!check(&robots, generic, "/private/public-page.html?x=1"));
+        assert!(check(&robots, generic, "/drafts/a"));
+
+        let ours = crate::utils::HTTP_USER_AGENT;
+        assert_eq!(robots.matched_agent(ours).as_deref(), Some("kodegen-citescrape"));
+        assert_eq!(robots.crawl_delay(ours), None);
+        assert!(!check(&robots, ours, "/drafts/a"));
+        assert!(check(&robots, ours, "/private/secret.html"));
+        assert!(check(&robots, ours, "/robots.txt"));
+    }
+
+    #[test]
+    fn test_disallow_all() {
+        let robots = RobotsTxt::disallow_all();
+        assert!(!check(&robots, "anybot", "/"));
+        assert!(check(&RobotsTxt::default(), "anybot", "/"));
+    }
+}
@@ -4,14 +4,23 @@
 //! handles document indexing operations, and executes search queries.
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
-use tantivy::directory::MmapDirectory;
-use tantivy::query::QueryParser;
-use tantivy::{Index, IndexReader, IndexSettings, IndexWriter, Term};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use tantivy::directory::{Advice, MmapDirectory};
+use tantivy::collector::DocSetCollector;
+use tantivy::query::{QueryParser, TermQuery};
+use tantivy::schema::{IndexRecordOption, Value};
+use tantivy::{Index, IndexReader, IndexSettings, IndexWriter, TantivyDocument, Term, Warmer};
+
+use futures::StreamExt;
+use tokio::sync::watch;
 
 use super::errors::{RetryConfig, SearchError, SearchResult};
+use super::indexer::{BatchConfig, MarkdownIndexer};
 use super::runtime_helpers::retry_task;
 use super::schema::SearchSchema;
+use super::types::{IndexProgress, IndexingPhase, StoredDocument};
+use super::warmer::IndexWarmer;
 use crate::config::CrawlConfig;
 
 /// Main search engine managing Tantivy index operations
@@ -22,6 +31,20 @@ pub struct SearchEngine {
     reader: IndexReader,
     query_parser: QueryParser,
     index_path: PathBuf,
+    /// Kept alive here; the reader only holds a weak reference
+    _warmer: Arc<IndexWarmer>,
+    /// Progress of the background reindex started by [`Self::create`], if any
+    reindex_progress: Option<watch::Receiver<Option<IndexProgress>>>,
+}
+
+/// Memory-map the index directory for random access
+///
+/// Queries jump between postings lists and stored documents, so kernel
+/// readahead mostly pulls in pages nothing reads; `IndexWarmer` preloads
+/// the parts every query needs instead.
+fn open_mmap_directory(index_dir: &Path) -> Result<MmapDirectory> {
+    MmapDirectory::open_with_madvice(index_dir, Advice::Random)
+        .with_context(|| format!("Failed to open index directory at {index_dir:?}"))
 }
 
 impl SearchEngine {
@@ -42,56 +65,55 @@ impl SearchEngine {
             .await
             .with_context(|| "Failed to build schema")?;
 
-        // Open or create Tantivy index with schema compatibility check
+        // Open or create Tantivy index, rebuilding it when its schema is out of
+        // date or an earlier rebuild stopped before its reindex finished
+        let mut needs_reindex = false;
         let index = if index_dir.join("meta.json").exists() {
-            let existing_index = Index::open_in_dir(&index_dir)
+            let existing_index = Index::open(open_mmap_directory(&index_dir)?)
                 .with_context(|| format!("Failed to open existing index at {index_dir:?}"))?;
 
-            // SCHEMA COMPATIBILITY CHECK
-            // Compare schema field counts to detect version mismatch
-            let existing_field_count = existing_index.schema().num_fields();
-            let expected_field_count = schema.schema.num_fields();
-
-            if existing_field_count != expected_field_count {
-                tracing::warn!(
-                    existing_fields = existing_field_count,
-                    expected_fields = expected_field_count,
-                    "Schema mismatch detected - recreating index"
-                );
-
-                // Close existing index handle before deletion
-                drop(existing_index);
-
-                // Remove old incompatible index
-                std::fs::remove_dir_all(&index_dir)
-                    .with_context(|| format!("Failed to remove old index at {index_dir:?}"))?;
-                std::fs::create_dir_all(&index_dir)
-                    .with_context(|| format!("Failed to recreate index directory at {index_dir:?}"))?;
-
-                // Create new index with current schema
-                let mmap_directory = MmapDirectory::open(&index_dir)
-                    .with_context(|| format!("Failed to create index directory at {index_dir:?}"))?;
-                Index::create(
-                    mmap_directory,
-                    schema.schema.clone(),
-                    IndexSettings::default(),
-                )
-                .with_context(|| "Failed to create new Tantivy index")?
+            let reason = if SearchSchema::reindex_pending(&index_dir) {
+                Some("an earlier rebuild did not finish reindexing".to_string())
             } else {
-                // Schema matches - use existing index
-                existing_index
+                schema.incompatibility(&existing_index.schema(), SearchSchema::stored_version(&index_dir))
+            };
+            match reason {
+                Some(reason) => {
+                    tracing::warn!(index = ?index_dir, %reason, "Search index schema changed - rebuilding index");
+
+                    // Close existing index handle before deletion
+                    drop(existing_index);
+
+                    // Remove old incompatible index
+                    std::fs::remove_dir_all(&index_dir)
+                        .with_context(|| format!("Failed to remove old index at {index_dir:?}"))?;
+                    std::fs::create_dir_all(&index_dir)
+                        .with_context(|| format!("Failed to recreate index directory at {index_dir:?}"))?;
+
+                    SearchSchema::mark_reindex_pending(&index_dir)?;
+                    needs_reindex = true;
+                    Index::create(
+                        open_mmap_directory(&index_dir)?,
+                        schema.schema.clone(),
+                        IndexSettings::default(),
+                    )
+                    .with_context(|| "Failed to create new Tantivy index")?
+                }
+                None => existing_index,
             }
         } else {
             // Create brand new index with the PROPER schema (not empty default)
-            let mmap_directory = MmapDirectory::open(&index_dir)
-                .with_context(|| format!("Failed to create index directory at {index_dir:?}"))?;
             Index::create(
-                mmap_directory,
+                open_mmap_directory(&index_dir)?,
                 schema.schema.clone(),
                 IndexSettings::default(),
             )
             .with_context(|| "Failed to create new Tantivy index")?
         };
+        // A reindexed index is stamped once its crawl output is back in
+        if !needs_reindex {
+            SearchSchema::write_version(&index_dir)?;
+        }
 
         // Register custom tokenizers with the index
         SearchSchema::builder()
@@ -107,10 +129,16 @@ impl SearchEngine {
         index_writer
             .commit()
             .with_context(|| "Failed to commit initial index state")?;
+        // Release the writer lock so a rebuild can reindex
+        drop(index_writer);
 
-        // Create reader for search operations
+        // Create reader for search operations, warming each searcher generation
+        // so the first query after a commit does not read a cold index
+        let warmer = Arc::new(IndexWarmer);
         let reader = index
-            .reader()
+            .reader_builder()
+            .warmers(vec![Arc::downgrade(&warmer) as Weak<dyn Warmer>])
+            .try_into()
             .with_context(|| "Failed to create index reader")?;
 
         // Create query parser for searchable fields only
@@ -126,13 +154,90 @@ impl SearchEngine {
         query_parser.set_field_boost(schema.title, 2.0);
         query_parser.set_field_boost(schema.plain_content, 1.0);
 
-        Ok(SearchEngine {
+        let mut engine = SearchEngine {
             index,
             schema,
             reader,
             query_parser,
             index_path: index_path_buf,
-        })
+            _warmer: warmer,
+            reindex_progress: None,
+        };
+        if needs_reindex {
+            let (progress_tx, progress_rx) = watch::channel(None);
+            engine.reindex_progress = Some(progress_rx);
+            tokio::spawn(engine.clone().reindex(config.storage_dir().to_path_buf(), progress_tx));
+        }
+        Ok(engine)
+    }
+
+    /// Index the crawl output under `storage_dir` again after a schema rebuild
+    ///
+    /// Runs in the background while the engine serves searches from what is
+    /// indexed so far; each update is published on `progress`. The index is
+    /// stamped with the schema version only once the whole crawl output was
+    /// processed (files that fail to index are logged); until then it stays
+    /// marked pending, so a reindex that errors out or is interrupted is
+    /// rebuilt the next time the index is opened.
+    async fn reindex(self, storage_dir: PathBuf, progress: watch::Sender<Option<IndexProgress>>) {
+        tracing::info!(dir = ?storage_dir, "Reindexing crawl output after schema change");
+        let (mut updates, _cancel) =
+            MarkdownIndexer::new(self.clone()).batch_index_directory(storage_dir, BatchConfig::default());
+        let mut last: Option<IndexProgress> = None;
+        while let Some(update) = updates.next().await {
+            match update {
+                Ok(update) => {
+                    if last.as_ref().is_none_or(|last| last.phase != update.phase) {
+                        tracing::info!(phase = ?update.phase, processed = update.processed, total = update.total, "Reindexing");
+                    }
+                    progress.send_replace(Some(update.clone()));
+                    last = Some(update);
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Reindexing after schema change failed");
+                    return;
+                }
+            }
+        }
+        if let Err(e) = self.reader.reload() {
+            tracing::warn!(error = %e, "Failed to reload reader after reindexing");
+        }
+        match last {
+            Some(last) if last.phase == IndexingPhase::Complete => {
+                if let Err(e) = SearchSchema::write_version(&self.index_path) {
+                    tracing::warn!(error = %e, "Failed to stamp reindexed search index");
+                }
+                tracing::info!(
+                    processed = last.processed,
+                    failed = last.failed,
+                    "Reindexed crawl output after schema change"
+                );
+            }
+            Some(last) => tracing::warn!(
+                processed = last.processed,
+                failed = last.failed,
+                phase = ?last.phase,
+                "Reindexing after schema change did not finish; it is retried on the next open"
+            ),
+            None => tracing::warn!("Reindexing after schema change produced no progress"),
+        }
+    }
+
+    /// Progress of the background reindex after a schema rebuild
+    ///
+    /// `None` when opening the index needed no reindex. The receiver holds
+    /// the latest update (`None` before the first) and reports the sender
+    /// closed once the reindex has ended.
+    #[must_use]
+    pub fn reindex_progress(&self) -> Option<watch::Receiver<Option<IndexProgress>>> {
+        self.reindex_progress.clone()
+    }
+
+    /// Wait for the background reindex to end, if one is running
+    pub async fn wait_for_reindex(&self) {
+        if let Some(mut progress) = self.reindex_progress() {
+            while progress.changed().await.is_ok() {}
+        }
     }
 
     /// Get a reference to the search schema
@@ -241,9 +346,63 @@ impl SearchEngine {
         None
     }
 
+    /// Get the stored document for an exact URL without running a query
+    ///
+    /// Looks the URL up as a single term of the untokenized `url_exact`
+    /// field. When a page was indexed more than once, the latest crawl is
+    /// returned.
+    pub fn get_document(&self, url: &str) -> Result<Option<StoredDocument>> {
+        let term = Term::from_field_text(self.schema.url_exact, url);
+        let query = TermQuery::new(term, IndexRecordOption::Basic);
+
+        let searcher = self.reader.searcher();
+        let candidates = searcher
+            .search(&query, &DocSetCollector)
+            .with_context(|| format!("Failed to look up {url}"))?;
+
+        let mut found: Option<StoredDocument> = None;
+        for address in candidates {
+            let doc: TantivyDocument = searcher
+                .doc(address)
+                .with_context(|| format!("Failed to load stored document for {url}"))?;
+            let document = self.stored_document(&doc);
+            if found.as_ref().is_none_or(|best| document.crawl_date > best.crawl_date) {
+                found = Some(document);
+            }
+        }
+        Ok(found)
+    }
+
+    fn stored_document(&self, doc: &TantivyDocument) -> StoredDocument {
+        let schema = &self.schema;
+        let text = |field| {
+            doc.get_first(field)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let number = |field| doc.get_first(field).and_then(|v| v.as_u64()).unwrap_or_default();
+
+        StoredDocument {
+            url: text(schema.url),
+            path: text(schema.path),
+            title: text(schema.title),
+            markdown: text(schema.raw_markdown),
+            snippet: text(schema.snippet),
+            crawl_date: doc
+                .get_first(schema.crawl_date)
+                .and_then(|v| v.as_datetime())
+                .and_then(|date| chrono::DateTime::from_timestamp(date.into_timestamp_secs(), 0)),
+            file_size: number(schema.file_size),
+            word_count: number(schema.word_count),
+            domain: text(schema.domain),
+            crawl_id: text(schema.crawl_id),
+        }
+    }
+
     /// Delete document by URL
     pub fn delete_document(&self, writer: &mut IndexWriter, url: String) -> Result<()> {
-        let url_term = Term::from_field_text(self.schema.url, &url);
+        let url_term = Term::from_field_text(self.schema.url_exact, &url);
         writer.delete_term(url_term);
         Ok(())
     }
@@ -73,6 +73,7 @@ impl IndexingSender {
             file_path,
             priority,
             completion_id,
+            span: tracing::Span::current(),
         };
 
         // Send message with error handling
@@ -296,7 +296,15 @@ impl IncrementalIndexingService {
             // Retry loop for transient failures
             loop {
                 let result = match &message {
-                    IndexingMessage::AddOrUpdate { url, file_path, .. } => {
+                    IndexingMessage::AddOrUpdate {
+                        url,
+                        file_path,
+                        span,
+                        ..
+                    } => {
+                        let _index_span =
+                            tracing::info_span!(parent: span, "crawl.index", url = %url, attempt = retry_count)
+                                .entered();
                         // Use batch indexing with existing writer (avoids double writer acquisition)
                         super::super::indexer::index_single_file_sync(
                             engine,
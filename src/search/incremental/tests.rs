@@ -19,6 +19,7 @@ fn test_deduplication_keeps_latest_operation() {
         file_path: PathBuf::from("/tmp/file1.md"),
         priority: MessagePriority::Normal,
         completion_id: 1,
+        span: tracing::Span::none(),
     });
 
     // Operation for url2 (should be kept)
@@ -27,6 +28,7 @@ fn test_deduplication_keeps_latest_operation() {
         file_path: PathBuf::from("/tmp/file2.md"),
         priority: MessagePriority::Normal,
         completion_id: 2,
+        span: tracing::Span::none(),
     });
 
     // Second operation for url1 (should be dropped)
@@ -41,6 +43,7 @@ fn test_deduplication_keeps_latest_operation() {
         file_path: PathBuf::from("/tmp/file3.md"),
         priority: MessagePriority::High,
         completion_id: 4,
+        span: tracing::Span::none(),
     });
 
     // Third operation for url1 - LATEST (should be kept)
@@ -49,6 +52,7 @@ fn test_deduplication_keeps_latest_operation() {
         file_path: PathBuf::from("/tmp/file1_updated.md"),
         priority: MessagePriority::High,
         completion_id: 5,
+        span: tracing::Span::none(),
     });
 
     // Optimize message (should always be kept)
@@ -169,6 +173,7 @@ fn test_deduplication_with_no_duplicates() {
         file_path: PathBuf::from("/tmp/file1.md"),
         priority: MessagePriority::Normal,
         completion_id: 1,
+        span: tracing::Span::none(),
     });
 
     message_batch.push(IndexingMessage::AddOrUpdate {
@@ -176,6 +181,7 @@ fn test_deduplication_with_no_duplicates() {
         file_path: PathBuf::from("/tmp/file2.md"),
         priority: MessagePriority::Normal,
         completion_id: 2,
+        span: tracing::Span::none(),
     });
 
     let original_len = message_batch.len();
@@ -227,6 +233,7 @@ fn test_deduplication_all_duplicates_same_url() {
             file_path: PathBuf::from(format!("/tmp/file{i}.md")),
             priority: MessagePriority::Normal,
             completion_id: i,
+            span: tracing::Span::none(),
         });
     }
 
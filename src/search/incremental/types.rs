@@ -28,6 +28,8 @@ pub enum IndexingMessage {
         file_path: PathBuf,
         priority: MessagePriority,
         completion_id: u64,
+        /// Span of the page that produced the file, so indexing shows up in its trace
+        span: tracing::Span,
     },
     /// Delete a document from the index by URL
     Delete { url: ImString, completion_id: u64 },
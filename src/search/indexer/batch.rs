@@ -13,11 +13,11 @@ use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
 use tantivy::DateTime as TantivyDateTime;
 use tantivy::{IndexWriter, TantivyDocument};
+use tokio_util::sync::CancellationToken;
 
 // Thread-local buffer pool for zero-allocation decompression
 thread_local! {
@@ -64,7 +64,10 @@ pub struct BatchConfig {
     /// File size and compression safety limits
     pub limits: IndexingLimits,
     /// Cancellation token for aborting long-running operations
-    pub cancellation_token: Option<Arc<AtomicBool>>,
+    ///
+    /// Indexing watches a child of this token, so cancelling it (for example
+    /// when the owning connection goes away) stops the batch.
+    pub cancellation_token: Option<CancellationToken>,
 }
 
 impl Default for BatchConfig {
@@ -90,7 +93,7 @@ impl BatchConfig {
     pub fn is_cancelled(&self) -> bool {
         self.cancellation_token
             .as_ref()
-            .is_some_and(|token| token.load(Ordering::Acquire))
+            .is_some_and(CancellationToken::is_cancelled)
     }
 }
 
@@ -169,7 +172,7 @@ pub(crate) fn process_batch_with_writer(
                         );
                         // Set cancellation token to stop all parallel workers
                         if let Some(token) = &ctx.config.cancellation_token {
-                            token.store(true, Ordering::Release);
+                            token.cancel();
                         }
                         return None;
                     }
@@ -310,6 +313,7 @@ fn prepare_document_from_file(
             doc.add_u64(engine.schema().word_count, processed.word_count);
             doc.add_text(engine.schema().domain, domain);
             doc.add_text(engine.schema().crawl_id, crawl_id);
+            doc.add_text(engine.schema().url_exact, processed.url.as_str());
             
             Ok(doc)
         })
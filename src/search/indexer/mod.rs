@@ -27,26 +27,26 @@ use imstr::ImString;
 use progress::{AtomicProgress, ErrorCollector};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 /// Handle for cancelling a long-running batch indexing operation
 #[derive(Clone)]
 pub struct CancellationHandle {
-    token: Arc<AtomicBool>,
+    token: CancellationToken,
 }
 
 impl CancellationHandle {
     /// Cancel the associated indexing operation
     pub fn cancel(&self) {
-        self.token.store(true, Ordering::Release);
+        self.token.cancel();
     }
 
     /// Check if the operation has been cancelled
     #[must_use]
     pub fn is_cancelled(&self) -> bool {
-        self.token.load(Ordering::Acquire)
+        self.token.is_cancelled()
     }
 }
 
@@ -82,8 +82,12 @@ impl MarkdownIndexer {
         let engine = self.engine.clone();
         let (tx, stream) = AsyncStream::channel();
 
-        // Create cancellation token
-        let cancel_token = Arc::new(AtomicBool::new(false));
+        // Create cancellation token, tied to the caller's token if one was given
+        let cancel_token = config
+            .cancellation_token
+            .as_ref()
+            .map(CancellationToken::child_token)
+            .unwrap_or_default();
         let cancel_handle = CancellationHandle {
             token: cancel_token.clone(),
         };
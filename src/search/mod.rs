@@ -12,6 +12,7 @@ pub mod query;
 pub mod runtime_helpers;
 pub mod schema;
 pub mod types;
+pub mod warmer;
 
 pub use engine::SearchEngine;
 pub use errors::{RetryConfig, SearchError, SearchResult};
@@ -20,7 +21,8 @@ pub use indexer::MarkdownIndexer;
 pub use query::{SearchQueryBuilder, SearchQueryType, SearchResults, search, search_with_options};
 pub use runtime_helpers::{fallback_task, retry_task};
 pub use schema::{SchemaError, SchemaPerformanceInfo, SearchSchema, SearchSchemaBuilder};
-pub use types::{IndexProgress, ProcessedMarkdown};
+pub use types::{IndexProgress, ProcessedMarkdown, StoredDocument};
+pub use warmer::IndexWarmer;
 
 use anyhow::Result;
 
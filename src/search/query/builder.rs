@@ -4,6 +4,7 @@ use anyhow::Result;
 
 use super::execution::execute_search_query;
 use super::results::SearchResults;
+use crate::link_index::LinkIndex;
 use crate::search::engine::SearchEngine;
 use crate::search::types::SearchResultItem;
 
@@ -15,6 +16,7 @@ pub struct SearchQueryBuilder {
     highlight: bool,
     domain_filter: Option<String>,
     crawl_id_filter: Option<String>,
+    link_index: Option<LinkIndex>,
 }
 
 impl SearchQueryBuilder {
@@ -27,6 +29,7 @@ impl SearchQueryBuilder {
             highlight: true,
             domain_filter: None,
             crawl_id_filter: None,
+            link_index: None,
         }
     }
 
@@ -65,6 +68,17 @@ impl SearchQueryBuilder {
         self
     }
 
+    /// Boost results by the PageRank stored in the crawl's link index
+    ///
+    /// Pages many other pages link to (overviews, guides) then rank above
+    /// equally relevant leaf pages. Without an index, or before the crawl
+    /// computed PageRank, results keep their text relevance order.
+    #[must_use]
+    pub fn page_ranks(mut self, link_index: Option<LinkIndex>) -> Self {
+        self.link_index = link_index;
+        self
+    }
+
     /// Execute the search query and return results
     pub async fn execute(self, engine: SearchEngine) -> Result<Vec<SearchResultItem>> {
         let query = self.query.clone();
@@ -74,8 +88,17 @@ impl SearchQueryBuilder {
         let domain_filter = self.domain_filter.as_deref();
         let crawl_id_filter = self.crawl_id_filter.as_deref();
 
-        let search_results =
-            execute_search_query(&engine, &query, limit, offset, highlight, domain_filter, crawl_id_filter).await?;
+        let search_results = execute_search_query(
+            &engine,
+            &query,
+            limit,
+            offset,
+            highlight,
+            domain_filter,
+            crawl_id_filter,
+            self.link_index.as_ref(),
+        )
+        .await?;
         Ok(search_results.results)
     }
 
@@ -88,6 +111,16 @@ impl SearchQueryBuilder {
         let domain_filter = self.domain_filter.as_deref();
         let crawl_id_filter = self.crawl_id_filter.as_deref();
 
-        execute_search_query(&engine, &query, limit, offset, highlight, domain_filter, crawl_id_filter).await
+        execute_search_query(
+            &engine,
+            &query,
+            limit,
+            offset,
+            highlight,
+            domain_filter,
+            crawl_id_filter,
+            self.link_index.as_ref(),
+        )
+        .await
     }
 }
@@ -1,17 +1,25 @@
 //! Search query execution logic
 
+use std::collections::HashMap;
+
 use anyhow::Result;
 use tantivy::collector::{Count, TopDocs};
 
 use super::parsing::parse_query_sync;
 use super::results::{SearchResults, convert_to_search_result};
 use super::snippets::SnippetGenerators;
+use crate::link_index::LinkIndex;
 use crate::search::engine::SearchEngine;
 use crate::search::errors::{SearchError, SearchResult};
 use crate::search::runtime_helpers::fallback_task;
 use crate::search::types::SearchResultItem;
 
+/// Largest factor PageRank multiplies a text relevance score by, reached by
+/// the best-linked page among the fetched results
+const PAGE_RANK_WEIGHT: f32 = 0.5;
+
 /// Execute a search query against the index with fallback behavior
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn execute_search_query(
     engine: &SearchEngine,
     query_str: &str,
@@ -20,6 +28,7 @@ pub(crate) async fn execute_search_query(
     highlight: bool,
     domain_filter: Option<&str>,
     crawl_id_filter: Option<&str>,
+    link_index: Option<&LinkIndex>,
 ) -> Result<SearchResults> {
     let engine = engine.clone();
     let query_str = query_str.to_string();
@@ -33,6 +42,8 @@ pub(crate) async fn execute_search_query(
     let crawl_id_filter_primary = crawl_id_filter.map(|s| s.to_string());
     let domain_filter_fallback = domain_filter.map(|s| s.to_string());
     let crawl_id_filter_fallback = crawl_id_filter.map(|s| s.to_string());
+    let link_index_primary = link_index.cloned();
+    let link_index_fallback = link_index.cloned();
 
     // Use fallback_task for primary and fallback search
     let result = fallback_task(
@@ -45,6 +56,7 @@ pub(crate) async fn execute_search_query(
                 highlight,
                 domain_filter_primary.as_deref(),
                 crawl_id_filter_primary.as_deref(),
+                link_index_primary.as_ref(),
             )
             .await
         },
@@ -58,6 +70,7 @@ pub(crate) async fn execute_search_query(
                 false,
                 domain_filter_fallback.as_deref(),
                 crawl_id_filter_fallback.as_deref(),
+                link_index_fallback.as_ref(),
             )
             .await
         },
@@ -69,6 +82,7 @@ pub(crate) async fn execute_search_query(
 }
 
 /// Internal search execution with configurable features
+#[allow(clippy::too_many_arguments)]
 async fn execute_search_with_features(
     engine: SearchEngine,
     query_str: String,
@@ -77,6 +91,7 @@ async fn execute_search_with_features(
     highlight: bool,
     domain_filter: Option<&str>,
     crawl_id_filter: Option<&str>,
+    link_index: Option<&LinkIndex>,
 ) -> SearchResult<SearchResults> {
     let reader = engine.reader();
     let searcher = reader.searcher();
@@ -151,7 +166,6 @@ async fn execute_search_with_features(
         })?;
 
     // Deduplicate by path, keeping highest score
-    use std::collections::HashMap;
     let mut path_to_result: HashMap<String, SearchResultItem> = HashMap::new();
 
     for (score, doc_address) in top_docs {
@@ -178,6 +192,13 @@ async fn execute_search_with_features(
 
     // Convert to sorted vec (by score descending)
     let mut results: Vec<SearchResultItem> = path_to_result.into_values().collect();
+    if let Some(index) = link_index {
+        let urls: Vec<&str> = results.iter().map(|item| item.url.as_str()).collect();
+        match index.page_ranks(&urls).await {
+            Ok(ranks) => apply_page_ranks(&mut results, &ranks),
+            Err(e) => tracing::warn!("Failed to read PageRank, ranking by relevance only: {e}"),
+        }
+    }
     results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
 
     // Apply limit after deduplication
@@ -191,3 +212,46 @@ async fn execute_search_with_features(
         limit,
     })
 }
+
+/// Scale each score by up to `1 + PAGE_RANK_WEIGHT` according to its page's
+/// PageRank relative to the best-ranked result
+fn apply_page_ranks(results: &mut [SearchResultItem], ranks: &HashMap<String, f64>) {
+    let max_rank = ranks.values().copied().fold(0.0, f64::max);
+    if max_rank <= 0.0 {
+        return;
+    }
+    for item in results {
+        if let Some(rank) = ranks.get(&item.url) {
+            item.score *= 1.0 + PAGE_RANK_WEIGHT * (rank / max_rank) as f32;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(url: &str, score: f32) -> SearchResultItem {
+        SearchResultItem {
+            path: String::new(),
+            url: url.to_string(),
+            title: String::new(),
+            excerpt: String::new(),
+            score,
+        }
+    }
+
+    #[test]
+    fn test_page_rank_boosts_linked_pages() {
+        let mut results = vec![item("https://a/leaf", 2.0), item("https://a/guide", 1.8), item("https://a/new", 1.0)];
+        let ranks = HashMap::from([("https://a/leaf".to_string(), 0.1), ("https://a/guide".to_string(), 0.4)]);
+        apply_page_ranks(&mut results, &ranks);
+        assert_eq!(results[0].score, 2.0 * 1.125);
+        assert_eq!(results[1].score, 1.8 * 1.5);
+        assert_eq!(results[2].score, 1.0);
+
+        let mut unranked = vec![item("https://a/leaf", 2.0)];
+        apply_page_ranks(&mut unranked, &HashMap::new());
+        assert_eq!(unranked[0].score, 2.0);
+    }
+}
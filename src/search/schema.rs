@@ -3,12 +3,13 @@
 //! This module implements a zero-allocation, lock-free search schema with dual indexing
 //! for both raw markdown preservation and natural language search optimization.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use tantivy::{
     schema::{
-        DateOptions, Field, IndexRecordOption, NumericOptions, Schema, TextFieldIndexing,
-        TextOptions,
+        DateOptions, Field, IndexRecordOption, NumericOptions, STRING, Schema,
+        TextFieldIndexing, TextOptions,
     },
     tokenizer::{
         AlphaNumOnlyFilter, Language, LowerCaser, NgramTokenizer, SimpleTokenizer, Stemmer,
@@ -22,16 +23,23 @@ const RAW_MARKDOWN_TOKENIZER: &str = "raw_markdown";
 const CONTENT_SEARCH_TOKENIZER: &str = "content_search";
 const NGRAM_TOKENIZER: &str = "ngram_search";
 
-/// Schema version - increment when adding/removing/modifying fields
+/// Schema version - increment when adding/removing/modifying fields, or when
+/// indexed content changes meaning so existing documents must be rebuilt
 /// Version history:
 /// - v1: Initial 9-field schema (url, path, title, raw_markdown, plain_content, snippet, crawl_date, file_size, word_count)
 /// - v2: Added domain, crawl_id fields (11 total)
-#[allow(dead_code)]
-pub const SCHEMA_VERSION: u32 = 2;
+/// - v3: Added url_exact, the untokenized URL used for exact lookups (12 total)
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// File in the index directory recording the [`SCHEMA_VERSION`] the index was built with
+pub const SCHEMA_VERSION_FILE: &str = "schema_version";
+
+/// File in the index directory marking a rebuild whose reindex has not finished
+pub const REINDEX_PENDING_FILE: &str = "reindex_pending";
 
 /// Expected field count for current schema version
 #[allow(dead_code)]
-pub const EXPECTED_FIELD_COUNT: usize = 11;
+pub const EXPECTED_FIELD_COUNT: usize = 12;
 
 /// Production search schema with optimized dual indexing for markdown content
 #[derive(Debug, Clone)]
@@ -48,6 +56,8 @@ pub struct SearchSchema {
     pub word_count: Field,
     pub domain: Field,   // NEW: For domain-scoped searches
     pub crawl_id: Field, // NEW: Crawl session identifier
+    /// Untokenized URL, one term per document, for exact lookups and deletes
+    pub url_exact: Field,
 }
 
 /// Schema builder for flexible configuration and validation
@@ -131,6 +141,7 @@ impl SearchSchema {
             "word_count",
             "domain",
             "crawl_id",
+            "url_exact",
         ];
 
         let existing_fields: HashSet<&str> = self
@@ -166,6 +177,7 @@ impl SearchSchema {
             ("word_count", "U64"),
             ("domain", "Text"),
             ("crawl_id", "Text"),
+            ("url_exact", "Text"),
         ];
 
         for (field_name, expected_type) in &field_type_expectations {
@@ -284,6 +296,76 @@ impl SearchSchema {
             .collect()
     }
 
+    /// Why an index with `existing` schema, stamped `stored_version`, cannot
+    /// be used with this schema; `None` when it can
+    ///
+    /// Indexes from before versioning carry no stamp and are accepted when
+    /// their fields match.
+    #[must_use]
+    pub fn incompatibility(&self, existing: &Schema, stored_version: Option<u32>) -> Option<String> {
+        if let Some(version) = stored_version
+            && version != SCHEMA_VERSION
+        {
+            return Some(format!("index has schema version {version}, expected {SCHEMA_VERSION}"));
+        }
+        if *existing == self.schema {
+            return None;
+        }
+
+        let mut changes = Vec::new();
+        for (_, expected) in self.schema.fields() {
+            match existing.get_field(expected.name()) {
+                Err(_) => changes.push(format!("added '{}'", expected.name())),
+                Ok(field) if existing.get_field_entry(field) != expected => {
+                    changes.push(format!("changed '{}'", expected.name()));
+                }
+                Ok(_) => {}
+            }
+        }
+        for (_, found) in existing.fields() {
+            if self.schema.get_field(found.name()).is_err() {
+                changes.push(format!("removed '{}'", found.name()));
+            }
+        }
+        if changes.is_empty() {
+            changes.push("fields reordered".to_string());
+        }
+        Some(format!("index fields differ: {}", changes.join(", ")))
+    }
+
+    /// Schema version stamped in `index_dir`, if any
+    #[must_use]
+    pub fn stored_version(index_dir: &Path) -> Option<u32> {
+        std::fs::read_to_string(index_dir.join(SCHEMA_VERSION_FILE))
+            .ok()
+            .and_then(|version| version.trim().parse().ok())
+    }
+
+    /// Stamp `index_dir` with the current [`SCHEMA_VERSION`], clearing a
+    /// pending reindex
+    pub fn write_version(index_dir: &Path) -> Result<()> {
+        std::fs::write(index_dir.join(SCHEMA_VERSION_FILE), format!("{SCHEMA_VERSION}\n"))
+            .with_context(|| format!("Failed to write schema version to {index_dir:?}"))?;
+        match std::fs::remove_file(index_dir.join(REINDEX_PENDING_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to clear pending reindex in {index_dir:?}"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Mark `index_dir` as rebuilt but not yet reindexed
+    pub fn mark_reindex_pending(index_dir: &Path) -> Result<()> {
+        std::fs::write(index_dir.join(REINDEX_PENDING_FILE), "")
+            .with_context(|| format!("Failed to mark pending reindex in {index_dir:?}"))
+    }
+
+    /// Whether a rebuild of `index_dir` stopped before its reindex finished
+    #[must_use]
+    pub fn reindex_pending(index_dir: &Path) -> bool {
+        index_dir.join(REINDEX_PENDING_FILE).exists()
+    }
+
     /// Get schema performance characteristics for monitoring
     #[must_use]
     pub fn performance_info(&self) -> SchemaPerformanceInfo {
@@ -580,6 +662,9 @@ impl SearchSchemaBuilder {
             );
         let crawl_id = schema_builder.add_text_field("crawl_id", crawl_id_options);
 
+        // Add the whole URL as a single raw term for exact lookups
+        let url_exact = schema_builder.add_text_field("url_exact", STRING);
+
         let schema = schema_builder.build();
 
         let search_schema = SearchSchema {
@@ -595,6 +680,7 @@ impl SearchSchemaBuilder {
             word_count,
             domain,
             crawl_id,
+            url_exact,
         };
 
         // Validate if enabled
@@ -18,6 +18,22 @@ pub struct SearchResultItem {
     pub score: f32,
 }
 
+/// A page as stored in the index, looked up by its exact URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredDocument {
+    pub url: String,
+    pub path: String,
+    pub title: String,
+    /// The page's markdown as it was indexed
+    pub markdown: String,
+    pub snippet: String,
+    pub crawl_date: Option<DateTime<Utc>>,
+    pub file_size: u64,
+    pub word_count: u64,
+    pub domain: String,
+    pub crawl_id: String,
+}
+
 /// Processed markdown document data
 #[derive(Debug, Clone)]
 pub struct ProcessedMarkdown {
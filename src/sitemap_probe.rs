@@ -0,0 +1,197 @@
+//! Sitemap discovery and enumeration
+//!
+//! Reads the sitemaps a site publishes (listed in robots.txt or at
+//! `/sitemap.xml`), follows sitemap indexes and collects the page URLs so a
+//! crawl can be scoped before it starts. XML, gzipped XML and plain-text
+//! sitemaps are supported.
+
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+
+use anyhow::{Result, bail};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::utils::http_fetch;
+use crate::utils::xml_tree::XmlElement;
+
+/// A `<url>` or `<sitemap>` entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapEntry {
+    /// Absolute URL
+    pub loc: String,
+    /// `<lastmod>` value, if present
+    pub lastmod: Option<String>,
+}
+
+/// A parsed sitemap document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SitemapDocument {
+    /// `<urlset>` or plain-text list of page URLs
+    UrlSet(Vec<SitemapEntry>),
+    /// `<sitemapindex>` listing further sitemaps
+    Index(Vec<SitemapEntry>),
+}
+
+/// Parse an XML or plain-text sitemap
+pub fn parse_sitemap(body: &str) -> Result<SitemapDocument> {
+    if !body.trim_start().starts_with('<') {
+        let entries = body
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("http://") || line.starts_with("https://"))
+            .map(|loc| SitemapEntry {
+                loc: loc.to_string(),
+                lastmod: None,
+            })
+            .collect();
+        return Ok(SitemapDocument::UrlSet(entries));
+    }
+
+    let root = XmlElement::parse(body)?;
+    let entries = |name: &str| {
+        root.children_named(name)
+            .filter_map(|entry| {
+                Some(SitemapEntry {
+                    loc: entry.child_text("loc")?,
+                    lastmod: entry.child_text("lastmod"),
+                })
+            })
+            .collect()
+    };
+    match root.name.as_str() {
+        "urlset" => Ok(SitemapDocument::UrlSet(entries("url"))),
+        "sitemapindex" => Ok(SitemapDocument::Index(entries("sitemap"))),
+        other => bail!("Not a sitemap: root element is <{other}>"),
+    }
+}
+
+/// One sitemap file visited during a probe
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SitemapFile {
+    /// Sitemap URL
+    pub url: String,
+    /// Whether this is a sitemap index
+    pub is_index: bool,
+    /// Entries listed (page URLs, or child sitemaps for an index)
+    pub entries: usize,
+    /// Error if the sitemap could not be read
+    pub error: Option<String>,
+}
+
+/// Everything found by [`probe_sitemaps`]
+#[derive(Debug, Clone, Default)]
+pub struct SitemapProbe {
+    /// Sitemap files in visit order
+    pub files: Vec<SitemapFile>,
+    /// Unique page URLs
+    pub urls: Vec<SitemapEntry>,
+    /// Whether limits stopped the probe early
+    pub truncated: bool,
+}
+
+/// Fetch the sitemaps in `roots`, following indexes breadth-first
+///
+/// Reads at most `max_files` sitemaps and keeps at most `max_urls` page URLs.
+pub async fn probe_sitemaps(
+    client: &reqwest::Client,
+    roots: Vec<Url>,
+    max_files: usize,
+    max_urls: usize,
+) -> SitemapProbe {
+    let mut probe = SitemapProbe::default();
+    let mut seen_files: HashSet<Url> = roots.iter().cloned().collect();
+    let mut seen_urls = HashSet::new();
+    let mut queue: VecDeque<Url> = roots.into();
+
+    while let Some(url) = queue.pop_front() {
+        if probe.files.len() >= max_files {
+            probe.truncated = true;
+            break;
+        }
+        let document = match fetch_sitemap(client, &url).await {
+            Ok(document) => document,
+            Err(e) => {
+                probe.files.push(SitemapFile {
+                    url: url.to_string(),
+                    is_index: false,
+                    entries: 0,
+                    error: Some(format!("{e:#}")),
+                });
+                continue;
+            }
+        };
+
+        let (is_index, entries) = match document {
+            SitemapDocument::Index(entries) => (true, entries),
+            SitemapDocument::UrlSet(entries) => (false, entries),
+        };
+        probe.files.push(SitemapFile {
+            url: url.to_string(),
+            is_index,
+            entries: entries.len(),
+            error: None,
+        });
+
+        for entry in entries {
+            if is_index {
+                if let Ok(child) = url.join(&entry.loc)
+                    && seen_files.insert(child.clone())
+                {
+                    queue.push_back(child);
+                }
+            } else if seen_urls.insert(entry.loc.clone()) {
+                if probe.urls.len() >= max_urls {
+                    probe.truncated = true;
+                    break;
+                }
+                probe.urls.push(entry);
+            }
+        }
+    }
+    probe
+}
+
+/// Fetch and parse one sitemap, decompressing gzip bodies
+async fn fetch_sitemap(client: &reqwest::Client, url: &Url) -> Result<SitemapDocument> {
+    let bytes = http_fetch::fetch_bytes(client, url).await?;
+    let body = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(&bytes[..]).read_to_string(&mut text)?;
+        text
+    } else {
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+    parse_sitemap(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sitemap_kinds() {
+        let urlset = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc>https://example.com/docs/a</loc><lastmod>2025-05-01</lastmod></url>
+              <url><loc>https://example.com/blog/b</loc></url>
+              <url><lastmod>2025-05-02</lastmod></url>
+            </urlset>"#;
+        let SitemapDocument::UrlSet(urls) = parse_sitemap(urlset).unwrap() else {
+            panic!("expected urlset");
+        };
+        assert_eq!(urls.len(), 2);
+        assert_eq!(urls[0].lastmod.as_deref(), Some("2025-05-01"));
+
+        let index = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://example.com/sitemap-docs.xml</loc></sitemap>
+            </sitemapindex>"#;
+        assert!(matches!(parse_sitemap(index).unwrap(), SitemapDocument::Index(s) if s.len() == 1));
+
+        let text = "https://example.com/a\n\nnot a url\nhttps://example.com/b\n";
+        assert!(matches!(parse_sitemap(text).unwrap(), SitemapDocument::UrlSet(u) if u.len() == 2));
+
+        assert!(parse_sitemap("<html><body/></html>").is_err());
+    }
+}
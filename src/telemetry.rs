@@ -0,0 +1,119 @@
+//! Export of crawl pipeline traces to an OpenTelemetry collector
+//!
+//! The crawl engine always emits `tracing` spans (see
+//! [`crate::crawl_engine::crawl_pages`]); they cost next to nothing until a
+//! subscriber records them. [`init_tracing`] installs a subscriber that sends
+//! them over OTLP/HTTP to a collector such as Jaeger or Tempo when
+//! `telemetry.otlp_endpoint` is set. Export needs the `otel` feature. The
+//! same subscriber writes JSON logs (see [`crate::logging`]) when those are
+//! enabled.
+
+use anyhow::{Context, Result};
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::{LogFormat, LoggingSettings, TelemetrySettings};
+
+/// Path of the OTLP/HTTP traces endpoint under the collector URL
+#[cfg(feature = "otel")]
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Keeps trace export running; flushes buffered spans when dropped
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = &self.provider
+            && let Err(e) = provider.shutdown()
+        {
+            log::warn!("Failed to flush traces: {e}");
+        }
+    }
+}
+
+/// Install the tracing subscriber for OTLP export and JSON logs
+///
+/// Returns `None` when neither is configured, leaving `log` records to the
+/// server's text logger. Hold the guard until shutdown so the last spans are
+/// flushed.
+pub fn init_tracing(telemetry: &TelemetrySettings, logging: &LoggingSettings) -> Result<Option<TelemetryGuard>> {
+    let json = (logging.format == LogFormat::Json)
+        .then(|| crate::logging::JsonLayer::stderr().with_filter(crate::logging::env_filter()));
+    let (otel, guard) = otel_layer::<tracing_subscriber::Registry>(telemetry)?;
+    if json.is_none() && guard.is_none() {
+        return Ok(None);
+    }
+
+    let json_logs = json.is_some();
+    let subscriber = tracing_subscriber::registry().with(otel).with(json);
+    if json_logs {
+        // Also routes `log` records through the subscriber
+        subscriber
+            .try_init()
+            .context("A tracing subscriber or logger is already installed")?;
+    } else {
+        tracing::subscriber::set_global_default(subscriber)
+            .context("A tracing subscriber is already installed")?;
+    }
+    if let Some(endpoint) = &telemetry.otlp_endpoint {
+        log::info!("Exporting traces to {endpoint}");
+    }
+    Ok(Some(guard.unwrap_or(TelemetryGuard {
+        #[cfg(feature = "otel")]
+        provider: None,
+    })))
+}
+
+/// Layer exporting spans to `settings.otlp_endpoint`, if set
+#[cfg(feature = "otel")]
+fn otel_layer<S>(settings: &TelemetrySettings) -> Result<(Option<impl Layer<S>>, Option<TelemetryGuard>)>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let Some(endpoint) = &settings.otlp_endpoint else {
+        return Ok((None, None));
+    };
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_url(endpoint))
+        .build()
+        .context("Failed to create OTLP span exporter")?;
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name(settings.service_name.clone())
+                .build(),
+        )
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer("citescrape"));
+    Ok((Some(layer), Some(TelemetryGuard { provider: Some(provider) })))
+}
+
+#[cfg(not(feature = "otel"))]
+fn otel_layer<S: tracing::Subscriber>(
+    settings: &TelemetrySettings,
+) -> Result<(Option<impl Layer<S>>, Option<TelemetryGuard>)> {
+    match &settings.otlp_endpoint {
+        Some(endpoint) => anyhow::bail!("Cannot export traces to {endpoint}: built without the `otel` feature"),
+        None => Ok((None::<tracing_subscriber::layer::Identity>, None)),
+    }
+}
+
+/// Collector base URL -> traces endpoint; a full traces URL is kept as is
+#[cfg(feature = "otel")]
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(TRACES_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}{TRACES_PATH}")
+    }
+}
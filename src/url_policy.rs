@@ -0,0 +1,226 @@
+//! Which URLs the server may fetch
+//!
+//! One [`UrlPolicy`] is in effect for the whole process (see
+//! [`set_url_policy`]). Every navigation consults it: crawl and tool pages
+//! through [`crate::crawl_engine::interception`], which also sees redirects
+//! and subresource requests, and plain HTTP fetches through the resolver and
+//! redirect policy of [`crate::utils::http_fetch::guarded_client`].
+//!
+//! Host names are resolved before they are allowed, so a public name pointing
+//! at a private address is rejected as well.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+
+use anyhow::{Result, bail};
+use url::{Host, Url};
+
+static POLICY: LazyLock<RwLock<Arc<UrlPolicy>>> = LazyLock::new(|| RwLock::new(Arc::new(UrlPolicy::default())));
+
+/// Replace the process-wide URL policy
+pub fn set_url_policy(policy: UrlPolicy) {
+    *POLICY.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(policy);
+}
+
+/// The process-wide URL policy in effect now
+#[must_use]
+pub fn url_policy() -> Arc<UrlPolicy> {
+    POLICY.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Hosts the server may fetch from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlPolicy {
+    /// Domains navigations may target (subdomains included); `None` allows any
+    allowed_domains: Option<Vec<String>>,
+    /// Reject loopback, private and link-local hosts, for every request
+    block_private_hosts: bool,
+}
+
+impl UrlPolicy {
+    #[must_use]
+    pub fn new(allowed_domains: Option<Vec<String>>, block_private_hosts: bool) -> Self {
+        Self {
+            allowed_domains,
+            block_private_hosts,
+        }
+    }
+
+    /// Whether any URL is allowed
+    #[must_use]
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed_domains.is_none() && !self.block_private_hosts
+    }
+
+    #[must_use]
+    pub fn blocks_private_hosts(&self) -> bool {
+        self.block_private_hosts
+    }
+
+    /// Check a navigation target without resolving its host
+    ///
+    /// Navigations are the documents a page loads (including redirects and
+    /// frames) and plain HTTP fetches; they must be on an allowed domain.
+    pub fn check_navigation(&self, url: &Url) -> Result<()> {
+        let host = self.check_host(url)?;
+        if let Some(allowed) = &self.allowed_domains {
+            let name = host.to_string().to_ascii_lowercase();
+            let permitted = allowed
+                .iter()
+                .any(|domain| name == *domain || name.ends_with(&format!(".{domain}")));
+            if !permitted {
+                bail!(
+                    "Domain '{name}' is not allowed on this server (allowed: {})",
+                    allowed.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Check any request a page makes (images, scripts, XHR) without
+    /// resolving its host; only private hosts are rejected
+    pub fn check_request(&self, url: &Url) -> Result<()> {
+        self.check_host(url).map(|_| ())
+    }
+
+    fn check_host<'a>(&self, url: &'a Url) -> Result<Host<&'a str>> {
+        let Some(host) = url.host() else {
+            bail!("URL '{url}' has no host");
+        };
+        if self.block_private_hosts && is_private_host(&host) {
+            bail!("Fetching internal host '{host}' is not allowed on this server");
+        }
+        Ok(host)
+    }
+
+    /// Fail if `url`'s host resolves to a private address
+    ///
+    /// Names that do not resolve are rejected too, since the browser might
+    /// resolve them differently.
+    pub async fn check_resolved(&self, url: &Url) -> Result<()> {
+        if !self.block_private_hosts {
+            return Ok(());
+        }
+        let Some(Host::Domain(name)) = url.host() else {
+            // IP literals are checked without resolving
+            return Ok(());
+        };
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name, port))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to resolve host '{name}': {e}"))?
+            .collect();
+        self.check_addrs(name, addrs.iter().map(SocketAddr::ip))
+    }
+
+    /// Fail if any of the addresses `name` resolved to is private
+    pub fn check_addrs(&self, name: &str, addrs: impl IntoIterator<Item = IpAddr>) -> Result<()> {
+        if !self.block_private_hosts {
+            return Ok(());
+        }
+        let mut resolved = false;
+        for ip in addrs {
+            resolved = true;
+            if is_private_ip(ip) {
+                bail!("Host '{name}' resolves to internal address {ip}, which is not allowed on this server");
+            }
+        }
+        if !resolved {
+            bail!("Host '{name}' did not resolve to any address");
+        }
+        Ok(())
+    }
+
+    /// Check a navigation target, resolving its host
+    pub async fn check_url(&self, url: &str) -> Result<Url> {
+        let parsed = Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid URL '{url}': {e}"))?;
+        self.check_navigation(&parsed)?;
+        self.check_resolved(&parsed).await?;
+        Ok(parsed)
+    }
+}
+
+/// Whether `host` names the local machine or a private network
+fn is_private_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(name) => {
+            let name = name.trim_end_matches('.').to_ascii_lowercase();
+            name == "localhost"
+                || [".localhost", ".local", ".internal", ".lan", ".home.arpa"]
+                    .iter()
+                    .any(|suffix| name.ends_with(suffix))
+        }
+        Host::Ipv4(ip) => is_private_ip(IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => is_private_ip(IpAddr::V6(*ip)),
+    }
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                // Carrier-grade NAT (100.64.0.0/10)
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_private_ip(IpAddr::V4(v4));
+            }
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_allowed_domains_apply_to_navigations_only() {
+        let policy = UrlPolicy::new(Some(vec!["docs.rs".to_string()]), false);
+        assert!(policy.check_navigation(&url("https://docs.rs/tokio")).is_ok());
+        assert!(policy.check_navigation(&url("https://evil.com/")).is_err());
+        assert!(policy.check_request(&url("https://cdn.example.net/app.js")).is_ok());
+        assert!(UrlPolicy::default().is_unrestricted());
+    }
+
+    #[test]
+    fn test_private_hosts_blocked_for_every_request() {
+        let policy = UrlPolicy::new(None, true);
+        assert!(policy.check_request(&url("http://169.254.169.254/latest/meta-data")).is_err());
+        assert!(policy.check_request(&url("http://[::ffff:10.0.0.1]/")).is_err());
+        assert!(policy.check_navigation(&url("http://printer.local/")).is_err());
+        assert!(policy.check_request(&url("https://8.8.8.8/")).is_ok());
+    }
+
+    #[test]
+    fn test_resolved_addresses_checked() {
+        let policy = UrlPolicy::new(None, true);
+        let private: IpAddr = "10.0.0.1".parse().unwrap();
+        let public: IpAddr = "93.184.216.34".parse().unwrap();
+        assert!(policy.check_addrs("rebind.example", [public, private]).is_err());
+        assert!(policy.check_addrs("example.com", [public]).is_ok());
+        assert!(policy.check_addrs("nowhere.example", []).is_err());
+        assert!(UrlPolicy::default().check_addrs("rebind.example", [private]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_ip_literals_checked_without_resolving() {
+        let policy = UrlPolicy::new(None, true);
+        assert!(policy.check_url("http://127.0.0.1:8080/").await.is_err());
+        assert!(policy.check_url("https://8.8.8.8/").await.is_ok());
+        assert!(UrlPolicy::default().check_url("http://localhost/").await.is_ok());
+    }
+}
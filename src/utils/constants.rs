@@ -36,3 +36,17 @@ pub const SEARCH_BATCH_SIZE: usize = 1000;
 /// Limits how deep the crawler will follow links from the starting URL.
 /// Helps prevent unbounded crawling while capturing most relevant content.
 pub const DEFAULT_MAX_DEPTH: u8 = 3;
+
+/// User agent for plain HTTP fetches (feeds, robots.txt, sitemaps)
+///
+/// Used where a document is fetched without a browser, so servers can tell
+/// these requests apart from rendered page loads.
+pub const HTTP_USER_AGENT: &str = concat!("Mozilla/5.0 (compatible; kodegen-citescrape/", env!("CARGO_PKG_VERSION"), ")");
+
+/// Maximum serialized page HTML: 32 MiB
+///
+/// Caps the HTML read back from the browser and fed to the markdown
+/// converter. Generated pages can run to hundreds of megabytes, and the
+/// parsed DOM takes several times the HTML size, so anything past this is
+/// dropped (with a truncation marker) rather than risking the process.
+pub const MAX_PAGE_HTML_BYTES: usize = 32 * 1024 * 1024;
@@ -0,0 +1,226 @@
+//! Plain HTTP fetches for small documents
+//!
+//! Feeds, robots.txt and sitemaps are read with reqwest rather than the
+//! browser pool; these helpers send them with [`HTTP_USER_AGENT`].
+//!
+//! Every request through [`get`] holds one of [`MAX_CONNECTIONS_PER_HOST`]
+//! slots for its host and takes a token from the per-domain crawl rate
+//! limiter, so plain fetches share the politeness budget of crawls to the
+//! same site. Other users of [`shared_client`] take slots with [`host_slot`].
+
+use std::net::SocketAddr;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use dashmap::DashMap;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+use super::HTTP_USER_AGENT;
+use crate::crawl_engine::rate_limiter::{RateLimitDecision, check_http_rate_limit};
+use crate::url_policy::{UrlPolicy, url_policy};
+
+/// Redirects followed by [`guarded_client`] before giving up
+const MAX_REDIRECTS: usize = 10;
+
+/// Idle keep-alive connections kept open per host by [`shared_client`]
+pub const MAX_IDLE_CONNECTIONS_PER_HOST: usize = 8;
+
+/// Requests in flight per host through [`get`] and [`host_slot`]
+pub const MAX_CONNECTIONS_PER_HOST: usize = 6;
+
+/// Requests per second per domain for fetches through [`get`]
+pub const FETCH_RATE_RPS: f64 = 5.0;
+
+/// Hosts tracked by [`host_slot`] before idle entries are dropped
+const MAX_TRACKED_HOSTS: usize = 1024;
+
+/// Overall timeout for each request through [`shared_client`]
+const SHARED_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SHARED_CLIENT: LazyLock<Result<reqwest::Client, String>> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent(HTTP_USER_AGENT)
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(SHARED_REQUEST_TIMEOUT)
+        .pool_max_idle_per_host(MAX_IDLE_CONNECTIONS_PER_HOST)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .gzip(true)
+        .brotli(true)
+        .build()
+        .map_err(|e| e.to_string())
+});
+
+/// Process-wide client for plain HTTP fetches
+///
+/// Clones share one connection pool, so repeated requests to a host reuse
+/// its keep-alive connections (HTTP/2 where the server offers it), and
+/// gzip/brotli responses are decoded transparently. Requests time out after
+/// 30 seconds unless they set their own timeout.
+pub fn shared_client() -> Result<reqwest::Client> {
+    SHARED_CLIENT
+        .clone()
+        .map_err(|e| anyhow!("Failed to build HTTP client: {e}"))
+}
+
+/// Connection slots per `host:port`
+static HOST_SLOTS: LazyLock<DashMap<String, Arc<Semaphore>>> = LazyLock::new(DashMap::new);
+
+/// Wait for a request slot on `url`'s host, then for its rate limit
+///
+/// At most [`MAX_CONNECTIONS_PER_HOST`] slots per host are held at once;
+/// keep the permit until the response body is read. With `rate_rps`, also
+/// waits for a token from the per-domain rate limiter crawls use.
+pub async fn host_slot(url: &Url, rate_rps: Option<f64>) -> Result<OwnedSemaphorePermit> {
+    let host = format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    );
+    if HOST_SLOTS.len() >= MAX_TRACKED_HOSTS {
+        HOST_SLOTS.retain(|_, slots| Arc::strong_count(slots) > 1);
+    }
+    let slots = HOST_SLOTS
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(MAX_CONNECTIONS_PER_HOST)))
+        .clone();
+    let permit = slots.acquire_owned().await.context("Host connection slots closed")?;
+
+    if let Some(rate) = rate_rps {
+        while let RateLimitDecision::Deny { retry_after } = check_http_rate_limit(url.as_str(), rate).await {
+            tokio::time::sleep(retry_after).await;
+        }
+    }
+    Ok(permit)
+}
+
+/// The last client built by [`guarded_client`] and the policy it enforces
+static GUARDED_CLIENT: Mutex<Option<(Arc<UrlPolicy>, reqwest::Client)>> = Mutex::new(None);
+
+/// Client for URLs supplied by a caller, under the server's URL policy
+///
+/// Without restrictions this is [`shared_client`]. Otherwise host names are
+/// resolved through the policy, which rejects private addresses, and every
+/// redirect hop is checked before it is followed. Check the first URL with
+/// [`UrlPolicy::check_navigation`] too: IP literals are never resolved.
+pub fn guarded_client() -> Result<reqwest::Client> {
+    let policy = url_policy();
+    if policy.is_unrestricted() {
+        return shared_client();
+    }
+
+    let mut cached = GUARDED_CLIENT.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((cached_policy, client)) = cached.as_ref()
+        && Arc::ptr_eq(cached_policy, &policy)
+    {
+        return Ok(client.clone());
+    }
+
+    let redirect_policy = policy.clone();
+    let client = reqwest::Client::builder()
+        .user_agent(HTTP_USER_AGENT)
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(SHARED_REQUEST_TIMEOUT)
+        .pool_max_idle_per_host(MAX_IDLE_CONNECTIONS_PER_HOST)
+        .gzip(true)
+        .brotli(true)
+        .dns_resolver(Arc::new(PolicyResolver(policy.clone())))
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error(format!("Stopped after {MAX_REDIRECTS} redirects"))
+            } else if let Err(e) = redirect_policy.check_navigation(attempt.url()) {
+                attempt.error(e.to_string())
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .context("Failed to build HTTP client")?;
+    *cached = Some((policy, client.clone()));
+    Ok(client)
+}
+
+/// Resolves host names and rejects those the URL policy does not allow
+struct PolicyResolver(Arc<UrlPolicy>);
+
+impl reqwest::dns::Resolve for PolicyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let policy = self.0.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            policy.check_addrs(host, addrs.iter().map(SocketAddr::ip))?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// GET `url`, returning the response whatever its status
+///
+/// Fails without sending anything if the server's URL policy refuses `url`.
+/// The host slot is released once the headers arrive.
+pub async fn get(client: &reqwest::Client, url: &Url) -> Result<reqwest::Response> {
+    Ok(send(client, url).await?.0)
+}
+
+/// GET `url` holding a host slot, returned so the body can be read under it
+async fn send(client: &reqwest::Client, url: &Url) -> Result<(reqwest::Response, OwnedSemaphorePermit)> {
+    url_policy().check_url(url.as_str()).await?;
+    let slot = host_slot(url, Some(FETCH_RATE_RPS)).await?;
+    let response = client
+        .get(url.clone())
+        .header(reqwest::header::USER_AGENT, HTTP_USER_AGENT)
+        .send()
+        .await
+        .with_context(|| format!("Failed to request {url}"))?;
+    Ok((response, slot))
+}
+
+/// GET `url` and return its body, failing on non-success status
+pub async fn fetch_bytes(client: &reqwest::Client, url: &Url) -> Result<Vec<u8>> {
+    let (response, _slot) = send(client, url).await?;
+    let bytes = response
+        .error_for_status()
+        .with_context(|| format!("Request failed for {url}"))?
+        .bytes()
+        .await
+        .with_context(|| format!("Failed to read response from {url}"))?;
+    Ok(bytes.to_vec())
+}
+
+/// GET `url` and return its body as text, failing on non-success status
+pub async fn fetch_text(client: &reqwest::Client, url: &Url) -> Result<String> {
+    let (response, _slot) = send(client, url).await?;
+    response
+        .error_for_status()
+        .with_context(|| format!("Request failed for {url}"))?
+        .text()
+        .await
+        .with_context(|| format!("Failed to read response from {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_host_slot_caps_requests_per_host() {
+        let url = Url::parse("https://slots.example.test/feed.xml").unwrap();
+        let mut held = Vec::new();
+        for _ in 0..MAX_CONNECTIONS_PER_HOST {
+            held.push(host_slot(&url, None).await.unwrap());
+        }
+
+        let waiting = tokio::time::timeout(Duration::from_millis(50), host_slot(&url, None)).await;
+        assert!(waiting.is_err(), "slot granted beyond the per-host cap");
+
+        let other = Url::parse("https://other.example.test/").unwrap();
+        assert!(host_slot(&other, None).await.is_ok());
+
+        held.pop();
+        assert!(host_slot(&url, None).await.is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+//! Interning of normalized URLs
+//!
+//! A URL discovered during a crawl is held by the queue, the visited set,
+//! the retry queue and every page that links to it. `UrlInterner` hands out
+//! one shared `ImString` per distinct URL so those holders clone a reference
+//! count instead of allocating their own copy of the string.
+
+use dashmap::DashSet;
+use imstr::ImString;
+
+/// Set of interned URLs, shared by everything that tracks URLs during one crawl.
+#[derive(Debug, Default)]
+pub struct UrlInterner {
+    urls: DashSet<ImString>,
+}
+
+impl UrlInterner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the shared copy of `url`, interning it on first use.
+    pub fn intern(&self, url: &str) -> ImString {
+        if let Some(existing) = self.urls.get(url) {
+            return existing.clone();
+        }
+        let interned = ImString::from(url);
+        if !self.urls.insert(interned.clone())
+            && let Some(existing) = self.urls.get(url)
+        {
+            // Another task interned it first
+            return existing.clone();
+        }
+        interned
+    }
+
+    /// Number of distinct URLs interned.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+}
+
+/// Serde adapter storing an `ImString` field as a plain string.
+pub mod serde_imstring {
+    use imstr::ImString;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &ImString, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ImString, D::Error> {
+        String::deserialize(deserializer).map(ImString::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_one_allocation() {
+        let interner = UrlInterner::new();
+        let a = interner.intern("https://example.com/a");
+        let again = interner.intern(&String::from("https://example.com/a"));
+        let b = interner.intern("https://example.com/b");
+
+        assert_eq!(a, again);
+        assert!(std::sync::Arc::ptr_eq(&a.raw_string(), &again.raw_string()));
+        assert!(!std::sync::Arc::ptr_eq(&a.raw_string(), &b.raw_string()));
+        assert_eq!(interner.len(), 2);
+    }
+}
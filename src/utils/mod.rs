@@ -1,7 +1,13 @@
 pub mod constants;
+pub mod http_fetch;
+pub mod intern;
 pub mod string_utils;
 pub mod url_utils;
+pub mod xml_tree;
 
 pub use constants::*;
+pub use intern::UrlInterner;
 pub use string_utils::{safe_truncate_boundary, safe_truncate_chars};
-pub use url_utils::{ensure_domain_gitignore, get_mirror_path, get_uri_from_path, is_valid_url};
+pub use url_utils::{
+    ensure_domain_gitignore, get_mirror_path, get_uri_from_path, is_valid_url, mirror_relative_path,
+};
@@ -8,6 +8,10 @@ use std::path::{Path, PathBuf};
 use url::Url;
 
 /// Extract a URI from a path, stripping the prefix and handling parent directory
+///
+/// This only reverses the directory layout; mirror paths drop query strings
+/// and may be escaped or hashed. Use `LinkIndex::find_saved_page` for the
+/// URL that was actually saved at a path.
 pub async fn get_uri_from_path(path: &Path, output_dir: &Path) -> Result<String> {
     let result = path
         .strip_prefix(output_dir)
@@ -21,21 +25,115 @@ pub async fn get_uri_from_path(path: &Path, output_dir: &Path) -> Result<String>
     Ok(result)
 }
 
+/// Longest file or directory name derived from a URL, in bytes
+///
+/// Most filesystems cap names at 255 bytes; the margin leaves room for the
+/// extensions savers append (`.md.gz`, `.json`).
+pub const MAX_MIRROR_SEGMENT_BYTES: usize = 200;
+
+/// Longest mirror path below the output directory, in bytes
+///
+/// Keeps paths under the Windows 260-character legacy limit only for shallow
+/// output directories, but well under `PATH_MAX` everywhere.
+pub const MAX_MIRROR_RELATIVE_BYTES: usize = 1024;
+
+/// Names Windows reserves regardless of extension
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
 /// Get the mirror path for a URL, preserving the domain and path structure
+///
+/// Internationalized domains map to their punycode directory and paths to
+/// their canonical percent-encoding, so every spelling of a URL shares one path.
+/// See [`mirror_relative_path`] for how unsafe and overlong URLs are mapped.
 pub async fn get_mirror_path(url: &str, output_dir: &Path, filename: &str) -> Result<PathBuf> {
     let url = Url::parse(url).map_err(|e| anyhow::anyhow!("Failed to parse URL: {e}"))?;
-    let domain = url
+    Ok(output_dir.join(mirror_relative_path(&url)?).join(filename))
+}
+
+/// Directory for a URL below the output directory: `host/path/segments`
+///
+/// Each name is made safe on every platform: characters Windows rejects are
+/// percent-encoded, reserved device names (`CON`, `nul.txt`) get a `_`
+/// suffix and trailing dots or spaces are encoded. Names longer than
+/// [`MAX_MIRROR_SEGMENT_BYTES`] and paths longer than
+/// [`MAX_MIRROR_RELATIVE_BYTES`] are truncated and given a hash of the full
+/// value (`prefix~0123456789abcdef`), so the mapping stays deterministic and
+/// distinct URLs keep distinct paths. The link index records which URL was
+/// saved where, for looking the original URL up from a path.
+pub fn mirror_relative_path(url: &Url) -> Result<PathBuf> {
+    let host = url
         .host_str()
         .ok_or_else(|| anyhow::anyhow!("Invalid URL: no host"))?;
-    let path = if url.path() == "/" {
-        PathBuf::new()
-    } else {
-        PathBuf::from(url.path().trim_start_matches('/'))
-    };
+    let url_path = crate::imurl::canonical_path(url.path());
+
+    let mut segments = vec![safe_segment(host)];
+    segments.extend(
+        url_path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(safe_segment),
+    );
+
+    let total: usize = segments.iter().map(|s| s.len() + 1).sum();
+    if total > MAX_MIRROR_RELATIVE_BYTES {
+        // Keep leading directories that fit and fold the rest into one hashed name
+        let budget = MAX_MIRROR_RELATIVE_BYTES - HASH_SUFFIX_BYTES - 1;
+        let mut used = 0;
+        let keep = segments
+            .iter()
+            .take_while(|s| {
+                used += s.len() + 1;
+                used <= budget
+            })
+            .count();
+        segments.truncate(keep.max(1));
+        segments.push(format!("~{:016x}", xxhash_rust::xxh3::xxh3_64(url_path.as_bytes())));
+    }
+
+    Ok(segments.iter().collect())
+}
+
+/// Length of the `~` plus 16 hex digits appended to shortened names
+const HASH_SUFFIX_BYTES: usize = 17;
+
+/// One URL-derived name made safe for any filesystem
+pub(crate) fn safe_segment(segment: &str) -> String {
+    let mut name = String::with_capacity(segment.len());
+    for ch in segment.chars() {
+        if matches!(ch, '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\\') || ch.is_control() {
+            let mut buf = [0u8; 4];
+            for byte in ch.encode_utf8(&mut buf).bytes() {
+                name.push_str(&format!("%{byte:02X}"));
+            }
+        } else {
+            name.push(ch);
+        }
+    }
 
-    let mirror_path = output_dir.join(domain).join(path).join(filename);
+    // Windows drops trailing dots and spaces
+    if let Some(last) = name.chars().last().filter(|c| matches!(c, '.' | ' ')) {
+        name.pop();
+        name.push_str(if last == '.' { "%2E" } else { "%20" });
+    }
+
+    let stem = name.split('.').next().unwrap_or_default();
+    if WINDOWS_RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        name.insert(stem.len(), '_');
+    }
 
-    Ok(mirror_path)
+    if name.len() > MAX_MIRROR_SEGMENT_BYTES {
+        let hash = xxhash_rust::xxh3::xxh3_64(name.as_bytes());
+        let mut cut = MAX_MIRROR_SEGMENT_BYTES - HASH_SUFFIX_BYTES;
+        while !name.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        name.truncate(cut);
+        name.push_str(&format!("~{hash:016x}"));
+    }
+    name
 }
 
 /// Check if a URL is valid
@@ -94,8 +192,65 @@ pub async fn ensure_domain_gitignore(_mirror_path: &Path, output_dir: &Path) ->
     tokio::fs::write(&gitignore_path, gitignore_content)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to write .gitignore: {e}"))?;
+    crate::content_saver::sink::note_output_changed(&gitignore_path);
 
     log::debug!("Created .gitignore in {}", output_dir.display());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mirror_path_sanitizes_reserved_names() {
+        let out = Path::new("/out");
+        let path = get_mirror_path("https://example.com/CON/aux.html/a:b*/v1./", out, "index.md").await.unwrap();
+        assert_eq!(path, PathBuf::from("/out/example.com/CON_/aux_.html/a%3Ab%2A/v1%2E/index.md"));
+        let path = get_mirror_path("http://[::1]:8080/console", out, "index.md").await.unwrap();
+        assert_eq!(path, PathBuf::from("/out/[%3A%3A1]/console/index.md"));
+    }
+
+    #[tokio::test]
+    async fn test_mirror_path_shortens_overlong_urls() {
+        let out = Path::new("/out");
+        let long_name = "x".repeat(300);
+        let url = format!("https://example.com/{long_name}");
+        let path = get_mirror_path(&url, out, "index.md").await.unwrap();
+        let name = path.parent().unwrap().file_name().unwrap().to_str().unwrap();
+        assert_eq!(name.len(), MAX_MIRROR_SEGMENT_BYTES);
+        assert!(name.starts_with("xxx") && name.contains('~'));
+        // Deterministic, and distinct for a different tail
+        assert_eq!(get_mirror_path(&url, out, "index.md").await.unwrap(), path);
+        let other = get_mirror_path(&format!("{url}y"), out, "index.md").await.unwrap();
+        assert_ne!(other, path);
+
+        let deep = format!("https://example.com/{}", vec!["segment"; 300].join("/"));
+        let relative = mirror_relative_path(&Url::parse(&deep).unwrap()).unwrap();
+        assert!(relative.as_os_str().len() <= MAX_MIRROR_RELATIVE_BYTES);
+        assert!(relative.file_name().unwrap().to_str().unwrap().starts_with('~'));
+        assert_eq!(
+            crate::content_saver::cache_check::get_mirror_path_sync(&deep, out, "index.md").unwrap(),
+            out.join(&relative).join("index.md")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mirror_path_idn_spellings_agree() {
+        let out = Path::new("/out");
+        let expected = PathBuf::from("/out/xn--mnchen-3ya.de/stra%C3%9Fe/index.md");
+        for url in [
+            "https://MÜNCHEN.de/straße",
+            "https://xn--mnchen-3ya.de/stra%c3%9fe",
+            "https://münchen.de/stra%C3%9Fe",
+        ] {
+            assert_eq!(get_mirror_path(url, out, "index.md").await.unwrap(), expected, "{url}");
+            assert_eq!(
+                crate::content_saver::cache_check::get_mirror_path_sync(url, out, "index.md").unwrap(),
+                expected,
+                "{url}"
+            );
+        }
+    }
+}
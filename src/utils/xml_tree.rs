@@ -0,0 +1,114 @@
+//! Owned XML element tree
+//!
+//! Parses XML documents (feeds, sitemaps) with xml5ever into a small owned
+//! tree keyed by local names, so callers can walk it without namespace or
+//! `Rc` bookkeeping. Namespace prefixes are dropped: `dc:creator` becomes
+//! `creator`.
+
+use std::rc::Rc;
+
+use anyhow::{Result, bail};
+use markup5ever_rcdom::{Node, NodeData, RcDom};
+use xml5ever::driver::{XmlParseOpts, parse_document};
+use xml5ever::tendril::TendrilSink;
+
+/// An XML element with its attributes, child elements and direct text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XmlElement {
+    /// Local name (without namespace prefix)
+    pub name: String,
+    /// Attributes as (local name, value)
+    pub attrs: Vec<(String, String)>,
+    /// Child elements in document order
+    pub children: Vec<XmlElement>,
+    /// Concatenated text and CDATA directly inside this element
+    pub text: String,
+}
+
+impl XmlElement {
+    /// Parse `xml` and return its root element
+    pub fn parse(xml: &str) -> Result<Self> {
+        let dom = parse_document(RcDom::default(), XmlParseOpts::default()).one(xml);
+        let root = dom
+            .document
+            .children
+            .borrow()
+            .iter()
+            .find(|node| matches!(node.data, NodeData::Element { .. }))
+            .cloned();
+        match root {
+            Some(root) => Ok(Self::from_node(&root)),
+            None => bail!("Document has no root element"),
+        }
+    }
+
+    fn from_node(node: &Rc<Node>) -> Self {
+        let NodeData::Element { name, attrs, .. } = &node.data else {
+            return Self::default();
+        };
+        let mut element = Self {
+            name: name.local.to_string(),
+            attrs: attrs
+                .borrow()
+                .iter()
+                .map(|a| (a.name.local.to_string(), a.value.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        for child in node.children.borrow().iter() {
+            match &child.data {
+                NodeData::Element { .. } => element.children.push(Self::from_node(child)),
+                NodeData::Text { contents } => element.text.push_str(&contents.borrow()),
+                _ => {}
+            }
+        }
+        element
+    }
+
+    /// Value of attribute `name`
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    /// First child element named `name`
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// Child elements named `name`
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Trimmed text of the first child named `name`, if non-empty
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(|c| c.text.trim().to_string())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_namespaces_and_cdata() {
+        let xml = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+              <channel>
+                <title>Blog &amp; News</title>
+                <item><dc:creator>Ana</dc:creator><description><![CDATA[<p>Hi</p>]]></description></item>
+              </channel>
+            </rss>"#;
+        let root = XmlElement::parse(xml).unwrap();
+        assert_eq!(root.name, "rss");
+        assert_eq!(root.attr("version"), Some("2.0"));
+
+        let channel = root.child("channel").unwrap();
+        assert_eq!(channel.child_text("title").as_deref(), Some("Blog & News"));
+        let item = channel.children_named("item").next().unwrap();
+        assert_eq!(item.child_text("creator").as_deref(), Some("Ana"));
+        assert_eq!(item.child_text("description").as_deref(), Some("<p>Hi</p>"));
+    }
+}
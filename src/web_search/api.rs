@@ -0,0 +1,274 @@
+//! API-backed search engines
+//!
+//! SerpAPI, the Brave Search API and Kagi answer with JSON, so they need no
+//! browser and are immune to SERP markup changes. Each needs an API key in
+//! its environment variable (see [`SearchEngineKind::api_key_env`]).
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::engines::SearchEngineKind;
+use crate::utils::http_fetch::{host_slot, shared_client};
+use super::types::{
+    ImageResult, MAX_PAGES, NewsResult, SearchOptions, SearchResult, SearchVertical, VerticalResult,
+};
+
+/// Timeout for each API request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Search `engine`'s API, paging until `options.max_results` unique results
+pub async fn search(engine: SearchEngineKind, query: &str, options: &SearchOptions) -> Result<Vec<VerticalResult>> {
+    let key = engine.api_key().ok_or_else(|| {
+        anyhow!(
+            "{engine} not configured: set {}",
+            engine.api_key_env().unwrap_or("its API key")
+        )
+    })?;
+    info!("Querying {} API for '{}'", engine, query);
+    let client = shared_client()?;
+
+    let mut results: Vec<VerticalResult> = Vec::new();
+    let mut seen = HashSet::new();
+    let first_page = options.start_page.saturating_sub(1);
+    for page_index in first_page..first_page + MAX_PAGES {
+        let Some(mut url) = engine.page_url(query, options, page_index)? else {
+            if page_index == first_page {
+                bail!("{engine}: paging not supported, use page 1");
+            }
+            break;
+        };
+        match engine {
+            SearchEngineKind::SerpApi => {
+                url.query_pairs_mut().append_pair("api_key", &key);
+            }
+            SearchEngineKind::Kagi => {
+                url.query_pairs_mut().append_pair("limit", &options.max_results.to_string());
+            }
+            _ => {}
+        }
+        let _slot = host_slot(&url, None).await?;
+        let request = client
+            .get(url)
+            .header("Accept", "application/json")
+            .timeout(REQUEST_TIMEOUT);
+        let request = match engine {
+            SearchEngineKind::SerpApi => request,
+            SearchEngineKind::BraveApi => request.header("X-Subscription-Token", &key),
+            SearchEngineKind::Kagi => request.header("Authorization", format!("Bot {key}")),
+            _ => bail!("{engine} is not an API engine"),
+        };
+
+        // reqwest errors quote the URL, which carries SerpAPI's key
+        let response = request
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("{engine} request failed"))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            bail!("{engine} rejected the API key (HTTP {status})");
+        }
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            bail!("{engine} rate limit exceeded (HTTP {status})");
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("{engine} returned invalid JSON (HTTP {status})"))?;
+        if !status.is_success() {
+            bail!("{engine} request failed (HTTP {status}): {}", api_error(&body).unwrap_or_default());
+        }
+        if let Some(error) = api_error(&body) {
+            if results.is_empty() && error.to_lowercase().contains("hasn't returned any results") {
+                bail!("{engine} returned zero results for this query. Try a different search term.");
+            }
+            bail!("{engine} error: {error}");
+        }
+
+        let page = parse_results(engine, options.vertical, &body);
+        debug!("{} API page {} returned {} results", engine, page_index + 1, page.len());
+        let page_len = page.len();
+        for mut result in page {
+            if results.len() >= options.max_results {
+                break;
+            }
+            if seen.insert(result.url().to_string()) {
+                result.set_rank(results.len() + 1);
+                results.push(result);
+            }
+        }
+        if page_len == 0 || results.len() >= options.max_results {
+            break;
+        }
+    }
+
+    if results.is_empty() {
+        bail!("{engine} returned zero results for this query. Try a different search term.");
+    }
+    Ok(results)
+}
+
+/// Error message embedded in an API response body
+fn api_error(body: &Value) -> Option<String> {
+    match body.get("error")? {
+        Value::String(message) => Some(message.clone()),
+        // Kagi: [{"code": ..., "msg": ...}]
+        Value::Array(errors) => Some(
+            errors
+                .iter()
+                .filter_map(|e| e.get("msg").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Results of `vertical` in an API response, ranked from 1
+fn parse_results(engine: SearchEngineKind, vertical: SearchVertical, body: &Value) -> Vec<VerticalResult> {
+    use SearchVertical::{Images, News, Web};
+    let items = match (engine, vertical) {
+        (SearchEngineKind::SerpApi, Web) => body.get("organic_results"),
+        (SearchEngineKind::SerpApi, Images) => body.get("images_results"),
+        (SearchEngineKind::SerpApi, News) => body.get("news_results"),
+        (SearchEngineKind::BraveApi, Web) => body.pointer("/web/results"),
+        (SearchEngineKind::BraveApi, Images | News) => body.get("results"),
+        (SearchEngineKind::Kagi, _) => body.get("data"),
+        _ => None,
+    };
+    let text = |item: &Value, pointer: &str| {
+        item.pointer(pointer)
+            .and_then(Value::as_str)
+            .map(strip_tags)
+            .filter(|t| !t.trim().is_empty())
+    };
+    let number = |item: &Value, pointer: &str| {
+        item.pointer(pointer)
+            .and_then(Value::as_u64)
+            .and_then(|n| u32::try_from(n).ok())
+    };
+    let brave = engine == SearchEngineKind::BraveApi;
+
+    items
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        // Kagi mixes in related searches (t = 1)
+        .filter(|item| item.get("t").and_then(Value::as_u64).unwrap_or(0) == 0)
+        .filter_map(|item| {
+            let url = text(item, if brave { "/url" } else { "/link" }).or_else(|| text(item, "/url"))?;
+            if !url.starts_with("http") {
+                return None;
+            }
+            let rank = 0;
+            let title = text(item, "/title").unwrap_or_default();
+            let result = match vertical {
+                Web => VerticalResult::Web(SearchResult {
+                    rank,
+                    title,
+                    url,
+                    snippet: text(item, if brave { "/description" } else { "/snippet" }).unwrap_or_default(),
+                }),
+                Images if brave => VerticalResult::Image(ImageResult {
+                    rank,
+                    title,
+                    image_url: text(item, "/properties/url")?,
+                    url,
+                    thumbnail_url: text(item, "/thumbnail/src"),
+                    width: number(item, "/properties/width"),
+                    height: number(item, "/properties/height"),
+                    source: text(item, "/source"),
+                }),
+                Images => VerticalResult::Image(ImageResult {
+                    rank,
+                    title,
+                    image_url: text(item, "/original")?,
+                    url,
+                    thumbnail_url: text(item, "/thumbnail"),
+                    width: number(item, "/original_width"),
+                    height: number(item, "/original_height"),
+                    source: text(item, "/source"),
+                }),
+                News => VerticalResult::News(NewsResult {
+                    rank,
+                    title,
+                    url,
+                    snippet: text(item, if brave { "/description" } else { "/snippet" }).unwrap_or_default(),
+                    source: text(item, if brave { "/meta_url/hostname" } else { "/source" }),
+                    published: text(item, if brave { "/age" } else { "/date" }),
+                }),
+            };
+            Some(result)
+        })
+        .collect()
+}
+
+/// Text with HTML tags (e.g. `<strong>` highlights) and common entities removed
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_responses() {
+        let brave = serde_json::json!({"web": {"results": [
+            {"title": "Tokio", "url": "https://tokio.rs/", "description": "An <strong>async</strong> runtime"},
+        ]}});
+        let results = parse_results(SearchEngineKind::BraveApi, SearchVertical::Web, &brave);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url(), "https://tokio.rs/");
+        assert_eq!(results[0].snippet(), "An async runtime");
+
+        let kagi = serde_json::json!({"data": [
+            {"t": 0, "url": "https://docs.rs/tokio", "title": "tokio - Rust", "snippet": "docs"},
+            {"t": 1, "list": ["tokio vs async-std"]},
+        ]});
+        assert_eq!(parse_results(SearchEngineKind::Kagi, SearchVertical::Web, &kagi).len(), 1);
+
+        let images = serde_json::json!({"images_results": [{
+            "title": "Ferris", "link": "https://rustacean.net/", "original": "https://rustacean.net/ferris.png",
+            "thumbnail": "https://serpapi.com/thumb.png", "original_width": 1200, "original_height": 800,
+        }]});
+        let results = parse_results(SearchEngineKind::SerpApi, SearchVertical::Images, &images);
+        let VerticalResult::Image(image) = &results[0] else {
+            panic!("expected an image result");
+        };
+        assert_eq!((image.width, image.height), (Some(1200), Some(800)));
+        assert_eq!(image.image_url, "https://rustacean.net/ferris.png");
+
+        let news = serde_json::json!({"results": [{
+            "title": "Rust 1.80", "url": "https://blog.rust-lang.org/", "description": "Released",
+            "age": "2 days ago", "meta_url": {"hostname": "blog.rust-lang.org"},
+        }]});
+        let results = parse_results(SearchEngineKind::BraveApi, SearchVertical::News, &news);
+        let VerticalResult::News(article) = &results[0] else {
+            panic!("expected a news result");
+        };
+        assert_eq!(article.published.as_deref(), Some("2 days ago"));
+
+        let error = serde_json::json!({"error": [{"code": 1, "msg": "Unauthorized"}]});
+        assert_eq!(api_error(&error).as_deref(), Some("Unauthorized"));
+    }
+}
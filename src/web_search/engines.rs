@@ -0,0 +1,687 @@
+//! Search engine definitions
+//!
+//! Each engine describes how to build its results URL, which selectors find
+//! results on its SERP and how its result links map back to the target page.
+//! Keeping several engines available means a markup change on one SERP does
+//! not take web search down. API engines (SerpAPI, Brave Search API, Kagi)
+//! return JSON instead and are available once their key is configured.
+
+use anyhow::{Context, Result, bail};
+use base64::Engine as _;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::types::{
+    Freshness, SEARCH_RESULT_SELECTOR, SEARCH_URL, SNIPPET_SELECTOR, SearchLocale, SearchOptions,
+    SearchVertical, TITLE_LINK_SELECTOR,
+};
+
+/// Environment variable naming the server's default engine
+pub const DEFAULT_ENGINE_ENV: &str = "CITESCRAPE_SEARCH_ENGINE";
+
+/// Search engine used by `web_search`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngineKind {
+    /// DuckDuckGo's JavaScript SERP
+    #[default]
+    #[serde(alias = "ddg")]
+    DuckDuckGo,
+    /// DuckDuckGo's static HTML SERP (html.duckduckgo.com)
+    #[serde(rename = "duckduckgo_html", alias = "ddg_html")]
+    DuckDuckGoHtml,
+    /// Brave Search
+    Brave,
+    /// Microsoft Bing
+    Bing,
+    /// SerpAPI (Google results as JSON), key in `CITESCRAPE_SERPAPI_KEY`
+    #[serde(rename = "serpapi")]
+    SerpApi,
+    /// Brave Search API, key in `CITESCRAPE_BRAVE_API_KEY`
+    #[serde(rename = "brave_api")]
+    BraveApi,
+    /// Kagi Search API, key in `CITESCRAPE_KAGI_API_KEY`
+    Kagi,
+}
+
+impl SearchEngineKind {
+    /// All engines, in the default fallback order (API engines first)
+    pub const ALL: [Self; 7] = [
+        Self::SerpApi,
+        Self::BraveApi,
+        Self::Kagi,
+        Self::DuckDuckGo,
+        Self::DuckDuckGoHtml,
+        Self::Brave,
+        Self::Bing,
+    ];
+
+    /// `preferred` followed by every other usable engine, in fallback order
+    #[must_use]
+    pub fn fallback_chain(preferred: Self) -> Vec<Self> {
+        let mut chain = vec![preferred];
+        chain.extend(Self::ALL.into_iter().filter(|e| *e != preferred && e.is_available()));
+        chain
+    }
+
+    /// Whether results come from a JSON API rather than a scraped SERP
+    #[must_use]
+    pub fn is_api(self) -> bool {
+        self.api_key_env().is_some()
+    }
+
+    /// Environment variable holding the API key of an API engine
+    #[must_use]
+    pub fn api_key_env(self) -> Option<&'static str> {
+        match self {
+            Self::SerpApi => Some("CITESCRAPE_SERPAPI_KEY"),
+            Self::BraveApi => Some("CITESCRAPE_BRAVE_API_KEY"),
+            Self::Kagi => Some("CITESCRAPE_KAGI_API_KEY"),
+            _ => None,
+        }
+    }
+
+    /// Configured API key of an API engine
+    #[must_use]
+    pub fn api_key(self) -> Option<String> {
+        let env = self.api_key_env()?;
+        std::env::var(env).ok().filter(|key| !key.trim().is_empty())
+    }
+
+    /// Whether the engine can be used (API engines need a key)
+    #[must_use]
+    pub fn is_available(self) -> bool {
+        !self.is_api() || self.api_key().is_some()
+    }
+
+    /// Identifier used in arguments and configuration
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "duckduckgo",
+            Self::DuckDuckGoHtml => "duckduckgo_html",
+            Self::Brave => "brave",
+            Self::Bing => "bing",
+            Self::SerpApi => "serpapi",
+            Self::BraveApi => "brave_api",
+            Self::Kagi => "kagi",
+        }
+    }
+
+    /// Human-readable engine name
+    #[must_use]
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::DuckDuckGo => "DuckDuckGo",
+            Self::DuckDuckGoHtml => "DuckDuckGo HTML",
+            Self::Brave => "Brave",
+            Self::Bing => "Bing",
+            Self::SerpApi => "SerpAPI",
+            Self::BraveApi => "Brave Search API",
+            Self::Kagi => "Kagi",
+        }
+    }
+
+    /// Server default from `CITESCRAPE_SEARCH_ENGINE`, else DuckDuckGo
+    #[must_use]
+    pub fn from_env() -> Self {
+        match std::env::var(DEFAULT_ENGINE_ENV) {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                log::warn!("Ignoring {DEFAULT_ENGINE_ENV}={value}: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// SERP URL for `query`
+    pub fn search_url(self, query: &str, options: &SearchOptions) -> Result<Url> {
+        self.page_url(query, options, 0)?
+            .with_context(|| format!("{self} has no first results page"))
+    }
+
+    /// URL of the `page_index`-th (0-based) SERP page for `query`
+    ///
+    /// `None` when the engine only pages by loading more results in place
+    /// (see [`Self::more_results_selector`]) or cannot page at all. For API
+    /// engines this is the API endpoint, without the key.
+    ///
+    /// Fails when `options` ask for a vertical or filter the engine does not
+    /// support.
+    pub fn page_url(self, query: &str, options: &SearchOptions, page_index: usize) -> Result<Option<Url>> {
+        use SearchVertical::{Images, News, Web};
+        let vertical = options.vertical;
+        let (base, extra): (&str, &[(&str, &str)]) = match (self, vertical) {
+            (Self::DuckDuckGo, Web) => (SEARCH_URL, &[("ia", "web")]),
+            (Self::DuckDuckGoHtml, Web) => ("https://html.duckduckgo.com/html/", &[]),
+            (Self::Brave, Web) => ("https://search.brave.com/search", &[("source", "web")]),
+            (Self::Bing, Web) => ("https://www.bing.com/search", &[]),
+            (Self::Bing, Images) => ("https://www.bing.com/images/search", &[]),
+            (Self::Bing, News) => ("https://www.bing.com/news/search", &[]),
+            (Self::SerpApi, Web) => ("https://serpapi.com/search.json", &[("engine", "google"), ("num", "10")]),
+            (Self::SerpApi, Images) => ("https://serpapi.com/search.json", &[("engine", "google_images")]),
+            (Self::SerpApi, News) => (
+                "https://serpapi.com/search.json",
+                &[("engine", "google"), ("tbm", "nws"), ("num", "10")],
+            ),
+            (Self::BraveApi, Web) => ("https://api.search.brave.com/res/v1/web/search", &[("count", "20")]),
+            (Self::BraveApi, Images) => ("https://api.search.brave.com/res/v1/images/search", &[("count", "100")]),
+            (Self::BraveApi, News) => ("https://api.search.brave.com/res/v1/news/search", &[("count", "20")]),
+            (Self::Kagi, Web) => ("https://kagi.com/api/v0/search", &[]),
+            (engine, vertical) => bail!("{engine}: {vertical} search not supported"),
+        };
+        let offset = match (self, vertical, page_index) {
+            (_, _, 0) => None,
+            (Self::DuckDuckGo | Self::Kagi, _, _) | (Self::Bing, Images | News, _) | (Self::BraveApi, Images, _) => {
+                return Ok(None);
+            }
+            (Self::DuckDuckGoHtml, _, n) => Some(("s", (n * 30).to_string())),
+            (Self::Brave | Self::BraveApi, _, n) => Some(("offset", n.to_string())),
+            (Self::Bing, _, n) => Some(("first", (n * 10 + 1).to_string())),
+            (Self::SerpApi, Images, n) => Some(("ijn", n.to_string())),
+            (Self::SerpApi, _, n) => Some(("start", (n * 10).to_string())),
+        };
+        let freshness = match options.freshness {
+            Some(freshness) => Some(
+                self.freshness_param(vertical, freshness)
+                    .with_context(|| format!("{self}: freshness filter not supported for {vertical} search"))?,
+            ),
+            None => None,
+        };
+        let locale = match &options.locale {
+            Some(locale) => {
+                let params = self.locale_params(locale);
+                if params.is_empty() {
+                    bail!("{self}: region and language not supported");
+                }
+                params
+            }
+            None => Vec::new(),
+        };
+        let mut url = Url::parse(base).with_context(|| format!("Failed to parse {} base URL", self.display_name()))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            pairs.append_pair("q", query);
+            for (key, value) in extra {
+                pairs.append_pair(key, value);
+            }
+            if let Some((key, value)) = &offset {
+                pairs.append_pair(key, value);
+            }
+            if let Some((key, value)) = &freshness {
+                pairs.append_pair(key, value);
+            }
+            for (key, value) in &locale {
+                pairs.append_pair(key, value);
+            }
+        }
+        Ok(Some(url))
+    }
+
+    /// Query parameters selecting the result region and interface language
+    fn locale_params(self, locale: &SearchLocale) -> Vec<(&'static str, String)> {
+        let region = locale.region.as_deref();
+        let language = locale.language.as_deref();
+        match self {
+            // kl=<country>-<language>, e.g. de-de, ca-fr; regionless means worldwide
+            Self::DuckDuckGo | Self::DuckDuckGoHtml => match (region, language) {
+                (Some(region), language) => {
+                    vec![("kl", format!("{region}-{}", language.unwrap_or(region)))]
+                }
+                (None, Some(_)) => vec![("kl", "wt-wt".to_string())],
+                (None, None) => Vec::new(),
+            },
+            Self::Brave | Self::BraveApi => region
+                .map(|r| ("country", r.to_string()))
+                .into_iter()
+                .chain(language.map(|l| ("search_lang", l.to_string())))
+                .collect(),
+            Self::Bing => region
+                .map(|r| ("cc", r.to_ascii_uppercase()))
+                .into_iter()
+                .chain(language.map(|l| ("setlang", l.to_string())))
+                .collect(),
+            Self::SerpApi => region
+                .map(|r| ("gl", r.to_string()))
+                .into_iter()
+                .chain(language.map(|l| ("hl", l.to_string())))
+                .collect(),
+            Self::Kagi => Vec::new(),
+        }
+    }
+
+    /// Query parameter restricting `vertical` results to `freshness`
+    /// (`None` if unsupported)
+    fn freshness_param(self, vertical: SearchVertical, freshness: Freshness) -> Option<(&'static str, String)> {
+        let param = match (self, vertical) {
+            // Bing News: qft=interval="7" (24 hours), "8" (week), "9" (month)
+            (Self::Bing, SearchVertical::News) => {
+                let interval = match freshness {
+                    Freshness::Day => 7,
+                    Freshness::Week => 8,
+                    Freshness::Month => 9,
+                    Freshness::Year | Freshness::Range { .. } => return None,
+                };
+                ("qft", format!("interval=\"{interval}\""))
+            }
+            // Bing Images: qft=+filterui:age-lt<minutes>
+            (Self::Bing, SearchVertical::Images) => {
+                let days = match freshness {
+                    Freshness::Day => 1,
+                    Freshness::Week => 7,
+                    Freshness::Month => 30,
+                    Freshness::Year => 365,
+                    Freshness::Range { .. } => return None,
+                };
+                ("qft", format!("+filterui:age-lt{}", days * 24 * 60))
+            }
+            (Self::BraveApi, SearchVertical::Images) | (Self::Kagi, _) => return None,
+            // df=d|w|m|y or df=2024-01-01..2024-02-01
+            (Self::DuckDuckGo | Self::DuckDuckGoHtml, _) => {
+                let value = match freshness {
+                    Freshness::Day => "d".to_string(),
+                    Freshness::Week => "w".to_string(),
+                    Freshness::Month => "m".to_string(),
+                    Freshness::Year => "y".to_string(),
+                    Freshness::Range { from, to } => format!("{from}..{to}"),
+                };
+                ("df", value)
+            }
+            // tf=pd|pw|pm|py or tf=2024-01-01to2024-02-01 (freshness= on the API)
+            (Self::Brave | Self::BraveApi, _) => {
+                let value = match freshness {
+                    Freshness::Day => "pd".to_string(),
+                    Freshness::Week => "pw".to_string(),
+                    Freshness::Month => "pm".to_string(),
+                    Freshness::Year => "py".to_string(),
+                    Freshness::Range { from, to } => format!("{from}to{to}"),
+                };
+                (if self == Self::Brave { "tf" } else { "freshness" }, value)
+            }
+            // filters=ex1:"ez1|ez2|ez3" or a custom range in days since the epoch
+            (Self::Bing, _) => {
+                let days = |date: chrono::NaiveDate| {
+                    date.signed_duration_since(chrono::NaiveDate::default()).num_days()
+                };
+                let today = chrono::Utc::now().date_naive();
+                let value = match freshness {
+                    Freshness::Day => "ez1".to_string(),
+                    Freshness::Week => "ez2".to_string(),
+                    Freshness::Month => "ez3".to_string(),
+                    Freshness::Year => {
+                        format!("ez5_{}_{}", days(today - chrono::Duration::days(365)), days(today))
+                    }
+                    Freshness::Range { from, to } => format!("ez5_{}_{}", days(from), days(to)),
+                };
+                ("filters", format!("ex1:\"{value}\""))
+            }
+            // Google's tbs=qdr:d|w|m|y or a custom date range
+            (Self::SerpApi, _) => {
+                let value = match freshness {
+                    Freshness::Day => "qdr:d".to_string(),
+                    Freshness::Week => "qdr:w".to_string(),
+                    Freshness::Month => "qdr:m".to_string(),
+                    Freshness::Year => "qdr:y".to_string(),
+                    Freshness::Range { from, to } => format!(
+                        "cdr:1,cd_min:{},cd_max:{}",
+                        from.format("%-m/%-d/%Y"),
+                        to.format("%-m/%-d/%Y")
+                    ),
+                };
+                ("tbs", value)
+            }
+        };
+        Some(param)
+    }
+
+    /// Button that appends the next page of results in place, for engines
+    /// without page URLs
+    #[must_use]
+    pub fn more_results_selector(self) -> Option<&'static str> {
+        match self {
+            Self::DuckDuckGo => Some("#more-results, button[id='more-results']"),
+            _ => None,
+        }
+    }
+
+    /// Selectors locating results on the SERP (the primary strategy)
+    #[must_use]
+    pub fn selectors(self) -> EngineSelectors {
+        self.selector_strategies()
+            .first()
+            .map_or(NO_SELECTORS, |strategy| strategy.selectors)
+    }
+
+    /// Selector strategies to try in order until one finds results
+    ///
+    /// The first is the current markup; later ones cover older or A/B-tested
+    /// layouts and finally any heading link on the page. When all fail,
+    /// extraction falls back to JSON-LD structured data.
+    #[must_use]
+    pub fn selector_strategies(self) -> &'static [SelectorStrategy] {
+        const GENERIC: SelectorStrategy = SelectorStrategy {
+            name: "generic-headings",
+            selectors: EngineSelectors {
+                result: "h2:has(a[href^='http']), h3:has(a[href^='http'])",
+                link: "a[href^='http']",
+                title: "a[href^='http']",
+                snippet: "p",
+            },
+        };
+        match self {
+            Self::DuckDuckGo => &[
+                SelectorStrategy {
+                    name: "primary",
+                    selectors: EngineSelectors {
+                        result: SEARCH_RESULT_SELECTOR,
+                        link: TITLE_LINK_SELECTOR,
+                        title: TITLE_LINK_SELECTOR,
+                        snippet: SNIPPET_SELECTOR,
+                    },
+                },
+                SelectorStrategy {
+                    name: "organic-layout",
+                    selectors: EngineSelectors {
+                        result: "li[data-layout='organic']",
+                        link: "h2 a, a[data-testid='result-title-a']",
+                        title: "h2",
+                        snippet: "[data-result='snippet'], div > span",
+                    },
+                },
+                SelectorStrategy {
+                    name: "legacy",
+                    selectors: EngineSelectors {
+                        result: "div.result.results_links_deep",
+                        link: "a.result__a",
+                        title: "a.result__a",
+                        snippet: ".result__snippet",
+                    },
+                },
+                GENERIC,
+            ],
+            Self::DuckDuckGoHtml => &[
+                SelectorStrategy {
+                    name: "primary",
+                    selectors: EngineSelectors {
+                        result: "div.result:not(.result--ad)",
+                        link: "a.result__a",
+                        title: "a.result__a",
+                        snippet: ".result__snippet",
+                    },
+                },
+                SelectorStrategy {
+                    name: "web-result",
+                    selectors: EngineSelectors {
+                        result: "div.web-result",
+                        link: "h2 a",
+                        title: "h2",
+                        snippet: ".result__snippet, .result__body",
+                    },
+                },
+                GENERIC,
+            ],
+            Self::Brave => &[
+                SelectorStrategy {
+                    name: "primary",
+                    selectors: EngineSelectors {
+                        result: "#results .snippet[data-type='web']",
+                        link: "a[href^='http']",
+                        title: ".title, .snippet-title",
+                        snippet: ".snippet-description, .generic-snippet .content",
+                    },
+                },
+                SelectorStrategy {
+                    name: "positioned",
+                    selectors: EngineSelectors {
+                        result: "#results .snippet[data-pos]",
+                        link: "a[href^='http']",
+                        title: ".title, .heading-serpresult, a",
+                        snippet: ".snippet-content, .description, p",
+                    },
+                },
+                GENERIC,
+            ],
+            Self::Bing => &[
+                SelectorStrategy {
+                    name: "primary",
+                    selectors: EngineSelectors {
+                        result: "#b_results > li.b_algo",
+                        link: "h2 > a",
+                        title: "h2 > a",
+                        snippet: ".b_caption p, p.b_lineclamp2, p.b_lineclamp3",
+                    },
+                },
+                SelectorStrategy {
+                    name: "algo",
+                    selectors: EngineSelectors {
+                        result: "li.b_algo",
+                        link: "h2 a, a.tilk",
+                        title: "h2",
+                        snippet: ".b_caption, .b_algoSlug, p",
+                    },
+                },
+                GENERIC,
+            ],
+            // JSON responses, see `api.rs`
+            Self::SerpApi | Self::BraveApi | Self::Kagi => &[],
+        }
+    }
+
+    /// Whether `url` points back at the engine itself (navigation, ads,
+    /// related searches) rather than at a result
+    #[must_use]
+    pub fn is_own_url(self, url: &str) -> bool {
+        let domain = match self {
+            Self::DuckDuckGo | Self::DuckDuckGoHtml => "duckduckgo.com",
+            Self::Brave | Self::BraveApi => "brave.com",
+            Self::Bing => "bing.com",
+            Self::SerpApi => "serpapi.com",
+            Self::Kagi => "kagi.com",
+        };
+        Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+            .is_some_and(|host| host == domain || host.ends_with(&format!(".{domain}")))
+    }
+
+    /// Selector whose presence means `vertical` results have rendered
+    ///
+    /// For web results any non-generic strategy's result selector counts.
+    #[must_use]
+    pub fn result_selector(self, vertical: SearchVertical) -> String {
+        match (self, vertical) {
+            (Self::Bing, SearchVertical::Images) => "a.iusc".to_string(),
+            (Self::Bing, SearchVertical::News) => "div.news-card".to_string(),
+            _ => self
+                .selector_strategies()
+                .iter()
+                .filter(|strategy| strategy.name != "generic-headings")
+                .map(|strategy| strategy.selectors.result)
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    /// Whether the SERP is rendered client-side (results appear after load)
+    #[must_use]
+    pub fn renders_with_javascript(self) -> bool {
+        matches!(self, Self::DuckDuckGo)
+    }
+
+    /// Target URL of a result link, unwrapping the engine's click redirect
+    #[must_use]
+    pub fn resolve_result_url(self, href: &str) -> String {
+        let absolute = if href.starts_with("//") { format!("https:{href}") } else { href.to_string() };
+        let Ok(url) = Url::parse(&absolute) else {
+            return href.to_string();
+        };
+        let param = |name: &str| url.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+        match self {
+            // //duckduckgo.com/l/?uddg=<encoded target>&rut=...
+            Self::DuckDuckGo | Self::DuckDuckGoHtml if url.path() == "/l/" => param("uddg").unwrap_or(absolute),
+            // https://www.bing.com/ck/a?...&u=a1<base64url target>
+            Self::Bing if url.path() == "/ck/a" => param("u")
+                .and_then(|u| {
+                    let encoded = u.strip_prefix("a1")?;
+                    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+                        .decode(encoded.trim_end_matches('='))
+                        .ok()?;
+                    String::from_utf8(bytes).ok()
+                })
+                .unwrap_or(absolute),
+            _ => absolute,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchEngineKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+impl std::str::FromStr for SearchEngineKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let normalized: String = s
+            .trim()
+            .to_ascii_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        match normalized.as_str() {
+            "duckduckgo" | "ddg" => Ok(Self::DuckDuckGo),
+            "duckduckgohtml" | "ddghtml" => Ok(Self::DuckDuckGoHtml),
+            "brave" => Ok(Self::Brave),
+            "bing" => Ok(Self::Bing),
+            "serpapi" => Ok(Self::SerpApi),
+            "braveapi" => Ok(Self::BraveApi),
+            "kagi" => Ok(Self::Kagi),
+            _ => anyhow::bail!(
+                "unknown search engine '{s}' (expected duckduckgo, duckduckgo_html, brave, bing, \
+                 serpapi, brave_api or kagi)"
+            ),
+        }
+    }
+}
+
+/// CSS selectors for one engine's SERP
+///
+/// `link`, `title` and `snippet` are looked up inside each `result` element;
+/// comma-separated alternatives are allowed.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineSelectors {
+    pub result: &'static str,
+    pub link: &'static str,
+    pub title: &'static str,
+    pub snippet: &'static str,
+}
+
+/// Selectors of engines without a SERP
+const NO_SELECTORS: EngineSelectors = EngineSelectors {
+    result: "",
+    link: "",
+    title: "",
+    snippet: "",
+};
+
+/// A named set of selectors tried during extraction
+#[derive(Debug, Clone, Copy)]
+pub struct SelectorStrategy {
+    /// Reported when this strategy is not the primary one
+    pub name: &'static str,
+    pub selectors: EngineSelectors,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freshness_params() {
+        let range: Freshness = "2024-01-01..2024-01-31".parse().unwrap();
+        assert!("2024-02-01..2024-01-01".parse::<Freshness>().is_err());
+        assert!("fortnight".parse::<Freshness>().is_err());
+        let options = |freshness| SearchOptions {
+            freshness: Some(freshness),
+            ..Default::default()
+        };
+
+        let url = SearchEngineKind::DuckDuckGoHtml.search_url("rust", &options(range)).unwrap();
+        assert_eq!(url.query(), Some("q=rust&df=2024-01-01..2024-01-31"));
+        let url = SearchEngineKind::Brave.search_url("rust", &options(Freshness::Week)).unwrap();
+        assert_eq!(url.query(), Some("q=rust&source=web&tf=pw"));
+        // 2024-01-01 is day 19723 since the Unix epoch
+        let url = SearchEngineKind::Bing.search_url("rust", &options(range)).unwrap();
+        let filters = url.query_pairs().find(|(k, _)| k == "filters").unwrap().1;
+        assert_eq!(filters, "ex1:\"ez5_19723_19753\"");
+    }
+
+    #[test]
+    fn test_locale_params() {
+        let options = SearchOptions {
+            locale: Some(SearchLocale::new(Some("DE"), Some("de")).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(options.locale.as_ref().unwrap().language_tag().as_deref(), Some("de-DE"));
+        assert!(SearchLocale::new(Some("germany"), None).is_err());
+
+        let url = SearchEngineKind::DuckDuckGoHtml.search_url("rust", &options).unwrap();
+        assert_eq!(url.query(), Some("q=rust&kl=de-de"));
+        let url = SearchEngineKind::Bing.search_url("rust", &options).unwrap();
+        assert_eq!(url.query(), Some("q=rust&cc=DE&setlang=de"));
+    }
+
+    #[test]
+    fn test_selector_strategies() {
+        for engine in SearchEngineKind::ALL {
+            let strategies = engine.selector_strategies();
+            if engine.is_api() {
+                assert!(strategies.is_empty());
+                continue;
+            }
+            assert_eq!(strategies[0].name, "primary");
+            assert_eq!(engine.selectors().result, strategies[0].selectors.result);
+            assert_eq!(strategies.last().unwrap().name, "generic-headings");
+            // Readiness waits on every known layout but not on bare headings
+            let ready = engine.result_selector(SearchVertical::Web);
+            assert!(ready.starts_with(strategies[0].selectors.result));
+            assert!(!ready.contains("h2:has"));
+        }
+
+        assert!(SearchEngineKind::Bing.is_own_url("https://www.bing.com/search?q=rust"));
+        assert!(SearchEngineKind::DuckDuckGo.is_own_url("https://duckduckgo.com/?q=rust"));
+        assert!(!SearchEngineKind::Bing.is_own_url("https://notbing.com/"));
+        assert!(!SearchEngineKind::Brave.is_own_url("https://tokio.rs/"));
+    }
+
+    #[test]
+    fn test_engine_urls_and_redirects() {
+        assert_eq!("DuckDuckGo-HTML".parse::<SearchEngineKind>().unwrap(), SearchEngineKind::DuckDuckGoHtml);
+        assert_eq!("bing".parse::<SearchEngineKind>().unwrap(), SearchEngineKind::Bing);
+        assert!("altavista".parse::<SearchEngineKind>().is_err());
+        let engine: SearchEngineKind = serde_json::from_str("\"ddg_html\"").unwrap();
+        assert_eq!(engine, SearchEngineKind::DuckDuckGoHtml);
+
+        let options = SearchOptions::default();
+        let url = SearchEngineKind::Brave.search_url("rust & tokio", &options).unwrap();
+        assert_eq!(url.as_str(), "https://search.brave.com/search?q=rust+%26+tokio&source=web");
+        let page = SearchEngineKind::Bing.page_url("rust", &options, 2).unwrap().unwrap();
+        assert_eq!(page.as_str(), "https://www.bing.com/search?q=rust&first=21");
+        assert!(SearchEngineKind::DuckDuckGo.page_url("rust", &options, 1).unwrap().is_none());
+
+        assert_eq!(
+            SearchEngineKind::DuckDuckGoHtml
+                .resolve_result_url("//duckduckgo.com/l/?uddg=https%3A%2F%2Ftokio.rs%2F&rut=abc"),
+            "https://tokio.rs/"
+        );
+        // "https://tokio.rs/" base64url-encoded behind Bing's "a1" prefix
+        assert_eq!(
+            SearchEngineKind::Bing.resolve_result_url("https://www.bing.com/ck/a?!&&p=x&u=a1aHR0cHM6Ly90b2tpby5ycy8&ntb=1"),
+            "https://tokio.rs/"
+        );
+        assert_eq!(SearchEngineKind::Bing.resolve_result_url("https://tokio.rs/"), "https://tokio.rs/");
+    }
+}
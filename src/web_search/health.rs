@@ -0,0 +1,197 @@
+//! Per-engine health tracking for web search fallback
+//!
+//! Counts successes, failures and CAPTCHA hits per engine. An engine that
+//! shows a CAPTCHA, or fails several times in a row, is demoted for a
+//! cooldown period: the fallback chain tries it only after healthy engines.
+//! Searches answered by a fallback selector strategy are counted too, as an
+//! early sign that the engine's markup changed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::engines::SearchEngineKind;
+
+/// Default time an unhealthy engine stays demoted
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Consecutive failures that demote an engine
+const FAILURES_BEFORE_DEMOTION: u32 = 3;
+
+#[derive(Debug, Default)]
+struct EngineStats {
+    successes: u64,
+    failures: u64,
+    captchas: u64,
+    consecutive_failures: u32,
+    demoted_until: Option<Instant>,
+    last_error: Option<String>,
+    selector_fallbacks: u64,
+    last_fallback_strategy: Option<String>,
+}
+
+/// Health of one engine as reported to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EngineHealthSnapshot {
+    pub engine: SearchEngineKind,
+    pub successes: u64,
+    pub failures: u64,
+    pub captchas: u64,
+    /// Share of searches that failed, 0.0 to 1.0
+    pub failure_rate: f64,
+    /// Seconds left in the engine's cooldown (0 when healthy)
+    pub demoted_for_secs: u64,
+    pub last_error: Option<String>,
+    /// Searches whose results came from a fallback selector strategy
+    pub selector_fallbacks: u64,
+    pub last_fallback_strategy: Option<String>,
+}
+
+/// Shared per-engine health state
+#[derive(Debug)]
+pub struct EngineHealth {
+    cooldown: Duration,
+    stats: Mutex<HashMap<SearchEngineKind, EngineStats>>,
+}
+
+impl EngineHealth {
+    #[must_use]
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_stats<T>(&self, f: impl FnOnce(&mut HashMap<SearchEngineKind, EngineStats>) -> T) -> T {
+        let mut stats = self.stats.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        f(&mut stats)
+    }
+
+    /// Record a search that returned results (ends any cooldown)
+    pub fn record_success(&self, engine: SearchEngineKind) {
+        self.with_stats(|stats| {
+            let entry = stats.entry(engine).or_default();
+            entry.successes += 1;
+            entry.consecutive_failures = 0;
+            entry.demoted_until = None;
+        });
+    }
+
+    /// Record a failed search; CAPTCHAs demote the engine immediately
+    pub fn record_failure(&self, engine: SearchEngineKind, error: &str, captcha: bool) {
+        let cooldown = self.cooldown;
+        self.with_stats(|stats| {
+            let entry = stats.entry(engine).or_default();
+            entry.failures += 1;
+            entry.consecutive_failures += 1;
+            entry.last_error = Some(error.to_string());
+            if captcha {
+                entry.captchas += 1;
+            }
+            if captcha || entry.consecutive_failures >= FAILURES_BEFORE_DEMOTION {
+                log::warn!("Demoting search engine {engine} for {}s: {error}", cooldown.as_secs());
+                entry.demoted_until = Some(Instant::now() + cooldown);
+            }
+        });
+    }
+
+    /// Record a search answered by fallback selector `strategy`
+    pub fn record_selector_fallback(&self, engine: SearchEngineKind, strategy: &str) {
+        self.with_stats(|stats| {
+            let entry = stats.entry(engine).or_default();
+            entry.selector_fallbacks += 1;
+            entry.last_fallback_strategy = Some(strategy.to_string());
+        });
+    }
+
+    /// Whether `engine` is in its cooldown period
+    #[must_use]
+    pub fn is_demoted(&self, engine: SearchEngineKind) -> bool {
+        self.with_stats(|stats| {
+            stats
+                .get(&engine)
+                .and_then(|s| s.demoted_until)
+                .is_some_and(|until| until > Instant::now())
+        })
+    }
+
+    /// `chain` reordered so healthy engines come first (order kept otherwise)
+    #[must_use]
+    pub fn order(&self, chain: &[SearchEngineKind]) -> Vec<SearchEngineKind> {
+        let (healthy, demoted): (Vec<_>, Vec<_>) = chain.iter().partition(|e| !self.is_demoted(**e));
+        healthy.into_iter().chain(demoted).collect()
+    }
+
+    /// Health of every engine that has been used
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<EngineHealthSnapshot> {
+        let now = Instant::now();
+        let mut snapshot: Vec<EngineHealthSnapshot> = self.with_stats(|stats| {
+            stats
+                .iter()
+                .map(|(engine, s)| {
+                    let total = s.successes + s.failures;
+                    EngineHealthSnapshot {
+                        engine: *engine,
+                        successes: s.successes,
+                        failures: s.failures,
+                        captchas: s.captchas,
+                        failure_rate: if total == 0 { 0.0 } else { s.failures as f64 / total as f64 },
+                        demoted_for_secs: s
+                            .demoted_until
+                            .map_or(0, |until| until.saturating_duration_since(now).as_secs()),
+                        last_error: s.last_error.clone(),
+                        selector_fallbacks: s.selector_fallbacks,
+                        last_fallback_strategy: s.last_fallback_strategy.clone(),
+                    }
+                })
+                .collect()
+        });
+        snapshot.sort_by_key(|s| s.engine.as_str());
+        snapshot
+    }
+}
+
+impl Default for EngineHealth {
+    fn default() -> Self {
+        Self::new(DEFAULT_COOLDOWN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SearchEngineKind::{Bing, Brave, DuckDuckGo};
+
+    #[test]
+    fn test_demotion_and_recovery() {
+        let health = EngineHealth::default();
+        let chain = [DuckDuckGo, Brave, Bing];
+
+        health.record_failure(DuckDuckGo, "DuckDuckGo CAPTCHA detected", true);
+        assert_eq!(health.order(&chain), [Brave, Bing, DuckDuckGo]);
+
+        for _ in 0..FAILURES_BEFORE_DEMOTION - 1 {
+            health.record_failure(Brave, "timeout", false);
+        }
+        assert!(!health.is_demoted(Brave));
+        health.record_failure(Brave, "timeout", false);
+        assert_eq!(health.order(&chain), [Bing, DuckDuckGo, Brave]);
+
+        health.record_success(DuckDuckGo);
+        assert_eq!(health.order(&chain), [DuckDuckGo, Bing, Brave]);
+
+        let snapshot = health.snapshot();
+        let ddg = snapshot.iter().find(|s| s.engine == DuckDuckGo).unwrap();
+        assert_eq!((ddg.captchas, ddg.failure_rate, ddg.demoted_for_secs), (1, 0.5, 0));
+
+        health.record_selector_fallback(Bing, "json-ld");
+        let snapshot = health.snapshot();
+        let bing = snapshot.iter().find(|s| s.engine == Bing).unwrap();
+        assert_eq!(bing.selector_fallbacks, 1);
+        assert_eq!(bing.last_fallback_strategy.as_deref(), Some("json-ld"));
+    }
+}
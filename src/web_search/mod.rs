@@ -1,23 +1,39 @@
 //! Web search functionality using browser automation
 //!
-//! Performs DuckDuckGo searches using pre-warmed browsers from the pool.
-//! Returns structured results with titles, URLs, and snippets.
+//! Performs searches on DuckDuckGo, DuckDuckGo HTML, Brave or Bing using
+//! pre-warmed browsers from the pool, or through the SerpAPI, Brave Search
+//! and Kagi APIs when their keys are configured. Returns structured results
+//! with titles, URLs, and snippets.
 
+mod api;
+mod engines;
+mod health;
+mod pacing;
 mod search;
+mod serp_cache;
 mod types;
 mod page_helpers;
 
 // Re-export public types
-pub use types::{MAX_QUERY_LENGTH, MAX_RESULTS, MAX_RETRIES, SearchResult, SearchResults};
+pub use engines::{DEFAULT_ENGINE_ENV, EngineSelectors, SearchEngineKind};
+pub use health::{DEFAULT_COOLDOWN, EngineHealth, EngineHealthSnapshot};
+pub use pacing::{EnginePacer, JITTER_ENV, MAX_CONCURRENT_ENV, MIN_INTERVAL_ENV, PacingConfig, PacingPermit};
+pub use serp_cache::{DEFAULT_SERP_CACHE_TTL, SERP_CACHE_TTL_ENV, SerpCache};
+pub use types::{
+    Freshness, ImageResult, MAX_PAGES, MAX_QUERY_LENGTH, MAX_RESULTS, MAX_RETRIES, MAX_TOTAL_RESULTS,
+    NewsResult, QueryOperators, SearchLocale, SearchOptions, SearchResult, SearchResults, SearchVertical,
+    VerticalResult,
+};
 
 use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-/// Perform web search using BrowserPool
+/// Perform web search on the default engine (DuckDuckGo) using BrowserPool
 ///
-/// Acquires a pre-warmed browser from the pool, performs the search,
-/// and automatically returns the browser to the pool when done.
+/// See [`search_with_engine`].
 ///
 /// # Arguments
 /// * `pool` - Shared browser pool reference
@@ -49,6 +65,27 @@ use tracing::info;
 pub async fn search_with_pool(
     pool: &Arc<crate::browser_pool::BrowserPool>,
     query: impl Into<String>,
+) -> Result<SearchResults> {
+    search_with_engine(pool, query, SearchEngineKind::default(), &SearchOptions::default()).await
+}
+
+/// Perform web search on `engine` using BrowserPool
+///
+/// Acquires a pre-warmed browser from the pool, performs the search,
+/// and automatically returns the browser to the pool when done.
+///
+/// # Arguments
+/// * `pool` - Shared browser pool reference
+/// * `query` - Search query string
+/// * `engine` - Search engine whose results page is scraped (API engines are
+///   queried directly, without a browser)
+/// * `options` - Result count and start page; later SERP pages are loaded
+///   until enough unique results are collected
+pub async fn search_with_engine(
+    pool: &Arc<crate::browser_pool::BrowserPool>,
+    query: impl Into<String>,
+    engine: SearchEngineKind,
+    options: &SearchOptions,
 ) -> Result<SearchResults> {
     let query = query.into();
     
@@ -77,7 +114,13 @@ pub async fn search_with_pool(
     // Convert to owned String for use in closure
     let query = trimmed_query.to_string();
     
-    info!("Starting web search for query: '{}' ({} chars)", query, query.len());
+    info!("Starting {} web search for query: '{}' ({} chars)", engine, query, query.len());
+
+    if engine.is_api() {
+        let results = api::search(engine, &query, options).await?;
+        info!("Search completed successfully with {} results", results.len());
+        return Ok(SearchResults::new(query, engine, results));
+    }
 
     // Acquire pre-warmed browser from pool
     let guard = pool.acquire().await
@@ -90,7 +133,7 @@ pub async fn search_with_pool(
 
     // Perform search with retry logic - fresh page per attempt
     // Each retry creates a new page to ensure clean stealth injection state
-    let results = search::retry_with_backoff(
+    let (results, fallback_strategy) = search::retry_with_backoff(
         || {
             let query = query.clone();
             async move {
@@ -103,8 +146,16 @@ pub async fn search_with_pool(
                 );
                 
                 // Execute search operations
-                search::perform_search(&page_guard, &query).await?;
-                search::extract_results(&page_guard).await
+                search::perform_search(&page_guard, &query, engine, options).await?;
+                if options.vertical == SearchVertical::Web {
+                    let (results, fallback_strategy) =
+                        search::collect_pages(&page_guard, &query, engine, options).await?;
+                    Ok((results.into_iter().map(VerticalResult::Web).collect(), fallback_strategy))
+                } else {
+                    search::extract_vertical_results(&page_guard, engine, options.vertical, options.max_results)
+                        .await
+                        .map(|results| (results, None))
+                }
                 // PageGuard dropped here - spawns async page.close()
             }
         },
@@ -116,6 +167,80 @@ pub async fn search_with_pool(
 
     // page_guard dropped here on success path
     // Drop::drop spawns async page.close() - guaranteed cleanup
-    Ok(SearchResults::new(query, results))
+    let mut results = SearchResults::new(query, engine, results);
+    results.fallback_strategy = fallback_strategy.map(str::to_string);
+    Ok(results)
     // Browser automatically returns to pool when guard drops
 }
+
+/// A failed engine attempt in a fallback search
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EngineAttempt {
+    pub engine: SearchEngineKind,
+    pub error: String,
+}
+
+/// Search `chain` in priority order until an engine returns results
+///
+/// Engines in their cooldown (see [`EngineHealth`]) are tried after healthy
+/// ones. Every attempt is recorded in `health`; a query with no results, an
+/// unsupported filter or a missing API key does not count against the engine
+/// but still moves on to the next one. Returns
+/// the results with the attempts that failed before them, or the last error.
+///
+/// With a `cache`, an engine's unexpired results for the same query and
+/// options are returned instead of searching it again, and fresh results are
+/// stored. Pass `None` to bypass the cache.
+///
+/// Searches wait for their turn on each engine in `pacer` (cache hits do not).
+pub async fn search_with_fallback(
+    pool: &Arc<crate::browser_pool::BrowserPool>,
+    query: impl Into<String>,
+    chain: &[SearchEngineKind],
+    options: &SearchOptions,
+    health: &EngineHealth,
+    cache: Option<&SerpCache>,
+    pacer: &EnginePacer,
+) -> Result<(SearchResults, Vec<EngineAttempt>)> {
+    let query = query.into();
+    let mut attempts = Vec::new();
+    let mut last_error = None;
+
+    for engine in health.order(chain) {
+        if let Some(hit) = cache.and_then(|c| c.get(engine, &query, options)) {
+            info!("Serving {} search for '{}' from cache", engine, query);
+            return Ok((hit, attempts));
+        }
+        let permit = pacer.acquire(engine).await;
+        let outcome = search_with_engine(pool, query.clone(), engine, options).await;
+        drop(permit);
+        match outcome {
+            Ok(results) => {
+                health.record_success(engine);
+                if let Some(strategy) = &results.fallback_strategy {
+                    health.record_selector_fallback(engine, strategy);
+                }
+                if let Some(cache) = cache {
+                    cache.insert(engine, &query, options, &results);
+                }
+                return Ok((results, attempts));
+            }
+            Err(e) => {
+                let message = format!("{e:#}");
+                let lower = message.to_lowercase();
+                if lower.contains("zero results") {
+                    health.record_success(engine);
+                } else if lower.contains("not supported") || lower.contains("not configured") {
+                    // Says nothing about the engine's health
+                } else {
+                    health.record_failure(engine, &message, lower.contains("captcha"));
+                }
+                warn!("{} search failed, trying next engine: {}", engine, message);
+                attempts.push(EngineAttempt { engine, error: message });
+                last_error = Some(e);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No search engines configured")))
+}
@@ -0,0 +1,223 @@
+//! Per-engine request pacing for web search
+//!
+//! Bursts of agent queries against one engine get the server's IP a CAPTCHA
+//! within minutes. Each engine therefore gets a minimum interval between
+//! searches plus random jitter, and a cap on searches in flight at once.
+//! Waiting searches queue up instead of failing.
+
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+use super::engines::SearchEngineKind;
+
+/// Environment variable with the minimum milliseconds between searches on one engine
+pub const MIN_INTERVAL_ENV: &str = "CITESCRAPE_SEARCH_MIN_INTERVAL_MS";
+
+/// Environment variable with the most random milliseconds added to the interval
+pub const JITTER_ENV: &str = "CITESCRAPE_SEARCH_JITTER_MS";
+
+/// Environment variable with the most concurrent searches on one engine
+pub const MAX_CONCURRENT_ENV: &str = "CITESCRAPE_SEARCH_MAX_CONCURRENT";
+
+/// Pacing settings, shared by all engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacingConfig {
+    /// Minimum time between the starts of two searches on one engine
+    pub min_interval: Duration,
+    /// Upper bound of the random delay added to `min_interval`
+    pub jitter: Duration,
+    /// Most searches in flight on one engine (at least 1)
+    pub max_concurrent: usize,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(1500),
+            jitter: Duration::from_millis(1000),
+            max_concurrent: 2,
+        }
+    }
+}
+
+impl PacingConfig {
+    /// Defaults overridden by `CITESCRAPE_SEARCH_MIN_INTERVAL_MS`,
+    /// `CITESCRAPE_SEARCH_JITTER_MS` and `CITESCRAPE_SEARCH_MAX_CONCURRENT`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let defaults = Self::default();
+        Self {
+            min_interval: var(MIN_INTERVAL_ENV).map_or(defaults.min_interval, Duration::from_millis),
+            jitter: var(JITTER_ENV).map_or(defaults.jitter, Duration::from_millis),
+            max_concurrent: var(MAX_CONCURRENT_ENV)
+                .and_then(|n| usize::try_from(n).ok())
+                .map_or(defaults.max_concurrent, |n| n.max(1)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EngineSlots {
+    /// Earliest start of the next search
+    next_start: Option<Instant>,
+    in_flight: Arc<Semaphore>,
+}
+
+/// Held while a search runs; releases the engine's concurrency slot on drop
+#[derive(Debug)]
+pub struct PacingPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Shared per-engine pacing state
+#[derive(Debug)]
+pub struct EnginePacer {
+    config: RwLock<PacingConfig>,
+    engines: Mutex<HashMap<SearchEngineKind, EngineSlots>>,
+}
+
+impl EnginePacer {
+    #[must_use]
+    pub fn new(config: PacingConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            engines: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Pacer with the settings from the environment (see [`PacingConfig::from_env`])
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::new(PacingConfig::from_env())
+    }
+
+    #[must_use]
+    pub fn config(&self) -> PacingConfig {
+        *self.config.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replace the pacing settings; searches already in flight keep their slots
+    pub fn reconfigure(&self, config: PacingConfig) {
+        let previous = std::mem::replace(
+            &mut *self.config.write().unwrap_or_else(PoisonError::into_inner),
+            config,
+        );
+        if previous.max_concurrent != config.max_concurrent {
+            let mut engines = self.engines.lock().unwrap_or_else(PoisonError::into_inner);
+            for slots in engines.values_mut() {
+                slots.in_flight = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+            }
+        }
+    }
+
+    fn with_slots<T>(&self, engine: SearchEngineKind, f: impl FnOnce(&mut EngineSlots) -> T) -> T {
+        let max_concurrent = self.config().max_concurrent.max(1);
+        let mut engines = self.engines.lock().unwrap_or_else(PoisonError::into_inner);
+        let slots = engines.entry(engine).or_insert_with(|| EngineSlots {
+            next_start: None,
+            in_flight: Arc::new(Semaphore::new(max_concurrent)),
+        });
+        f(slots)
+    }
+
+    /// Claim the next start time on `engine` no earlier than `now`
+    fn reserve(&self, engine: SearchEngineKind, now: Instant) -> Instant {
+        let config = self.config();
+        let jitter = if config.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            let max = u64::try_from(config.jitter.as_millis()).unwrap_or(u64::MAX);
+            Duration::from_millis(rand::rng().random_range(0..=max))
+        };
+        let gap = config.min_interval + jitter;
+        self.with_slots(engine, |slots| {
+            let start = slots.next_start.map_or(now, |next| next.max(now));
+            slots.next_start = Some(start + gap);
+            start
+        })
+    }
+
+    /// Wait for a concurrency slot and the engine's next start time
+    ///
+    /// Hold the returned permit for the duration of the search.
+    pub async fn acquire(&self, engine: SearchEngineKind) -> PacingPermit {
+        let in_flight = self.with_slots(engine, |slots| Arc::clone(&slots.in_flight));
+        let permit = in_flight
+            .acquire_owned()
+            .await
+            .expect("pacing semaphore is never closed");
+
+        let start = self.reserve(engine, Instant::now());
+        let wait = start.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            debug!("Pacing {} search: waiting {}ms", engine, wait.as_millis());
+            tokio::time::sleep(wait).await;
+        }
+        PacingPermit { _permit: permit }
+    }
+}
+
+impl Default for EnginePacer {
+    fn default() -> Self {
+        Self::new(PacingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SearchEngineKind::{Bing, DuckDuckGo};
+
+    #[test]
+    fn test_reserve_spaces_searches() {
+        let pacer = EnginePacer::new(PacingConfig {
+            min_interval: Duration::from_millis(1000),
+            jitter: Duration::from_millis(500),
+            max_concurrent: 1,
+        });
+        let now = Instant::now();
+
+        let first = pacer.reserve(DuckDuckGo, now);
+        let second = pacer.reserve(DuckDuckGo, now);
+        let third = pacer.reserve(DuckDuckGo, now);
+        assert_eq!(first, now);
+        let gap = second - first;
+        assert!(gap >= Duration::from_millis(1000) && gap <= Duration::from_millis(1500));
+        assert!(third - second >= Duration::from_millis(1000));
+
+        // Engines are paced independently
+        assert_eq!(pacer.reserve(Bing, now), now);
+
+        // An idle engine starts right away
+        let later = third + Duration::from_secs(10);
+        assert_eq!(pacer.reserve(DuckDuckGo, later), later);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_cap() {
+        let pacer = EnginePacer::new(PacingConfig {
+            min_interval: Duration::ZERO,
+            jitter: Duration::ZERO,
+            max_concurrent: 1,
+        });
+        let held = pacer.acquire(Bing).await;
+        let blocked = tokio::time::timeout(Duration::from_millis(50), pacer.acquire(Bing)).await;
+        assert!(blocked.is_err());
+        drop(held);
+        let held = pacer.acquire(Bing).await;
+
+        // Raising the cap admits a second search at once
+        pacer.reconfigure(PacingConfig {
+            max_concurrent: 2,
+            ..pacer.config()
+        });
+        let second = tokio::time::timeout(Duration::from_millis(50), pacer.acquire(Bing)).await;
+        assert!(second.is_ok());
+        drop(held);
+    }
+}
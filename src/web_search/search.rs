@@ -8,37 +8,59 @@ use chromiumoxide::page::Page;
 use once_cell::sync::Lazy;
 use rand::Rng;
 use regex::Regex;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
-use url::Url;
 
+use super::engines::{EngineSelectors, SearchEngineKind};
 use super::types::{
-    MAX_RESULTS, POLL_INTERVAL_MS, SEARCH_RESULT_SELECTOR, SEARCH_URL, SNIPPET_SELECTOR,
-    SearchResult, TITLE_LINK_SELECTOR,
+    ImageResult, MAX_PAGES, NewsResult, POLL_INTERVAL_MS, SearchOptions, SearchResult, SearchVertical, VerticalResult,
 };
 use super::page_helpers::get_page_url_with_fallback;
 
+/// Whether a SERP URL is a CAPTCHA / bot challenge page
+fn is_challenge_url(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.contains("/sorry/") || url.contains("captcha") || url.contains("/challenge")
+}
+
 /// Perform a search with kromekover stealth injection
 ///
-/// Applies kromekover stealth features to the page before navigating to `DuckDuckGo`,
-/// then navigates directly to the search results URL. `DuckDuckGo` uses React-based
-/// rendering, so we wait for results to appear after navigation.
+/// Applies kromekover stealth features to the page before navigating to the
+/// engine, then navigates directly to its search results URL. Client-side
+/// rendered SERPs (`DuckDuckGo`) are polled until results appear after
+/// navigation.
 ///
 /// # Arguments
 /// * `page` - Blank page instance to enhance and use for search
 /// * `query` - Search query string
+/// * `engine` - Engine whose SERP to load
+/// * `options` - Filters applied through the engine's query parameters; the
+///   locale's language also sets the browser's `Accept-Language`
 ///
 /// # Based on
 /// - packages/citescrape/src/crawl_engine/core.rs:231-259 (stealth pattern)
 /// - `DuckDuckGo` DOM analysis from `tasks/DUCK_DUCK_GO_DOM_PARSING.md`
-pub async fn perform_search(page: &Page, query: &str) -> Result<()> {
+pub async fn perform_search(
+    page: &Page,
+    query: &str,
+    engine: SearchEngineKind,
+    options: &SearchOptions,
+) -> Result<()> {
     // Apply kromekover stealth injection BEFORE navigation - FAIL on error
-    // Stealth is CRITICAL for web search (DuckDuckGo will CAPTCHA without it)
+    // Stealth is CRITICAL for web search (engines will CAPTCHA without it)
+    // The browser's Accept-Language follows the requested search language
     info!("Applying kromekover stealth injection");
     
+    let stealth_config = options
+        .locale
+        .as_ref()
+        .and_then(|locale| locale.language_tag())
+        .map(|tag| crate::kromekover::Config::for_language(&tag))
+        .unwrap_or_default();
     tokio::time::timeout(
         Duration::from_secs(5),
-        crate::kromekover::inject(page),
+        crate::kromekover::inject_with_config(page, &stealth_config),
     )
     .await
     .context("Stealth injection timeout after 5s")?
@@ -60,33 +82,45 @@ pub async fn perform_search(page: &Page, query: &str) -> Result<()> {
     .await
     .context("Failed to set viewport dimensions")?;
 
-    // Navigate directly to DuckDuckGo search results with proper URL encoding
-    let mut search_url = Url::parse(SEARCH_URL).context("Failed to parse DuckDuckGo base URL")?;
-    search_url
-        .query_pairs_mut()
-        .append_pair("q", query)
-        .append_pair("ia", "web");
+    // Navigate directly to the engine's search results with proper URL encoding
+    let search_url = engine.search_url(query, options)?;
+    load_results_page(page, &search_url, engine, options.vertical).await
+}
 
-    info!("Navigating to DuckDuckGo search: {}", search_url);
+/// Navigate to a SERP URL and wait until its results are present
+///
+/// Used for the first page by [`perform_search`] and for later pages of
+/// engines with page URLs.
+pub async fn load_results_page(
+    page: &Page,
+    search_url: &url::Url,
+    engine: SearchEngineKind,
+    vertical: SearchVertical,
+) -> Result<()> {
+    info!("Navigating to {} search: {}", engine, search_url);
     page.goto(search_url.as_str())
         .await
-        .context("Failed to navigate to DuckDuckGo")?;
+        .with_context(|| format!("Failed to navigate to {engine}"))?;
 
-    // DuckDuckGo uses React for rendering - wait for initial load
     page.wait_for_navigation()
         .await
         .context("Failed to wait for initial page load")?;
 
     // Smart wait: Poll for results instead of fixed 3s delay
     // This is faster when results load quickly, but waits up to 5s if needed
+    let result_selector = engine.result_selector(vertical);
     let poll_start = Instant::now();
-    let max_wait = Duration::from_secs(5);
+    let max_wait = if engine.renders_with_javascript() {
+        Duration::from_secs(5)
+    } else {
+        Duration::from_secs(2)
+    };
     let poll_interval = Duration::from_millis(POLL_INTERVAL_MS);
 
-    info!("Waiting for React to render search results...");
+    info!("Waiting for {} to render search results...", engine);
     loop {
         // Check if results are present
-        if page.find_element(SEARCH_RESULT_SELECTOR).await.is_ok() {
+        if page.find_element(&result_selector).await.is_ok() {
             let elapsed = poll_start.elapsed();
             debug!(
                 "Search results appeared after {:.2}s",
@@ -99,16 +133,29 @@ pub async fn perform_search(page: &Page, query: &str) -> Result<()> {
         if poll_start.elapsed() >= max_wait {
             // Check if we got a CAPTCHA or error page
             let url = get_page_url_with_fallback(page).await;
-            if url.contains("/sorry/") || url.contains("captcha") {
+            if is_challenge_url(&url) {
                 return Err(anyhow!(
-                    "DuckDuckGo presented a CAPTCHA page. Try again later or use a different network."
+                    "{engine} presented a CAPTCHA page. Try again later, use a different engine or a different network."
                 ));
             }
 
+            // Static SERPs are complete once loaded; extraction reports "no results"
+            if !engine.renders_with_javascript() {
+                break;
+            }
+
+            // Unknown markup: let the fallback strategies have a go
+            if vertical == SearchVertical::Web
+                && page.find_element(FALLBACK_READY_SELECTOR).await.is_ok()
+            {
+                warn!("{} result selectors did not appear; trying fallback extraction", engine);
+                break;
+            }
+
             return Err(anyhow!(
-                "Timeout waiting for DuckDuckGo results to render. \
-                 React took longer than {}s to load results. \
-                 This may indicate network issues or DuckDuckGo changes.",
+                "Timeout waiting for {engine} results to render. \
+                 Results took longer than {}s to load. \
+                 This may indicate network issues or {engine} changes.",
                 max_wait.as_secs()
             ));
         }
@@ -120,114 +167,185 @@ pub async fn perform_search(page: &Page, query: &str) -> Result<()> {
     Ok(())
 }
 
-/// Extract search results from the page
+/// Content the fallback strategies can extract from when no known result
+/// selector appears
+const FALLBACK_READY_SELECTOR: &str =
+    "script[type='application/ld+json'], h2 > a[href^='http'], h3 > a[href^='http']";
+
+/// Strategy name reported when results came from JSON-LD structured data
+pub const JSON_LD_STRATEGY: &str = "json-ld";
+
+/// Extract search results from the page, with the strategy that found them
 ///
-/// Extracts title, URL, and snippet for each result up to `MAX_RESULTS`.
-/// Uses fail-fast approach: URLs must exist (critical), titles use fallback text,
-/// snippets gracefully default to "No description available".
+/// Each strategy is tried in order until one yields results, so a markup
+/// change on the engine degrades to an older layout, generic heading links or
+/// JSON-LD `ItemList` data instead of zero results. Using anything but the
+/// primary strategy is logged as a warning. When every strategy comes up
+/// empty the page is checked for a CAPTCHA or "no results" message.
 ///
 /// # Arguments
 /// * `page` - Page containing search results
+/// * `engine` - Engine that produced the page
+/// * `limit` - Most results to extract
 ///
 /// # Returns
-/// Vector of `SearchResult` structs
+/// The results and the name of the strategy that produced them
+pub async fn extract_results(
+    page: &Page,
+    engine: SearchEngineKind,
+    limit: usize,
+) -> Result<(Vec<SearchResult>, &'static str)> {
+    for (index, strategy) in engine.selector_strategies().iter().enumerate() {
+        match extract_with_selectors(page, engine, &strategy.selectors, limit).await {
+            Ok(results) if !results.is_empty() => {
+                if index > 0 {
+                    warn!(
+                        "{} primary selectors found nothing; extracted {} results with fallback strategy '{}'",
+                        engine,
+                        results.len(),
+                        strategy.name
+                    );
+                } else {
+                    info!("Found {} search results", results.len());
+                }
+                return Ok((results, strategy.name));
+            }
+            Ok(_) => debug!("{} selector strategy '{}' found no results", engine, strategy.name),
+            Err(e) => debug!("{} selector strategy '{}' failed: {}", engine, strategy.name, e),
+        }
+    }
+
+    match extract_json_ld(page, engine, limit).await {
+        Ok(results) if !results.is_empty() => {
+            warn!(
+                "{} selectors found nothing; extracted {} results from JSON-LD",
+                engine,
+                results.len()
+            );
+            return Ok((results, JSON_LD_STRATEGY));
+        }
+        Ok(_) => {}
+        Err(e) => debug!("{} JSON-LD extraction failed: {}", engine, e),
+    }
+
+    // No strategy matched (likely CAPTCHA, error page, or SERP DOM change)
+    let url = get_page_url_with_fallback(page).await;
+
+    // Check for known error conditions
+    if is_challenge_url(&url) {
+        return Err(anyhow!(
+            "{engine} CAPTCHA detected. No search results available. \
+             Try again later, use a different engine or a different network connection."
+        ));
+    }
+
+    // Check if this might be a "no results" page
+    if let Ok(body) = page.find_element("body").await
+        && let Ok(Some(text)) = body.inner_text().await
+    {
+        let text = text.to_lowercase();
+        if text.contains("no results") || text.contains("there are no results") {
+            return Err(anyhow!(
+                "{engine} returned zero results for this query. \
+                 Try a different search term."
+            ));
+        }
+        if text.contains("captcha") || text.contains("verify you are human") {
+            return Err(anyhow!(
+                "{engine} CAPTCHA detected. No search results available. \
+                 Try again later, use a different engine or a different network connection."
+            ));
+        }
+    }
+
+    Err(anyhow!(
+        "No search results found on {engine}. This may indicate:\n\
+         • {engine} DOM structure changed (selector '{}' not found, no fallback matched)\n\
+         • Network/connection issues\n\
+         • {engine} is temporarily unavailable\n\
+         Current URL: {url}",
+        engine.selectors().result
+    ))
+}
+
+/// Extract results with one set of selectors
 ///
-/// # Based on
-/// - packages/citescrape/src/google_search.rs:208-263
+/// Result elements without a link are skipped (the strategy does not fit
+/// them); titles fall back to the link text and snippets to "No description
+/// available". Links back to the engine itself are dropped and the rest are
+/// unwrapped from its click-tracking redirects.
 ///
 /// # Note
 /// Extraction logic is inlined because `chromiumoxide::Element` doesn't implement
 /// Clone, making it difficult to reuse elements across multiple extraction calls.
-pub async fn extract_results(page: &Page) -> Result<Vec<SearchResult>> {
+async fn extract_with_selectors(
+    page: &Page,
+    engine: SearchEngineKind,
+    selectors: &EngineSelectors,
+    limit: usize,
+) -> Result<Vec<SearchResult>> {
     let search_results = page
-        .find_elements(SEARCH_RESULT_SELECTOR)
+        .find_elements(selectors.result)
         .await
         .context("Failed to find search results")?;
 
-    info!("Found {} search results", search_results.len());
-
-    // Fail fast if no results (likely CAPTCHA, error page, or DuckDuckGo DOM change)
-    if search_results.is_empty() {
-        // Get current URL for diagnostics
-        let url = get_page_url_with_fallback(page).await;
+    let mut results = Vec::new();
+    let mut seen = HashSet::new();
 
-        // Check for known error conditions
-        if url.contains("/sorry/") || url.contains("captcha") {
-            return Err(anyhow!(
-                "DuckDuckGo CAPTCHA detected. No search results available. \
-                 Try again later or use a different network connection."
-            ));
+    for (index, result) in search_results.into_iter().enumerate() {
+        if results.len() >= limit {
+            break;
         }
 
-        // Check if this might be a "no results" page from DDG
-        if let Ok(body) = page.find_element("body").await
-            && let Ok(Some(text)) = body.inner_text().await
-            && text.to_lowercase().contains("no results")
-        {
-            return Err(anyhow!(
-                "DuckDuckGo returned zero results for this query. \
-                 Try a different search term."
-            ));
+        // Find link element (contains the href, and the title where they share an element)
+        let Ok(link) = result.find_element(selectors.link).await else {
+            debug!(
+                "{} result {}: no link matching '{}', skipping",
+                engine,
+                index + 1,
+                selectors.link
+            );
+            continue;
+        };
+        let Some(href) = link.attribute("href").await.ok().flatten() else {
+            debug!("{} result {}: link has no href, skipping", engine, index + 1);
+            continue;
+        };
+        let url = engine.resolve_result_url(&href);
+        if !url.starts_with("http") || engine.is_own_url(&url) || !seen.insert(url.clone()) {
+            continue;
         }
 
-        return Err(anyhow!(
-            "No search results found on DuckDuckGo. This may indicate:\n\
-             • DuckDuckGo DOM structure changed (selector '{SEARCH_RESULT_SELECTOR}' not found)\n\
-             • Network/connection issues\n\
-             • DuckDuckGo is temporarily unavailable\n\
-             Current URL: {url}"
-        ));
-    }
-
-    let mut results = Vec::new();
-
-    for (index, result) in search_results.into_iter().enumerate().take(MAX_RESULTS) {
-        // Find title/link element ONCE (contains both title text and href)
-        let title_link = result
-            .find_element(TITLE_LINK_SELECTOR)
-            .await
-            .with_context(|| {
-                format!(
-                    "DuckDuckGo result {}: Title/link element not found with selector '{}'. \
-                     DOM structure may have changed.",
-                    index + 1,
-                    TITLE_LINK_SELECTOR
-                )
-            })?;
-
-        // Extract title from the element
-        let title = title_link
-            .inner_text()
-            .await
-            .with_context(|| format!("Failed to get title text for result {}", index + 1))?
-            .unwrap_or_else(|| format!("Untitled Result {}", index + 1));
-
-        // Extract URL from the SAME element (no additional DOM query)
-        let url = title_link
-            .attribute("href")
-            .await
-            .with_context(|| format!("Failed to get href attribute for result {}", index + 1))?
-            .ok_or_else(|| {
-                anyhow!(
-                    "DuckDuckGo result {}: Link href attribute is empty. \
-                     This shouldn't happen - may indicate a DuckDuckGo UI change.",
-                    index + 1
-                )
-            })?;
-
-        // Extract snippet - separate element, unchanged
-        let snippet = match result.find_element(SNIPPET_SELECTOR).await {
+        // Extract title (same element as the link on most engines)
+        let title_text = if selectors.title == selectors.link {
+            link.inner_text().await.ok().flatten()
+        } else {
+            match result.find_element(selectors.title).await {
+                Ok(el) => el.inner_text().await.ok().flatten(),
+                Err(_) => link.inner_text().await.ok().flatten(),
+            }
+        };
+        let rank = results.len() + 1;
+        let title = title_text
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| format!("Untitled Result {rank}"));
+
+        // Extract snippet - separate element
+        let snippet = match result.find_element(selectors.snippet).await {
             Ok(el) => el
                 .inner_text()
                 .await
                 .ok()
                 .flatten()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
                 .unwrap_or_else(|| "No description available".to_string()),
             Err(_) => "No description available".to_string(),
         };
 
         results.push(SearchResult {
-            rank: index + 1,
+            rank,
             title,
             url,
             snippet,
@@ -237,6 +355,307 @@ pub async fn extract_results(page: &Page) -> Result<Vec<SearchResult>> {
     Ok(results)
 }
 
+/// Reads `ItemList` / `SearchResultsPage` entries from the page's JSON-LD
+/// blocks as `[{url, title, snippet}]`
+const JSON_LD_SCRIPT: &str = r#"
+(() => {
+  const found = [];
+  const visit = (node) => {
+    if (!node || typeof node !== 'object') return;
+    if (Array.isArray(node)) { node.forEach(visit); return; }
+    const item = node.item && typeof node.item === 'object' ? node.item : node;
+    const url = item.url || (typeof node.item === 'string' ? node.item : null);
+    if (node['@type'] === 'ListItem' && typeof url === 'string') {
+      found.push({ url, title: item.name || item.headline || '', snippet: item.description || '' });
+    }
+    for (const key of ['@graph', 'itemListElement', 'mainEntity']) {
+      if (node[key]) visit(node[key]);
+    }
+  };
+  for (const script of document.querySelectorAll('script[type="application/ld+json"]')) {
+    try { visit(JSON.parse(script.textContent)); } catch (e) {}
+  }
+  return found;
+})()
+"#;
+
+/// Extract results from the SERP's JSON-LD structured data
+async fn extract_json_ld(page: &Page, engine: SearchEngineKind, limit: usize) -> Result<Vec<SearchResult>> {
+    let items: Vec<serde_json::Value> = page
+        .evaluate(JSON_LD_SCRIPT)
+        .await
+        .context("Failed to read JSON-LD")?
+        .into_value()
+        .context("Invalid JSON-LD extraction result")?;
+    Ok(json_ld_results(engine, &items, limit))
+}
+
+/// Search results from extracted JSON-LD items, ranked from 1
+fn json_ld_results(engine: SearchEngineKind, items: &[serde_json::Value], limit: usize) -> Vec<SearchResult> {
+    let text = |item: &serde_json::Value, key: &str| {
+        item.get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    for item in items {
+        if results.len() >= limit {
+            break;
+        }
+        let Some(href) = text(item, "url") else {
+            continue;
+        };
+        let url = engine.resolve_result_url(&href);
+        if !url.starts_with("http") || engine.is_own_url(&url) || !seen.insert(url.clone()) {
+            continue;
+        }
+        let rank = results.len() + 1;
+        results.push(SearchResult {
+            rank,
+            title: text(item, "title").unwrap_or_else(|| format!("Untitled Result {rank}")),
+            url,
+            snippet: text(item, "snippet").unwrap_or_else(|| "No description available".to_string()),
+        });
+    }
+    results
+}
+
+/// Extract image or news results from a loaded vertical SERP
+///
+/// Only Bing's image and news pages are scraped; other engines serve
+/// verticals through their APIs (see `api.rs`).
+pub async fn extract_vertical_results(
+    page: &Page,
+    engine: SearchEngineKind,
+    vertical: SearchVertical,
+    limit: usize,
+) -> Result<Vec<VerticalResult>> {
+    let script = match (engine, vertical) {
+        // Each tile's `m` attribute holds JSON with the image (murl),
+        // thumbnail (turl), page (purl) and title (t)
+        (SearchEngineKind::Bing, SearchVertical::Images) => {
+            r"Array.from(document.querySelectorAll('a.iusc')).map(a => {
+                let m = {};
+                try { m = JSON.parse(a.getAttribute('m') || '{}'); } catch (e) {}
+                const tile = a.closest('li') || a.parentElement;
+                const info = tile && tile.querySelector('.img_info span.nowrap');
+                const source = tile && tile.querySelector('.img_info .lnkw a');
+                return {
+                    title: m.t || a.getAttribute('aria-label') || '',
+                    url: m.purl || '',
+                    image_url: m.murl || '',
+                    thumbnail_url: m.turl || null,
+                    size: info ? info.textContent : null,
+                    source: source ? source.textContent : null,
+                };
+            })"
+        }
+        (SearchEngineKind::Bing, SearchVertical::News) => {
+            r"Array.from(document.querySelectorAll('div.news-card[data-url]')).map(card => {
+                const snippet = card.querySelector('.snippet');
+                const age = card.querySelector('.source span[aria-label]');
+                return {
+                    title: card.getAttribute('data-title') || '',
+                    url: card.getAttribute('data-url') || '',
+                    snippet: snippet ? snippet.textContent : '',
+                    source: card.getAttribute('data-author'),
+                    published: age ? age.getAttribute('aria-label') : null,
+                };
+            })"
+        }
+        _ => return Err(anyhow!("{engine}: {vertical} search not supported")),
+    };
+    let items: Vec<serde_json::Value> = page
+        .evaluate(script)
+        .await
+        .with_context(|| format!("Failed to read {engine} {vertical} results"))?
+        .into_value()
+        .with_context(|| format!("Unexpected {engine} {vertical} results"))?;
+
+    let text = |item: &serde_json::Value, key: &str| {
+        item.get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    for item in &items {
+        let Some(url) = text(item, "url").filter(|u| u.starts_with("http")) else {
+            continue;
+        };
+        if results.len() >= limit || !seen.insert(dedupe_key(&url)) {
+            continue;
+        }
+        let rank = results.len() + 1;
+        let title = text(item, "title").unwrap_or_else(|| format!("Untitled Result {rank}"));
+        results.push(match vertical {
+            SearchVertical::Images => {
+                // "1920 x 1080 · jpeg"
+                let size = text(item, "size").unwrap_or_default();
+                let mut dims = size
+                    .split(|c: char| !c.is_ascii_digit())
+                    .filter(|d| !d.is_empty())
+                    .map(|d| d.parse::<u32>().ok());
+                VerticalResult::Image(ImageResult {
+                    rank,
+                    title,
+                    image_url: text(item, "image_url").unwrap_or_else(|| url.clone()),
+                    url,
+                    thumbnail_url: text(item, "thumbnail_url"),
+                    width: dims.next().flatten(),
+                    height: dims.next().flatten(),
+                    source: text(item, "source"),
+                })
+            }
+            _ => VerticalResult::News(NewsResult {
+                rank,
+                title,
+                url,
+                snippet: text(item, "snippet").unwrap_or_default(),
+                source: text(item, "source"),
+                published: text(item, "published"),
+            }),
+        });
+    }
+
+    if results.is_empty() {
+        let url = get_page_url_with_fallback(page).await;
+        if is_challenge_url(&url) {
+            return Err(anyhow!(
+                "{engine} CAPTCHA detected. No search results available. \
+                 Try again later, use a different engine or a different network connection."
+            ));
+        }
+        return Err(anyhow!(
+            "{engine} returned zero results for this {vertical} query. Try a different search term."
+        ));
+    }
+    Ok(results)
+}
+
+/// Append the next page of results in place by clicking the engine's
+/// "more results" button
+///
+/// Returns `false` when the engine has no such button or it is gone (no more
+/// results). Waits up to 5s for the result count to grow.
+pub async fn load_more_results(page: &Page, engine: SearchEngineKind) -> Result<bool> {
+    let Some(selector) = engine.more_results_selector() else {
+        return Ok(false);
+    };
+    let result_selector = engine.result_selector(SearchVertical::Web);
+    let before = page.find_elements(&result_selector).await.map(|r| r.len()).unwrap_or(0);
+
+    let Ok(button) = page.find_element(selector).await else {
+        debug!("No '{}' button on {} SERP", selector, engine);
+        return Ok(false);
+    };
+    button
+        .click()
+        .await
+        .with_context(|| format!("Failed to click {engine} more results button"))?;
+
+    let poll_start = Instant::now();
+    while poll_start.elapsed() < Duration::from_secs(5) {
+        tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+        let now = page.find_elements(&result_selector).await.map(|r| r.len()).unwrap_or(0);
+        if now > before {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Key identifying the same page across SERP pages
+fn dedupe_key(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed.as_str().trim_end_matches('/').to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Collect results from the loaded SERP and the pages after it
+///
+/// Pages before `options.start_page` are walked but not returned. Pages are
+/// loaded by URL where the engine has page URLs, otherwise by clicking its
+/// "more results" button. Results are deduplicated by URL across pages and
+/// re-ranked. Paging stops at `options.max_results`, when a page adds nothing
+/// new or after [`MAX_PAGES`] pages; failures after the first page end paging
+/// with the results collected so far.
+///
+/// Also returns the fallback strategy used on any page when the primary
+/// selectors found nothing.
+pub async fn collect_pages(
+    page: &Page,
+    query: &str,
+    engine: SearchEngineKind,
+    options: &SearchOptions,
+) -> Result<(Vec<SearchResult>, Option<&'static str>)> {
+    let skip_pages = options.start_page.saturating_sub(1);
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    let mut fallback_strategy = None;
+    let mut page_index = 0;
+
+    loop {
+        // In-place paging keeps earlier results on the page; `seen` skips them
+        let found = match extract_results(page, engine, usize::MAX).await {
+            Ok((found, strategy)) => {
+                if engine.selector_strategies().first().is_none_or(|s| s.name != strategy) {
+                    fallback_strategy = Some(strategy);
+                }
+                found
+            }
+            Err(e) if page_index == 0 => return Err(e),
+            Err(e) => {
+                warn!("Stopping {} pagination at page {}: {}", engine, page_index + 1, e);
+                break;
+            }
+        };
+        let mut new_results = 0;
+        for result in found {
+            if !seen.insert(dedupe_key(&result.url)) {
+                continue;
+            }
+            new_results += 1;
+            if page_index >= skip_pages && results.len() < options.max_results {
+                results.push(result);
+            }
+        }
+
+        if results.len() >= options.max_results
+            || (page_index > 0 && new_results == 0)
+            || page_index + 1 >= skip_pages + MAX_PAGES
+        {
+            break;
+        }
+
+        page_index += 1;
+        let advanced = match engine.page_url(query, options, page_index)? {
+            Some(url) => load_results_page(page, &url, engine, options.vertical).await.map(|()| true),
+            None => load_more_results(page, engine).await,
+        };
+        match advanced {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                warn!("Stopping {} pagination at page {}: {}", engine, page_index + 1, e);
+                break;
+            }
+        }
+    }
+
+    for (index, result) in results.iter_mut().enumerate() {
+        result.rank = index + 1;
+    }
+    Ok((results, fallback_strategy))
+}
+
 /// Regex patterns for permanent browser errors (non-retryable)
 ///
 /// These indicate the browser/page/session state is broken and cannot recover.
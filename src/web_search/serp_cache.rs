@@ -0,0 +1,159 @@
+//! Short-lived cache of search results
+//!
+//! Identical searches within an agent session are answered from memory, so
+//! repeating a query does not hit the engine again (rate limits, CAPTCHAs).
+//! Entries are keyed by engine, normalized query and search options.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::engines::SearchEngineKind;
+use super::types::{SearchOptions, SearchResults};
+
+/// Environment variable with the cache TTL in seconds (0 disables caching)
+pub const SERP_CACHE_TTL_ENV: &str = "CITESCRAPE_SERP_CACHE_TTL_SECS";
+
+/// Default time a cached result stays valid
+pub const DEFAULT_SERP_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Most cached searches; the oldest is dropped beyond this
+const MAX_ENTRIES: usize = 256;
+
+/// Cache of [`SearchResults`] per engine, query and options
+#[derive(Debug)]
+pub struct SerpCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, SearchResults)>>,
+}
+
+impl SerpCache {
+    /// Cache keeping results for `ttl` (zero disables it)
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache with the TTL from `CITESCRAPE_SERP_CACHE_TTL_SECS`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(SERP_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .map_or(DEFAULT_SERP_CACHE_TTL, Duration::from_secs);
+        Self::new(ttl)
+    }
+
+    /// Time cached results stay valid
+    #[must_use]
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    fn key(engine: SearchEngineKind, query: &str, options: &SearchOptions) -> String {
+        let query = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        format!("{engine}\n{query}\n{options:?}")
+    }
+
+    fn with_entries<T>(&self, f: impl FnOnce(&mut HashMap<String, (Instant, SearchResults)>) -> T) -> T {
+        let mut entries = self.entries.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        f(&mut entries)
+    }
+
+    /// Unexpired results of an earlier identical search, marked as cached
+    #[must_use]
+    pub fn get(&self, engine: SearchEngineKind, query: &str, options: &SearchOptions) -> Option<SearchResults> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let key = Self::key(engine, query, options);
+        self.with_entries(|entries| match entries.get(&key) {
+            Some((stored, results)) if stored.elapsed() < self.ttl => {
+                let mut results = results.clone();
+                results.cached = true;
+                Some(results)
+            }
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        })
+    }
+
+    /// Remember `results` of a search on `engine`
+    pub fn insert(&self, engine: SearchEngineKind, query: &str, options: &SearchOptions, results: &SearchResults) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let key = Self::key(engine, query, options);
+        let ttl = self.ttl;
+        self.with_entries(|entries| {
+            entries.retain(|_, (stored, _)| stored.elapsed() < ttl);
+            if entries.len() >= MAX_ENTRIES
+                && let Some(oldest) = entries.iter().min_by_key(|(_, (stored, _))| *stored).map(|(k, _)| k.clone())
+            {
+                entries.remove(&oldest);
+            }
+            entries.insert(key, (Instant::now(), results.clone()));
+        });
+    }
+
+    /// Number of cached searches (expired ones included until evicted)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.with_entries(|entries| entries.len())
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for SerpCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SERP_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web_search::SearchResult;
+
+    #[test]
+    fn test_cache_key_and_expiry() {
+        let cache = SerpCache::default();
+        let options = SearchOptions::default();
+        let results = SearchResults::new(
+            "rust  Tokio".to_string(),
+            SearchEngineKind::Bing,
+            vec![SearchResult {
+                rank: 1,
+                title: "Tokio".to_string(),
+                url: "https://tokio.rs".to_string(),
+                snippet: String::new(),
+            }
+            .into()],
+        );
+        cache.insert(SearchEngineKind::Bing, "rust  Tokio", &options, &results);
+
+        let hit = cache.get(SearchEngineKind::Bing, " rust tokio", &options).unwrap();
+        assert!(hit.cached);
+        assert_eq!(hit.results.len(), 1);
+        assert!(cache.get(SearchEngineKind::Brave, "rust tokio", &options).is_none());
+        let paged = SearchOptions {
+            start_page: 2,
+            ..SearchOptions::default()
+        };
+        assert!(cache.get(SearchEngineKind::Bing, "rust tokio", &paged).is_none());
+
+        let disabled = SerpCache::new(Duration::ZERO);
+        disabled.insert(SearchEngineKind::Bing, "rust", &options, &results);
+        assert!(disabled.is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 //! Data structures and constants for web search functionality
 
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use super::engines::SearchEngineKind;
 
 // =============================================================================
 // Constants
@@ -33,6 +39,12 @@ pub const MAX_RETRIES: u32 = 3;
 /// Maximum number of results to extract
 pub const MAX_RESULTS: usize = 10;
 
+/// Most results one search may collect across SERP pages
+pub const MAX_TOTAL_RESULTS: usize = 100;
+
+/// Most SERP pages loaded for one search
+pub const MAX_PAGES: usize = 10;
+
 /// Polling interval in milliseconds for waiting on DOM elements
 ///
 /// 100ms provides good responsiveness without excessive CDP overhead.
@@ -56,7 +68,7 @@ pub const MAX_QUERY_LENGTH: usize = 1000;
 // =============================================================================
 
 /// A single search result with rank, title, URL, and snippet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchResult {
     /// Result ranking (1-indexed)
     pub rank: usize,
@@ -73,22 +85,362 @@ pub struct SearchResult {
 
 
 
+/// Kind of results to search for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchVertical {
+    /// Web pages
+    #[default]
+    Web,
+    /// Images with thumbnails and dimensions
+    Images,
+    /// News articles with sources and publication dates
+    News,
+}
+
+impl std::fmt::Display for SearchVertical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Web => "web",
+            Self::Images => "images",
+            Self::News => "news",
+        })
+    }
+}
+
+/// An image search result
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageResult {
+    /// Result ranking (1-indexed)
+    pub rank: usize,
+    /// Image title or alt text
+    pub title: String,
+    /// Page the image appears on
+    pub url: String,
+    /// Full-size image
+    pub image_url: String,
+    /// Engine-hosted thumbnail
+    pub thumbnail_url: Option<String>,
+    /// Image width in pixels, if known
+    pub width: Option<u32>,
+    /// Image height in pixels, if known
+    pub height: Option<u32>,
+    /// Site the image comes from
+    pub source: Option<String>,
+}
+
+/// A news search result
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewsResult {
+    /// Result ranking (1-indexed)
+    pub rank: usize,
+    /// Headline
+    pub title: String,
+    /// Article URL
+    pub url: String,
+    /// Article excerpt
+    pub snippet: String,
+    /// Publisher
+    pub source: Option<String>,
+    /// Publication date or age as reported by the engine (e.g. "2 hours ago")
+    pub published: Option<String>,
+}
+
+/// A result of any vertical, tagged with its kind
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "vertical", rename_all = "lowercase")]
+pub enum VerticalResult {
+    Web(SearchResult),
+    Image(ImageResult),
+    News(NewsResult),
+}
+
+impl VerticalResult {
+    #[must_use]
+    pub fn rank(&self) -> usize {
+        match self {
+            Self::Web(r) => r.rank,
+            Self::Image(r) => r.rank,
+            Self::News(r) => r.rank,
+        }
+    }
+
+    pub fn set_rank(&mut self, rank: usize) {
+        match self {
+            Self::Web(r) => r.rank = rank,
+            Self::Image(r) => r.rank = rank,
+            Self::News(r) => r.rank = rank,
+        }
+    }
+
+    #[must_use]
+    pub fn title(&self) -> &str {
+        match self {
+            Self::Web(r) => &r.title,
+            Self::Image(r) => &r.title,
+            Self::News(r) => &r.title,
+        }
+    }
+
+    /// Page the result links to (for images, the page showing the image)
+    #[must_use]
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Web(r) => &r.url,
+            Self::Image(r) => &r.url,
+            Self::News(r) => &r.url,
+        }
+    }
+
+    /// Description: the snippet, or an image's source site
+    #[must_use]
+    pub fn snippet(&self) -> &str {
+        match self {
+            Self::Web(r) => &r.snippet,
+            Self::Image(r) => r.source.as_deref().unwrap_or_default(),
+            Self::News(r) => &r.snippet,
+        }
+    }
+}
+
+impl From<SearchResult> for VerticalResult {
+    fn from(result: SearchResult) -> Self {
+        Self::Web(result)
+    }
+}
+
+/// How recent results must be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    Day,
+    Week,
+    Month,
+    Year,
+    /// Published between two dates (inclusive)
+    Range { from: NaiveDate, to: NaiveDate },
+}
+
+impl std::str::FromStr for Freshness {
+    type Err = anyhow::Error;
+
+    /// Parses `day`, `week`, `month`, `year` or `YYYY-MM-DD..YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "day" | "d" | "24h" => return Ok(Self::Day),
+            "week" | "w" => return Ok(Self::Week),
+            "month" | "m" => return Ok(Self::Month),
+            "year" | "y" => return Ok(Self::Year),
+            _ => {}
+        }
+        let Some((from, to)) = s.split_once("..") else {
+            bail!("freshness must be day, week, month, year or YYYY-MM-DD..YYYY-MM-DD, got '{s}'");
+        };
+        let date = |d: &str| {
+            NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").with_context(|| format!("invalid date '{d}' in freshness"))
+        };
+        let (from, to) = (date(from)?, date(to)?);
+        if from > to {
+            bail!("freshness range starts after it ends ({from} > {to})");
+        }
+        Ok(Self::Range { from, to })
+    }
+}
+
+/// Region and language to search in (gl/hl-style)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchLocale {
+    /// ISO 3166-1 alpha-2 country code, lowercase (e.g. `de`)
+    pub region: Option<String>,
+    /// ISO 639-1 language code, lowercase (e.g. `de`)
+    pub language: Option<String>,
+}
+
+impl SearchLocale {
+    /// Validate and normalize `region` and `language` codes
+    pub fn new(region: Option<&str>, language: Option<&str>) -> Result<Self> {
+        let code = |value: Option<&str>, what: &str, lengths: &[usize]| -> Result<Option<String>> {
+            let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+                return Ok(None);
+            };
+            if !lengths.contains(&value.len()) || !value.bytes().all(|b| b.is_ascii_alphabetic()) {
+                bail!("invalid {what} code '{value}'");
+            }
+            Ok(Some(value.to_ascii_lowercase()))
+        };
+        Ok(Self {
+            region: code(region, "region", &[2])?,
+            language: code(language, "language", &[2, 3])?,
+        })
+    }
+
+    /// BCP 47 tag for the browser, e.g. `de-DE` (`None` without a language)
+    #[must_use]
+    pub fn language_tag(&self) -> Option<String> {
+        let language = self.language.as_deref()?;
+        Some(match &self.region {
+            Some(region) => format!("{language}-{}", region.to_ascii_uppercase()),
+            None => language.to_string(),
+        })
+    }
+}
+
+/// Search operators added to a query
+///
+/// `site:`, `filetype:` and `-` exclusions share one syntax across the
+/// supported engines, so they are composed into the query text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryOperators {
+    /// Only results from these domains (ORed)
+    pub sites: Vec<String>,
+    /// No results from these domains
+    pub exclude_sites: Vec<String>,
+    /// Only documents of this type (e.g. `pdf`)
+    pub filetype: Option<String>,
+    /// Words or phrases results must not contain
+    pub exclude_terms: Vec<String>,
+}
+
+impl QueryOperators {
+    /// `query` with the operators appended
+    ///
+    /// Domains and file types are validated so they cannot inject further
+    /// operators; excluded phrases are quoted.
+    pub fn apply(&self, query: &str) -> Result<String> {
+        let domain = |value: &str| -> Result<String> {
+            let value = value.trim().trim_start_matches("https://").trim_start_matches("http://");
+            let value = value.trim_end_matches('/').to_ascii_lowercase();
+            let valid = !value.is_empty()
+                && value.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-' || b == b'/');
+            if !valid {
+                bail!("invalid site '{value}'");
+            }
+            Ok(value)
+        };
+
+        let mut composed = query.trim().to_string();
+        let sites = self.sites.iter().map(|s| domain(s)).collect::<Result<Vec<_>>>()?;
+        match sites.as_slice() {
+            [] => {}
+            [site] => {
+                let _ = write!(composed, " site:{site}");
+            }
+            _ => {
+                let sites: Vec<String> = sites.iter().map(|s| format!("site:{s}")).collect();
+                let _ = write!(composed, " ({})", sites.join(" OR "));
+            }
+        }
+        for site in &self.exclude_sites {
+            let _ = write!(composed, " -site:{}", domain(site)?);
+        }
+        if let Some(filetype) = &self.filetype {
+            let filetype = filetype.trim().trim_start_matches('.').to_ascii_lowercase();
+            if filetype.is_empty() || !filetype.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                bail!("invalid filetype '{filetype}'");
+            }
+            let _ = write!(composed, " filetype:{filetype}");
+        }
+        for term in &self.exclude_terms {
+            let term = term.replace('"', " ");
+            let term = term.split_whitespace().collect::<Vec<_>>().join(" ");
+            match term.as_str() {
+                "" => {}
+                t if t.contains(' ') => {
+                    let _ = write!(composed, " -\"{t}\"");
+                }
+                t => {
+                    let _ = write!(composed, " -{}", t.trim_start_matches('-'));
+                }
+            }
+        }
+        Ok(composed)
+    }
+}
+
+/// Options for one search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// First SERP page to return results from (1-based)
+    pub start_page: usize,
+    /// Results to collect, paging through the SERP as needed
+    pub max_results: usize,
+    /// Only results from this period
+    pub freshness: Option<Freshness>,
+    /// Region and language of results and of the browser
+    pub locale: Option<SearchLocale>,
+    /// Web pages, images or news
+    pub vertical: SearchVertical,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            start_page: 1,
+            max_results: MAX_RESULTS,
+            freshness: None,
+            locale: None,
+            vertical: SearchVertical::Web,
+        }
+    }
+}
+
 /// Collection of search results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResults {
     /// Search query that produced these results
     pub query: String,
 
-    /// List of search results
-    pub results: Vec<SearchResult>,
+    /// Engine the results came from
+    pub engine: SearchEngineKind,
+
+    /// List of search results, all of the searched vertical
+    pub results: Vec<VerticalResult>,
+
+    /// Served from the SERP cache rather than a fresh search
+    #[serde(default)]
+    pub cached: bool,
+
+    /// Extraction strategy used when the engine's primary selectors found
+    /// nothing (e.g. "legacy" or "json-ld")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_strategy: Option<String>,
 }
 
 impl SearchResults {
     /// Create new `SearchResults`
     #[must_use]
-    pub fn new(query: String, results: Vec<SearchResult>) -> Self {
-        Self { query, results }
+    pub fn new(query: String, engine: SearchEngineKind, results: Vec<VerticalResult>) -> Self {
+        Self {
+            query,
+            engine,
+            results,
+            cached: false,
+            fallback_strategy: None,
+        }
     }
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_query_operators() {
+        let operators = QueryOperators {
+            sites: vec!["https://docs.rs/".to_string(), "github.com".to_string()],
+            exclude_sites: vec!["reddit.com".to_string()],
+            filetype: Some(".PDF".to_string()),
+            exclude_terms: vec!["async \"std\"".to_string(), "-nightly".to_string()],
+        };
+        assert_eq!(
+            operators.apply("tokio runtime").unwrap(),
+            "tokio runtime (site:docs.rs OR site:github.com) -site:reddit.com filetype:pdf -\"async std\" -nightly"
+        );
+
+        let injected = QueryOperators {
+            sites: vec!["docs.rs OR evil.com".to_string()],
+            ..Default::default()
+        };
+        assert!(injected.apply("tokio").is_err());
+    }
 }
@@ -0,0 +1,55 @@
+//! Redirect aliases recorded by a real crawl
+//!
+//! Needs a Chrome/Chromium executable; the test returns early without one.
+
+mod common;
+
+use anyhow::Result;
+use kodegen_tools_citescrape::link_index::normalize_url;
+use kodegen_tools_citescrape::{ChromiumoxideCrawler, CrawlConfig, LinkIndex, find_browser_executable};
+
+#[tokio::test]
+async fn test_redirect_target_links_resolve_to_saved_page() -> Result<()> {
+    if find_browser_executable().await.is_err() {
+        eprintln!("Skipping: no Chrome/Chromium executable found");
+        return Ok(());
+    }
+
+    let mut server = common::setup_mock_server().await?;
+    let home = common::create_test_html("Home", r#"<a href="/old">Old</a> <a href="/about">About</a>"#);
+    let about = common::create_test_html("About", r#"<a href="/moved">Moved</a>"#);
+    let moved = common::create_test_html("Moved", "<p>The page moved here.</p>");
+    let _home = common::create_html_mock(&mut server, "/", &home);
+    let _about = common::create_html_mock(&mut server, "/about", &about);
+    let _old = common::create_redirect_mock(&mut server, "/old", "/moved");
+    let _moved = common::create_html_mock(&mut server, "/moved", &moved);
+
+    let temp_dir = common::create_test_dir()?;
+    // Depth 1 keeps /moved from being crawled under its own URL
+    let config = CrawlConfig::builder()
+        .storage_dir(temp_dir.path().to_path_buf())
+        .start_url(common::test_url(&server, "/"))
+        .limit(Some(10))
+        .max_depth(1)
+        .save_raw_html(true)
+        .build()?;
+    ChromiumoxideCrawler::new(config).crawl_with_progress(|_| {}).await?;
+
+    let old_url = normalize_url(&common::test_url(&server, "/old"));
+    let moved_url = normalize_url(&common::test_url(&server, "/moved"));
+    let index = LinkIndex::open(temp_dir.path()).await?;
+    let resolved = index.resolve_existing(std::slice::from_ref(&moved_url)).await?;
+    index.close().await;
+    assert_eq!(resolved.get(&moved_url), Some(&old_url));
+
+    let about_path = kodegen_tools_citescrape::get_mirror_path(
+        &common::test_url(&server, "/about"),
+        temp_dir.path(),
+        "index.html",
+    )
+    .await?;
+    let about_html = tokio::fs::read_to_string(about_path).await?;
+    assert!(about_html.contains(r#"href="../old/index.html""#), "{about_html}");
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use kodegen_tools_citescrape::config::CrawlConfig;
 use kodegen_tools_citescrape::search::{
     engine::SearchEngine,
     indexer::{BatchConfig, MarkdownIndexer},
+    schema::{REINDEX_PENDING_FILE, SCHEMA_VERSION, SCHEMA_VERSION_FILE},
     types::IndexingPhase,
 };
 use std::fs;
@@ -312,3 +313,90 @@ async fn test_url_deduplication() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_schema_version_change_rebuilds_and_reindexes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    create_test_crawl_output(&temp_dir)?;
+    let crawl_output = temp_dir.path().join("crawl_output");
+
+    let config = CrawlConfig::builder()
+        .storage_dir(crawl_output.clone())
+        .start_url("https://example.com")
+        .build()
+        .map_err(anyhow::Error::msg)?;
+
+    let engine = SearchEngine::create(&config).await?;
+    let indexer = MarkdownIndexer::new(engine.clone());
+    let (mut stream, _cancel_handle) = indexer.batch_index_directory(crawl_output, BatchConfig::default());
+    while stream.next().await.is_some() {}
+    assert_eq!(indexer.get_index_stats().await?.num_documents, 6);
+    drop((indexer, engine));
+
+    let version_file = config.search_index_dir().join(SCHEMA_VERSION_FILE);
+    assert_eq!(fs::read_to_string(&version_file)?.trim(), SCHEMA_VERSION.to_string());
+
+    // Same schema: the index is reused as is
+    let engine = SearchEngine::create(&config).await?;
+    assert!(engine.reindex_progress().is_none());
+    assert_eq!(engine.get_stats().await?.num_documents, 6);
+    drop(engine);
+
+    // An index built with another schema version is rebuilt from the crawl
+    // output in the background and stamped once that is done
+    fs::write(&version_file, "1\n")?;
+    let engine = SearchEngine::create(&config).await?;
+    assert!(engine.reindex_progress().is_some());
+    engine.wait_for_reindex().await;
+    assert_eq!(engine.get_stats().await?.num_documents, 6);
+    assert_eq!(fs::read_to_string(&version_file)?.trim(), SCHEMA_VERSION.to_string());
+    drop(engine);
+
+    // A rebuild whose reindex did not finish is rebuilt again, without duplicates
+    assert!(!config.search_index_dir().join(REINDEX_PENDING_FILE).exists());
+    fs::write(config.search_index_dir().join(REINDEX_PENDING_FILE), "")?;
+    let engine = SearchEngine::create(&config).await?;
+    engine.wait_for_reindex().await;
+    assert_eq!(engine.get_stats().await?.num_documents, 6);
+    assert_eq!(fs::read_to_string(&version_file)?.trim(), SCHEMA_VERSION.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_document_by_exact_url() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    create_test_crawl_output(&temp_dir)?;
+    let crawl_output = temp_dir.path().join("crawl_output");
+
+    let config = CrawlConfig::builder()
+        .storage_dir(crawl_output.clone())
+        .start_url("https://example.com")
+        .build()
+        .map_err(anyhow::Error::msg)?;
+
+    let engine = SearchEngine::create(&config).await?;
+    let indexer = MarkdownIndexer::new(engine.clone());
+    let (mut stream, _cancel_handle) = indexer.batch_index_directory(crawl_output, BatchConfig::default());
+    while stream.next().await.is_some() {}
+    engine.reader().reload()?;
+
+    // Every token of this URL also occurs in https://example.com/docs/api/
+    let docs = engine
+        .get_document("https://example.com/docs/")?
+        .expect("docs page is indexed");
+    assert_eq!(docs.url, "https://example.com/docs/");
+    assert!(docs.markdown.contains("This is the docs section"), "{}", docs.markdown);
+    assert_eq!(docs.domain, "example.com");
+    assert!(docs.crawl_date.is_some());
+    assert!(docs.word_count > 0);
+
+    let home = engine.get_document("https://example.com/")?.expect("homepage is indexed");
+    assert!(home.markdown.contains("Welcome to example.com"), "{}", home.markdown);
+
+    assert!(engine.get_document("https://example.com/missing/")?.is_none());
+    assert!(engine.get_document("https://EXAMPLE.com/docs/")?.is_none());
+    assert!(engine.get_document("")?.is_none());
+
+    Ok(())
+}
@@ -1,6 +1,11 @@
 //! Tests for the type-safe configuration builder pattern
 
-use kodegen_tools_citescrape::config::CrawlConfig;
+use kodegen_tools_citescrape::config::{
+    ConfigFormat, Cookie, CrawlConfig, CrawlConfigFile, CrawlProfile, Secret, ServerConfig, parse_netscape_cookies,
+};
+use kodegen_tools_citescrape::content_saver::markdown_converter::ChromeFilterLevel;
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use tempfile::TempDir;
 
@@ -228,6 +233,284 @@ async fn test_builder_state_transitions() {
     // The above should compile and work correctly
 }
 
+#[tokio::test]
+async fn test_page_ready_conditions() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let config = CrawlConfig::builder()
+        .storage_dir(temp_dir.path().to_path_buf())
+        .start_url("https://example.com")
+        .build()
+        .unwrap();
+    assert!(config.page_ready_conditions().is_empty());
+
+    let config = CrawlConfig::builder()
+        .storage_dir(temp_dir.path().to_path_buf())
+        .start_url("https://example.com")
+        .wait_for_selector(Some("#app main".to_string()))
+        .wait_for_network_idle_ms(Some(500))
+        .wait_for_function(Some("() => window.appReady".to_string()))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.wait_for_selector(), Some("#app main"));
+    assert_eq!(config.wait_for_network_idle_ms(), Some(500));
+    assert_eq!(config.wait_for_function(), Some("() => window.appReady"));
+
+    let conditions = config.page_ready_conditions();
+    assert!(!conditions.is_empty());
+    assert_eq!(conditions.selector.as_deref(), Some("#app main"));
+    assert_eq!(conditions.network_idle_ms, Some(500));
+}
+
+#[test]
+fn test_config_from_toml_and_yaml_files() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let toml_path = temp_dir.path().join("crawl.toml");
+    std::fs::write(
+        &toml_path,
+        r#"
+start_url = "docs.rs/tokio"
+storage_dir = "out"
+stealth_mode = true
+
+[filters]
+allowed_domains = ["docs.rs"]
+excluded_patterns = ["*/src/*"]
+
+[budgets]
+max_depth = 5
+limit = 200
+crawl_rate_rps = 0
+
+[output]
+save_screenshots = false
+screenshot_quality = 60
+event_journal = false
+chrome_filter_level = "lenient"
+drop_chrome_patterns = ["announcement-bar"]
+normalize_punctuation = true
+strip_emoji = true
+
+[retry_pass]
+timeout_multiplier = 3
+headless = false
+proxy = "socks5://127.0.0.1:1080"
+
+[[extraction_schemas]]
+url_pattern = "*/releases/*"
+fields.version = { selector = "h1", regex = "v([0-9.]+)" }
+"#,
+    )
+    .unwrap();
+    let config = CrawlConfig::from_file(&toml_path).unwrap();
+    assert_eq!(config.start_url(), "https://docs.rs/tokio");
+    assert_eq!(config.storage_dir(), &temp_dir.path().join("out"));
+    assert_eq!(config.max_depth(), 5);
+    assert_eq!(config.limit(), Some(200));
+    assert_eq!(config.crawl_rate_rps(), None);
+    assert!(!config.save_screenshots());
+    assert_eq!(config.screenshot_quality(), 60);
+    assert_eq!(config.excluded_patterns_compiled().len(), 1);
+    assert!(!config.event_journal());
+    let schema = config.extraction_schema_for("https://docs.rs/releases/1").unwrap();
+    assert_eq!(schema.spec().fields["version"].regex.as_deref(), Some("v([0-9.]+)"));
+    assert!(config.extraction_schema_for("https://docs.rs/tokio").is_none());
+    let chrome = config.chrome_filter();
+    assert_eq!(chrome.level, ChromeFilterLevel::Lenient);
+    assert!(chrome.keep.is_none());
+    assert!(chrome.drop.is_some_and(|drop| drop.is_match("site announcement-bar")));
+    let typography = config.conversion_options("https://docs.rs/tokio").typography;
+    assert!(typography.normalize_punctuation && typography.strip_emoji);
+    assert!(!typography.decode_numeric_entities);
+    assert!(config.retry_pass_enabled());
+    assert_eq!(config.retry_pass_timeout_multiplier(), 3.0);
+    assert!(!config.retry_pass_headless());
+    assert_eq!(config.retry_pass_proxy(), Some("socks5://127.0.0.1:1080"));
+
+    let yaml_path = temp_dir.path().join("crawl.yml");
+    std::fs::write(
+        &yaml_path,
+        "start_url: https://example.com\nstorage_dir: /tmp/crawl\nbudgets:\n  max_depth: 2\n",
+    )
+    .unwrap();
+    let config = CrawlConfig::from_file(&yaml_path).unwrap();
+    assert_eq!(config.storage_dir(), &PathBuf::from("/tmp/crawl"));
+    assert_eq!(config.max_depth(), 2);
+    assert!(config.event_journal());
+    assert!(config.typography().is_noop());
+    assert_eq!(config.retry_pass_timeout_multiplier(), 2.0);
+    assert_eq!(config.retry_pass_headless(), config.headless());
+    assert_eq!(config.retry_pass_proxy(), None);
+}
+
+#[test]
+fn test_request_headers_and_cookies() {
+    let cookies = parse_netscape_cookies(
+        "# Netscape HTTP Cookie File\n\
+         .example.com\tTRUE\t/\tTRUE\t1893456000\tconsent\tyes\n\
+         #HttpOnly_example.com\tFALSE\t/app\tFALSE\t0\tsession\tabc\n",
+    )
+    .unwrap();
+    assert_eq!(cookies.len(), 2);
+    assert_eq!(cookies[0].domain.as_deref(), Some(".example.com"));
+    assert!(cookies[0].secure);
+    assert_eq!(cookies[0].expires, Some(1_893_456_000.0));
+    assert!(cookies[1].http_only);
+    assert_eq!(cookies[1].path.as_deref(), Some("/app"));
+    assert_eq!(cookies[1].expires, None);
+    assert!(parse_netscape_cookies("example.com\tTRUE\t/\n").is_err());
+
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join("cookies.txt"),
+        "example.com\tFALSE\t/\tFALSE\t0\tconsent\tno\n",
+    )
+    .unwrap();
+    let config_path = temp_dir.path().join("crawl.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+start_url = "https://example.com"
+storage_dir = "out"
+
+[request]
+headers = { Authorization = "Bearer token", X-Token = "file:token.txt" }
+cookies = [{ name = "lang", value = "en" }, { name = "sid", value = "env:DOCS_SESSION" }]
+cookie_file = "cookies.txt"
+"#,
+    )
+    .unwrap();
+    let config = CrawlConfig::from_file(&config_path).unwrap();
+    assert_eq!(
+        config.headers().get("Authorization"),
+        Some(&Secret::Literal("Bearer token".into()))
+    );
+    // File cookies first, explicit cookies after
+    let names: Vec<_> = config.cookies().iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["consent", "lang", "sid"]);
+    // Secret references stay references; relative files are relative to the config file
+    assert_eq!(
+        config.headers().get("X-Token"),
+        Some(&Secret::File(temp_dir.path().join("token.txt")))
+    );
+    assert_eq!(config.cookies()[2].value, Secret::Env("DOCS_SESSION".into()));
+
+    let config = CrawlConfig::builder()
+        .storage_dir(temp_dir.path())
+        .start_url("https://example.com")
+        .header("X-Api-Key", "secret")
+        .cookie(Cookie::new("consent", "yes"))
+        .build()
+        .unwrap();
+    assert_eq!(config.headers().len(), 1);
+    assert_eq!(config.cookies(), &[Cookie::new("consent", "yes")]);
+    // Credentials are not written out with the config
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(!json.contains("secret"));
+
+    let bad = CrawlConfig::builder()
+        .storage_dir(temp_dir.path())
+        .start_url("https://example.com")
+        .header("Bad Header", "x")
+        .build();
+    assert!(bad.is_err());
+
+    // Control characters are refused when the file is validated, as by the builder
+    let file = CrawlConfigFile::parse(
+        "start_url = \"https://example.com\"\nstorage_dir = \"out\"\n\n[request]\nheaders = { \"X-A\\u0007\" = \"x\" }\n",
+        ConfigFormat::Toml,
+    )
+    .unwrap();
+    assert!(file.validate().unwrap_err().to_string().starts_with("request.headers.X-A"));
+}
+
+#[test]
+fn test_crawl_profiles() {
+    for profile in CrawlProfile::ALL {
+        assert_eq!(profile.as_str().parse::<CrawlProfile>().unwrap(), profile);
+    }
+    assert_eq!(" Stealth ".parse::<CrawlProfile>().unwrap(), CrawlProfile::Stealth);
+    assert!("turbo".parse::<CrawlProfile>().is_err());
+
+    let mut config = CrawlConfig::default();
+    config.apply_profile(CrawlProfile::Stealth);
+    assert!(config.stealth_mode());
+    assert_eq!(config.max_concurrent_per_domain(), 1);
+    assert_eq!(config.crawl_rate_rps(), Some(0.5));
+
+    // Options set after the profile win
+    let temp_dir = TempDir::new().unwrap();
+    let config = CrawlConfig::builder()
+        .storage_dir(temp_dir.path().to_path_buf())
+        .start_url("https://example.com")
+        .profile(CrawlProfile::Fast)
+        .max_page_retries(4)
+        .build()
+        .unwrap();
+    assert!(!config.save_screenshots());
+    assert_eq!(config.max_concurrent_pages(), 20);
+    assert_eq!(config.max_page_retries(), 4);
+
+    let path = temp_dir.path().join("crawl.toml");
+    std::fs::write(
+        &path,
+        "start_url = \"https://example.com\"\nstorage_dir = \"out\"\nprofile = \"thorough\"\n[output]\nsave_raw_html = false\n",
+    )
+    .unwrap();
+    let config = CrawlConfig::from_file(&path).unwrap();
+    assert_eq!(config.max_page_retries(), 5);
+    assert!(!config.save_raw_html());
+}
+
+#[test]
+fn test_config_file_errors_name_the_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let load = |name: &str, contents: &str| {
+        let path = temp_dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        format!("{:#}", CrawlConfig::from_file(&path).unwrap_err())
+    };
+    let base = "start_url: https://example.com\nstorage_dir: out\n";
+
+    let error = load("type.yaml", &format!("{base}budgets:\n  max_depth: deep\n"));
+    assert!(error.contains("budgets.max_depth"), "{error}");
+
+    let error = load("unknown.yaml", &format!("{base}output:\n  save_pdf: true\n"));
+    assert!(error.contains("output") && error.contains("save_pdf"), "{error}");
+
+    let error = load("range.yaml", &format!("{base}output:\n  screenshot_quality: 0\n"));
+    assert!(error.contains("output.screenshot_quality"), "{error}");
+
+    let error = load("pattern.toml", "start_url = \"https://example.com\"\nstorage_dir = \"out\"\n[filters]\nexcluded_patterns = [\"ok/*\", \"re:(\"]\n");
+    assert!(error.contains("filters.excluded_patterns[1]"), "{error}");
+
+    let error = load("chrome.yaml", &format!("{base}output:\n  keep_chrome_patterns: ['ok', '[']\n"));
+    assert!(error.contains("output.keep_chrome_patterns[1]"), "{error}");
+
+    let error = load("level.yaml", &format!("{base}output:\n  chrome_filter_level: extreme\n"));
+    assert!(error.contains("output.chrome_filter_level"), "{error}");
+
+    let error = load("sitemap.yaml", &format!("{base}output:\n  sitemap_base_url: /mirror/\n"));
+    assert!(error.contains("output.sitemap_base_url"), "{error}");
+
+    let error = load("retry.yaml", &format!("{base}retry_pass:\n  timeout_multiplier: 0.5\n"));
+    assert!(error.contains("retry_pass.timeout_multiplier"), "{error}");
+
+    let error = load(
+        "schema.yaml",
+        &format!("{base}extraction_schemas:\n  - url_pattern: '*'\n    fields:\n      price: {{ selector: .price, regex: '(' }}\n"),
+    );
+    assert!(error.contains("extraction_schemas[0]") && error.contains("price"), "{error}");
+
+    let error = load("missing.toml", "storage_dir = \"out\"\n");
+    assert!(error.contains("start_url"), "{error}");
+
+    let error = load("crawl.json", "{}");
+    assert!(error.contains("Unsupported config file"), "{error}");
+}
+
 // NOTE: These tests are commented out because max_concurrent_requests and request_timeout
 // methods don't exist on the CrawlConfigBuilder. These may need to be re-added to the builder
 // or these tests should be removed.
@@ -276,3 +559,247 @@ fn test_timeout_duration_handling() {
     assert_eq!(config.request_timeout, std::time::Duration::from_secs(3600));
 }
 */
+
+#[test]
+fn test_server_config_layers() {
+    let mut config = ServerConfig::parse(
+        "[pool]\nmin_size = 1\nmax_size = 4\n\n[crawl]\nmax_pages_per_crawl = 100\nallowed_domains = [\"*.Docs.rs\"]\n",
+        ConfigFormat::Toml,
+    )
+    .unwrap();
+    assert_eq!(config.pool.max_size, 4);
+    assert_eq!(config.server.shutdown_timeout_secs, 30);
+
+    let env: HashMap<&str, &str> = [
+        ("CITESCRAPE_POOL_MAX_SIZE", "6"),
+        ("CITESCRAPE_OUTPUT_ROOT", "/srv/citescrape"),
+        ("CITESCRAPE_BLOCK_PRIVATE_HOSTS", "yes"),
+        ("CITESCRAPE_TRACKING_PARAMS", "utm_*, gclid,"),
+    ]
+    .into();
+    config.apply_env(|name| env.get(name).map(ToString::to_string)).unwrap();
+    config.validate().unwrap();
+
+    assert_eq!(config.pool.min_size, 1);
+    assert_eq!(config.pool.max_size, 6);
+    assert_eq!(config.output.root, Some(PathBuf::from("/srv/citescrape")));
+    assert_eq!(config.output.tracking_params, Some(vec!["utm_*".to_string(), "gclid".to_string()]));
+    assert_eq!(config.pool_config().max_pool_size, 6);
+
+    let quota = config.crawl_quota();
+    assert_eq!(quota.max_pages_per_crawl, Some(100));
+    assert_eq!(quota.allowed_domains, Some(vec!["docs.rs".to_string()]));
+    assert!(quota.block_private_hosts);
+
+    // The printed config parses back to the same settings
+    let printed = config.to_toml().unwrap();
+    assert_eq!(ServerConfig::parse(&printed, ConfigFormat::Toml).unwrap(), config);
+}
+
+#[test]
+fn test_server_config_errors_name_the_setting() {
+    let err = ServerConfig::parse("[pool]\nmax_size = \"many\"\n", ConfigFormat::Toml).unwrap_err();
+    assert!(err.to_string().starts_with("pool.max_size"), "{err}");
+
+    let err = ServerConfig::parse("[server]\nport = 1\n", ConfigFormat::Toml).unwrap_err();
+    assert!(err.to_string().contains("port"), "{err}");
+
+    let mut config = ServerConfig::default();
+    let err = config
+        .apply_env(|name| (name == "CITESCRAPE_HTTP").then(|| "nowhere".to_string()))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("CITESCRAPE_HTTP"), "{err}");
+
+    config.pool.min_size = 12;
+    assert!(config.validate().unwrap_err().to_string().starts_with("pool.min_size"));
+
+    config.pool.min_size = 2;
+    config.server.tls_cert = Some(PathBuf::from("cert.pem"));
+    assert!(config.validate().unwrap_err().to_string().starts_with("server.tls_key"));
+}
+
+#[test]
+fn test_server_config_metrics_and_telemetry() {
+    let mut config = ServerConfig::default();
+    assert_eq!(config.telemetry.otlp_endpoint, None);
+    assert_eq!(config.telemetry.service_name, "citescrape");
+
+    let env: HashMap<&str, &str> = [
+        ("CITESCRAPE_OTLP_ENDPOINT", "collector:4318"),
+        ("CITESCRAPE_OTLP_SERVICE_NAME", "docs-mirror"),
+    ]
+    .into();
+    config.apply_env(|name| env.get(name).map(ToString::to_string)).unwrap();
+    assert_eq!(config.telemetry.service_name, "docs-mirror");
+    let err = config.validate().unwrap_err();
+    assert!(err.to_string().starts_with("telemetry.otlp_endpoint"), "{err}");
+
+    config.telemetry.otlp_endpoint = None;
+    config.validate().unwrap();
+    let port = config.server.http.unwrap().port();
+    config.apply_env(|name| (name == "CITESCRAPE_METRICS_HTTP").then(|| format!("0.0.0.0:{port}"))).unwrap();
+    let err = config.validate().unwrap_err();
+    assert!(err.to_string().starts_with("metrics.http"), "{err}");
+    config.metrics.http = Some(SocketAddr::from(([127, 0, 0, 1], 9464)));
+    config.validate().unwrap();
+
+    // Export is only available in builds with the `otel` feature
+    config.telemetry.otlp_endpoint = Some("http://collector:4318".to_string());
+    assert_eq!(config.validate().is_ok(), cfg!(feature = "otel"));
+}
+
+#[test]
+fn test_server_config_rest_api() {
+    let mut config = ServerConfig::default();
+    assert_eq!(config.api.http, None);
+
+    let env: HashMap<&str, &str> = [
+        ("CITESCRAPE_API_HTTP", "127.0.0.1:30440"),
+        ("CITESCRAPE_API_TOKEN", "env:CI_CITESCRAPE_TOKEN"),
+    ]
+    .into();
+    config.apply_env(|name| env.get(name).map(ToString::to_string)).unwrap();
+    assert_eq!(config.api.http, Some(SocketAddr::from(([127, 0, 0, 1], 30440))));
+    config.validate().unwrap();
+
+    // The token is printed as its reference, never resolved
+    let toml = config.to_toml().unwrap();
+    assert!(toml.contains("[api]") && toml.contains("token = \"env:CI_CITESCRAPE_TOKEN\""), "{toml}");
+
+    // The same address is shared with the metrics endpoint, an overlapping one is not
+    config.metrics.http = config.api.http;
+    config.validate().unwrap();
+    config.metrics.http = Some(SocketAddr::from(([0, 0, 0, 0], 30440)));
+    let err = config.validate().unwrap_err();
+    assert!(err.to_string().starts_with("api.http"), "{err}");
+
+    // Off loopback the API needs a token
+    config.metrics.http = None;
+    config.api.http = Some(SocketAddr::from(([0, 0, 0, 0], 30440)));
+    config.validate().unwrap();
+    config.api.token = None;
+    let err = config.validate().unwrap_err();
+    assert!(err.to_string().starts_with("api.token"), "{err}");
+}
+
+#[test]
+fn test_server_config_grpc() {
+    let mut config = ServerConfig::default();
+    let env: HashMap<&str, &str> = [
+        ("CITESCRAPE_API_HTTP", "127.0.0.1:30440"),
+        ("CITESCRAPE_API_GRPC", "127.0.0.1:30441"),
+    ]
+    .into();
+    config.apply_env(|name| env.get(name).map(ToString::to_string)).unwrap();
+    assert_eq!(config.api.grpc, Some(SocketAddr::from(([127, 0, 0, 1], 30441))));
+    assert_eq!(config.validate().is_ok(), cfg!(feature = "grpc"));
+
+    config.api.grpc = config.api.http;
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.to_string(), "api.grpc: 127.0.0.1:30440 is already used by api.http");
+
+    // Off loopback the gRPC service needs a token too
+    config.api.grpc = Some(SocketAddr::from(([0, 0, 0, 0], 30441)));
+    let err = config.validate().unwrap_err();
+    assert_eq!(err.to_string(), "api.token: required when api.grpc (0.0.0.0:30441) is not a loopback address");
+}
+
+#[test]
+fn test_server_config_log_format() {
+    use kodegen_tools_citescrape::config::LogFormat;
+
+    let mut config = ServerConfig::default();
+    assert_eq!(config.logging.format, LogFormat::Text);
+    config.apply_env(|name| (name == "CITESCRAPE_LOG_FORMAT").then(|| "JSON".to_string())).unwrap();
+    assert_eq!(config.logging.format, LogFormat::Json);
+
+    let config = ServerConfig::parse("[logging]\nformat = \"text\"\n", ConfigFormat::Toml).unwrap();
+    assert_eq!(config.logging.format, LogFormat::Text);
+    assert!(ServerConfig::parse("[logging]\nformat = \"xml\"\n", ConfigFormat::Toml).is_err());
+    let err = ServerConfig::default()
+        .apply_env(|name| (name == "CITESCRAPE_LOG_FORMAT").then(|| "xml".to_string()))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("CITESCRAPE_LOG_FORMAT"), "{err}");
+}
+
+#[tokio::test]
+async fn test_server_config_reload_applies_safe_changes() {
+    use kodegen_tools_citescrape::config::{ConfigReloader, ReloadTargets};
+    use kodegen_tools_citescrape::{BrowserPool, CrawlRegistry, SearchEngineCache};
+    use std::sync::Arc;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("server.toml");
+    std::fs::write(&path, "[pool]\nmin_size = 1\nmax_size = 4\n").unwrap();
+    let initial = ServerConfig::from_file(&path).unwrap();
+
+    // Pools are not started, so no browser is launched
+    let engine_cache = Arc::new(SearchEngineCache::new());
+    let browser_pool = BrowserPool::new(initial.pool_config());
+    let registry = Arc::new(CrawlRegistry::new(engine_cache.clone(), browser_pool.clone()));
+    let targets = ReloadTargets {
+        browser_pool: browser_pool.clone(),
+        crawl_registry: registry.clone(),
+        search_pacer: engine_cache.web_search_pacer().clone(),
+    };
+    let mut reloader = ConfigReloader::new(&path, initial, targets)
+        .with_overrides(|config| config.search.jitter_ms = 0);
+
+    std::fs::write(
+        &path,
+        "[server]\nhttp = \"127.0.0.1:40000\"\n\n[pool]\nmin_size = 1\nmax_size = 6\n\n\
+         [crawl]\nmax_pages_per_crawl = 25\n\n[search]\nmin_interval_ms = 100\n",
+    )
+    .unwrap();
+    let applied = reloader.reload().unwrap();
+    let keys: Vec<_> = applied.iter().map(|c| c.key).collect();
+    assert_eq!(
+        keys,
+        ["pool.max_size", "crawl.max_pages_per_crawl", "search.min_interval_ms", "search.jitter_ms"]
+    );
+    assert_eq!(applied[0].to_string(), "pool.max_size: 4 -> 6");
+    assert_eq!(browser_pool.max_pool_size(), 6);
+    assert_eq!(registry.quota().max_pages_per_crawl, Some(25));
+    assert_eq!(engine_cache.web_search_pacer().config().min_interval.as_millis(), 100);
+    // The listener needs a restart and keeps its running value
+    assert_ne!(reloader.current().server.http.unwrap().port(), 40000);
+
+    // An invalid file leaves the running settings alone
+    std::fs::write(&path, "[pool]\nmin_size = 9\nmax_size = 2\n").unwrap();
+    assert!(reloader.reload().is_err());
+    assert_eq!(browser_pool.max_pool_size(), 6);
+}
+
+#[test]
+fn test_secret_parse_and_redact() {
+    assert_eq!(Secret::parse("env:DOCS_TOKEN").unwrap(), Secret::Env("DOCS_TOKEN".into()));
+    assert_eq!(
+        Secret::parse("keychain:docs/alice").unwrap(),
+        Secret::Keychain { service: "docs".into(), account: "alice".into() }
+    );
+    assert!(Secret::parse("keychain:docs").is_err());
+    assert!(Secret::parse("env:").is_err());
+
+    let literal = Secret::from("hunter2");
+    assert!(!literal.is_reference());
+    assert!(!format!("{literal:?} {literal}").contains("hunter2"));
+    assert_eq!(serde_json::to_string(&literal).unwrap(), "\"<redacted>\"");
+    assert!(!format!("{:?}", literal.resolve().unwrap()).contains("hunter2"));
+
+    let reference = Secret::Env("DOCS_TOKEN".into());
+    assert_eq!(serde_json::to_string(&reference).unwrap(), "\"env:DOCS_TOKEN\"");
+    let round_trip: Secret = serde_json::from_str("\"file:/run/secrets/token\"").unwrap();
+    assert_eq!(round_trip, Secret::File("/run/secrets/token".into()));
+}
+
+#[test]
+fn test_secret_resolve_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("token");
+    std::fs::write(&path, "s3cret\n").unwrap();
+    let secret = Secret::File(path);
+    assert_eq!(secret.resolve().unwrap().expose(), "s3cret");
+
+    let err = Secret::Env("CITESCRAPE_TEST_UNSET_SECRET".into()).resolve().unwrap_err();
+    assert!(err.to_string().contains("CITESCRAPE_TEST_UNSET_SECRET"));
+}
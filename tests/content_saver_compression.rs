@@ -1,5 +1,8 @@
 use flate2::{Compression, GzBuilder};
-use kodegen_tools_citescrape::content_saver::save_compressed_file;
+use chrono::{Duration, TimeZone, Utc};
+use kodegen_tools_citescrape::content_saver::{
+    CacheMetadata, ResponseValidators, save_compressed_file,
+};
 use std::io::{Cursor, Write};
 use std::time::Instant;
 
@@ -138,6 +141,7 @@ async fn test_memory_efficiency_no_double_clone() {
         test_data, // Ownership transferred - no clone by caller needed!
         &temp_path,
         "application/octet-stream",
+        ResponseValidators::default(),
         true, // Enable compression to test the compression code path
         1_048_576, // 1MB compression threshold
     )
@@ -161,6 +165,7 @@ async fn test_no_clone_in_signature() {
         data, // Move ownership - no clone required
         &temp_path,
         "application/octet-stream",
+        ResponseValidators::default(),
         true, // Enable compression to test the compression code path
         1_048_576, // 1MB compression threshold
     )
@@ -172,3 +177,88 @@ async fn test_no_clone_in_signature() {
     // data is moved, this should NOT compile if you uncomment:
     // println!("{:?}", data);  // ❌ Would fail: value was moved
 }
+
+#[tokio::test]
+async fn test_cache_freshness_uses_server_clock() -> anyhow::Result<()> {
+    let temp = tempfile::tempdir()?;
+    let served = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+    let response = ResponseValidators {
+        date: Some(served),
+        ..ResponseValidators::default()
+    };
+    let (_, metadata) = save_compressed_file(
+        b"# cached".to_vec(),
+        &temp.path().join("index.md"),
+        "text/markdown",
+        response,
+        true,
+        1_048_576,
+    )
+    .await?;
+    assert_eq!(metadata.server_date, Some(served));
+    let max_age = Duration::seconds(metadata.max_age_secs as i64);
+
+    // Local clock jumped a year ahead, but the server says an hour passed
+    let skewed_now = metadata.last_modified + Duration::days(365);
+    assert!(metadata.is_fresh(skewed_now, Some(served + Duration::hours(1))));
+    // A server clock a minute fast is tolerated, a day past the lifetime is not
+    assert!(metadata.is_fresh(skewed_now, Some(served + max_age + Duration::minutes(1))));
+    assert!(!metadata.is_fresh(skewed_now, Some(served + max_age + Duration::days(1))));
+
+    // Without a server date the local clock decides
+    assert!(!metadata.is_fresh(skewed_now, None));
+    // A clock set back before the save time is age zero, not negative
+    let rewound = metadata.last_modified - Duration::days(30);
+    assert_eq!(metadata.age(rewound, None), std::time::Duration::ZERO);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_hit_requires_matching_validator() -> anyhow::Result<()> {
+    let temp = tempfile::tempdir()?;
+    let modified = Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap();
+    let (_, metadata) = save_compressed_file(
+        b"# cached".to_vec(),
+        &temp.path().join("index.md"),
+        "text/markdown",
+        ResponseValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some(modified),
+            date: None,
+        },
+        true,
+        1_048_576,
+    )
+    .await?;
+    let now = Utc::now();
+    let etag = |etag: &str| ResponseValidators {
+        etag: Some(etag.to_string()),
+        ..ResponseValidators::default()
+    };
+    let last_modified = |date| ResponseValidators {
+        last_modified: Some(date),
+        ..ResponseValidators::default()
+    };
+
+    assert!(metadata.validated_by(&etag("\"v1\""), now));
+    assert!(!metadata.validated_by(&etag("\"v2\""), now));
+    assert!(metadata.validated_by(&last_modified(modified), now));
+    assert!(!metadata.validated_by(&last_modified(modified + Duration::hours(1)), now));
+
+    // A fresh copy is not a hit without a validator
+    assert!(!metadata.validated_by(&ResponseValidators::default(), now));
+    // Nor is a matching validator on a stale copy
+    assert!(!metadata.validated_by(&etag("\"v1\""), now + Duration::days(30)));
+    Ok(())
+}
+
+#[test]
+fn test_cache_metadata_without_new_fields_deserializes() -> anyhow::Result<()> {
+    // Metadata written before freshness lifetimes were recorded
+    let legacy = r#"{"etag":"\"abc\"","expires":1700604800,"last_modified":1700000000,"content_type":"text/markdown"}"#;
+    let metadata: CacheMetadata = serde_json::from_str(legacy)?;
+    assert_eq!(metadata.max_age_secs, 7 * 24 * 60 * 60);
+    assert_eq!(metadata.server_date, None);
+    assert_eq!(metadata.server_etag, None);
+    Ok(())
+}
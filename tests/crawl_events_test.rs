@@ -95,6 +95,9 @@ async fn test_multiple_subscribers() {
             links_for_crawling: 5,
             screenshot_captured: true,
             processing_duration: Duration::from_millis(100),
+            queue_size: 0,
+            phase_timings: PhaseTimings::default(),
+            content_hash: None,
         },
     );
 
@@ -153,6 +156,9 @@ fn test_event_creation_helpers() {
         links_for_crawling: 8,
         screenshot_captured: false,
         processing_duration: Duration::from_millis(200),
+        queue_size: 0,
+        phase_timings: PhaseTimings::default(),
+        content_hash: None,
     };
 
     let page_event = CrawlEvent::page_crawled(
@@ -199,6 +205,9 @@ async fn test_filtered_receiver() {
         links_for_crawling: 3,
         screenshot_captured: true,
         processing_duration: Duration::from_millis(100),
+        queue_size: 0,
+        phase_timings: PhaseTimings::default(),
+        content_hash: None,
     };
     let page_event = CrawlEvent::page_crawled(
         "https://test.com/page".to_string(),
@@ -354,6 +363,9 @@ async fn test_block_backpressure_no_race_condition() {
                         links_for_crawling: 5,
                         screenshot_captured: true,
                         processing_duration: Duration::from_millis(50),
+                        queue_size: 0,
+                        phase_timings: PhaseTimings::default(),
+                        content_hash: None,
                     },
                 );
 
@@ -432,3 +444,70 @@ async fn test_block_backpressure_no_race_condition() {
         "✅ Race condition test passed: {total_events} events published and received with no drops"
     );
 }
+
+#[tokio::test]
+async fn test_crawler_subscribe_uses_configured_bus() {
+    use kodegen_tools_citescrape::{ChromiumoxideCrawler, config::CrawlConfig};
+    use std::sync::Arc;
+
+    let bus = Arc::new(CrawlEventBus::new(10));
+    let mut crawler = ChromiumoxideCrawler::new(CrawlConfig::default().with_event_bus(bus.clone()));
+    let mut receiver = crawler.subscribe();
+
+    bus.publish(CrawlEvent::page_queued("https://test.com/a".to_string(), 1))
+        .await
+        .expect("crawler subscription is on the configured bus");
+    bus.publish(CrawlEvent::page_fetched("https://test.com/a".to_string(), Some(200), 512))
+        .await
+        .unwrap();
+    bus.publish(CrawlEvent::error("https://test.com/b".to_string(), "timeout".to_string()))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        receiver.recv().await,
+        Ok(CrawlEvent::PageQueued { depth: 1, .. })
+    ));
+    assert!(matches!(
+        receiver.recv().await,
+        Ok(CrawlEvent::PageFetched { status_code: Some(200), html_size: 512, .. })
+    ));
+    match receiver.recv().await {
+        Ok(CrawlEvent::Error { url, message, .. }) => {
+            assert_eq!(url, "https://test.com/b");
+            assert_eq!(message, "timeout");
+        }
+        other => panic!("Expected Error event, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_lossless_subscriber_never_lags() {
+    let bus = CrawlEventBus::new(4);
+    let mut lagging = bus.subscribe();
+    let mut lossless = bus.subscribe_lossless();
+    assert_eq!(bus.subscriber_count(), 2);
+
+    for i in 0..20 {
+        bus.publish(CrawlEvent::page_queued(format!("https://test.com/{i}"), 1))
+            .await
+            .unwrap();
+    }
+
+    assert!(matches!(
+        lagging.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(_))
+    ));
+    for i in 0..20 {
+        match lossless.recv().await {
+            Some(CrawlEvent::PageQueued { url, .. }) => assert_eq!(url, format!("https://test.com/{i}")),
+            other => panic!("Expected PageQueued {i}, got: {other:?}"),
+        }
+    }
+
+    // A lossless subscriber alone still counts as a subscriber
+    drop(lagging);
+    assert_eq!(bus.publish(CrawlEvent::page_queued("https://test.com/x".into(), 1)).await.ok(), Some(1));
+    drop(lossless);
+    assert!(bus.publish(CrawlEvent::page_queued("https://test.com/y".into(), 1)).await.is_err());
+}
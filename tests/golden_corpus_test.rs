@@ -0,0 +1,108 @@
+//! Golden-corpus tests for the markdown converter
+//!
+//! Two fixture corpora are checked:
+//!
+//! - `tests/fixtures/golden`: every `<name>.html` is converted with each
+//!   extraction backend and checked against `<name>.heuristic.md` and
+//!   `<name>.readability.md`. The goldens sit side by side, so `diff` between
+//!   them (or the backend diff printed with `--nocapture`) shows what switching
+//!   the default backend would change.
+//! - `tests/fixtures/snapshots`: real-world pages converted with the default
+//!   options, each checked against `<name>.md`. Relative links resolve against
+//!   `https://example.com/<name>/` so link resolution is covered too.
+//!
+//! After an intended output change, regenerate both with
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test --test golden_corpus_test
+//! ```
+//!
+//! and review the fixture diff like any other change.
+
+use kodegen_tools_citescrape::content_saver::markdown_converter::{
+    ConversionOptions, compare_backends_sync, convert_html_to_markdown_sync,
+};
+use kodegen_tools_citescrape::markdown_diff::diff_markdown;
+use std::path::{Path, PathBuf};
+
+/// The `.html` pages of the corpus in `tests/fixtures/<corpus>`, sorted by name
+fn corpus_pages(corpus: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(corpus);
+    let mut pages: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    pages.sort();
+    assert!(!pages.is_empty(), "{} is empty", dir.display());
+    pages
+}
+
+/// Compare `markdown` with the golden file, or rewrite it under `UPDATE_GOLDEN`
+fn check_golden(golden: &Path, markdown: &str, failures: &mut Vec<String>) {
+    let actual = format!("{markdown}\n");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        if std::fs::read_to_string(golden).ok().as_deref() != Some(actual.as_str()) {
+            std::fs::write(golden, &actual).unwrap();
+            println!("updated {}", golden.display());
+        }
+        return;
+    }
+    let expected = std::fs::read_to_string(golden).unwrap_or_default();
+    if expected != actual {
+        let diff = diff_markdown(&expected, &actual, 3);
+        failures.push(format!("{} differs from its golden:\n{}", golden.display(), diff.unified));
+    }
+}
+
+fn assert_no_failures(failures: &[String]) {
+    assert!(
+        failures.is_empty(),
+        "{}\n\n{} golden(s) changed; regenerate with UPDATE_GOLDEN=1 if the change is intended",
+        failures.join("\n"),
+        failures.len()
+    );
+}
+
+#[test]
+fn test_backends_match_golden_corpus() {
+    let mut failures = Vec::new();
+    for page in corpus_pages("golden") {
+        let html = std::fs::read_to_string(&page).unwrap();
+        let comparison = compare_backends_sync(&html, &ConversionOptions::default()).unwrap();
+        let name = page.file_stem().unwrap().to_string_lossy();
+
+        if comparison.diff.identical {
+            println!("{name}: backends agree");
+        } else {
+            println!(
+                "{name}: readability used {}, +{} -{} lines\n{}",
+                comparison.readability.extraction.used,
+                comparison.diff.lines_added,
+                comparison.diff.lines_removed,
+                comparison.diff.unified
+            );
+        }
+
+        for (backend, conversion) in [("heuristic", &comparison.heuristic), ("readability", &comparison.readability)] {
+            check_golden(&page.with_extension(format!("{backend}.md")), &conversion.markdown, &mut failures);
+        }
+    }
+    assert_no_failures(&failures);
+}
+
+#[test]
+fn test_corpus_matches_snapshots() {
+    let mut failures = Vec::new();
+    for page in corpus_pages("snapshots") {
+        let html = std::fs::read_to_string(&page).unwrap();
+        let name = page.file_stem().unwrap().to_string_lossy();
+        let options = ConversionOptions {
+            base_url: Some(format!("https://example.com/{name}/")),
+            ..ConversionOptions::default()
+        };
+        let markdown = convert_html_to_markdown_sync(&html, &options).unwrap();
+        check_golden(&page.with_extension("md"), &markdown, &mut failures);
+    }
+    assert_no_failures(&failures);
+}
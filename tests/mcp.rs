@@ -4,3 +4,9 @@
 
 #[path = "mcp/test_validation.rs"]
 mod test_validation;
+
+#[path = "mcp/test_manifest.rs"]
+mod test_manifest;
+
+#[path = "mcp/test_quota.rs"]
+mod test_quota;
@@ -0,0 +1,139 @@
+//! Tests for per-page outcomes recorded in crawl manifests
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use kodegen_tools_citescrape::crawl_engine::CrawlError;
+use kodegen_tools_citescrape::crawl_events::{CrawlEvent, PageCrawlMetadata, PhaseTimings};
+use kodegen_tools_citescrape::mcp::{
+    ConfigSummary, CrawlManifest, CrawlStatus, ManifestManager, PageOutcome, PageStatus,
+};
+
+fn manifest(output_dir: PathBuf) -> CrawlManifest {
+    CrawlManifest {
+        crawl_id: "0".to_string(),
+        start_url: "https://example.com/".to_string(),
+        search_index_dir: output_dir.join(".search_index"),
+        output_dir,
+        start_time: chrono::Utc::now(),
+        end_time: None,
+        status: CrawlStatus::Running,
+        total_pages: 0,
+        config_summary: ConfigSummary {
+            start_url: "https://example.com/".to_string(),
+            max_depth: 2,
+            limit: None,
+            save_markdown: true,
+            save_screenshots: false,
+            enable_search: false,
+            crawl_rate_rps: 2.0,
+            conversion: Default::default(),
+        },
+        site_audit: None,
+        pages: Default::default(),
+    }
+}
+
+fn crawled(url: &str, depth: u32) -> CrawlEvent {
+    CrawlEvent::page_crawled(
+        url.to_string(),
+        PathBuf::from("out/example.com/index.md"),
+        depth,
+        PageCrawlMetadata {
+            html_size: 100,
+            compressed_size: 0,
+            links_found: 1,
+            links_for_crawling: 1,
+            screenshot_captured: false,
+            processing_duration: Duration::from_millis(250),
+            queue_size: 1,
+            phase_timings: PhaseTimings::default(),
+            content_hash: Some("00000000deadbeef".to_string()),
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_page_outcomes_are_recorded_and_persisted() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let mut manifest = manifest(temp_dir.path().to_path_buf());
+
+    let events = [
+        CrawlEvent::page_queued("https://example.com/".into(), 0),
+        CrawlEvent::page_fetched("https://example.com/".into(), Some(200), 100),
+        CrawlEvent::page_saved("https://example.com/".into(), "out/example.com/index.html".into()),
+        crawled("https://example.com/", 0),
+        CrawlEvent::page_queued("https://example.com/old".into(), 1),
+        CrawlEvent::cache_hit("https://example.com/old".into()),
+        CrawlEvent::page_queued("https://example.com/broken".into(), 1),
+        CrawlEvent::error("https://example.com/broken".into(), "HTTP 500".into()),
+        CrawlEvent::page_queued("https://example.com/pending".into(), 1),
+    ];
+    for event in &events {
+        assert!(manifest.record_event(event));
+    }
+    assert!(!manifest.record_event(&CrawlEvent::crawl_completed(1, 0, Duration::ZERO)));
+
+    let home = &manifest.pages["https://example.com/"];
+    assert_eq!(home.status, PageStatus::Saved);
+    assert_eq!(home.depth, Some(0));
+    assert_eq!(home.http_status, Some(200));
+    assert_eq!(home.content_hash.as_deref(), Some("00000000deadbeef"));
+    assert_eq!(home.duration_ms, Some(250));
+    assert_eq!(
+        home.output_paths,
+        [PathBuf::from("out/example.com/index.html"), PathBuf::from("out/example.com/index.md")]
+    );
+    assert!(home.queued_at.is_some() && home.finished_at.is_some());
+
+    assert_eq!(manifest.pages["https://example.com/old"].status, PageStatus::Cached);
+    let broken = &manifest.pages["https://example.com/broken"];
+    assert_eq!(broken.status, PageStatus::Failed);
+    assert_eq!(
+        broken.error,
+        Some(CrawlError::Http { status: 500, message: "HTTP 500".to_string() })
+    );
+    let pending = &manifest.pages["https://example.com/pending"];
+    assert_eq!((pending.status, pending.depth), (PageStatus::Queued, Some(1)));
+
+    // A later successful attempt clears the failure
+    manifest.record_event(&CrawlEvent::page_fetched("https://example.com/broken".into(), Some(200), 50));
+    manifest.record_event(&crawled("https://example.com/broken", 1));
+    assert_eq!(manifest.pages["https://example.com/broken"].error, None);
+
+    // Pages recovered by the end-of-crawl retry pass are flagged
+    assert!(manifest.record_event(&CrawlEvent::retry_pass_completed(
+        vec!["https://example.com/broken".into()],
+        vec![],
+    )));
+    assert!(manifest.pages["https://example.com/broken"].recovered);
+    assert!(!manifest.record_event(&CrawlEvent::retry_pass_completed(vec!["https://example.com/unknown".into()], vec![])));
+    assert!(!manifest.pages.contains_key("https://example.com/unknown"));
+
+    // The failure kind of a page given up on after retries is kept
+    manifest.record_event(
+        &CrawlEvent::retry_exhausted("https://example.com/slow".into(), 3, "Page load timeout after 30 seconds".into())
+            .with_error(CrawlError::NavTimeout { message: "Page load timeout after 30 seconds".into() }),
+    );
+    let slow = &manifest.pages["https://example.com/slow"];
+    assert_eq!(slow.attempts, Some(3));
+    assert_eq!(slow.error.as_ref().map(CrawlError::kind), Some("nav_timeout"));
+
+    // Timestamps are stored with second precision
+    ManifestManager::save(&manifest).await.unwrap();
+    let loaded = ManifestManager::load(temp_dir.path()).await.unwrap();
+    assert_eq!(loaded.pages.len(), 5);
+    assert_eq!(loaded.pages["https://example.com/slow"].error, slow.error.clone());
+    let queued_at = manifest.pages["https://example.com/pending"].queued_at.unwrap();
+    assert_eq!(
+        loaded.pages["https://example.com/pending"],
+        PageOutcome {
+            depth: Some(1),
+            queued_at: chrono::DateTime::from_timestamp(queued_at.timestamp(), 0),
+            ..PageOutcome::default()
+        }
+    );
+    let home = &loaded.pages["https://example.com/"];
+    assert_eq!(home.status, PageStatus::Saved);
+    assert_eq!(home.output_paths.len(), 2);
+}
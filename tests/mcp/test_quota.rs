@@ -0,0 +1,25 @@
+//! Tests for per-connection crawl admission
+
+use std::sync::Arc;
+
+use kodegen_tools_citescrape::{BrowserPool, CrawlQuota, CrawlRegistry, SearchEngineCache};
+
+#[tokio::test]
+async fn test_admission_reserves_slot_until_dropped() {
+    // The pool is not started, so no browser is launched
+    let pool = BrowserPool::new(Default::default());
+    let registry = CrawlRegistry::new(Arc::new(SearchEngineCache::new()), pool).with_quota(CrawlQuota {
+        max_concurrent_crawls: Some(1),
+        ..Default::default()
+    });
+
+    let first = registry.admit_crawl("conn", 0).await.unwrap();
+    assert!(registry.admit_crawl("conn", 1).await.is_err());
+    // Restarting the same crawl and other connections are not limited by it
+    let restart = registry.admit_crawl("conn", 0).await.unwrap();
+    let _other = registry.admit_crawl("other", 0).await.unwrap();
+
+    drop(first);
+    drop(restart);
+    assert!(registry.admit_crawl("conn", 1).await.is_ok());
+}
@@ -1,4 +1,4 @@
-use kodegen_tools_citescrape::config::CrawlConfig;
+use kodegen_tools_citescrape::config::{CrawlConfig, CrawlScope};
 use kodegen_tools_citescrape::crawl_engine::should_visit_url;
 
 #[test]
@@ -220,3 +220,24 @@ fn test_invalid_url_rejected() {
     assert!(!should_visit_url("", &config));
     assert!(!should_visit_url("://invalid", &config));
 }
+
+#[test]
+fn test_registrable_domain_scope() {
+    let config = CrawlConfig::builder()
+        .storage_dir("/tmp/test")
+        .start_url("https://www.example.co.uk/docs")
+        .scope(CrawlScope::SameRegistrableDomain)
+        .build()
+        .unwrap();
+
+    // Start host keeps its path scope
+    assert!(should_visit_url("https://www.example.co.uk/docs/intro", &config));
+    assert!(!should_visit_url("https://www.example.co.uk/blog", &config));
+
+    // Sibling hosts under example.co.uk - ALLOWED from their root
+    assert!(should_visit_url("https://blog.example.co.uk/post", &config));
+    assert!(should_visit_url("https://example.co.uk/", &config));
+
+    // Another registrable domain under the same public suffix - REJECTED
+    assert!(!should_visit_url("https://other.co.uk/docs", &config));
+}